@@ -0,0 +1,51 @@
+use criterion::*;
+use pprof::criterion::{Output, PProfProfiler};
+
+use ark_pallas::{Fr as pallas_Fr, Projective as pallas_G};
+use ark_std::UniformRand;
+
+use folding_schemes::commitment::{pedersen::Pedersen, CommitmentScheme};
+
+fn bench_pedersen_commit(c: &mut Criterion) {
+    let mut rng = rand::rngs::OsRng;
+
+    // iterate over the powers of n
+    for n in [10_usize, 14, 16, 18, 20].iter() {
+        let len = 1 << n; // 2^n
+        let (params, _) = Pedersen::<pallas_G>::setup(&mut rng, len).unwrap();
+        let v: Vec<pallas_Fr> = std::iter::repeat_with(|| pallas_Fr::rand(&mut rng))
+            .take(len)
+            .collect();
+        // boolean-heavy vector, as the witnesses of circuits with many bit decompositions
+        let v_bits: Vec<pallas_Fr> = (0..len)
+            .map(|i| pallas_Fr::from((i % 3 == 0) as u64))
+            .collect();
+
+        let mut group = c.benchmark_group(format!("Pedersen commit - Pallas - 2^{} elements", n));
+        group.significance_level(0.1).sample_size(10);
+        group.bench_function("variable-base MSM", |b| {
+            b.iter(|| Pedersen::<pallas_G>::commit(&params, black_box(&v), &pallas_Fr::from(0)))
+        });
+        for window in [8, 12, 16] {
+            let params_tables = params.clone().with_fixed_base_tables(window).unwrap();
+            group.bench_function(format!("fixed-base tables, window {}", window), |b| {
+                b.iter(|| {
+                    Pedersen::<pallas_G>::commit(&params_tables, black_box(&v), &pallas_Fr::from(0))
+                })
+            });
+        }
+        group.bench_function("boolean vector", |b| {
+            b.iter(|| {
+                Pedersen::<pallas_G>::commit(&params, black_box(&v_bits), &pallas_Fr::from(0))
+            })
+        });
+        group.finish();
+    }
+}
+
+criterion_group! {
+    name = benches;
+    config = Criterion::default().with_profiler(PProfProfiler::new(100, Output::Flamegraph(None)));
+    targets = bench_pedersen_commit
+}
+criterion_main!(benches);
@@ -8,7 +8,7 @@ use ark_vesta::Projective as vesta_G;
 
 use folding_schemes::{
     commitment::pedersen::Pedersen,
-    folding::protogalaxy::ProtoGalaxy,
+    folding::{nova::PreprocessorParam, protogalaxy::ProtoGalaxy},
     frontend::{utils::CustomFCircuit, FCircuit},
     transcript::poseidon::poseidon_canonical_config,
 };
@@ -23,7 +23,7 @@ fn bench_protogalaxy_ivc(c: &mut Criterion) {
     for n in [0_usize, 14, 16, 18, 19, 20, 21, 22].iter() {
         let fcircuit_size = 1 << n; // 2^n
         let fcircuit = CustomFCircuit::<pallas_Fr>::new(fcircuit_size).unwrap();
-        let prep_param = (poseidon_config.clone(), fcircuit);
+        let prep_param = PreprocessorParam::new(poseidon_config.clone(), fcircuit);
 
         bench_ivc_opt::<
             pallas_G,
@@ -34,6 +34,7 @@ fn bench_protogalaxy_ivc(c: &mut Criterion) {
                 CustomFCircuit<pallas_Fr>,
                 Pedersen<pallas_G>,
                 Pedersen<vesta_G>,
+                1,
             >,
         >(
             c,
@@ -48,7 +49,7 @@ fn bench_protogalaxy_ivc(c: &mut Criterion) {
     for n in [0_usize, 14, 16, 18, 19, 20, 21, 22].iter() {
         let fcircuit_size = 1 << n; // 2^n
         let fcircuit = CustomFCircuit::<bn_Fr>::new(fcircuit_size).unwrap();
-        let prep_param = (poseidon_config.clone(), fcircuit);
+        let prep_param = PreprocessorParam::new(poseidon_config.clone(), fcircuit);
 
         bench_ivc_opt::<
             bn_G,
@@ -59,6 +60,7 @@ fn bench_protogalaxy_ivc(c: &mut Criterion) {
                 CustomFCircuit<bn_Fr>,
                 Pedersen<bn_G>,
                 Pedersen<grumpkin_G>,
+                1,
             >,
         >(
             c,
@@ -0,0 +1,64 @@
+use ark_bn254::Fr;
+use ark_r1cs_std::fields::fp::FpVar;
+use ark_relations::r1cs::{ConstraintSystemRef, SynthesisError};
+use experimental_frontends::{
+    circom::CircomFCircuit,
+    noir::NoirFCircuit,
+    noname::NonameFCircuit,
+    utils::{VecF, VecFpVar},
+};
+use folding_schemes::{frontend::FCircuit, utils::PathOrBin, Error};
+use noname::backends::r1cs::R1csBn254Field;
+
+/// Parameters to load the circuit being folded from any of the supported frontends.
+#[derive(Debug, Clone)]
+pub(crate) enum FrontendParams {
+    /// (r1cs_path, wasm_path)
+    Circom(PathOrBin, PathOrBin),
+    /// Compiled Noir program
+    Noir(PathOrBin),
+    /// Noname source code
+    Noname(String),
+}
+
+/// Circuit being folded, written in any of the supported frontends. The state length `SL` and
+/// external inputs length `EIL` are shared by all the frontends, so that a single instantiation of
+/// Nova and its Decider per shape covers all of them.
+#[derive(Debug, Clone)]
+pub(crate) enum FrontendFCircuit<const SL: usize, const EIL: usize> {
+    Circom(CircomFCircuit<Fr, SL, EIL>),
+    Noir(NoirFCircuit<Fr, SL, EIL>),
+    Noname(NonameFCircuit<Fr, R1csBn254Field, SL, EIL>),
+}
+
+impl<const SL: usize, const EIL: usize> FCircuit<Fr> for FrontendFCircuit<SL, EIL> {
+    type Params = FrontendParams;
+    type ExternalInputs = VecF<Fr, EIL>;
+    type ExternalInputsVar = VecFpVar<Fr, EIL>;
+
+    fn new(params: Self::Params) -> Result<Self, Error> {
+        Ok(match params {
+            FrontendParams::Circom(r1cs, wasm) => Self::Circom(CircomFCircuit::new((r1cs, wasm))?),
+            FrontendParams::Noir(program) => Self::Noir(NoirFCircuit::new(program)?),
+            FrontendParams::Noname(code) => Self::Noname(NonameFCircuit::new(code)?),
+        })
+    }
+
+    fn state_len(&self) -> usize {
+        SL
+    }
+
+    fn generate_step_constraints(
+        &self,
+        cs: ConstraintSystemRef<Fr>,
+        i: usize,
+        z_i: Vec<FpVar<Fr>>,
+        external_inputs: Self::ExternalInputsVar,
+    ) -> Result<Vec<FpVar<Fr>>, SynthesisError> {
+        match self {
+            Self::Circom(circuit) => circuit.generate_step_constraints(cs, i, z_i, external_inputs),
+            Self::Noir(circuit) => circuit.generate_step_constraints(cs, i, z_i, external_inputs),
+            Self::Noname(circuit) => circuit.generate_step_constraints(cs, i, z_i, external_inputs),
+        }
+    }
+}
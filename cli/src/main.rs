@@ -1,10 +1,14 @@
 use ark_serialize::Write;
-use clap::Parser;
-use settings::Cli;
+use clap::{error::ErrorKind, CommandFactory, Parser};
+use folding_schemes::Error;
+use settings::{Cli, Command};
 use std::path::Path;
 use std::{fs, io};
+use workflow::dispatch;
 
+mod circuit;
 mod settings;
+mod workflow;
 
 fn create_or_open_then_write<T: AsRef<[u8]>>(path: &Path, content: &T) -> Result<(), io::Error> {
     let mut file = fs::OpenOptions::new()
@@ -15,7 +19,7 @@ fn create_or_open_then_write<T: AsRef<[u8]>>(path: &Path, content: &T) -> Result
     file.write_all(content.as_ref())
 }
 
-fn main() {
+fn main() -> Result<(), Error> {
     let cli = Cli::parse();
 
     // generate a subscriber with the desired log level
@@ -24,17 +28,48 @@ fn main() {
         .filter_level(cli.verbosity.log_level_filter())
         .init();
 
-    let out_path = cli.out;
+    match (cli.command, cli.render) {
+        (Some(_), Some(_)) => Cli::command()
+            .error(
+                ErrorKind::ArgumentConflict,
+                "the render options can not be given together with a command",
+            )
+            .exit(),
+        (None, None) => Cli::command()
+            .error(ErrorKind::MissingSubcommand, "a command is required")
+            .exit(),
+        // the render options can be given without the `render` subcommand, as in the previous
+        // versions of the CLI
+        (Some(Command::Render(args)), None) | (None, Some(args)) => {
+            let out_path = args.out;
 
-    // Fetch the exact protocol for which we need to generate the Decider verifier contract.
-    let protocol = cli.protocol;
-    // Fetch the protocol data passed by the user from the file.
-    let protocol_vk = std::fs::read(cli.protocol_vk).unwrap();
+            // Fetch the exact protocol for which we need to generate the Decider verifier contract.
+            let protocol = args.protocol;
+            // Fetch the protocol data passed by the user from the file.
+            let protocol_vk = std::fs::read(args.protocol_vk)?;
 
-    // Generate the Solidity Verifier contract for the selected protocol with the given data.
-    create_or_open_then_write(
-        &out_path,
-        &protocol.render(&protocol_vk, cli.pragma).unwrap(),
-    )
-    .unwrap();
+            // Generate the Solidity Verifier contract for the selected protocol with the given data.
+            create_or_open_then_write(&out_path, &protocol.render(&protocol_vk, args.pragma)?)?;
+            Ok(())
+        }
+        (Some(Command::Setup { circuit, ptau, dir }), None) => {
+            dispatch!(circuit, setup(&circuit, ptau.as_deref(), &dir))
+        }
+        (
+            Some(Command::Prove {
+                circuit,
+                inputs,
+                steps,
+                dir,
+            }),
+            None,
+        ) => dispatch!(circuit, prove(&circuit, &inputs, steps, &dir)),
+        (Some(Command::Decide { circuit, dir }), None) => {
+            dispatch!(circuit, decide(&circuit, &dir))
+        }
+        (Some(Command::Verify { circuit, dir }), None) => {
+            dispatch!(circuit, verify(&circuit, &dir))
+        }
+        (Some(Command::Calldata { mode, dir }), None) => workflow::calldata(mode, &dir),
+    }
 }
@@ -1,9 +1,12 @@
 use ark_serialize::SerializationError;
-use clap::{Parser, ValueEnum};
+use clap::{Args, Parser, Subcommand, ValueEnum};
 use solidity_verifiers::{
-    Groth16VerifierKey, KZG10VerifierKey, NovaCycleFoldVerifierKey, ProtocolVerifierKey,
+    calldata::NovaVerificationMode, Groth16VerifierKey, KZG10VerifierKey, NovaCycleFoldVerifierKey,
+    ProtoGalaxyCycleFoldVerifierKey, ProtocolVerifierKey,
 };
-use std::{env, fmt::Display, path::PathBuf};
+use std::{env, fmt::Display, fs, io, path::PathBuf};
+
+use crate::circuit::FrontendParams;
 
 fn get_default_out_path() -> PathBuf {
     let mut path = env::current_dir().unwrap();
@@ -11,11 +14,40 @@ fn get_default_out_path() -> PathBuf {
     path
 }
 
+fn get_default_artifacts_dir() -> PathBuf {
+    env::current_dir().unwrap()
+}
+
 #[derive(Debug, Copy, Clone, ValueEnum)]
 pub(crate) enum Protocol {
     Groth16,
     Kzg,
     NovaCycleFold,
+    ProtoGalaxyCycleFold,
+}
+
+#[derive(Debug, Copy, Clone, ValueEnum)]
+pub(crate) enum Frontend {
+    Circom,
+    Noir,
+    Noname,
+}
+
+#[derive(Debug, Copy, Clone, ValueEnum)]
+pub(crate) enum VerificationMode {
+    Explicit,
+    Opaque,
+    OpaqueWithInputs,
+}
+
+impl From<VerificationMode> for NovaVerificationMode {
+    fn from(mode: VerificationMode) -> Self {
+        match mode {
+            VerificationMode::Explicit => Self::Explicit,
+            VerificationMode::Opaque => Self::Opaque,
+            VerificationMode::OpaqueWithInputs => Self::OpaqueWithInputs,
+        }
+    }
 }
 
 impl Display for Protocol {
@@ -38,10 +70,36 @@ impl Protocol {
 
             Self::Kzg => Ok(KZG10VerifierKey::deserialize_protocol_verifier_key(data)?
                 .render_as_template(pragma)),
-            Self::NovaCycleFold => Ok(NovaCycleFoldVerifierKey::deserialize_protocol_verifier_key(
-                data,
-            )?
-            .render_as_template(pragma)),
+            // the keys of the hiding and Keccak deciders are tagged with different protocol names
+            Self::NovaCycleFold => {
+                if let Ok(vk) =
+                    NovaCycleFoldVerifierKey::<false, false>::deserialize_protocol_verifier_key(
+                        data,
+                    )
+                {
+                    return Ok(vk.render_as_template(pragma));
+                }
+                if let Ok(vk) =
+                    NovaCycleFoldVerifierKey::<true, false>::deserialize_protocol_verifier_key(data)
+                {
+                    return Ok(vk.render_as_template(pragma));
+                }
+                if let Ok(vk) =
+                    NovaCycleFoldVerifierKey::<false, true>::deserialize_protocol_verifier_key(data)
+                {
+                    return Ok(vk.render_as_template(pragma));
+                }
+                Ok(
+                    NovaCycleFoldVerifierKey::<true, true>::deserialize_protocol_verifier_key(
+                        data,
+                    )?
+                    .render_as_template(pragma),
+                )
+            }
+            Self::ProtoGalaxyCycleFold => Ok(
+                ProtoGalaxyCycleFoldVerifierKey::deserialize_protocol_verifier_key(data)?
+                    .render_as_template(pragma),
+            ),
         }
     }
 }
@@ -85,16 +143,104 @@ Solidity Verifier currently supports the generation of Solidity smart contracts
 
     Nova + CycleFold Decider:
         Implements the decider circuit verification for the Nova proof system in conjunction with the CycleFold protocol optimization.
+
+    ProtoGalaxy + CycleFold Decider:
+        Implements the decider circuit verification for the ProtoGalaxy proof system in conjunction with the CycleFold protocol optimization.
+
+Besides rendering the verifier contracts, it runs the whole Nova + CycleFold workflow over BN254 & Grumpkin for Circom, Noir and Noname circuits, reading and writing its artifacts from a directory:
+
+    setup:    generates the Nova and Decider prover & verifier params, and the Nova + CycleFold Decider verifier key.
+    prove:    folds the steps of the circuit over the inputs of a JSON file and stores the IVC proof.
+    decide:   generates the Decider proof of the IVC proof.
+    verify:   verifies the IVC proof and the Decider proof.
+    calldata: generates the calldata of the Nova + CycleFold Decider contract.
+
+Since the frontends take the state length and the external inputs length of the circuit as const generics, only the circuits with a state of 1 to 4 field elements and with 0 to 4 external inputs per step are supported.
+
+The options of the render command can still be given without the subcommand, e.g. `solidity-verifiers-cli -p groth16 -k ./G16_vk -o ./verifier.sol`.
 ";
 #[derive(Debug, Parser)]
 #[command(author = "0xPARC & PSE", version, about = ABOUT, long_about = Some(LONG_ABOUT))]
 #[command(propagate_version = true)]
 /// A tool to create Solidity Contracts which act as verifiers for the major Folding Schemes implemented
 /// within the `sonobe` repo.
+#[command(subcommand_negates_reqs = true)]
 pub(crate) struct Cli {
     #[command(flatten)]
     pub verbosity: clap_verbosity_flag::Verbosity,
 
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
+    /// The options of the `render` command can also be given without the subcommand, as in the
+    /// previous versions of the CLI, e.g. `solidity-verifiers-cli -p groth16 -k ./G16_vk`.
+    #[command(
+        flatten,
+        next_help_heading = "Render options (when no command is given)"
+    )]
+    pub render: Option<RenderArgs>,
+}
+
+#[derive(Debug, Subcommand)]
+pub(crate) enum Command {
+    /// Generates the Solidity Verifier contract of a protocol from its serialized verifier key.
+    Render(RenderArgs),
+    /// Generates the Nova and Decider prover & verifier params of a circuit.
+    Setup {
+        #[command(flatten)]
+        circuit: CircuitArgs,
+        /// Sets the path of a Powers of Tau file in snarkjs' `.ptau` format, from which the KZG
+        /// params are loaded instead of being sampled.
+        #[arg(long)]
+        ptau: Option<PathBuf>,
+        #[arg(short = 'd', long, default_value=get_default_artifacts_dir().into_os_string())]
+        /// Sets the directory where the artifacts are read from and written to.
+        dir: PathBuf,
+    },
+    /// Folds the steps of a circuit with Nova+CycleFold and stores the resulting IVC proof.
+    Prove {
+        #[command(flatten)]
+        circuit: CircuitArgs,
+        /// Sets the path of the JSON file containing the initial state `z_0` and the external
+        /// inputs of each step.
+        #[arg(short = 'i', long)]
+        inputs: PathBuf,
+        /// Sets the number of steps to fold, which defaults to the number of external inputs given.
+        #[arg(short = 'n', long)]
+        steps: Option<usize>,
+        #[arg(short = 'd', long, default_value=get_default_artifacts_dir().into_os_string())]
+        /// Sets the directory where the artifacts are read from and written to.
+        dir: PathBuf,
+    },
+    /// Generates the Decider proof of the stored IVC proof.
+    Decide {
+        #[command(flatten)]
+        circuit: CircuitArgs,
+        #[arg(short = 'd', long, default_value=get_default_artifacts_dir().into_os_string())]
+        /// Sets the directory where the artifacts are read from and written to.
+        dir: PathBuf,
+    },
+    /// Verifies the stored IVC proof and, if present, the stored Decider proof.
+    Verify {
+        #[command(flatten)]
+        circuit: CircuitArgs,
+        #[arg(short = 'd', long, default_value=get_default_artifacts_dir().into_os_string())]
+        /// Sets the directory where the artifacts are read from and written to.
+        dir: PathBuf,
+    },
+    /// Generates the calldata of the Nova+CycleFold Decider contract for the stored Decider proof.
+    Calldata {
+        /// Selects the function of the Decider contract that the calldata is prepared for.
+        #[arg(short = 'm', long, value_enum, default_value_t = VerificationMode::Explicit)]
+        mode: VerificationMode,
+        #[arg(short = 'd', long, default_value=get_default_artifacts_dir().into_os_string())]
+        /// Sets the directory where the artifacts are read from and written to.
+        dir: PathBuf,
+    },
+}
+
+#[derive(Debug, Args)]
+pub(crate) struct RenderArgs {
     /// Selects the protocol for which we want to generate the Solidity Verifier contract.
     #[arg(short = 'p', long, value_enum, rename_all = "lower")]
     pub protocol: Protocol,
@@ -111,3 +257,43 @@ pub(crate) struct Cli {
     #[arg(long, default_value=None)]
     pub pragma: Option<String>,
 }
+
+#[derive(Debug, Args)]
+pub(crate) struct CircuitArgs {
+    /// Selects the frontend in which the circuit to be folded is written.
+    #[arg(short = 'f', long, value_enum)]
+    pub frontend: Frontend,
+
+    /// Sets the path of the circuit: the `.r1cs` file for Circom, the compiled `.json` program for Noir, or the source code for Noname.
+    #[arg(short = 'c', long)]
+    pub circuit: PathBuf,
+
+    /// Sets the path of the `.wasm` witness generator of a Circom circuit.
+    #[arg(long, required_if_eq("frontend", "circom"))]
+    pub wasm: Option<PathBuf>,
+
+    /// Sets the number of field elements of the state of the circuit. Only the lengths from 1 to
+    /// 4 are supported, since each one is compiled into the binary (see the README).
+    #[arg(long)]
+    pub state_len: usize,
+
+    /// Sets the number of field elements of the external inputs of each step of the circuit. Only
+    /// the lengths from 0 to 4 are supported, since each one is compiled into the binary (see the
+    /// README).
+    #[arg(long, default_value_t = 0)]
+    pub external_inputs_len: usize,
+}
+
+impl CircuitArgs {
+    pub(crate) fn params(&self) -> Result<FrontendParams, io::Error> {
+        Ok(match self.frontend {
+            Frontend::Circom => FrontendParams::Circom(
+                self.circuit.clone().into(),
+                // `wasm` is required by clap when the frontend is Circom
+                self.wasm.clone().unwrap_or_default().into(),
+            ),
+            Frontend::Noir => FrontendParams::Noir(self.circuit.clone().into()),
+            Frontend::Noname => FrontendParams::Noname(fs::read_to_string(&self.circuit)?),
+        })
+    }
+}
@@ -0,0 +1,339 @@
+use ark_bn254::{Bn254, Fr, G1Projective as G1};
+use ark_crypto_primitives::sponge::poseidon::PoseidonSponge;
+use ark_groth16::Groth16;
+use ark_grumpkin::Projective as G2;
+use ark_serialize::Compress;
+use experimental_frontends::utils::VecF;
+use folding_schemes::{
+    arith::Arith,
+    commitment::{kzg::KZG, pedersen::Pedersen, ptau::load_ptau},
+    folding::{
+        envelope::{read_unchecked, Envelope, Kind},
+        nova::{
+            decider_eth::{Decider as DeciderEth, Proof as DeciderProof},
+            get_r1cs, IVCProof, Nova, PreprocessorParam, ProverParams, VerifierParams,
+        },
+        traits::CommittedInstanceOps,
+    },
+    frontend::FCircuit,
+    transcript::poseidon::poseidon_canonical_config,
+    Decider, Error, FoldingScheme,
+};
+use serde::Deserialize;
+use solidity_verifiers::{
+    calldata::{get_formatted_calldata, prepare_calldata_for_nova_cyclefold_verifier},
+    NovaCycleFoldVerifierKey, ProtocolVerifierKey,
+};
+use std::{
+    cmp::max,
+    fs::{self, File},
+    io::{BufReader, BufWriter, Write},
+    path::Path,
+    str::FromStr,
+    time::Instant,
+};
+
+use crate::{
+    circuit::FrontendFCircuit,
+    settings::{CircuitArgs, VerificationMode},
+};
+
+// Names of the artifacts stored in the directory given to the commands.
+const NOVA_PP: &str = "nova_pp.bin";
+const NOVA_VP: &str = "nova_vp.bin";
+const DECIDER_PP: &str = "decider_pp.bin";
+const DECIDER_VP: &str = "decider_vp.bin";
+const NOVA_CYCLEFOLD_VK: &str = "nova_cyclefold_vk.bin";
+const IVC_PROOF: &str = "ivc_proof.bin";
+const DECIDER_PROOF: &str = "decider_proof.bin";
+const CALLDATA: &str = "calldata.bin";
+const CALLDATA_INPUTS: &str = "calldata.inputs";
+
+type FC<const SL: usize, const EIL: usize> = FrontendFCircuit<SL, EIL>;
+type N<const SL: usize, const EIL: usize> =
+    Nova<G1, G2, FC<SL, EIL>, KZG<'static, Bn254>, Pedersen<G2>, false>;
+type D<const SL: usize, const EIL: usize> =
+    DeciderEth<G1, G2, FC<SL, EIL>, KZG<'static, Bn254>, Pedersen<G2>, Groth16<Bn254>, N<SL, EIL>>;
+type NovaParams = (
+    ProverParams<G1, G2, KZG<'static, Bn254>, Pedersen<G2>>,
+    VerifierParams<G1, G2, KZG<'static, Bn254>, Pedersen<G2>>,
+);
+
+/// Calls `$f::<SL, EIL>$args` with the state length and external inputs length given at runtime,
+/// since the frontends take them as const generics. Only the shapes listed here are compiled into
+/// the binary, any other one needs to be added to the lists (and documented in the README). Each
+/// shape monomorphizes Nova and its Decider once, so the 20 shapes listed here already dominate
+/// the compilation time and the size of the binary.
+macro_rules! dispatch {
+    (@sl $sl:expr, $eil:expr, $f:ident $args:tt, [$($sls:literal),*], $eils:tt) => {
+        match $sl {
+            $($sls => dispatch!(@eil $sls, $eil, $f $args, $eils),)*
+            sl => Err(folding_schemes::Error::Other(format!(
+                "unsupported state length {sl}, the CLI supports the lengths {:?}",
+                [$($sls),*]
+            ))),
+        }
+    };
+    (@eil $sl:literal, $eil:expr, $f:ident $args:tt, [$($eils:literal),*]) => {
+        match $eil {
+            $($eils => $crate::workflow::$f::<$sl, $eils> $args,)*
+            eil => Err(folding_schemes::Error::Other(format!(
+                "unsupported external inputs length {eil}, the CLI supports the lengths {:?}",
+                [$($eils),*]
+            ))),
+        }
+    };
+    ($circuit:expr, $f:ident $args:tt) => {
+        dispatch!(
+            @sl $circuit.state_len,
+            $circuit.external_inputs_len,
+            $f $args,
+            [1, 2, 3, 4],
+            [0, 1, 2, 3, 4]
+        )
+    };
+}
+pub(crate) use dispatch;
+
+/// Inputs of the `prove` command, where the field elements are given as decimal strings.
+#[derive(Debug, Deserialize)]
+struct Inputs {
+    z_0: Vec<String>,
+    #[serde(default)]
+    external_inputs: Vec<Vec<String>>,
+}
+
+fn parse_field_elements(values: &[String], len: usize) -> Result<Vec<Fr>, Error> {
+    if values.len() != len {
+        return Err(Error::NotExpectedLength(values.len(), len));
+    }
+    values
+        .iter()
+        .map(|v| Fr::from_str(v).map_err(|_| Error::Other(format!("invalid field element {v}"))))
+        .collect()
+}
+
+fn read_nova_vp<const SL: usize, const EIL: usize>(
+    circuit: &CircuitArgs,
+    dir: &Path,
+) -> Result<VerifierParams<G1, G2, KZG<'static, Bn254>, Pedersen<G2>>, Error> {
+    N::<SL, EIL>::read_vp(
+        BufReader::new(File::open(dir.join(NOVA_VP))?),
+        circuit.params()?,
+    )
+}
+
+fn read_nova_params<const SL: usize, const EIL: usize>(
+    circuit: &CircuitArgs,
+    dir: &Path,
+) -> Result<NovaParams, Error> {
+    let vp = read_nova_vp::<SL, EIL>(circuit, dir)?;
+    // the prover params are checked to be the ones of the verifier params
+    let pp = N::<SL, EIL>::read_pp(
+        BufReader::new(File::open(dir.join(NOVA_PP))?),
+        circuit.params()?,
+        vp.pp_hash()?,
+    )?;
+    Ok((pp, vp))
+}
+
+fn write_ivc_proof<const SL: usize, const EIL: usize>(
+    dir: &Path,
+    ivc_proof: &IVCProof<G1, G2>,
+    pp_hash: Fr,
+) -> Result<(), Error> {
+    let mut writer = BufWriter::new(File::create(dir.join(IVC_PROOF))?);
+    N::<SL, EIL>::write_ivc_proof(ivc_proof, pp_hash, &mut writer, Compress::Yes)?;
+    Ok(writer.flush()?)
+}
+
+fn read_ivc_proof<const SL: usize, const EIL: usize>(
+    dir: &Path,
+    pp_hash: Fr,
+) -> Result<IVCProof<G1, G2>, Error> {
+    N::<SL, EIL>::read_ivc_proof(BufReader::new(File::open(dir.join(IVC_PROOF))?), pp_hash)
+}
+
+pub(crate) fn setup<const SL: usize, const EIL: usize>(
+    circuit: &CircuitArgs,
+    ptau: Option<&Path>,
+    dir: &Path,
+) -> Result<(), Error> {
+    let mut rng = rand::rngs::OsRng;
+    let f_circuit = FC::<SL, EIL>::new(circuit.params()?)?;
+
+    let mut preprocessor_param =
+        PreprocessorParam::new(poseidon_canonical_config::<Fr>(), f_circuit.clone());
+    if let Some(ptau) = ptau {
+        // KZG commits to Nova's witness and error vectors, so the ceremony needs to cover the
+        // largest of them
+        let (r1cs, _) = get_r1cs::<G1, G2, _, PoseidonSponge<Fr>>(
+            &preprocessor_param.sponge_config,
+            f_circuit.clone(),
+        )?;
+        let len = max(r1cs.n_constraints(), r1cs.n_witnesses());
+        let (kzg_pk, kzg_vk) = load_ptau(&mut rng, BufReader::new(File::open(ptau)?), len)?;
+        preprocessor_param = preprocessor_param.with_cs_params(kzg_pk, kzg_vk);
+    }
+
+    let start = Instant::now();
+    let nova_params = N::<SL, EIL>::preprocess(&mut rng, &preprocessor_param)?;
+    log::info!("Nova::preprocess: {:?}", start.elapsed());
+    let start = Instant::now();
+    let (decider_pp, decider_vp) =
+        D::<SL, EIL>::preprocess(&mut rng, (nova_params.clone(), f_circuit.state_len()))?;
+    log::info!("Decider::preprocess: {:?}", start.elapsed());
+
+    let pp_hash = nova_params.1.pp_hash()?;
+    let mut writer = BufWriter::new(File::create(dir.join(NOVA_PP))?);
+    N::<SL, EIL>::write_pp(&nova_params.0, pp_hash, &mut writer, Compress::Yes)?;
+    writer.flush()?;
+    let mut writer = BufWriter::new(File::create(dir.join(NOVA_VP))?);
+    N::<SL, EIL>::write_vp(&nova_params.1, &mut writer, Compress::Yes)?;
+    writer.flush()?;
+    let mut writer = BufWriter::new(File::create(dir.join(DECIDER_PP))?);
+    N::<SL, EIL>::write_decider_pp::<D<SL, EIL>>(&decider_pp, pp_hash, &mut writer, Compress::Yes)?;
+    writer.flush()?;
+    let mut writer = BufWriter::new(File::create(dir.join(DECIDER_VP))?);
+    N::<SL, EIL>::write_decider_vp::<D<SL, EIL>>(&decider_vp, pp_hash, &mut writer, Compress::Yes)?;
+    writer.flush()?;
+
+    // verifier key of the Solidity contract, to be given to the `render` command
+    let nova_cyclefold_vk = NovaCycleFoldVerifierKey::from((decider_vp, f_circuit.state_len()));
+    let mut writer = BufWriter::new(File::create(dir.join(NOVA_CYCLEFOLD_VK))?);
+    nova_cyclefold_vk.serialize_protocol_verifier_key(&mut writer)?;
+    Ok(writer.flush()?)
+}
+
+pub(crate) fn prove<const SL: usize, const EIL: usize>(
+    circuit: &CircuitArgs,
+    inputs: &Path,
+    steps: Option<usize>,
+    dir: &Path,
+) -> Result<(), Error> {
+    let mut rng = rand::rngs::OsRng;
+    let inputs: Inputs = serde_json::from_reader(BufReader::new(File::open(inputs)?))
+        .map_err(|e| Error::JSONSerdeError(e.to_string()))?;
+    let z_0 = parse_field_elements(&inputs.z_0, SL)?;
+    let external_inputs = inputs
+        .external_inputs
+        .iter()
+        .map(|v| parse_field_elements(v, EIL))
+        .collect::<Result<Vec<_>, _>>()?;
+    let steps = steps.unwrap_or(external_inputs.len());
+    // circuits without external inputs can run any number of steps
+    if EIL > 0 && external_inputs.len() < steps {
+        return Err(Error::NotExpectedLength(external_inputs.len(), steps));
+    }
+
+    let f_circuit = FC::<SL, EIL>::new(circuit.params()?)?;
+    let nova_params = read_nova_params::<SL, EIL>(circuit, dir)?;
+    let mut nova = N::<SL, EIL>::init(&nova_params, f_circuit, z_0)?;
+    for i in 0..steps {
+        let start = Instant::now();
+        let external_inputs_at_step = external_inputs
+            .get(i)
+            .map(|v| VecF(v.clone()))
+            .unwrap_or_default();
+        nova.prove_step(&mut rng, external_inputs_at_step, None)?;
+        log::info!("Nova::prove_step {}: {:?}", i, start.elapsed());
+    }
+
+    write_ivc_proof::<SL, EIL>(dir, &nova.ivc_proof(), nova.pp_hash)
+}
+
+pub(crate) fn decide<const SL: usize, const EIL: usize>(
+    circuit: &CircuitArgs,
+    dir: &Path,
+) -> Result<(), Error> {
+    let rng = rand::rngs::OsRng;
+    let nova_params = read_nova_params::<SL, EIL>(circuit, dir)?;
+    let pp_hash = nova_params.1.pp_hash()?;
+    let ivc_proof = read_ivc_proof::<SL, EIL>(dir, pp_hash)?;
+    let nova = N::<SL, EIL>::from_ivc_proof(ivc_proof, circuit.params()?, nova_params)?;
+    let decider_pp = N::<SL, EIL>::read_decider_pp::<D<SL, EIL>>(
+        BufReader::new(File::open(dir.join(DECIDER_PP))?),
+        pp_hash,
+    )?;
+
+    let start = Instant::now();
+    let proof = D::<SL, EIL>::prove(rng, decider_pp, nova)?;
+    log::info!("Decider::prove: {:?}", start.elapsed());
+
+    let mut writer = BufWriter::new(File::create(dir.join(DECIDER_PROOF))?);
+    N::<SL, EIL>::write_decider_proof::<D<SL, EIL>>(&proof, pp_hash, &mut writer, Compress::Yes)?;
+    Ok(writer.flush()?)
+}
+
+pub(crate) fn verify<const SL: usize, const EIL: usize>(
+    circuit: &CircuitArgs,
+    dir: &Path,
+) -> Result<(), Error> {
+    let nova_vp = read_nova_vp::<SL, EIL>(circuit, dir)?;
+    let pp_hash = nova_vp.pp_hash()?;
+    let ivc_proof = read_ivc_proof::<SL, EIL>(dir, pp_hash)?;
+    N::<SL, EIL>::verify(nova_vp, ivc_proof.clone())?;
+    println!("IVC proof verified");
+
+    // the Decider proof is only checked once it has been generated by the `decide` command
+    if !dir.join(DECIDER_PROOF).exists() {
+        return Ok(());
+    }
+    let decider_vp = N::<SL, EIL>::read_decider_vp::<D<SL, EIL>>(
+        BufReader::new(File::open(dir.join(DECIDER_VP))?),
+        pp_hash,
+    )?;
+    let proof = N::<SL, EIL>::read_decider_proof::<D<SL, EIL>>(
+        BufReader::new(File::open(dir.join(DECIDER_PROOF))?),
+        pp_hash,
+    )?;
+    if !D::<SL, EIL>::verify(
+        decider_vp,
+        ivc_proof.i,
+        ivc_proof.z_0,
+        ivc_proof.z_i,
+        &ivc_proof.U_i.get_commitments(),
+        &ivc_proof.u_i.get_commitments(),
+        &proof,
+    )? {
+        return Err(Error::SNARKVerificationFail);
+    }
+    println!("Decider proof verified");
+    Ok(())
+}
+
+/// Stores both the raw calldata and its arguments formatted as decimal strings, which is useful
+/// for debugging the call on the EVM.
+pub(crate) fn calldata(mode: VerificationMode, dir: &Path) -> Result<(), Error> {
+    // the proofs have already been checked against the params by the `verify` command, so here
+    // only the kind of content of the files is checked, together with both proofs being bound to
+    // the same params
+    let (ivc_proof_header, ivc_proof): (_, IVCProof<G1, G2>) = read_unchecked::<Fr, _>(
+        BufReader::new(File::open(dir.join(IVC_PROOF))?),
+        Kind::IVCProof,
+    )?;
+    let (proof_header, proof): (_, DeciderProof<G1, KZG<'static, Bn254>, Groth16<Bn254>>) =
+        read_unchecked::<Fr, _>(
+            BufReader::new(File::open(dir.join(DECIDER_PROOF))?),
+            Kind::DeciderProof,
+        )?;
+    if ivc_proof_header.pp_hash != proof_header.pp_hash {
+        return Err(Error::PPHashMismatch);
+    }
+
+    let calldata = prepare_calldata_for_nova_cyclefold_verifier(
+        mode.into(),
+        ivc_proof.i,
+        ivc_proof.z_0,
+        ivc_proof.z_i,
+        &ivc_proof.U_i,
+        &ivc_proof.u_i,
+        &proof,
+    )?;
+    fs::write(dir.join(CALLDATA), &calldata)?;
+    fs::write(
+        dir.join(CALLDATA_INPUTS),
+        get_formatted_calldata(calldata).join(",\n"),
+    )?;
+    Ok(())
+}
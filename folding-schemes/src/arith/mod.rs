@@ -131,6 +131,18 @@ pub trait ArithSerializer {
     fn params_to_le_bytes(&self) -> Vec<u8>;
 }
 
+impl<A: ArithSerializer> ArithSerializer for Vec<A> {
+    /// Returns the concatenation of the parameters of each of the constraint
+    /// systems in `self`, prefixed by their amount.
+    fn params_to_le_bytes(&self) -> Vec<u8> {
+        [
+            self.len().to_le_bytes().to_vec(),
+            self.iter().flat_map(|a| a.params_to_le_bytes()).collect(),
+        ]
+        .concat()
+    }
+}
+
 /// `ArithSampler` allows sampling random pairs of witness and instance that
 /// satisfy the constraint system `self`.
 ///
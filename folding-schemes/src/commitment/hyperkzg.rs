@@ -0,0 +1,539 @@
+/// Implementation of the multilinear polynomial commitment scheme HyperKZG, a variant of
+/// [Gemini](https://eprint.iacr.org/2022/420.pdf) (section 2.4.2) over the KZG parameters, into
+/// the CommitmentScheme trait.
+///
+/// The committed vector `v` (padded to a power of two `2^k`) is seen as the evaluations over the
+/// boolean hypercube of a multilinear polynomial `f` in `k` variables, following the same
+/// little-endian variable ordering as `DenseMultilinearExtension` (and thus as `vec_to_dense_mle`
+/// and the sum-check points), and the opening proofs prove the evaluation `f(u)` at a point
+/// `u ∈ F^k`, which is the challenge of the scheme. This allows opening the committed vectors at
+/// the points output by sum-check directly, without re-encoding them as univariate polynomials.
+///
+/// The commitment is the univariate KZG commitment to the polynomial whose coefficients are `v`,
+/// i.e. `C = Σ v_i⋅β^i⋅G`, so it is additively homomorphic and it shares the `ProverKey` and
+/// `VerifierKey` of `KZG` (they can be also loaded from a ceremony through `ptau`, wrapping the
+/// `VerifierKey` with `VerifierParams::new`). The verifier params also fix the number of variables
+/// `k`, from which `verify` derives the challenge point. To open it,
+/// the prover commits to the polynomials `f^(1), ..., f^(k-1)` obtained by folding the
+/// coefficients of `f^(0)` pairwise with the coordinates of `u`, and proves the consistency of
+/// their evaluations at `r, -r, r^2` for a random `r`, batching the KZG openings of all of them.
+///
+/// As in `KZG`, in hiding mode (`H = true`) the commitment is blinded as `C + r⋅γ⋅G`, and so are
+/// the intermediate commitments, with fresh blinding factors. The prover also samples a fresh
+/// blinding polynomial `b(X)` of degree `HIDING_BOUND = 3` and absorbs its commitment `M` (a
+/// hiding commitment to the zero polynomial) before `r`, and `M` is batched with the other
+/// commitments. The batched openings include the quotients of the batched blinding polynomial in
+/// their witnesses and reveal its evaluations at `r, -r, r^2` as `random_v`, which are uniformly
+/// distributed thanks to `b(X)`. Notice that the evaluations of the folded polynomials are not
+/// masked, so the opening hides the commitments but it is not zero-knowledge.
+use ark_ec::{pairing::Pairing, CurveGroup};
+use ark_ff::Field;
+use ark_poly::univariate::DensePolynomial;
+use ark_poly_commit::kzg10::{
+    Commitment as KZG10Commitment, Proof as KZG10Proof, VerifierKey, KZG10,
+};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::rand::RngCore;
+use ark_std::{One, UniformRand, Zero};
+use core::marker::PhantomData;
+
+use super::{
+    kzg::{divide_by_linear, evaluate, ProverKey, KZG},
+    CommitmentScheme, CommitmentSchemeId, EvaluationProof,
+};
+use crate::constants::HYPERKZG_DOMAIN;
+use crate::transcript::{keccak::KeccakTranscript, Transcript};
+use crate::utils::msm::small_value_msm;
+use crate::{Curve, Error};
+
+#[derive(Debug, Clone, Default, Eq, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct Proof<C: Curve> {
+    /// evaluation of the multilinear extension of the committed vector at the challenge point
+    pub eval: C::ScalarField,
+    /// commitments to the folded polynomials `f^(1), ..., f^(k-1)`
+    pub cms: Vec<C>,
+    /// evaluations of `f^(0), ..., f^(k-1)` at `r`, `-r` and `r^2`
+    pub evals: Vec<[C::ScalarField; 3]>,
+    /// KZG witnesses of the batched polynomial at `r`, `-r` and `r^2`
+    pub proofs: [C; 3],
+    /// evaluations of the batched blinding polynomial at `r`, `-r` and `r^2`, zero when not in
+    /// hiding mode
+    pub random_v: [C::ScalarField; 3],
+    /// hiding commitment to the zero polynomial batched with the commitments to blind the
+    /// openings, zero when not in hiding mode
+    pub mask: C,
+}
+
+impl<C: Curve> EvaluationProof<C::ScalarField> for Proof<C> {
+    fn evaluation(&self) -> C::ScalarField {
+        self.eval
+    }
+}
+
+/// Verifier parameters of HyperKZG, which contain the `VerifierKey` of `KZG` and the number of
+/// variables of the committed multilinear polynomials.
+#[derive(Debug, Clone, CanonicalSerialize, CanonicalDeserialize)]
+pub struct VerifierParams<E: Pairing> {
+    pub vk: VerifierKey<E>,
+    pub n_vars: usize,
+}
+
+impl<E: Pairing> VerifierParams<E> {
+    /// Returns the verifier params for vectors of length `len`.
+    pub fn new(vk: VerifierKey<E>, len: usize) -> Self {
+        Self {
+            vk,
+            n_vars: n_vars(len),
+        }
+    }
+}
+
+/// HyperKZG implements the CommitmentScheme trait for the HyperKZG multilinear commitment scheme.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct HyperKZG<'a, E: Pairing, const H: bool = false> {
+    _a: PhantomData<&'a ()>,
+    _e: PhantomData<E>,
+}
+
+impl<'a, E: Pairing<G1: Curve>, const H: bool> CommitmentSchemeId for HyperKZG<'a, E, H> {
+    const ID: &'static str = "hyperkzg";
+}
+
+impl<'a, E: Pairing<G1: Curve>, const H: bool> CommitmentScheme<E::G1, H> for HyperKZG<'a, E, H> {
+    type ProverParams = ProverKey<'a, E::G1>;
+    type VerifierParams = VerifierParams<E>;
+    type Proof = Proof<E::G1>;
+    type ProverChallenge = Vec<E::ScalarField>;
+    type Challenge = Vec<E::ScalarField>;
+
+    fn is_hiding() -> bool {
+        if H {
+            return true;
+        }
+        false
+    }
+
+    /// setup returns the same parameters as `KZG::setup`, which contain the `len` powers needed
+    /// to commit to vectors of length `len`, and fixes the number of variables to `log2(len)`.
+    fn setup(
+        rng: impl RngCore,
+        len: usize,
+    ) -> Result<(Self::ProverParams, Self::VerifierParams), Error> {
+        let (pk, vk) = KZG::<'a, E, H>::setup(rng, len)?;
+        Ok((pk, VerifierParams::new(vk, len)))
+    }
+
+    fn commit(
+        params: &Self::ProverParams,
+        v: &[E::ScalarField],
+        blind: &E::ScalarField,
+    ) -> Result<E::G1, Error> {
+        if !H && (!blind.is_zero()) {
+            return Err(Error::BlindingNotZero);
+        }
+        let commitment = msm(params, v)?;
+        if !H || blind.is_zero() {
+            return Ok(commitment);
+        }
+        Ok(commitment + params.gamma_g()? * blind)
+    }
+
+    /// prove opens the multilinear extension of `v` at a point derived from the transcript, whose
+    /// number of variables is `log2(v.len())`, so `v` must have the length given at `setup` (up to
+    /// padding to a power of two) for the proof to verify.
+    fn prove(
+        params: &Self::ProverParams,
+        transcript: &mut impl Transcript<E::ScalarField>,
+        cm: &E::G1,
+        v: &[E::ScalarField],
+        blind: &E::ScalarField,
+        rng: Option<&mut dyn RngCore>,
+    ) -> Result<Self::Proof, Error> {
+        transcript.absorb_nonnative(cm);
+        let k = n_vars(v.len());
+        let point = transcript.get_challenges(k);
+        Self::prove_inner(params, transcript, &point, v, blind, rng)
+    }
+
+    /// prove_with_challenge proves the evaluation of the multilinear extension of `v` at the given
+    /// point, which determines the number of variables, and must be at least `log2(v.len())`.
+    fn prove_with_challenge(
+        params: &Self::ProverParams,
+        challenge: Self::ProverChallenge,
+        v: &[E::ScalarField],
+        blind: &E::ScalarField,
+        rng: Option<&mut dyn RngCore>,
+    ) -> Result<Self::Proof, Error> {
+        let cm = Self::commit(params, v, blind)?;
+        let transcript = &mut Self::challenge_transcript(&challenge, &cm);
+        Self::prove_inner(params, transcript, &challenge, v, blind, rng)
+    }
+
+    fn verify(
+        params: &Self::VerifierParams,
+        transcript: &mut impl Transcript<E::ScalarField>,
+        cm: &E::G1,
+        proof: &Self::Proof,
+    ) -> Result<(), Error> {
+        transcript.absorb_nonnative(cm);
+        let point = transcript.get_challenges(params.n_vars);
+        Self::verify_inner(&params.vk, transcript, &point, cm, proof)
+    }
+
+    fn verify_with_challenge(
+        params: &Self::VerifierParams,
+        challenge: Self::Challenge,
+        cm: &E::G1,
+        proof: &Self::Proof,
+    ) -> Result<(), Error> {
+        let transcript = &mut Self::challenge_transcript(&challenge, cm);
+        Self::verify_inner(&params.vk, transcript, &challenge, cm, proof)
+    }
+}
+
+impl<'a, E: Pairing<G1: Curve>, const H: bool> HyperKZG<'a, E, H> {
+    /// Transcript used to derive the challenges of the opening when the point is given externally.
+    fn challenge_transcript(
+        point: &[E::ScalarField],
+        cm: &E::G1,
+    ) -> KeccakTranscript<E::ScalarField> {
+        let mut transcript = KeccakTranscript::new(&());
+        transcript.domain_separator(HYPERKZG_DOMAIN);
+        transcript.absorb(&point.to_vec());
+        transcript.absorb_nonnative(cm);
+        transcript
+    }
+
+    fn prove_inner(
+        params: &ProverKey<'a, E::G1>,
+        transcript: &mut impl Transcript<E::ScalarField>,
+        point: &[E::ScalarField],
+        v: &[E::ScalarField],
+        blind: &E::ScalarField,
+        rng: Option<&mut dyn RngCore>,
+    ) -> Result<Proof<E::G1>, Error> {
+        if !H && (!blind.is_zero()) {
+            return Err(Error::BlindingNotZero);
+        }
+        let k = point.len();
+        if v.len() > 1 << k {
+            return Err(Error::NotExpectedLength(v.len(), 1 << k));
+        }
+
+        // f^(0) is v padded to 2^k, and f^(i+1) is f^(i) with its i-th variable fixed to u_i
+        let mut f = v.to_vec();
+        f.resize(1 << k, E::ScalarField::zero());
+        let mut polys = vec![f];
+        for u_i in point {
+            let f_i = polys.last().unwrap();
+            let next = f_i.chunks(2).map(|c| c[0] + *u_i * (c[1] - c[0])).collect();
+            polys.push(next);
+        }
+        let eval = polys[k][0];
+        // keep f^(0), ..., f^(k-1), or only f^(0) when there are no variables
+        polys.truncate(k.max(1));
+
+        // in hiding mode, the commitments to f^(1), ..., f^(k-1) are blinded with fresh factors,
+        // and the openings with the fresh blinding polynomial `b`, committed in `mask`
+        let (blinds, b, mask) = if H {
+            let rng = rng.ok_or(Error::MissingRandomness)?;
+            let blinds: Vec<E::ScalarField> = (1..polys.len())
+                .map(|_| E::ScalarField::rand(rng))
+                .collect();
+            let (b, mask) = params.sample_mask(rng)?;
+            (blinds, b, mask)
+        } else {
+            (
+                vec![E::ScalarField::zero(); polys.len() - 1],
+                vec![],
+                E::G1::zero(),
+            )
+        };
+        let cms = polys[1..]
+            .iter()
+            .zip(&blinds)
+            .map(|(f_i, blind_i)| Self::commit(params, f_i, blind_i))
+            .collect::<Result<Vec<_>, _>>()?;
+        cms.iter()
+            .for_each(|cm_i| transcript.absorb_nonnative(cm_i));
+        if H {
+            transcript.absorb_nonnative(&mask);
+        }
+        let r = transcript.get_challenge();
+        if r.is_zero() {
+            return Err(Error::CantBeZero("r".to_string()));
+        }
+        let xs = [r, -r, r.square()];
+
+        let evals: Vec<[E::ScalarField; 3]> = polys
+            .iter()
+            .map(|f_i| xs.map(|x| evaluate(f_i, x)))
+            .collect();
+        transcript.absorb(&evals.concat());
+        let gamma = transcript.get_challenge();
+
+        // batch the polynomials as q = Σ γ^i f^(i), whose commitment is C + Σ γ^i C_i, and whose
+        // blinding factor is thus blind + Σ γ^i blind_i. In hiding mode, the mask is batched too,
+        // as the k-th term, so the blinding polynomial of q is blind + Σ γ^i blind_i + γ^k b(X)
+        let mut q = vec![E::ScalarField::zero(); 1 << k];
+        let mut q_blind = *blind;
+        let mut gamma_i = E::ScalarField::one();
+        for (i, f_i) in polys.iter().enumerate() {
+            for (q_j, f_ij) in q.iter_mut().zip(f_i) {
+                *q_j += gamma_i * f_ij;
+            }
+            if i > 0 {
+                q_blind += gamma_i * blinds[i - 1];
+            }
+            gamma_i *= gamma;
+        }
+        let mut proofs = [E::G1::zero(); 3];
+        let mut random_v = [E::ScalarField::zero(); 3];
+        for (j, x) in xs.iter().enumerate() {
+            proofs[j] = msm(params, &divide_by_linear(&q, *x))?;
+            if H {
+                random_v[j] = q_blind + gamma_i * evaluate(&b, *x);
+                proofs[j] += params.commit_blinding(&divide_by_linear(&b, *x)) * gamma_i;
+            }
+        }
+
+        Ok(Proof {
+            eval,
+            cms,
+            evals,
+            proofs,
+            random_v,
+            mask,
+        })
+    }
+
+    fn verify_inner(
+        params: &VerifierKey<E>,
+        transcript: &mut impl Transcript<E::ScalarField>,
+        point: &[E::ScalarField],
+        cm: &E::G1,
+        proof: &Proof<E::G1>,
+    ) -> Result<(), Error> {
+        let k = point.len();
+        if proof.evals.len() != k.max(1) || proof.cms.len() != k.saturating_sub(1) {
+            return Err(Error::CommitmentVerificationFail);
+        }
+
+        proof
+            .cms
+            .iter()
+            .for_each(|cm_i| transcript.absorb_nonnative(cm_i));
+        if H {
+            transcript.absorb_nonnative(&proof.mask);
+        }
+        let r = transcript.get_challenge();
+        if r.is_zero() {
+            return Err(Error::CantBeZero("r".to_string()));
+        }
+        let xs = [r, -r, r.square()];
+        transcript.absorb(&proof.evals.concat());
+        let gamma = transcript.get_challenge();
+
+        // check that f^(i+1)(r^2) = (1-u_i)⋅e_i(r^2) + u_i⋅o_i(r^2), where f^(i)(X) =
+        // e_i(X^2) + X⋅o_i(X^2), and f^(k) is the constant polynomial `eval`
+        let two_inv = E::ScalarField::from(2u64).inverse().unwrap();
+        let two_r_inv = (r + r).inverse().unwrap();
+        for (i, u_i) in point.iter().enumerate() {
+            let [y_pos, y_neg, _] = proof.evals[i];
+            let even = (y_pos + y_neg) * two_inv;
+            let odd = (y_pos - y_neg) * two_r_inv;
+            let next = if i + 1 < k {
+                proof.evals[i + 1][2]
+            } else {
+                proof.eval
+            };
+            if next != even + *u_i * (odd - even) {
+                return Err(Error::CommitmentVerificationFail);
+            }
+        }
+        if k == 0 && proof.evals[0].iter().any(|y| *y != proof.eval) {
+            return Err(Error::CommitmentVerificationFail);
+        }
+
+        // check the batched KZG openings of q = Σ γ^i f^(i) at r, -r and r^2, which in hiding
+        // mode also batches the mask, that evaluates to zero
+        let mut cm_q = *cm;
+        let mut gamma_i = gamma;
+        for cm_i in &proof.cms {
+            cm_q += *cm_i * gamma_i;
+            gamma_i *= gamma;
+        }
+        if H {
+            cm_q += proof.mask * gamma_i;
+        }
+        let cm_q = KZG10Commitment(cm_q.into_affine());
+        for (j, (x, w)) in xs.iter().zip(&proof.proofs).enumerate() {
+            let mut y = E::ScalarField::zero();
+            let mut gamma_i = E::ScalarField::one();
+            for evals_i in &proof.evals {
+                y += gamma_i * evals_i[j];
+                gamma_i *= gamma;
+            }
+            let v = KZG10::<E, DensePolynomial<E::ScalarField>>::check(
+                params,
+                &cm_q,
+                *x,
+                y,
+                &KZG10Proof::<E> {
+                    w: w.into_affine(),
+                    random_v: if H { Some(proof.random_v[j]) } else { None },
+                },
+            )?;
+            if !v {
+                return Err(Error::CommitmentVerificationFail);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Number of variables of the multilinear extension of a vector of length `len`.
+fn n_vars(len: usize) -> usize {
+    len.next_power_of_two().trailing_zeros() as usize
+}
+
+/// Commits to the polynomial with coefficients `coeffs`.
+fn msm<C: Curve>(params: &ProverKey<C>, coeffs: &[C::ScalarField]) -> Result<C, Error> {
+    if coeffs.len() > params.powers_of_g.len() {
+        return Err(ark_poly_commit::Error::TooManyCoefficients {
+            num_coefficients: coeffs.len(),
+            num_powers: params.powers_of_g.len(),
+        }
+        .into());
+    }
+    Ok(small_value_msm(&params.powers_of_g[..coeffs.len()], coeffs))
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_bn254::{Bn254, Fr, G1Projective as G1};
+    use ark_crypto_primitives::sponge::{poseidon::PoseidonSponge, CryptographicSponge};
+    use ark_poly::Polynomial;
+    use ark_std::{test_rng, UniformRand};
+
+    use super::*;
+    use crate::transcript::poseidon::poseidon_canonical_config;
+    use crate::utils::mle::dense_vec_to_dense_mle;
+
+    #[test]
+    fn test_hyperkzg_commitment_scheme() -> Result<(), Error> {
+        let mut rng = &mut test_rng();
+        let poseidon_config = poseidon_canonical_config::<Fr>();
+        let transcript_p = &mut PoseidonSponge::<Fr>::new(&poseidon_config);
+        let transcript_v = &mut PoseidonSponge::<Fr>::new(&poseidon_config);
+
+        let n = 10;
+        let (pk, vk) = HyperKZG::<Bn254>::setup(&mut rng, n)?;
+        let v: Vec<Fr> = std::iter::repeat_with(|| Fr::rand(rng)).take(n).collect();
+        let cm = HyperKZG::<Bn254>::commit(&pk, &v, &Fr::zero())?;
+
+        let proof = HyperKZG::<Bn254>::prove(&pk, transcript_p, &cm, &v, &Fr::zero(), None)?;
+        HyperKZG::<Bn254>::verify(&vk, transcript_v, &cm, &proof)?;
+
+        // a wrong evaluation is rejected
+        let mut wrong_proof = proof.clone();
+        wrong_proof.eval += Fr::one();
+        let transcript_v = &mut PoseidonSponge::<Fr>::new(&poseidon_config);
+        assert!(HyperKZG::<Bn254>::verify(&vk, transcript_v, &cm, &wrong_proof).is_err());
+
+        // the number of variables is fixed by the verifier params, so a proof for a point with
+        // fewer variables is rejected
+        let short_proof = HyperKZG::<Bn254>::prove_with_challenge(
+            &pk,
+            vec![Fr::rand(rng); vk.n_vars - 1],
+            &v[..n / 2],
+            &Fr::zero(),
+            None,
+        )?;
+        let short_cm = HyperKZG::<Bn254>::commit(&pk, &v[..n / 2], &Fr::zero())?;
+        let transcript_v = &mut PoseidonSponge::<Fr>::new(&poseidon_config);
+        assert!(HyperKZG::<Bn254>::verify(&vk, transcript_v, &short_cm, &short_proof).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_hyperkzg_with_challenge() -> Result<(), Error> {
+        let mut rng = &mut test_rng();
+
+        for n in [1, 2, 7, 16] {
+            let (pk, vk): (ProverKey<G1>, VerifierParams<Bn254>) =
+                HyperKZG::<Bn254>::setup(&mut rng, n)?;
+            let v: Vec<Fr> = std::iter::repeat_with(|| Fr::rand(rng)).take(n).collect();
+            let cm = HyperKZG::<Bn254>::commit(&pk, &v, &Fr::zero())?;
+
+            // the commitment is the same as the KZG commitment to `v` as coefficients, so it is
+            // homomorphic
+            let v2: Vec<Fr> = std::iter::repeat_with(|| Fr::rand(rng)).take(n).collect();
+            let cm2 = HyperKZG::<Bn254>::commit(&pk, &v2, &Fr::zero())?;
+            let sum: Vec<Fr> = v.iter().zip(&v2).map(|(a, b)| *a + b).collect();
+            assert_eq!(HyperKZG::<Bn254>::commit(&pk, &sum, &Fr::zero())?, cm + cm2);
+
+            // the opening proves the evaluation of the MLE of `v`
+            let n_vars = n_vars(n);
+            let point: Vec<Fr> = std::iter::repeat_with(|| Fr::rand(rng))
+                .take(n_vars)
+                .collect();
+            let proof =
+                HyperKZG::<Bn254>::prove_with_challenge(&pk, point.clone(), &v, &Fr::zero(), None)?;
+            assert_eq!(
+                proof.eval,
+                dense_vec_to_dense_mle(n_vars, &v).evaluate(&point)
+            );
+            HyperKZG::<Bn254>::verify_with_challenge(&vk, point.clone(), &cm, &proof)?;
+
+            // but not at a different point nor for a different commitment
+            let mut other_point = point.clone();
+            other_point.push(Fr::rand(rng));
+            assert!(
+                HyperKZG::<Bn254>::verify_with_challenge(&vk, other_point, &cm, &proof).is_err()
+            );
+            assert!(HyperKZG::<Bn254>::verify_with_challenge(&vk, point, &cm2, &proof).is_err());
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_hyperkzg_hiding() -> Result<(), Error> {
+        let mut rng = &mut test_rng();
+
+        let n = 16;
+        let (pk, vk) = HyperKZG::<Bn254, true>::setup(&mut rng, n)?;
+        let v: Vec<Fr> = std::iter::repeat_with(|| Fr::rand(rng)).take(n).collect();
+        let blind = Fr::rand(rng);
+        let cm = HyperKZG::<Bn254, true>::commit(&pk, &v, &blind)?;
+        assert_ne!(cm, HyperKZG::<Bn254>::commit(&pk, &v, &Fr::zero())?);
+
+        let point: Vec<Fr> = std::iter::repeat_with(|| Fr::rand(rng)).take(4).collect();
+        // the intermediate commitments are blinded with fresh randomness
+        assert!(matches!(
+            HyperKZG::<Bn254, true>::prove_with_challenge(&pk, point.clone(), &v, &blind, None),
+            Err(Error::MissingRandomness)
+        ));
+        let proof = HyperKZG::<Bn254, true>::prove_with_challenge(
+            &pk,
+            point.clone(),
+            &v,
+            &blind,
+            Some(&mut rng),
+        )?;
+        // the openings reveal evaluations of the blinding polynomial, not the blinding factor
+        assert!(proof.random_v.iter().all(|random_v| *random_v != blind));
+        HyperKZG::<Bn254, true>::verify_with_challenge(&vk, point.clone(), &cm, &proof)?;
+
+        // a mask that does not commit to the zero polynomial is rejected
+        let mut bad_proof = proof.clone();
+        bad_proof.mask += HyperKZG::<Bn254>::commit(&pk, &[Fr::one()], &Fr::zero())?;
+        assert!(
+            HyperKZG::<Bn254, true>::verify_with_challenge(&vk, point, &cm, &bad_proof).is_err()
+        );
+
+        // blinding is rejected when not in hiding mode
+        assert!(HyperKZG::<Bn254>::commit(&pk, &v, &blind).is_err());
+        Ok(())
+    }
+}
@@ -0,0 +1,516 @@
+/// Implementation of the [Hyrax](https://eprint.iacr.org/2017/1132.pdf) multilinear commitment
+/// scheme over Pedersen rows, a transparent commitment scheme whose openings prove the evaluation
+/// `f(u)` at a point `u` of the multilinear polynomial `f` whose evaluations over the boolean
+/// hypercube are the committed vector `v` (with the `DenseMultilinearExtension` variable ordering,
+/// as in `HyperKZG`).
+///
+/// The vector `v` is arranged as a matrix of `n_cols = params.generators.len()` columns (which
+/// `setup_rows` sets to `sqrt(len(v))`), and the commitment is the vector of the Pedersen
+/// commitments to its rows. The evaluation vector at `u` is the tensor product of the `eq` vectors
+/// of its coordinates, `eq(u) = L ⊗ R`, so the verifier folds the row commitments with `L` into
+/// a commitment to the combination of the rows `w = Σ L_i⋅row_i`, and the prover proves
+/// `<w, R> = f(u)` with Hyrax's proof-of-dot-product: it commits to a random mask `d` and its
+/// product `<d, R>`, and reveals `z = c⋅w + d` for a challenge `c`, which the verifier checks
+/// against both the folded commitment and the evaluation. Both the commitment and the proof
+/// contain `sqrt(len(v))` elements, and in hiding mode (`H = true`) the openings are
+/// zero-knowledge, since each row is blinded with `r_i⋅h` and `d` masks `w`.
+///
+/// Since the commitment is a vector of group elements that can not be compressed into a single
+/// one, Hyrax does not implement the `CommitmentScheme` trait, whose commitments are folded
+/// homomorphically as single group elements by the folding schemes. `PedersenMLE` provides the
+/// same kind of openings for the single-element Pedersen commitments, with linear-size proofs.
+use ark_ff::PrimeField;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::{marker::PhantomData, rand::RngCore, UniformRand, Zero};
+
+use super::{pedersen::Params, EvaluationProof};
+use crate::constants::HYRAX_DOMAIN;
+use crate::transcript::{keccak::KeccakTranscript, Transcript};
+use crate::utils::espresso::virtual_polynomial::build_eq_x_r_vec;
+use crate::utils::msm::small_value_msm;
+use crate::utils::vec::mat_vec_mul_dense;
+use crate::{Curve, Error};
+
+/// Hyrax's proof-of-dot-product, which proves that the vector committed in a Pedersen commitment
+/// has the evaluation `eval` as dot product with a public vector `b`.
+#[derive(Debug, Clone, Eq, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct DotProductProof<C: Curve> {
+    /// dot product of the committed vector and `b`, ie. the evaluation of the multilinear
+    /// extension of the committed vector at the challenge point
+    pub eval: C::ScalarField,
+    /// commitment to the mask `d`, `δ = <g, d> + h⋅r_δ`
+    pub delta: C,
+    /// product of the mask and the public vector, `β = <d, b>`
+    pub beta: C::ScalarField,
+    /// masked vector, `z = c⋅v + d`
+    pub z: Vec<C::ScalarField>,
+    /// masked blinding factor, `z_r = c⋅r + r_δ`, zero when not in hiding mode
+    pub z_r: C::ScalarField,
+}
+
+impl<C: Curve> EvaluationProof<C::ScalarField> for DotProductProof<C> {
+    fn evaluation(&self) -> C::ScalarField {
+        self.eval
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Hyrax<C: Curve, const H: bool = false> {
+    _c: PhantomData<C>,
+}
+
+impl<C: Curve, const H: bool> Hyrax<C, H> {
+    /// Proves that the vector `v` committed with the blinding factor `blind` under the first
+    /// `b.len()` generators of `params` has the dot product `<v, b>` with `b`. In hiding mode the
+    /// rng is needed to mask `v`.
+    pub(crate) fn prove_dot_product(
+        params: &Params<C>,
+        transcript: &mut impl Transcript<C::ScalarField>,
+        b: &[C::ScalarField],
+        v: &[C::ScalarField],
+        blind: &C::ScalarField,
+        rng: Option<&mut dyn RngCore>,
+    ) -> Result<DotProductProof<C>, Error> {
+        if !H && (!blind.is_zero()) {
+            return Err(Error::BlindingNotZero);
+        }
+        let n = b.len();
+        if v.len() > n {
+            return Err(Error::NotExpectedLength(v.len(), n));
+        }
+        if params.generators.len() < n {
+            return Err(Error::PedersenParamsLen(params.generators.len(), n));
+        }
+        let mut v = v.to_vec();
+        v.resize(n, C::ScalarField::zero());
+        let eval = inner_product(&v, b);
+
+        // the masks are only needed to hide `v` in hiding mode, otherwise they are zero unless an
+        // rng is given
+        let (d, r_delta): (Vec<C::ScalarField>, C::ScalarField) = match rng {
+            Some(rng) => {
+                let d = (0..n).map(|_| C::ScalarField::rand(rng)).collect();
+                let r_delta = if H {
+                    C::ScalarField::rand(rng)
+                } else {
+                    C::ScalarField::zero()
+                };
+                (d, r_delta)
+            }
+            None if H => return Err(Error::MissingRandomness),
+            None => (vec![C::ScalarField::zero(); n], C::ScalarField::zero()),
+        };
+        // δ = <g, d> + h⋅r_δ, β = <d, b>
+        let mut delta = small_value_msm(&params.generators[..n], &d);
+        if H {
+            delta += params.h.mul(r_delta);
+        }
+        let beta = inner_product(&d, b);
+
+        let c = Self::dot_product_challenge(transcript, &eval, &delta, &beta);
+        // z = c⋅v + d, z_r = c⋅r + r_δ
+        let z = v.iter().zip(&d).map(|(v_i, d_i)| c * v_i + d_i).collect();
+        let z_r = if H {
+            c * blind + r_delta
+        } else {
+            C::ScalarField::zero()
+        };
+        Ok(DotProductProof {
+            eval,
+            delta,
+            beta,
+            z,
+            z_r,
+        })
+    }
+
+    /// Verifies the proof-of-dot-product of the vector committed in `cm` and `b`.
+    pub(crate) fn verify_dot_product(
+        params: &Params<C>,
+        transcript: &mut impl Transcript<C::ScalarField>,
+        b: &[C::ScalarField],
+        cm: &C,
+        proof: &DotProductProof<C>,
+    ) -> Result<(), Error> {
+        let n = b.len();
+        if proof.z.len() != n {
+            return Err(Error::NotExpectedLength(proof.z.len(), n));
+        }
+        if params.generators.len() < n {
+            return Err(Error::PedersenParamsLen(params.generators.len(), n));
+        }
+        if !H && (!proof.z_r.is_zero()) {
+            return Err(Error::BlindingNotZero);
+        }
+        let c = Self::dot_product_challenge(transcript, &proof.eval, &proof.delta, &proof.beta);
+
+        // check that c⋅cm + δ == <g, z> + h⋅z_r
+        // use msm_unchecked because we already ensured at the if that lengths match
+        let mut rhs = C::msm_unchecked(&params.generators[..n], &proof.z);
+        if H {
+            rhs += params.h.mul(proof.z_r);
+        }
+        if cm.mul(c) + proof.delta != rhs {
+            return Err(Error::CommitmentVerificationFail);
+        }
+
+        // check that c⋅eval + β == <z, b>
+        if c * proof.eval + proof.beta != inner_product(&proof.z, b) {
+            return Err(Error::CommitmentVerificationFail);
+        }
+        Ok(())
+    }
+
+    /// Absorbs the evaluation and the first messages of the proof-of-dot-product, and derives its
+    /// challenge `c`.
+    fn dot_product_challenge(
+        transcript: &mut impl Transcript<C::ScalarField>,
+        eval: &C::ScalarField,
+        delta: &C,
+        beta: &C::ScalarField,
+    ) -> C::ScalarField {
+        transcript.absorb(eval);
+        transcript.absorb_nonnative(delta);
+        transcript.absorb(beta);
+        transcript.get_challenge()
+    }
+
+    /// setup_rows returns the parameters to commit to the rows of vectors of length up to `len`,
+    /// which contain `sqrt(len)` generators (rounded up to a power of two).
+    pub fn setup_rows(mut rng: impl RngCore, len: usize) -> Result<Params<C>, Error> {
+        let n_vars = len.next_power_of_two().trailing_zeros();
+        let n_cols = 1 << n_vars.div_ceil(2);
+        let generators: Vec<C::Affine> = std::iter::repeat_with(|| C::Affine::rand(&mut rng))
+            .take(n_cols)
+            .collect();
+        Ok(Params::<C> {
+            h: C::rand(&mut rng),
+            generators,
+            tables: None,
+        })
+    }
+
+    /// commit_rows returns the commitments to the rows of `v`, blinded with `blinds` (one per row)
+    /// in hiding mode.
+    pub fn commit_rows(
+        params: &Params<C>,
+        v: &[C::ScalarField],
+        blinds: &[C::ScalarField],
+    ) -> Result<Vec<C>, Error> {
+        let rows = Self::rows(params, v);
+        Self::check_blinds(blinds, rows.len())?;
+        Ok(rows
+            .iter()
+            .enumerate()
+            .map(|(i, row)| {
+                let cm = small_value_msm(&params.generators[..row.len()], row);
+                if H {
+                    cm + params.h.mul(blinds[i])
+                } else {
+                    cm
+                }
+            })
+            .collect())
+    }
+
+    /// prove_rows derives the evaluation point from the transcript and proves the evaluation of
+    /// `v` at it. In hiding mode the rng is needed to mask the combination of the rows.
+    pub fn prove_rows(
+        params: &Params<C>,
+        transcript: &mut impl Transcript<C::ScalarField>,
+        cm: &[C],
+        v: &[C::ScalarField],
+        blinds: &[C::ScalarField],
+        rng: Option<&mut dyn RngCore>,
+    ) -> Result<DotProductProof<C>, Error> {
+        let point = Self::challenge(params, transcript, cm);
+        Self::prove_rows_inner(params, transcript, &point, v, blinds, rng)
+    }
+
+    /// same as `prove_rows` but providing the evaluation point, which must have at least
+    /// `log2(n_cols)` coordinates, and enough of them to index all the rows.
+    pub fn prove_rows_with_challenge(
+        params: &Params<C>,
+        challenge: Vec<C::ScalarField>,
+        v: &[C::ScalarField],
+        blinds: &[C::ScalarField],
+        rng: Option<&mut dyn RngCore>,
+    ) -> Result<DotProductProof<C>, Error> {
+        let cm = Self::commit_rows(params, v, blinds)?;
+        let transcript = &mut Self::challenge_transcript(&challenge, &cm);
+        Self::prove_rows_inner(params, transcript, &challenge, v, blinds, rng)
+    }
+
+    pub fn verify_rows(
+        params: &Params<C>,
+        transcript: &mut impl Transcript<C::ScalarField>,
+        cm: &[C],
+        proof: &DotProductProof<C>,
+    ) -> Result<(), Error> {
+        let point = Self::challenge(params, transcript, cm);
+        Self::verify_rows_inner(params, transcript, &point, cm, proof)
+    }
+
+    /// same as `verify_rows` but providing the evaluation point
+    pub fn verify_rows_with_challenge(
+        params: &Params<C>,
+        challenge: Vec<C::ScalarField>,
+        cm: &[C],
+        proof: &DotProductProof<C>,
+    ) -> Result<(), Error> {
+        let transcript = &mut Self::challenge_transcript(&challenge, cm);
+        Self::verify_rows_inner(params, transcript, &challenge, cm, proof)
+    }
+
+    /// Transcript used to derive the challenge of the proof-of-dot-product when the point is given
+    /// externally.
+    fn challenge_transcript(
+        point: &[C::ScalarField],
+        cm: &[C],
+    ) -> KeccakTranscript<C::ScalarField> {
+        let mut transcript = KeccakTranscript::new(&());
+        transcript.domain_separator(HYRAX_DOMAIN);
+        transcript.absorb(&point.to_vec());
+        cm.iter().for_each(|cm_i| transcript.absorb_nonnative(cm_i));
+        transcript
+    }
+
+    fn prove_rows_inner(
+        params: &Params<C>,
+        transcript: &mut impl Transcript<C::ScalarField>,
+        point: &[C::ScalarField],
+        v: &[C::ScalarField],
+        blinds: &[C::ScalarField],
+        rng: Option<&mut dyn RngCore>,
+    ) -> Result<DotProductProof<C>, Error> {
+        let rows = Self::rows(params, v);
+        Self::check_blinds(blinds, rows.len())?;
+        let (L, R) = Self::tensor(params, point, rows.len())?;
+
+        // w = Σ L_i⋅row_i, computed as the product of the transposed matrix by L, which is the
+        // vector committed in the combination of the row commitments Σ L_i⋅cm_i, with the
+        // blinding factor r_w = Σ L_i⋅r_i
+        let n_cols = params.generators.len();
+        let columns: Vec<Vec<C::ScalarField>> = (0..n_cols)
+            .map(|j| rows.iter().map(|row| row[j]).collect())
+            .collect();
+        let w = mat_vec_mul_dense(&columns, &L[..rows.len()])?;
+        let r_w = if H {
+            blinds.iter().zip(&L).map(|(r_i, l_i)| *r_i * l_i).sum()
+        } else {
+            C::ScalarField::zero()
+        };
+
+        // prove that <w, R> is the evaluation, with a proof of `n_cols` elements
+        Self::prove_dot_product(params, transcript, &R, &w, &r_w, rng)
+    }
+
+    fn verify_rows_inner(
+        params: &Params<C>,
+        transcript: &mut impl Transcript<C::ScalarField>,
+        point: &[C::ScalarField],
+        cm: &[C],
+        proof: &DotProductProof<C>,
+    ) -> Result<(), Error> {
+        let (L, R) = Self::tensor(params, point, cm.len())?;
+
+        // fold the row commitments into the commitment to w = Σ L_i⋅row_i, and check that
+        // <w, R> is the evaluation
+        let cm_w: C = cm.iter().zip(&L).map(|(cm_i, l_i)| cm_i.mul(l_i)).sum();
+        Self::verify_dot_product(params, transcript, &R, &cm_w, proof)
+    }
+
+    /// Splits `v` in rows of `n_cols` elements, padding the last one with zeros.
+    fn rows(params: &Params<C>, v: &[C::ScalarField]) -> Vec<Vec<C::ScalarField>> {
+        let n_cols = params.generators.len();
+        let mut v = v.to_vec();
+        v.resize(
+            v.len().div_ceil(n_cols).max(1) * n_cols,
+            C::ScalarField::zero(),
+        );
+        v.chunks(n_cols).map(|row| row.to_vec()).collect()
+    }
+
+    fn check_blinds(blinds: &[C::ScalarField], n_rows: usize) -> Result<(), Error> {
+        if !H {
+            if blinds.iter().any(|r| !r.is_zero()) {
+                return Err(Error::BlindingNotZero);
+            }
+            return Ok(());
+        }
+        if blinds.len() != n_rows {
+            return Err(Error::NotSameLength(
+                "blinds.len()".to_string(),
+                blinds.len(),
+                "n_rows".to_string(),
+                n_rows,
+            ));
+        }
+        Ok(())
+    }
+
+    /// Absorbs the row commitments and derives a point with as many coordinates as the
+    /// multilinear extension of the committed vector has variables.
+    fn challenge(
+        params: &Params<C>,
+        transcript: &mut impl Transcript<C::ScalarField>,
+        cm: &[C],
+    ) -> Vec<C::ScalarField> {
+        cm.iter().for_each(|cm_i| transcript.absorb_nonnative(cm_i));
+        let n_vars = params.generators.len().trailing_zeros()
+            + cm.len().next_power_of_two().trailing_zeros();
+        transcript.get_challenges(n_vars as usize)
+    }
+
+    /// Returns the vectors `L` and `R` such that the evaluation vector of the multilinear
+    /// extension at `point` is `L ⊗ R`, checking that `L` covers the `n_rows` rows.
+    #[allow(clippy::type_complexity)]
+    fn tensor(
+        params: &Params<C>,
+        point: &[C::ScalarField],
+        n_rows: usize,
+    ) -> Result<(Vec<C::ScalarField>, Vec<C::ScalarField>), Error> {
+        let n_cols = params.generators.len();
+        let col_vars = n_cols.trailing_zeros() as usize;
+        if !n_cols.is_power_of_two() {
+            return Err(Error::NotPowerOfTwo("n_cols".to_string(), n_cols));
+        }
+        if point.len() < col_vars || (1 << (point.len() - col_vars)) < n_rows {
+            return Err(Error::NotExpectedLength(
+                point.len(),
+                col_vars + n_rows.next_power_of_two().trailing_zeros() as usize,
+            ));
+        }
+        // the first coordinates of the point correspond to the columns (least significant bits
+        // of the index) and the last ones to the rows
+        let R = eq_vec(&point[..col_vars])?;
+        let L = eq_vec(&point[col_vars..])?;
+        Ok((L, R))
+    }
+}
+
+/// Returns the evaluation vector `eq(r)` of the multilinear extensions at the point `r`, which is
+/// `[1]` when `r` is empty.
+pub(crate) fn eq_vec<F: PrimeField>(r: &[F]) -> Result<Vec<F>, Error> {
+    if r.is_empty() {
+        return Ok(vec![F::one()]);
+    }
+    Ok(build_eq_x_r_vec(r)?)
+}
+
+pub(crate) fn inner_product<F: PrimeField>(a: &[F], b: &[F]) -> F {
+    a.iter().zip(b).map(|(a_i, b_i)| *a_i * b_i).sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_crypto_primitives::sponge::{poseidon::PoseidonSponge, CryptographicSponge};
+    use ark_pallas::{Fr, Projective};
+    use ark_poly::Polynomial;
+
+    use super::*;
+    use crate::transcript::poseidon::poseidon_canonical_config;
+    use crate::utils::mle::dense_vec_to_dense_mle;
+
+    #[test]
+    fn test_hyrax_rows() -> Result<(), Error> {
+        let _ = test_hyrax_rows_opt::<false>()?;
+        let _ = test_hyrax_rows_opt::<true>()?;
+        Ok(())
+    }
+    fn test_hyrax_rows_opt<const hiding: bool>() -> Result<(), Error> {
+        let mut rng = ark_std::test_rng();
+        let poseidon_config = poseidon_canonical_config::<Fr>();
+
+        for n in [1, 5, 16, 100] {
+            let params = Hyrax::<Projective, hiding>::setup_rows(&mut rng, n)?;
+            assert_eq!(
+                params.generators.len(),
+                1 << n.next_power_of_two().trailing_zeros().div_ceil(2)
+            );
+
+            let v: Vec<Fr> = std::iter::repeat_with(|| Fr::rand(&mut rng))
+                .take(n)
+                .collect();
+            let n_rows = n.div_ceil(params.generators.len());
+            let blinds: Vec<Fr> = if hiding {
+                std::iter::repeat_with(|| Fr::rand(&mut rng))
+                    .take(n_rows)
+                    .collect()
+            } else {
+                vec![]
+            };
+            let cm = Hyrax::<Projective, hiding>::commit_rows(&params, &v, &blinds)?;
+            assert_eq!(cm.len(), n_rows);
+
+            let mut transcript_p = PoseidonSponge::<Fr>::new(&poseidon_config);
+            let mut transcript_v = PoseidonSponge::<Fr>::new(&poseidon_config);
+            let proof = Hyrax::<Projective, hiding>::prove_rows(
+                &params,
+                &mut transcript_p,
+                &cm,
+                &v,
+                &blinds,
+                Some(&mut rng),
+            )?;
+            // the opening has as many elements as the columns of the matrix
+            assert_eq!(proof.z.len(), params.generators.len());
+            Hyrax::<Projective, hiding>::verify_rows(&params, &mut transcript_v, &cm, &proof)?;
+
+            // the opening proves the evaluation of the MLE of `v`
+            let n_vars = n.next_power_of_two().trailing_zeros() as usize;
+            let point: Vec<Fr> = std::iter::repeat_with(|| Fr::rand(&mut rng))
+                .take(n_vars.max(params.generators.len().trailing_zeros() as usize))
+                .collect();
+            let proof = Hyrax::<Projective, hiding>::prove_rows_with_challenge(
+                &params,
+                point.clone(),
+                &v,
+                &blinds,
+                Some(&mut rng),
+            )?;
+            assert_eq!(
+                proof.evaluation(),
+                dense_vec_to_dense_mle(point.len(), &v).evaluate(&point)
+            );
+            Hyrax::<Projective, hiding>::verify_rows_with_challenge(
+                &params,
+                point.clone(),
+                &cm,
+                &proof,
+            )?;
+
+            // a wrong evaluation or different row commitments are rejected
+            let mut wrong_proof = proof.clone();
+            wrong_proof.eval += Fr::from(1);
+            assert!(Hyrax::<Projective, hiding>::verify_rows_with_challenge(
+                &params,
+                point.clone(),
+                &cm,
+                &wrong_proof
+            )
+            .is_err());
+            let mut wrong_cm = cm.clone();
+            wrong_cm[0] += params.h;
+            assert!(Hyrax::<Projective, hiding>::verify_rows_with_challenge(
+                &params, point, &wrong_cm, &proof
+            )
+            .is_err());
+        }
+
+        // in hiding mode, the opening needs randomness to mask the combination of the rows
+        if hiding {
+            let params = Hyrax::<Projective, hiding>::setup_rows(&mut rng, 4)?;
+            let v = vec![Fr::from(1); 4];
+            assert!(Hyrax::<Projective, hiding>::prove_rows_with_challenge(
+                &params,
+                vec![Fr::from(2); 2],
+                &v,
+                &[Fr::from(3), Fr::from(4)],
+                None
+            )
+            .is_err());
+        }
+        Ok(())
+    }
+}
@@ -1,3 +1,4 @@
+use ark_crypto_primitives::sponge::CryptographicSponge;
 /// IPA implements the modified Inner Product Argument described in
 /// [Halo](https://eprint.iacr.org/2019/1021.pdf). The variable names used follow the paper
 /// notation in order to make it more readable.
@@ -9,6 +10,7 @@
 /// ii. s computation is done in 2^{k+1}-2 instead of k*2^k.
 use ark_ec::AffineRepr;
 use ark_ff::{Field, PrimeField};
+use ark_poly::{EvaluationDomain, GeneralEvaluationDomain};
 use ark_r1cs_std::{
     alloc::{AllocVar, AllocationMode},
     boolean::Boolean,
@@ -23,9 +25,12 @@ use ark_std::{cfg_iter, rand::RngCore, UniformRand, Zero};
 use core::{borrow::Borrow, marker::PhantomData};
 use rayon::iter::{IndexedParallelIterator, IntoParallelRefIterator, ParallelIterator};
 
-use super::{pedersen::Params as PedersenParams, CommitmentScheme};
+use super::{
+    pedersen::Params as PedersenParams, CommitmentScheme, CommitmentSchemeId, EvaluationProof,
+};
+use crate::constants::IPA_DOMAIN;
 use crate::folding::circuits::CF2;
-use crate::transcript::Transcript;
+use crate::transcript::{keccak::KeccakTranscript, Transcript};
 use crate::utils::{
     powers_of,
     vec::{vec_add, vec_scalar_mul},
@@ -41,6 +46,12 @@ pub struct Proof<C: Curve> {
     R: Vec<C>,
 }
 
+impl<C: Curve> EvaluationProof<C::ScalarField> for (Proof<C>, C::ScalarField, C::ScalarField) {
+    fn evaluation(&self) -> C::ScalarField {
+        self.1
+    }
+}
+
 /// IPA implements the Inner Product Argument protocol following the CommitmentScheme trait. The
 /// `H` parameter indicates if to use the commitment in hiding mode or not.
 #[derive(Debug, Clone, Eq, PartialEq)]
@@ -48,13 +59,67 @@ pub struct IPA<C: Curve, const H: bool = false> {
     _c: PhantomData<C>,
 }
 
-/// Implements the CommitmentScheme trait for IPA
+/// Opening proof of several committed vectors at the same point, consisting of the evaluation of
+/// each vector and a single IPA proof of their random linear combination.
+#[derive(Debug, Clone, Eq, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct BatchProof<C: Curve> {
+    pub evals: Vec<C::ScalarField>,
+    pub proof: (Proof<C>, C::ScalarField, C::ScalarField), // (proof, v=p(x), r=blinding factor)
+}
+
+/// Basis in which the committed vector is opened at the challenge point `x`.
+#[derive(Debug, Clone, Copy)]
+enum Basis {
+    /// `v = <a, (1, x, x^2, ..., x^{d-1})>`, ie. `a` are the coefficients of the polynomial, as in
+    /// Halo. Used by `prove` & `verify`, and by the `IPAGadget`.
+    Monomial,
+    /// `v = <a, (L_0(x), L_1(x), ..., L_{d-1}(x))>`, ie. `a` are the evaluations of the polynomial
+    /// over the radix-2 domain of size `d`. This is the polynomial that `KZG` commits to and that
+    /// the deciders' `EvalGadget` evaluates, so it is used by the `*_with_challenge` methods, which
+    /// allows using IPA in place of KZG in the deciders.
+    Lagrange,
+}
+
+impl Basis {
+    /// Returns the vector `b` such that `v = <a, b>`.
+    fn b_vec<F: PrimeField>(self, x: F, d: usize) -> Result<Vec<F>, Error> {
+        match self {
+            Basis::Monomial => Ok(powers_of(x, d)),
+            Basis::Lagrange => {
+                let domain = GeneralEvaluationDomain::<F>::new(d).ok_or(Error::NewDomainFail)?;
+                if domain.size() != d {
+                    return Err(Error::NotExpectedLength(domain.size(), d));
+                }
+                Ok(domain.evaluate_all_lagrange_coefficients(x))
+            }
+        }
+    }
+
+    /// Returns `<s, b>`, which for the monomial basis is computed in log time.
+    fn s_b_inner<F: PrimeField>(self, s: &[F], u: &[F], x: F) -> Result<F, Error> {
+        match self {
+            Basis::Monomial => s_b_inner(u, &x),
+            Basis::Lagrange => inner_prod(s, &self.b_vec(x, s.len())?),
+        }
+    }
+}
+
+impl<C: Curve, const H: bool> CommitmentSchemeId for IPA<C, H> {
+    const ID: &'static str = "ipa";
+}
+
+/// Implements the CommitmentScheme trait for IPA.
+///
+/// `prove` & `verify` derive all the challenges from the given transcript. Instead,
+/// `prove_with_challenge` & `verify_with_challenge` take the evaluation point `x` as the challenge
+/// (eg. computed in-circuit by the deciders' `KZGChallengesGadget`), and derive the challenges of
+/// the IPA rounds from `x`, the commitment and the proof with an internal Keccak transcript.
 impl<C: Curve, const H: bool> CommitmentScheme<C, H> for IPA<C, H> {
     type ProverParams = PedersenParams<C>;
     type VerifierParams = PedersenParams<C>;
     type Proof = (Proof<C>, C::ScalarField, C::ScalarField); // (proof, v=p(x), r=blinding factor)
-    type ProverChallenge = (C::ScalarField, C, Vec<C::ScalarField>);
-    type Challenge = (C::ScalarField, C, Vec<C::ScalarField>);
+    type ProverChallenge = C::ScalarField;
+    type Challenge = C::ScalarField;
 
     fn is_hiding() -> bool {
         if H {
@@ -73,6 +138,7 @@ impl<C: Curve, const H: bool> CommitmentScheme<C, H> for IPA<C, H> {
         let p = PedersenParams::<C> {
             h: C::rand(&mut rng),
             generators,
+            tables: None,
         };
         Ok((p.clone(), p))
     }
@@ -108,6 +174,195 @@ impl<C: Curve, const H: bool> CommitmentScheme<C, H> for IPA<C, H> {
         if !a.len().is_power_of_two() {
             return Err(Error::NotPowerOfTwo("a".to_string(), a.len()));
         }
+
+        transcript.absorb_nonnative(P);
+        let x = transcript.get_challenge(); // challenge value at which we evaluate
+
+        Self::prove_inner(params, transcript, x, Basis::Monomial, a, blind, rng)
+    }
+
+    /// Proves the opening of `a` at the given challenge `x`, where `a` is seen as the evaluations
+    /// of a polynomial over the radix-2 domain (zero-padding `a` to the next power of two, which
+    /// does not change its commitment), as done in KZG.
+    fn prove_with_challenge(
+        params: &Self::ProverParams,
+        challenge: Self::ProverChallenge,
+        a: &[C::ScalarField], // vector
+        blind: &C::ScalarField,
+        rng: Option<&mut dyn RngCore>,
+    ) -> Result<Self::Proof, Error> {
+        let mut a = a.to_vec();
+        a.resize(a.len().next_power_of_two(), C::ScalarField::zero());
+
+        let P = Self::commit(params, &a, blind)?;
+        let transcript = &mut Self::challenge_transcript(&challenge, &[P]);
+        Self::prove_inner(
+            params,
+            transcript,
+            challenge,
+            Basis::Lagrange,
+            &a,
+            blind,
+            rng,
+        )
+    }
+
+    fn verify(
+        params: &Self::VerifierParams,
+        transcript: &mut impl Transcript<C::ScalarField>,
+        P: &C, // commitment
+        proof: &Self::Proof,
+    ) -> Result<(), Error> {
+        let (x, U, u) = Self::challenges(transcript, P, proof)?;
+
+        Self::verify_inner(params, x, Basis::Monomial, U, u, P, proof)
+    }
+
+    fn verify_with_challenge(
+        params: &Self::VerifierParams,
+        challenge: Self::Challenge,
+        P: &C, // commitment
+        proof: &Self::Proof,
+    ) -> Result<(), Error> {
+        let transcript = &mut Self::challenge_transcript(&challenge, &[*P]);
+        let (U, u) = Self::round_challenges(transcript, &proof.0)?;
+
+        Self::verify_inner(params, challenge, Basis::Lagrange, U, u, P, proof)
+    }
+}
+
+impl<C: Curve, const H: bool> IPA<C, H> {
+    /// Proves the opening of the vectors `a_i` committed in `P_i` at a single point, by opening
+    /// their random linear combination `a = Σ ρ^i a_i` in `P = Σ ρ^i P_i`. The vectors can have
+    /// different lengths, since they are zero-padded to a common power of two length.
+    pub fn batch_prove(
+        params: &PedersenParams<C>,
+        transcript: &mut impl Transcript<C::ScalarField>,
+        P: &[C],                   // commitments
+        a: &[Vec<C::ScalarField>], // vectors
+        blinds: &[C::ScalarField],
+        rng: Option<&mut dyn RngCore>,
+    ) -> Result<BatchProof<C>, Error> {
+        for P_i in P {
+            transcript.absorb_nonnative(P_i);
+        }
+        let x = transcript.get_challenge(); // challenge value at which we evaluate
+
+        Self::batch_prove_inner(params, transcript, x, Basis::Monomial, a, blinds, rng)
+    }
+
+    /// Same as `batch_prove` but for the given challenge `x`, with the vectors seen as evaluations
+    /// over the radix-2 domain as in `prove_with_challenge`.
+    pub fn batch_prove_with_challenge(
+        params: &PedersenParams<C>,
+        challenge: C::ScalarField,
+        P: &[C],                   // commitments
+        a: &[Vec<C::ScalarField>], // vectors
+        blinds: &[C::ScalarField],
+        rng: Option<&mut dyn RngCore>,
+    ) -> Result<BatchProof<C>, Error> {
+        let transcript = &mut Self::challenge_transcript(&challenge, P);
+
+        Self::batch_prove_inner(
+            params,
+            transcript,
+            challenge,
+            Basis::Lagrange,
+            a,
+            blinds,
+            rng,
+        )
+    }
+
+    /// Verifies a `BatchProof` generated by `batch_prove`.
+    pub fn batch_verify(
+        params: &PedersenParams<C>,
+        transcript: &mut impl Transcript<C::ScalarField>,
+        P: &[C], // commitments
+        proof: &BatchProof<C>,
+    ) -> Result<(), Error> {
+        for P_i in P {
+            transcript.absorb_nonnative(P_i);
+        }
+        let x = transcript.get_challenge(); // challenge value at which we evaluate
+
+        Self::batch_verify_inner(params, transcript, x, Basis::Monomial, P, proof)
+    }
+
+    /// Verifies a `BatchProof` generated by `batch_prove_with_challenge`.
+    pub fn batch_verify_with_challenge(
+        params: &PedersenParams<C>,
+        challenge: C::ScalarField,
+        P: &[C], // commitments
+        proof: &BatchProof<C>,
+    ) -> Result<(), Error> {
+        let transcript = &mut Self::challenge_transcript(&challenge, P);
+
+        Self::batch_verify_inner(params, transcript, challenge, Basis::Lagrange, P, proof)
+    }
+
+    /// Derives from the transcript the evaluation point `x`, the challenge `U` and the challenges
+    /// `u` of the IPA rounds used by `verify`, after binding the commitment `P` to the transcript.
+    /// These are the challenges that the `IPAGadget` takes as inputs.
+    pub fn challenges(
+        transcript: &mut impl Transcript<C::ScalarField>,
+        P: &C, // commitment
+        proof: &(Proof<C>, C::ScalarField, C::ScalarField),
+    ) -> Result<(C::ScalarField, C, Vec<C::ScalarField>), Error> {
+        transcript.absorb_nonnative(P);
+        let x = transcript.get_challenge(); // challenge value at which we evaluate
+        let (U, u) = Self::round_challenges(transcript, &proof.0)?;
+        Ok((x, U, u))
+    }
+
+    /// Transcript from which the `*_with_challenge` methods derive the challenges of the IPA
+    /// rounds, bound to the given evaluation point and commitments.
+    fn challenge_transcript(x: &C::ScalarField, P: &[C]) -> KeccakTranscript<C::ScalarField> {
+        let mut transcript = KeccakTranscript::new(&());
+        transcript.domain_separator(IPA_DOMAIN);
+        transcript.absorb(x);
+        for P_i in P {
+            transcript.absorb_nonnative(P_i);
+        }
+        transcript
+    }
+
+    /// Derives the challenge `U` and the challenges `u` of the IPA rounds, absorbing the `L` and
+    /// `R` values of the proof.
+    fn round_challenges(
+        transcript: &mut impl Transcript<C::ScalarField>,
+        p: &Proof<C>,
+    ) -> Result<(C, Vec<C::ScalarField>), Error> {
+        let k = p.L.len();
+        if p.R.len() != k {
+            return Err(Error::CommitmentVerificationFail);
+        }
+
+        let s = transcript.get_challenge();
+        let U = C::generator().mul(s);
+        let mut u: Vec<C::ScalarField> = vec![C::ScalarField::zero(); k];
+        for i in (0..k).rev() {
+            transcript.absorb_nonnative(&p.L[i]);
+            transcript.absorb_nonnative(&p.R[i]);
+            u[i] = transcript.get_challenge();
+        }
+        Ok((U, u))
+    }
+
+    /// Runs the IPA prover for the evaluation point `x`, once the commitment has been bound to the
+    /// transcript.
+    fn prove_inner(
+        params: &PedersenParams<C>,
+        transcript: &mut impl Transcript<C::ScalarField>,
+        x: C::ScalarField,
+        basis: Basis,
+        a: &[C::ScalarField], // vector
+        blind: &C::ScalarField,
+        rng: Option<&mut dyn RngCore>,
+    ) -> Result<(Proof<C>, C::ScalarField, C::ScalarField), Error> {
+        if !a.len().is_power_of_two() {
+            return Err(Error::NotPowerOfTwo("a".to_string(), a.len()));
+        }
         if !H && (!blind.is_zero()) {
             return Err(Error::BlindingNotZero);
         }
@@ -133,13 +388,11 @@ impl<C: Curve, const H: bool> CommitmentScheme<C, H> for IPA<C, H> {
             r = vec![];
         }
 
-        transcript.absorb_nonnative(P);
-        let x = transcript.get_challenge(); // challenge value at which we evaluate
         let s = transcript.get_challenge();
         let U = C::generator().mul(s);
 
         let mut a = a.to_owned();
-        let mut b = powers_of(x, d);
+        let mut b = basis.b_vec(x, d)?;
         let v = inner_prod(&a, &b)?;
 
         let mut G = params.generators.clone();
@@ -214,52 +467,20 @@ impl<C: Curve, const H: bool> CommitmentScheme<C, H> for IPA<C, H> {
         ))
     }
 
-    fn prove_with_challenge(
-        _params: &Self::ProverParams,
-        _challenge: Self::ProverChallenge,
-        _a: &[C::ScalarField], // vector
-        _blind: &C::ScalarField,
-        _rng: Option<&mut dyn RngCore>,
-    ) -> Result<Self::Proof, Error> {
-        // not supported because the prover logic computes challenges as it advances on the logic
-        Err(Error::NotSupported("IPA::prove_with_challenge".to_string()))
-    }
-
-    fn verify(
-        params: &Self::VerifierParams,
-        transcript: &mut impl Transcript<C::ScalarField>,
-        P: &C, // commitment
-        proof: &Self::Proof,
-    ) -> Result<(), Error> {
-        let (p, _r) = (proof.0.clone(), proof.1);
-        let k = p.L.len();
-
-        transcript.absorb_nonnative(P);
-        let x = transcript.get_challenge(); // challenge value at which we evaluate
-        let s = transcript.get_challenge();
-        let U = C::generator().mul(s);
-        let mut u: Vec<C::ScalarField> = vec![C::ScalarField::zero(); k];
-        for i in (0..k).rev() {
-            transcript.absorb_nonnative(&p.L[i]);
-            transcript.absorb_nonnative(&p.R[i]);
-            u[i] = transcript.get_challenge();
-        }
-        let challenge = (x, U, u);
-
-        Self::verify_with_challenge(params, challenge, P, proof)
-    }
-
-    fn verify_with_challenge(
-        params: &Self::VerifierParams,
-        challenge: Self::Challenge,
+    /// Checks the IPA proof for the evaluation point `x` and the challenges `U`, `u`.
+    fn verify_inner(
+        params: &PedersenParams<C>,
+        x: C::ScalarField,
+        basis: Basis,
+        U: C,
+        u: Vec<C::ScalarField>,
         P: &C, // commitment
-        proof: &Self::Proof,
+        proof: &(Proof<C>, C::ScalarField, C::ScalarField),
     ) -> Result<(), Error> {
         let (p, v, r) = (proof.0.clone(), proof.1, proof.2);
-        let (x, U, u) = challenge;
 
         let k = p.L.len();
-        if p.R.len() != k {
+        if p.R.len() != k || u.len() != k {
             return Err(Error::CommitmentVerificationFail);
         }
         if !H && (!r.is_zero()) {
@@ -287,8 +508,8 @@ impl<C: Curve, const H: bool> CommitmentScheme<C, H> for IPA<C, H> {
 
         // compute b & G from s
         let s = build_s(&u, &u_invs, k)?;
-        // b = <s, b_vec> = <s, [1, x, x^2, ..., x^d-1]>
-        let b = s_b_inner(&u, &x)?;
+        // b = <s, b_vec>, eg. <s, [1, x, x^2, ..., x^d-1]> for the monomial basis
+        let b = basis.s_b_inner(&s, &u, x)?;
         let d: usize = 2_u64.pow(k as u32) as usize;
         if params.generators.len() < d {
             return Err(Error::PedersenParamsLen(params.generators.len(), d));
@@ -316,6 +537,98 @@ impl<C: Curve, const H: bool> CommitmentScheme<C, H> for IPA<C, H> {
         }
         Ok(())
     }
+
+    /// Opens the random linear combination of the vectors `a_i` at `x`, once the commitments have
+    /// been bound to the transcript.
+    fn batch_prove_inner(
+        params: &PedersenParams<C>,
+        transcript: &mut impl Transcript<C::ScalarField>,
+        x: C::ScalarField,
+        basis: Basis,
+        a: &[Vec<C::ScalarField>], // vectors
+        blinds: &[C::ScalarField],
+        rng: Option<&mut dyn RngCore>,
+    ) -> Result<BatchProof<C>, Error> {
+        if a.len() != blinds.len() {
+            return Err(Error::NotSameLength(
+                "a".to_string(),
+                a.len(),
+                "blinds".to_string(),
+                blinds.len(),
+            ));
+        }
+        let d = a
+            .iter()
+            .map(|a_i| a_i.len())
+            .max()
+            .unwrap_or(1)
+            .next_power_of_two();
+        let a = a
+            .iter()
+            .map(|a_i| {
+                let mut a_i = a_i.clone();
+                a_i.resize(d, C::ScalarField::zero());
+                a_i
+            })
+            .collect::<Vec<_>>();
+
+        let b = basis.b_vec(x, d)?;
+        let evals = a
+            .iter()
+            .map(|a_i| inner_prod(a_i, &b))
+            .collect::<Result<Vec<_>, _>>()?;
+        transcript.absorb(&evals);
+        let rho = transcript.get_challenge();
+
+        // a = Σ ρ^i a_i, r = Σ ρ^i r_i
+        let mut a_rlc = vec![C::ScalarField::zero(); d];
+        let mut blind = C::ScalarField::zero();
+        for ((a_i, r_i), rho_i) in a.iter().zip(blinds).zip(powers_of(rho, a.len())) {
+            a_rlc = vec_add(&a_rlc, &vec_scalar_mul(a_i, &rho_i))?;
+            blind += rho_i * r_i;
+        }
+
+        let proof = Self::prove_inner(params, transcript, x, basis, &a_rlc, &blind, rng)?;
+        Ok(BatchProof { evals, proof })
+    }
+
+    /// Checks that the `BatchProof` opens the random linear combination of the commitments to the
+    /// random linear combination of the claimed evaluations.
+    fn batch_verify_inner(
+        params: &PedersenParams<C>,
+        transcript: &mut impl Transcript<C::ScalarField>,
+        x: C::ScalarField,
+        basis: Basis,
+        P: &[C], // commitments
+        proof: &BatchProof<C>,
+    ) -> Result<(), Error> {
+        if P.len() != proof.evals.len() {
+            return Err(Error::NotSameLength(
+                "P".to_string(),
+                P.len(),
+                "evals".to_string(),
+                proof.evals.len(),
+            ));
+        }
+        transcript.absorb(&proof.evals);
+        let rho = transcript.get_challenge();
+
+        // P = Σ ρ^i P_i, v = Σ ρ^i v_i
+        let rhos = powers_of(rho, P.len());
+        let P_rlc: C = P.iter().zip(&rhos).map(|(P_i, rho_i)| P_i.mul(rho_i)).sum();
+        let v: C::ScalarField = proof
+            .evals
+            .iter()
+            .zip(&rhos)
+            .map(|(v_i, rho_i)| *v_i * rho_i)
+            .sum();
+        if proof.proof.1 != v {
+            return Err(Error::CommitmentVerificationFail);
+        }
+
+        let (U, u) = Self::round_challenges(transcript, &proof.proof.0)?;
+        Self::verify_inner(params, x, basis, U, u, &P_rlc, &proof.proof)
+    }
 }
 
 /// Computes s such that
@@ -571,12 +884,13 @@ impl<C: Curve, const H: bool> IPAGadget<C, H> {
 #[cfg(test)]
 mod tests {
     use ark_crypto_primitives::sponge::{poseidon::PoseidonSponge, CryptographicSponge};
-    use ark_ec::PrimeGroup;
     use ark_pallas::{constraints::GVar, Fq, Fr, Projective};
     use ark_r1cs_std::eq::EqGadget;
     use ark_relations::r1cs::ConstraintSystem;
+    use ark_std::One;
 
     use super::*;
+    use crate::folding::circuits::decider::EvalGadget;
     use crate::transcript::poseidon::poseidon_canonical_config;
 
     #[test]
@@ -624,6 +938,133 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_ipa_with_challenge() -> Result<(), Error> {
+        let _ = test_ipa_with_challenge_opt::<false>()?;
+        let _ = test_ipa_with_challenge_opt::<true>()?;
+        Ok(())
+    }
+    fn test_ipa_with_challenge_opt<const hiding: bool>() -> Result<(), Error> {
+        let mut rng = ark_std::test_rng();
+
+        // use a length that is not a power of two, as the witnesses opened by the deciders
+        let d = 13;
+        let (params, _) = IPA::<Projective, hiding>::setup(&mut rng, d)?;
+
+        let a: Vec<Fr> = std::iter::repeat_with(|| Fr::rand(&mut rng))
+            .take(d)
+            .collect();
+        let r_blind: Fr = if hiding {
+            Fr::rand(&mut rng)
+        } else {
+            Fr::zero()
+        };
+        let cm = IPA::<Projective, hiding>::commit(&params, &a, &r_blind)?;
+
+        let x = Fr::rand(&mut rng);
+        let proof = IPA::<Projective, hiding>::prove_with_challenge(
+            &params,
+            x,
+            &a,
+            &r_blind,
+            Some(&mut rng),
+        )?;
+        // the evaluation matches the one computed by the deciders (and opened by KZG)
+        assert_eq!(proof.evaluation(), EvalGadget::evaluate_native(&a, x)?);
+
+        IPA::<Projective, hiding>::verify_with_challenge(&params, x, &cm, &proof)?;
+        assert!(IPA::<Projective, hiding>::verify_with_challenge(
+            &params,
+            x + Fr::one(),
+            &cm,
+            &proof
+        )
+        .is_err());
+        // the challenges of the rounds are bound to the commitment
+        assert!(IPA::<Projective, hiding>::verify_with_challenge(
+            &params,
+            x,
+            &(cm + params.generators[0]),
+            &proof
+        )
+        .is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_ipa_batch() -> Result<(), Error> {
+        let _ = test_ipa_batch_opt::<false>()?;
+        let _ = test_ipa_batch_opt::<true>()?;
+        Ok(())
+    }
+    fn test_ipa_batch_opt<const hiding: bool>() -> Result<(), Error> {
+        let mut rng = ark_std::test_rng();
+
+        let lens = [16, 5, 9];
+        let (params, _) = IPA::<Projective, hiding>::setup(&mut rng, 16)?;
+
+        let a: Vec<Vec<Fr>> = lens
+            .iter()
+            .map(|&len| {
+                std::iter::repeat_with(|| Fr::rand(&mut rng))
+                    .take(len)
+                    .collect()
+            })
+            .collect();
+        let r_blinds: Vec<Fr> = lens
+            .iter()
+            .map(|_| {
+                if hiding {
+                    Fr::rand(&mut rng)
+                } else {
+                    Fr::zero()
+                }
+            })
+            .collect();
+        let cms = a
+            .iter()
+            .zip(&r_blinds)
+            .map(|(a_i, r_i)| IPA::<Projective, hiding>::commit(&params, a_i, r_i))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // batch opening with the challenge derived from the transcript
+        let poseidon_config = poseidon_canonical_config::<Fr>();
+        let mut transcript_p = PoseidonSponge::<Fr>::new(&poseidon_config);
+        let mut transcript_v = PoseidonSponge::<Fr>::new(&poseidon_config);
+        let proof = IPA::<Projective, hiding>::batch_prove(
+            &params,
+            &mut transcript_p,
+            &cms,
+            &a,
+            &r_blinds,
+            Some(&mut rng),
+        )?;
+        IPA::<Projective, hiding>::batch_verify(&params, &mut transcript_v, &cms, &proof)?;
+
+        // batch opening at a given challenge
+        let x = Fr::rand(&mut rng);
+        let mut proof = IPA::<Projective, hiding>::batch_prove_with_challenge(
+            &params,
+            x,
+            &cms,
+            &a,
+            &r_blinds,
+            Some(&mut rng),
+        )?;
+        for (a_i, eval) in a.iter().zip(&proof.evals) {
+            assert_eq!(*eval, EvalGadget::evaluate_native(a_i, x)?);
+        }
+        IPA::<Projective, hiding>::batch_verify_with_challenge(&params, x, &cms, &proof)?;
+
+        // a wrong evaluation must not verify
+        proof.evals[1] += Fr::one();
+        assert!(
+            IPA::<Projective, hiding>::batch_verify_with_challenge(&params, x, &cms, &proof)
+                .is_err()
+        );
+        Ok(())
+    }
+
     #[test]
     fn test_ipa_gadget() -> Result<(), Error> {
         let _ = test_ipa_gadget_opt::<false>()?;
@@ -670,17 +1111,10 @@ mod tests {
         // circuit
         let cs = ConstraintSystem::<Fq>::new_ref();
 
+        // the challenges given to the gadget are bound to the commitment
         let mut transcript_v = PoseidonSponge::<Fr>::new(&poseidon_config);
-        transcript_v.absorb_nonnative(&cm);
-        let challenge = transcript_v.get_challenge(); // challenge value at which we evaluate
-        let s = transcript_v.get_challenge();
-        let U = Projective::generator() * s;
-        let mut u: Vec<Fr> = vec![Fr::zero(); k];
-        for i in (0..k).rev() {
-            transcript_v.absorb_nonnative(&proof.0.L[i]);
-            transcript_v.absorb_nonnative(&proof.0.R[i]);
-            u[i] = transcript_v.get_challenge();
-        }
+        let (challenge, U, u) =
+            IPA::<Projective, hiding>::challenges(&mut transcript_v, &cm, &proof)?;
 
         // prepare inputs
         let gVar = Vec::<GVar>::new_constant(cs.clone(), params.generators)?;
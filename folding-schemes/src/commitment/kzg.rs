@@ -5,11 +5,23 @@
 /// vectors indistinctly, and the arkworks KZG10 implementation contains all the methods under the
 /// same trait, which requires the Pairing trait, where the prover does not need access to the
 /// Pairing but only to G1.
-use ark_ec::{pairing::Pairing, CurveGroup, VariableBaseMSM};
-use ark_ff::PrimeField;
+///
+/// In hiding mode (`H = true`) the commitment is blinded as `C = p(β)⋅G + r⋅γ⋅G`, where `γ⋅G` is
+/// the randomness generator of the SRS, so that it stays additively homomorphic in the committed
+/// vector and the blinding factor, which the folding schemes fold together (`rW`, `rE`). Since the
+/// blinding polynomial of such a commitment is the constant `r`, an opening of `C` alone would
+/// reveal it. Instead, every opening samples a fresh blinding polynomial `b(X)` of degree
+/// `HIDING_BOUND`, commits to it through the public powers `β^i⋅γ⋅G` of the SRS, which gives a
+/// hiding commitment `M` to the zero polynomial, and opens `C + ξ⋅M`, as in the arkworks KZG10
+/// hiding openings. The coefficient `ξ` is derived with a `KeccakTranscript` from the challenge,
+/// the evaluation, `C` and `M`, so that `M` can not shift the evaluation, and the revealed
+/// `random_v = r + ξ⋅b(z)` is uniformly distributed. The `ProverKey` only contains public
+/// elements.
+use ark_ec::{pairing::Pairing, AffineRepr, CurveGroup, VariableBaseMSM};
+use ark_ff::{Field, PrimeField};
 use ark_poly::{
     univariate::{DenseOrSparsePolynomial, DensePolynomial},
-    DenseUVPolynomial, Polynomial,
+    DenseUVPolynomial, EvaluationDomain, GeneralEvaluationDomain, Polynomial,
 };
 use ark_poly_commit::kzg10::{
     Commitment as KZG10Commitment, Proof as KZG10Proof, VerifierKey, KZG10,
@@ -17,21 +29,115 @@ use ark_poly_commit::kzg10::{
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Valid};
 use ark_std::rand::RngCore;
 use ark_std::{borrow::Cow, fmt::Debug};
-use ark_std::{One, Zero};
+use ark_std::{One, UniformRand, Zero};
 use core::marker::PhantomData;
 use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
 
-use super::CommitmentScheme;
-use crate::transcript::Transcript;
-use crate::utils::vec::poly_from_vec;
+use super::{CommitmentScheme, CommitmentSchemeId, EvaluationProof};
+use crate::transcript::{keccak::KeccakTranscript, Transcript};
+use crate::utils::{msm::small_value_msm, vec::poly_from_vec};
 use crate::{Curve, Error};
 
+/// Degree of the blinding polynomials sampled at the hiding openings, which allows opening a
+/// commitment at up to `HIDING_BOUND` points at once (as `HyperKZG` does) without revealing its
+/// blinding factor.
+pub const HIDING_BOUND: usize = 3;
+
+/// Marks the versioned serialization of the `ProverKey`, in the position where the (unversioned)
+/// first format stores the number of `powers_of_g`, so that both can be deserialized.
+const PROVER_KEY_VERSIONED: u64 = u64::MAX;
+/// Current version of the serialization of the `ProverKey`. Version 1 also stored a secret
+/// blinding polynomial after `powers_of_gamma_g`, which is skipped when deserializing.
+const PROVER_KEY_VERSION: u8 = 2;
+
 /// ProverKey defines a similar struct as in ark_poly_commit::kzg10::Powers, but instead of
 /// depending on the Pairing trait it depends on the SonobeCurve trait.
 #[derive(Debug, Clone, Default, Eq, PartialEq)]
 pub struct ProverKey<'a, C: Curve> {
     /// Group elements of the form `β^i G`, for different values of `i`.
     pub powers_of_g: Cow<'a, [C::Affine]>,
+    /// Group elements of the form `β^i γ G`, for `i` up to `HIDING_BOUND`, used to blind the
+    /// commitments and to commit to the blinding polynomials of the openings in hiding mode. Empty
+    /// when the key does not support hiding, e.g. when it is loaded from a Powers of Tau ceremony.
+    pub powers_of_gamma_g: Vec<C::Affine>,
+    /// Optional Lagrange bases `L_i(β) G` of the radix-2 domains of size `2^j`, at position `j`.
+    /// When present, `commit` uses them to commit to the evaluations directly, which allows it to
+    /// exploit the small values of the vectors. They are not serialized, since they can be
+    /// recomputed from `powers_of_g`.
+    pub lagrange_bases: Option<Vec<Vec<C::Affine>>>,
+}
+
+impl<'a, C: Curve> ProverKey<'a, C> {
+    /// Precomputes the Lagrange bases of all the radix-2 domains supported by `powers_of_g`, as
+    /// the inverse FFT of the powers, which doubles the size of the key.
+    pub fn with_lagrange_bases(mut self) -> Result<Self, Error> {
+        let max_size = self.powers_of_g.len();
+        let mut lagrange_bases = vec![];
+        let mut size = 1;
+        while size <= max_size {
+            let domain = GeneralEvaluationDomain::<C::ScalarField>::new(size)
+                .filter(|domain| domain.size() == size)
+                .ok_or(Error::NewDomainFail)?;
+            let mut bases: Vec<C> = self.powers_of_g[..size]
+                .iter()
+                .map(|p| p.into_group())
+                .collect();
+            domain.ifft_in_place(&mut bases);
+            lagrange_bases.push(C::normalize_batch(&bases));
+            size *= 2;
+        }
+        self.lagrange_bases = Some(lagrange_bases);
+        Ok(self)
+    }
+
+    /// Returns the randomness generator `γ⋅G` with which the hiding commitments are blinded.
+    pub(crate) fn gamma_g(&self) -> Result<C, Error> {
+        // the openings need at least `γ⋅G` and `β⋅γ⋅G` to commit to their blinding polynomials
+        if self.powers_of_gamma_g.len() < 2 {
+            return Err(Error::NotSupported(
+                "hiding commitments with a KZG key without the powers of γ⋅G".to_string(),
+            ));
+        }
+        Ok(self.powers_of_gamma_g[0].into_group())
+    }
+
+    /// Samples a random blinding polynomial `b(X)` of degree up to `HIDING_BOUND` and returns its
+    /// coefficients together with `M = b(β)⋅γ⋅G`, a hiding commitment to the zero polynomial.
+    pub(crate) fn sample_mask(
+        &self,
+        rng: &mut dyn RngCore,
+    ) -> Result<(Vec<C::ScalarField>, C), Error> {
+        self.gamma_g()?;
+        let b: Vec<C::ScalarField> = (0..self.powers_of_gamma_g.len().min(HIDING_BOUND + 1))
+            .map(|_| C::ScalarField::rand(rng))
+            .collect();
+        let mask = self.commit_blinding(&b);
+        Ok((b, mask))
+    }
+
+    /// Commits to the blinding polynomial with coefficients `coeffs` through the powers of
+    /// `γ⋅G`, which must be enough for its degree.
+    pub(crate) fn commit_blinding(&self, coeffs: &[C::ScalarField]) -> C {
+        C::msm_unchecked(&self.powers_of_gamma_g[..coeffs.len()], coeffs)
+    }
+}
+
+/// Derives the coefficient `ξ` with which the mask `M` of a hiding opening of `cm` at `z` with
+/// evaluation `y` is added to `cm`. The NovaDecider Solidity contract recomputes it as
+/// `keccak256(bytes32(0) || z || y || cm || mask)`, where the points are absorbed as in
+/// `AbsorbNonNative`.
+pub(crate) fn mask_challenge<C: Curve>(
+    z: C::ScalarField,
+    y: C::ScalarField,
+    cm: &C,
+    mask: &C,
+) -> C::ScalarField {
+    let mut transcript = KeccakTranscript::<C::ScalarField>::new(&());
+    transcript.absorb(&z);
+    transcript.absorb(&y);
+    transcript.absorb_nonnative(cm);
+    transcript.absorb_nonnative(mask);
+    transcript.get_challenge()
 }
 
 impl<'a, C: Curve> CanonicalSerialize for ProverKey<'a, C> {
@@ -40,23 +146,55 @@ impl<'a, C: Curve> CanonicalSerialize for ProverKey<'a, C> {
         mut writer: W,
         compress: ark_serialize::Compress,
     ) -> Result<(), ark_serialize::SerializationError> {
-        self.powers_of_g.serialize_with_mode(&mut writer, compress)
+        PROVER_KEY_VERSIONED.serialize_with_mode(&mut writer, compress)?;
+        PROVER_KEY_VERSION.serialize_with_mode(&mut writer, compress)?;
+        self.powers_of_g
+            .serialize_with_mode(&mut writer, compress)?;
+        self.powers_of_gamma_g
+            .serialize_with_mode(&mut writer, compress)
     }
 
     fn serialized_size(&self, compress: ark_serialize::Compress) -> usize {
-        self.powers_of_g.serialized_size(compress)
+        PROVER_KEY_VERSIONED.serialized_size(compress)
+            + PROVER_KEY_VERSION.serialized_size(compress)
+            + self.powers_of_g.serialized_size(compress)
+            + self.powers_of_gamma_g.serialized_size(compress)
     }
 }
 
 impl<'a, C: Curve> CanonicalDeserialize for ProverKey<'a, C> {
+    /// Deserializes the current format and the previous ones. The first format only contains the
+    /// `powers_of_g`, so the keys in that format do not support hiding.
     fn deserialize_with_mode<R: std::io::prelude::Read>(
-        reader: R,
+        mut reader: R,
         compress: ark_serialize::Compress,
         validate: ark_serialize::Validate,
     ) -> Result<Self, ark_serialize::SerializationError> {
-        let powers_of_g_vec = Vec::deserialize_with_mode(reader, compress, validate)?;
+        let len = u64::deserialize_with_mode(&mut reader, compress, validate)?;
+        if len != PROVER_KEY_VERSIONED {
+            let powers_of_g_vec = (0..len)
+                .map(|_| C::Affine::deserialize_with_mode(&mut reader, compress, validate))
+                .collect::<Result<Vec<_>, _>>()?;
+            return Ok(ProverKey {
+                powers_of_g: ark_std::borrow::Cow::Owned(powers_of_g_vec),
+                powers_of_gamma_g: vec![],
+                lagrange_bases: None,
+            });
+        }
+        let version = u8::deserialize_with_mode(&mut reader, compress, validate)?;
+        if version != 1 && version != PROVER_KEY_VERSION {
+            return Err(ark_serialize::SerializationError::UnexpectedFlags);
+        }
+        let powers_of_g_vec = Vec::deserialize_with_mode(&mut reader, compress, validate)?;
+        let powers_of_gamma_g = Vec::deserialize_with_mode(&mut reader, compress, validate)?;
+        if version == 1 {
+            // skip the secret blinding polynomial, which is no longer used
+            Vec::<C::ScalarField>::deserialize_with_mode(&mut reader, compress, validate)?;
+        }
         Ok(ProverKey {
             powers_of_g: ark_std::borrow::Cow::Owned(powers_of_g_vec),
+            powers_of_gamma_g,
+            lagrange_bases: None,
         })
     }
 }
@@ -66,14 +204,36 @@ impl<'a, C: Curve> Valid for ProverKey<'a, C> {
         match self.powers_of_g.clone() {
             Cow::Borrowed(powers) => powers.to_vec().check(),
             Cow::Owned(powers) => powers.check(),
-        }
+        }?;
+        self.powers_of_gamma_g.check()
     }
 }
 
 #[derive(Debug, Clone, Default, Eq, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(bound = "")
+)]
 pub struct Proof<C: Curve> {
+    #[cfg_attr(feature = "serde", serde(with = "crate::utils::json::field"))]
     pub eval: C::ScalarField,
+    #[cfg_attr(feature = "serde", serde(with = "crate::utils::json::point"))]
     pub proof: C,
+    /// evaluation at the challenge of the blinding polynomial of the opened commitment `C + ξ⋅M`,
+    /// zero when not in hiding mode
+    #[cfg_attr(feature = "serde", serde(with = "crate::utils::json::field"))]
+    pub random_v: C::ScalarField,
+    /// hiding commitment `M` to the zero polynomial that re-randomizes the blinding of the opened
+    /// commitment, zero when not in hiding mode
+    #[cfg_attr(feature = "serde", serde(with = "crate::utils::json::point"))]
+    pub mask: C,
+}
+
+impl<C: Curve> EvaluationProof<C::ScalarField> for Proof<C> {
+    fn evaluation(&self) -> C::ScalarField {
+        self.eval
+    }
 }
 
 /// KZG implements the CommitmentScheme trait for the KZG commitment scheme.
@@ -83,6 +243,10 @@ pub struct KZG<'a, E: Pairing, const H: bool = false> {
     _e: PhantomData<E>,
 }
 
+impl<'a, E: Pairing<G1: Curve>, const H: bool> CommitmentSchemeId for KZG<'a, E, H> {
+    const ID: &'static str = "kzg";
+}
+
 impl<'a, E: Pairing<G1: Curve>, const H: bool> CommitmentScheme<E::G1, H> for KZG<'a, E, H> {
     type ProverParams = ProverKey<'a, E::G1>;
     type VerifierParams = VerifierKey<E>;
@@ -108,8 +272,15 @@ impl<'a, E: Pairing<G1: Curve>, const H: bool> CommitmentScheme<E::G1, H> for KZ
             KZG10::<E, DensePolynomial<E::ScalarField>>::setup(len, false, &mut rng)
                 .expect("Setup failed");
         let powers_of_g = universal_params.powers_of_g[..=len].to_vec();
+        // the setup provides `len + 2` powers of γ⋅G, so for `len = 1` the blinding polynomials
+        // have degree 2
+        let powers_of_gamma_g: Vec<_> = (0..=HIDING_BOUND)
+            .map_while(|i| universal_params.powers_of_gamma_g.get(&i).copied())
+            .collect();
         let powers = ProverKey::<E::G1> {
             powers_of_g: ark_std::borrow::Cow::Owned(powers_of_g),
+            powers_of_gamma_g,
+            lagrange_bases: None,
         };
         let vk = VerifierKey {
             g: universal_params.powers_of_g[0],
@@ -124,55 +295,70 @@ impl<'a, E: Pairing<G1: Curve>, const H: bool> CommitmentScheme<E::G1, H> for KZ
 
     /// commit implements the CommitmentScheme commit interface, adapting the implementation from
     /// https://github.com/arkworks-rs/poly-commit/tree/c724fa666e935bbba8db5a1421603bab542e15ab/poly-commit/src/kzg10/mod.rs#L178
-    /// with the main difference being the removal of the blinding factors and the no-dependency to
-    /// the Pairing trait.
+    /// with the main differences being that the blinding polynomial is the constant `blind` (see
+    /// the module docs) and the no-dependency to the Pairing trait.
     fn commit(
         params: &Self::ProverParams,
         v: &[E::ScalarField],
-        _blind: &E::ScalarField,
+        blind: &E::ScalarField,
     ) -> Result<E::G1, Error> {
-        if !_blind.is_zero() || H {
-            return Err(Error::NotSupportedYet("hiding".to_string()));
+        if !H && (!blind.is_zero()) {
+            return Err(Error::BlindingNotZero);
         }
 
-        let polynomial = poly_from_vec(v.to_vec())?;
-        check_degree_is_too_large(polynomial.degree(), params.powers_of_g.len())?;
-
-        let (num_leading_zeros, plain_coeffs) =
-            skip_first_zero_coeffs_and_convert_to_bigints(&polynomial);
-        let commitment = <E::G1 as VariableBaseMSM>::msm_bigint(
-            &params.powers_of_g[num_leading_zeros..],
-            &plain_coeffs,
-        );
-        Ok(commitment)
+        // with the Lagrange bases of the domain of `v`, commit to its evaluations directly, which
+        // are usually small, instead of to the coefficients of the interpolated polynomial
+        let lagrange_bases = params
+            .lagrange_bases
+            .as_ref()
+            .and_then(|bases| bases.get(v.len().next_power_of_two().trailing_zeros() as usize));
+        let commitment = match lagrange_bases {
+            Some(bases) => small_value_msm(&bases[..v.len()], v),
+            None => {
+                let polynomial = poly_from_vec(v.to_vec())?;
+                check_degree_is_too_large(polynomial.degree(), params.powers_of_g.len())?;
+
+                let (num_leading_zeros, plain_coeffs) =
+                    skip_first_zero_coeffs_and_convert_to_bigints(&polynomial);
+                <E::G1 as VariableBaseMSM>::msm_bigint(
+                    &params.powers_of_g[num_leading_zeros..],
+                    &plain_coeffs,
+                )
+            }
+        };
+        if !H || blind.is_zero() {
+            return Ok(commitment);
+        }
+        Ok(commitment + params.gamma_g()? * blind)
     }
 
     /// prove implements the CommitmentScheme prove interface, adapting the implementation from
     /// https://github.com/arkworks-rs/poly-commit/tree/c724fa666e935bbba8db5a1421603bab542e15ab/poly-commit/src/kzg10/mod.rs#L307
-    /// with the main difference being the removal of the blinding factors and the no-dependency to
-    /// the Pairing trait.
+    /// with the main differences being that the opened commitment is re-randomized with a fresh
+    /// blinding polynomial (see the module docs) and the no-dependency to the Pairing trait.
     fn prove(
         params: &Self::ProverParams,
         transcript: &mut impl Transcript<E::ScalarField>,
         cm: &E::G1,
         v: &[E::ScalarField],
-        _blind: &E::ScalarField,
-        _rng: Option<&mut dyn RngCore>,
+        blind: &E::ScalarField,
+        rng: Option<&mut dyn RngCore>,
     ) -> Result<Self::Proof, Error> {
         transcript.absorb_nonnative(cm);
         let challenge = transcript.get_challenge();
-        Self::prove_with_challenge(params, challenge, v, _blind, _rng)
+        Self::prove_with_challenge(params, challenge, v, blind, rng)
     }
 
+    /// In hiding mode, `rng` is required to sample the blinding polynomial of the opening.
     fn prove_with_challenge(
         params: &Self::ProverParams,
         challenge: Self::ProverChallenge,
         v: &[E::ScalarField],
-        _blind: &E::ScalarField,
-        _rng: Option<&mut dyn RngCore>,
+        blind: &E::ScalarField,
+        rng: Option<&mut dyn RngCore>,
     ) -> Result<Self::Proof, Error> {
-        if !_blind.is_zero() || H {
-            return Err(Error::NotSupportedYet("hiding".to_string()));
+        if !H && (!blind.is_zero()) {
+            return Err(Error::BlindingNotZero);
         }
 
         let polynomial = poly_from_vec(v.to_vec())?;
@@ -201,12 +387,29 @@ impl<'a, E: Pairing<G1: Curve>, const H: bool> CommitmentScheme<E::G1, H> for KZ
         check_degree_is_too_large(witness_poly.degree(), params.powers_of_g.len())?;
         let (num_leading_zeros, witness_coeffs) =
             skip_first_zero_coeffs_and_convert_to_bigints(&witness_poly);
-        let proof = <E::G1 as VariableBaseMSM>::msm_bigint(
+        let mut proof = <E::G1 as VariableBaseMSM>::msm_bigint(
             &params.powers_of_g[num_leading_zeros..],
             &witness_coeffs,
         );
 
-        Ok(Proof { eval, proof })
+        let mut random_v = E::ScalarField::zero();
+        let mut mask = E::G1::zero();
+        if H {
+            // open `C + ξ⋅M`, whose blinding polynomial is `blind + ξ⋅b(X)`
+            let (b, M) = params.sample_mask(rng.ok_or(Error::MissingRandomness)?)?;
+            let cm = Self::commit(params, v, blind)?;
+            let xi = mask_challenge(challenge, eval, &cm, &M);
+            random_v = *blind + xi * evaluate(&b, challenge);
+            proof += params.commit_blinding(&divide_by_linear(&b, challenge)) * xi;
+            mask = M;
+        }
+
+        Ok(Proof {
+            eval,
+            proof,
+            random_v,
+            mask,
+        })
     }
 
     fn verify(
@@ -226,10 +429,12 @@ impl<'a, E: Pairing<G1: Curve>, const H: bool> CommitmentScheme<E::G1, H> for KZ
         cm: &E::G1,
         proof: &Self::Proof,
     ) -> Result<(), Error> {
-        if H {
-            return Err(Error::NotSupportedYet("hiding".to_string()));
-        }
-
+        // in hiding mode, the opened commitment is re-randomized with the mask
+        let cm = if H {
+            *cm + proof.mask * mask_challenge(challenge, proof.eval, cm, &proof.mask)
+        } else {
+            *cm
+        };
         // verify the KZG proof using arkworks method
         let v = KZG10::<E, DensePolynomial<E::ScalarField>>::check(
             params, // vk
@@ -238,7 +443,7 @@ impl<'a, E: Pairing<G1: Curve>, const H: bool> CommitmentScheme<E::G1, H> for KZ
             proof.eval,
             &KZG10Proof::<E> {
                 w: proof.proof.into_affine(),
-                random_v: None,
+                random_v: if H { Some(proof.random_v) } else { None },
             },
         )?;
         if !v {
@@ -280,6 +485,22 @@ fn convert_to_bigints<F: PrimeField>(p: &[F]) -> Vec<F::BigInt> {
         .collect::<Vec<_>>()
 }
 
+/// Evaluates the polynomial with coefficients `coeffs` at `x`.
+pub(crate) fn evaluate<F: Field>(coeffs: &[F], x: F) -> F {
+    coeffs.iter().rev().fold(F::zero(), |acc, c| acc * x + c)
+}
+
+/// Returns the coefficients of `(p(X) - p(x)) / (X - x)`, where `p` has coefficients `coeffs`.
+pub(crate) fn divide_by_linear<F: Field>(coeffs: &[F], x: F) -> Vec<F> {
+    let mut quotient = vec![F::zero(); coeffs.len().saturating_sub(1)];
+    let mut acc = F::zero();
+    for i in (1..coeffs.len()).rev() {
+        acc = acc * x + coeffs[i];
+        quotient[i - 1] = acc;
+    }
+    quotient
+}
+
 #[cfg(test)]
 mod tests {
     use ark_bn254::{Bn254, Fr, G1Projective as G1};
@@ -308,4 +529,132 @@ mod tests {
         KZG::<Bn254>::verify(&vk, transcript_v, &cm, &proof)?;
         Ok(())
     }
+
+    #[test]
+    fn test_kzg_hiding_commitment_scheme() -> Result<(), Error> {
+        let mut rng = &mut test_rng();
+        let poseidon_config = poseidon_canonical_config::<Fr>();
+
+        let n = 10;
+        let (pk, vk): (ProverKey<G1>, VerifierKey<Bn254>) = KZG::<Bn254, true>::setup(&mut rng, n)?;
+
+        let v: Vec<Fr> = std::iter::repeat_with(|| Fr::rand(rng)).take(n).collect();
+        let blind = Fr::rand(rng);
+        let cm = KZG::<Bn254, true>::commit(&pk, &v, &blind)?;
+        // the blinded commitment differs from the non-hiding one
+        assert_ne!(cm, KZG::<Bn254>::commit(&pk, &v, &Fr::zero())?);
+
+        // the hiding openings need randomness
+        let transcript_p = &mut PoseidonSponge::<Fr>::new(&poseidon_config);
+        assert!(matches!(
+            KZG::<Bn254, true>::prove(&pk, transcript_p, &cm, &v, &blind, None),
+            Err(Error::MissingRandomness)
+        ));
+
+        let transcript_p = &mut PoseidonSponge::<Fr>::new(&poseidon_config);
+        let proof = KZG::<Bn254, true>::prove(&pk, transcript_p, &cm, &v, &blind, Some(&mut rng))?;
+        // the opening reveals the evaluation of the blinding polynomial, not the blinding factor
+        assert_ne!(proof.random_v, blind);
+
+        // verify the proof:
+        let transcript_v = &mut PoseidonSponge::<Fr>::new(&poseidon_config);
+        KZG::<Bn254, true>::verify(&vk, transcript_v, &cm, &proof)?;
+
+        // another opening at the same point is blinded with a fresh polynomial
+        let transcript_p = &mut PoseidonSponge::<Fr>::new(&poseidon_config);
+        let proof_2 =
+            KZG::<Bn254, true>::prove(&pk, transcript_p, &cm, &v, &blind, Some(&mut rng))?;
+        assert_eq!(proof_2.eval, proof.eval);
+        assert_ne!(proof_2.random_v, proof.random_v);
+        assert_ne!(proof_2.mask, proof.mask);
+        let transcript_v = &mut PoseidonSponge::<Fr>::new(&poseidon_config);
+        KZG::<Bn254, true>::verify(&vk, transcript_v, &cm, &proof_2)?;
+
+        // a proof with a wrong blinding evaluation must not verify
+        let mut bad_proof = proof.clone();
+        bad_proof.random_v += Fr::one();
+        let transcript_v = &mut PoseidonSponge::<Fr>::new(&poseidon_config);
+        assert!(KZG::<Bn254, true>::verify(&vk, transcript_v, &cm, &bad_proof).is_err());
+
+        // nor a proof whose mask shifts the evaluation
+        let mut bad_proof = proof.clone();
+        bad_proof.eval += Fr::one();
+        bad_proof.mask += KZG::<Bn254>::commit(&pk, &[Fr::one()], &Fr::zero())?;
+        let transcript_v = &mut PoseidonSponge::<Fr>::new(&poseidon_config);
+        assert!(KZG::<Bn254, true>::verify(&vk, transcript_v, &cm, &bad_proof).is_err());
+
+        // the non-hiding verifier does not accept the blinded commitment
+        let transcript_v = &mut PoseidonSponge::<Fr>::new(&poseidon_config);
+        assert!(KZG::<Bn254>::verify(&vk, transcript_v, &cm, &proof).is_err());
+
+        // the non-hiding scheme rejects non-zero blinding factors
+        assert!(matches!(
+            KZG::<Bn254>::commit(&pk, &v, &blind),
+            Err(Error::BlindingNotZero)
+        ));
+
+        // a key without the powers of γ⋅G does not support hiding
+        let pk_non_hiding = ProverKey {
+            powers_of_gamma_g: vec![],
+            ..pk.clone()
+        };
+        assert!(KZG::<Bn254, true>::commit(&pk_non_hiding, &v, &blind).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_kzg_prover_key_serialization() -> Result<(), Error> {
+        let mut rng = &mut test_rng();
+        let (pk, _): (ProverKey<G1>, VerifierKey<Bn254>) = KZG::<Bn254, true>::setup(&mut rng, 8)?;
+
+        let mut bytes = vec![];
+        pk.serialize_compressed(&mut bytes)?;
+        assert_eq!(ProverKey::<G1>::deserialize_compressed(&bytes[..])?, pk);
+
+        // the keys serialized in the first format, which only contains the powers of G, can
+        // still be deserialized, without hiding support
+        let mut bytes = vec![];
+        pk.powers_of_g.serialize_compressed(&mut bytes)?;
+        let pk_legacy = ProverKey::<G1>::deserialize_compressed(&bytes[..])?;
+        assert_eq!(pk_legacy.powers_of_g, pk.powers_of_g);
+        assert!(pk_legacy.powers_of_gamma_g.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_kzg_lagrange_bases() -> Result<(), Error> {
+        let mut rng = &mut test_rng();
+
+        let (pk, _): (ProverKey<G1>, VerifierKey<Bn254>) = KZG::<Bn254, true>::setup(&mut rng, 32)?;
+        let pk_lagrange = pk.clone().with_lagrange_bases()?;
+        assert_eq!(pk_lagrange.lagrange_bases.as_ref().unwrap().len(), 6);
+
+        for n in [1, 5, 16, 32] {
+            let blind = Fr::rand(rng);
+            // random, boolean and mixed vectors
+            let vectors: Vec<Vec<Fr>> = vec![
+                std::iter::repeat_with(|| Fr::rand(rng)).take(n).collect(),
+                (0..n).map(|i| Fr::from((i % 2) as u64)).collect(),
+                (0..n)
+                    .map(|i| match i % 3 {
+                        0 => Fr::from(i as u64),
+                        1 => Fr::zero(),
+                        _ => Fr::rand(rng),
+                    })
+                    .collect(),
+            ];
+            for v in vectors {
+                assert_eq!(
+                    KZG::<Bn254, true>::commit(&pk_lagrange, &v, &blind)?,
+                    KZG::<Bn254, true>::commit(&pk, &v, &blind)?
+                );
+            }
+        }
+
+        // the Lagrange bases are not serialized
+        let mut bytes = vec![];
+        pk_lagrange.serialize_compressed(&mut bytes)?;
+        assert_eq!(ProverKey::<G1>::deserialize_compressed(&bytes[..])?, pk);
+        Ok(())
+    }
 }
@@ -5,9 +5,20 @@ use ark_std::rand::RngCore;
 use crate::transcript::Transcript;
 use crate::{Curve, Error};
 
+pub mod hyperkzg;
+pub mod hyrax;
 pub mod ipa;
 pub mod kzg;
 pub mod pedersen;
+pub mod pedersen_mle;
+pub mod ptau;
+
+/// CommitmentSchemeId identifies a commitment scheme in the params and proofs written to disk
+/// (see `folding::envelope`). It is kept apart from `CommitmentScheme` so that commitment schemes
+/// defined outside of this crate only need to implement it when they are used with the envelope.
+pub trait CommitmentSchemeId {
+    const ID: &'static str;
+}
 
 /// CommitmentScheme defines the vector commitment scheme trait. Where `H` indicates if to use the
 /// commitment in hiding mode or not.
@@ -65,6 +76,26 @@ pub trait CommitmentScheme<C: Curve, const H: bool = false>: Clone + Debug {
         cm: &C,
         proof: &Self::Proof,
     ) -> Result<(), Error>;
+
+    /// Returns the prover params with precomputed fixed-base tables for windows of `window` bits
+    /// attached, for the schemes whose `commit` can use them (see `pedersen::FixedBaseTables`).
+    /// The other schemes return the params unchanged.
+    fn with_fixed_base_tables(
+        params: Self::ProverParams,
+        _window: usize,
+    ) -> Result<Self::ProverParams, Error> {
+        Ok(params)
+    }
+}
+
+/// EvaluationProof is implemented by the opening proofs that prove the evaluation at the challenge
+/// of the committed vector, seen as the evaluations of a polynomial over the radix-2 domain (see
+/// `EvalGadget`) or, for the multilinear schemes, of its multilinear extension, as returned by
+/// `CommitmentScheme::prove_with_challenge`. The deciders use it to bind the openings to the
+/// evaluations that they check in-circuit (see `DeciderChallenge`).
+pub trait EvaluationProof<F> {
+    /// Returns the evaluation claimed by the proof.
+    fn evaluation(&self) -> F;
 }
 
 #[cfg(test)]
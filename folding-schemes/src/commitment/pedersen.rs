@@ -1,12 +1,18 @@
+use ark_ec::AffineRepr;
+use ark_ff::PrimeField;
 use ark_r1cs_std::{boolean::Boolean, convert::ToBitsGadget, prelude::CurveVar};
 use ark_relations::r1cs::SynthesisError;
-use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Valid};
 use ark_std::{marker::PhantomData, rand::RngCore, UniformRand, Zero};
+use rayon::prelude::*;
 
-use super::CommitmentScheme;
+use super::{CommitmentScheme, CommitmentSchemeId};
 use crate::folding::circuits::CF2;
 use crate::transcript::Transcript;
-use crate::utils::vec::{vec_add, vec_scalar_mul};
+use crate::utils::{
+    msm::small_value_msm,
+    vec::{vec_add, vec_scalar_mul},
+};
 use crate::{Curve, Error};
 
 #[derive(Debug, Clone, Eq, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
@@ -16,10 +22,155 @@ pub struct Proof<C: Curve> {
     pub r_u: C::ScalarField, // blind
 }
 
-#[derive(Debug, Clone, Eq, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+#[derive(Debug, Clone, Eq, PartialEq)]
 pub struct Params<C: Curve> {
     pub h: C,
     pub generators: Vec<C::Affine>,
+    /// optional precomputed tables of the generators, used by `commit` when present. They are
+    /// not serialized, since they can be recomputed from the generators.
+    pub tables: Option<FixedBaseTables<C>>,
+}
+
+impl<C: Curve> Params<C> {
+    /// Precomputes the fixed-base tables of the generators for windows of `window` bits (see
+    /// `FixedBaseTables`), which speed up `commit` at the cost of storing `⌈254/window⌉` points per
+    /// generator. Since the generators are fixed for the lifetime of the prover params, this is
+    /// worth it for the provers that commit on every step. The folding schemes build them in
+    /// `preprocess` when requested through `PreprocessorParam::with_fixed_base_tables`.
+    pub fn with_fixed_base_tables(mut self, window: usize) -> Result<Self, Error> {
+        self.tables = Some(FixedBaseTables::new(&self.generators, window)?);
+        Ok(self)
+    }
+}
+
+impl<C: Curve> CanonicalSerialize for Params<C> {
+    fn serialize_with_mode<W: std::io::prelude::Write>(
+        &self,
+        mut writer: W,
+        compress: ark_serialize::Compress,
+    ) -> Result<(), ark_serialize::SerializationError> {
+        self.h.serialize_with_mode(&mut writer, compress)?;
+        self.generators.serialize_with_mode(&mut writer, compress)
+    }
+
+    fn serialized_size(&self, compress: ark_serialize::Compress) -> usize {
+        self.h.serialized_size(compress) + self.generators.serialized_size(compress)
+    }
+}
+
+impl<C: Curve> CanonicalDeserialize for Params<C> {
+    fn deserialize_with_mode<R: std::io::prelude::Read>(
+        mut reader: R,
+        compress: ark_serialize::Compress,
+        validate: ark_serialize::Validate,
+    ) -> Result<Self, ark_serialize::SerializationError> {
+        let h = C::deserialize_with_mode(&mut reader, compress, validate)?;
+        let generators = Vec::deserialize_with_mode(&mut reader, compress, validate)?;
+        Ok(Params {
+            h,
+            generators,
+            tables: None,
+        })
+    }
+}
+
+impl<C: Curve> Valid for Params<C> {
+    fn check(&self) -> Result<(), ark_serialize::SerializationError> {
+        self.h.check()?;
+        self.generators.check()
+    }
+}
+
+/// Largest window supported by `FixedBaseTables`. `msm` keeps `2^window` buckets per thread, so
+/// larger windows quickly run out of memory (and overflow `usize` on 32-bit targets).
+pub const MAX_FIXED_BASE_WINDOW: usize = 20;
+
+/// FixedBaseTables contains, for windows of `window` bits, the multiples `2^{window⋅j}⋅g_i` of
+/// every generator `g_i` and window `j`. With them, an MSM over the generators becomes a single
+/// bucket accumulation of `window`-bit digits, which needs no doublings and pays the reduction of
+/// the buckets only once instead of once per window, so it allows larger windows than a
+/// variable-base MSM.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct FixedBaseTables<C: Curve> {
+    pub window: usize,
+    pub n_windows: usize,
+    /// `bases[i * n_windows + j] = 2^{window⋅j}⋅g_i`
+    pub bases: Vec<C::Affine>,
+}
+
+impl<C: Curve> FixedBaseTables<C> {
+    pub fn new(generators: &[C::Affine], window: usize) -> Result<Self, Error> {
+        if window == 0 || window > MAX_FIXED_BASE_WINDOW {
+            return Err(Error::InvalidFixedBaseWindow(window, MAX_FIXED_BASE_WINDOW));
+        }
+        let n_windows = (C::ScalarField::MODULUS_BIT_SIZE as usize).div_ceil(window);
+        let bases: Vec<C> = generators
+            .par_iter()
+            .flat_map_iter(|g| {
+                let mut base = g.into_group();
+                (0..n_windows).map(move |_| {
+                    let current = base;
+                    for _ in 0..window {
+                        base.double_in_place();
+                    }
+                    current
+                })
+            })
+            .collect();
+        Ok(Self {
+            window,
+            n_windows,
+            bases: C::normalize_batch(&bases),
+        })
+    }
+
+    /// Computes `<g, v>` for the generators `g` of the tables, which must be at least as many as
+    /// the elements of `v`.
+    pub fn msm(&self, v: &[C::ScalarField]) -> C {
+        let window = self.window;
+        v.par_iter()
+            .zip(self.bases.par_chunks(self.n_windows))
+            .fold(
+                || vec![C::zero(); (1 << window) - 1],
+                |mut buckets, (v_i, bases)| {
+                    if v_i.is_zero() {
+                        return buckets;
+                    }
+                    let v_i = v_i.into_bigint();
+                    for (j, base) in bases.iter().enumerate() {
+                        let digit = window_digit(v_i.as_ref(), j * window, window);
+                        if digit != 0 {
+                            buckets[digit - 1] += *base;
+                        }
+                    }
+                    buckets
+                },
+            )
+            .map(|buckets| {
+                // Σ_d d⋅buckets[d-1], through running sums
+                let mut running_sum = C::zero();
+                let mut res = C::zero();
+                for bucket in buckets.into_iter().rev() {
+                    running_sum += bucket;
+                    res += running_sum;
+                }
+                res
+            })
+            .sum()
+    }
+}
+
+/// Returns the `window` bits of `limbs` starting at bit `start`.
+fn window_digit(limbs: &[u64], start: usize, window: usize) -> usize {
+    let (limb, offset) = (start / 64, start % 64);
+    if limb >= limbs.len() {
+        return 0;
+    }
+    let mut digit = limbs[limb] >> offset;
+    if offset + window > 64 && limb + 1 < limbs.len() {
+        digit |= limbs[limb + 1] << (64 - offset);
+    }
+    (digit & ((1 << window) - 1)) as usize
 }
 
 #[derive(Debug, Clone, Eq, PartialEq)]
@@ -27,6 +178,10 @@ pub struct Pedersen<C: Curve, const H: bool = false> {
     _c: PhantomData<C>,
 }
 
+impl<C: Curve, const H: bool> CommitmentSchemeId for Pedersen<C, H> {
+    const ID: &'static str = "pedersen";
+}
+
 /// Implements the CommitmentScheme trait for Pedersen commitments
 impl<C: Curve, const H: bool> CommitmentScheme<C, H> for Pedersen<C, H> {
     type ProverParams = Params<C>;
@@ -52,6 +207,7 @@ impl<C: Curve, const H: bool> CommitmentScheme<C, H> for Pedersen<C, H> {
         let p = Params::<C> {
             h: C::rand(&mut rng),
             generators,
+            tables: None,
         };
         Ok((p.clone(), p))
     }
@@ -68,12 +224,15 @@ impl<C: Curve, const H: bool> CommitmentScheme<C, H> for Pedersen<C, H> {
             return Err(Error::BlindingNotZero);
         }
 
-        // h⋅r + <g, v>
-        // use msm_unchecked because we already ensured at the if that lengths match
+        // h⋅r + <g, v>, where the lengths were already checked above
+        let cm = match &params.tables {
+            Some(tables) => tables.msm(v),
+            None => small_value_msm(&params.generators[..v.len()], v),
+        };
         if !H {
-            return Ok(C::msm_unchecked(&params.generators[..v.len()], v));
+            return Ok(cm);
         }
-        Ok(params.h.mul(r) + C::msm_unchecked(&params.generators[..v.len()], v))
+        Ok(params.h.mul(r) + cm)
     }
 
     fn prove(
@@ -172,6 +331,13 @@ impl<C: Curve, const H: bool> CommitmentScheme<C, H> for Pedersen<C, H> {
         }
         Ok(())
     }
+
+    fn with_fixed_base_tables(
+        params: Self::ProverParams,
+        window: usize,
+    ) -> Result<Self::ProverParams, Error> {
+        params.with_fixed_base_tables(window)
+    }
 }
 
 pub struct PedersenGadget<C: Curve, const H: bool = false> {
@@ -256,6 +422,52 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_pedersen_fixed_base_tables() -> Result<(), Error> {
+        let mut rng = ark_std::test_rng();
+
+        let n: usize = 37;
+        let (params, _) = Pedersen::<Projective, true>::setup(&mut rng, n)?;
+        let v: Vec<Fr> = std::iter::repeat_with(|| Fr::rand(&mut rng))
+            .take(n)
+            .chain([Fr::zero(), Fr::from(1), -Fr::from(1)])
+            .collect();
+        let r = Fr::rand(&mut rng);
+        let expected = Pedersen::<Projective, true>::commit(&params, &v, &r)?;
+
+        // windows dividing the scalar size or not, and crossing the limbs or not
+        for window in [1, 3, 8, 13, 16] {
+            let params_tables = params.clone().with_fixed_base_tables(window)?;
+            // also for vectors shorter than the generators
+            assert_eq!(
+                Pedersen::<Projective, true>::commit(&params_tables, &v, &r)?,
+                expected
+            );
+            assert_eq!(
+                Pedersen::<Projective, true>::commit(&params_tables, &v[..5], &r)?,
+                Pedersen::<Projective, true>::commit(&params, &v[..5], &r)?
+            );
+        }
+
+        // windows whose buckets would not fit in memory are rejected
+        for window in [0, MAX_FIXED_BASE_WINDOW + 1, 32] {
+            assert!(matches!(
+                params.clone().with_fixed_base_tables(window),
+                Err(Error::InvalidFixedBaseWindow(w, MAX_FIXED_BASE_WINDOW)) if w == window
+            ));
+        }
+
+        // the tables are not serialized
+        let params_tables = params.clone().with_fixed_base_tables(8)?;
+        let mut bytes = vec![];
+        params_tables.serialize_compressed(&mut bytes)?;
+        assert_eq!(
+            Params::<Projective>::deserialize_compressed(&bytes[..])?,
+            params
+        );
+        Ok(())
+    }
+
     #[test]
     fn test_pedersen_circuit() -> Result<(), Error> {
         let _ = test_pedersen_circuit_opt::<false>(8)?;
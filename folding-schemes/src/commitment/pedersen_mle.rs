@@ -0,0 +1,266 @@
+/// Pedersen commitment with openings of the multilinear extension of the committed vector, ie.
+/// the commitment is the Pedersen commitment to `v` (the same as `Pedersen`'s), a single group
+/// element that can be folded homomorphically, and the opening proves the evaluation `f(u)` at a
+/// point `u` of the multilinear polynomial `f` whose evaluations over the boolean hypercube are
+/// `v` (with the `DenseMultilinearExtension` variable ordering, as in `HyperKZG`), so it can be
+/// used as `CS1`/`CS2` of the folding schemes and of their deciders where the openings are at
+/// multilinear extension points.
+///
+/// The opening is Hyrax's proof-of-dot-product `<v, eq(u)> = f(u)` (see `hyrax`), which contains
+/// `len(v)` scalars, as `Pedersen`'s, and in hiding mode (`H = true`) it is zero-knowledge. For
+/// square-root sized openings, use `Hyrax`'s commitments to the rows of `v`, which are not a single
+/// group element.
+use ark_std::{marker::PhantomData, rand::RngCore};
+
+use super::{
+    hyrax::{eq_vec, DotProductProof, Hyrax},
+    pedersen::{Params, Pedersen},
+    CommitmentScheme, CommitmentSchemeId,
+};
+use crate::constants::PEDERSEN_MLE_DOMAIN;
+use crate::transcript::{keccak::KeccakTranscript, Transcript};
+use crate::{Curve, Error};
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct PedersenMLE<C: Curve, const H: bool = false> {
+    _c: PhantomData<C>,
+}
+
+impl<C: Curve, const H: bool> CommitmentSchemeId for PedersenMLE<C, H> {
+    const ID: &'static str = "pedersen-mle";
+}
+
+/// Implements the CommitmentScheme trait for PedersenMLE, whose challenges are the points at which
+/// the multilinear extensions of the committed vectors are opened.
+impl<C: Curve, const H: bool> CommitmentScheme<C, H> for PedersenMLE<C, H> {
+    type ProverParams = Params<C>;
+    type VerifierParams = Params<C>;
+    type Proof = DotProductProof<C>;
+    type ProverChallenge = Vec<C::ScalarField>;
+    type Challenge = Vec<C::ScalarField>;
+
+    fn is_hiding() -> bool {
+        if H {
+            return true;
+        }
+        false
+    }
+
+    /// setup returns the same parameters as `Pedersen::setup`, which contain `len` generators
+    /// (rounded up to a power of two).
+    fn setup(
+        rng: impl RngCore,
+        len: usize,
+    ) -> Result<(Self::ProverParams, Self::VerifierParams), Error> {
+        Pedersen::<C, H>::setup(rng, len)
+    }
+
+    fn commit(
+        params: &Self::ProverParams,
+        v: &[C::ScalarField],
+        blind: &C::ScalarField,
+    ) -> Result<C, Error> {
+        Pedersen::<C, H>::commit(params, v, blind)
+    }
+
+    /// prove opens the multilinear extension of `v` at a point derived from the transcript, whose
+    /// number of variables is `log2(params.generators.len())`, so that `verify` can derive the
+    /// same point from the params.
+    fn prove(
+        params: &Self::ProverParams,
+        transcript: &mut impl Transcript<C::ScalarField>,
+        cm: &C,
+        v: &[C::ScalarField],
+        blind: &C::ScalarField,
+        rng: Option<&mut dyn RngCore>,
+    ) -> Result<Self::Proof, Error> {
+        transcript.absorb_nonnative(cm);
+        let point = transcript.get_challenges(n_vars(params.generators.len()));
+        Self::prove_inner(params, transcript, &point, v, blind, rng)
+    }
+
+    /// prove_with_challenge proves the evaluation of the multilinear extension of `v` at the given
+    /// point, which determines the number of variables, and must be at least `log2(v.len())`.
+    fn prove_with_challenge(
+        params: &Self::ProverParams,
+        challenge: Self::ProverChallenge,
+        v: &[C::ScalarField],
+        blind: &C::ScalarField,
+        rng: Option<&mut dyn RngCore>,
+    ) -> Result<Self::Proof, Error> {
+        let cm = Self::commit(params, v, blind)?;
+        let transcript = &mut Self::challenge_transcript(&challenge, &cm);
+        Self::prove_inner(params, transcript, &challenge, v, blind, rng)
+    }
+
+    fn verify(
+        params: &Self::VerifierParams,
+        transcript: &mut impl Transcript<C::ScalarField>,
+        cm: &C,
+        proof: &Self::Proof,
+    ) -> Result<(), Error> {
+        transcript.absorb_nonnative(cm);
+        let point = transcript.get_challenges(n_vars(params.generators.len()));
+        Self::verify_inner(params, transcript, &point, cm, proof)
+    }
+
+    fn verify_with_challenge(
+        params: &Self::VerifierParams,
+        challenge: Self::Challenge,
+        cm: &C,
+        proof: &Self::Proof,
+    ) -> Result<(), Error> {
+        let transcript = &mut Self::challenge_transcript(&challenge, cm);
+        Self::verify_inner(params, transcript, &challenge, cm, proof)
+    }
+
+    fn with_fixed_base_tables(
+        params: Self::ProverParams,
+        window: usize,
+    ) -> Result<Self::ProverParams, Error> {
+        params.with_fixed_base_tables(window)
+    }
+}
+
+impl<C: Curve, const H: bool> PedersenMLE<C, H> {
+    /// Transcript used to derive the challenge of the opening when the point is given externally.
+    fn challenge_transcript(point: &[C::ScalarField], cm: &C) -> KeccakTranscript<C::ScalarField> {
+        let mut transcript = KeccakTranscript::new(&());
+        transcript.domain_separator(PEDERSEN_MLE_DOMAIN);
+        transcript.absorb(&point.to_vec());
+        transcript.absorb_nonnative(cm);
+        transcript
+    }
+
+    /// Proves the evaluation at `point` with the proof-of-dot-product of `v` and `eq(point)`.
+    fn prove_inner(
+        params: &Params<C>,
+        transcript: &mut impl Transcript<C::ScalarField>,
+        point: &[C::ScalarField],
+        v: &[C::ScalarField],
+        blind: &C::ScalarField,
+        rng: Option<&mut dyn RngCore>,
+    ) -> Result<DotProductProof<C>, Error> {
+        Hyrax::<C, H>::prove_dot_product(params, transcript, &eq_vec(point)?, v, blind, rng)
+    }
+
+    fn verify_inner(
+        params: &Params<C>,
+        transcript: &mut impl Transcript<C::ScalarField>,
+        point: &[C::ScalarField],
+        cm: &C,
+        proof: &DotProductProof<C>,
+    ) -> Result<(), Error> {
+        Hyrax::<C, H>::verify_dot_product(params, transcript, &eq_vec(point)?, cm, proof)
+    }
+}
+
+/// Number of variables of the multilinear extensions of the vectors of length `len`.
+fn n_vars(len: usize) -> usize {
+    len.next_power_of_two().trailing_zeros() as usize
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_crypto_primitives::sponge::{poseidon::PoseidonSponge, CryptographicSponge};
+    use ark_pallas::{Fr, Projective};
+    use ark_poly::Polynomial;
+    use ark_std::{UniformRand, Zero};
+
+    use super::*;
+    use crate::commitment::EvaluationProof;
+    use crate::transcript::poseidon::poseidon_canonical_config;
+    use crate::utils::mle::dense_vec_to_dense_mle;
+
+    #[test]
+    fn test_pedersen_mle() -> Result<(), Error> {
+        let _ = test_pedersen_mle_opt::<false>()?;
+        let _ = test_pedersen_mle_opt::<true>()?;
+        Ok(())
+    }
+    fn test_pedersen_mle_opt<const hiding: bool>() -> Result<(), Error> {
+        let mut rng = ark_std::test_rng();
+        let poseidon_config = poseidon_canonical_config::<Fr>();
+
+        for n in [1, 5, 16, 100] {
+            let (pp, vp) = PedersenMLE::<Projective, hiding>::setup(&mut rng, n)?;
+            let v: Vec<Fr> = std::iter::repeat_with(|| Fr::rand(&mut rng))
+                .take(n)
+                .collect();
+            let blind = if hiding {
+                Fr::rand(&mut rng)
+            } else {
+                Fr::zero()
+            };
+            let cm = PedersenMLE::<Projective, hiding>::commit(&pp, &v, &blind)?;
+
+            let mut transcript_p = PoseidonSponge::<Fr>::new(&poseidon_config);
+            let mut transcript_v = PoseidonSponge::<Fr>::new(&poseidon_config);
+            let proof = PedersenMLE::<Projective, hiding>::prove(
+                &pp,
+                &mut transcript_p,
+                &cm,
+                &v,
+                &blind,
+                Some(&mut rng),
+            )?;
+            PedersenMLE::<Projective, hiding>::verify(&vp, &mut transcript_v, &cm, &proof)?;
+
+            // the opening at a given point proves the evaluation of the MLE of `v`
+            let point: Vec<Fr> = std::iter::repeat_with(|| Fr::rand(&mut rng))
+                .take(n.next_power_of_two().trailing_zeros() as usize)
+                .collect();
+            let proof = PedersenMLE::<Projective, hiding>::prove_with_challenge(
+                &pp,
+                point.clone(),
+                &v,
+                &blind,
+                Some(&mut rng),
+            )?;
+            assert_eq!(
+                proof.evaluation(),
+                dense_vec_to_dense_mle(point.len(), &v).evaluate(&point)
+            );
+            PedersenMLE::<Projective, hiding>::verify_with_challenge(
+                &vp,
+                point.clone(),
+                &cm,
+                &proof,
+            )?;
+
+            // a wrong evaluation or a different commitment are rejected
+            let mut wrong_proof = proof.clone();
+            wrong_proof.eval += Fr::from(1);
+            assert!(PedersenMLE::<Projective, hiding>::verify_with_challenge(
+                &vp,
+                point.clone(),
+                &cm,
+                &wrong_proof
+            )
+            .is_err());
+            let v2: Vec<Fr> = std::iter::repeat_with(|| Fr::rand(&mut rng))
+                .take(n)
+                .collect();
+            let cm2 = PedersenMLE::<Projective, hiding>::commit(&pp, &v2, &blind)?;
+            assert!(PedersenMLE::<Projective, hiding>::verify_with_challenge(
+                &vp, point, &cm2, &proof
+            )
+            .is_err());
+        }
+
+        // in hiding mode, the opening needs randomness to mask the vector
+        if hiding {
+            let (pp, _) = PedersenMLE::<Projective, hiding>::setup(&mut rng, 4)?;
+            let v = vec![Fr::from(1); 4];
+            assert!(PedersenMLE::<Projective, hiding>::prove_with_challenge(
+                &pp,
+                vec![Fr::from(2); 2],
+                &v,
+                &Fr::from(3),
+                None
+            )
+            .is_err());
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,465 @@
+/// Loaders of the KZG parameters over BN254 from the outputs of public Powers of Tau ceremonies,
+/// so that the KZG-based deciders do not depend on the toxic waste sampled by `KZG::setup`.
+///
+/// Two formats are supported:
+/// - the `.ptau` files produced by [snarkjs](https://github.com/iden3/snarkjs), which store the
+///   points in little-endian Montgomery form inside a sectioned binary file.
+/// - the raw (uncompressed) `challenge` transcripts of the
+///   [Perpetual Powers of Tau](https://github.com/privacy-scaling-explorations/perpetualpowersoftau)
+///   ceremony, which store the points in big-endian standard form after a 64 bytes hash.
+///
+/// The ceremonies only provide the powers `τ^i⋅G` and `τ^i⋅H`, and not the powers `τ^i⋅γ⋅G` that
+/// the hiding KZG commitments are blinded with, so the loaded keys do not support hiding. The
+/// generator `γ⋅G` of the `VerifierKey` is derived by hashing to the curve, which ensures that
+/// nobody knows its discrete logarithm with respect to `G`.
+///
+/// The loaded parameters are plugged into the deciders through the `cs_pp` and `cs_vp` fields of
+/// the folding scheme's `PreprocessorParam` (see `nova::PreprocessorParam::with_cs_params`), which
+/// are then forwarded by `decider_eth::Decider::preprocess`.
+use ark_bn254::{Bn254, Fq, Fq2, G1Affine, G1Projective, G2Affine};
+use ark_ec::{pairing::Pairing, AffineRepr, VariableBaseMSM};
+use ark_ff::{BigInt, PrimeField, UniformRand};
+use ark_poly_commit::kzg10::VerifierKey;
+use ark_std::{borrow::Cow, rand::RngCore};
+use sha3::{Digest, Keccak256};
+use std::io::{Read, Seek, SeekFrom};
+
+use super::kzg::ProverKey;
+use crate::constants::KZG_GAMMA_G_DOMAIN;
+use crate::Error;
+
+/// Number of bytes of a BN254 base field element.
+const N8: usize = 32;
+
+/// Loads the KZG parameters for committing to vectors of length up to `len` from a snarkjs
+/// `.ptau` file. `rng` is only used to batch the consistency checks of the powers.
+pub fn load_ptau<R: Read + Seek>(
+    rng: impl RngCore,
+    mut reader: R,
+    len: usize,
+) -> Result<(ProverKey<'static, G1Projective>, VerifierKey<Bn254>), Error> {
+    let mut magic = [0u8; 4];
+    reader.read_exact(&mut magic)?;
+    if &magic != b"ptau" {
+        return Err(Error::InvalidSRS("not a ptau file".to_string()));
+    }
+    let _version = read_u32(&mut reader)?;
+    let n_sections = read_u32(&mut reader)?;
+
+    // collect the position and size of the sections, which may appear in any order
+    let mut sections = vec![None; 4];
+    for _ in 0..n_sections {
+        let section_type = read_u32(&mut reader)? as usize;
+        let size = read_u64(&mut reader)?;
+        let position = reader.stream_position()?;
+        if section_type < sections.len() {
+            sections[section_type] = Some((position, size));
+        }
+        reader.seek(SeekFrom::Current(size as i64))?;
+    }
+    let section = |i: usize| {
+        sections[i].ok_or_else(|| Error::InvalidSRS(format!("missing section {} in ptau file", i)))
+    };
+
+    // header section
+    reader.seek(SeekFrom::Start(section(1)?.0))?;
+    let n8 = read_u32(&mut reader)? as usize;
+    if n8 != N8 {
+        return Err(Error::InvalidSRS(format!(
+            "unexpected field size {} in ptau file",
+            n8
+        )));
+    }
+    let mut q = [0u8; N8];
+    reader.read_exact(&mut q)?;
+    if bigint_from_le(&q) != Fq::MODULUS {
+        return Err(Error::InvalidSRS(
+            "ptau file is not over the BN254 curve".to_string(),
+        ));
+    }
+    let power = read_u32(&mut reader)?;
+    let n = n_powers(power, len)?;
+
+    let (position, size) = section(2)?;
+    if size < (n * 2 * N8) as u64 {
+        return Err(Error::InvalidSRS("truncated tauG1 section".to_string()));
+    }
+    reader.seek(SeekFrom::Start(position))?;
+    let powers_of_g = (0..n)
+        .map(|_| read_g1(&mut reader, read_fq_lem))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let (position, size) = section(3)?;
+    if size < (2 * 4 * N8) as u64 {
+        return Err(Error::InvalidSRS("truncated tauG2 section".to_string()));
+    }
+    reader.seek(SeekFrom::Start(position))?;
+    let h = read_g2(&mut reader, read_fq_lem, false)?;
+    let beta_h = read_g2(&mut reader, read_fq_lem, false)?;
+
+    build_params(rng, powers_of_g, h, beta_h)
+}
+
+/// Loads the KZG parameters for committing to vectors of length up to `len` from a raw
+/// (uncompressed) Perpetual Powers of Tau `challenge` transcript of `2^power` powers. `rng` is only
+/// used to batch the consistency checks of the powers.
+pub fn load_ppot<R: Read + Seek>(
+    rng: impl RngCore,
+    mut reader: R,
+    power: u32,
+    len: usize,
+) -> Result<(ProverKey<'static, G1Projective>, VerifierKey<Bn254>), Error> {
+    let n = n_powers(power, len)?;
+    let n_g1 = (1u64 << (power + 1)) - 1;
+
+    // skip the hash of the previous contribution
+    reader.seek(SeekFrom::Start(64))?;
+    let powers_of_g = (0..n)
+        .map(|_| read_g1(&mut reader, read_fq_be))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    reader.seek(SeekFrom::Start(64 + n_g1 * 2 * N8 as u64))?;
+    let h = read_g2(&mut reader, read_fq_be, true)?;
+    let beta_h = read_g2(&mut reader, read_fq_be, true)?;
+
+    build_params(rng, powers_of_g, h, beta_h)
+}
+
+/// Returns the number of G1 powers that `KZG::setup` would use for vectors of length `len`,
+/// checking that a ceremony of `2^power` powers (thus `2^(power+1)-1` G1 powers) contains them.
+fn n_powers(power: u32, len: usize) -> Result<usize, Error> {
+    let n = len.next_power_of_two() + 1;
+    if power >= 63 || n as u64 > (1u64 << (power + 1)) - 1 {
+        return Err(Error::InvalidSRS(format!(
+            "the ceremony of 2^{} powers does not support vectors of length {}",
+            power, len
+        )));
+    }
+    Ok(n)
+}
+
+/// Checks that the loaded powers are well formed and consistent, i.e. that they are of the form
+/// `τ^i⋅G` and `τ⋅H` for the same `τ` and the standard generators `G`, `H`, and builds the KZG
+/// parameters from them.
+fn build_params(
+    mut rng: impl RngCore,
+    powers_of_g: Vec<G1Affine>,
+    h: G2Affine,
+    beta_h: G2Affine,
+) -> Result<(ProverKey<'static, G1Projective>, VerifierKey<Bn254>), Error> {
+    let g = powers_of_g[0];
+    if g != G1Affine::generator() || h != G2Affine::generator() {
+        return Err(Error::InvalidSRS(
+            "the SRS does not start with the generators".to_string(),
+        ));
+    }
+
+    // check that e(τ^{i+1}⋅G, H) == e(τ^i⋅G, τ⋅H) for all i, batched through a random linear
+    // combination with coefficients ρ_i, which also covers e(τ⋅G, H) == e(G, τ⋅H)
+    let rho: Vec<_> = (0..powers_of_g.len() - 1)
+        .map(|_| ark_bn254::Fr::rand(&mut rng))
+        .collect();
+    let lhs = G1Projective::msm_unchecked(&powers_of_g[1..], &rho);
+    let rhs = G1Projective::msm_unchecked(&powers_of_g[..powers_of_g.len() - 1], &rho);
+    if Bn254::pairing(lhs, h) != Bn254::pairing(rhs, beta_h) {
+        return Err(Error::InvalidSRS(
+            "the powers of tau are not consistent".to_string(),
+        ));
+    }
+
+    let gamma_g = hash_to_g1(KZG_GAMMA_G_DOMAIN);
+    let pk = ProverKey {
+        powers_of_g: Cow::Owned(powers_of_g),
+        powers_of_gamma_g: vec![],
+        lagrange_bases: None,
+    };
+    let vk = VerifierKey {
+        g,
+        gamma_g,
+        h,
+        beta_h,
+        prepared_h: h.into(),
+        prepared_beta_h: beta_h.into(),
+    };
+    Ok((pk, vk))
+}
+
+/// Try-and-increment hash to G1 (BN254's G1 has cofactor 1, so any point of the curve is valid).
+fn hash_to_g1(domain: &[u8]) -> G1Affine {
+    let mut counter = 0u64;
+    loop {
+        let digest = Keccak256::new()
+            .chain_update(domain)
+            .chain_update(counter.to_le_bytes())
+            .finalize();
+        let x = Fq::from_le_bytes_mod_order(&digest);
+        if let Some(p) = G1Affine::get_point_from_x_unchecked(x, false) {
+            return p;
+        }
+        counter += 1;
+    }
+}
+
+fn read_u32<R: Read>(reader: &mut R) -> Result<u32, Error> {
+    let mut buf = [0u8; 4];
+    reader.read_exact(&mut buf)?;
+    Ok(u32::from_le_bytes(buf))
+}
+
+fn read_u64<R: Read>(reader: &mut R) -> Result<u64, Error> {
+    let mut buf = [0u8; 8];
+    reader.read_exact(&mut buf)?;
+    Ok(u64::from_le_bytes(buf))
+}
+
+fn bigint_from_le(bytes: &[u8; N8]) -> BigInt<4> {
+    let mut limbs = [0u64; 4];
+    for (limb, chunk) in limbs.iter_mut().zip(bytes.chunks(8)) {
+        *limb = u64::from_le_bytes(chunk.try_into().unwrap());
+    }
+    BigInt::new(limbs)
+}
+
+/// Reads a field element in little-endian Montgomery form (snarkjs).
+fn read_fq_lem<R: Read>(reader: &mut R) -> Result<Fq, Error> {
+    let mut buf = [0u8; N8];
+    reader.read_exact(&mut buf)?;
+    let repr = bigint_from_le(&buf);
+    if repr >= Fq::MODULUS {
+        return Err(Error::InvalidSRS("non-canonical field element".to_string()));
+    }
+    Ok(Fq::new_unchecked(repr))
+}
+
+/// Reads a field element in big-endian standard form (Perpetual Powers of Tau), whose two most
+/// significant bits are reserved for the point flags.
+fn read_fq_be<R: Read>(reader: &mut R) -> Result<Fq, Error> {
+    let mut buf = [0u8; N8];
+    reader.read_exact(&mut buf)?;
+    if buf[0] & 0xc0 != 0 {
+        // either the compression or the infinity flag is set, neither of which is expected
+        // for the powers of a non-zero τ in an uncompressed transcript
+        return Err(Error::InvalidSRS(
+            "unexpected compressed or infinity point".to_string(),
+        ));
+    }
+    buf.reverse();
+    Fq::from_bigint(bigint_from_le(&buf))
+        .ok_or_else(|| Error::InvalidSRS("non-canonical field element".to_string()))
+}
+
+fn read_g1<R: Read>(
+    reader: &mut R,
+    read_fq: impl Fn(&mut R) -> Result<Fq, Error>,
+) -> Result<G1Affine, Error> {
+    let x = read_fq(reader)?;
+    let y = read_fq(reader)?;
+    let p = G1Affine::new_unchecked(x, y);
+    if !p.is_on_curve() {
+        return Err(Error::InvalidSRS("G1 point not on the curve".to_string()));
+    }
+    Ok(p)
+}
+
+/// Reads a G2 point, whose coordinates are stored as `c0, c1` by snarkjs and as `c1, c0` by the
+/// Perpetual Powers of Tau (`c1_first`).
+fn read_g2<R: Read>(
+    reader: &mut R,
+    read_fq: impl Fn(&mut R) -> Result<Fq, Error>,
+    c1_first: bool,
+) -> Result<G2Affine, Error> {
+    let read_fq2 = |reader: &mut R| -> Result<Fq2, Error> {
+        let (a, b) = (read_fq(reader)?, read_fq(reader)?);
+        Ok(if c1_first {
+            Fq2::new(b, a)
+        } else {
+            Fq2::new(a, b)
+        })
+    };
+    let x = read_fq2(reader)?;
+    let y = read_fq2(reader)?;
+    let p = G2Affine::new_unchecked(x, y);
+    if !p.is_on_curve() || !p.is_in_correct_subgroup_assuming_on_curve() {
+        return Err(Error::InvalidSRS("invalid G2 point".to_string()));
+    }
+    Ok(p)
+}
+
+/// Writers of the ceremony formats, used to build synthetic ceremonies from a known `τ` in tests.
+#[cfg(test)]
+pub(crate) mod tests_utils {
+    use super::*;
+    use ark_bn254::{Fr, G2Projective};
+    use ark_ec::CurveGroup;
+    use ark_ff::{BigInteger, Field};
+
+    fn powers(tau: Fr, power: u32) -> (Vec<G1Affine>, Vec<G2Affine>) {
+        let n_g1 = (1usize << (power + 1)) - 1;
+        let n_g2 = 1usize << power;
+        let mut tau_i = Fr::ONE;
+        let mut g1 = vec![];
+        for _ in 0..n_g1 {
+            g1.push(G1Affine::generator() * tau_i);
+            tau_i *= tau;
+        }
+        let g2: Vec<G2Projective> = (0..n_g2)
+            .map(|i| G2Affine::generator() * tau.pow([i as u64]))
+            .collect();
+        (
+            G1Projective::normalize_batch(&g1),
+            G2Projective::normalize_batch(&g2),
+        )
+    }
+
+    fn write_fq_lem(buf: &mut Vec<u8>, x: Fq) {
+        // `x.0` is the Montgomery representation of `x`
+        buf.extend(x.0.to_bytes_le());
+    }
+
+    fn write_fq_be(buf: &mut Vec<u8>, x: Fq) {
+        buf.extend(x.into_bigint().to_bytes_be());
+    }
+
+    /// Returns a snarkjs `.ptau` file with `2^power` powers of `tau`.
+    pub(crate) fn ptau_bytes(tau: Fr, power: u32) -> Vec<u8> {
+        let (g1, g2) = powers(tau, power);
+
+        let mut header = vec![];
+        header.extend((N8 as u32).to_le_bytes());
+        header.extend(Fq::MODULUS.to_bytes_le());
+        header.extend(power.to_le_bytes());
+        header.extend(power.to_le_bytes()); // ceremony power
+        let mut tau_g1 = vec![];
+        for p in g1 {
+            write_fq_lem(&mut tau_g1, p.x);
+            write_fq_lem(&mut tau_g1, p.y);
+        }
+        let mut tau_g2 = vec![];
+        for p in g2 {
+            for c in [p.x.c0, p.x.c1, p.y.c0, p.y.c1] {
+                write_fq_lem(&mut tau_g2, c);
+            }
+        }
+
+        let mut file = b"ptau".to_vec();
+        file.extend(1u32.to_le_bytes()); // version
+        file.extend(3u32.to_le_bytes()); // number of sections
+        for (section_type, data) in [(1u32, header), (2, tau_g1), (3, tau_g2)] {
+            file.extend(section_type.to_le_bytes());
+            file.extend((data.len() as u64).to_le_bytes());
+            file.extend(data);
+        }
+        file
+    }
+
+    /// Returns a Perpetual Powers of Tau `challenge` transcript with `2^power` powers of `tau`,
+    /// without the (unused) alpha and beta sections.
+    pub(crate) fn ppot_bytes(tau: Fr, power: u32) -> Vec<u8> {
+        let (g1, g2) = powers(tau, power);
+
+        let mut file = vec![0u8; 64];
+        for p in g1 {
+            write_fq_be(&mut file, p.x);
+            write_fq_be(&mut file, p.y);
+        }
+        for p in g2 {
+            for c in [p.x.c1, p.x.c0, p.y.c1, p.y.c0] {
+                write_fq_be(&mut file, c);
+            }
+        }
+        file
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::tests_utils::{ppot_bytes, ptau_bytes};
+    use super::*;
+    use ark_bn254::Fr;
+    use ark_ec::CurveGroup;
+    use ark_ff::Field;
+    use ark_std::{test_rng, UniformRand};
+    use std::io::Cursor;
+
+    use crate::commitment::{kzg::KZG, CommitmentScheme};
+    use crate::transcript::poseidon::poseidon_canonical_config;
+    use ark_crypto_primitives::sponge::{poseidon::PoseidonSponge, CryptographicSponge};
+
+    fn test_commit_and_open(pk: &ProverKey<G1Projective>, vk: &VerifierKey<Bn254>, len: usize) {
+        let mut rng = test_rng();
+        let poseidon_config = poseidon_canonical_config::<Fr>();
+        let v: Vec<Fr> = (0..len).map(|_| Fr::rand(&mut rng)).collect();
+
+        let cm = KZG::<Bn254>::commit(pk, &v, &Fr::from(0)).unwrap();
+        let mut transcript_p = PoseidonSponge::<Fr>::new(&poseidon_config);
+        let proof =
+            KZG::<Bn254>::prove(pk, &mut transcript_p, &cm, &v, &Fr::from(0), None).unwrap();
+        let mut transcript_v = PoseidonSponge::<Fr>::new(&poseidon_config);
+        KZG::<Bn254>::verify(vk, &mut transcript_v, &cm, &proof).unwrap();
+    }
+
+    #[test]
+    fn test_load_ptau() -> Result<(), Error> {
+        let mut rng = test_rng();
+        let tau = Fr::rand(&mut rng);
+        let file = ptau_bytes(tau, 4);
+
+        let len = 10;
+        let (pk, vk) = load_ptau(&mut rng, Cursor::new(&file), len)?;
+        assert_eq!(pk.powers_of_g.len(), len.next_power_of_two() + 1);
+        assert_eq!(
+            pk.powers_of_g[3],
+            (G1Affine::generator() * tau * tau * tau).into_affine()
+        );
+        assert_eq!(vk.beta_h, (G2Affine::generator() * tau).into_affine());
+        test_commit_and_open(&pk, &vk, len);
+
+        // the ceremony is too small for the requested length
+        assert!(load_ptau(&mut rng, Cursor::new(&file), 32).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_load_ppot() -> Result<(), Error> {
+        let mut rng = test_rng();
+        let tau = Fr::rand(&mut rng);
+        let file = ppot_bytes(tau, 4);
+
+        let len = 16;
+        let (pk, vk) = load_ppot(&mut rng, Cursor::new(&file), 4, len)?;
+        assert_eq!(pk.powers_of_g.len(), len + 1);
+        test_commit_and_open(&pk, &vk, len);
+
+        // both formats yield the same parameters for the same τ
+        let (pk_ptau, vk_ptau) = load_ptau(&mut rng, Cursor::new(ptau_bytes(tau, 4)), len)?;
+        assert_eq!(pk, pk_ptau);
+        assert_eq!(vk.beta_h, vk_ptau.beta_h);
+        assert_eq!(vk.gamma_g, vk_ptau.gamma_g);
+        Ok(())
+    }
+
+    #[test]
+    fn test_load_inconsistent_powers() {
+        let mut rng = test_rng();
+        let tau = Fr::rand(&mut rng);
+
+        // replace τ^5⋅G by τ^5⋅G + G in the tauG1 section of the ptau file
+        let mut file = ptau_bytes(tau, 4);
+        let tampered = (G1Affine::generator() * tau.pow([5]) + G1Affine::generator()).into_affine();
+        let mut point = vec![];
+        for c in [tampered.x, tampered.y] {
+            point.extend(ark_ff::BigInteger::to_bytes_le(&c.0));
+        }
+        // magic, version, n_sections, header section (12 + 44 bytes), tauG1 section header
+        let offset = 4 + 4 + 4 + 12 + 44 + 12 + 5 * 2 * N8;
+        file[offset..offset + 2 * N8].copy_from_slice(&point);
+        assert!(load_ptau(&mut rng, Cursor::new(&file), 8).is_err());
+        // the tampered power is not used for shorter vectors
+        assert!(load_ptau(&mut rng, Cursor::new(&file), 4).is_ok());
+
+        // a point not on the curve is rejected
+        let mut file = ppot_bytes(tau, 4);
+        file[64 + 2 * N8 - 1] ^= 1;
+        assert!(load_ppot(&mut rng, Cursor::new(&file), 4, 4).is_err());
+    }
+}
@@ -3,3 +3,27 @@
 // error ≤ 2/|S|, where S is the subset of the field F from which the challenges are drawn. In this
 // case, we keep the size of S close to 2^128.
 pub const NOVA_N_BITS_RO: usize = 128;
+
+// Domain separators absorbed by each folding scheme before deriving its challenges, so that the
+// transcripts of different schemes (or of the main and CycleFold instances of the same scheme)
+// never share a state.
+pub const NOVA_NIFS_DOMAIN: &[u8] = b"sonobe/nova/nifs";
+pub const OVA_NIFS_DOMAIN: &[u8] = b"sonobe/ova/nifs";
+pub const MOVA_NIFS_DOMAIN: &[u8] = b"sonobe/mova/nifs";
+pub const CYCLEFOLD_NIFS_DOMAIN: &[u8] = b"sonobe/cyclefold/nifs";
+pub const HYPERNOVA_NIMFS_DOMAIN: &[u8] = b"sonobe/hypernova/nimfs";
+pub const PROTOGALAXY_FOLDING_DOMAIN: &[u8] = b"sonobe/protogalaxy/folding";
+
+// Domain separator of the transcript used by IPA to derive the challenges of its rounds when the
+// evaluation point is given externally.
+pub const IPA_DOMAIN: &[u8] = b"sonobe/ipa";
+// Same as `IPA_DOMAIN`, for the HyperKZG openings at an externally given point.
+pub const HYPERKZG_DOMAIN: &[u8] = b"sonobe/hyperkzg";
+// Same as `IPA_DOMAIN`, for the Hyrax openings at an externally given point.
+pub const HYRAX_DOMAIN: &[u8] = b"sonobe/hyrax";
+// Same as `IPA_DOMAIN`, for the PedersenMLE openings at an externally given point.
+pub const PEDERSEN_MLE_DOMAIN: &[u8] = b"sonobe/pedersen-mle";
+
+// Domain separator hashed to the curve to derive the randomness generator `γ⋅G` of the KZG
+// parameters loaded from Powers of Tau ceremonies, which do not provide one.
+pub const KZG_GAMMA_G_DOMAIN: &[u8] = b"sonobe/kzg/gamma_g";
@@ -0,0 +1,181 @@
+//! Checkpoints of the prover state of the folding schemes, which allow long-running provers to
+//! resume from disk instead of restarting from the first step.
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::rand::RngCore;
+use std::{
+    ffi::OsString,
+    fs::{self, File},
+    io::{BufReader, BufWriter, Write},
+    path::{Path, PathBuf},
+};
+
+use crate::frontend::FCircuit;
+use crate::{Curve, Error, FoldingScheme};
+
+/// Checkpoint trait, implemented by the folding schemes whose whole prover state (running and
+/// incoming instances and witnesses, CycleFold instances and step counter) is contained in their
+/// `IVCProof`. A checkpoint file stores the `IVCProof` together with the `pp_hash` of the params
+/// it was generated with, so that it can not be resumed with different params.
+pub trait Checkpoint<
+    C1: Curve<BaseField = C2::ScalarField, ScalarField = C2::BaseField>,
+    C2: Curve,
+    FC: FCircuit<C1::ScalarField>,
+>: FoldingScheme<C1, C2, FC>
+{
+    /// returns the hash of the public params that the prover state is bound to
+    fn pp_hash(&self) -> C1::ScalarField;
+
+    /// Writes the current prover state to `path`. The state is first written to a temporary file
+    /// next to `path`, which then replaces `path`, so that a crash while writing never leaves a
+    /// partially written checkpoint behind.
+    fn checkpoint(&self, path: impl AsRef<Path>) -> Result<(), Error> {
+        let path = path.as_ref();
+        let tmp_path = tmp_path(path);
+
+        let file = File::create(&tmp_path)?;
+        let mut writer = BufWriter::new(&file);
+        self.pp_hash().serialize_uncompressed(&mut writer)?;
+        self.ivc_proof().serialize_uncompressed(&mut writer)?;
+        writer.flush()?;
+        drop(writer);
+        file.sync_all()?;
+
+        fs::rename(&tmp_path, path)?;
+        Ok(())
+    }
+
+    /// Resumes the prover from the checkpoint at `path`, which must have been generated with the
+    /// given params.
+    fn resume(
+        path: impl AsRef<Path>,
+        fcircuit_params: FC::Params,
+        params: (Self::ProverParam, Self::VerifierParam),
+    ) -> Result<Self, Error> {
+        let mut reader = BufReader::new(File::open(path)?);
+        let pp_hash = C1::ScalarField::deserialize_uncompressed(&mut reader)?;
+        let ivc_proof = Self::IVCProof::deserialize_uncompressed(&mut reader)?;
+
+        let folding_scheme = Self::from_ivc_proof(ivc_proof, fcircuit_params, params)?;
+        if folding_scheme.pp_hash() != pp_hash {
+            return Err(Error::PPHashMismatch);
+        }
+        Ok(folding_scheme)
+    }
+
+    /// Performs a step for each of the given external inputs, writing a checkpoint to `path`
+    /// every `interval` steps and after the last one.
+    fn prove_steps_with_checkpoints(
+        &mut self,
+        mut rng: impl RngCore,
+        external_inputs: impl IntoIterator<Item = FC::ExternalInputs>,
+        path: impl AsRef<Path>,
+        interval: usize,
+    ) -> Result<(), Error> {
+        if interval == 0 {
+            return Err(Error::CantBeZero("checkpoint interval".to_string()));
+        }
+        let mut n_steps = 0;
+        for external_inputs_at_step in external_inputs {
+            self.prove_step(&mut rng, external_inputs_at_step, None)?;
+            n_steps += 1;
+            if n_steps % interval == 0 {
+                self.checkpoint(&path)?;
+            }
+        }
+        if n_steps % interval != 0 {
+            self.checkpoint(&path)?;
+        }
+        Ok(())
+    }
+}
+
+/// returns the path of the temporary file in which a checkpoint is written before being moved to
+/// `path`
+fn tmp_path(path: &Path) -> PathBuf {
+    let mut tmp_path = OsString::from(path.as_os_str());
+    tmp_path.push(".tmp");
+    tmp_path.into()
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_pallas::{Fr, Projective as G1};
+    use ark_vesta::Projective as G2;
+
+    use super::*;
+    use crate::commitment::pedersen::Pedersen;
+    use crate::folding::{
+        hypernova::HyperNova,
+        nova::{Nova, PreprocessorParam as NovaPreprocessorParam},
+        protogalaxy::ProtoGalaxy,
+    };
+    use crate::frontend::utils::CubicFCircuit;
+    use crate::transcript::poseidon::poseidon_canonical_config;
+
+    #[test]
+    fn test_checkpoint_nova_hypernova_protogalaxy() -> Result<(), Error> {
+        let poseidon_config = poseidon_canonical_config::<Fr>();
+        type FC = CubicFCircuit<Fr>;
+        let f_circuit = FC::new(())?;
+
+        type N = Nova<G1, G2, FC, Pedersen<G1>, Pedersen<G2>, false>;
+        let prep_param = NovaPreprocessorParam::new(poseidon_config.clone(), f_circuit);
+        test_checkpoint_opt::<G1, G2, FC, N>("nova", prep_param.clone())?;
+
+        type HN = HyperNova<G1, G2, FC, Pedersen<G1>, Pedersen<G2>, 1, 1, false>;
+        test_checkpoint_opt::<G1, G2, FC, HN>("hypernova", prep_param)?;
+
+        type P = ProtoGalaxy<G1, G2, FC, Pedersen<G1>, Pedersen<G2>, 1>;
+        let prep_param = NovaPreprocessorParam::new(poseidon_config, f_circuit);
+        test_checkpoint_opt::<G1, G2, FC, P>("protogalaxy", prep_param)?;
+        Ok(())
+    }
+
+    fn test_checkpoint_opt<
+        C1: Curve<BaseField = C2::ScalarField, ScalarField = C2::BaseField>,
+        C2: Curve,
+        FC: FCircuit<C1::ScalarField, Params = ()>,
+        FS: Checkpoint<C1, C2, FC>,
+    >(
+        name: &str,
+        prep_param: FS::PreprocessorParam,
+    ) -> Result<(), Error> {
+        let mut rng = ark_std::test_rng();
+        let path = std::env::temp_dir().join(format!("sonobe-checkpoint-{}", name));
+
+        let fs_params = FS::preprocess(&mut rng, &prep_param)?;
+        let z_0 = vec![C1::ScalarField::from(3_u32)];
+        let mut fs = FS::init(&fs_params, FC::new(())?, z_0)?;
+
+        // 5 steps with an interval of 2, checkpointing after the 2nd, 4th and 5th steps
+        let num_steps = 5;
+        fs.prove_steps_with_checkpoints(
+            &mut rng,
+            vec![FC::ExternalInputs::default(); num_steps],
+            &path,
+            2,
+        )?;
+        assert!(!tmp_path(&path).exists());
+
+        let mut resumed_fs = FS::resume(&path, (), fs_params.clone())?;
+        assert_eq!(resumed_fs.ivc_proof(), fs.ivc_proof());
+
+        // both provers keep folding the same steps
+        for _ in 0..2 {
+            resumed_fs.prove_step(&mut rng, FC::ExternalInputs::default(), None)?;
+            fs.prove_step(&mut rng, FC::ExternalInputs::default(), None)?;
+        }
+        assert_eq!(resumed_fs.ivc_proof(), fs.ivc_proof());
+        FS::verify(fs_params.1.clone(), resumed_fs.ivc_proof())?;
+
+        // the checkpoint can not be resumed with different params
+        let other_fs_params = FS::preprocess(&mut rng, &prep_param)?;
+        assert!(matches!(
+            FS::resume(&path, (), other_fs_params),
+            Err(Error::PPHashMismatch)
+        ));
+
+        std::fs::remove_file(&path)?;
+        Ok(())
+    }
+}
@@ -26,7 +26,7 @@ use crate::arith::{
     Arith, ArithRelationGadget,
 };
 use crate::commitment::CommitmentScheme;
-use crate::constants::NOVA_N_BITS_RO;
+use crate::constants::{CYCLEFOLD_NIFS_DOMAIN, NOVA_N_BITS_RO};
 use crate::folding::{
     nova::nifs::{nova::NIFS, NIFSTrait},
     traits::InputizeNonNative,
@@ -238,6 +238,43 @@ impl<C: Curve> NIFSFullGadget<C> {
         })
     }
 
+    /// Folds two running (relaxed) CycleFold instances, ie. it does not assume that `ci2` is a
+    /// fresh instance, and computes `cmE = ci1.cmE + r * cmT + r^2 * ci2.cmE` and
+    /// `u = ci1.u + r * ci2.u`. This is used when merging the CycleFold running instances of two
+    /// independent proofs, as in [`crate::folding::nova::pcd`].
+    pub fn fold_running_instances(
+        r_bits: Vec<Boolean<CF2<C>>>,
+        cmT: C::Var,
+        ci1: CycleFoldCommittedInstanceVar<C>,
+        ci2: CycleFoldCommittedInstanceVar<C>,
+    ) -> Result<CycleFoldCommittedInstanceVar<C>, SynthesisError> {
+        // r_nonnat is equal to r_bits just that in a different format
+        let r_nonnat = {
+            let mut bits = r_bits.clone();
+            bits.resize(CF1::<C>::MODULUS_BIT_SIZE as usize, Boolean::FALSE);
+            NonNativeUintVar::from(&bits)
+        };
+        Ok(CycleFoldCommittedInstanceVar {
+            // cmE = (ci2.cmE * r + cmT) * r + ci1.cmE
+            cmE: (ci2.cmE.scalar_mul_le(r_bits.iter())? + cmT).scalar_mul_le(r_bits.iter())?
+                + ci1.cmE,
+            cmW: ci1.cmW + ci2.cmW.scalar_mul_le(r_bits.iter())?,
+            u: ci1
+                .u
+                .add_no_align(&r_nonnat.mul_no_align(&ci2.u)?)?
+                .modulo::<CF1<C>>()?,
+            x: ci1
+                .x
+                .iter()
+                .zip(ci2.x)
+                .map(|(a, b)| {
+                    a.add_no_align(&r_nonnat.mul_no_align(&b)?)?
+                        .modulo::<CF1<C>>()
+                })
+                .collect::<Result<Vec<_>, _>>()?,
+        })
+    }
+
     pub fn verify(
         // assumes that r_bits is equal to r_nonnat just that in a different format
         r_bits: Vec<Boolean<CF2<C>>>,
@@ -295,10 +332,15 @@ impl<C: Curve> CycleFoldChallengeGadget<C> {
         u_i: CycleFoldCommittedInstance<C>,
         cmT: C,
     ) -> Vec<bool> {
-        transcript.absorb(&pp_hash);
+        transcript.domain_separator(CYCLEFOLD_NIFS_DOMAIN);
+        transcript.absorb_labelled(b"pp_hash", &pp_hash);
+        transcript.absorb_label(b"U_i");
         transcript.absorb_nonnative(&U_i);
+        transcript.absorb_label(b"u_i");
         transcript.absorb_nonnative(&u_i);
+        transcript.absorb_label(b"cmT");
         transcript.absorb_point(&cmT);
+        transcript.absorb_label(b"r");
         transcript.squeeze_bits(NOVA_N_BITS_RO)
     }
 
@@ -310,10 +352,14 @@ impl<C: Curve> CycleFoldChallengeGadget<C> {
         u_i: CycleFoldCommittedInstanceVar<C>,
         cmT: C::Var,
     ) -> Result<Vec<Boolean<C::BaseField>>, SynthesisError> {
-        transcript.absorb(&pp_hash)?;
-        transcript.absorb(&U_i_vec)?;
+        transcript.domain_separator(CYCLEFOLD_NIFS_DOMAIN)?;
+        transcript.absorb_labelled(b"pp_hash", &pp_hash)?;
+        transcript.absorb_labelled(b"U_i", &U_i_vec)?;
+        transcript.absorb_label(b"u_i")?;
         transcript.absorb_nonnative(&u_i)?;
+        transcript.absorb_label(b"cmT")?;
         transcript.absorb_point(&cmT)?;
+        transcript.absorb_label(b"r")?;
         transcript.squeeze_bits(NOVA_N_BITS_RO)
     }
 }
@@ -576,6 +622,7 @@ pub mod tests {
     };
     use ark_r1cs_std::R1CSVar;
     use ark_std::{One, UniformRand};
+    use num_bigint::BigUint;
 
     use super::*;
     use crate::commitment::pedersen::Pedersen;
@@ -689,6 +736,57 @@ pub mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_nifs_full_gadget_running_instances() -> Result<(), Error> {
+        let mut rng = ark_std::test_rng();
+
+        let poseidon_config = poseidon_canonical_config::<Fr>();
+        let mut transcript_v = PoseidonSponge::<Fr>::new(&poseidon_config);
+        let pp_hash = Fr::rand(&mut rng);
+
+        // both instances are running instances (ie. cmE!=0, u!=1)
+        let ci: Vec<CommittedInstance<Projective>> = (0..2)
+            .map(|_| CommittedInstance::<Projective> {
+                cmE: Projective::rand(&mut rng),
+                u: Fr::rand(&mut rng),
+                cmW: Projective::rand(&mut rng),
+                x: vec![Fr::rand(&mut rng); 2],
+            })
+            .collect();
+
+        let cmT = Projective::rand(&mut rng); // random only for testing
+        let (ci3, r_bits) = NIFS::<Projective, Pedersen<Projective>, PoseidonSponge<Fr>>::verify(
+            &mut transcript_v,
+            pp_hash,
+            &ci[0],
+            &ci[1],
+            &cmT,
+        )?;
+
+        let cs = ConstraintSystem::<Fq>::new_ref();
+        let r_bitsVar = Vec::<Boolean<Fq>>::new_witness(cs.clone(), || Ok(r_bits))?;
+        let ci1Var = CycleFoldCommittedInstanceVar::<Projective>::new_witness(cs.clone(), || {
+            Ok(ci[0].clone())
+        })?;
+        let ci2Var = CycleFoldCommittedInstanceVar::<Projective>::new_witness(cs.clone(), || {
+            Ok(ci[1].clone())
+        })?;
+        let cmTVar = GVar::new_witness(cs.clone(), || Ok(cmT))?;
+
+        let ci3Var = NIFSFullGadget::<Projective>::fold_running_instances(
+            r_bitsVar, cmTVar, ci1Var, ci2Var,
+        )?;
+        assert!(cs.is_satisfied()?);
+        assert_eq!(ci3Var.cmE.value()?, ci3.cmE);
+        assert_eq!(ci3Var.cmW.value()?, ci3.cmW);
+        assert_eq!(ci3Var.u.value()?, BigUint::from(ci3.u));
+        assert_eq!(
+            ci3Var.x.value()?,
+            ci3.x.iter().map(|x| BigUint::from(*x)).collect::<Vec<_>>()
+        );
+        Ok(())
+    }
+
     #[test]
     fn test_cyclefold_challenge_gadget() -> Result<(), Error> {
         let mut rng = ark_std::test_rng();
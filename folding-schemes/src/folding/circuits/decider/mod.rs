@@ -1,6 +1,4 @@
-use ark_crypto_primitives::sponge::{
-    poseidon::constraints::PoseidonSpongeVar, CryptographicSponge,
-};
+use ark_crypto_primitives::sponge::CryptographicSponge;
 use ark_ff::PrimeField;
 use ark_poly::Polynomial;
 use ark_r1cs_std::{
@@ -12,7 +10,7 @@ use ark_std::log2;
 
 use crate::folding::traits::{CommittedInstanceOps, CommittedInstanceVarOps, Dummy, WitnessOps};
 use crate::transcript::{Transcript, TranscriptVar};
-use crate::utils::vec::poly_from_vec;
+use crate::utils::{gadgets::eval_mle, mle::dense_vec_to_dense_mle, vec::poly_from_vec};
 use crate::{arith::ArithRelation, folding::circuits::CF1};
 use crate::{Curve, Error};
 
@@ -52,6 +50,170 @@ impl KZGChallengesGadget {
         }
         Ok(challenges)
     }
+
+    /// Same as `get_challenges_native`, but for the commitment schemes whose challenges are of
+    /// type `P`, where `lens` are the lengths of the vectors committed in `U_i`.
+    pub fn get_opening_challenges_native<
+        C: Curve,
+        T: Transcript<CF1<C>>,
+        U: CommittedInstanceOps<C>,
+        P: DeciderChallenge<CF1<C>>,
+    >(
+        transcript: &mut T,
+        U_i: &U,
+        lens: &[usize],
+    ) -> Vec<P> {
+        let mut challenges = vec![];
+        for (cm, len) in U_i.get_commitments().iter().zip(lens) {
+            transcript.absorb_nonnative(cm);
+            challenges.push(P::get_challenge_native(transcript, *len));
+        }
+        challenges
+    }
+
+    /// Same as `get_challenges_gadget`, but for the commitment schemes whose challenges are of
+    /// type `P`, where `lens` are the lengths of the vectors committed in `U_i`.
+    pub fn get_opening_challenges_gadget<
+        C: Curve,
+        S: CryptographicSponge,
+        T: TranscriptVar<CF1<C>, S>,
+        U: CommittedInstanceVarOps<C>,
+        P: DeciderChallenge<CF1<C>>,
+    >(
+        transcript: &mut T,
+        U_i: &U,
+        lens: &[usize],
+    ) -> Result<Vec<Vec<FpVar<CF1<C>>>>, SynthesisError> {
+        let mut challenges = vec![];
+        for (cm, len) in U_i.get_commitments().iter().zip(lens) {
+            transcript.absorb_nonnative(cm)?;
+            challenges.push(P::get_challenge_gadget(transcript, *len)?);
+        }
+        Ok(challenges)
+    }
+}
+
+/// DeciderChallenge is implemented by the challenges at which the offchain deciders open the
+/// committed vectors, i.e. by the `CommitmentScheme::Challenge` of the schemes that they support:
+/// - a field element for the univariate schemes (`KZG`, `IPA`), at which the vector is evaluated
+///   as a polynomial over the radix-2 domain (see `EvalGadget`),
+/// - a point for the multilinear schemes (`HyperKZG`), at which the multilinear extension of the
+///   vector is evaluated (see `utils::gadgets::eval_mle`).
+pub trait DeciderChallenge<F: PrimeField>: Clone + Sized {
+    /// Returns the number of field elements of the challenge for a vector of length `len`.
+    fn n_elements(len: usize) -> usize;
+
+    /// Returns a dummy challenge for a vector of length `len`.
+    fn dummy(len: usize) -> Self;
+
+    /// Builds the challenge from its field elements.
+    fn from_elements(elements: Vec<F>) -> Result<Self, Error>;
+
+    /// Returns the field elements of the challenge.
+    fn to_elements(&self) -> Vec<F>;
+
+    /// Squeezes the challenge for a vector of length `len` from the transcript.
+    fn get_challenge_native<T: Transcript<F>>(transcript: &mut T, len: usize) -> Self;
+
+    /// In-circuit counterpart of `get_challenge_native`, which returns the field elements of the
+    /// challenge.
+    fn get_challenge_gadget<S: CryptographicSponge, T: TranscriptVar<F, S>>(
+        transcript: &mut T,
+        len: usize,
+    ) -> Result<Vec<FpVar<F>>, SynthesisError>;
+
+    /// Evaluates `v` at the challenge.
+    fn evaluate_native(&self, v: &[F]) -> Result<F, Error>;
+
+    /// Evaluates `v` at the challenge given by its field elements in-circuit.
+    fn evaluate_gadget(v: &[FpVar<F>], challenge: &[FpVar<F>]) -> Result<FpVar<F>, SynthesisError>;
+}
+
+impl<F: PrimeField> DeciderChallenge<F> for F {
+    fn n_elements(_len: usize) -> usize {
+        1
+    }
+
+    fn dummy(_len: usize) -> Self {
+        F::zero()
+    }
+
+    fn from_elements(elements: Vec<F>) -> Result<Self, Error> {
+        match elements[..] {
+            [c] => Ok(c),
+            _ => Err(Error::NotExpectedLength(elements.len(), 1)),
+        }
+    }
+
+    fn to_elements(&self) -> Vec<F> {
+        vec![*self]
+    }
+
+    fn get_challenge_native<T: Transcript<F>>(transcript: &mut T, _len: usize) -> Self {
+        transcript.get_challenge()
+    }
+
+    fn get_challenge_gadget<S: CryptographicSponge, T: TranscriptVar<F, S>>(
+        transcript: &mut T,
+        _len: usize,
+    ) -> Result<Vec<FpVar<F>>, SynthesisError> {
+        Ok(vec![transcript.get_challenge()?])
+    }
+
+    fn evaluate_native(&self, v: &[F]) -> Result<F, Error> {
+        EvalGadget::evaluate_native(v, *self)
+    }
+
+    fn evaluate_gadget(v: &[FpVar<F>], challenge: &[FpVar<F>]) -> Result<FpVar<F>, SynthesisError> {
+        match challenge {
+            [c] => EvalGadget::evaluate_gadget(v, c),
+            _ => Err(SynthesisError::Unsatisfiable),
+        }
+    }
+}
+
+impl<F: PrimeField> DeciderChallenge<F> for Vec<F> {
+    /// The multilinear extension of a vector of length `len` has `log2(len)` variables, rounded up.
+    fn n_elements(len: usize) -> usize {
+        len.next_power_of_two().trailing_zeros() as usize
+    }
+
+    fn dummy(len: usize) -> Self {
+        vec![F::zero(); Self::n_elements(len)]
+    }
+
+    fn from_elements(elements: Vec<F>) -> Result<Self, Error> {
+        Ok(elements)
+    }
+
+    fn to_elements(&self) -> Vec<F> {
+        self.clone()
+    }
+
+    fn get_challenge_native<T: Transcript<F>>(transcript: &mut T, len: usize) -> Self {
+        transcript.get_challenges(Self::n_elements(len))
+    }
+
+    fn get_challenge_gadget<S: CryptographicSponge, T: TranscriptVar<F, S>>(
+        transcript: &mut T,
+        len: usize,
+    ) -> Result<Vec<FpVar<F>>, SynthesisError> {
+        transcript.get_challenges(Self::n_elements(len))
+    }
+
+    fn evaluate_native(&self, v: &[F]) -> Result<F, Error> {
+        if v.len() > 1 << self.len() {
+            return Err(Error::NotExpectedLength(v.len(), 1 << self.len()));
+        }
+        Ok(dense_vec_to_dense_mle(self.len(), v).evaluate(self))
+    }
+
+    fn evaluate_gadget(v: &[FpVar<F>], challenge: &[FpVar<F>]) -> Result<FpVar<F>, SynthesisError> {
+        if v.len() > 1 << challenge.len() {
+            return Err(SynthesisError::Unsatisfiable);
+        }
+        Ok(eval_mle(challenge.len(), v.to_vec(), challenge.to_vec()))
+    }
 }
 
 /// Gadget that interpolates the polynomial from the given vector and returns
@@ -112,9 +274,9 @@ pub trait DeciderEnabledNIFS<
     /// `U_vec` is `U` expressed as a vector of `FpVar`s, which can be reused
     /// before or after calling this function to save constraints.
     #[allow(clippy::too_many_arguments)]
-    fn fold_field_elements_gadget(
+    fn fold_field_elements_gadget<S: CryptographicSponge, T: TranscriptVar<CF1<C>, S>>(
         arith: &A,
-        transcript: &mut PoseidonSpongeVar<CF1<C>>,
+        transcript: &mut T,
         pp_hash: FpVar<CF1<C>>,
         U: RU::Var,
         U_vec: Vec<FpVar<CF1<C>>>,
@@ -136,7 +298,8 @@ pub trait DeciderEnabledNIFS<
 #[cfg(test)]
 pub mod tests {
     use ark_crypto_primitives::sponge::{
-        constraints::CryptographicSpongeVar, poseidon::PoseidonSponge,
+        constraints::CryptographicSpongeVar,
+        poseidon::{constraints::PoseidonSpongeVar, PoseidonSponge},
     };
     use ark_pallas::{Fr, Projective};
     use ark_r1cs_std::{alloc::AllocVar, R1CSVar};
@@ -203,4 +366,47 @@ pub mod tests {
         assert!(cs.is_satisfied()?);
         Ok(())
     }
+
+    // checks that the gadget and native implementations of the MLE evaluation at a multilinear
+    // challenge match, and that they match the evaluation opened by HyperKZG
+    #[test]
+    fn test_mle_challenge_evaluation() -> Result<(), Error> {
+        use crate::commitment::{hyperkzg::HyperKZG, CommitmentScheme, EvaluationProof};
+        use ark_bn254::{Bn254, Fr};
+        use ark_std::Zero;
+
+        let mut rng = ark_std::test_rng();
+        let poseidon_config = poseidon_canonical_config::<Fr>();
+
+        for n in [1, 5, 16] {
+            let v: Vec<Fr> = std::iter::repeat_with(|| Fr::rand(&mut rng))
+                .take(n)
+                .collect();
+            let mut transcript = PoseidonSponge::<Fr>::new(&poseidon_config);
+            let challenge = Vec::<Fr>::get_challenge_native(&mut transcript, n);
+            assert_eq!(challenge.len(), Vec::<Fr>::n_elements(n));
+            let eval = challenge.evaluate_native(&v)?;
+
+            let (pk, _) = HyperKZG::<Bn254>::setup(&mut rng, n)?;
+            let proof = HyperKZG::<Bn254>::prove_with_challenge(
+                &pk,
+                challenge.clone(),
+                &v,
+                &Fr::zero(),
+                None,
+            )?;
+            assert_eq!(proof.evaluation(), eval);
+
+            let cs = ConstraintSystem::<Fr>::new_ref();
+            let vVar = Vec::<FpVar<Fr>>::new_witness(cs.clone(), || Ok(v))?;
+            let mut transcript_var = PoseidonSpongeVar::<Fr>::new(cs.clone(), &poseidon_config);
+            let challengeVar = Vec::<Fr>::get_challenge_gadget(&mut transcript_var, n)?;
+            assert_eq!(challengeVar.value()?, challenge);
+
+            let evalVar = Vec::<Fr>::evaluate_gadget(&vVar, &challengeVar)?;
+            assert_eq!(evalVar.value()?, eval);
+            assert!(cs.is_satisfied()?);
+        }
+        Ok(())
+    }
 }
@@ -4,7 +4,7 @@
 /// https://privacy-scaling-explorations.github.io/sonobe-docs/design/nova-decider-offchain.html
 use ark_crypto_primitives::sponge::{
     constraints::{AbsorbGadget, CryptographicSpongeVar},
-    poseidon::{constraints::PoseidonSpongeVar, PoseidonConfig},
+    poseidon::{constraints::PoseidonSpongeVar, PoseidonConfig, PoseidonSponge},
 };
 use ark_r1cs_std::{alloc::AllocVar, eq::EqGadget, fields::fp::FpVar};
 use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystemRef, SynthesisError};
@@ -20,20 +20,23 @@ use crate::{
             cyclefold::{
                 CycleFoldCommittedInstance, CycleFoldCommittedInstanceVar, CycleFoldWitness,
             },
-            decider::{EvalGadget, KZGChallengesGadget},
+            decider::{DeciderChallenge, KZGChallengesGadget},
             nonnative::affine::NonNativeAffineVar,
             CF1, CF2,
         },
         nova::{decider_eth_circuit::WitnessVar, nifs::nova_circuits::CommittedInstanceVar},
         traits::{CommittedInstanceOps, CommittedInstanceVarOps, Dummy, WitnessOps, WitnessVarOps},
     },
+    transcript::SpongeWithGadget,
     Curve,
 };
 
 use super::DeciderEnabledNIFS;
 
 /// Circuit that implements part of the in-circuit checks needed for the offchain verification over
-/// the Curve2's BaseField (=Curve1's ScalarField).
+/// the Curve2's BaseField (=Curve1's ScalarField). `P` is the challenge at which the commitments of
+/// `U_{i+1}` are opened (see `DeciderChallenge`), and `SP` is the sponge of the folding scheme, with
+/// which the instances are hashed and folded.
 pub struct GenericOffchainDeciderCircuit1<
     C1: Curve,
     C2: Curve,
@@ -43,11 +46,14 @@ pub struct GenericOffchainDeciderCircuit1<
     A: ArithRelation<W, RU>,                    // Constraint system
     AVar: ArithRelationGadget<W::Var, RU::Var>, // In-circuit representation of `A`
     D: DeciderEnabledNIFS<C1, RU, IU, W, A>,
+    P: DeciderChallenge<CF1<C1>> = CF1<C1>,
+    SP: SpongeWithGadget<CF1<C1>> = PoseidonSponge<CF1<C1>>,
 > {
     pub _avar: PhantomData<AVar>,
     /// Constraint system of the Augmented Function circuit
     pub arith: A,
-    pub poseidon_config: PoseidonConfig<CF1<C1>>,
+    /// Configuration of the sponge `SP`
+    pub sponge_config: SP::Config,
     /// public params hash
     pub pp_hash: CF1<C1>,
     pub i: CF1<C1>,
@@ -71,7 +77,7 @@ pub struct GenericOffchainDeciderCircuit1<
     pub cf_U_i: CycleFoldCommittedInstance<C2>,
 
     /// KZG challenges
-    pub kzg_challenges: Vec<CF1<C1>>,
+    pub kzg_challenges: Vec<P>,
     pub kzg_evaluations: Vec<CF1<C1>>,
 }
 
@@ -84,22 +90,24 @@ impl<
         A: ArithRelation<W, RU>,
         AVar: ArithRelationGadget<W::Var, RU::Var> + AllocVar<A, CF1<C1>>,
         D: DeciderEnabledNIFS<C1, RU, IU, W, A>,
+        P: DeciderChallenge<CF1<C1>>,
+        SP: SpongeWithGadget<CF1<C1>>,
     >
     Dummy<(
         A,
         &R1CS<CF1<C2>>,
-        PoseidonConfig<CF1<C1>>,
+        SP::Config,
         D::ProofDummyCfg,
         D::RandomnessDummyCfg,
         usize,
         usize,
-    )> for GenericOffchainDeciderCircuit1<C1, C2, RU, IU, W, A, AVar, D>
+    )> for GenericOffchainDeciderCircuit1<C1, C2, RU, IU, W, A, AVar, D, P, SP>
 {
     fn dummy(
         (
             arith,
             cf_arith,
-            poseidon_config,
+            sponge_config,
             proof_config,
             randomness_config,
             state_len,
@@ -107,16 +115,22 @@ impl<
         ): (
             A,
             &R1CS<CF1<C2>>,
-            PoseidonConfig<CF1<C1>>,
+            SP::Config,
             D::ProofDummyCfg,
             D::RandomnessDummyCfg,
             usize,
             usize,
         ),
     ) -> Self {
+        let W_i1 = W::dummy(&arith);
+        let kzg_challenges = W_i1
+            .get_openings()
+            .iter()
+            .map(|(v, _)| P::dummy(v.len()))
+            .collect();
         Self {
             _avar: PhantomData,
-            poseidon_config,
+            sponge_config,
             pp_hash: Zero::zero(),
             i: Zero::zero(),
             z_0: vec![Zero::zero(); state_len],
@@ -126,11 +140,11 @@ impl<
             u_i: IU::dummy(&arith),
             w_i: W::dummy(&arith),
             U_i1: RU::dummy(&arith),
-            W_i1: W::dummy(&arith),
+            W_i1,
             proof: D::Proof::dummy(proof_config),
             randomness: D::Randomness::dummy(randomness_config),
             cf_U_i: CycleFoldCommittedInstance::dummy(cf_arith),
-            kzg_challenges: vec![Zero::zero(); num_commitments],
+            kzg_challenges,
             kzg_evaluations: vec![Zero::zero(); num_commitments],
             arith,
         }
@@ -146,8 +160,10 @@ impl<
         A: ArithRelation<W, RU>,
         AVar: ArithRelationGadget<W::Var, RU::Var> + AllocVar<A, CF1<C1>>,
         D: DeciderEnabledNIFS<C1, RU, IU, W, A>,
+        P: DeciderChallenge<CF1<C1>>,
+        SP: SpongeWithGadget<CF1<C1>>,
     > ConstraintSynthesizer<CF1<C1>>
-    for GenericOffchainDeciderCircuit1<C1, C2, RU, IU, W, A, AVar, D>
+    for GenericOffchainDeciderCircuit1<C1, C2, RU, IU, W, A, AVar, D, P, SP>
 where
     RU::Var: AbsorbGadget<CF1<C1>> + CommittedInstanceVarOps<C1, PointVar = NonNativeAffineVar<C1>>,
 {
@@ -173,11 +189,17 @@ where
             CycleFoldCommittedInstanceVar::<C2>::new_input(cs.clone(), || Ok(self.cf_U_i))?;
 
         // allocate the inputs for the checks 7.1 and 7.2
-        let kzg_challenges = Vec::new_input(cs.clone(), || Ok(self.kzg_challenges))?;
+        let kzg_challenges = Vec::new_input(cs.clone(), || {
+            Ok(self
+                .kzg_challenges
+                .iter()
+                .flat_map(P::to_elements)
+                .collect::<Vec<_>>())
+        })?;
         let kzg_evaluations = Vec::new_input(cs.clone(), || Ok(self.kzg_evaluations))?;
 
         // `sponge` is for digest computation.
-        let sponge = PoseidonSpongeVar::new(cs.clone(), &self.poseidon_config);
+        let sponge = SP::Var::new(cs.clone(), &self.sponge_config);
         // `transcript` is for challenge generation.
         let mut transcript = sponge.clone();
         // notice that the `pp_hash` is absorbed inside the ChallengeGadget::get_challenge_gadget call
@@ -207,18 +229,19 @@ where
         .enforce_partial_equal(&U_i1)?;
 
         // 7.1. compute and check KZG challenges
-        KZGChallengesGadget::get_challenges_gadget(&mut transcript, &U_i1)?
-            .enforce_equal(&kzg_challenges)?;
+        let openings = W_i1.get_openings();
+        let lens = openings.iter().map(|(v, _)| v.len()).collect::<Vec<_>>();
+        let challenges = KZGChallengesGadget::get_opening_challenges_gadget::<C1, _, _, _, P>(
+            &mut transcript,
+            &U_i1,
+            &lens,
+        )?;
+        challenges.concat().enforce_equal(&kzg_challenges)?;
 
         // 7.2. check the claimed evaluations
-        for (((v, _r), c), e) in W_i1
-            .get_openings()
-            .iter()
-            .zip(&kzg_challenges)
-            .zip(&kzg_evaluations)
-        {
+        for (((v, _r), c), e) in openings.iter().zip(&challenges).zip(&kzg_evaluations) {
             // The randomness `_r` is currently not used.
-            EvalGadget::evaluate_gadget(v, c)?.enforce_equal(e)?;
+            P::evaluate_gadget(v, c)?.enforce_equal(e)?;
         }
 
         Ok(())
@@ -226,8 +249,9 @@ where
 }
 
 /// Circuit that implements part of the in-circuit checks needed for the offchain verification over
-/// the Curve1's BaseField (=Curve2's ScalarField).
-pub struct GenericOffchainDeciderCircuit2<C2: Curve> {
+/// the Curve1's BaseField (=Curve2's ScalarField). `P` is the challenge at which the commitments of
+/// `cf_U_i` are opened (see `DeciderChallenge`).
+pub struct GenericOffchainDeciderCircuit2<C2: Curve, P: DeciderChallenge<CF1<C2>> = CF1<C2>> {
     /// R1CS of the CycleFold circuit
     pub cf_arith: R1CS<CF1<C2>>,
     pub poseidon_config: PoseidonConfig<CF1<C2>>,
@@ -239,12 +263,12 @@ pub struct GenericOffchainDeciderCircuit2<C2: Curve> {
     pub cf_W_i: CycleFoldWitness<C2>,
 
     /// KZG challenges
-    pub kzg_challenges: Vec<CF1<C2>>,
+    pub kzg_challenges: Vec<P>,
     pub kzg_evaluations: Vec<CF1<C2>>,
 }
 
-impl<C2: Curve> Dummy<(R1CS<CF1<C2>>, PoseidonConfig<CF1<C2>>, usize)>
-    for GenericOffchainDeciderCircuit2<C2>
+impl<C2: Curve, P: DeciderChallenge<CF1<C2>>> Dummy<(R1CS<CF1<C2>>, PoseidonConfig<CF1<C2>>, usize)>
+    for GenericOffchainDeciderCircuit2<C2, P>
 {
     fn dummy(
         (cf_arith, poseidon_config, num_commitments): (
@@ -253,19 +277,27 @@ impl<C2: Curve> Dummy<(R1CS<CF1<C2>>, PoseidonConfig<CF1<C2>>, usize)>
             usize,
         ),
     ) -> Self {
+        let cf_W_i = CycleFoldWitness::<C2>::dummy(&cf_arith);
+        let kzg_challenges = cf_W_i
+            .get_openings()
+            .iter()
+            .map(|(v, _)| P::dummy(v.len()))
+            .collect();
         Self {
             poseidon_config,
             pp_hash: Zero::zero(),
             cf_U_i: CycleFoldCommittedInstance::dummy(&cf_arith),
-            cf_W_i: CycleFoldWitness::dummy(&cf_arith),
-            kzg_challenges: vec![Zero::zero(); num_commitments],
+            cf_W_i,
+            kzg_challenges,
             kzg_evaluations: vec![Zero::zero(); num_commitments],
             cf_arith,
         }
     }
 }
 
-impl<C2: Curve> ConstraintSynthesizer<CF1<C2>> for GenericOffchainDeciderCircuit2<C2> {
+impl<C2: Curve, P: DeciderChallenge<CF1<C2>>> ConstraintSynthesizer<CF1<C2>>
+    for GenericOffchainDeciderCircuit2<C2, P>
+{
     fn generate_constraints(self, cs: ConstraintSystemRef<CF1<C2>>) -> Result<(), SynthesisError> {
         let cf_r1cs = R1CSMatricesVar::<CF1<C2>, FpVar<CF1<C2>>>::new_witness(cs.clone(), || {
             Ok(self.cf_arith.clone())
@@ -277,7 +309,13 @@ impl<C2: Curve> ConstraintSynthesizer<CF1<C2>> for GenericOffchainDeciderCircuit
         let cf_W_i = WitnessVar::new_witness(cs.clone(), || Ok(self.cf_W_i))?;
 
         // allocate the inputs for the checks 4.1 and 4.2
-        let kzg_challenges = Vec::new_input(cs.clone(), || Ok(self.kzg_challenges))?;
+        let kzg_challenges = Vec::new_input(cs.clone(), || {
+            Ok(self
+                .kzg_challenges
+                .iter()
+                .flat_map(P::to_elements)
+                .collect::<Vec<_>>())
+        })?;
         let kzg_evaluations = Vec::new_input(cs.clone(), || Ok(self.kzg_evaluations))?;
 
         // `transcript` is for challenge generation.
@@ -288,18 +326,19 @@ impl<C2: Curve> ConstraintSynthesizer<CF1<C2>> for GenericOffchainDeciderCircuit
         cf_r1cs.enforce_relation(&cf_W_i, &cf_U_i)?;
 
         // 4.1. compute and check KZG challenges
-        KZGChallengesGadget::get_challenges_gadget(&mut transcript, &cf_U_i)?
-            .enforce_equal(&kzg_challenges)?;
+        let openings = cf_W_i.get_openings();
+        let lens = openings.iter().map(|(v, _)| v.len()).collect::<Vec<_>>();
+        let challenges = KZGChallengesGadget::get_opening_challenges_gadget::<C2, _, _, _, P>(
+            &mut transcript,
+            &cf_U_i,
+            &lens,
+        )?;
+        challenges.concat().enforce_equal(&kzg_challenges)?;
 
         // 4.2. check the claimed evaluations
-        for (((v, _r), c), e) in cf_W_i
-            .get_openings()
-            .iter()
-            .zip(&kzg_challenges)
-            .zip(&kzg_evaluations)
-        {
+        for (((v, _r), c), e) in openings.iter().zip(&challenges).zip(&kzg_evaluations) {
             // The randomness `_r` is currently not used.
-            EvalGadget::evaluate_gadget(v, c)?.enforce_equal(e)?;
+            P::evaluate_gadget(v, c)?.enforce_equal(e)?;
         }
 
         Ok(())
@@ -2,8 +2,10 @@
 /// other more efficient approaches can be used.
 use ark_crypto_primitives::sponge::{
     constraints::{AbsorbGadget, CryptographicSpongeVar},
-    poseidon::{constraints::PoseidonSpongeVar, PoseidonConfig},
+    poseidon::PoseidonSponge,
+    Absorb, CryptographicSponge,
 };
+use ark_ff::PrimeField;
 use ark_r1cs_std::{alloc::AllocVar, eq::EqGadget, fields::fp::FpVar};
 use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystemRef, SynthesisError};
 use ark_std::{marker::PhantomData, Zero};
@@ -22,11 +24,55 @@ use crate::{
         },
         traits::{CommittedInstanceOps, CommittedInstanceVarOps, Dummy, WitnessOps, WitnessVarOps},
     },
+    transcript::{
+        keccak::KeccakTranscript, poseidon2::Poseidon2Sponge, SpongeWithGadget, Transcript,
+    },
     Curve,
 };
 
 use super::DeciderEnabledNIFS;
 
+/// `DeciderEthTranscript` is the transcript from which the onchain decider derives the KZG
+/// challenges of the folded instance `U_{i+1}`.
+pub trait DeciderEthTranscript<F: PrimeField>: Transcript<F> {
+    /// Whether the challenges are computed in-circuit. If not, they are public inputs of the
+    /// decider circuit that the verifier (i.e., the Solidity contract) derives by itself from
+    /// `pp_hash` and the commitments of `U_{i+1}`.
+    const IN_CIRCUIT: bool;
+
+    /// Returns the transcript from which the challenges are derived natively, or `None` if they
+    /// continue the transcript of the last fold, which is the case iff `IN_CIRCUIT`.
+    fn kzg_transcript(pp_hash: F) -> Option<Self>;
+}
+
+impl<F: PrimeField + Absorb> DeciderEthTranscript<F> for PoseidonSponge<F> {
+    const IN_CIRCUIT: bool = true;
+
+    fn kzg_transcript(_pp_hash: F) -> Option<Self> {
+        None
+    }
+}
+
+impl<F: PrimeField + Absorb> DeciderEthTranscript<F> for Poseidon2Sponge<F> {
+    const IN_CIRCUIT: bool = true;
+
+    fn kzg_transcript(_pp_hash: F) -> Option<Self> {
+        None
+    }
+}
+
+/// The challenges are derived from a fresh Keccak transcript that absorbs `pp_hash` and then the
+/// commitments of `U_{i+1}`, which is what the Solidity verifier recomputes with `keccak256`.
+impl<F: PrimeField + Absorb> DeciderEthTranscript<F> for KeccakTranscript<F> {
+    const IN_CIRCUIT: bool = false;
+
+    fn kzg_transcript(pp_hash: F) -> Option<Self> {
+        let mut transcript = KeccakTranscript::new(&());
+        transcript.absorb(&pp_hash);
+        Some(transcript)
+    }
+}
+
 /// A generic circuit tailored for the onchain (Ethereum's EVM) verification of
 /// IVC proofs, where we support IVC built upon any folding scheme.
 ///
@@ -57,6 +103,9 @@ use super::DeciderEnabledNIFS;
 /// with the witness `W_{i+1}` and instance `U_{i+1}` in the circuit, but the
 /// actual commitment checks are done with the help of KZG.
 ///
+/// The KZG challenges are derived from the transcript `T`, see [`DeciderEthTranscript`], and `SP`
+/// is the sponge of the folding scheme, with which the instances are hashed and folded.
+///
 /// For more details, see [https://privacy-scaling-explorations.github.io/sonobe-docs/design/nova-decider-onchain.html].
 pub struct GenericOnchainDeciderCircuit<
     C1: Curve,
@@ -67,15 +116,19 @@ pub struct GenericOnchainDeciderCircuit<
     A: ArithRelation<W, RU>,                    // Constraint system
     AVar: ArithRelationGadget<W::Var, RU::Var>, // In-circuit representation of `A`
     D: DeciderEnabledNIFS<C1, RU, IU, W, A>,
+    T: DeciderEthTranscript<CF1<C1>> = PoseidonSponge<CF1<C1>>,
+    SP: SpongeWithGadget<CF1<C1>> = PoseidonSponge<CF1<C1>>,
 > {
     pub _avar: PhantomData<AVar>,
+    pub _t: PhantomData<T>,
     /// Constraint system of the Augmented Function circuit
     pub arith: A,
     /// R1CS of the CycleFold circuit
     pub cf_arith: R1CS<CF1<C2>>,
     /// CycleFold PedersenParams over C2
     pub cf_pedersen_params: PedersenParams<C2>,
-    pub poseidon_config: PoseidonConfig<CF1<C1>>,
+    /// Configuration of the sponge `SP`
+    pub sponge_config: SP::Config,
     /// public params hash
     pub pp_hash: CF1<C1>,
     pub i: CF1<C1>,
@@ -113,24 +166,26 @@ impl<
         A: ArithRelation<W, RU>,
         AVar: ArithRelationGadget<W::Var, RU::Var> + AllocVar<A, CF1<C1>>,
         D: DeciderEnabledNIFS<C1, RU, IU, W, A>,
+        T: DeciderEthTranscript<CF1<C1>>,
+        SP: SpongeWithGadget<CF1<C1>>,
     >
     Dummy<(
         A,
         R1CS<CF1<C2>>,
         PedersenParams<C2>,
-        PoseidonConfig<CF1<C1>>,
+        SP::Config,
         D::ProofDummyCfg,
         D::RandomnessDummyCfg,
         usize,
         usize,
-    )> for GenericOnchainDeciderCircuit<C1, C2, RU, IU, W, A, AVar, D>
+    )> for GenericOnchainDeciderCircuit<C1, C2, RU, IU, W, A, AVar, D, T, SP>
 {
     fn dummy(
         (
             arith,
             cf_arith,
             cf_pedersen_params,
-            poseidon_config,
+            sponge_config,
             proof_config,
             randomness_config,
             state_len,
@@ -139,7 +194,7 @@ impl<
             A,
             R1CS<CF1<C2>>,
             PedersenParams<C2>,
-            PoseidonConfig<CF1<C1>>,
+            SP::Config,
             D::ProofDummyCfg,
             D::RandomnessDummyCfg,
             usize,
@@ -148,8 +203,9 @@ impl<
     ) -> Self {
         Self {
             _avar: PhantomData,
+            _t: PhantomData,
             cf_pedersen_params,
-            poseidon_config,
+            sponge_config,
             pp_hash: Zero::zero(),
             i: Zero::zero(),
             z_0: vec![Zero::zero(); state_len],
@@ -181,7 +237,10 @@ impl<
         A: ArithRelation<W, RU>,
         AVar: ArithRelationGadget<W::Var, RU::Var> + AllocVar<A, CF1<C1>>,
         D: DeciderEnabledNIFS<C1, RU, IU, W, A>,
-    > ConstraintSynthesizer<CF1<C1>> for GenericOnchainDeciderCircuit<C1, C2, RU, IU, W, A, AVar, D>
+        T: DeciderEthTranscript<CF1<C1>>,
+        SP: SpongeWithGadget<CF1<C1>>,
+    > ConstraintSynthesizer<CF1<C1>>
+    for GenericOnchainDeciderCircuit<C1, C2, RU, IU, W, A, AVar, D, T, SP>
 where
     RU::Var: AbsorbGadget<CF1<C1>> + CommittedInstanceVarOps<C1, PointVar = NonNativeAffineVar<C1>>,
 {
@@ -211,7 +270,7 @@ where
         let kzg_evaluations = Vec::new_input(cs.clone(), || Ok(self.kzg_evaluations))?;
 
         // `sponge` is for digest computation.
-        let sponge = PoseidonSpongeVar::new(cs.clone(), &self.poseidon_config);
+        let sponge = SP::Var::new(cs.clone(), &self.sponge_config);
         // `transcript` is for challenge generation.
         let mut transcript = sponge.clone();
 
@@ -295,9 +354,11 @@ where
         )?
         .enforce_partial_equal(&U_i1)?;
 
-        // 7.1. compute and check KZG challenges
-        KZGChallengesGadget::get_challenges_gadget(&mut transcript, &U_i1)?
-            .enforce_equal(&kzg_challenges)?;
+        // 7.1. compute and check KZG challenges, unless the verifier derives them by itself
+        if T::IN_CIRCUIT {
+            KZGChallengesGadget::get_challenges_gadget(&mut transcript, &U_i1)?
+                .enforce_equal(&kzg_challenges)?;
+        }
 
         // 7.2. check the claimed evaluations
         for (((v, _r), c), e) in W_i1
@@ -8,6 +8,7 @@ use ark_r1cs_std::{
     eq::EqGadget,
     fields::fp::FpVar,
     prelude::Boolean,
+    select::CondSelectGadget,
     R1CSVar,
 };
 use ark_relations::r1cs::{ConstraintSystemRef, Namespace, SynthesisError};
@@ -135,6 +136,19 @@ impl<C: Curve> EqGadget<C::ScalarField> for NonNativeAffineVar<C> {
     }
 }
 
+impl<C: Curve> CondSelectGadget<C::ScalarField> for NonNativeAffineVar<C> {
+    fn conditionally_select(
+        cond: &Boolean<C::ScalarField>,
+        true_value: &Self,
+        false_value: &Self,
+    ) -> Result<Self, SynthesisError> {
+        Ok(Self {
+            x: cond.select(&true_value.x, &false_value.x)?,
+            y: cond.select(&true_value.y, &false_value.y)?,
+        })
+    }
+}
+
 impl<C: Curve> NonNativeAffineVar<C> {
     pub fn zero() -> Self {
         // `unwrap` below is safe because we are allocating a constant value,
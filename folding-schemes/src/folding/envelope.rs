@@ -0,0 +1,567 @@
+//! Versioned, self-describing on-disk format for the params and IVC proofs of the folding schemes,
+//! and for the params and proofs of their deciders.
+//!
+//! Each file starts with a [`Header`] that identifies what it contains and what it was generated
+//! with: magic bytes, format version, kind of content, folding scheme, curves, commitment schemes,
+//! serialization mode and `pp_hash`. Reading a file checks its header against the expected ones,
+//! returning a descriptive error on mismatch, instead of the opaque `SerializationError` (or worse,
+//! silently wrong values) obtained when deserializing the raw bytes with the wrong types.
+//!
+//! When the format changes, `FORMAT_VERSION` is increased, `Header::read` keeps reading the
+//! headers of the previous versions, and `Envelope::migrate` upgrades their payloads.
+use ark_ec::PrimeGroup;
+use ark_ff::{BigInteger, PrimeField};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Compress, Validate};
+use ark_std::fmt;
+use sha3::{Digest, Sha3_256};
+use std::io::{Read, Write};
+
+use crate::frontend::FCircuit;
+use crate::{Curve, Decider, Error, FoldingScheme};
+
+/// Magic bytes at the start of every file.
+pub const MAGIC: [u8; 6] = *b"SONOBE";
+/// Current version of the format.
+pub const FORMAT_VERSION: u16 = 1;
+
+/// Kind of the content of a file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    ProverParam,
+    VerifierParam,
+    IVCProof,
+    DeciderProverParam,
+    DeciderVerifierParam,
+    DeciderProof,
+}
+
+impl Kind {
+    fn to_u8(self) -> u8 {
+        match self {
+            Self::ProverParam => 0,
+            Self::VerifierParam => 1,
+            Self::IVCProof => 2,
+            Self::DeciderProverParam => 3,
+            Self::DeciderVerifierParam => 4,
+            Self::DeciderProof => 5,
+        }
+    }
+
+    fn from_u8(kind: u8) -> Result<Self, Error> {
+        match kind {
+            0 => Ok(Self::ProverParam),
+            1 => Ok(Self::VerifierParam),
+            2 => Ok(Self::IVCProof),
+            3 => Ok(Self::DeciderProverParam),
+            4 => Ok(Self::DeciderVerifierParam),
+            5 => Ok(Self::DeciderProof),
+            _ => Err(Error::FormatMismatch(
+                "kind".to_string(),
+                "prover params, verifier params, IVC proof or their decider counterparts"
+                    .to_string(),
+                format!("unknown kind {}", kind),
+            )),
+        }
+    }
+}
+
+impl fmt::Display for Kind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ProverParam => write!(f, "prover params"),
+            Self::VerifierParam => write!(f, "verifier params"),
+            Self::IVCProof => write!(f, "IVC proof"),
+            Self::DeciderProverParam => write!(f, "decider prover params"),
+            Self::DeciderVerifierParam => write!(f, "decider verifier params"),
+            Self::DeciderProof => write!(f, "decider proof"),
+        }
+    }
+}
+
+/// Header of a file, describing its content.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Header<F: PrimeField> {
+    /// version of the format the file was written with
+    pub version: u16,
+    pub kind: Kind,
+    /// id of the folding scheme, see `Envelope::scheme_id`
+    pub scheme: String,
+    /// ids of the curves (C1, C2), see `curve_id`
+    pub curves: [String; 2],
+    /// ids of the commitment schemes over (C1, C2) together with their hiding mode, see
+    /// `Envelope::commitment_ids`
+    pub commitments: [(String, bool); 2],
+    /// whether the payload is serialized in compressed mode
+    pub compress: bool,
+    /// hash of the public params that the content is bound to
+    pub pp_hash: F,
+}
+
+impl<F: PrimeField> Header<F> {
+    /// Writes the header.
+    pub fn write(&self, mut writer: impl Write) -> Result<(), Error> {
+        writer.write_all(&MAGIC)?;
+        self.version.serialize_uncompressed(&mut writer)?;
+        self.kind.to_u8().serialize_uncompressed(&mut writer)?;
+        self.scheme.serialize_uncompressed(&mut writer)?;
+        self.curves.serialize_uncompressed(&mut writer)?;
+        self.commitments.serialize_uncompressed(&mut writer)?;
+        self.compress.serialize_uncompressed(&mut writer)?;
+        self.pp_hash.serialize_uncompressed(&mut writer)?;
+        Ok(())
+    }
+
+    /// Reads the header of any supported version of the format, leaving the reader at the start
+    /// of the payload.
+    pub fn read(mut reader: impl Read) -> Result<Self, Error> {
+        let mut magic = [0; MAGIC.len()];
+        reader.read_exact(&mut magic)?;
+        if magic != MAGIC {
+            return Err(Error::InvalidMagic);
+        }
+        let version = u16::deserialize_uncompressed(&mut reader)?;
+        match version {
+            // when the header layout changes, the readers of the previous layouts are kept here
+            1 => Ok(Self {
+                version,
+                kind: Kind::from_u8(u8::deserialize_uncompressed(&mut reader)?)?,
+                scheme: String::deserialize_uncompressed(&mut reader)?,
+                curves: <[String; 2]>::deserialize_uncompressed(&mut reader)?,
+                commitments: <[(String, bool); 2]>::deserialize_uncompressed(&mut reader)?,
+                compress: bool::deserialize_uncompressed(&mut reader)?,
+                pp_hash: F::deserialize_uncompressed(&mut reader)?,
+            }),
+            _ => Err(Error::UnsupportedFormatVersion(version, FORMAT_VERSION)),
+        }
+    }
+}
+
+/// Reads a file of the given kind, checking only its kind and not the rest of its header, and
+/// returns the header together with the content. Meant for the tools that consume files already
+/// checked against their params elsewhere, and which do not know the folding scheme they belong to.
+pub fn read_unchecked<F: PrimeField, T: CanonicalDeserialize>(
+    mut reader: impl Read,
+    kind: Kind,
+) -> Result<(Header<F>, T), Error> {
+    let header = Header::<F>::read(&mut reader)?;
+    check("kind", &kind, &header.kind)?;
+    let value = T::deserialize_with_mode(reader, compress_mode(&header), Validate::Yes)?;
+    Ok((header, value))
+}
+
+/// returns an id of the curve, computed from its generator and the moduli of its base and scalar
+/// fields, which identifies the curve independently of the type used to represent it
+pub fn curve_id<C: Curve>() -> Result<String, Error> {
+    let mut hasher = Sha3_256::new();
+    hasher.update(C::BaseField::MODULUS.to_bytes_le());
+    hasher.update(C::ScalarField::MODULUS.to_bytes_le());
+    let mut generator_bytes = Vec::new();
+    C::generator().serialize_uncompressed(&mut generator_bytes)?;
+    hasher.update(generator_bytes);
+    let hash = hasher.finalize();
+    Ok(hash[..8].iter().map(|b| format!("{:02x}", b)).collect())
+}
+
+/// Envelope trait, implemented by the folding schemes to read and write their params and IVC
+/// proofs in the versioned format of this module. The params and proofs of a decider `D` of the
+/// folding scheme are written under the header of the folding scheme, bound to the `pp_hash` of the
+/// folding scheme params that the decider was preprocessed from.
+pub trait Envelope<
+    C1: Curve<BaseField = C2::ScalarField, ScalarField = C2::BaseField>,
+    C2: Curve,
+    FC: FCircuit<C1::ScalarField>,
+>: FoldingScheme<C1, C2, FC>
+{
+    /// returns the id of the folding scheme, including the parameters of the scheme that change
+    /// the shape of its params and proofs
+    fn scheme_id() -> String;
+
+    /// returns the ids of the commitment schemes over C1 and C2, together with their hiding mode
+    fn commitment_ids() -> [(String, bool); 2];
+
+    /// returns the hash of the public params that the given verifier params correspond to
+    fn vp_pp_hash(vp: &Self::VerifierParam) -> Result<C1::ScalarField, Error>;
+
+    /// Upgrades a payload written with a previous version of the format to the current one. There
+    /// are no previous versions yet, so the default implementation only accepts the current one;
+    /// schemes whose params or proofs change in a future version override it.
+    fn migrate(header: &Header<C1::ScalarField>, payload: Vec<u8>) -> Result<Vec<u8>, Error> {
+        if header.version != FORMAT_VERSION {
+            return Err(Error::UnsupportedFormatVersion(
+                header.version,
+                FORMAT_VERSION,
+            ));
+        }
+        Ok(payload)
+    }
+
+    /// returns the header describing the given kind of content of this folding scheme
+    fn header(
+        kind: Kind,
+        pp_hash: C1::ScalarField,
+        compress: Compress,
+    ) -> Result<Header<C1::ScalarField>, Error> {
+        Ok(Header {
+            version: FORMAT_VERSION,
+            kind,
+            scheme: Self::scheme_id(),
+            curves: [curve_id::<C1>()?, curve_id::<C2>()?],
+            commitments: Self::commitment_ids(),
+            compress: compress == Compress::Yes,
+            pp_hash,
+        })
+    }
+
+    /// Checks that the header of a file matches the expected one, and returns the payload that
+    /// follows it, migrated to the current version of the format.
+    fn read_payload(
+        mut reader: impl Read,
+        kind: Kind,
+        pp_hash: Option<C1::ScalarField>,
+    ) -> Result<(Header<C1::ScalarField>, Vec<u8>), Error> {
+        let header = Header::<C1::ScalarField>::read(&mut reader)?;
+        let expected = Self::header(kind, header.pp_hash, Compress::No)?;
+
+        check("kind", &expected.kind, &header.kind)?;
+        check("folding scheme", &expected.scheme, &header.scheme)?;
+        check("C1 curve id", &expected.curves[0], &header.curves[0])?;
+        check("C2 curve id", &expected.curves[1], &header.curves[1])?;
+        for (i, (expected, found)) in expected
+            .commitments
+            .iter()
+            .zip(header.commitments.iter())
+            .enumerate()
+        {
+            check(
+                &format!("commitment scheme over C{}", i + 1),
+                &commitment_to_string(expected),
+                &commitment_to_string(found),
+            )?;
+        }
+        if pp_hash.is_some_and(|pp_hash| pp_hash != header.pp_hash) {
+            return Err(Error::PPHashMismatch);
+        }
+
+        let mut payload = Vec::new();
+        reader.read_to_end(&mut payload)?;
+        let payload = Self::migrate(&header, payload)?;
+        Ok((header, payload))
+    }
+
+    /// Writes the prover params, bound to the given `pp_hash` (the one of their verifier params).
+    fn write_pp(
+        pp: &Self::ProverParam,
+        pp_hash: C1::ScalarField,
+        mut writer: impl Write,
+        compress: Compress,
+    ) -> Result<(), Error> {
+        Self::header(Kind::ProverParam, pp_hash, compress)?.write(&mut writer)?;
+        pp.serialize_with_mode(&mut writer, compress)?;
+        Ok(())
+    }
+
+    /// Reads the prover params, checking that they are bound to the given `pp_hash`.
+    fn read_pp(
+        reader: impl Read,
+        fc_params: FC::Params,
+        pp_hash: C1::ScalarField,
+    ) -> Result<Self::ProverParam, Error> {
+        let (header, payload) = Self::read_payload(reader, Kind::ProverParam, Some(pp_hash))?;
+        Self::pp_deserialize_with_mode(
+            &payload[..],
+            compress_mode(&header),
+            Validate::Yes,
+            fc_params,
+        )
+    }
+
+    /// Writes the verifier params.
+    fn write_vp(
+        vp: &Self::VerifierParam,
+        mut writer: impl Write,
+        compress: Compress,
+    ) -> Result<(), Error> {
+        Self::header(Kind::VerifierParam, Self::vp_pp_hash(vp)?, compress)?.write(&mut writer)?;
+        vp.serialize_with_mode(&mut writer, compress)?;
+        Ok(())
+    }
+
+    /// Reads the verifier params. Since parts of them are recomputed from `fc_params`, their
+    /// `pp_hash` is checked against the one in the header, which detects a mismatch in the
+    /// `fc_params`.
+    fn read_vp(reader: impl Read, fc_params: FC::Params) -> Result<Self::VerifierParam, Error> {
+        let (header, payload) = Self::read_payload(reader, Kind::VerifierParam, None)?;
+        let vp = Self::vp_deserialize_with_mode(
+            &payload[..],
+            compress_mode(&header),
+            Validate::Yes,
+            fc_params,
+        )?;
+        if Self::vp_pp_hash(&vp)? != header.pp_hash {
+            return Err(Error::PPHashMismatch);
+        }
+        Ok(vp)
+    }
+
+    /// Writes the IVC proof, bound to the given `pp_hash`.
+    fn write_ivc_proof(
+        ivc_proof: &Self::IVCProof,
+        pp_hash: C1::ScalarField,
+        mut writer: impl Write,
+        compress: Compress,
+    ) -> Result<(), Error> {
+        Self::header(Kind::IVCProof, pp_hash, compress)?.write(&mut writer)?;
+        ivc_proof.serialize_with_mode(&mut writer, compress)?;
+        Ok(())
+    }
+
+    /// Reads the IVC proof, checking that it is bound to the given `pp_hash`.
+    fn read_ivc_proof(
+        reader: impl Read,
+        pp_hash: C1::ScalarField,
+    ) -> Result<Self::IVCProof, Error> {
+        let (header, payload) = Self::read_payload(reader, Kind::IVCProof, Some(pp_hash))?;
+        Ok(Self::IVCProof::deserialize_with_mode(
+            &payload[..],
+            compress_mode(&header),
+            Validate::Yes,
+        )?)
+    }
+
+    /// Writes the prover params of the decider `D`, bound to the given `pp_hash`.
+    fn write_decider_pp<D: Decider<C1, C2, FC, Self>>(
+        pp: &D::ProverParam,
+        pp_hash: C1::ScalarField,
+        mut writer: impl Write,
+        compress: Compress,
+    ) -> Result<(), Error>
+    where
+        D::ProverParam: CanonicalSerialize,
+    {
+        Self::header(Kind::DeciderProverParam, pp_hash, compress)?.write(&mut writer)?;
+        pp.serialize_with_mode(&mut writer, compress)?;
+        Ok(())
+    }
+
+    /// Reads the prover params of the decider `D`, checking that they are bound to the given
+    /// `pp_hash`.
+    fn read_decider_pp<D: Decider<C1, C2, FC, Self>>(
+        reader: impl Read,
+        pp_hash: C1::ScalarField,
+    ) -> Result<D::ProverParam, Error>
+    where
+        D::ProverParam: CanonicalDeserialize,
+    {
+        let (header, payload) =
+            Self::read_payload(reader, Kind::DeciderProverParam, Some(pp_hash))?;
+        Ok(D::ProverParam::deserialize_with_mode(
+            &payload[..],
+            compress_mode(&header),
+            Validate::Yes,
+        )?)
+    }
+
+    /// Writes the verifier params of the decider `D`, bound to the given `pp_hash`.
+    fn write_decider_vp<D: Decider<C1, C2, FC, Self>>(
+        vp: &D::VerifierParam,
+        pp_hash: C1::ScalarField,
+        mut writer: impl Write,
+        compress: Compress,
+    ) -> Result<(), Error>
+    where
+        D::VerifierParam: CanonicalSerialize,
+    {
+        Self::header(Kind::DeciderVerifierParam, pp_hash, compress)?.write(&mut writer)?;
+        vp.serialize_with_mode(&mut writer, compress)?;
+        Ok(())
+    }
+
+    /// Reads the verifier params of the decider `D`, checking that they are bound to the given
+    /// `pp_hash`.
+    fn read_decider_vp<D: Decider<C1, C2, FC, Self>>(
+        reader: impl Read,
+        pp_hash: C1::ScalarField,
+    ) -> Result<D::VerifierParam, Error>
+    where
+        D::VerifierParam: CanonicalDeserialize,
+    {
+        let (header, payload) =
+            Self::read_payload(reader, Kind::DeciderVerifierParam, Some(pp_hash))?;
+        Ok(D::VerifierParam::deserialize_with_mode(
+            &payload[..],
+            compress_mode(&header),
+            Validate::Yes,
+        )?)
+    }
+
+    /// Writes the proof of the decider `D`, bound to the given `pp_hash`.
+    fn write_decider_proof<D: Decider<C1, C2, FC, Self>>(
+        proof: &D::Proof,
+        pp_hash: C1::ScalarField,
+        mut writer: impl Write,
+        compress: Compress,
+    ) -> Result<(), Error>
+    where
+        D::Proof: CanonicalSerialize,
+    {
+        Self::header(Kind::DeciderProof, pp_hash, compress)?.write(&mut writer)?;
+        proof.serialize_with_mode(&mut writer, compress)?;
+        Ok(())
+    }
+
+    /// Reads the proof of the decider `D`, checking that it is bound to the given `pp_hash`.
+    fn read_decider_proof<D: Decider<C1, C2, FC, Self>>(
+        reader: impl Read,
+        pp_hash: C1::ScalarField,
+    ) -> Result<D::Proof, Error>
+    where
+        D::Proof: CanonicalDeserialize,
+    {
+        let (header, payload) = Self::read_payload(reader, Kind::DeciderProof, Some(pp_hash))?;
+        Ok(D::Proof::deserialize_with_mode(
+            &payload[..],
+            compress_mode(&header),
+            Validate::Yes,
+        )?)
+    }
+}
+
+fn check<T: PartialEq + fmt::Display>(name: &str, expected: &T, found: &T) -> Result<(), Error> {
+    if expected != found {
+        return Err(Error::FormatMismatch(
+            name.to_string(),
+            expected.to_string(),
+            found.to_string(),
+        ));
+    }
+    Ok(())
+}
+
+fn commitment_to_string((id, hiding): &(String, bool)) -> String {
+    if *hiding {
+        format!("{} (hiding)", id)
+    } else {
+        id.clone()
+    }
+}
+
+fn compress_mode<F: PrimeField>(header: &Header<F>) -> Compress {
+    if header.compress {
+        Compress::Yes
+    } else {
+        Compress::No
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_pallas::{Fr, Projective as G1};
+    use ark_vesta::Projective as G2;
+
+    use super::*;
+    use crate::commitment::pedersen::Pedersen;
+    use crate::folding::{
+        hypernova::HyperNova,
+        nova::{Nova, PreprocessorParam as NovaPreprocessorParam},
+    };
+    use crate::frontend::utils::CubicFCircuit;
+    use crate::transcript::{poseidon::poseidon_canonical_config, poseidon2::Poseidon2Sponge};
+
+    type FC = CubicFCircuit<Fr>;
+    type N = Nova<G1, G2, FC, Pedersen<G1>, Pedersen<G2>, false>;
+    type N2 = Nova<G1, G2, FC, Pedersen<G1>, Pedersen<G2>, false, Poseidon2Sponge<Fr>>;
+    type HN = HyperNova<G1, G2, FC, Pedersen<G1>, Pedersen<G2>, 1, 1, false>;
+
+    #[test]
+    fn test_envelope_roundtrip() -> Result<(), Error> {
+        let mut rng = ark_std::test_rng();
+        let prep_param =
+            NovaPreprocessorParam::new(poseidon_canonical_config::<Fr>(), FC::new(())?);
+        let (pp, vp) = N::preprocess(&mut rng, &prep_param)?;
+        let pp_hash = vp.pp_hash()?;
+
+        let mut nova = N::init(
+            &(pp.clone(), vp.clone()),
+            FC::new(())?,
+            vec![Fr::from(3_u32)],
+        )?;
+        nova.prove_step(&mut rng, (), None)?;
+        let ivc_proof = nova.ivc_proof();
+
+        for compress in [Compress::Yes, Compress::No] {
+            let mut vp_bytes = Vec::new();
+            N::write_vp(&vp, &mut vp_bytes, compress)?;
+            let header = Header::<Fr>::read(&vp_bytes[..])?;
+            assert_eq!(header, N::header(Kind::VerifierParam, pp_hash, compress)?);
+            let vp_read = N::read_vp(&vp_bytes[..], ())?;
+            assert_eq!(vp_read.pp_hash()?, pp_hash);
+
+            let mut pp_bytes = Vec::new();
+            N::write_pp(&pp, pp_hash, &mut pp_bytes, compress)?;
+            let pp_read = N::read_pp(&pp_bytes[..], (), pp_hash)?;
+
+            let mut proof_bytes = Vec::new();
+            N::write_ivc_proof(&ivc_proof, pp_hash, &mut proof_bytes, compress)?;
+            let ivc_proof_read = N::read_ivc_proof(&proof_bytes[..], pp_hash)?;
+            assert_eq!(ivc_proof_read, ivc_proof);
+
+            N::verify(vp_read.clone(), ivc_proof_read.clone())?;
+            // the read params can be used to keep proving
+            let mut nova = N::from_ivc_proof(ivc_proof_read, (), (pp_read, vp_read))?;
+            nova.prove_step(&mut rng, (), None)?;
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_envelope_mismatch() -> Result<(), Error> {
+        let mut rng = ark_std::test_rng();
+        let prep_param =
+            NovaPreprocessorParam::new(poseidon_canonical_config::<Fr>(), FC::new(())?);
+        let (pp, vp) = N::preprocess(&mut rng, &prep_param)?;
+        let pp_hash = vp.pp_hash()?;
+
+        let mut vp_bytes = Vec::new();
+        N::write_vp(&vp, &mut vp_bytes, Compress::Yes)?;
+        let mut pp_bytes = Vec::new();
+        N::write_pp(&pp, pp_hash, &mut pp_bytes, Compress::Yes)?;
+
+        // wrong kind of content
+        assert!(matches!(
+            N::read_pp(&vp_bytes[..], (), pp_hash),
+            Err(Error::FormatMismatch(name, _, _)) if name == "kind"
+        ));
+        // wrong folding scheme
+        assert!(matches!(
+            HN::read_vp(&vp_bytes[..], ()),
+            Err(Error::FormatMismatch(name, _, _)) if name == "folding scheme"
+        ));
+        // same folding scheme with another sponge
+        assert!(matches!(
+            N2::read_vp(&vp_bytes[..], ()),
+            Err(Error::FormatMismatch(name, _, _)) if name == "folding scheme"
+        ));
+        // prover params of other public params
+        assert!(matches!(
+            N::read_pp(&pp_bytes[..], (), pp_hash + Fr::from(1_u32)),
+            Err(Error::PPHashMismatch)
+        ));
+        // not a file of this format
+        assert!(matches!(
+            N::read_vp(&vp_bytes[1..], ()),
+            Err(Error::InvalidMagic)
+        ));
+        // future version of the format
+        let mut future_vp_bytes = vp_bytes.clone();
+        future_vp_bytes[MAGIC.len()..MAGIC.len() + 2]
+            .copy_from_slice(&(FORMAT_VERSION + 1).to_le_bytes());
+        assert!(matches!(
+            N::read_vp(&future_vp_bytes[..], ()),
+            Err(Error::UnsupportedFormatVersion(v, FORMAT_VERSION)) if v == FORMAT_VERSION + 1
+        ));
+
+        // curves are identified independently of their types
+        assert_ne!(curve_id::<G1>()?, curve_id::<G2>()?);
+        Ok(())
+    }
+}
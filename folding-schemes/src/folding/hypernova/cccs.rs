@@ -18,10 +18,17 @@ use crate::{Curve, Error};
 
 /// Committed CCS instance
 #[derive(Debug, Clone, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(bound = "")
+)]
 pub struct CCCS<C: Curve> {
     // Commitment to witness
+    #[cfg_attr(feature = "serde", serde(with = "crate::utils::json::point"))]
     pub C: C,
     // Public input/output
+    #[cfg_attr(feature = "serde", serde(with = "crate::utils::json::field_vec"))]
     pub x: Vec<C::ScalarField>,
 }
 
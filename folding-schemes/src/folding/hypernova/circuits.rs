@@ -1,7 +1,7 @@
 /// Implementation of [HyperNova](https://eprint.iacr.org/2023/573.pdf) circuits
 use ark_crypto_primitives::sponge::{
     constraints::{AbsorbGadget, CryptographicSpongeVar},
-    poseidon::{constraints::PoseidonSpongeVar, PoseidonConfig, PoseidonSponge},
+    poseidon::PoseidonSponge,
     CryptographicSponge,
 };
 use ark_ff::PrimeField;
@@ -32,7 +32,7 @@ use crate::arith::{
     r1cs::{extract_r1cs, R1CS},
     Arith,
 };
-use crate::constants::NOVA_N_BITS_RO;
+use crate::constants::{HYPERNOVA_NIMFS_DOMAIN, NOVA_N_BITS_RO};
 use crate::folding::{
     circuits::{
         cyclefold::{
@@ -48,7 +48,7 @@ use crate::folding::{
     traits::{CommittedInstanceVarOps, Dummy},
 };
 use crate::frontend::FCircuit;
-use crate::transcript::{AbsorbNonNativeGadget, TranscriptVar};
+use crate::transcript::{AbsorbNonNativeGadget, SpongeWithGadget, TranscriptVar};
 use crate::utils::virtual_polynomial::VPAuxInfo;
 use crate::{Curve, Error};
 
@@ -252,20 +252,15 @@ impl<C: Curve> NIMFSGadget<C> {
         enabled: Boolean<C::ScalarField>,
     ) -> Result<(LCCCSVar<C>, Vec<Boolean<CF1<C>>>), SynthesisError> {
         // absorb instances to transcript
-        transcript.absorb(&running_instances)?;
-        transcript.absorb(&new_instances)?;
+        transcript.domain_separator(HYPERNOVA_NIMFS_DOMAIN)?;
+        transcript.absorb_labelled(b"running_instances", &running_instances)?;
+        transcript.absorb_labelled(b"new_instances", &new_instances)?;
 
         // get the challenges
-        let gamma_scalar_raw = C::ScalarField::from_le_bytes_mod_order(b"gamma");
-        let gamma_scalar: FpVar<CF1<C>> =
-            FpVar::<CF1<C>>::new_constant(cs.clone(), gamma_scalar_raw)?;
-        transcript.absorb(&gamma_scalar)?;
+        transcript.absorb_label(b"gamma")?;
         let gamma: FpVar<CF1<C>> = transcript.get_challenge()?;
 
-        let beta_scalar_raw = C::ScalarField::from_le_bytes_mod_order(b"beta");
-        let beta_scalar: FpVar<CF1<C>> =
-            FpVar::<CF1<C>>::new_constant(cs.clone(), beta_scalar_raw)?;
-        transcript.absorb(&beta_scalar)?;
+        transcript.absorb_label(b"beta")?;
         let beta: Vec<FpVar<CF1<C>>> = transcript.get_challenges(ccs.s)?;
 
         let vp_aux_info_raw = VPAuxInfo::<C::ScalarField> {
@@ -313,9 +308,7 @@ impl<C: Curve> NIMFSGadget<C> {
         computed_c.conditional_enforce_equal(&e_vars[e_vars.len() - 1], &enabled)?;
 
         // get the folding challenge
-        let rho_scalar_raw = C::ScalarField::from_le_bytes_mod_order(b"rho");
-        let rho_scalar: FpVar<CF1<C>> = FpVar::<CF1<C>>::new_constant(cs.clone(), rho_scalar_raw)?;
-        transcript.absorb(&rho_scalar)?;
+        transcript.absorb_label(b"rho")?;
         let rho_bits: Vec<Boolean<CF1<C>>> = transcript.get_challenge_nbits(NOVA_N_BITS_RO)?;
         let rho = Boolean::le_bits_to_fp(&rho_bits)?;
 
@@ -464,15 +457,19 @@ fn compute_c_gadget<F: PrimeField>(
 /// For multi-instance folding, one needs to specify the const generics below:
 /// * `MU` - the number of LCCCS instances to be folded
 /// * `NU` - the number of CCCS instances to be folded
-#[derive(Debug, Clone)]
+///
+/// The instances are hashed and the challenges are derived in-circuit with the gadget of the
+/// sponge `T`, which must match the sponge used natively by `HyperNova`.
+#[derive(Clone)]
 pub struct AugmentedFCircuit<
     C1: Curve,
     C2: Curve,
     FC: FCircuit<CF1<C1>>,
     const MU: usize,
     const NU: usize,
+    T: SpongeWithGadget<CF1<C1>> = PoseidonSponge<CF1<C1>>,
 > {
-    pub(super) poseidon_config: PoseidonConfig<CF1<C1>>,
+    pub(super) sponge_config: T::Config,
     pub(super) ccs: CCS<C1::ScalarField>, // CCS of the AugmentedFCircuit
     pub(super) pp_hash: Option<CF1<C1>>,
     pub(super) i: Option<CF1<C1>>,
@@ -494,14 +491,15 @@ pub struct AugmentedFCircuit<
     pub(super) cf_cmT: Option<C2>,
 }
 
-impl<C1, C2, FC, const MU: usize, const NU: usize> AugmentedFCircuit<C1, C2, FC, MU, NU>
+impl<C1, C2, FC, const MU: usize, const NU: usize, T> AugmentedFCircuit<C1, C2, FC, MU, NU, T>
 where
     C1: Curve<BaseField = C2::ScalarField, ScalarField = C2::BaseField>,
     C2: Curve,
     FC: FCircuit<CF1<C1>>,
+    T: SpongeWithGadget<CF1<C1>>,
 {
     pub fn default(
-        poseidon_config: &PoseidonConfig<CF1<C1>>,
+        sponge_config: &T::Config,
         F_circuit: FC,
         ccs: CCS<C1::ScalarField>,
     ) -> Result<Self, Error> {
@@ -509,7 +507,7 @@ where
             return Err(Error::CantBeZero("mu,nu".to_string()));
         }
         Ok(Self {
-            poseidon_config: poseidon_config.clone(),
+            sponge_config: sponge_config.clone(),
             ccs,
             pp_hash: None,
             i: None,
@@ -531,7 +529,7 @@ where
     }
 
     pub fn empty(
-        poseidon_config: &PoseidonConfig<CF1<C1>>,
+        sponge_config: &T::Config,
         F: FC, // FCircuit
         ccs: Option<CCS<C1::ScalarField>>,
     ) -> Result<Self, Error> {
@@ -546,7 +544,7 @@ where
         // will panic if `s = 0` (0 is arkworks' fallback value for `log(0)`).
         // Similarly, `s` will also be overwritten by `compute_concrete_ccs`.
         initial_ccs.s = 1;
-        let mut augmented_f_circuit = Self::default(poseidon_config, F, initial_ccs)?;
+        let mut augmented_f_circuit = Self::default(sponge_config, F, initial_ccs)?;
         augmented_f_circuit.ccs = ccs
             .ok_or(())
             .or_else(|_| augmented_f_circuit.compute_concrete_ccs())?;
@@ -579,20 +577,13 @@ where
             let all_Ws = [vec![W_i.clone()], Ws].concat();
             let all_ws = [vec![w_i.clone()], ws].concat();
 
-            let mut transcript_p: PoseidonSponge<C1::ScalarField> =
-                PoseidonSponge::<C1::ScalarField>::new(&self.poseidon_config.clone());
+            let mut transcript_p = T::new(&self.sponge_config);
             // since this is only for the number of constraints, no need to absorb the pp_hash here
-            let (nimfs_proof, U_i1, _, _) = NIMFS::<C1, PoseidonSponge<C1::ScalarField>>::prove(
-                &mut transcript_p,
-                &ccs,
-                &all_Us,
-                &all_us,
-                &all_Ws,
-                &all_ws,
-            )?;
+            let (nimfs_proof, U_i1, _, _) =
+                NIMFS::<C1, T>::prove(&mut transcript_p, &ccs, &all_Us, &all_us, &all_Ws, &all_ws)?;
 
             let augmented_f_circuit = Self {
-                poseidon_config: self.poseidon_config.clone(),
+                sponge_config: self.sponge_config.clone(),
                 ccs: ccs.clone(),
                 pp_hash: Some(C1::ScalarField::zero()),
                 i: Some(C1::ScalarField::zero()),
@@ -642,11 +633,12 @@ where
     }
 }
 
-impl<C1, C2, FC, const MU: usize, const NU: usize> AugmentedFCircuit<C1, C2, FC, MU, NU>
+impl<C1, C2, FC, const MU: usize, const NU: usize, T> AugmentedFCircuit<C1, C2, FC, MU, NU, T>
 where
     C1: Curve<BaseField = C2::ScalarField, ScalarField = C2::BaseField>,
     C2: Curve,
     FC: FCircuit<CF1<C1>>,
+    T: SpongeWithGadget<CF1<C1>>,
 {
     pub fn compute_next_state(
         self,
@@ -698,7 +690,7 @@ where
         })?;
         let cf_cmT = C2::Var::new_witness(cs.clone(), || Ok(self.cf_cmT.unwrap_or_else(C2::zero)))?;
 
-        let sponge = PoseidonSpongeVar::<C1::ScalarField>::new(cs.clone(), &self.poseidon_config);
+        let sponge = T::Var::new(cs.clone(), &self.sponge_config);
 
         let is_basecase = i.is_zero()?;
         let is_not_basecase = !&is_basecase;
@@ -727,7 +719,7 @@ where
         // Notice that NIMFSGadget::fold_committed_instance does not fold C. We set `U_i1.C` to
         // unconstrained witnesses `U_i1_C` respectively. Its correctness will be checked on the
         // other curve.
-        let mut transcript = PoseidonSpongeVar::new(cs.clone(), &self.poseidon_config);
+        let mut transcript = T::Var::new(cs.clone(), &self.sponge_config);
         transcript.absorb(&pp_hash)?;
         let (mut U_i1, rho_bits) = NIMFSGadget::<C1>::verify(
             cs.clone(),
@@ -848,12 +840,13 @@ where
     }
 }
 
-impl<C1, C2, FC, const MU: usize, const NU: usize> ConstraintSynthesizer<CF1<C1>>
-    for AugmentedFCircuit<C1, C2, FC, MU, NU>
+impl<C1, C2, FC, const MU: usize, const NU: usize, T> ConstraintSynthesizer<CF1<C1>>
+    for AugmentedFCircuit<C1, C2, FC, MU, NU, T>
 where
     C1: Curve<BaseField = C2::ScalarField, ScalarField = C2::BaseField>,
     C2: Curve,
     FC: FCircuit<CF1<C1>>,
+    T: SpongeWithGadget<CF1<C1>>,
 {
     fn generate_constraints(self, cs: ConstraintSystemRef<CF1<C1>>) -> Result<(), SynthesisError> {
         self.compute_next_state(cs).map(|_| ())
@@ -863,7 +856,7 @@ where
 #[cfg(test)]
 mod tests {
     use ark_bn254::{Fq, Fr, G1Projective as Projective};
-    use ark_crypto_primitives::sponge::Absorb;
+    use ark_crypto_primitives::sponge::{poseidon::constraints::PoseidonSpongeVar, Absorb};
     use ark_ff::BigInteger;
     use ark_grumpkin::Projective as Projective2;
     use ark_std::{cmp::max, test_rng, time::Instant, UniformRand};
@@ -1260,7 +1253,7 @@ mod tests {
 
                 augmented_f_circuit =
                     AugmentedFCircuit::<Projective, Projective2, CubicFCircuit<Fr>, MU, NU> {
-                        poseidon_config: poseidon_config.clone(),
+                        sponge_config: poseidon_config.clone(),
                         ccs: ccs.clone(),
                         pp_hash: Some(pp_hash),
                         i: Some(Fr::zero()),
@@ -1344,7 +1337,7 @@ mod tests {
 
                 augmented_f_circuit =
                     AugmentedFCircuit::<Projective, Projective2, CubicFCircuit<Fr>, MU, NU> {
-                        poseidon_config: poseidon_config.clone(),
+                        sponge_config: poseidon_config.clone(),
                         ccs: ccs.clone(),
                         pp_hash: Some(pp_hash),
                         i: Some(iFr),
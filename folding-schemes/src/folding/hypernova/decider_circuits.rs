@@ -0,0 +1,234 @@
+/// This file implements the offchain decider circuits for HyperNova. For ethereum use cases, use
+/// the DeciderEthCircuit.
+use ark_crypto_primitives::sponge::{poseidon::PoseidonSponge, CryptographicSponge};
+use ark_ff::{BigInteger, PrimeField};
+use core::marker::PhantomData;
+
+use super::{
+    decider_eth_circuit::DeciderHyperNovaGadget, nimfs::NIMFS, HyperNova, Witness, CCCS, LCCCS,
+};
+use crate::arith::ccs::{circuits::CCSMatricesVar, CCS};
+use crate::commitment::CommitmentScheme;
+use crate::folding::{
+    circuits::{
+        decider::{
+            off_chain::{GenericOffchainDeciderCircuit1, GenericOffchainDeciderCircuit2},
+            DeciderChallenge, KZGChallengesGadget,
+        },
+        CF1,
+    },
+    traits::WitnessOps,
+};
+use crate::frontend::FCircuit;
+use crate::transcript::{poseidon::poseidon_canonical_config, SpongeWithGadget};
+use crate::{Curve, Error};
+
+/// Circuit that implements part of the in-circuit checks needed for the offchain verification over
+/// the Curve2's BaseField (=Curve1's ScalarField).
+pub type DeciderCircuit1<C1, C2, P = CF1<C1>, SP = PoseidonSponge<CF1<C1>>> =
+    GenericOffchainDeciderCircuit1<
+        C1,
+        C2,
+        LCCCS<C1>,
+        CCCS<C1>,
+        Witness<CF1<C1>>,
+        CCS<CF1<C1>>,
+        CCSMatricesVar<CF1<C1>>,
+        DeciderHyperNovaGadget,
+        P,
+        SP,
+    >;
+
+impl<
+        C1: Curve,
+        C2: Curve,
+        FC: FCircuit<C1::ScalarField>,
+        CS1: CommitmentScheme<C1, H>,
+        CS2: CommitmentScheme<C2, H>,
+        const MU: usize,
+        const NU: usize,
+        const H: bool,
+        SP: SpongeWithGadget<CF1<C1>>,
+    > TryFrom<HyperNova<C1, C2, FC, CS1, CS2, MU, NU, H, SP>>
+    for DeciderCircuit1<C1, C2, CS1::Challenge, SP>
+where
+    CS1::Challenge: DeciderChallenge<CF1<C1>>,
+{
+    type Error = Error;
+
+    fn try_from(hn: HyperNova<C1, C2, FC, CS1, CS2, MU, NU, H, SP>) -> Result<Self, Error> {
+        // compute the U_{i+1}, W_{i+1}, by folding the last running & incoming instances
+        let mut transcript = SP::new(&hn.sponge_config);
+        transcript.absorb(&hn.pp_hash);
+        let (nimfs_proof, U_i1, W_i1, rho) = NIMFS::<C1, SP>::prove(
+            &mut transcript,
+            &hn.ccs,
+            &[hn.U_i.clone()],
+            &[hn.u_i.clone()],
+            &[hn.W_i.clone()],
+            &[hn.w_i.clone()],
+        )?;
+
+        // compute the KZG challenges used as inputs in the circuit
+        let openings = W_i1.get_openings();
+        let lens = openings.iter().map(|(v, _)| v.len()).collect::<Vec<_>>();
+        let kzg_challenges = KZGChallengesGadget::get_opening_challenges_native::<
+            C1,
+            _,
+            _,
+            CS1::Challenge,
+        >(&mut transcript, &U_i1, &lens);
+
+        // get KZG evals
+        let kzg_evaluations = openings
+            .iter()
+            .zip(&kzg_challenges)
+            .map(|((v, _), c)| c.evaluate_native(v))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self {
+            _avar: PhantomData,
+            arith: hn.ccs,
+            sponge_config: hn.sponge_config,
+            pp_hash: hn.pp_hash,
+            i: hn.i,
+            z_0: hn.z_0,
+            z_i: hn.z_i,
+            U_i: hn.U_i,
+            W_i: hn.W_i,
+            u_i: hn.u_i,
+            w_i: hn.w_i,
+            U_i1,
+            W_i1,
+            proof: nimfs_proof,
+            randomness: rho,
+            cf_U_i: hn.cf_U_i,
+            kzg_challenges,
+            kzg_evaluations,
+        })
+    }
+}
+
+/// Circuit that implements part of the in-circuit checks needed for the offchain verification over
+/// the Curve1's BaseField (=Curve2's ScalarField).
+pub type DeciderCircuit2<C2, P = CF1<C2>> = GenericOffchainDeciderCircuit2<C2, P>;
+
+impl<
+        C1: Curve,
+        C2: Curve,
+        FC: FCircuit<C1::ScalarField>,
+        CS1: CommitmentScheme<C1, H>,
+        CS2: CommitmentScheme<C2, H>,
+        const MU: usize,
+        const NU: usize,
+        const H: bool,
+        SP: SpongeWithGadget<CF1<C1>>,
+    > TryFrom<HyperNova<C1, C2, FC, CS1, CS2, MU, NU, H, SP>>
+    for DeciderCircuit2<C2, CS2::Challenge>
+where
+    CS2::Challenge: DeciderChallenge<CF1<C2>>,
+{
+    type Error = Error;
+
+    fn try_from(hn: HyperNova<C1, C2, FC, CS1, CS2, MU, NU, H, SP>) -> Result<Self, Error> {
+        // `circuit2` derives its challenges with the canonical Poseidon config on `C2`'s scalar
+        // field, independently of the sponge `SP` that HyperNova uses over `C1`'s scalar field.
+        let poseidon_config = poseidon_canonical_config::<C2::ScalarField>();
+        let mut transcript = PoseidonSponge::<C2::ScalarField>::new(&poseidon_config);
+        let pp_hash_Fq =
+            C2::ScalarField::from_le_bytes_mod_order(&hn.pp_hash.into_bigint().to_bytes_le());
+        transcript.absorb(&pp_hash_Fq);
+
+        // compute the KZG challenges used as inputs in the circuit
+        let openings = hn.cf_W_i.get_openings();
+        let lens = openings.iter().map(|(v, _)| v.len()).collect::<Vec<_>>();
+        let kzg_challenges = KZGChallengesGadget::get_opening_challenges_native::<
+            C2,
+            _,
+            _,
+            CS2::Challenge,
+        >(&mut transcript, &hn.cf_U_i, &lens);
+
+        // get KZG evals
+        let kzg_evaluations = openings
+            .iter()
+            .zip(&kzg_challenges)
+            .map(|((v, _), c)| c.evaluate_native(v))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self {
+            cf_arith: hn.cf_r1cs,
+            poseidon_config,
+            pp_hash: pp_hash_Fq,
+            cf_U_i: hn.cf_U_i,
+            cf_W_i: hn.cf_W_i,
+            kzg_challenges,
+            kzg_evaluations,
+        })
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use ark_pallas::{Fq, Fr, Projective};
+    use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystem};
+    use ark_vesta::Projective as Projective2;
+
+    use super::*;
+    use crate::commitment::pedersen::Pedersen;
+    use crate::folding::nova::PreprocessorParam;
+    use crate::frontend::utils::CubicFCircuit;
+    use crate::transcript::poseidon::poseidon_canonical_config;
+    use crate::FoldingScheme;
+
+    #[test]
+    fn test_decider_circuits() -> Result<(), Error> {
+        let mut rng = ark_std::test_rng();
+        let poseidon_config = poseidon_canonical_config::<Fr>();
+
+        let F_circuit = CubicFCircuit::<Fr>::new(())?;
+        let z_0 = vec![Fr::from(3_u32)];
+
+        type HN = HyperNova<
+            Projective,
+            Projective2,
+            CubicFCircuit<Fr>,
+            Pedersen<Projective>,
+            Pedersen<Projective2>,
+            1,
+            1,
+            false,
+        >;
+
+        let prep_param = PreprocessorParam::<
+            Projective,
+            Projective2,
+            CubicFCircuit<Fr>,
+            Pedersen<Projective>,
+            Pedersen<Projective2>,
+            false,
+        >::new(poseidon_config, F_circuit);
+        let hn_params = HN::preprocess(&mut rng, &prep_param)?;
+
+        // generate a HyperNova instance and do a step of it
+        let mut hypernova = HN::init(&hn_params, F_circuit, z_0.clone())?;
+        hypernova.prove_step(&mut rng, (), None)?;
+        // verify the IVC
+        let ivc_proof = hypernova.ivc_proof();
+        HN::verify(hn_params.1, ivc_proof)?;
+
+        // load the DeciderCircuit 1 & 2 from the HyperNova instance
+        let decider_circuit1 =
+            DeciderCircuit1::<Projective, Projective2>::try_from(hypernova.clone())?;
+        let decider_circuit2 = DeciderCircuit2::<Projective2>::try_from(hypernova)?;
+
+        // generate the constraints of both circuits and check that are satisfied by the inputs
+        let cs1 = ConstraintSystem::<Fr>::new_ref();
+        decider_circuit1.generate_constraints(cs1.clone())?;
+        assert!(cs1.is_satisfied()?);
+        let cs2 = ConstraintSystem::<Fq>::new_ref();
+        decider_circuit2.generate_constraints(cs2.clone())?;
+        assert!(cs2.is_satisfied()?);
+        Ok(())
+    }
+}
@@ -1,4 +1,5 @@
 /// This file implements the HyperNova's onchain (Ethereum's EVM) decider.
+use ark_crypto_primitives::sponge::poseidon::PoseidonSponge;
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
 use ark_snark::SNARK;
 use ark_std::rand::{CryptoRng, RngCore};
@@ -11,32 +12,79 @@ use super::HyperNova;
 use crate::commitment::{
     kzg::Proof as KZGProof, pedersen::Params as PedersenParams, CommitmentScheme,
 };
-use crate::folding::circuits::decider::DeciderEnabledNIFS;
+use crate::folding::circuits::{decider::DeciderEnabledNIFS, CF1};
 use crate::folding::nova::decider_eth::VerifierParam;
 use crate::folding::traits::{Dummy, WitnessOps};
 use crate::frontend::FCircuit;
+use crate::transcript::SpongeWithGadget;
 use crate::{Curve, Error};
 use crate::{Decider as DeciderTrait, FoldingScheme};
 
 #[derive(Debug, Clone, Eq, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(bound(
+        serialize = "CS1::Proof: serde::Serialize",
+        deserialize = "CS1::Proof: serde::Deserialize<'de>"
+    ))
+)]
 pub struct Proof<C1, CS1, S>
 where
     C1: Curve,
     CS1: CommitmentScheme<C1, ProverChallenge = C1::ScalarField, Challenge = C1::ScalarField>,
     S: SNARK<C1::ScalarField>,
 {
+    #[cfg_attr(feature = "serde", serde(with = "crate::utils::json::canonical"))]
     snark_proof: S::Proof,
     kzg_proof: CS1::Proof,
     // rho used at the last fold, U_{i+1}=NIMFS.V(rho, U_i, u_i), it is checked in-circuit
+    #[cfg_attr(feature = "serde", serde(with = "crate::utils::json::field"))]
     rho: C1::ScalarField,
     // the KZG challenge is provided by the prover, but in-circuit it is checked to match
     // the in-circuit computed computed one.
+    #[cfg_attr(feature = "serde", serde(with = "crate::utils::json::field"))]
     kzg_challenge: C1::ScalarField,
 }
 
-/// Onchain Decider, for ethereum use cases
+impl<C1, CS1, S> Proof<C1, CS1, S>
+where
+    C1: Curve,
+    CS1: CommitmentScheme<C1, ProverChallenge = C1::ScalarField, Challenge = C1::ScalarField>,
+    S: SNARK<C1::ScalarField>,
+{
+    pub fn snark_proof(&self) -> &S::Proof {
+        &self.snark_proof
+    }
+
+    pub fn kzg_proof(&self) -> &CS1::Proof {
+        &self.kzg_proof
+    }
+
+    pub fn rho(&self) -> C1::ScalarField {
+        self.rho
+    }
+
+    pub fn kzg_challenge(&self) -> C1::ScalarField {
+        self.kzg_challenge
+    }
+}
+
+/// Onchain Decider, for ethereum use cases, where `SP` is the sponge of the decided HyperNova
+/// instance
 #[derive(Clone, Debug)]
-pub struct Decider<C1, C2, FC, CS1, CS2, S, FS, const MU: usize, const NU: usize> {
+pub struct Decider<
+    C1,
+    C2,
+    FC,
+    CS1,
+    CS2,
+    S,
+    FS,
+    const MU: usize,
+    const NU: usize,
+    SP = PoseidonSponge<CF1<C1>>,
+> {
     _c1: PhantomData<C1>,
     _c2: PhantomData<C2>,
     _fc: PhantomData<FC>,
@@ -44,10 +92,11 @@ pub struct Decider<C1, C2, FC, CS1, CS2, S, FS, const MU: usize, const NU: usize
     _cs2: PhantomData<CS2>,
     _s: PhantomData<S>,
     _fs: PhantomData<FS>,
+    _sp: PhantomData<SP>,
 }
 
-impl<C1, C2, FC, CS1, CS2, S, FS, const MU: usize, const NU: usize> DeciderTrait<C1, C2, FC, FS>
-    for Decider<C1, C2, FC, CS1, CS2, S, FS, MU, NU>
+impl<C1, C2, FC, CS1, CS2, S, FS, const MU: usize, const NU: usize, SP> DeciderTrait<C1, C2, FC, FS>
+    for Decider<C1, C2, FC, CS1, CS2, S, FS, MU, NU, SP>
 where
     C1: Curve<BaseField = C2::ScalarField, ScalarField = C2::BaseField>,
     C2: Curve,
@@ -64,11 +113,12 @@ where
     S: SNARK<C1::ScalarField>,
     FS: FoldingScheme<C1, C2, FC>,
     // constrain FS into HyperNova, since this is a Decider specifically for HyperNova
-    HyperNova<C1, C2, FC, CS1, CS2, MU, NU, false>: From<FS>,
-    crate::folding::hypernova::ProverParams<C1, C2, CS1, CS2, false>:
+    HyperNova<C1, C2, FC, CS1, CS2, MU, NU, false, SP>: From<FS>,
+    crate::folding::hypernova::ProverParams<C1, C2, CS1, CS2, false, SP>:
         From<<FS as FoldingScheme<C1, C2, FC>>::ProverParam>,
-    crate::folding::hypernova::VerifierParams<C1, C2, CS1, CS2, false>:
+    crate::folding::hypernova::VerifierParams<C1, C2, CS1, CS2, false, SP>:
         From<<FS as FoldingScheme<C1, C2, FC>>::VerifierParam>,
+    SP: SpongeWithGadget<C1::ScalarField>,
 {
     type PreprocessorParam = ((FS::ProverParam, FS::VerifierParam), usize);
     type ProverParam = (S::ProvingKey, CS1::ProverParams);
@@ -82,12 +132,12 @@ where
         ((pp, vp), state_len): Self::PreprocessorParam,
     ) -> Result<(Self::ProverParam, Self::VerifierParam), Error> {
         // get the FoldingScheme prover & verifier params from HyperNova
-        let hypernova_pp: <HyperNova<C1, C2, FC, CS1, CS2, MU, NU, false> as FoldingScheme<
+        let hypernova_pp: <HyperNova<C1, C2, FC, CS1, CS2, MU, NU, false, SP> as FoldingScheme<
             C1,
             C2,
             FC,
         >>::ProverParam = pp.into();
-        let hypernova_vp: <HyperNova<C1, C2, FC, CS1, CS2, MU, NU, false> as FoldingScheme<
+        let hypernova_vp: <HyperNova<C1, C2, FC, CS1, CS2, MU, NU, false, SP> as FoldingScheme<
             C1,
             C2,
             FC,
@@ -97,11 +147,11 @@ where
         let s = hypernova_vp.ccs.s;
         let t = hypernova_vp.ccs.t;
 
-        let circuit = DeciderEthCircuit::<C1, C2>::dummy((
+        let circuit = DeciderEthCircuit::<C1, C2, SP>::dummy((
             hypernova_vp.ccs,
             hypernova_vp.cf_r1cs,
             hypernova_pp.cf_cs_pp,
-            hypernova_pp.poseidon_config,
+            hypernova_pp.sponge_config,
             (s, t, MU, NU),
             (),
             state_len,
@@ -118,6 +168,7 @@ where
             pp_hash,
             snark_vp: g16_vk,
             cs_vp: hypernova_vp.cs_vp,
+            _t: PhantomData,
         };
         Ok((pp, vp))
     }
@@ -129,7 +180,7 @@ where
     ) -> Result<Self::Proof, Error> {
         let (snark_pk, cs_pk): (S::ProvingKey, CS1::ProverParams) = pp;
 
-        let circuit = DeciderEthCircuit::<C1, C2>::try_from(HyperNova::from(folding_scheme))?;
+        let circuit = DeciderEthCircuit::<C1, C2, SP>::try_from(HyperNova::from(folding_scheme))?;
 
         let rho = circuit.randomness;
 
@@ -181,6 +232,7 @@ where
             pp_hash,
             snark_vp,
             cs_vp,
+            ..
         } = vp;
 
         // 6.2. Fold the commitments
@@ -1,9 +1,7 @@
 /// This file implements the onchain (Ethereum's EVM) decider circuit. For non-ethereum use cases,
 /// other more efficient approaches can be used.
 use ark_crypto_primitives::sponge::{
-    constraints::CryptographicSpongeVar,
-    poseidon::{constraints::PoseidonSpongeVar, PoseidonSponge},
-    CryptographicSponge,
+    constraints::CryptographicSpongeVar, poseidon::PoseidonSponge, CryptographicSponge,
 };
 use ark_ff::PrimeField;
 use ark_r1cs_std::{
@@ -23,6 +21,7 @@ use super::{
 use crate::folding::circuits::{decider::on_chain::GenericOnchainDeciderCircuit, CF1};
 use crate::folding::traits::{WitnessOps, WitnessVarOps};
 use crate::frontend::FCircuit;
+use crate::transcript::{SpongeWithGadget, TranscriptVar};
 use crate::utils::gadgets::{eval_mle, MatrixGadget};
 use crate::Error;
 use crate::{
@@ -98,7 +97,9 @@ impl<F: PrimeField> WitnessVarOps<F> for WitnessVar<F> {
     }
 }
 
-pub type DeciderEthCircuit<C1, C2> = GenericOnchainDeciderCircuit<
+/// The KZG challenges are always derived in-circuit from the transcript of the last multifold,
+/// which uses the sponge `SP` of the decided HyperNova instance.
+pub type DeciderEthCircuit<C1, C2, SP = PoseidonSponge<CF1<C1>>> = GenericOnchainDeciderCircuit<
     C1,
     C2,
     LCCCS<C1>,
@@ -107,6 +108,8 @@ pub type DeciderEthCircuit<C1, C2> = GenericOnchainDeciderCircuit<
     CCS<CF1<C1>>,
     CCSMatricesVar<CF1<C1>>,
     DeciderHyperNovaGadget,
+    PoseidonSponge<CF1<C1>>,
+    SP,
 >;
 
 impl<
@@ -119,15 +122,16 @@ impl<
         const MU: usize,
         const NU: usize,
         const H: bool,
-    > TryFrom<HyperNova<C1, C2, FC, CS1, CS2, MU, NU, H>> for DeciderEthCircuit<C1, C2>
+        SP: SpongeWithGadget<CF1<C1>>,
+    > TryFrom<HyperNova<C1, C2, FC, CS1, CS2, MU, NU, H, SP>> for DeciderEthCircuit<C1, C2, SP>
 {
     type Error = Error;
 
-    fn try_from(hn: HyperNova<C1, C2, FC, CS1, CS2, MU, NU, H>) -> Result<Self, Error> {
+    fn try_from(hn: HyperNova<C1, C2, FC, CS1, CS2, MU, NU, H, SP>) -> Result<Self, Error> {
         // compute the U_{i+1}, W_{i+1}, by folding the last running & incoming instances
-        let mut transcript = PoseidonSponge::<C1::ScalarField>::new(&hn.poseidon_config);
+        let mut transcript = SP::new(&hn.sponge_config);
         transcript.absorb(&hn.pp_hash);
-        let (nimfs_proof, U_i1, W_i1, rho) = NIMFS::<C1, PoseidonSponge<C1::ScalarField>>::prove(
+        let (nimfs_proof, U_i1, W_i1, rho) = NIMFS::<C1, SP>::prove(
             &mut transcript,
             &hn.ccs,
             &[hn.U_i.clone()],
@@ -149,10 +153,11 @@ impl<
 
         Ok(Self {
             _avar: PhantomData,
+            _t: PhantomData,
             arith: hn.ccs,
             cf_arith: hn.cf_r1cs,
             cf_pedersen_params: hn.cf_cs_pp,
-            poseidon_config: hn.poseidon_config,
+            sponge_config: hn.sponge_config,
             pp_hash: hn.pp_hash,
             i: hn.i,
             z_0: hn.z_0,
@@ -183,9 +188,9 @@ impl<C: Curve> DeciderEnabledNIFS<C, LCCCS<C>, CCCS<C>, Witness<C::ScalarField>,
     type Randomness = CF1<C>;
     type RandomnessDummyCfg = ();
 
-    fn fold_field_elements_gadget(
+    fn fold_field_elements_gadget<S: CryptographicSponge, T: TranscriptVar<CF1<C>, S>>(
         arith: &CCS<CF1<C>>,
-        transcript: &mut PoseidonSpongeVar<CF1<C>>,
+        transcript: &mut T,
         pp_hash: FpVar<CF1<C>>,
         U: LCCCSVar<C>,
         _U_vec: Vec<FpVar<CF1<C>>>,
@@ -20,16 +20,26 @@ use crate::{Curve, Error};
 
 /// Linearized Committed CCS instance
 #[derive(Debug, Clone, Eq, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(bound = "")
+)]
 pub struct LCCCS<C: Curve> {
     // Commitment to witness
+    #[cfg_attr(feature = "serde", serde(with = "crate::utils::json::point"))]
     pub C: C,
     // Relaxation factor of z for folded LCCCS
+    #[cfg_attr(feature = "serde", serde(with = "crate::utils::json::field"))]
     pub u: C::ScalarField,
     // Public input/output
+    #[cfg_attr(feature = "serde", serde(with = "crate::utils::json::field_vec"))]
     pub x: Vec<C::ScalarField>,
     // Random evaluation point for the v_i
+    #[cfg_attr(feature = "serde", serde(with = "crate::utils::json::field_vec"))]
     pub r_x: Vec<C::ScalarField>,
     // Vector of v_i
+    #[cfg_attr(feature = "serde", serde(with = "crate::utils::json::field_vec"))]
     pub v: Vec<C::ScalarField>,
 }
 
@@ -1,21 +1,27 @@
 /// Implements the scheme described in [HyperNova](https://eprint.iacr.org/2023/573.pdf)
-use ark_crypto_primitives::sponge::{
-    poseidon::{PoseidonConfig, PoseidonSponge},
-    CryptographicSponge,
-};
+use ark_crypto_primitives::sponge::{poseidon::PoseidonSponge, CryptographicSponge};
 use ark_ff::{BigInteger, PrimeField};
 use ark_r1cs_std::R1CSVar;
 use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystem};
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Compress, SerializationError};
-use ark_std::{cmp::max, fmt::Debug, marker::PhantomData, rand::RngCore, One, Zero};
+use ark_std::{
+    cmp::max,
+    fmt::{self, Debug, Formatter},
+    marker::PhantomData,
+    rand::RngCore,
+    One, Zero,
+};
 
 pub mod cccs;
 pub mod circuits;
+pub mod decider;
+pub mod decider_circuits;
 pub mod decider_eth;
 pub mod decider_eth_circuit;
 pub mod lcccs;
 pub mod nimfs;
 pub mod utils;
+pub mod zk;
 
 use cccs::CCCS;
 use circuits::AugmentedFCircuit;
@@ -28,18 +34,23 @@ use crate::arith::{
     r1cs::{extract_w_x, R1CS},
     Arith, ArithRelation,
 };
-use crate::commitment::CommitmentScheme;
+use crate::commitment::{CommitmentScheme, CommitmentSchemeId};
 use crate::constants::NOVA_N_BITS_RO;
 use crate::folding::{
-    circuits::cyclefold::{
-        fold_cyclefold_circuit, CycleFoldCircuit, CycleFoldCommittedInstance, CycleFoldConfig,
-        CycleFoldWitness,
+    checkpoint::Checkpoint,
+    circuits::{
+        cyclefold::{
+            fold_cyclefold_circuit, CycleFoldCircuit, CycleFoldCommittedInstance, CycleFoldConfig,
+            CycleFoldWitness,
+        },
+        CF1,
     },
+    envelope::Envelope,
     nova::{get_r1cs_from_cs, PreprocessorParam},
     traits::{CommittedInstanceOps, Dummy, WitnessOps},
 };
 use crate::frontend::FCircuit;
-use crate::transcript::poseidon::poseidon_canonical_config;
+use crate::transcript::SpongeWithGadget;
 use crate::utils::pp_hash;
 use crate::{Curve, Error, FoldingScheme, MultiFolding};
 
@@ -63,8 +74,15 @@ pub type HyperNovaCycleFoldCircuit<C, const MU: usize, const NU: usize> =
 
 /// Witness for the LCCCS & CCCS, containing the w vector, and the r_w used as randomness in the Pedersen commitment.
 #[derive(Debug, Clone, Eq, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(bound = "")
+)]
 pub struct Witness<F: PrimeField> {
+    #[cfg_attr(feature = "serde", serde(with = "crate::utils::json::field_vec"))]
     pub w: Vec<F>,
+    #[cfg_attr(feature = "serde", serde(with = "crate::utils::json::field"))]
     pub r_w: F,
 }
 
@@ -91,16 +109,17 @@ impl<F: PrimeField> WitnessOps<F> for Witness<F> {
 }
 
 /// Proving parameters for HyperNova-based IVC
-#[derive(Debug, Clone)]
-pub struct ProverParams<C1, C2, CS1, CS2, const H: bool>
+#[derive(Clone)]
+pub struct ProverParams<C1, C2, CS1, CS2, const H: bool, T = PoseidonSponge<CF1<C1>>>
 where
     C1: Curve,
     C2: Curve,
     CS1: CommitmentScheme<C1, H>,
     CS2: CommitmentScheme<C2, H>,
+    T: SpongeWithGadget<C1::ScalarField>,
 {
-    /// Poseidon sponge configuration
-    pub poseidon_config: PoseidonConfig<C1::ScalarField>,
+    /// Configuration of the sponge `T`
+    pub sponge_config: T::Config,
     /// Proving parameters of the underlying commitment scheme over C1
     pub cs_pp: CS1::ProverParams,
     /// Proving parameters of the underlying commitment scheme over C2
@@ -110,13 +129,32 @@ where
     pub ccs: Option<CCS<C1::ScalarField>>,
 }
 
+impl<C1, C2, CS1, CS2, const H: bool, T> Debug for ProverParams<C1, C2, CS1, CS2, H, T>
+where
+    C1: Curve,
+    C2: Curve,
+    CS1: CommitmentScheme<C1, H>,
+    CS2: CommitmentScheme<C2, H>,
+    T: SpongeWithGadget<C1::ScalarField>,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ProverParams")
+            .field("sponge_config", &self.sponge_config)
+            .field("cs_pp", &self.cs_pp)
+            .field("cf_cs_pp", &self.cf_cs_pp)
+            .field("ccs", &self.ccs)
+            .finish()
+    }
+}
+
 impl<
         C1: Curve,
         C2: Curve,
         CS1: CommitmentScheme<C1, H>,
         CS2: CommitmentScheme<C2, H>,
         const H: bool,
-    > CanonicalSerialize for ProverParams<C1, C2, CS1, CS2, H>
+        T: SpongeWithGadget<C1::ScalarField>,
+    > CanonicalSerialize for ProverParams<C1, C2, CS1, CS2, H, T>
 {
     fn serialize_with_mode<W: std::io::prelude::Write>(
         &self,
@@ -133,16 +171,17 @@ impl<
 }
 
 /// Verification parameters for HyperNova-based IVC
-#[derive(Debug, Clone)]
+#[derive(Clone)]
 pub struct VerifierParams<
     C1: Curve,
     C2: Curve,
     CS1: CommitmentScheme<C1, H>,
     CS2: CommitmentScheme<C2, H>,
     const H: bool,
+    T: SpongeWithGadget<C1::ScalarField> = PoseidonSponge<CF1<C1>>,
 > {
-    /// Poseidon sponge configuration
-    pub poseidon_config: PoseidonConfig<C1::ScalarField>,
+    /// Configuration of the sponge `T`
+    pub sponge_config: T::Config,
     /// CCS of the Augmented step circuit
     pub ccs: CCS<C1::ScalarField>,
     /// R1CS of the CycleFold circuit
@@ -153,12 +192,33 @@ pub struct VerifierParams<
     pub cf_cs_vp: CS2::VerifierParams,
 }
 
-impl<C1, C2, CS1, CS2, const H: bool> CanonicalSerialize for VerifierParams<C1, C2, CS1, CS2, H>
+impl<C1, C2, CS1, CS2, const H: bool, T> Debug for VerifierParams<C1, C2, CS1, CS2, H, T>
 where
     C1: Curve,
     C2: Curve,
     CS1: CommitmentScheme<C1, H>,
     CS2: CommitmentScheme<C2, H>,
+    T: SpongeWithGadget<C1::ScalarField>,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("VerifierParams")
+            .field("sponge_config", &self.sponge_config)
+            .field("ccs", &self.ccs)
+            .field("cf_r1cs", &self.cf_r1cs)
+            .field("cs_vp", &self.cs_vp)
+            .field("cf_cs_vp", &self.cf_cs_vp)
+            .finish()
+    }
+}
+
+impl<C1, C2, CS1, CS2, const H: bool, T> CanonicalSerialize
+    for VerifierParams<C1, C2, CS1, CS2, H, T>
+where
+    C1: Curve,
+    C2: Curve,
+    CS1: CommitmentScheme<C1, H>,
+    CS2: CommitmentScheme<C2, H>,
+    T: SpongeWithGadget<C1::ScalarField>,
 {
     fn serialize_with_mode<W: std::io::prelude::Write>(
         &self,
@@ -174,33 +234,42 @@ where
     }
 }
 
-impl<C1, C2, CS1, CS2, const H: bool> VerifierParams<C1, C2, CS1, CS2, H>
+impl<C1, C2, CS1, CS2, const H: bool, T> VerifierParams<C1, C2, CS1, CS2, H, T>
 where
     C1: Curve,
     C2: Curve,
     CS1: CommitmentScheme<C1, H>,
     CS2: CommitmentScheme<C2, H>,
+    T: SpongeWithGadget<C1::ScalarField>,
 {
     /// returns the hash of the public parameters of HyperNova
     pub fn pp_hash(&self) -> Result<C1::ScalarField, Error> {
-        pp_hash::<C1, C2, CS1, CS2, H>(
+        pp_hash::<C1, C2, CS1, CS2, H, T>(
             &self.ccs,
             &self.cf_r1cs,
             &self.cs_vp,
             &self.cf_cs_vp,
-            &self.poseidon_config,
+            &self.sponge_config,
         )
     }
 }
 
 #[derive(PartialEq, Eq, Debug, Clone, CanonicalSerialize, CanonicalDeserialize)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(bound = "")
+)]
 pub struct IVCProof<C1, C2>
 where
     C1: Curve,
     C2: Curve,
 {
+    #[cfg_attr(feature = "serde", serde(with = "crate::utils::json::field"))]
     pub i: C1::ScalarField,
+    #[cfg_attr(feature = "serde", serde(with = "crate::utils::json::field_vec"))]
     pub z_0: Vec<C1::ScalarField>,
+    #[cfg_attr(feature = "serde", serde(with = "crate::utils::json::field_vec"))]
     pub z_i: Vec<C1::ScalarField>,
     pub W_i: Witness<C1::ScalarField>,
     pub U_i: LCCCS<C1>,
@@ -217,20 +286,34 @@ where
 /// For multi-instance folding, one needs to specify the const generics below:
 /// * `MU` - the number of LCCCS instances to be folded
 /// * `NU` - the number of CCCS instances to be folded
-#[derive(Clone, Debug)]
-pub struct HyperNova<C1, C2, FC, CS1, CS2, const MU: usize, const NU: usize, const H: bool>
-where
+///
+/// The `T` type parameter selects the sponge used to hash the instances and to derive the
+/// challenges.
+#[derive(Clone)]
+pub struct HyperNova<
+    C1,
+    C2,
+    FC,
+    CS1,
+    CS2,
+    const MU: usize,
+    const NU: usize,
+    const H: bool,
+    T = PoseidonSponge<CF1<C1>>,
+> where
     C1: Curve,
     C2: Curve,
     FC: FCircuit<C1::ScalarField>,
     CS1: CommitmentScheme<C1, H>,
     CS2: CommitmentScheme<C2, H>,
+    T: SpongeWithGadget<C1::ScalarField>,
 {
     /// CCS of the Augmented Function circuit
     pub ccs: CCS<C1::ScalarField>,
     /// R1CS of the CycleFold circuit
     pub cf_r1cs: R1CS<C2::ScalarField>,
-    pub poseidon_config: PoseidonConfig<C1::ScalarField>,
+    /// Configuration of the sponge `T`
+    pub sponge_config: T::Config,
     /// CommitmentScheme::ProverParams over C1
     pub cs_pp: CS1::ProverParams,
     /// CycleFold CommitmentScheme::ProverParams, over C2
@@ -255,8 +338,40 @@ where
     pub cf_U_i: CycleFoldCommittedInstance<C2>,
 }
 
-impl<C1, C2, FC, CS1, CS2, const MU: usize, const NU: usize, const H: bool> MultiFolding<C1, C2, FC>
-    for HyperNova<C1, C2, FC, CS1, CS2, MU, NU, H>
+impl<C1, C2, FC, CS1, CS2, const MU: usize, const NU: usize, const H: bool, T> Debug
+    for HyperNova<C1, C2, FC, CS1, CS2, MU, NU, H, T>
+where
+    C1: Curve,
+    C2: Curve,
+    FC: FCircuit<C1::ScalarField>,
+    CS1: CommitmentScheme<C1, H>,
+    CS2: CommitmentScheme<C2, H>,
+    T: SpongeWithGadget<C1::ScalarField>,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HyperNova")
+            .field("ccs", &self.ccs)
+            .field("cf_r1cs", &self.cf_r1cs)
+            .field("sponge_config", &self.sponge_config)
+            .field("cs_pp", &self.cs_pp)
+            .field("cf_cs_pp", &self.cf_cs_pp)
+            .field("F", &self.F)
+            .field("pp_hash", &self.pp_hash)
+            .field("i", &self.i)
+            .field("z_0", &self.z_0)
+            .field("z_i", &self.z_i)
+            .field("W_i", &self.W_i)
+            .field("U_i", &self.U_i)
+            .field("w_i", &self.w_i)
+            .field("u_i", &self.u_i)
+            .field("cf_W_i", &self.cf_W_i)
+            .field("cf_U_i", &self.cf_U_i)
+            .finish()
+    }
+}
+
+impl<C1, C2, FC, CS1, CS2, const MU: usize, const NU: usize, const H: bool, T>
+    MultiFolding<C1, C2, FC> for HyperNova<C1, C2, FC, CS1, CS2, MU, NU, H, T>
 where
     C1: Curve,
     C2: Curve,
@@ -264,6 +379,7 @@ where
     CS1: CommitmentScheme<C1, H>,
     CS2: CommitmentScheme<C2, H>,
     C1: Curve<BaseField = C2::ScalarField, ScalarField = C2::BaseField>,
+    T: SpongeWithGadget<C1::ScalarField>,
 {
     type RunningInstance = (LCCCS<C1>, Witness<C1::ScalarField>);
     type IncomingInstance = (CCCS<C1>, Witness<C1::ScalarField>);
@@ -314,8 +430,8 @@ where
     }
 }
 
-impl<C1, C2, FC, CS1, CS2, const MU: usize, const NU: usize, const H: bool>
-    HyperNova<C1, C2, FC, CS1, CS2, MU, NU, H>
+impl<C1, C2, FC, CS1, CS2, const MU: usize, const NU: usize, const H: bool, T>
+    HyperNova<C1, C2, FC, CS1, CS2, MU, NU, H, T>
 where
     C1: Curve,
     C2: Curve,
@@ -323,6 +439,7 @@ where
     CS1: CommitmentScheme<C1, H>,
     CS2: CommitmentScheme<C2, H>,
     C1: Curve<BaseField = C2::ScalarField, ScalarField = C2::BaseField>,
+    T: SpongeWithGadget<C1::ScalarField>,
 {
     /// internal helper for new_running_instance & new_incoming_instance methods, returns the R1CS
     /// z=[u,x,w] vector to be used to create the LCCCS & CCCS fresh instances.
@@ -337,7 +454,7 @@ where
         let (_, cf_U_i): (CycleFoldWitness<C2>, CycleFoldCommittedInstance<C2>) =
             self.cf_r1cs.dummy_witness_instance();
 
-        let sponge = PoseidonSponge::<C1::ScalarField>::new(&self.poseidon_config);
+        let sponge = T::new(&self.sponge_config);
 
         u_i.x = vec![
             U_i.hash(
@@ -354,8 +471,8 @@ where
         // compute u_{i+1}.x
         let U_i1 = LCCCS::dummy(&self.ccs);
 
-        let augmented_f_circuit = AugmentedFCircuit::<C1, C2, FC, MU, NU> {
-            poseidon_config: self.poseidon_config.clone(),
+        let augmented_f_circuit = AugmentedFCircuit::<C1, C2, FC, MU, NU, T> {
+            sponge_config: self.sponge_config.clone(),
             ccs: self.ccs.clone(),
             pp_hash: Some(self.pp_hash),
             i: Some(C1::ScalarField::zero()),
@@ -396,8 +513,8 @@ where
     }
 }
 
-impl<C1, C2, FC, CS1, CS2, const MU: usize, const NU: usize, const H: bool>
-    FoldingScheme<C1, C2, FC> for HyperNova<C1, C2, FC, CS1, CS2, MU, NU, H>
+impl<C1, C2, FC, CS1, CS2, const MU: usize, const NU: usize, const H: bool, T>
+    FoldingScheme<C1, C2, FC> for HyperNova<C1, C2, FC, CS1, CS2, MU, NU, H, T>
 where
     C1: Curve,
     C2: Curve,
@@ -405,11 +522,12 @@ where
     CS1: CommitmentScheme<C1, H>,
     CS2: CommitmentScheme<C2, H>,
     C1: Curve<BaseField = C2::ScalarField, ScalarField = C2::BaseField>,
+    T: SpongeWithGadget<C1::ScalarField>,
 {
     /// Reuse Nova's PreprocessorParam.
-    type PreprocessorParam = PreprocessorParam<C1, C2, FC, CS1, CS2, H>;
-    type ProverParam = ProverParams<C1, C2, CS1, CS2, H>;
-    type VerifierParam = VerifierParams<C1, C2, CS1, CS2, H>;
+    type PreprocessorParam = PreprocessorParam<C1, C2, FC, CS1, CS2, H, T>;
+    type ProverParam = ProverParams<C1, C2, CS1, CS2, H, T>;
+    type VerifierParam = VerifierParams<C1, C2, CS1, CS2, H, T>;
     type RunningInstance = (LCCCS<C1>, Witness<C1::ScalarField>);
     type IncomingInstance = (CCCS<C1>, Witness<C1::ScalarField>);
     type MultiCommittedInstanceWithWitness =
@@ -423,15 +541,15 @@ where
         validate: ark_serialize::Validate,
         fc_params: FC::Params,
     ) -> Result<Self::ProverParam, Error> {
-        let poseidon_config = poseidon_canonical_config::<C1::ScalarField>();
+        let sponge_config = T::default_config()?;
 
         // generate the r1cs & cf_r1cs needed for the VerifierParams. In this way we avoid needing
         // to serialize them, saving significant space in the VerifierParams serialized size.
 
         // main circuit R1CS:
         let f_circuit = FC::new(fc_params)?;
-        let augmented_F_circuit = AugmentedFCircuit::<C1, C2, FC, MU, NU>::empty(
-            &poseidon_config,
+        let augmented_F_circuit = AugmentedFCircuit::<C1, C2, FC, MU, NU, T>::empty(
+            &sponge_config,
             f_circuit.clone(),
             None,
         )?;
@@ -441,7 +559,7 @@ where
         let cf_cs_pp = CS2::ProverParams::deserialize_with_mode(&mut reader, compress, validate)?;
 
         Ok(ProverParams {
-            poseidon_config,
+            sponge_config,
             cs_pp,
             cf_cs_pp,
             ccs: Some(ccs),
@@ -454,15 +572,15 @@ where
         validate: ark_serialize::Validate,
         fc_params: FC::Params,
     ) -> Result<Self::VerifierParam, Error> {
-        let poseidon_config = poseidon_canonical_config::<C1::ScalarField>();
+        let sponge_config = T::default_config()?;
 
         // generate the r1cs & cf_r1cs needed for the VerifierParams. In this way we avoid needing
         // to serialize them, saving significant space in the VerifierParams serialized size.
 
         // main circuit R1CS:
         let f_circuit = FC::new(fc_params)?;
-        let augmented_F_circuit = AugmentedFCircuit::<C1, C2, FC, MU, NU>::empty(
-            &poseidon_config,
+        let augmented_F_circuit = AugmentedFCircuit::<C1, C2, FC, MU, NU, T>::empty(
+            &sponge_config,
             f_circuit.clone(),
             None,
         )?;
@@ -476,7 +594,7 @@ where
         let cf_cs_vp = CS2::VerifierParams::deserialize_with_mode(&mut reader, compress, validate)?;
 
         Ok(VerifierParams {
-            poseidon_config,
+            sponge_config,
             ccs,
             cf_r1cs,
             cs_vp,
@@ -492,8 +610,8 @@ where
             return Err(Error::CantBeZero("mu,nu".to_string()));
         }
 
-        let augmented_f_circuit = AugmentedFCircuit::<C1, C2, FC, MU, NU>::empty(
-            &prep_param.poseidon_config,
+        let augmented_f_circuit = AugmentedFCircuit::<C1, C2, FC, MU, NU, T>::empty(
+            &prep_param.sponge_config,
             prep_param.F.clone(),
             None,
         )?;
@@ -520,15 +638,16 @@ where
                 max(cf_r1cs.n_constraints(), cf_r1cs.n_witnesses()),
             )?,
         };
+        let (cs_pp, cf_cs_pp) = prep_param.attach_fixed_base_tables(cs_pp, cf_cs_pp)?;
 
-        let pp = ProverParams::<C1, C2, CS1, CS2, H> {
-            poseidon_config: prep_param.poseidon_config.clone(),
+        let pp = ProverParams::<C1, C2, CS1, CS2, H, T> {
+            sponge_config: prep_param.sponge_config.clone(),
             cs_pp,
             cf_cs_pp,
             ccs: Some(ccs.clone()),
         };
-        let vp = VerifierParams::<C1, C2, CS1, CS2, H> {
-            poseidon_config: prep_param.poseidon_config.clone(),
+        let vp = VerifierParams::<C1, C2, CS1, CS2, H, T> {
+            sponge_config: prep_param.sponge_config.clone(),
             ccs,
             cf_r1cs,
             cs_vp: cs_vp.clone(),
@@ -549,12 +668,12 @@ where
         }
 
         // `sponge` is for digest computation.
-        let sponge = PoseidonSponge::<C1::ScalarField>::new(&pp.poseidon_config);
+        let sponge = T::new(&pp.sponge_config);
 
         // prepare the HyperNova's AugmentedFCircuit and CycleFold's circuits and obtain its CCS
         // and R1CS respectively
-        let augmented_f_circuit = AugmentedFCircuit::<C1, C2, FC, MU, NU>::empty(
-            &pp.poseidon_config,
+        let augmented_f_circuit = AugmentedFCircuit::<C1, C2, FC, MU, NU, T>::empty(
+            &pp.sponge_config,
             F.clone(),
             pp.ccs.clone(),
         )?;
@@ -583,7 +702,7 @@ where
         Ok(Self {
             ccs,
             cf_r1cs,
-            poseidon_config: pp.poseidon_config.clone(),
+            sponge_config: pp.sponge_config.clone(),
             cs_pp: pp.cs_pp.clone(),
             cf_cs_pp: pp.cf_cs_pp.clone(),
             F,
@@ -661,7 +780,7 @@ where
             (vec![], vec![], vec![], vec![])
         };
 
-        let augmented_f_circuit: AugmentedFCircuit<C1, C2, FC, MU, NU>;
+        let augmented_f_circuit: AugmentedFCircuit<C1, C2, FC, MU, NU, T>;
 
         if self.z_i.len() != self.F.state_len() {
             return Err(Error::NotSameLength(
@@ -699,8 +818,8 @@ where
             W_i1.r_w = self.W_i.r_w;
             U_i1 = LCCCS::dummy(&self.ccs);
 
-            augmented_f_circuit = AugmentedFCircuit::<C1, C2, FC, MU, NU> {
-                poseidon_config: self.poseidon_config.clone(),
+            augmented_f_circuit = AugmentedFCircuit::<C1, C2, FC, MU, NU, T> {
+                sponge_config: self.sponge_config.clone(),
                 ccs: self.ccs.clone(),
                 pp_hash: Some(self.pp_hash),
                 i: Some(C1::ScalarField::zero()),
@@ -722,8 +841,7 @@ where
                 cf_cmT: None,
             };
         } else {
-            let mut transcript_p: PoseidonSponge<C1::ScalarField> =
-                PoseidonSponge::<C1::ScalarField>::new(&self.poseidon_config);
+            let mut transcript_p = T::new(&self.sponge_config);
             transcript_p.absorb(&self.pp_hash);
 
             let (all_Us, all_us, all_Ws, all_ws) = (
@@ -734,7 +852,7 @@ where
             );
 
             let (rho, nimfs_proof);
-            (nimfs_proof, U_i1, W_i1, rho) = NIMFS::<C1, PoseidonSponge<C1::ScalarField>>::prove(
+            (nimfs_proof, U_i1, W_i1, rho) = NIMFS::<C1, T>::prove(
                 &mut transcript_p,
                 &self.ccs,
                 &all_Us,
@@ -773,8 +891,8 @@ where
                     &mut rng,
                 )?;
 
-            augmented_f_circuit = AugmentedFCircuit::<C1, C2, FC, MU, NU> {
-                poseidon_config: self.poseidon_config.clone(),
+            augmented_f_circuit = AugmentedFCircuit::<C1, C2, FC, MU, NU, T> {
+                sponge_config: self.sponge_config.clone(),
                 ccs: self.ccs.clone(),
                 pp_hash: Some(self.pp_hash),
                 i: Some(self.i),
@@ -881,8 +999,8 @@ where
         let (pp, vp) = params;
 
         let f_circuit = FC::new(fcircuit_params)?;
-        let augmented_f_circuit = AugmentedFCircuit::<C1, C2, FC, MU, NU>::empty(
-            &pp.poseidon_config,
+        let augmented_f_circuit = AugmentedFCircuit::<C1, C2, FC, MU, NU, T>::empty(
+            &pp.sponge_config,
             f_circuit.clone(),
             None,
         )?;
@@ -894,7 +1012,7 @@ where
         Ok(Self {
             ccs,
             cf_r1cs,
-            poseidon_config: pp.poseidon_config,
+            sponge_config: pp.sponge_config,
             cs_pp: pp.cs_pp,
             cf_cs_pp: pp.cf_cs_pp,
             F: f_circuit,
@@ -933,7 +1051,7 @@ where
             return Ok(());
         }
         // `sponge` is for digest computation.
-        let sponge = PoseidonSponge::<C1::ScalarField>::new(&vp.poseidon_config);
+        let sponge = T::new(&vp.sponge_config);
 
         if u_i.x.len() != 2 || U_i.x.len() != 2 {
             return Err(Error::IVCVerificationFail);
@@ -965,10 +1083,54 @@ where
     }
 }
 
+impl<C1, C2, FC, CS1, CS2, const MU: usize, const NU: usize, const H: bool, T>
+    Checkpoint<C1, C2, FC> for HyperNova<C1, C2, FC, CS1, CS2, MU, NU, H, T>
+where
+    C1: Curve,
+    C2: Curve,
+    FC: FCircuit<C1::ScalarField>,
+    CS1: CommitmentScheme<C1, H>,
+    CS2: CommitmentScheme<C2, H>,
+    C1: Curve<BaseField = C2::ScalarField, ScalarField = C2::BaseField>,
+    T: SpongeWithGadget<C1::ScalarField>,
+{
+    fn pp_hash(&self) -> C1::ScalarField {
+        self.pp_hash
+    }
+}
+
+impl<C1, C2, FC, CS1, CS2, const MU: usize, const NU: usize, const H: bool, T> Envelope<C1, C2, FC>
+    for HyperNova<C1, C2, FC, CS1, CS2, MU, NU, H, T>
+where
+    C1: Curve,
+    C2: Curve,
+    FC: FCircuit<C1::ScalarField>,
+    CS1: CommitmentScheme<C1, H> + CommitmentSchemeId,
+    CS2: CommitmentScheme<C2, H> + CommitmentSchemeId,
+    C1: Curve<BaseField = C2::ScalarField, ScalarField = C2::BaseField>,
+    T: SpongeWithGadget<C1::ScalarField>,
+{
+    fn scheme_id() -> String {
+        format!("hypernova-mu{}-nu{}-{}", MU, NU, T::ID)
+    }
+
+    fn commitment_ids() -> [(String, bool); 2] {
+        [
+            (CS1::ID.to_string(), CS1::is_hiding()),
+            (CS2::ID.to_string(), CS2::is_hiding()),
+        ]
+    }
+
+    fn vp_pp_hash(vp: &Self::VerifierParam) -> Result<C1::ScalarField, Error> {
+        vp.pp_hash()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::commitment::kzg::KZG;
     use ark_bn254::{Bn254, Fr, G1Projective as Projective};
+    use ark_crypto_primitives::sponge::poseidon::PoseidonConfig;
     use ark_grumpkin::Projective as Projective2;
     use ark_std::UniformRand;
 
@@ -984,20 +1146,25 @@ mod tests {
         let F_circuit = CubicFCircuit::<Fr>::new(())?;
 
         // run the test using Pedersen commitments on both sides of the curve cycle
-        let _ = test_ivc_opt::<Pedersen<Projective>, Pedersen<Projective2>, false>(
+        let _ = test_ivc_opt::<Pedersen<Projective>, Pedersen<Projective2>, 2, 3, false>(
             poseidon_config.clone(),
             F_circuit,
+            3,
         )?;
 
-        let _ = test_ivc_opt::<Pedersen<Projective, true>, Pedersen<Projective2, true>, true>(
+        let _ = test_ivc_opt::<Pedersen<Projective, true>, Pedersen<Projective2, true>, 2, 3, true>(
             poseidon_config.clone(),
             F_circuit,
+            3,
         )?;
 
         // run the test using KZG for the commitments on the main curve, and Pedersen for the
         // commitments on the secondary curve
-        let _ =
-            test_ivc_opt::<KZG<Bn254>, Pedersen<Projective2>, false>(poseidon_config, F_circuit)?;
+        let _ = test_ivc_opt::<KZG<Bn254>, Pedersen<Projective2>, 2, 3, false>(
+            poseidon_config,
+            F_circuit,
+            3,
+        )?;
         Ok(())
     }
 
@@ -1006,17 +1173,18 @@ mod tests {
     pub fn test_ivc_opt<
         CS1: CommitmentScheme<Projective, H>,
         CS2: CommitmentScheme<Projective2, H>,
+        const MU: usize,
+        const NU: usize,
         const H: bool,
     >(
         poseidon_config: PoseidonConfig<Fr>,
         F_circuit: CubicFCircuit<Fr>,
-    ) -> Result<(), Error> {
+        num_steps: usize,
+    ) -> Result<HyperNova<Projective, Projective2, CubicFCircuit<Fr>, CS1, CS2, MU, NU, H>, Error>
+    {
         let mut rng = ark_std::test_rng();
 
-        const MU: usize = 2;
-        const NU: usize = 3;
-
-        type HN<CS1, CS2, const H: bool> =
+        type HN<CS1, CS2, const MU: usize, const NU: usize, const H: bool> =
             HyperNova<Projective, Projective2, CubicFCircuit<Fr>, CS1, CS2, MU, NU, H>;
 
         let prep_param =
@@ -1024,10 +1192,11 @@ mod tests {
                 poseidon_config.clone(),
                 F_circuit,
             );
-        let hypernova_params = HN::preprocess(&mut rng, &prep_param)?;
+        let hypernova_params = HN::<CS1, CS2, MU, NU, H>::preprocess(&mut rng, &prep_param)?;
 
         let z_0 = vec![Fr::from(3_u32)];
-        let mut hypernova = HN::init(&hypernova_params, F_circuit, z_0.clone())?;
+        let mut hypernova =
+            HN::<CS1, CS2, MU, NU, H>::init(&hypernova_params, F_circuit, z_0.clone())?;
 
         let (w_i_blinding, W_i_blinding) = if H {
             (Fr::rand(&mut rng), Fr::rand(&mut rng))
@@ -1037,7 +1206,6 @@ mod tests {
         hypernova.w_i.r_w = w_i_blinding;
         hypernova.W_i.r_w = W_i_blinding;
 
-        let num_steps: usize = 3;
         for _ in 0..num_steps {
             // prepare some new instances to fold in the multifolding step
             let mut lcccs = vec![];
@@ -1058,10 +1226,10 @@ mod tests {
         assert_eq!(Fr::from(num_steps as u32), hypernova.i);
 
         let ivc_proof = hypernova.ivc_proof();
-        HN::verify(
+        HN::<CS1, CS2, MU, NU, H>::verify(
             hypernova_params.1.clone(), // verifier_params
             ivc_proof,
         )?;
-        Ok(())
+        Ok(hypernova)
     }
 }
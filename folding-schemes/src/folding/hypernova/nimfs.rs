@@ -10,7 +10,7 @@ use super::{
     Witness,
 };
 use crate::arith::{ccs::CCS, Arith};
-use crate::constants::NOVA_N_BITS_RO;
+use crate::constants::{HYPERNOVA_NIMFS_DOMAIN, NOVA_N_BITS_RO};
 use crate::folding::circuits::CF1;
 use crate::folding::traits::Dummy;
 use crate::transcript::Transcript;
@@ -197,8 +197,9 @@ impl<C: Curve, T: Transcript<C::ScalarField>> NIMFS<C, T> {
         Error,
     > {
         // absorb instances to transcript
-        transcript.absorb(&running_instances);
-        transcript.absorb(&new_instances);
+        transcript.domain_separator(HYPERNOVA_NIMFS_DOMAIN);
+        transcript.absorb_labelled(b"running_instances", &running_instances);
+        transcript.absorb_labelled(b"new_instances", &new_instances);
 
         if running_instances.is_empty() {
             return Err(Error::Empty);
@@ -231,11 +232,9 @@ impl<C: Curve, T: Transcript<C::ScalarField>> NIMFS<C, T> {
         }
 
         // Step 1: Get some challenges
-        let gamma_scalar = C::ScalarField::from_le_bytes_mod_order(b"gamma");
-        let beta_scalar = C::ScalarField::from_le_bytes_mod_order(b"beta");
-        transcript.absorb(&gamma_scalar);
+        transcript.absorb_label(b"gamma");
         let gamma: C::ScalarField = transcript.get_challenge();
-        transcript.absorb(&beta_scalar);
+        transcript.absorb_label(b"beta");
         let beta: Vec<C::ScalarField> = transcript.get_challenges(ccs.s);
 
         // Compute g(x)
@@ -252,8 +251,7 @@ impl<C: Curve, T: Transcript<C::ScalarField>> NIMFS<C, T> {
         let sigmas_thetas = compute_sigmas_thetas(ccs, &z_lcccs, &z_cccs, &r_x_prime)?;
 
         // Step 6: Get the folding challenge
-        let rho_scalar = C::ScalarField::from_le_bytes_mod_order(b"rho");
-        transcript.absorb(&rho_scalar);
+        transcript.absorb_label(b"rho");
         let rho_bits: Vec<bool> = transcript.get_challenge_nbits(NOVA_N_BITS_RO);
         let rho: C::ScalarField = C::ScalarField::from(
             <C::ScalarField as PrimeField>::BigInt::from_bits_le(&rho_bits),
@@ -292,9 +290,23 @@ impl<C: Curve, T: Transcript<C::ScalarField>> NIMFS<C, T> {
         new_instances: &[CCCS<C>],
         proof: NIMFSProof<C>,
     ) -> Result<LCCCS<C>, Error> {
+        Self::verify_with_rho(transcript, ccs, running_instances, new_instances, proof)
+            .map(|(folded_lcccs, _)| folded_lcccs)
+    }
+
+    /// Same as [`NIMFS::verify`], but it also returns the folding challenge `rho`, which the
+    /// decider circuits take as public input.
+    pub fn verify_with_rho(
+        transcript: &mut impl Transcript<C::ScalarField>,
+        ccs: &CCS<C::ScalarField>,
+        running_instances: &[LCCCS<C>],
+        new_instances: &[CCCS<C>],
+        proof: NIMFSProof<C>,
+    ) -> Result<(LCCCS<C>, C::ScalarField), Error> {
         // absorb instances to transcript
-        transcript.absorb(&running_instances);
-        transcript.absorb(&new_instances);
+        transcript.domain_separator(HYPERNOVA_NIMFS_DOMAIN);
+        transcript.absorb_labelled(b"running_instances", &running_instances);
+        transcript.absorb_labelled(b"new_instances", &new_instances);
 
         if running_instances.is_empty() {
             return Err(Error::Empty);
@@ -304,12 +316,10 @@ impl<C: Curve, T: Transcript<C::ScalarField>> NIMFS<C, T> {
         }
 
         // Step 1: Get some challenges
-        let gamma_scalar = C::ScalarField::from_le_bytes_mod_order(b"gamma");
-        transcript.absorb(&gamma_scalar);
+        transcript.absorb_label(b"gamma");
         let gamma: C::ScalarField = transcript.get_challenge();
 
-        let beta_scalar = C::ScalarField::from_le_bytes_mod_order(b"beta");
-        transcript.absorb(&beta_scalar);
+        transcript.absorb_label(b"beta");
         let beta: Vec<C::ScalarField> = transcript.get_challenges(ccs.s);
 
         let vp_aux_info = VPAuxInfo::<C::ScalarField> {
@@ -372,19 +382,21 @@ impl<C: Curve, T: Transcript<C::ScalarField>> NIMFS<C, T> {
         }
 
         // Step 6: Get the folding challenge
-        let rho_scalar = C::ScalarField::from_le_bytes_mod_order(b"rho");
-        transcript.absorb(&rho_scalar);
+        transcript.absorb_label(b"rho");
         let rho_bits: Vec<bool> = transcript.get_challenge_nbits(NOVA_N_BITS_RO);
         let rho: C::ScalarField = C::ScalarField::from(
             <C::ScalarField as PrimeField>::BigInt::from_bits_le(&rho_bits),
         );
 
         // Step 7: Compute the folded instance
-        Ok(Self::fold(
-            running_instances,
-            new_instances,
-            &proof.sigmas_thetas,
-            r_x_prime,
+        Ok((
+            Self::fold(
+                running_instances,
+                new_instances,
+                &proof.sigmas_thetas,
+                r_x_prime,
+                rho,
+            ),
             rho,
         ))
     }
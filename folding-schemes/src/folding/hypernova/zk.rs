@@ -0,0 +1,991 @@
+//! Implements a zero-knowledge layer for HyperNova's IVC proofs, covering the Use-case-3 described
+//! in [`crate::folding::nova::zk`], i.e. the randomization of the last IVC proof so that it can be
+//! handed to a verifier (or to a server computing the final proof) without revealing the
+//! witnesses of the running instance.
+//!
+//! Since the LCCCS relation is linear in `z = (u, x, w)` for a fixed evaluation point, the running
+//! instance `U_i` is first masked by taking a random linear combination `U_m = U_i + rho * U_r`
+//! with a random satisfying LCCCS `U_r` that shares the same `r_x`, so that the witness of `U_m`
+//! is uniformly distributed. The last incoming CCCS `u_i` is then multifolded into `U_m`, hence
+//! the sumcheck messages and the `sigmas` of the published NIMFS proof, and the folded witness,
+//! only depend on the masked witness.
+//!
+//! Notice that the NIMFS verifier needs the `thetas` of `u_i` (i.e., the evaluations of
+//! `M_j z_i` at the random point `r_x'` obtained from the sumcheck) to check the sumcheck claim,
+//! so they can not be masked and [`RandomizedIVCProof`] still reveals these `t` field elements.
+//! When this is not acceptable, the [`RandomizedDecider`] can be used, which checks the masking
+//! and the multifolding in-circuit, so that the NIMFS proof stays in the SNARK witness.
+use ark_crypto_primitives::sponge::{
+    constraints::CryptographicSpongeVar, poseidon::PoseidonSponge, CryptographicSponge,
+};
+use ark_ff::{BigInteger, PrimeField};
+use ark_r1cs_std::{alloc::AllocVar, boolean::Boolean, eq::EqGadget, fields::fp::FpVar};
+use ark_relations::r1cs::SynthesisError;
+use ark_snark::SNARK;
+use ark_std::{
+    marker::PhantomData,
+    rand::{CryptoRng, RngCore},
+    One, UniformRand, Zero,
+};
+
+use super::{
+    cccs::CCCS,
+    circuits::{CCCSVar, LCCCSVar, NIMFSGadget, ProofVar as NIMFSProofVar},
+    decider::{ProverParam, VerifierParam},
+    decider_circuits::DeciderCircuit2,
+    lcccs::LCCCS,
+    nimfs::{NIMFSProof, NIMFS},
+    HyperNova, ProverParams, VerifierParams, Witness,
+};
+use crate::{
+    arith::{
+        ccs::{circuits::CCSMatricesVar, CCS},
+        r1cs::R1CS,
+        Arith, ArithRelation,
+    },
+    commitment::{CommitmentScheme, EvaluationProof},
+    folding::{
+        circuits::{
+            cyclefold::{CycleFoldCommittedInstance, CycleFoldWitness},
+            decider::{
+                off_chain::GenericOffchainDeciderCircuit1, DeciderChallenge, DeciderEnabledNIFS,
+                KZGChallengesGadget,
+            },
+            nonnative::affine::NonNativeAffineVar,
+            CF1,
+        },
+        traits::{CommittedInstanceOps, Dummy, Inputize, InputizeNonNative, WitnessOps},
+    },
+    frontend::FCircuit,
+    transcript::{
+        poseidon::poseidon_canonical_config, SpongeWithGadget, Transcript, TranscriptVar,
+    },
+    utils::vec::{vec_add, vec_scalar_mul},
+    Curve, Error,
+};
+
+pub struct RandomizedIVCProof<C1: Curve, C2: Curve> {
+    pub U_i: LCCCS<C1>,
+    pub u_i: CCCS<C1>,
+    pub U_r: LCCCS<C1>,
+    pub nimfs_proof: NIMFSProof<C1>, // proof of multifolding u_i into U_i + rho * U_r
+    pub W_i_prime: Witness<C1::ScalarField>,
+    pub cf_U_i: CycleFoldCommittedInstance<C2>,
+    pub cf_W_i: CycleFoldWitness<C2>,
+}
+
+impl<C1: Curve, C2: Curve> RandomizedIVCProof<C1, C2> {
+    /// Compute a zero-knowledge proof of a HyperNova IVC proof
+    pub fn new<
+        FC: FCircuit<C1::ScalarField>,
+        CS1: CommitmentScheme<C1, true>,
+        CS2: CommitmentScheme<C2, true>,
+        const MU: usize,
+        const NU: usize,
+        T: SpongeWithGadget<C1::ScalarField>,
+    >(
+        hypernova: &HyperNova<C1, C2, FC, CS1, CS2, MU, NU, true, T>,
+        mut rng: impl RngCore,
+    ) -> Result<RandomizedIVCProof<C1, C2>, Error>
+    where
+        C1: Curve<BaseField = C2::ScalarField, ScalarField = C2::BaseField>,
+    {
+        let mut transcript = T::new(&hypernova.sponge_config);
+        transcript.absorb(&hypernova.pp_hash);
+
+        // 1. Sample a satisfying LCCCS instance-witness pair (U_r, W_r) at the same evaluation
+        // point as U_i
+        let (U_r, W_r) = sample_lcccs::<C1, CS1>(
+            &hypernova.ccs,
+            &hypernova.cs_pp,
+            hypernova.U_i.r_x.clone(),
+            &mut rng,
+        )?;
+
+        // 2. Mask the running instance-witness pair (U_i, W_i) with (U_r, W_r)
+        transcript.absorb(&hypernova.U_i);
+        transcript.absorb(&U_r);
+        let rho = transcript.get_challenge();
+        let U_m = blind_lcccs(&hypernova.U_i, &U_r, rho)?;
+        let W_m = Witness {
+            w: vec_add(&hypernova.W_i.w, &vec_scalar_mul(&W_r.w, &rho))?,
+            r_w: hypernova.W_i.r_w + rho * W_r.r_w,
+        };
+
+        // 3. Multifold the masked instance-witness pair (U_m, W_m) with the incoming one
+        // (u_i, w_i)
+        let (nimfs_proof, _, W_i_prime, _) = NIMFS::<C1, T>::prove(
+            &mut transcript,
+            &hypernova.ccs,
+            &[U_m],
+            &[hypernova.u_i.clone()],
+            &[W_m],
+            &[hypernova.w_i.clone()],
+        )?;
+
+        Ok(RandomizedIVCProof {
+            U_i: hypernova.U_i.clone(),
+            u_i: hypernova.u_i.clone(),
+            U_r,
+            nimfs_proof,
+            W_i_prime,
+            cf_U_i: hypernova.cf_U_i.clone(),
+            cf_W_i: hypernova.cf_W_i.clone(),
+        })
+    }
+
+    /// Recomputes the challenge `rho`, masks `U_i` with it and multifolds `u_i` into the masked
+    /// instance. Returns `rho`, the folded instance and the folding challenge of NIMFS.
+    /// `transcript` must have absorbed `pp_hash` already.
+    fn fold<T: Transcript<C1::ScalarField>>(
+        &self,
+        ccs: &CCS<C1::ScalarField>,
+        transcript: &mut T,
+    ) -> Result<(C1::ScalarField, LCCCS<C1>, C1::ScalarField), Error> {
+        // Check that U_r is evaluated at the same point as U_i, so that their linear combination
+        // is a valid LCCCS
+        if self.U_r.r_x != self.U_i.r_x {
+            return Err(Error::zkIVCVerificationFail);
+        }
+        transcript.absorb(&self.U_i);
+        transcript.absorb(&self.U_r);
+        let rho = transcript.get_challenge();
+        let U_m = blind_lcccs(&self.U_i, &self.U_r, rho)?;
+
+        let (U_f, nimfs_rho) = NIMFS::<C1, T>::verify_with_rho(
+            transcript,
+            ccs,
+            &[U_m],
+            &[self.u_i.clone()],
+            self.nimfs_proof.clone(),
+        )?;
+        Ok((rho, U_f, nimfs_rho))
+    }
+
+    /// Verify a zero-knowledge proof of a HyperNova IVC proof
+    #[allow(clippy::too_many_arguments)]
+    pub fn verify<T: SpongeWithGadget<C1::ScalarField>>(
+        ccs: &CCS<C1::ScalarField>,
+        cf_r1cs: &R1CS<C2::ScalarField>,
+        pp_hash: C1::ScalarField,
+        sponge_config: &T::Config,
+        i: C1::ScalarField,
+        z_0: Vec<C1::ScalarField>,
+        z_i: Vec<C1::ScalarField>,
+        proof: &RandomizedIVCProof<C1, C2>,
+    ) -> Result<(), Error>
+    where
+        C1: Curve<BaseField = C2::ScalarField, ScalarField = C2::BaseField>,
+    {
+        // Handles case where i=0
+        if i == C1::ScalarField::zero() {
+            if z_0 == z_i {
+                return Ok(());
+            } else {
+                return Err(Error::zkIVCVerificationFail);
+            }
+        }
+
+        // 1. Check that u_i.x is correct - including the cyclefold running instance
+        // a. Check length
+        if proof.u_i.x.len() != 2 || proof.U_i.x.len() != 2 {
+            return Err(Error::IVCVerificationFail);
+        }
+
+        // b. Check computed hashes are correct
+        let sponge = T::new(sponge_config);
+        let expected_u_i_x = proof.U_i.hash(&sponge, pp_hash, i, &z_0, &z_i);
+        if expected_u_i_x != proof.u_i.x[0] {
+            return Err(Error::zkIVCVerificationFail);
+        }
+
+        let expected_cf_u_i_x = proof.cf_U_i.hash_cyclefold(&sponge, pp_hash);
+        if expected_cf_u_i_x != proof.u_i.x[1] {
+            return Err(Error::IVCVerificationFail);
+        }
+
+        // 2. Obtain the U^{\prime}_i folded instance, i.e., multifold u_i into U_i + rho * U_r
+        let mut transcript = T::new(sponge_config);
+        transcript.absorb(&pp_hash);
+        let (_, U_i_prime, _) = proof.fold(ccs, &mut transcript)?;
+
+        // 3. Check that W^{\prime}_i is a satisfying witness
+        ccs.check_relation(&proof.W_i_prime, &U_i_prime)?;
+
+        // 4. Check that the cyclefold instance-witness pair satisfies the cyclefold relaxed r1cs
+        cf_r1cs.check_relation(&proof.cf_W_i, &proof.cf_U_i)?;
+
+        Ok(())
+    }
+}
+
+/// Samples a random LCCCS instance-witness pair evaluated at the given point `r_x`, where all of
+/// `u`, `x` and `w` are random.
+fn sample_lcccs<C: Curve, CS: CommitmentScheme<C, true>>(
+    ccs: &CCS<CF1<C>>,
+    cs_pp: &CS::ProverParams,
+    r_x: Vec<CF1<C>>,
+    mut rng: impl RngCore,
+) -> Result<(LCCCS<C>, Witness<CF1<C>>), Error> {
+    let w = (0..ccs.n_witnesses())
+        .map(|_| CF1::<C>::rand(&mut rng))
+        .collect::<Vec<_>>();
+    let r_w = CF1::<C>::rand(&mut rng);
+    let W = Witness { w, r_w };
+
+    let mut U = LCCCS {
+        C: CS::commit(cs_pp, &W.w, &W.r_w)?,
+        u: CF1::<C>::rand(&mut rng),
+        x: (0..ccs.n_public_inputs())
+            .map(|_| CF1::<C>::rand(&mut rng))
+            .collect(),
+        r_x,
+        v: vec![],
+    };
+    U.v = ccs.eval_relation(&W, &U)?;
+
+    Ok((U, W))
+}
+
+/// Computes `U + rho * U_r` for two LCCCS instances evaluated at the same point.
+fn blind_lcccs<C: Curve>(U: &LCCCS<C>, U_r: &LCCCS<C>, rho: CF1<C>) -> Result<LCCCS<C>, Error> {
+    Ok(LCCCS {
+        C: U.C + U_r.C * rho,
+        u: U.u + rho * U_r.u,
+        x: vec_add(&U.x, &vec_scalar_mul(&U_r.x, &rho))?,
+        r_x: U.r_x.clone(),
+        v: vec_add(&U.v, &vec_scalar_mul(&U_r.v, &rho))?,
+    })
+}
+
+/// The masking instance `U_r` and the NIMFS proof of a [`RandomizedIVCProof`], which are
+/// witnesses of the decider circuit.
+#[derive(Debug, Clone)]
+pub struct MaskedFoldingProof<C: Curve> {
+    pub U_r: LCCCS<C>,
+    pub nimfs_proof: NIMFSProof<C>,
+}
+
+impl<C: Curve> Dummy<(usize, usize, usize)> for MaskedFoldingProof<C> {
+    fn dummy((io_len, s, t): (usize, usize, usize)) -> Self {
+        Self {
+            U_r: LCCCS {
+                C: C::zero(),
+                u: Zero::zero(),
+                x: vec![Zero::zero(); io_len],
+                r_x: vec![Zero::zero(); s],
+                v: vec![Zero::zero(); t],
+            },
+            // the decider only folds the masked running instance and the incoming one, i.e.
+            // mu = nu = 1
+            nimfs_proof: NIMFSProof::dummy((s, t, 1, 1)),
+        }
+    }
+}
+
+/// The public values of the fold done by [`RandomizedDeciderGadget`]: the masking challenge
+/// `rho_m`, the commitment `C_m = U_i.C + rho_m * U_r.C` of the masked instance and the folding
+/// challenge `rho` of NIMFS.
+#[derive(Debug, Clone)]
+pub struct MaskedFoldingRandomness<C: Curve> {
+    pub rho_m: CF1<C>,
+    pub C_m: C,
+    pub rho: CF1<C>,
+}
+
+impl<C: Curve> Default for MaskedFoldingRandomness<C> {
+    fn default() -> Self {
+        Self {
+            rho_m: Zero::zero(),
+            C_m: C::zero(),
+            rho: Zero::zero(),
+        }
+    }
+}
+
+/// Same as [`super::decider_eth_circuit::DeciderHyperNovaGadget`], but it masks the running
+/// instance with `U_r` before multifolding the incoming instance into it, as done by
+/// [`RandomizedIVCProof`].
+pub struct RandomizedDeciderGadget;
+
+impl<C: Curve> DeciderEnabledNIFS<C, LCCCS<C>, CCCS<C>, Witness<C::ScalarField>, CCS<CF1<C>>>
+    for RandomizedDeciderGadget
+{
+    type ProofDummyCfg = (usize, usize, usize);
+    type Proof = MaskedFoldingProof<C>;
+    type Randomness = MaskedFoldingRandomness<C>;
+    type RandomnessDummyCfg = ();
+
+    fn fold_field_elements_gadget<S: CryptographicSponge, T: TranscriptVar<CF1<C>, S>>(
+        arith: &CCS<CF1<C>>,
+        transcript: &mut T,
+        pp_hash: FpVar<CF1<C>>,
+        U: LCCCSVar<C>,
+        _U_vec: Vec<FpVar<CF1<C>>>,
+        u: CCCSVar<C>,
+        proof: Self::Proof,
+        randomness: Self::Randomness,
+    ) -> Result<LCCCSVar<C>, SynthesisError> {
+        let cs = transcript.cs();
+        let U_r = LCCCSVar::<C> {
+            C: NonNativeAffineVar::new_input(cs.clone(), || Ok(proof.U_r.C))?,
+            u: FpVar::new_witness(cs.clone(), || Ok(proof.U_r.u))?,
+            x: Vec::new_witness(cs.clone(), || Ok(proof.U_r.x))?,
+            r_x: U.r_x.clone(),
+            v: Vec::new_witness(cs.clone(), || Ok(proof.U_r.v))?,
+        };
+        let nimfs_proof = NIMFSProofVar::<C>::new_witness(cs.clone(), || Ok(proof.nimfs_proof))?;
+        let rho_m = FpVar::new_input(cs.clone(), || Ok(randomness.rho_m))?;
+        // `C_m` is checked by the verifier, who computes it from `U_i.C` and `U_r.C`
+        let C_m = NonNativeAffineVar::new_input(cs.clone(), || Ok(randomness.C_m))?;
+        let rho = FpVar::new_input(cs.clone(), || Ok(randomness.rho))?;
+
+        transcript.absorb(&pp_hash)?;
+        transcript.absorb(&U)?;
+        transcript.absorb(&U_r)?;
+        transcript.get_challenge()?.enforce_equal(&rho_m)?;
+
+        let U_m = LCCCSVar::<C> {
+            C: C_m,
+            u: &U.u + &rho_m * &U_r.u,
+            x: U.x
+                .iter()
+                .zip(&U_r.x)
+                .map(|(x, x_r)| x + &rho_m * x_r)
+                .collect(),
+            v: U.v
+                .iter()
+                .zip(&U_r.v)
+                .map(|(v, v_r)| v + &rho_m * v_r)
+                .collect(),
+            r_x: U.r_x,
+        };
+
+        let (U_next, rho_bits) = NIMFSGadget::<C>::verify(
+            cs.clone(),
+            arith,
+            transcript,
+            &[U_m],
+            &[u],
+            nimfs_proof,
+            Boolean::TRUE, // enabled
+        )?;
+        Boolean::le_bits_to_fp(&rho_bits)?.enforce_equal(&rho)?;
+        Ok(U_next)
+    }
+
+    /// `U_commitments` are the commitments of the masked running instance, i.e., `[C_m]`.
+    fn fold_group_elements_native(
+        U_commitments: &[C],
+        u_commitments: &[C],
+        _: Option<Self::Proof>,
+        randomness: Self::Randomness,
+    ) -> Result<Vec<C>, Error> {
+        Ok(vec![U_commitments[0] + u_commitments[0] * randomness.rho])
+    }
+}
+
+/// Circuit that implements part of the in-circuit checks needed for the offchain verification of
+/// a [`RandomizedIVCProof`] over the Curve2's BaseField (=Curve1's ScalarField).
+pub type RandomizedDeciderCircuit1<C1, C2, P = CF1<C1>, SP = PoseidonSponge<CF1<C1>>> =
+    GenericOffchainDeciderCircuit1<
+        C1,
+        C2,
+        LCCCS<C1>,
+        CCCS<C1>,
+        Witness<CF1<C1>>,
+        CCS<CF1<C1>>,
+        CCSMatricesVar<CF1<C1>>,
+        RandomizedDeciderGadget,
+        P,
+        SP,
+    >;
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct RandomizedDeciderProof<C1, C2, CS1, CS2, S1, S2>
+where
+    C1: Curve,
+    C2: Curve,
+    CS1: CommitmentScheme<C1, true>,
+    CS2: CommitmentScheme<C2, true>,
+    S1: SNARK<C1::ScalarField>,
+    S2: SNARK<C2::ScalarField>,
+{
+    c1_snark_proof: S1::Proof,
+    c2_snark_proof: S2::Proof,
+    cs1_proofs: [CS1::Proof; 1],
+    cs2_proofs: [CS2::Proof; 2],
+    // the commitment of the masking instance U_r
+    C_r: C1,
+    // the challenge used to mask U_i with U_r, it is checked in-circuit
+    rho_m: C1::ScalarField,
+    // rho used at the last fold, U_{i+1}=NIMFS.V(rho, U_i + rho_m * U_r, u_i), it is checked
+    // in-circuit
+    rho: C1::ScalarField,
+    // cyclefold committed instance
+    cf_U_final: CycleFoldCommittedInstance<C2>,
+    // the CS challenges are provided by the prover, but in-circuit they are checked to match the
+    // in-circuit computed computed ones.
+    cs1_challenges: [CS1::Challenge; 1],
+    cs2_challenges: [CS2::Challenge; 2],
+}
+
+/// Offchain Decider for [`RandomizedIVCProof`]s. Unlike [`super::decider::Decider`], its prover
+/// only needs the randomized proof, so it can be run by a party that must not learn the
+/// witnesses of the folded steps, and the commitments are opened in hiding mode. `SP` is the
+/// sponge of the HyperNova instance from which the proofs are obtained.
+#[derive(Clone, Debug)]
+pub struct RandomizedDecider<C1, C2, CS1, CS2, S1, S2, SP = PoseidonSponge<CF1<C1>>> {
+    _c1: PhantomData<C1>,
+    _c2: PhantomData<C2>,
+    _cs1: PhantomData<CS1>,
+    _cs2: PhantomData<CS2>,
+    _s1: PhantomData<S1>,
+    _s2: PhantomData<S2>,
+    _sp: PhantomData<SP>,
+}
+
+impl<C1, C2, CS1, CS2, S1, S2, SP> RandomizedDecider<C1, C2, CS1, CS2, S1, S2, SP>
+where
+    C1: Curve<BaseField = C2::ScalarField, ScalarField = C2::BaseField>,
+    C2: Curve,
+    CS1: CommitmentScheme<C1, true>,
+    CS1::ProverChallenge: DeciderChallenge<C1::ScalarField>,
+    CS1::Challenge: DeciderChallenge<C1::ScalarField>,
+    CS1::Proof: EvaluationProof<C1::ScalarField>,
+    CS2: CommitmentScheme<C2, true>,
+    CS2::ProverChallenge: DeciderChallenge<C2::ScalarField>,
+    CS2::Challenge: DeciderChallenge<C2::ScalarField>,
+    CS2::Proof: EvaluationProof<C2::ScalarField>,
+    S1: SNARK<C1::ScalarField>,
+    S2: SNARK<C2::ScalarField>,
+    SP: SpongeWithGadget<C1::ScalarField>,
+{
+    #[allow(clippy::type_complexity)]
+    pub fn preprocess(
+        mut rng: impl RngCore + CryptoRng,
+        hn_pp: ProverParams<C1, C2, CS1, CS2, true, SP>,
+        hn_vp: VerifierParams<C1, C2, CS1, CS2, true, SP>,
+        state_len: usize,
+    ) -> Result<
+        (
+            ProverParam<CS1::ProverParams, S1::ProvingKey, CS2::ProverParams, S2::ProvingKey>,
+            VerifierParam<
+                C1,
+                CS1::VerifierParams,
+                S1::VerifyingKey,
+                CS2::VerifierParams,
+                S2::VerifyingKey,
+            >,
+        ),
+        Error,
+    > {
+        let pp_hash = hn_vp.pp_hash()?;
+
+        let io_len = hn_vp.ccs.n_public_inputs();
+        let s = hn_vp.ccs.s;
+        let t = hn_vp.ccs.t;
+
+        // `circuit2` uses the canonical Poseidon config on `C2`'s scalar field, see
+        // `DeciderCircuit2::try_from`
+        let poseidon_config2 = poseidon_canonical_config::<C2::ScalarField>();
+
+        let circuit1 = RandomizedDeciderCircuit1::<C1, C2, CS1::Challenge, SP>::dummy((
+            hn_vp.ccs,
+            &hn_vp.cf_r1cs,
+            hn_vp.sponge_config,
+            (io_len, s, t),
+            (),
+            state_len,
+            1, // HyperNova's LCCCS contains 1 commitment
+        ));
+        let circuit2 = DeciderCircuit2::<C2, CS2::Challenge>::dummy((
+            hn_vp.cf_r1cs,
+            poseidon_config2,
+            2, // CycleFold's running CommittedInstance contains 2 commitments
+        ));
+
+        let (c1_snark_pp, c1_snark_vp) = S1::circuit_specific_setup(circuit1, &mut rng)
+            .map_err(|e| Error::SNARKSetupFail(e.to_string()))?;
+        let (c2_snark_pp, c2_snark_vp) = S2::circuit_specific_setup(circuit2, &mut rng)
+            .map_err(|e| Error::SNARKSetupFail(e.to_string()))?;
+
+        let pp = ProverParam {
+            c1_snark_pp,
+            c1_cs_pp: hn_pp.cs_pp,
+            c2_snark_pp,
+            c2_cs_pp: hn_pp.cf_cs_pp,
+        };
+        let vp = VerifierParam {
+            pp_hash,
+            c1_snark_vp,
+            c1_cs_vp: hn_vp.cs_vp,
+            c2_snark_vp,
+            c2_cs_vp: hn_vp.cf_cs_vp,
+        };
+        Ok((pp, vp))
+    }
+
+    pub fn prove(
+        mut rng: impl RngCore + CryptoRng,
+        pp: &ProverParam<CS1::ProverParams, S1::ProvingKey, CS2::ProverParams, S2::ProvingKey>,
+        hn_vp: &VerifierParams<C1, C2, CS1, CS2, true, SP>,
+        i: C1::ScalarField,
+        z_0: Vec<C1::ScalarField>,
+        z_i: Vec<C1::ScalarField>,
+        proof: &RandomizedIVCProof<C1, C2>,
+    ) -> Result<RandomizedDeciderProof<C1, C2, CS1, CS2, S1, S2>, Error> {
+        let pp_hash = hn_vp.pp_hash()?;
+
+        // recompute U_{i+1} = NIMFS.V(U_i + rho_m * U_r, u_i), whose witness is W_i_prime
+        let mut transcript = SP::new(&hn_vp.sponge_config);
+        transcript.absorb(&pp_hash);
+        let (rho_m, U_i1, rho) = proof.fold(&hn_vp.ccs, &mut transcript)?;
+        let W_i1 = proof.W_i_prime.clone();
+
+        let openings = W_i1.get_openings();
+        let lens = openings.iter().map(|(v, _)| v.len()).collect::<Vec<_>>();
+        let c1_kzg_challenges = KZGChallengesGadget::get_opening_challenges_native::<
+            C1,
+            _,
+            _,
+            CS1::Challenge,
+        >(&mut transcript, &U_i1, &lens);
+        let c1_kzg_evaluations = openings
+            .iter()
+            .zip(&c1_kzg_challenges)
+            .map(|((v, _), c)| c.evaluate_native(v))
+            .collect::<Result<Vec<_>, _>>()?;
+        let c1_kzg_proofs = openings
+            .iter()
+            .zip(&c1_kzg_challenges)
+            .map(|((v, r), c)| {
+                let c = CS1::ProverChallenge::from_elements(c.to_elements())?;
+                CS1::prove_with_challenge(&pp.c1_cs_pp, c, v, r, Some(&mut rng))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let circuit1 = RandomizedDeciderCircuit1::<C1, C2, CS1::Challenge, SP> {
+            _avar: PhantomData,
+            arith: hn_vp.ccs.clone(),
+            sponge_config: hn_vp.sponge_config.clone(),
+            pp_hash,
+            i,
+            z_0,
+            z_i,
+            U_i: proof.U_i.clone(),
+            // the witnesses of `U_i` and `u_i` are not used by the circuit
+            W_i: Witness::dummy(&hn_vp.ccs),
+            u_i: proof.u_i.clone(),
+            w_i: Witness::dummy(&hn_vp.ccs),
+            U_i1,
+            W_i1,
+            proof: MaskedFoldingProof {
+                U_r: proof.U_r.clone(),
+                nimfs_proof: proof.nimfs_proof.clone(),
+            },
+            randomness: MaskedFoldingRandomness {
+                rho_m,
+                C_m: proof.U_i.C + proof.U_r.C * rho_m,
+                rho,
+            },
+            cf_U_i: proof.cf_U_i.clone(),
+            kzg_challenges: c1_kzg_challenges.clone(),
+            kzg_evaluations: c1_kzg_evaluations,
+        };
+
+        // `circuit2` uses the canonical Poseidon config on `C2`'s scalar field, as in
+        // `DeciderCircuit2::try_from`
+        let poseidon_config2 = poseidon_canonical_config::<C2::ScalarField>();
+        let pp_hash_Fq =
+            C2::ScalarField::from_le_bytes_mod_order(&pp_hash.into_bigint().to_bytes_le());
+        let mut transcript = PoseidonSponge::<C2::ScalarField>::new(&poseidon_config2);
+        transcript.absorb(&pp_hash_Fq);
+
+        let openings = proof.cf_W_i.get_openings();
+        let lens = openings.iter().map(|(v, _)| v.len()).collect::<Vec<_>>();
+        let c2_kzg_challenges = KZGChallengesGadget::get_opening_challenges_native::<
+            C2,
+            _,
+            _,
+            CS2::Challenge,
+        >(&mut transcript, &proof.cf_U_i, &lens);
+        let c2_kzg_evaluations = openings
+            .iter()
+            .zip(&c2_kzg_challenges)
+            .map(|((v, _), c)| c.evaluate_native(v))
+            .collect::<Result<Vec<_>, _>>()?;
+        let c2_kzg_proofs = openings
+            .iter()
+            .zip(&c2_kzg_challenges)
+            .map(|((v, r), c)| {
+                let c = CS2::ProverChallenge::from_elements(c.to_elements())?;
+                CS2::prove_with_challenge(&pp.c2_cs_pp, c, v, r, Some(&mut rng))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let circuit2 = DeciderCircuit2::<C2, CS2::Challenge> {
+            cf_arith: hn_vp.cf_r1cs.clone(),
+            poseidon_config: poseidon_config2,
+            pp_hash: pp_hash_Fq,
+            cf_U_i: proof.cf_U_i.clone(),
+            cf_W_i: proof.cf_W_i.clone(),
+            kzg_challenges: c2_kzg_challenges.clone(),
+            kzg_evaluations: c2_kzg_evaluations,
+        };
+
+        let c1_snark_proof = S1::prove(&pp.c1_snark_pp, circuit1, &mut rng)
+            .map_err(|e| Error::Other(e.to_string()))?;
+        let c2_snark_proof = S2::prove(&pp.c2_snark_pp, circuit2, &mut rng)
+            .map_err(|e| Error::Other(e.to_string()))?;
+
+        Ok(RandomizedDeciderProof {
+            c1_snark_proof,
+            c2_snark_proof,
+            cs1_proofs: c1_kzg_proofs
+                .try_into()
+                .map_err(|e: Vec<_>| Error::NotExpectedLength(e.len(), 1))?,
+            cs2_proofs: c2_kzg_proofs
+                .try_into()
+                .map_err(|e: Vec<_>| Error::NotExpectedLength(e.len(), 2))?,
+            C_r: proof.U_r.C,
+            rho_m,
+            rho,
+            cf_U_final: proof.cf_U_i.clone(),
+            cs1_challenges: c1_kzg_challenges
+                .try_into()
+                .map_err(|e: Vec<_>| Error::NotExpectedLength(e.len(), 1))?,
+            cs2_challenges: c2_kzg_challenges
+                .try_into()
+                .map_err(|e: Vec<_>| Error::NotExpectedLength(e.len(), 2))?,
+        })
+    }
+
+    pub fn verify(
+        vp: &VerifierParam<
+            C1,
+            CS1::VerifierParams,
+            S1::VerifyingKey,
+            CS2::VerifierParams,
+            S2::VerifyingKey,
+        >,
+        i: C1::ScalarField,
+        z_0: Vec<C1::ScalarField>,
+        z_i: Vec<C1::ScalarField>,
+        running_commitments: &[C1],
+        incoming_commitments: &[C1],
+        proof: &RandomizedDeciderProof<C1, C2, CS1, CS2, S1, S2>,
+    ) -> Result<bool, Error> {
+        if i <= C1::ScalarField::one() {
+            return Err(Error::NotEnoughSteps);
+        }
+
+        // fold the commitments of the masked running instance and of the incoming instance
+        let C_m = running_commitments[0] + proof.C_r * proof.rho_m;
+        let randomness = MaskedFoldingRandomness {
+            rho_m: proof.rho_m,
+            C_m,
+            rho: proof.rho,
+        };
+        let U_final_commitments = RandomizedDeciderGadget::fold_group_elements_native(
+            &[C_m],
+            incoming_commitments,
+            None,
+            randomness,
+        )?;
+        let cf_U = &proof.cf_U_final;
+
+        // snark proof 1, the inputs allocated by `RandomizedDeciderGadget` come last
+        let c1_public_input = [
+            &[vp.pp_hash, i][..],
+            &z_0,
+            &z_i,
+            &U_final_commitments.inputize_nonnative(),
+            &cf_U.inputize_nonnative(),
+            &proof
+                .cs1_challenges
+                .iter()
+                .flat_map(|c| c.to_elements())
+                .collect::<Vec<_>>(),
+            &proof
+                .cs1_proofs
+                .iter()
+                .map(|p| p.evaluation())
+                .collect::<Vec<_>>(),
+            &proof.C_r.inputize_nonnative(),
+            &[proof.rho_m],
+            &C_m.inputize_nonnative(),
+            &[proof.rho],
+        ]
+        .concat();
+
+        let c1_snark_v = S1::verify(&vp.c1_snark_vp, &c1_public_input, &proof.c1_snark_proof)
+            .map_err(|e| Error::Other(e.to_string()))?;
+        if !c1_snark_v {
+            return Err(Error::SNARKVerificationFail);
+        }
+
+        // snark proof 2
+        let pp_hash_Fq =
+            C2::ScalarField::from_le_bytes_mod_order(&vp.pp_hash.into_bigint().to_bytes_le());
+        let c2_public_input: Vec<C2::ScalarField> = [
+            &[pp_hash_Fq][..],
+            &cf_U.inputize(),
+            &proof
+                .cs2_challenges
+                .iter()
+                .flat_map(|c| c.to_elements())
+                .collect::<Vec<_>>(),
+            &proof
+                .cs2_proofs
+                .iter()
+                .map(|p| p.evaluation())
+                .collect::<Vec<_>>(),
+        ]
+        .concat();
+
+        let c2_snark_v = S2::verify(&vp.c2_snark_vp, &c2_public_input, &proof.c2_snark_proof)
+            .map_err(|e| Error::Other(e.to_string()))?;
+        if !c2_snark_v {
+            return Err(Error::SNARKVerificationFail);
+        }
+
+        // check C1 commitments (main instance commitments)
+        for ((cm, c), pi) in U_final_commitments
+            .iter()
+            .zip(&proof.cs1_challenges)
+            .zip(&proof.cs1_proofs)
+        {
+            CS1::verify_with_challenge(&vp.c1_cs_vp, c.clone(), cm, pi)?;
+        }
+
+        // check C2 commitments (CycleFold instance commitments)
+        for ((cm, c), pi) in cf_U
+            .get_commitments()
+            .iter()
+            .zip(&proof.cs2_challenges)
+            .zip(&proof.cs2_proofs)
+        {
+            CS2::verify_with_challenge(&vp.c2_cs_vp, c.clone(), cm, pi)?;
+        }
+
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::commitment::pedersen::Pedersen;
+    use crate::folding::hypernova::tests::test_ivc_opt;
+    use crate::frontend::utils::CubicFCircuit;
+    use crate::transcript::poseidon::poseidon_canonical_config;
+    use ark_bn254::{Fr, G1Projective as Projective};
+    use ark_grumpkin::Projective as Projective2;
+    use rand::rngs::OsRng;
+
+    // Tests zk proof generation and verification for a valid HyperNova IVC proof
+    #[test]
+    fn test_zk_hypernova_ivc() -> Result<(), Error> {
+        let mut rng = OsRng;
+        let poseidon_config = poseidon_canonical_config::<Fr>();
+        let F_circuit = CubicFCircuit::<Fr>::new(())?;
+        let hypernova =
+            test_ivc_opt::<Pedersen<Projective, true>, Pedersen<Projective2, true>, 2, 3, true>(
+                poseidon_config,
+                F_circuit,
+                3,
+            )?;
+
+        let proof = RandomizedIVCProof::new(&hypernova, &mut rng)?;
+        RandomizedIVCProof::verify::<PoseidonSponge<Fr>>(
+            &hypernova.ccs,
+            &hypernova.cf_r1cs,
+            hypernova.pp_hash,
+            &hypernova.sponge_config,
+            hypernova.i,
+            hypernova.z_0,
+            hypernova.z_i,
+            &proof,
+        )?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_zk_hypernova_when_i_is_zero() -> Result<(), Error> {
+        let mut rng = OsRng;
+        let poseidon_config = poseidon_canonical_config::<Fr>();
+        let F_circuit = CubicFCircuit::<Fr>::new(())?;
+        let hypernova =
+            test_ivc_opt::<Pedersen<Projective, true>, Pedersen<Projective2, true>, 2, 3, true>(
+                poseidon_config,
+                F_circuit,
+                0,
+            )?;
+
+        let proof = RandomizedIVCProof::new(&hypernova, &mut rng)?;
+        RandomizedIVCProof::verify::<PoseidonSponge<Fr>>(
+            &hypernova.ccs,
+            &hypernova.cf_r1cs,
+            hypernova.pp_hash,
+            &hypernova.sponge_config,
+            hypernova.i,
+            hypernova.z_0,
+            hypernova.z_i,
+            &proof,
+        )?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_zk_hypernova_verification_fails_with_wrong_running_instance() -> Result<(), Error> {
+        let mut rng = OsRng;
+        let poseidon_config = poseidon_canonical_config::<Fr>();
+        let F_circuit = CubicFCircuit::<Fr>::new(())?;
+        let mut hypernova =
+            test_ivc_opt::<Pedersen<Projective, true>, Pedersen<Projective2, true>, 2, 3, true>(
+                poseidon_config,
+                F_circuit,
+                3,
+            )?;
+
+        // proof verification fails with incorrect running instance
+        let (U_r, _) = sample_lcccs::<Projective, Pedersen<Projective, true>>(
+            &hypernova.ccs,
+            &hypernova.cs_pp,
+            hypernova.U_i.r_x.clone(),
+            &mut rng,
+        )?;
+        hypernova.U_i = U_r;
+        let incorrect_proof = RandomizedIVCProof::new(&hypernova, &mut rng)?;
+        let verify = RandomizedIVCProof::verify::<PoseidonSponge<Fr>>(
+            &hypernova.ccs,
+            &hypernova.cf_r1cs,
+            hypernova.pp_hash,
+            &hypernova.sponge_config,
+            hypernova.i,
+            hypernova.z_0,
+            hypernova.z_i,
+            &incorrect_proof,
+        );
+        assert!(verify.is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_zk_hypernova_verification_fails_with_wrong_running_witness() -> Result<(), Error> {
+        let mut rng = OsRng;
+        let poseidon_config = poseidon_canonical_config::<Fr>();
+        let F_circuit = CubicFCircuit::<Fr>::new(())?;
+        let mut hypernova =
+            test_ivc_opt::<Pedersen<Projective, true>, Pedersen<Projective2, true>, 2, 3, true>(
+                poseidon_config,
+                F_circuit,
+                3,
+            )?;
+
+        // proof verification fails with incorrect running witness
+        let (_, W_r) = sample_lcccs::<Projective, Pedersen<Projective, true>>(
+            &hypernova.ccs,
+            &hypernova.cs_pp,
+            hypernova.U_i.r_x.clone(),
+            &mut rng,
+        )?;
+        hypernova.W_i = W_r;
+        let incorrect_proof = RandomizedIVCProof::new(&hypernova, &mut rng)?;
+        let verify = RandomizedIVCProof::verify::<PoseidonSponge<Fr>>(
+            &hypernova.ccs,
+            &hypernova.cf_r1cs,
+            hypernova.pp_hash,
+            &hypernova.sponge_config,
+            hypernova.i,
+            hypernova.z_0,
+            hypernova.z_i,
+            &incorrect_proof,
+        );
+        assert!(verify.is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_randomized_decider() -> Result<(), Error> {
+        use crate::commitment::ipa::IPA;
+        use crate::folding::nova::PreprocessorParam;
+        use crate::FoldingScheme;
+        use ark_groth16::Groth16;
+        // Note: do not use the MNTx_298 curves in practice, these are just for tests. Use the
+        // MNTx_753 curves instead.
+        use ark_mnt4_298::{Fr, G1Projective as Projective, MNT4_298 as MNT4};
+        use ark_mnt6_298::{G1Projective as Projective2, MNT6_298 as MNT6};
+
+        type HN = HyperNova<
+            Projective,
+            Projective2,
+            CubicFCircuit<Fr>,
+            IPA<Projective, true>,
+            IPA<Projective2, true>,
+            1,
+            1,
+            true,
+        >;
+        type D = RandomizedDecider<
+            Projective,
+            Projective2,
+            IPA<Projective, true>,
+            IPA<Projective2, true>,
+            Groth16<MNT4>,
+            Groth16<MNT6>,
+        >;
+
+        let mut rng = OsRng;
+        let poseidon_config = poseidon_canonical_config::<Fr>();
+        let F_circuit = CubicFCircuit::<Fr>::new(())?;
+
+        let hn_params = HN::preprocess(
+            &mut rng,
+            &PreprocessorParam::new(poseidon_config, F_circuit),
+        )?;
+        let mut hypernova = HN::init(&hn_params, F_circuit, vec![Fr::from(3_u32)])?;
+        hypernova.prove_step(&mut rng, (), None)?;
+        hypernova.prove_step(&mut rng, (), None)?;
+
+        // the decider prover only gets the randomized proof
+        let randomized_proof = RandomizedIVCProof::new(&hypernova, &mut rng)?;
+        let (hn_pp, hn_vp) = hn_params;
+        let (decider_pp, decider_vp) =
+            D::preprocess(&mut rng, hn_pp, hn_vp.clone(), F_circuit.state_len())?;
+        let proof = D::prove(
+            &mut rng,
+            &decider_pp,
+            &hn_vp,
+            hypernova.i,
+            hypernova.z_0.clone(),
+            hypernova.z_i.clone(),
+            &randomized_proof,
+        )?;
+
+        let verified = D::verify(
+            &decider_vp,
+            hypernova.i,
+            hypernova.z_0.clone(),
+            hypernova.z_i.clone(),
+            &randomized_proof.U_i.get_commitments(),
+            &randomized_proof.u_i.get_commitments(),
+            &proof,
+        )?;
+        assert!(verified);
+
+        // a proof for a different final state must be rejected
+        assert!(D::verify(
+            &decider_vp,
+            hypernova.i,
+            hypernova.z_0,
+            vec![Fr::zero()],
+            &randomized_proof.U_i.get_commitments(),
+            &randomized_proof.u_i.get_commitments(),
+            &proof,
+        )
+        .is_err());
+        Ok(())
+    }
+}
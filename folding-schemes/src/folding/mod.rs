@@ -1,6 +1,10 @@
+pub mod checkpoint;
 pub mod circuits;
+pub mod envelope;
 pub mod hypernova;
+pub mod mova;
 pub mod nova;
+pub mod ova;
 pub mod protogalaxy;
 pub mod traits;
 
@@ -51,8 +55,8 @@ pub mod tests {
         test_serialize_ivc_opt::<G1, G2, FC, HN>("hypernova".to_string(), prep_param)?;
 
         // test ProtoGalaxy
-        type P = ProtoGalaxy<G1, G2, FC, Pedersen<G1>, Pedersen<G2>>;
-        let prep_param = (poseidon_config, f_circuit);
+        type P = ProtoGalaxy<G1, G2, FC, Pedersen<G1>, Pedersen<G2>, 1>;
+        let prep_param = NovaPreprocessorParam::new(poseidon_config, f_circuit);
         test_serialize_ivc_opt::<G1, G2, FC, P>("protogalaxy".to_string(), prep_param)?;
         Ok(())
     }
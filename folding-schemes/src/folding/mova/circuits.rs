@@ -0,0 +1,372 @@
+/// contains [Mova](https://eprint.iacr.org/2024/1220.pdf) IVC related circuits
+use ark_crypto_primitives::sponge::{
+    constraints::CryptographicSpongeVar,
+    poseidon::{constraints::PoseidonSpongeVar, PoseidonConfig, PoseidonSponge},
+};
+use ark_ff::PrimeField;
+use ark_r1cs_std::{
+    alloc::AllocVar,
+    boolean::Boolean,
+    eq::EqGadget,
+    fields::{fp::FpVar, FieldVar},
+    prelude::CurveVar,
+    R1CSVar,
+};
+use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystemRef, SynthesisError};
+use ark_std::{One, Zero};
+
+use super::MovaCycleFoldConfig;
+use crate::folding::circuits::{
+    cyclefold::{
+        CycleFoldChallengeGadget, CycleFoldCommittedInstance, CycleFoldCommittedInstanceVar,
+        CycleFoldConfig, NIFSFullGadget,
+    },
+    nonnative::{affine::NonNativeAffineVar, uint::NonNativeUintVar},
+    CF1,
+};
+use crate::folding::nova::nifs::{
+    mova::{CommittedInstance, Proof},
+    mova_circuits::{CommittedInstanceVar, NIFSGadget, ProofVar},
+    NIFSGadgetTrait,
+};
+use crate::folding::traits::{CommittedInstanceVarOps, Dummy};
+use crate::frontend::FCircuit;
+use crate::transcript::TranscriptVar;
+use crate::Curve;
+
+/// Generates the constraints that derive the evaluation point `rE` of an incoming instance from
+/// its public inputs `x` and its commitment `cmW`. Compatible with the native
+/// [`super::incoming_rE`].
+pub fn incoming_rE_gadget<C: Curve>(
+    sponge: &PoseidonSpongeVar<CF1<C>>,
+    pp_hash: &FpVar<CF1<C>>,
+    x: &[FpVar<CF1<C>>],
+    cmW: &NonNativeAffineVar<C>,
+    n_vars: usize,
+) -> Result<Vec<FpVar<CF1<C>>>, SynthesisError> {
+    let mut sponge = sponge.clone();
+    sponge.absorb(pp_hash)?;
+    sponge.absorb(&x)?;
+    sponge.absorb_nonnative(cmW)?;
+    sponge.get_challenges(n_vars)
+}
+
+/// `AugmentedFCircuit` enhances the original step function `F`, so that it can
+/// be used in recursive arguments such as IVC.
+///
+/// It follows the same structure as Nova's
+/// [`AugmentedFCircuit`](crate::folding::nova::circuits::AugmentedFCircuit),
+/// but the primary instances are Mova's `CommittedInstance`s, which are folded
+/// with Mova's NIFS verifier.
+///
+/// Since Mova does not commit to the error term nor to the cross term, the only
+/// commitment to be folded is `cmW`, and thus a single CycleFold instance is
+/// folded at each step. On the other hand, Mova's folding challenge is a full
+/// field element, so the CycleFold circuit uses all of its bits.
+///
+/// `n_vars` is the number of variables of the MLE of the error term, i.e., the
+/// log2 of the number of constraints of this circuit, which determines the
+/// length of the evaluation points `rE`.
+#[derive(Debug, Clone)]
+pub struct AugmentedFCircuit<C1: Curve, C2: Curve, FC: FCircuit<CF1<C1>>> {
+    pub(super) poseidon_config: PoseidonConfig<CF1<C1>>,
+    pub(super) n_vars: usize,
+    pub(super) pp_hash: Option<CF1<C1>>,
+    pub(super) i: Option<CF1<C1>>,
+    pub(super) i_usize: Option<usize>,
+    pub(super) z_0: Option<Vec<C1::ScalarField>>,
+    pub(super) z_i: Option<Vec<C1::ScalarField>>,
+    pub(super) external_inputs: Option<FC::ExternalInputs>,
+    pub(super) u_i_cmW: Option<C1>,
+    pub(super) U_i: Option<CommittedInstance<C1>>,
+    pub(super) U_i1_cmW: Option<C1>,
+    pub(super) proof: Option<Proof<C1>>,
+    pub(super) F: FC, // F circuit
+
+    // cyclefold verifier on C1
+    pub(super) cf_u_i_cmW: Option<C2>, // input
+    pub(super) cf_U_i: Option<CycleFoldCommittedInstance<C2>>, // input
+    pub(super) cf_cmT: Option<C2>,
+}
+
+impl<C1: Curve, C2: Curve, FC: FCircuit<CF1<C1>>> AugmentedFCircuit<C1, C2, FC> {
+    pub fn empty(poseidon_config: &PoseidonConfig<CF1<C1>>, F_circuit: FC, n_vars: usize) -> Self {
+        Self {
+            poseidon_config: poseidon_config.clone(),
+            n_vars,
+            pp_hash: None,
+            i: None,
+            i_usize: None,
+            z_0: None,
+            z_i: None,
+            external_inputs: None,
+            u_i_cmW: None,
+            U_i: None,
+            U_i1_cmW: None,
+            proof: None,
+            F: F_circuit,
+            // cyclefold values
+            cf_u_i_cmW: None,
+            cf_U_i: None,
+            cf_cmT: None,
+        }
+    }
+}
+
+impl<C1, C2, FC> AugmentedFCircuit<C1, C2, FC>
+where
+    C1: Curve<BaseField = C2::ScalarField, ScalarField = C2::BaseField>,
+    C2: Curve,
+    FC: FCircuit<CF1<C1>>,
+{
+    pub fn compute_next_state(
+        self,
+        cs: ConstraintSystemRef<CF1<C1>>,
+    ) -> Result<Vec<FpVar<CF1<C1>>>, SynthesisError> {
+        let pp_hash = FpVar::<CF1<C1>>::new_witness(cs.clone(), || {
+            Ok(self.pp_hash.unwrap_or_else(CF1::<C1>::zero))
+        })?;
+        let i = FpVar::<CF1<C1>>::new_witness(cs.clone(), || {
+            Ok(self.i.unwrap_or_else(CF1::<C1>::zero))
+        })?;
+        let z_0 = Vec::<FpVar<CF1<C1>>>::new_witness(cs.clone(), || {
+            Ok(self
+                .z_0
+                .unwrap_or(vec![CF1::<C1>::zero(); self.F.state_len()]))
+        })?;
+        let z_i = Vec::<FpVar<CF1<C1>>>::new_witness(cs.clone(), || {
+            Ok(self
+                .z_i
+                .unwrap_or(vec![CF1::<C1>::zero(); self.F.state_len()]))
+        })?;
+        let external_inputs = FC::ExternalInputsVar::new_witness(cs.clone(), || {
+            Ok(self.external_inputs.unwrap_or_default())
+        })?;
+
+        let u_dummy = CommittedInstance {
+            rE: vec![CF1::<C1>::zero(); self.n_vars],
+            ..CommittedInstance::dummy(2)
+        };
+        let U_i = CommittedInstanceVar::<C1>::new_witness(cs.clone(), || {
+            Ok(self.U_i.unwrap_or(u_dummy.clone()))
+        })?;
+        let U_i1_cmW = NonNativeAffineVar::new_witness(cs.clone(), || {
+            Ok(self.U_i1_cmW.unwrap_or_else(C1::zero))
+        })?;
+        let proof = ProofVar::<C1>::new_witness(cs.clone(), || {
+            Ok(self.proof.unwrap_or(Proof::dummy(self.n_vars)))
+        })?;
+
+        let cf_u_dummy = CycleFoldCommittedInstance::dummy(MovaCycleFoldConfig::<C1>::IO_LEN);
+        let cf_U_i = CycleFoldCommittedInstanceVar::<C2>::new_witness(cs.clone(), || {
+            Ok(self.cf_U_i.unwrap_or(cf_u_dummy.clone()))
+        })?;
+        let cf_cmT = C2::Var::new_witness(cs.clone(), || Ok(self.cf_cmT.unwrap_or_else(C2::zero)))?;
+
+        // `sponge` is for digest computation.
+        let sponge = PoseidonSpongeVar::<C1::ScalarField>::new(cs.clone(), &self.poseidon_config);
+        // `transcript` is for challenge generation.
+        let mut transcript = sponge.clone();
+
+        let is_basecase = i.is_zero()?;
+
+        // Primary Part
+        // P.1. Compute u_i.x
+        // u_i.x[0] = H(i, z_0, z_i, U_i)
+        let (u_i_x, U_i_vec) = U_i.clone().hash(&sponge, &pp_hash, &i, &z_0, &z_i)?;
+        // u_i.x[1] = H(cf_U_i)
+        let (cf_u_i_x, cf_U_i_vec) = cf_U_i.clone().hash(&sponge, pp_hash.clone())?;
+
+        // P.2. Construct u_i
+        // u_i.cmW is provided by the prover as witness
+        let u_i_cmW =
+            NonNativeAffineVar::new_witness(cs.clone(), || Ok(self.u_i_cmW.unwrap_or(C1::zero())))?;
+        // u_i.x is computed in step 1
+        let u_i_x = vec![u_i_x, cf_u_i_x];
+        let u_i = CommittedInstanceVar {
+            // u_i.rE is derived from u_i.x and u_i.cmW, so that it is random even though the
+            // prover knows the (zero) error term of u_i beforehand
+            rE: incoming_rE_gadget(&sponge, &pp_hash, &u_i_x, &u_i_cmW, self.n_vars)?,
+            // u_i.mleE = 0, since u_i.E = 0
+            mleE: FpVar::zero(),
+            // u_i.u = 1
+            u: FpVar::one(),
+            cmW: u_i_cmW,
+            x: u_i_x,
+        };
+
+        // P.3. nifs.verify, obtains U_{i+1} by folding u_i & U_i.
+        // Notice that NIFSGadget::verify does not fold cmW.
+        // We set `U_i1.cmW` to the unconstrained witness `U_i1_cmW`, whose correctness will be
+        // checked on the other curve.
+        let (mut U_i1, alpha_bits) = NIFSGadget::<
+            C1,
+            PoseidonSponge<C1::ScalarField>,
+            PoseidonSpongeVar<C1::ScalarField>,
+        >::verify(
+            &mut transcript,
+            pp_hash.clone(),
+            U_i.clone(),
+            U_i_vec,
+            u_i.clone(),
+            Some(proof),
+        )?;
+        U_i1.cmW = U_i1_cmW;
+
+        // P.4.a compute and check the first output of F'
+
+        // get z_{i+1} from the F circuit
+        let i_usize = self.i_usize.unwrap_or(0);
+        let z_i1 = self
+            .F
+            .generate_step_constraints(cs.clone(), i_usize, z_i, external_inputs)?;
+
+        // Base case: u_{i+1}.x[0] == H((i+1, z_0, z_{i+1}, U_{\bot})
+        // Non-base case: u_{i+1}.x[0] == H((i+1, z_0, z_{i+1}, U_{i+1})
+        let (u_i1_x, _) = U_i1.clone().hash(
+            &sponge,
+            &pp_hash,
+            &(i + FpVar::<CF1<C1>>::one()),
+            &z_0,
+            &z_i1,
+        )?;
+        let (u_i1_x_base, _) = CommittedInstanceVar::new_constant(cs.clone(), u_dummy)?.hash(
+            &sponge,
+            &pp_hash,
+            &FpVar::<CF1<C1>>::one(),
+            &z_0,
+            &z_i1,
+        )?;
+        let x = is_basecase.select(&u_i1_x_base, &u_i1_x)?;
+        // This line "converts" `x` from a witness to a public input.
+        // Instead of directly modifying the constraint system, we explicitly
+        // allocate a public input and enforce that its value is indeed `x`.
+        // While comparing `x` with itself seems redundant, this is necessary
+        // because:
+        // - `.value()` allows an honest prover to extract public inputs without
+        //   computing them outside the circuit.
+        // - `.enforce_equal()` prevents a malicious prover from claiming wrong
+        //   public inputs that are not the honest `x` computed in-circuit.
+        FpVar::new_input(cs.clone(), || x.value())?.enforce_equal(&x)?;
+
+        // CycleFold part
+        // C.1. Compute cf_u_i.x
+        // The folding challenge has `C1::ScalarField::MODULUS_BIT_SIZE` bits, so it may not fit
+        // in a single `C1::BaseField` element, and we split it into chunks as in the
+        // `CycleFoldCircuit`.
+        let cf_x = [
+            alpha_bits
+                .chunks(C1::BaseField::MODULUS_BIT_SIZE as usize - 1)
+                .map(|bits| {
+                    let mut bits = bits.to_vec();
+                    bits.resize(C1::BaseField::MODULUS_BIT_SIZE as usize, Boolean::FALSE);
+                    NonNativeUintVar::from(&bits)
+                })
+                .collect::<Vec<_>>(),
+            vec![
+                U_i.cmW.x, U_i.cmW.y, u_i.cmW.x, u_i.cmW.y, U_i1.cmW.x, U_i1.cmW.y,
+            ],
+        ]
+        .concat();
+
+        // C.2. Construct `cf_u_i`
+        let cf_u_i = CycleFoldCommittedInstanceVar {
+            // cf_u_i.cmE = 0
+            cmE: C2::Var::zero(),
+            // cf_u_i.u = 1
+            u: NonNativeUintVar::new_constant(cs.clone(), C1::BaseField::one())?,
+            // cf_u_i.cmW is provided by the prover as witness
+            cmW: C2::Var::new_witness(cs.clone(), || Ok(self.cf_u_i_cmW.unwrap_or(C2::zero())))?,
+            // cf_u_i.x is computed in step 1
+            x: cf_x,
+        };
+
+        // C.3. nifs.verify, obtains cf_U_{i+1} by folding cf_u_i & cf_U_i.
+
+        // compute cf_r = H(cf_u_i, cf_U_i, cf_cmT)
+        // cf_r_bits is denoted by rho* in the paper.
+        let cf_r_bits = CycleFoldChallengeGadget::<C2>::get_challenge_gadget(
+            &mut transcript,
+            pp_hash.clone(),
+            cf_U_i_vec,
+            cf_u_i.clone(),
+            cf_cmT.clone(),
+        )?;
+        // Fold cf_u_i & cf_U_i into cf_U_{i+1}
+        let cf_U_i1 =
+            NIFSFullGadget::<C2>::fold_committed_instance(cf_r_bits, cf_cmT, cf_U_i, cf_u_i)?;
+
+        // Back to Primary Part
+        // P.4.b compute and check the second output of F'
+        // Base case: u_{i+1}.x[1] == H(cf_U_{\bot})
+        // Non-base case: u_{i+1}.x[1] == H(cf_U_{i+1})
+        let (cf_u_i1_x, _) = cf_U_i1.clone().hash(&sponge, pp_hash.clone())?;
+        let (cf_u_i1_x_base, _) =
+            CycleFoldCommittedInstanceVar::<C2>::new_constant(cs.clone(), cf_u_dummy)?
+                .hash(&sponge, pp_hash)?;
+        let cf_x = is_basecase.select(&cf_u_i1_x_base, &cf_u_i1_x)?;
+        // This line "converts" `cf_x` from a witness to a public input.
+        // Instead of directly modifying the constraint system, we explicitly
+        // allocate a public input and enforce that its value is indeed `cf_x`.
+        // While comparing `cf_x` with itself seems redundant, this is necessary
+        // because:
+        // - `.value()` allows an honest prover to extract public inputs without
+        //   computing them outside the circuit.
+        // - `.enforce_equal()` prevents a malicious prover from claiming wrong
+        //   public inputs that are not the honest `cf_x` computed in-circuit.
+        FpVar::new_input(cs.clone(), || cf_x.value())?.enforce_equal(&cf_x)?;
+
+        Ok(z_i1)
+    }
+}
+
+impl<C1, C2, FC> ConstraintSynthesizer<CF1<C1>> for AugmentedFCircuit<C1, C2, FC>
+where
+    C1: Curve<BaseField = C2::ScalarField, ScalarField = C2::BaseField>,
+    C2: Curve,
+    FC: FCircuit<CF1<C1>>,
+{
+    fn generate_constraints(self, cs: ConstraintSystemRef<CF1<C1>>) -> Result<(), SynthesisError> {
+        self.compute_next_state(cs).map(|_| ())
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use ark_bn254::{Fr, G1Projective as Projective};
+    use ark_crypto_primitives::sponge::CryptographicSponge;
+    use ark_relations::r1cs::ConstraintSystem;
+    use ark_std::UniformRand;
+
+    use crate::folding::mova::incoming_rE;
+    use crate::transcript::poseidon::poseidon_canonical_config;
+    use crate::Error;
+
+    // checks that the gadget and native derivations of the incoming instance's rE match
+    #[test]
+    fn test_incoming_rE_gadget() -> Result<(), Error> {
+        let mut rng = ark_std::test_rng();
+        let poseidon_config = poseidon_canonical_config::<Fr>();
+        let sponge = PoseidonSponge::<Fr>::new(&poseidon_config);
+
+        let pp_hash = Fr::from(42u32); // only for testing
+        let x = vec![Fr::rand(&mut rng), Fr::rand(&mut rng)];
+        let cmW = Projective::rand(&mut rng);
+        let n_vars = 5;
+
+        let rE = incoming_rE(&sponge, pp_hash, &x, &cmW, n_vars);
+        assert_eq!(rE.len(), n_vars);
+
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let spongeVar = PoseidonSpongeVar::<Fr>::new(cs.clone(), &poseidon_config);
+        let pp_hashVar = FpVar::<Fr>::new_witness(cs.clone(), || Ok(pp_hash))?;
+        let xVar = Vec::<FpVar<Fr>>::new_witness(cs.clone(), || Ok(x))?;
+        let cmWVar = NonNativeAffineVar::<Projective>::new_witness(cs.clone(), || Ok(cmW))?;
+
+        let rEVar = incoming_rE_gadget(&spongeVar, &pp_hashVar, &xVar, &cmWVar, n_vars)?;
+        assert!(cs.is_satisfied()?);
+        assert_eq!(rEVar.value()?, rE);
+        Ok(())
+    }
+}
@@ -0,0 +1,760 @@
+/// Implements an IVC based on the folding scheme described in
+/// [Mova](https://eprint.iacr.org/2024/1220.pdf), together with
+/// [CycleFold](https://eprint.iacr.org/2023/1192.pdf).
+///
+/// The IVC follows the same design as Nova+CycleFold (see
+/// [`crate::folding::nova`]), but the running instances are Mova's committed
+/// instances, which do not contain any commitment to the error term `E`, and
+/// which are folded without committing to the cross term `T`. As a result, the
+/// only commitment of the primary instances is `cmW`, and a single CycleFold
+/// circuit is needed at each step.
+use ark_crypto_primitives::sponge::{
+    poseidon::{PoseidonConfig, PoseidonSponge},
+    CryptographicSponge,
+};
+use ark_ff::{BigInteger, PrimeField};
+use ark_r1cs_std::{alloc::AllocVar, R1CSVar};
+use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystem, SynthesisMode};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::{log2, marker::PhantomData, rand::RngCore, One, Zero};
+
+use crate::arith::{
+    r1cs::{extract_w_x, R1CS},
+    Arith, ArithRelation,
+};
+use crate::commitment::CommitmentScheme;
+use crate::folding::{
+    circuits::{
+        cyclefold::{
+            fold_cyclefold_circuit, CycleFoldCircuit, CycleFoldCommittedInstance, CycleFoldConfig,
+            CycleFoldWitness,
+        },
+        CF1,
+    },
+    nova::{
+        get_r1cs_from_cs,
+        nifs::{
+            mova::{CommittedInstance, Witness, NIFS},
+            NIFSTrait,
+        },
+        PreprocessorParam, ProverParams, VerifierParams,
+    },
+    traits::CommittedInstanceOps,
+};
+use crate::frontend::{utils::DummyCircuit, FCircuit};
+use crate::transcript::{poseidon::poseidon_canonical_config, Transcript};
+use crate::{Curve, Error, FoldingScheme};
+
+pub mod circuits;
+
+use circuits::AugmentedFCircuit;
+
+/// Configuration for Mova's CycleFold circuit
+pub struct MovaCycleFoldConfig<C: Curve> {
+    _c: PhantomData<C>,
+}
+
+impl<C: Curve> CycleFoldConfig for MovaCycleFoldConfig<C> {
+    // Mova's folding challenge is a full field element
+    const RANDOMNESS_BIT_LENGTH: usize = C::ScalarField::MODULUS_BIT_SIZE as usize;
+    // Only `cmW` is folded, i.e., 2 points to be folded.
+    const N_INPUT_POINTS: usize = 2;
+    type C = C;
+}
+
+/// CycleFold circuit for computing random linear combinations of group elements
+/// in Mova instances.
+pub type MovaCycleFoldCircuit<C> = CycleFoldCircuit<MovaCycleFoldConfig<C>>;
+
+/// Derives the evaluation point `rE` of an incoming instance from its public
+/// inputs `x` and its commitment `cmW`.
+///
+/// The error term of an incoming instance is zero, so its evaluation `mleE` at
+/// `rE` is always zero. For this to imply that the error term is indeed zero,
+/// `rE` needs to be random, and hence it is squeezed from a sponge after the
+/// instance has been fixed, instead of being chosen by the prover.
+pub fn incoming_rE<C: Curve>(
+    sponge: &PoseidonSponge<CF1<C>>,
+    pp_hash: CF1<C>,
+    x: &[CF1<C>],
+    cmW: &C,
+    n_vars: usize,
+) -> Vec<CF1<C>> {
+    let mut sponge = sponge.clone();
+    sponge.absorb(&pp_hash);
+    sponge.absorb(&x);
+    sponge.absorb_nonnative(cmW);
+    sponge.get_challenges(n_vars)
+}
+
+/// Computes `n_vars`, the number of variables of the MLE of the error term,
+/// which is the log2 of the number of constraints of the augmented circuit.
+fn compute_n_vars<C1, C2, FC>(
+    poseidon_config: &PoseidonConfig<CF1<C1>>,
+    F: &FC,
+) -> Result<usize, Error>
+where
+    C1: Curve<BaseField = C2::ScalarField, ScalarField = C2::BaseField>,
+    C2: Curve,
+    FC: FCircuit<C1::ScalarField>,
+{
+    // As in ProtoGalaxy's `compute_t`, `n_vars` depends on the size of the
+    // augmented circuit `F'`, whose in-circuit NIFS verifier in turn depends on
+    // `n_vars`. We first compute a lower bound of `n_vars` with `n_vars = 1`,
+    // and then find the smallest value that is consistent with the size of `F'`.
+
+    // The size of `F` is constant with respect to `n_vars`, so we compute it
+    // only once.
+    let state_len = F.state_len();
+    let cs = ConstraintSystem::<C1::ScalarField>::new_ref();
+    cs.set_mode(SynthesisMode::Setup);
+    F.generate_step_constraints(
+        cs.clone(),
+        0,
+        Vec::new_witness(cs.clone(), || Ok(vec![Zero::zero(); state_len]))?,
+        FC::ExternalInputsVar::new_witness(cs.clone(), || Ok(FC::ExternalInputs::default()))?,
+    )?;
+    let step_constraints = cs.num_constraints();
+
+    // The rest of `F'` is measured with a dummy circuit in place of `F`.
+    let dummy_circuit: DummyCircuit = FCircuit::<C1::ScalarField>::new(state_len)?;
+    let augmentation_constraints = |n_vars| -> Result<usize, Error> {
+        let cs = ConstraintSystem::<C1::ScalarField>::new_ref();
+        cs.set_mode(SynthesisMode::Setup);
+        AugmentedFCircuit::<C1, C2, DummyCircuit>::empty(
+            poseidon_config,
+            dummy_circuit.clone(),
+            n_vars,
+        )
+        .generate_constraints(cs.clone())?;
+        Ok(cs.num_constraints())
+    };
+
+    let n_vars_lower_bound = log2(step_constraints + augmentation_constraints(1)?) as usize;
+    // Increasing `n_vars` by one only adds a few constraints to `F'`, so the
+    // actual `n_vars` is either the lower bound or the lower bound plus one.
+    for n_vars in n_vars_lower_bound..=n_vars_lower_bound + 1 {
+        if n_vars == log2(step_constraints + augmentation_constraints(n_vars)?) as usize {
+            return Ok(n_vars);
+        }
+    }
+    unreachable!()
+}
+
+/// helper method to get the R1CS for both the AugmentedFCircuit and the CycleFold circuit
+#[allow(clippy::type_complexity)]
+pub fn get_r1cs<C1, C2, FC>(
+    poseidon_config: &PoseidonConfig<C1::ScalarField>,
+    F_circuit: FC,
+) -> Result<(R1CS<C1::ScalarField>, R1CS<C2::ScalarField>), Error>
+where
+    C1: Curve<BaseField = C2::ScalarField, ScalarField = C2::BaseField>,
+    C2: Curve,
+    FC: FCircuit<C1::ScalarField>,
+{
+    let n_vars = compute_n_vars::<C1, C2, FC>(poseidon_config, &F_circuit)?;
+    let augmented_F_circuit =
+        AugmentedFCircuit::<C1, C2, FC>::empty(poseidon_config, F_circuit, n_vars);
+    let cf_circuit = MovaCycleFoldCircuit::<C1>::empty();
+    let r1cs = get_r1cs_from_cs::<C1::ScalarField>(augmented_F_circuit)?;
+    let cf_r1cs = get_r1cs_from_cs::<C2::ScalarField>(cf_circuit)?;
+    Ok((r1cs, cf_r1cs))
+}
+
+#[derive(PartialEq, Eq, Debug, Clone, CanonicalSerialize, CanonicalDeserialize)]
+pub struct IVCProof<C1, C2>
+where
+    C1: Curve,
+    C2: Curve,
+{
+    // current step of the IVC
+    pub i: C1::ScalarField,
+    // initial state
+    pub z_0: Vec<C1::ScalarField>,
+    // current state
+    pub z_i: Vec<C1::ScalarField>,
+    // running instance
+    pub W_i: Witness<C1>,
+    pub U_i: CommittedInstance<C1>,
+    // incoming instance
+    pub w_i: Witness<C1>,
+    pub u_i: CommittedInstance<C1>,
+    // CycleFold instances
+    pub cf_W_i: CycleFoldWitness<C2>,
+    pub cf_U_i: CycleFoldCommittedInstance<C2>,
+}
+
+/// Implements Mova+CycleFold's IVC, following the FoldingScheme trait.
+/// The `H` const generic specifies whether the homorphic commitment scheme is blinding
+#[derive(Clone, Debug)]
+pub struct Mova<C1, C2, FC, CS1, CS2, const H: bool = false>
+where
+    C1: Curve,
+    C2: Curve,
+    FC: FCircuit<C1::ScalarField>,
+    CS1: CommitmentScheme<C1, H>,
+    CS2: CommitmentScheme<C2, H>,
+{
+    /// R1CS of the Augmented Function circuit
+    pub r1cs: R1CS<C1::ScalarField>,
+    /// R1CS of the CycleFold circuit
+    pub cf_r1cs: R1CS<C2::ScalarField>,
+    pub poseidon_config: PoseidonConfig<C1::ScalarField>,
+    /// CommitmentScheme::ProverParams over C1
+    pub cs_pp: CS1::ProverParams,
+    /// CycleFold CommitmentScheme::ProverParams, over C2
+    pub cf_cs_pp: CS2::ProverParams,
+    /// F circuit, the circuit that is being folded
+    pub F: FC,
+    /// public params hash
+    pub pp_hash: C1::ScalarField,
+    pub i: C1::ScalarField,
+    /// initial state
+    pub z_0: Vec<C1::ScalarField>,
+    /// current i-th state
+    pub z_i: Vec<C1::ScalarField>,
+    /// Mova instances
+    pub w_i: Witness<C1>,
+    pub u_i: CommittedInstance<C1>,
+    pub W_i: Witness<C1>,
+    pub U_i: CommittedInstance<C1>,
+
+    /// CycleFold running instance
+    pub cf_W_i: CycleFoldWitness<C2>,
+    pub cf_U_i: CycleFoldCommittedInstance<C2>,
+}
+
+impl<C1, C2, FC, CS1, CS2, const H: bool> FoldingScheme<C1, C2, FC>
+    for Mova<C1, C2, FC, CS1, CS2, H>
+where
+    C1: Curve<BaseField = C2::ScalarField, ScalarField = C2::BaseField>,
+    C2: Curve,
+    FC: FCircuit<C1::ScalarField>,
+    CS1: CommitmentScheme<C1, H>,
+    CS2: CommitmentScheme<C2, H>,
+{
+    type PreprocessorParam = PreprocessorParam<C1, C2, FC, CS1, CS2, H>;
+    type ProverParam = ProverParams<C1, C2, CS1, CS2, H>;
+    type VerifierParam = VerifierParams<C1, C2, CS1, CS2, H>;
+    type RunningInstance = (CommittedInstance<C1>, Witness<C1>);
+    type IncomingInstance = (CommittedInstance<C1>, Witness<C1>);
+    type MultiCommittedInstanceWithWitness = ();
+    type CFInstance = (CycleFoldCommittedInstance<C2>, CycleFoldWitness<C2>);
+    type IVCProof = IVCProof<C1, C2>;
+
+    fn pp_deserialize_with_mode<R: std::io::prelude::Read>(
+        reader: R,
+        compress: ark_serialize::Compress,
+        validate: ark_serialize::Validate,
+        _fc_params: FC::Params, // FCircuit params
+    ) -> Result<Self::ProverParam, Error> {
+        Ok(Self::ProverParam::deserialize_with_mode(
+            reader, compress, validate,
+        )?)
+    }
+
+    fn vp_deserialize_with_mode<R: std::io::prelude::Read>(
+        mut reader: R,
+        compress: ark_serialize::Compress,
+        validate: ark_serialize::Validate,
+        fc_params: FC::Params,
+    ) -> Result<Self::VerifierParam, Error> {
+        let poseidon_config = poseidon_canonical_config::<C1::ScalarField>();
+
+        // generate the r1cs & cf_r1cs needed for the VerifierParams, so that they don't need to
+        // be serialized.
+        let (r1cs, cf_r1cs) = get_r1cs::<C1, C2, FC>(&poseidon_config, FC::new(fc_params)?)?;
+
+        let cs_vp = CS1::VerifierParams::deserialize_with_mode(&mut reader, compress, validate)?;
+        let cf_cs_vp = CS2::VerifierParams::deserialize_with_mode(&mut reader, compress, validate)?;
+
+        Ok(Self::VerifierParam {
+            poseidon_config,
+            r1cs,
+            cf_r1cs,
+            cs_vp,
+            cf_cs_vp,
+        })
+    }
+
+    fn preprocess(
+        mut rng: impl RngCore,
+        prep_param: &Self::PreprocessorParam,
+    ) -> Result<(Self::ProverParam, Self::VerifierParam), Error> {
+        let (r1cs, cf_r1cs) =
+            get_r1cs::<C1, C2, FC>(&prep_param.sponge_config, prep_param.F.clone())?;
+
+        // if cs params exist, use them, if not, generate new ones
+        let (cs_pp, cs_vp) = match (&prep_param.cs_pp, &prep_param.cs_vp) {
+            (Some(cs_pp), Some(cs_vp)) => (cs_pp.clone(), cs_vp.clone()),
+            // `CS1` is only used for committing to Mova's witness vector `w`,
+            // since neither the error term nor the cross term are committed.
+            _ => CS1::setup(&mut rng, r1cs.n_witnesses())?,
+        };
+        let (cf_cs_pp, cf_cs_vp) = match (&prep_param.cf_cs_pp, &prep_param.cf_cs_vp) {
+            (Some(cf_cs_pp), Some(cf_cs_vp)) => (cf_cs_pp.clone(), cf_cs_vp.clone()),
+            _ => CS2::setup(
+                &mut rng,
+                // `CS2` is for committing to CycleFold's witness vector `w` and
+                // error term `e`, where the length of `e` is the number of
+                // constraints, so we set `len` to the maximum of `e` and `w`'s
+                // lengths.
+                cf_r1cs.n_constraints().max(cf_r1cs.n_witnesses()),
+            )?,
+        };
+        let (cs_pp, cf_cs_pp) = prep_param.attach_fixed_base_tables(cs_pp, cf_cs_pp)?;
+
+        let prover_params = ProverParams::<C1, C2, CS1, CS2, H> {
+            sponge_config: prep_param.sponge_config.clone(),
+            cs_pp,
+            cf_cs_pp,
+        };
+        let verifier_params = VerifierParams::<C1, C2, CS1, CS2, H> {
+            sponge_config: prep_param.sponge_config.clone(),
+            r1cs,
+            cf_r1cs,
+            cs_vp,
+            cf_cs_vp,
+        };
+
+        Ok((prover_params, verifier_params))
+    }
+
+    /// Initializes the Mova+CycleFold's IVC for the given parameters and initial state `z_0`.
+    fn init(
+        params: &(Self::ProverParam, Self::VerifierParam),
+        F: FC,
+        z_0: Vec<C1::ScalarField>,
+    ) -> Result<Self, Error> {
+        let (pp, vp) = params;
+
+        let (r1cs, cf_r1cs) = get_r1cs::<C1, C2, FC>(&pp.sponge_config, F.clone())?;
+
+        // compute the public params hash
+        let pp_hash = vp.pp_hash()?;
+
+        // setup the dummy instances
+        let (W_dummy, U_dummy) = r1cs.dummy_witness_instance();
+        let (w_dummy, u_dummy) = r1cs.dummy_witness_instance();
+        let (cf_W_dummy, cf_U_dummy) = cf_r1cs.dummy_witness_instance();
+
+        Ok(Self {
+            r1cs,
+            cf_r1cs,
+            poseidon_config: pp.sponge_config.clone(),
+            cs_pp: pp.cs_pp.clone(),
+            cf_cs_pp: pp.cf_cs_pp.clone(),
+            F,
+            pp_hash,
+            i: C1::ScalarField::zero(),
+            z_0: z_0.clone(),
+            z_i: z_0,
+            w_i: w_dummy,
+            u_i: u_dummy,
+            W_i: W_dummy,
+            U_i: U_dummy,
+            // cyclefold running instance
+            cf_W_i: cf_W_dummy,
+            cf_U_i: cf_U_dummy,
+        })
+    }
+
+    /// Implements IVC.P of Mova+CycleFold
+    fn prove_step(
+        &mut self,
+        mut rng: impl RngCore,
+        external_inputs: FC::ExternalInputs,
+        // Mova does not support multi-instances folding
+        _other_instances: Option<Self::MultiCommittedInstanceWithWitness>,
+    ) -> Result<(), Error> {
+        // ensure that commitments are blinding if user has specified so.
+        if H && self.i >= C1::ScalarField::one() {
+            let blinding_commitments = if self.i == C1::ScalarField::one() {
+                // blinding values of the running instances are zero at the first iteration
+                vec![self.w_i.rW]
+            } else {
+                vec![self.w_i.rW, self.W_i.rW]
+            };
+            if blinding_commitments.contains(&C1::ScalarField::zero()) {
+                return Err(Error::IncorrectBlinding(
+                    H,
+                    format!("{blinding_commitments:?}"),
+                ));
+            }
+        }
+        // `sponge` is for digest computation.
+        let sponge = PoseidonSponge::<C1::ScalarField>::new(&self.poseidon_config);
+        // `transcript` is for challenge generation.
+        let mut transcript = sponge.clone();
+
+        let augmented_F_circuit: AugmentedFCircuit<C1, C2, FC>;
+
+        if _other_instances.is_some() {
+            return Err(Error::NoMultiInstances);
+        }
+
+        if self.z_i.len() != self.F.state_len() {
+            return Err(Error::NotSameLength(
+                "z_i.len()".to_string(),
+                self.z_i.len(),
+                "F.state_len()".to_string(),
+                self.F.state_len(),
+            ));
+        }
+
+        if self.i > C1::ScalarField::from_le_bytes_mod_order(&usize::MAX.to_le_bytes()) {
+            return Err(Error::MaxStep);
+        }
+
+        let i_usize;
+
+        #[cfg(target_pointer_width = "64")]
+        {
+            let mut i_bytes: [u8; 8] = [0; 8];
+            i_bytes.copy_from_slice(&self.i.into_bigint().to_bytes_le()[..8]);
+            i_usize = usize::from_le_bytes(i_bytes);
+        }
+
+        #[cfg(target_pointer_width = "32")]
+        {
+            let mut i_bytes: [u8; 4] = [0; 4];
+            i_bytes.copy_from_slice(&self.i.into_bigint().to_bytes_le()[..4]);
+            i_usize = usize::from_le_bytes(i_bytes);
+        }
+
+        let n_vars = log2(self.r1cs.n_constraints()) as usize;
+
+        let (W_i1, U_i1) = if self.i == C1::ScalarField::zero() {
+            // base case: there is nothing to fold yet, so U_1 and W_1 are the dummy instance and
+            // witness, which is also what the augmented circuit outputs in the base case.
+            augmented_F_circuit = AugmentedFCircuit::<C1, C2, FC> {
+                poseidon_config: self.poseidon_config.clone(),
+                n_vars,
+                pp_hash: Some(self.pp_hash),
+                i: Some(C1::ScalarField::zero()), // = i=0
+                i_usize: Some(0),
+                z_0: Some(self.z_0.clone()), // = z_i
+                z_i: Some(self.z_i.clone()),
+                external_inputs: Some(external_inputs.clone()),
+                u_i_cmW: Some(self.u_i.cmW),  // = dummy
+                U_i: Some(self.U_i.clone()),  // = dummy
+                U_i1_cmW: Some(self.U_i.cmW), // = dummy
+                proof: None,                  // = dummy
+                F: self.F.clone(),
+                cf_u_i_cmW: None,
+                cf_U_i: None,
+                cf_cmT: None,
+            };
+
+            (self.W_i.clone(), self.U_i.clone())
+        } else {
+            // fold Mova instances
+            let (W_i1, U_i1, proof, r_bits) =
+                NIFS::<C1, CS1, PoseidonSponge<C1::ScalarField>, H>::prove(
+                    &self.cs_pp,
+                    &self.r1cs,
+                    &mut transcript,
+                    self.pp_hash,
+                    &self.W_i,
+                    &self.U_i,
+                    &self.w_i,
+                    &self.u_i,
+                )?;
+
+            // CycleFold part:
+            let cf_circuit = MovaCycleFoldCircuit::<C1> {
+                r_bits: Some(r_bits),
+                points: Some(vec![self.U_i.cmW, self.u_i.cmW]),
+            };
+
+            // fold self.cf_U_i + cf_u_i -> cf_U_i1
+            let (cf_u_i, cf_W_i1, cf_U_i1, cf_cmT) =
+                fold_cyclefold_circuit::<MovaCycleFoldConfig<C1>, C2, CS2, H>(
+                    &mut transcript,
+                    self.cf_r1cs.clone(),
+                    self.cf_cs_pp.clone(),
+                    self.pp_hash,
+                    self.cf_W_i.clone(), // CycleFold running instance witness
+                    self.cf_U_i.clone(), // CycleFold running instance
+                    cf_circuit,
+                    &mut rng,
+                )?;
+
+            augmented_F_circuit = AugmentedFCircuit::<C1, C2, FC> {
+                poseidon_config: self.poseidon_config.clone(),
+                n_vars,
+                pp_hash: Some(self.pp_hash),
+                i: Some(self.i),
+                i_usize: Some(i_usize),
+                z_0: Some(self.z_0.clone()),
+                z_i: Some(self.z_i.clone()),
+                external_inputs: Some(external_inputs.clone()),
+                u_i_cmW: Some(self.u_i.cmW),
+                U_i: Some(self.U_i.clone()),
+                U_i1_cmW: Some(U_i1.cmW),
+                proof: Some(proof),
+                F: self.F.clone(),
+                // cyclefold values
+                cf_u_i_cmW: Some(cf_u_i.cmW),
+                cf_U_i: Some(self.cf_U_i.clone()),
+                cf_cmT: Some(cf_cmT),
+            };
+
+            self.cf_W_i = cf_W_i1;
+            self.cf_U_i = cf_U_i1;
+
+            (W_i1, U_i1)
+        };
+
+        let cs = ConstraintSystem::<C1::ScalarField>::new_ref();
+
+        let z_i1 = augmented_F_circuit
+            .compute_next_state(cs.clone())?
+            .value()?;
+
+        #[cfg(test)]
+        assert!(cs.is_satisfied()?);
+
+        let cs = cs.into_inner().ok_or(Error::NoInnerConstraintSystem)?;
+        let (w_i1, x_i1) = extract_w_x::<C1::ScalarField>(&cs);
+
+        #[cfg(test)]
+        if x_i1.len() != 2 {
+            return Err(Error::NotExpectedLength(x_i1.len(), 2));
+        }
+
+        // set values for next iteration
+        self.i += C1::ScalarField::one();
+        self.z_i = z_i1;
+        self.w_i = Witness::<C1>::new::<H>(w_i1, self.r1cs.n_constraints(), &mut rng);
+        // the evaluation point of the new incoming instance is only known once `cmW` is computed,
+        // and since its error term is zero, `mleE` is zero regardless of `rE`.
+        let mut u_i = self.w_i.commit::<CS1, H>(&self.cs_pp, x_i1, vec![])?;
+        u_i.rE = incoming_rE(&sponge, self.pp_hash, &u_i.x, &u_i.cmW, n_vars);
+        self.u_i = u_i;
+        self.W_i = W_i1;
+        self.U_i = U_i1;
+
+        #[cfg(test)]
+        {
+            self.u_i.check_incoming()?;
+            self.r1cs.check_relation(&self.w_i, &self.u_i)?;
+            self.r1cs.check_relation(&self.W_i, &self.U_i)?;
+        }
+
+        Ok(())
+    }
+
+    fn state(&self) -> Vec<C1::ScalarField> {
+        self.z_i.clone()
+    }
+
+    fn ivc_proof(&self) -> Self::IVCProof {
+        Self::IVCProof {
+            i: self.i,
+            z_0: self.z_0.clone(),
+            z_i: self.z_i.clone(),
+            W_i: self.W_i.clone(),
+            U_i: self.U_i.clone(),
+            w_i: self.w_i.clone(),
+            u_i: self.u_i.clone(),
+            cf_W_i: self.cf_W_i.clone(),
+            cf_U_i: self.cf_U_i.clone(),
+        }
+    }
+
+    fn from_ivc_proof(
+        ivc_proof: IVCProof<C1, C2>,
+        fcircuit_params: FC::Params,
+        params: (Self::ProverParam, Self::VerifierParam),
+    ) -> Result<Self, Error> {
+        let IVCProof {
+            i,
+            z_0,
+            z_i,
+            W_i,
+            U_i,
+            w_i,
+            u_i,
+            cf_W_i,
+            cf_U_i,
+        } = ivc_proof;
+        let (pp, vp) = params;
+
+        let f_circuit = FC::new(fcircuit_params)?;
+        let (r1cs, cf_r1cs) = get_r1cs::<C1, C2, FC>(&pp.sponge_config, f_circuit.clone())?;
+
+        Ok(Self {
+            r1cs,
+            cf_r1cs,
+            poseidon_config: pp.sponge_config,
+            cs_pp: pp.cs_pp,
+            cf_cs_pp: pp.cf_cs_pp,
+            F: f_circuit,
+            pp_hash: vp.pp_hash()?,
+            i,
+            z_0,
+            z_i,
+            w_i,
+            u_i,
+            W_i,
+            U_i,
+            cf_W_i,
+            cf_U_i,
+        })
+    }
+
+    /// Implements IVC.V of Mova+CycleFold. Notice that this method does not include the
+    /// commitments verification.
+    fn verify(vp: Self::VerifierParam, ivc_proof: Self::IVCProof) -> Result<(), Error> {
+        let Self::IVCProof {
+            i: num_steps,
+            z_0,
+            z_i,
+            W_i,
+            U_i,
+            w_i,
+            u_i,
+            cf_W_i,
+            cf_U_i,
+        } = ivc_proof;
+
+        let sponge = PoseidonSponge::<C1::ScalarField>::new(&vp.sponge_config);
+
+        if num_steps == C1::ScalarField::zero() {
+            if z_0 != z_i {
+                return Err(Error::IVCVerificationFail);
+            }
+            return Ok(());
+        }
+
+        if u_i.x.len() != 2 || U_i.x.len() != 2 {
+            return Err(Error::IVCVerificationFail);
+        }
+
+        let pp_hash = vp.pp_hash()?;
+
+        // check that u_i's output points to the running instance
+        // u_i.X[0] == H(i, z_0, z_i, U_i)
+        let expected_u_i_x = U_i.hash(&sponge, pp_hash, num_steps, &z_0, &z_i);
+        if expected_u_i_x != u_i.x[0] {
+            return Err(Error::IVCVerificationFail);
+        }
+        // u_i.X[1] == H(cf_U_i)
+        let expected_cf_u_i_x = cf_U_i.hash_cyclefold(&sponge, pp_hash);
+        if expected_cf_u_i_x != u_i.x[1] {
+            return Err(Error::IVCVerificationFail);
+        }
+
+        // check that u_i's evaluation point was derived from u_i, as in the augmented circuit
+        let n_vars = log2(vp.r1cs.n_constraints()) as usize;
+        if incoming_rE(&sponge, pp_hash, &u_i.x, &u_i.cmW, n_vars) != u_i.rE {
+            return Err(Error::IVCVerificationFail);
+        }
+
+        // check R1CS satisfiability, which is equivalent to checking if `u_i`
+        // is an incoming instance and if `w_i` and `u_i` satisfy RelaxedR1CS
+        u_i.check_incoming()?;
+        vp.r1cs.check_relation(&w_i, &u_i)?;
+        // check RelaxedR1CS satisfiability
+        vp.r1cs.check_relation(&W_i, &U_i)?;
+
+        // check CycleFold RelaxedR1CS satisfiability
+        vp.cf_r1cs.check_relation(&cf_W_i, &cf_U_i)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use ark_bn254::{Bn254, Fr, G1Projective as Projective};
+    use ark_grumpkin::Projective as Projective2;
+
+    use super::*;
+    use crate::commitment::{kzg::KZG, pedersen::Pedersen};
+    use crate::frontend::utils::CubicFCircuit;
+
+    type M<CS1, CS2, const H: bool> = Mova<Projective, Projective2, CubicFCircuit<Fr>, CS1, CS2, H>;
+
+    /// This test tests the Mova+CycleFold IVC, and by consequence it is also testing the
+    /// AugmentedFCircuit
+    #[test]
+    fn test_ivc() -> Result<(), Error> {
+        let poseidon_config = poseidon_canonical_config::<Fr>();
+
+        let F_circuit = CubicFCircuit::<Fr>::new(())?;
+
+        // run the test using Pedersen commitments on both sides of the curve cycle
+        test_ivc_opt::<Pedersen<Projective>, Pedersen<Projective2>, false>(
+            poseidon_config.clone(),
+            F_circuit,
+            3,
+        )?;
+
+        test_ivc_opt::<Pedersen<Projective, true>, Pedersen<Projective2, true>, true>(
+            poseidon_config.clone(),
+            F_circuit,
+            3,
+        )?;
+
+        // run the test using KZG for the commitments on the main curve, and Pedersen for the
+        // commitments on the secondary curve
+        test_ivc_opt::<KZG<Bn254>, Pedersen<Projective2>, false>(poseidon_config, F_circuit, 3)?;
+        Ok(())
+    }
+
+    // test_ivc allowing to choose the CommitmentSchemes
+    fn test_ivc_opt<
+        CS1: CommitmentScheme<Projective, H>,
+        CS2: CommitmentScheme<Projective2, H>,
+        const H: bool,
+    >(
+        poseidon_config: PoseidonConfig<Fr>,
+        F_circuit: CubicFCircuit<Fr>,
+        num_steps: usize,
+    ) -> Result<(), Error> {
+        let mut rng = ark_std::test_rng();
+
+        let prep_param = PreprocessorParam::new(poseidon_config, F_circuit);
+        let mova_params = M::<CS1, CS2, H>::preprocess(&mut rng, &prep_param)?;
+
+        let z_0 = vec![Fr::from(3_u32)];
+        let mut mova = M::<CS1, CS2, H>::init(&mova_params, F_circuit, z_0)?;
+
+        for _ in 0..num_steps {
+            mova.prove_step(&mut rng, (), None)?;
+        }
+        assert_eq!(Fr::from(num_steps as u32), mova.i);
+
+        // serialize the verifier params, and deserialize them
+        let mut mova_vp_serialized = vec![];
+        mova_params
+            .1
+            .serialize_compressed(&mut mova_vp_serialized)?;
+        let mova_vp_deserialized = M::<CS1, CS2, H>::vp_deserialize_with_mode(
+            &mut mova_vp_serialized.as_slice(),
+            ark_serialize::Compress::Yes,
+            ark_serialize::Validate::Yes,
+            (), // fcircuit_params
+        )?;
+
+        // serialize the IVCProof, and deserialize it
+        let ivc_proof = mova.ivc_proof();
+        let mut ivc_proof_serialized = vec![];
+        ivc_proof.serialize_compressed(&mut ivc_proof_serialized)?;
+        let ivc_proof_deserialized = IVCProof::<Projective, Projective2>::deserialize_compressed(
+            ivc_proof_serialized.as_slice(),
+        )?;
+        assert_eq!(ivc_proof, ivc_proof_deserialized);
+
+        // verify the deserialized IVCProof with the deserialized VerifierParams
+        M::<CS1, CS2, H>::verify(mova_vp_deserialized, ivc_proof_deserialized.clone())?;
+
+        // a tampered evaluation point of the incoming instance must be rejected
+        let mut tampered_ivc_proof = ivc_proof_deserialized;
+        tampered_ivc_proof.u_i.rE[0] += Fr::one();
+        assert!(M::<CS1, CS2, H>::verify(mova_params.1, tampered_ivc_proof).is_err());
+        Ok(())
+    }
+}
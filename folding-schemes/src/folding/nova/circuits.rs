@@ -1,8 +1,5 @@
 /// contains [Nova](https://eprint.iacr.org/2021/370.pdf) related circuits
-use ark_crypto_primitives::sponge::{
-    constraints::CryptographicSpongeVar,
-    poseidon::{constraints::PoseidonSpongeVar, PoseidonConfig, PoseidonSponge},
-};
+use ark_crypto_primitives::sponge::poseidon::PoseidonSponge;
 use ark_ff::PrimeField;
 use ark_r1cs_std::{
     alloc::AllocVar,
@@ -13,7 +10,7 @@ use ark_r1cs_std::{
     R1CSVar,
 };
 use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystemRef, SynthesisError};
-use ark_std::{fmt::Debug, One, Zero};
+use ark_std::{One, Zero};
 
 use super::{
     nifs::{
@@ -32,7 +29,7 @@ use crate::folding::circuits::{
 };
 use crate::folding::traits::{CommittedInstanceVarOps, Dummy};
 use crate::frontend::FCircuit;
-use crate::transcript::AbsorbNonNativeGadget;
+use crate::transcript::{AbsorbNonNativeGadget, SpongeWithGadget};
 use crate::Curve;
 
 /// `AugmentedFCircuit` enhances the original step function `F`, so that it can
@@ -47,9 +44,17 @@ use crate::Curve;
 /// Furthermore, to reduce circuit size over `C2`, we implement the constraints
 /// defined in [CycleFold](https://eprint.iacr.org/2023/1192.pdf). These extra
 /// constraints verify the correct folding of CycleFold instances.
-#[derive(Debug, Clone)]
-pub struct AugmentedFCircuit<C1: Curve, C2: Curve, FC: FCircuit<CF1<C1>>> {
-    pub(super) poseidon_config: PoseidonConfig<CF1<C1>>,
+///
+/// The instances are hashed and the challenges are derived in-circuit with the gadget of the
+/// sponge `T`, which must match the sponge used natively by `Nova`.
+#[derive(Clone)]
+pub struct AugmentedFCircuit<
+    C1: Curve,
+    C2: Curve,
+    FC: FCircuit<CF1<C1>>,
+    T: SpongeWithGadget<CF1<C1>> = PoseidonSponge<CF1<C1>>,
+> {
+    pub(super) sponge_config: T::Config,
     pub(super) pp_hash: Option<CF1<C1>>,
     pub(super) i: Option<CF1<C1>>,
     pub(super) i_usize: Option<usize>,
@@ -73,10 +78,12 @@ pub struct AugmentedFCircuit<C1: Curve, C2: Curve, FC: FCircuit<CF1<C1>>> {
     pub(super) cf2_cmT: Option<C2>,
 }
 
-impl<C1: Curve, C2: Curve, FC: FCircuit<CF1<C1>>> AugmentedFCircuit<C1, C2, FC> {
-    pub fn empty(poseidon_config: &PoseidonConfig<CF1<C1>>, F_circuit: FC) -> Self {
+impl<C1: Curve, C2: Curve, FC: FCircuit<CF1<C1>>, T: SpongeWithGadget<CF1<C1>>>
+    AugmentedFCircuit<C1, C2, FC, T>
+{
+    pub fn empty(sponge_config: &T::Config, F_circuit: FC) -> Self {
         Self {
-            poseidon_config: poseidon_config.clone(),
+            sponge_config: sponge_config.clone(),
             pp_hash: None,
             i: None,
             i_usize: None,
@@ -99,11 +106,12 @@ impl<C1: Curve, C2: Curve, FC: FCircuit<CF1<C1>>> AugmentedFCircuit<C1, C2, FC>
     }
 }
 
-impl<C1, C2, FC> AugmentedFCircuit<C1, C2, FC>
+impl<C1, C2, FC, T> AugmentedFCircuit<C1, C2, FC, T>
 where
     C1: Curve<BaseField = C2::ScalarField, ScalarField = C2::BaseField>,
     C2: Curve,
     FC: FCircuit<CF1<C1>>,
+    T: SpongeWithGadget<CF1<C1>>,
 {
     pub fn compute_next_state(
         self,
@@ -153,7 +161,7 @@ where
             C2::Var::new_witness(cs.clone(), || Ok(self.cf2_cmT.unwrap_or_else(C2::zero)))?;
 
         // `sponge` is for digest computation.
-        let sponge = PoseidonSpongeVar::<C1::ScalarField>::new(cs.clone(), &self.poseidon_config);
+        let sponge = T::Var::new(cs.clone(), &self.sponge_config);
         // `transcript` is for challenge generation.
         let mut transcript = sponge.clone();
 
@@ -185,11 +193,7 @@ where
         // We set `U_i1.cmE` and `U_i1.cmW` to unconstrained witnesses `U_i1_cmE` and `U_i1_cmW`
         // respectively.
         // The correctness of them will be checked on the other curve.
-        let (mut U_i1, r_bits) = NIFSGadget::<
-            C1,
-            PoseidonSponge<C1::ScalarField>,
-            PoseidonSpongeVar<C1::ScalarField>,
-        >::verify(
+        let (mut U_i1, r_bits) = NIFSGadget::<C1, T, T::Var>::verify(
             &mut transcript,
             pp_hash.clone(),
             U_i.clone(),
@@ -335,11 +339,12 @@ where
     }
 }
 
-impl<C1, C2, FC> ConstraintSynthesizer<CF1<C1>> for AugmentedFCircuit<C1, C2, FC>
+impl<C1, C2, FC, T> ConstraintSynthesizer<CF1<C1>> for AugmentedFCircuit<C1, C2, FC, T>
 where
     C1: Curve<BaseField = C2::ScalarField, ScalarField = C2::BaseField>,
     C2: Curve,
     FC: FCircuit<CF1<C1>>,
+    T: SpongeWithGadget<CF1<C1>>,
 {
     fn generate_constraints(self, cs: ConstraintSystemRef<CF1<C1>>) -> Result<(), SynthesisError> {
         self.compute_next_state(cs).map(|_| ())
@@ -351,13 +356,16 @@ pub mod tests {
     use super::*;
     use ark_bn254::{Fr, G1Projective as Projective};
     use ark_crypto_primitives::sponge::{
-        constraints::AbsorbGadget, poseidon::PoseidonSponge, CryptographicSponge,
+        constraints::{AbsorbGadget, CryptographicSpongeVar},
+        poseidon::{constraints::PoseidonSpongeVar, PoseidonSponge},
+        CryptographicSponge,
     };
     use ark_ff::BigInteger;
 
     use ark_relations::r1cs::ConstraintSystem;
     use ark_std::UniformRand;
 
+    use crate::constants::NOVA_NIFS_DOMAIN;
     use crate::folding::nova::nifs::nova::ChallengeGadget;
     use crate::transcript::poseidon::poseidon_canonical_config;
     use crate::Error;
@@ -389,6 +397,7 @@ pub mod tests {
         let r_bits =
             ChallengeGadget::<Projective, CommittedInstance<Projective>>::get_challenge_native(
                 &mut transcript,
+                NOVA_NIFS_DOMAIN,
                 pp_hash,
                 &U_i,
                 &u_i,
@@ -409,6 +418,7 @@ pub mod tests {
         let r_bitsVar =
             ChallengeGadget::<Projective, CommittedInstance<Projective>>::get_challenge_gadget(
                 &mut transcriptVar,
+                NOVA_NIFS_DOMAIN,
                 pp_hashVar,
                 U_iVar.to_sponge_field_elements()?,
                 u_iVar,
@@ -2,6 +2,7 @@
 /// DeciderEth from decider_eth.rs file.
 /// More details can be found at the documentation page:
 /// https://privacy-scaling-explorations.github.io/sonobe-docs/design/nova-decider-offchain.html
+use ark_crypto_primitives::sponge::poseidon::PoseidonSponge;
 use ark_ff::{BigInteger, PrimeField};
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
 use ark_snark::SNARK;
@@ -12,14 +13,14 @@ use core::marker::PhantomData;
 use super::decider_circuits::{DeciderCircuit1, DeciderCircuit2};
 use super::decider_eth_circuit::DeciderNovaGadget;
 use super::Nova;
-use crate::commitment::CommitmentScheme;
-use crate::folding::circuits::cyclefold::CycleFoldCommittedInstance;
-use crate::folding::circuits::decider::DeciderEnabledNIFS;
+use crate::commitment::{CommitmentScheme, EvaluationProof};
+use crate::folding::circuits::decider::{DeciderChallenge, DeciderEnabledNIFS};
+use crate::folding::circuits::{cyclefold::CycleFoldCommittedInstance, CF1};
 use crate::folding::traits::{
     CommittedInstanceOps, Dummy, Inputize, InputizeNonNative, WitnessOps,
 };
 use crate::frontend::FCircuit;
-use crate::transcript::poseidon::poseidon_custom_config;
+use crate::transcript::{poseidon::poseidon_canonical_config, SpongeWithGadget};
 use crate::{Curve, Error};
 use crate::{Decider as DeciderTrait, FoldingScheme};
 
@@ -45,8 +46,8 @@ where
     cf_U_final: CycleFoldCommittedInstance<C2>,
     // the CS challenges are provided by the prover, but in-circuit they are checked to match the
     // in-circuit computed computed ones.
-    cs1_challenges: [C1::ScalarField; 2],
-    cs2_challenges: [C2::ScalarField; 2],
+    cs1_challenges: [CS1::Challenge; 2],
+    cs2_challenges: [CS2::Challenge; 2],
 }
 
 #[derive(Debug, Clone, Eq, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
@@ -79,9 +80,9 @@ where
     pub c2_cs_vp: CS2_VerifyingKey,
 }
 
-/// Onchain Decider, for ethereum use cases
+/// Offchain Decider, where `SP` is the sponge of the decided Nova instance
 #[derive(Clone, Debug)]
-pub struct Decider<C1, C2, FC, CS1, CS2, S1, S2, FS> {
+pub struct Decider<C1, C2, FC, CS1, CS2, S1, S2, FS, SP = PoseidonSponge<CF1<C1>>> {
     _c1: PhantomData<C1>,
     _c2: PhantomData<C2>,
     _fc: PhantomData<FC>,
@@ -90,35 +91,33 @@ pub struct Decider<C1, C2, FC, CS1, CS2, S1, S2, FS> {
     _s1: PhantomData<S1>,
     _s2: PhantomData<S2>,
     _fs: PhantomData<FS>,
+    _sp: PhantomData<SP>,
 }
 
-impl<C1, C2, FC, CS1, CS2, S1, S2, FS> DeciderTrait<C1, C2, FC, FS>
-    for Decider<C1, C2, FC, CS1, CS2, S1, S2, FS>
+impl<C1, C2, FC, CS1, CS2, S1, S2, FS, SP> DeciderTrait<C1, C2, FC, FS>
+    for Decider<C1, C2, FC, CS1, CS2, S1, S2, FS, SP>
 where
     C1: Curve<BaseField = C2::ScalarField, ScalarField = C2::BaseField>,
     C2: Curve,
     FC: FCircuit<C1::ScalarField>,
-    CS1: CommitmentScheme<
-        C1,
-        ProverChallenge = C1::ScalarField,
-        Challenge = C1::ScalarField,
-        Proof = crate::commitment::kzg::Proof<C1>,
-    >,
-    CS2: CommitmentScheme<
-        C2,
-        ProverChallenge = C2::ScalarField,
-        Challenge = C2::ScalarField,
-        Proof = crate::commitment::kzg::Proof<C2>,
-    >,
+    CS1: CommitmentScheme<C1>,
+    CS1::ProverChallenge: DeciderChallenge<C1::ScalarField>,
+    CS1::Challenge: DeciderChallenge<C1::ScalarField>,
+    CS1::Proof: EvaluationProof<C1::ScalarField>,
+    CS2: CommitmentScheme<C2>,
+    CS2::ProverChallenge: DeciderChallenge<C2::ScalarField>,
+    CS2::Challenge: DeciderChallenge<C2::ScalarField>,
+    CS2::Proof: EvaluationProof<C2::ScalarField>,
     S1: SNARK<C1::ScalarField>,
     S2: SNARK<C2::ScalarField>,
     FS: FoldingScheme<C1, C2, FC>,
     // constrain FS into Nova, since this is a Decider specifically for Nova
-    Nova<C1, C2, FC, CS1, CS2, false>: From<FS>,
-    crate::folding::nova::ProverParams<C1, C2, CS1, CS2, false>:
+    Nova<C1, C2, FC, CS1, CS2, false, SP>: From<FS>,
+    crate::folding::nova::ProverParams<C1, C2, CS1, CS2, false, SP>:
         From<<FS as FoldingScheme<C1, C2, FC>>::ProverParam>,
-    crate::folding::nova::VerifierParams<C1, C2, CS1, CS2, false>:
+    crate::folding::nova::VerifierParams<C1, C2, CS1, CS2, false, SP>:
         From<<FS as FoldingScheme<C1, C2, FC>>::VerifierParam>,
+    SP: SpongeWithGadget<C1::ScalarField>,
 {
     type PreprocessorParam = ((FS::ProverParam, FS::VerifierParam), usize);
     type ProverParam =
@@ -139,39 +138,32 @@ where
         ((pp, vp), state_len): Self::PreprocessorParam,
     ) -> Result<(Self::ProverParam, Self::VerifierParam), Error> {
         // get the FoldingScheme prover & verifier params from Nova
-        let nova_pp: <Nova<C1, C2, FC, CS1, CS2, false> as FoldingScheme<C1, C2, FC>>::ProverParam =
-            pp.into();
-        let nova_vp: <Nova<C1, C2, FC, CS1, CS2, false> as FoldingScheme<
+        let nova_pp: <Nova<C1, C2, FC, CS1, CS2, false, SP> as FoldingScheme<
+            C1,
+            C2,
+            FC,
+        >>::ProverParam = pp.into();
+        let nova_vp: <Nova<C1, C2, FC, CS1, CS2, false, SP> as FoldingScheme<
             C1,
             C2,
             FC,
         >>::VerifierParam = vp.into();
         let pp_hash = nova_vp.pp_hash()?;
 
-        let poseidon_config1 = nova_vp.poseidon_config;
-        // Create a poseidon config on `C2`'s scalar field for `circuit2`, with
-        // the same parameters (`full_rounds` etc.) as `circuit1` to ensure the
-        // security level is the same.
-        // Note: `ark` and `mds` will be different because they depend on the
-        // field, but they will not affect the security level.
-        let poseidon_config2 = poseidon_custom_config(
-            poseidon_config1.full_rounds,
-            poseidon_config1.partial_rounds,
-            poseidon_config1.alpha,
-            poseidon_config1.rate,
-            poseidon_config1.capacity,
-        );
-
-        let circuit1 = DeciderCircuit1::<C1, C2>::dummy((
+        // `circuit2` uses the canonical Poseidon config on `C2`'s scalar field, see
+        // `DeciderCircuit2::try_from`
+        let poseidon_config2 = poseidon_canonical_config::<C2::ScalarField>();
+
+        let circuit1 = DeciderCircuit1::<C1, C2, CS1::Challenge, SP>::dummy((
             nova_vp.r1cs,
             &nova_vp.cf_r1cs,
-            poseidon_config1,
+            nova_vp.sponge_config,
             (),
             (),
             state_len,
             2, // Nova's running CommittedInstance contains 2 commitments
         ));
-        let circuit2 = DeciderCircuit2::<C2>::dummy((
+        let circuit2 = DeciderCircuit2::<C2, CS2::Challenge>::dummy((
             nova_vp.cf_r1cs,
             poseidon_config2,
             2, // Nova's running CommittedInstance contains 2 commitments
@@ -204,8 +196,9 @@ where
         pp: Self::ProverParam,
         fs: FS,
     ) -> Result<Self::Proof, Error> {
-        let circuit1 = DeciderCircuit1::<C1, C2>::try_from(Nova::from(fs.clone()))?;
-        let circuit2 = DeciderCircuit2::<C2>::try_from(Nova::from(fs))?;
+        let circuit1 =
+            DeciderCircuit1::<C1, C2, CS1::Challenge, SP>::try_from(Nova::from(fs.clone()))?;
+        let circuit2 = DeciderCircuit2::<C2, CS2::Challenge>::try_from(Nova::from(fs))?;
 
         let cmT = circuit1.proof;
         let r = circuit1.randomness;
@@ -217,7 +210,8 @@ where
             .get_openings()
             .iter()
             .zip(&c1_kzg_challenges)
-            .map(|((v, _), &c)| {
+            .map(|((v, _), c)| {
+                let c = CS1::ProverChallenge::from_elements(c.to_elements())?;
                 CS1::prove_with_challenge(&pp.c1_cs_pp, c, v, &C1::ScalarField::zero(), None)
             })
             .collect::<Result<Vec<_>, _>>()?;
@@ -227,7 +221,8 @@ where
             .get_openings()
             .iter()
             .zip(&c2_kzg_challenges)
-            .map(|((v, _), &c)| {
+            .map(|((v, _), c)| {
+                let c = CS2::ProverChallenge::from_elements(c.to_elements())?;
                 CS2::prove_with_challenge(&pp.c2_cs_pp, c, v, &C2::ScalarField::zero(), None)
             })
             .collect::<Result<Vec<_>, _>>()?;
@@ -288,8 +283,16 @@ where
             &z_i,
             &U_final_commitments.inputize_nonnative(),
             &cf_U.inputize_nonnative(),
-            &proof.cs1_challenges,
-            &proof.cs1_proofs.iter().map(|p| p.eval).collect::<Vec<_>>(),
+            &proof
+                .cs1_challenges
+                .iter()
+                .flat_map(|c| c.to_elements())
+                .collect::<Vec<_>>(),
+            &proof
+                .cs1_proofs
+                .iter()
+                .map(|p| p.evaluation())
+                .collect::<Vec<_>>(),
             &proof.cmT.inputize_nonnative(),
         ]
         .concat();
@@ -307,8 +310,16 @@ where
         let c2_public_input: Vec<C2::ScalarField> = [
             &[pp_hash_Fq][..],
             &cf_U.inputize(),
-            &proof.cs2_challenges,
-            &proof.cs2_proofs.iter().map(|p| p.eval).collect::<Vec<_>>(),
+            &proof
+                .cs2_challenges
+                .iter()
+                .flat_map(|c| c.to_elements())
+                .collect::<Vec<_>>(),
+            &proof
+                .cs2_proofs
+                .iter()
+                .map(|p| p.evaluation())
+                .collect::<Vec<_>>(),
         ]
         .concat();
 
@@ -319,22 +330,22 @@ where
         }
 
         // 7.3. check C1 commitments (main instance commitments)
-        for ((cm, &c), pi) in U_final_commitments
+        for ((cm, c), pi) in U_final_commitments
             .iter()
             .zip(&proof.cs1_challenges)
             .zip(&proof.cs1_proofs)
         {
-            CS1::verify_with_challenge(&vp.c1_cs_vp, c, cm, pi)?;
+            CS1::verify_with_challenge(&vp.c1_cs_vp, c.clone(), cm, pi)?;
         }
 
         // 4.3. check C2 commitments (CycleFold instance commitments)
-        for ((cm, &c), pi) in cf_U
+        for ((cm, c), pi) in cf_U
             .get_commitments()
             .iter()
             .zip(&proof.cs2_challenges)
             .zip(&proof.cs2_proofs)
         {
-            CS2::verify_with_challenge(&vp.c2_cs_vp, c, cm, pi)?;
+            CS2::verify_with_challenge(&vp.c2_cs_vp, c.clone(), cm, pi)?;
         }
 
         Ok(true)
@@ -352,7 +363,7 @@ pub mod tests {
     use std::time::Instant;
 
     use super::*;
-    use crate::commitment::kzg::KZG;
+    use crate::commitment::{ipa::IPA, kzg::KZG, pedersen_mle::PedersenMLE};
     use crate::folding::nova::PreprocessorParam;
     use crate::frontend::utils::CubicFCircuit;
     use crate::transcript::poseidon::poseidon_canonical_config;
@@ -426,4 +437,109 @@ pub mod tests {
         println!("Decider verify, {:?}", start.elapsed());
         Ok(())
     }
+
+    #[test]
+    fn test_decider_ipa() -> Result<(), Error> {
+        // use IPA instead of KZG for the commitments, which does not need a trusted setup
+        type N = Nova<
+            Projective,
+            Projective2,
+            CubicFCircuit<Fr>,
+            IPA<Projective>,
+            IPA<Projective2>,
+            false,
+        >;
+        type D = Decider<
+            Projective,
+            Projective2,
+            CubicFCircuit<Fr>,
+            IPA<Projective>,
+            IPA<Projective2>,
+            Groth16<MNT4>,
+            Groth16<MNT6>,
+            N,
+        >;
+
+        let mut rng = ark_std::test_rng();
+        let poseidon_config = poseidon_canonical_config::<Fr>();
+
+        let F_circuit = CubicFCircuit::<Fr>::new(())?;
+        let z_0 = vec![Fr::from(3_u32)];
+
+        let prep_param = PreprocessorParam::new(poseidon_config, F_circuit);
+        let nova_params = N::preprocess(&mut rng, &prep_param)?;
+        let mut nova = N::init(&nova_params, F_circuit, z_0)?;
+        nova.prove_step(&mut rng, (), None)?;
+        nova.prove_step(&mut rng, (), None)?;
+
+        let mut rng = rand::rngs::OsRng;
+        let (decider_pp, decider_vp) =
+            D::preprocess(&mut rng, (nova_params, F_circuit.state_len()))?;
+        let proof = D::prove(rng, decider_pp, nova.clone())?;
+
+        let verified = D::verify(
+            decider_vp,
+            nova.i,
+            nova.z_0,
+            nova.z_i,
+            &nova.U_i.get_commitments(),
+            &nova.u_i.get_commitments(),
+            &proof,
+        )?;
+        assert!(verified);
+        Ok(())
+    }
+
+    #[test]
+    fn test_decider_pedersen_mle() -> Result<(), Error> {
+        // use PedersenMLE for the commitments, whose openings are at the multilinear extension
+        // points
+        type N = Nova<
+            Projective,
+            Projective2,
+            CubicFCircuit<Fr>,
+            PedersenMLE<Projective>,
+            PedersenMLE<Projective2>,
+            false,
+        >;
+        type D = Decider<
+            Projective,
+            Projective2,
+            CubicFCircuit<Fr>,
+            PedersenMLE<Projective>,
+            PedersenMLE<Projective2>,
+            Groth16<MNT4>,
+            Groth16<MNT6>,
+            N,
+        >;
+
+        let mut rng = ark_std::test_rng();
+        let poseidon_config = poseidon_canonical_config::<Fr>();
+
+        let F_circuit = CubicFCircuit::<Fr>::new(())?;
+        let z_0 = vec![Fr::from(3_u32)];
+
+        let prep_param = PreprocessorParam::new(poseidon_config, F_circuit);
+        let nova_params = N::preprocess(&mut rng, &prep_param)?;
+        let mut nova = N::init(&nova_params, F_circuit, z_0)?;
+        nova.prove_step(&mut rng, (), None)?;
+        nova.prove_step(&mut rng, (), None)?;
+
+        let mut rng = rand::rngs::OsRng;
+        let (decider_pp, decider_vp) =
+            D::preprocess(&mut rng, (nova_params, F_circuit.state_len()))?;
+        let proof = D::prove(rng, decider_pp, nova.clone())?;
+
+        let verified = D::verify(
+            decider_vp,
+            nova.i,
+            nova.z_0,
+            nova.z_i,
+            &nova.U_i.get_commitments(),
+            &nova.u_i.get_commitments(),
+            &proof,
+        )?;
+        assert!(verified);
+        Ok(())
+    }
 }
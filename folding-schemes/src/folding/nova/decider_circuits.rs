@@ -14,28 +14,32 @@ use super::{
 };
 use crate::commitment::CommitmentScheme;
 use crate::folding::{circuits::CF1, traits::WitnessOps};
+use crate::frontend::FCircuit;
+use crate::transcript::{poseidon::poseidon_canonical_config, SpongeWithGadget};
 use crate::{
     arith::r1cs::{circuits::R1CSMatricesVar, R1CS},
     folding::circuits::decider::{
         off_chain::{GenericOffchainDeciderCircuit1, GenericOffchainDeciderCircuit2},
-        EvalGadget, KZGChallengesGadget,
+        DeciderChallenge, KZGChallengesGadget,
     },
 };
-use crate::{frontend::FCircuit, transcript::poseidon::poseidon_custom_config};
 use crate::{Curve, Error};
 
 /// Circuit that implements part of the in-circuit checks needed for the offchain verification over
-/// the Curve2's BaseField (=Curve1's ScalarField).
-pub type DeciderCircuit1<C1, C2> = GenericOffchainDeciderCircuit1<
-    C1,
-    C2,
-    CommittedInstance<C1>,
-    CommittedInstance<C1>,
-    Witness<C1>,
-    R1CS<CF1<C1>>,
-    R1CSMatricesVar<CF1<C1>, FpVar<CF1<C1>>>,
-    DeciderNovaGadget,
->;
+/// the Curve2's BaseField (=Curve1's ScalarField), where `SP` is the sponge of Nova.
+pub type DeciderCircuit1<C1, C2, P = CF1<C1>, SP = PoseidonSponge<CF1<C1>>> =
+    GenericOffchainDeciderCircuit1<
+        C1,
+        C2,
+        CommittedInstance<C1>,
+        CommittedInstance<C1>,
+        Witness<C1>,
+        R1CS<CF1<C1>>,
+        R1CSMatricesVar<CF1<C1>, FpVar<CF1<C1>>>,
+        DeciderNovaGadget,
+        P,
+        SP,
+    >;
 
 impl<
         C1: Curve,
@@ -44,16 +48,19 @@ impl<
         CS1: CommitmentScheme<C1, H>,
         CS2: CommitmentScheme<C2, H>,
         const H: bool,
-    > TryFrom<Nova<C1, C2, FC, CS1, CS2, H>> for DeciderCircuit1<C1, C2>
+        SP: SpongeWithGadget<CF1<C1>>,
+    > TryFrom<Nova<C1, C2, FC, CS1, CS2, H, SP>> for DeciderCircuit1<C1, C2, CS1::Challenge, SP>
+where
+    CS1::Challenge: DeciderChallenge<CF1<C1>>,
 {
     type Error = Error;
 
-    fn try_from(nova: Nova<C1, C2, FC, CS1, CS2, H>) -> Result<Self, Error> {
-        let mut transcript = PoseidonSponge::<C1::ScalarField>::new(&nova.poseidon_config);
+    fn try_from(nova: Nova<C1, C2, FC, CS1, CS2, H, SP>) -> Result<Self, Error> {
+        let mut transcript = SP::new(&nova.sponge_config);
         // pp_hash is absorbed to transcript at the NIFS::prove call
 
         // compute the U_{i+1}, W_{i+1}
-        let (W_i1, U_i1, cmT, r_bits) = NIFS::<C1, CS1, PoseidonSponge<C1::ScalarField>, H>::prove(
+        let (W_i1, U_i1, cmT, r_bits) = NIFS::<C1, CS1, SP, H>::prove(
             &nova.cs_pp,
             &nova.r1cs.clone(),
             &mut transcript,
@@ -67,20 +74,26 @@ impl<
             .ok_or(Error::OutOfBounds)?;
 
         // compute the KZG challenges used as inputs in the circuit
-        let kzg_challenges = KZGChallengesGadget::get_challenges_native(&mut transcript, &U_i1);
+        let openings = W_i1.get_openings();
+        let lens = openings.iter().map(|(v, _)| v.len()).collect::<Vec<_>>();
+        let kzg_challenges = KZGChallengesGadget::get_opening_challenges_native::<
+            C1,
+            _,
+            _,
+            CS1::Challenge,
+        >(&mut transcript, &U_i1, &lens);
 
         // get KZG evals
-        let kzg_evaluations = W_i1
-            .get_openings()
+        let kzg_evaluations = openings
             .iter()
             .zip(&kzg_challenges)
-            .map(|((v, _), &c)| EvalGadget::evaluate_native(v, c))
+            .map(|((v, _), c)| c.evaluate_native(v))
             .collect::<Result<Vec<_>, _>>()?;
 
         Ok(Self {
             _avar: PhantomData,
             arith: nova.r1cs,
-            poseidon_config: nova.poseidon_config,
+            sponge_config: nova.sponge_config,
             pp_hash: nova.pp_hash,
             i: nova.i,
             z_0: nova.z_0,
@@ -102,7 +115,7 @@ impl<
 
 /// Circuit that implements part of the in-circuit checks needed for the offchain verification over
 /// the Curve1's BaseField (=Curve2's ScalarField).
-pub type DeciderCircuit2<C2> = GenericOffchainDeciderCircuit2<C2>;
+pub type DeciderCircuit2<C2, P = CF1<C2>> = GenericOffchainDeciderCircuit2<C2, P>;
 
 impl<
         C1: Curve,
@@ -111,39 +124,37 @@ impl<
         CS1: CommitmentScheme<C1, H>,
         CS2: CommitmentScheme<C2, H>,
         const H: bool,
-    > TryFrom<Nova<C1, C2, FC, CS1, CS2, H>> for DeciderCircuit2<C2>
+        SP: SpongeWithGadget<CF1<C1>>,
+    > TryFrom<Nova<C1, C2, FC, CS1, CS2, H, SP>> for DeciderCircuit2<C2, CS2::Challenge>
+where
+    CS2::Challenge: DeciderChallenge<CF1<C2>>,
 {
     type Error = Error;
 
-    fn try_from(nova: Nova<C1, C2, FC, CS1, CS2, H>) -> Result<Self, Error> {
-        // Create a poseidon config on `C2`'s scalar field for `circuit2`, with
-        // the same parameters (`full_rounds` etc.) as `circuit1` to ensure the
-        // security level is the same.
-        // Note: `ark` and `mds` will be different because they depend on the
-        // field, but they will not affect the security level.
-        let poseidon_config = poseidon_custom_config(
-            nova.poseidon_config.full_rounds,
-            nova.poseidon_config.partial_rounds,
-            nova.poseidon_config.alpha,
-            nova.poseidon_config.rate,
-            nova.poseidon_config.capacity,
-        );
+    fn try_from(nova: Nova<C1, C2, FC, CS1, CS2, H, SP>) -> Result<Self, Error> {
+        // `circuit2` derives its challenges with the canonical Poseidon config on `C2`'s scalar
+        // field, independently of the sponge `SP` that Nova uses over `C1`'s scalar field.
+        let poseidon_config = poseidon_canonical_config::<C2::ScalarField>();
         let mut transcript = PoseidonSponge::<C2::ScalarField>::new(&poseidon_config);
         let pp_hash_Fq =
             C2::ScalarField::from_le_bytes_mod_order(&nova.pp_hash.into_bigint().to_bytes_le());
         transcript.absorb(&pp_hash_Fq);
 
         // compute the KZG challenges used as inputs in the circuit
-        let kzg_challenges =
-            KZGChallengesGadget::get_challenges_native(&mut transcript, &nova.cf_U_i);
+        let openings = nova.cf_W_i.get_openings();
+        let lens = openings.iter().map(|(v, _)| v.len()).collect::<Vec<_>>();
+        let kzg_challenges = KZGChallengesGadget::get_opening_challenges_native::<
+            C2,
+            _,
+            _,
+            CS2::Challenge,
+        >(&mut transcript, &nova.cf_U_i, &lens);
 
         // get KZG evals
-        let kzg_evaluations = nova
-            .cf_W_i
-            .get_openings()
+        let kzg_evaluations = openings
             .iter()
             .zip(&kzg_challenges)
-            .map(|((v, _), &c)| EvalGadget::evaluate_native(v, c))
+            .map(|((v, _), c)| c.evaluate_native(v))
             .collect::<Result<Vec<_>, _>>()?;
 
         Ok(Self {
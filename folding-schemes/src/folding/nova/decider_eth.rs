@@ -2,20 +2,26 @@
 /// the Decider from decider.rs file will be more efficient.
 /// More details can be found at the documentation page:
 /// https://privacy-scaling-explorations.github.io/sonobe-docs/design/nova-decider-onchain.html
+use ark_crypto_primitives::sponge::poseidon::PoseidonSponge;
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
 use ark_snark::SNARK;
 use ark_std::{
+    fmt::{self, Debug, Formatter},
     rand::{CryptoRng, RngCore},
-    One, Zero,
+    One,
 };
 use core::marker::PhantomData;
 
 pub use super::decider_eth_circuit::DeciderEthCircuit;
 use super::decider_eth_circuit::DeciderNovaGadget;
 use super::Nova;
-use crate::folding::circuits::decider::DeciderEnabledNIFS;
+use crate::folding::circuits::{
+    decider::{on_chain::DeciderEthTranscript, DeciderEnabledNIFS},
+    CF1,
+};
 use crate::folding::traits::{InputizeNonNative, WitnessOps};
 use crate::frontend::FCircuit;
+use crate::transcript::{SpongeWithGadget, Transcript};
 use crate::{
     commitment::{kzg::Proof as KZGProof, pedersen::Params as PedersenParams, CommitmentScheme},
     folding::traits::Dummy,
@@ -24,27 +30,39 @@ use crate::{Curve, Error};
 use crate::{Decider as DeciderTrait, FoldingScheme};
 
 #[derive(Debug, Clone, Eq, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
-pub struct Proof<C, CS, S>
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(bound(
+        serialize = "CS::Proof: serde::Serialize",
+        deserialize = "CS::Proof: serde::Deserialize<'de>"
+    ))
+)]
+pub struct Proof<C, CS, S, const H: bool = false>
 where
     C: Curve,
-    CS: CommitmentScheme<C, ProverChallenge = C::ScalarField, Challenge = C::ScalarField>,
+    CS: CommitmentScheme<C, H, ProverChallenge = C::ScalarField, Challenge = C::ScalarField>,
     S: SNARK<C::ScalarField>,
 {
+    #[cfg_attr(feature = "serde", serde(with = "crate::utils::json::canonical"))]
     snark_proof: S::Proof,
     kzg_proofs: [CS::Proof; 2],
     // cmT and r are values for the last fold, U_{i+1}=NIFS.V(r, U_i, u_i, cmT), and they are
     // checked in-circuit
+    #[cfg_attr(feature = "serde", serde(with = "crate::utils::json::point"))]
     cmT: C,
+    #[cfg_attr(feature = "serde", serde(with = "crate::utils::json::field"))]
     r: C::ScalarField,
     // the KZG challenges are provided by the prover, but in-circuit they are checked to match
     // the in-circuit computed ones.
+    #[cfg_attr(feature = "serde", serde(with = "crate::utils::json::field_array"))]
     kzg_challenges: [C::ScalarField; 2],
 }
 
-impl<C, CS, S> Proof<C, CS, S>
+impl<C, CS, S, const H: bool> Proof<C, CS, S, H>
 where
     C: Curve,
-    CS: CommitmentScheme<C, ProverChallenge = C::ScalarField, Challenge = C::ScalarField>,
+    CS: CommitmentScheme<C, H, ProverChallenge = C::ScalarField, Challenge = C::ScalarField>,
     S: SNARK<C::ScalarField>,
 {
     pub fn snark_proof(&self) -> &S::Proof {
@@ -68,21 +86,118 @@ where
     }
 }
 
-#[derive(Debug, Clone, Eq, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
-pub struct VerifierParam<C1, CS_VerifyingKey, S_VerifyingKey>
-where
+/// Verifier parameters of the onchain Decider. `H` is the one of the `Decider` that generated them,
+/// and `T` is the transcript from which its KZG challenges are derived, so that the verifier
+/// contracts can be rendered accordingly.
+#[derive(CanonicalSerialize, CanonicalDeserialize)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(bound = "")
+)]
+pub struct VerifierParam<
+    C1,
+    CS_VerifyingKey,
+    S_VerifyingKey,
+    const H: bool = false,
+    T = PoseidonSponge<CF1<C1>>,
+> where
     C1: Curve,
     CS_VerifyingKey: Clone + CanonicalSerialize + CanonicalDeserialize,
     S_VerifyingKey: Clone + CanonicalSerialize + CanonicalDeserialize,
+    T: Send + Sync,
 {
+    #[cfg_attr(feature = "serde", serde(with = "crate::utils::json::field"))]
     pub pp_hash: C1::ScalarField,
+    #[cfg_attr(feature = "serde", serde(with = "crate::utils::json::canonical"))]
     pub snark_vp: S_VerifyingKey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::utils::json::canonical"))]
     pub cs_vp: CS_VerifyingKey,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    pub _t: PhantomData<T>,
+}
+
+// `Debug`, `Clone`, `PartialEq` and `Eq` are implemented by hand, since deriving them would
+// require the transcript `T` (which is only a marker here) to implement them as well.
+impl<C1, CS_VerifyingKey, S_VerifyingKey, const H: bool, T> Debug
+    for VerifierParam<C1, CS_VerifyingKey, S_VerifyingKey, H, T>
+where
+    C1: Curve,
+    CS_VerifyingKey: Clone + CanonicalSerialize + CanonicalDeserialize + Debug,
+    S_VerifyingKey: Clone + CanonicalSerialize + CanonicalDeserialize + Debug,
+    T: Send + Sync,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("VerifierParam")
+            .field("pp_hash", &self.pp_hash)
+            .field("snark_vp", &self.snark_vp)
+            .field("cs_vp", &self.cs_vp)
+            .finish()
+    }
 }
 
-/// Onchain Decider, for ethereum use cases
+impl<C1, CS_VerifyingKey, S_VerifyingKey, const H: bool, T> Clone
+    for VerifierParam<C1, CS_VerifyingKey, S_VerifyingKey, H, T>
+where
+    C1: Curve,
+    CS_VerifyingKey: Clone + CanonicalSerialize + CanonicalDeserialize,
+    S_VerifyingKey: Clone + CanonicalSerialize + CanonicalDeserialize,
+    T: Send + Sync,
+{
+    fn clone(&self) -> Self {
+        Self {
+            pp_hash: self.pp_hash,
+            snark_vp: self.snark_vp.clone(),
+            cs_vp: self.cs_vp.clone(),
+            _t: PhantomData,
+        }
+    }
+}
+
+impl<C1, CS_VerifyingKey, S_VerifyingKey, const H: bool, T> PartialEq
+    for VerifierParam<C1, CS_VerifyingKey, S_VerifyingKey, H, T>
+where
+    C1: Curve,
+    CS_VerifyingKey: Clone + CanonicalSerialize + CanonicalDeserialize + PartialEq,
+    S_VerifyingKey: Clone + CanonicalSerialize + CanonicalDeserialize + PartialEq,
+    T: Send + Sync,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.pp_hash == other.pp_hash
+            && self.snark_vp == other.snark_vp
+            && self.cs_vp == other.cs_vp
+    }
+}
+
+impl<C1, CS_VerifyingKey, S_VerifyingKey, const H: bool, T> Eq
+    for VerifierParam<C1, CS_VerifyingKey, S_VerifyingKey, H, T>
+where
+    C1: Curve,
+    CS_VerifyingKey: Clone + CanonicalSerialize + CanonicalDeserialize + Eq,
+    S_VerifyingKey: Clone + CanonicalSerialize + CanonicalDeserialize + Eq,
+    T: Send + Sync,
+{
+}
+
+/// Onchain Decider, for ethereum use cases. `H` indicates if the decided Nova instance uses hiding
+/// commitments, in which case the KZG openings are blinded accordingly. `T` is the transcript from
+/// which the KZG challenges are derived: with the default Poseidon transcript they are computed
+/// in-circuit, while with [`KeccakTranscript`](crate::transcript::keccak::KeccakTranscript) they
+/// are computed natively with `keccak256` by the verifier, which saves the in-circuit
+/// non-native absorption of the commitments. `SP` is the sponge of the decided Nova instance.
 #[derive(Clone, Debug)]
-pub struct Decider<C1, C2, FC, CS1, CS2, S, FS> {
+pub struct Decider<
+    C1: Curve,
+    C2,
+    FC,
+    CS1,
+    CS2,
+    S,
+    FS,
+    const H: bool = false,
+    T = PoseidonSponge<CF1<C1>>,
+    SP = PoseidonSponge<CF1<C1>>,
+> {
     _c1: PhantomData<C1>,
     _c2: PhantomData<C2>,
     _fc: PhantomData<FC>,
@@ -90,10 +205,12 @@ pub struct Decider<C1, C2, FC, CS1, CS2, S, FS> {
     _cs2: PhantomData<CS2>,
     _s: PhantomData<S>,
     _fs: PhantomData<FS>,
+    _t: PhantomData<T>,
+    _sp: PhantomData<SP>,
 }
 
-impl<C1, C2, FC, CS1, CS2, S, FS> DeciderTrait<C1, C2, FC, FS>
-    for Decider<C1, C2, FC, CS1, CS2, S, FS>
+impl<C1, C2, FC, CS1, CS2, S, FS, const H: bool, T, SP> DeciderTrait<C1, C2, FC, FS>
+    for Decider<C1, C2, FC, CS1, CS2, S, FS, H, T, SP>
 where
     C1: Curve<BaseField = C2::ScalarField, ScalarField = C2::BaseField>,
     C2: Curve,
@@ -101,25 +218,28 @@ where
     // CS1 is a KZG commitment, where challenge is C1::Fr elem
     CS1: CommitmentScheme<
         C1,
+        H,
         ProverChallenge = C1::ScalarField,
         Challenge = C1::ScalarField,
         Proof = KZGProof<C1>,
     >,
     // enforce that the CS2 is Pedersen commitment scheme, since we're at Ethereum's EVM decider
-    CS2: CommitmentScheme<C2, ProverParams = PedersenParams<C2>>,
+    CS2: CommitmentScheme<C2, H, ProverParams = PedersenParams<C2>>,
     S: SNARK<C1::ScalarField>,
     FS: FoldingScheme<C1, C2, FC>,
     // constrain FS into Nova, since this is a Decider specifically for Nova
-    Nova<C1, C2, FC, CS1, CS2, false>: From<FS>,
-    crate::folding::nova::ProverParams<C1, C2, CS1, CS2, false>:
+    Nova<C1, C2, FC, CS1, CS2, H, SP>: From<FS>,
+    crate::folding::nova::ProverParams<C1, C2, CS1, CS2, H, SP>:
         From<<FS as FoldingScheme<C1, C2, FC>>::ProverParam>,
-    crate::folding::nova::VerifierParams<C1, C2, CS1, CS2, false>:
+    crate::folding::nova::VerifierParams<C1, C2, CS1, CS2, H, SP>:
         From<<FS as FoldingScheme<C1, C2, FC>>::VerifierParam>,
+    T: DeciderEthTranscript<C1::ScalarField> + Send + Sync,
+    SP: SpongeWithGadget<C1::ScalarField>,
 {
     type PreprocessorParam = ((FS::ProverParam, FS::VerifierParam), usize);
     type ProverParam = (S::ProvingKey, CS1::ProverParams);
-    type Proof = Proof<C1, CS1, S>;
-    type VerifierParam = VerifierParam<C1, CS1::VerifierParams, S::VerifyingKey>;
+    type Proof = Proof<C1, CS1, S, H>;
+    type VerifierParam = VerifierParam<C1, CS1::VerifierParams, S::VerifyingKey, H, T>;
     type PublicInput = Vec<C1::ScalarField>;
     type CommittedInstance = Vec<C1>;
 
@@ -128,21 +248,18 @@ where
         ((pp, vp), state_len): Self::PreprocessorParam,
     ) -> Result<(Self::ProverParam, Self::VerifierParam), Error> {
         // get the FoldingScheme prover & verifier params from Nova
-        let nova_pp: <Nova<C1, C2, FC, CS1, CS2, false> as FoldingScheme<C1, C2, FC>>::ProverParam =
+        let nova_pp: <Nova<C1, C2, FC, CS1, CS2, H, SP> as FoldingScheme<C1, C2, FC>>::ProverParam =
             pp.into();
-        let nova_vp: <Nova<C1, C2, FC, CS1, CS2, false> as FoldingScheme<
-                    C1,
-                    C2,
-                    FC,
-                >>::VerifierParam = vp.into();
+        let nova_vp: <Nova<C1, C2, FC, CS1, CS2, H, SP> as FoldingScheme<C1, C2, FC>>::VerifierParam =
+            vp.into();
 
         let pp_hash = nova_vp.pp_hash()?;
 
-        let circuit = DeciderEthCircuit::<C1, C2>::dummy((
+        let circuit = DeciderEthCircuit::<C1, C2, T, SP>::dummy((
             nova_vp.r1cs,
             nova_vp.cf_r1cs,
             nova_pp.cf_cs_pp,
-            nova_pp.poseidon_config,
+            nova_pp.sponge_config,
             (),
             (),
             state_len,
@@ -158,6 +275,7 @@ where
             pp_hash,
             snark_vp: g16_vk,
             cs_vp: nova_vp.cs_vp,
+            _t: PhantomData,
         };
         Ok((pp, vp))
     }
@@ -169,7 +287,7 @@ where
     ) -> Result<Self::Proof, Error> {
         let (snark_pk, cs_pk): (S::ProvingKey, CS1::ProverParams) = pp;
 
-        let circuit = DeciderEthCircuit::<C1, C2>::try_from(Nova::from(folding_scheme))?;
+        let circuit = DeciderEthCircuit::<C1, C2, T, SP>::try_from(Nova::from(folding_scheme))?;
 
         let cmT = circuit.proof;
         let r = circuit.randomness;
@@ -178,15 +296,13 @@ where
         // the above `try_from` call
         let kzg_challenges = circuit.kzg_challenges.clone();
 
-        // generate KZG proofs
+        // generate KZG proofs, which in hiding mode are blinded with fresh randomness
         let kzg_proofs = circuit
             .W_i1
             .get_openings()
             .iter()
             .zip(&kzg_challenges)
-            .map(|((v, _), &c)| {
-                CS1::prove_with_challenge(&cs_pk, c, v, &C1::ScalarField::zero(), None)
-            })
+            .map(|((v, r), &c)| CS1::prove_with_challenge(&cs_pk, c, v, r, Some(&mut rng)))
             .collect::<Result<Vec<_>, _>>()?;
 
         let snark_proof =
@@ -223,6 +339,7 @@ where
             pp_hash,
             snark_vp,
             cs_vp,
+            ..
         } = vp;
 
         // 6.2. Fold the commitments
@@ -233,6 +350,16 @@ where
             proof.r,
         )?;
 
+        // 7.1. derive the KZG challenges, if they are not computed in-circuit
+        if let Some(mut transcript) = T::kzg_transcript(pp_hash) {
+            for (cm, c) in U_final_commitments.iter().zip(&proof.kzg_challenges) {
+                transcript.absorb_nonnative(cm);
+                if transcript.get_challenge() != *c {
+                    return Err(Error::KZGChallengesMismatch);
+                }
+            }
+        }
+
         let public_input = [
             &[pp_hash, i][..],
             &z_0,
@@ -267,15 +394,24 @@ where
 #[cfg(test)]
 pub mod tests {
     use super::*;
+    use crate::arith::Arith;
     use crate::commitment::kzg::KZG;
     use crate::commitment::pedersen::Pedersen;
-    use crate::folding::nova::{PreprocessorParam, ProverParams as NovaProverParams};
+    use crate::commitment::ptau::{load_ptau, tests_utils::ptau_bytes};
+    use crate::folding::envelope::Envelope;
+    use crate::folding::nova::{get_r1cs, PreprocessorParam, ProverParams as NovaProverParams};
     use crate::folding::traits::CommittedInstanceOps;
     use crate::frontend::utils::CubicFCircuit;
-    use crate::transcript::poseidon::poseidon_canonical_config;
+    use crate::transcript::{
+        keccak::KeccakTranscript,
+        poseidon::poseidon_canonical_config,
+        poseidon2::{poseidon2_canonical_config, Poseidon2Sponge},
+    };
     use ark_bn254::{Bn254, Fr, G1Projective as Projective};
     use ark_groth16::Groth16;
     use ark_grumpkin::Projective as Projective2;
+    use ark_std::{cmp::max, UniformRand, Zero};
+    use std::io::Cursor;
     use std::time::Instant;
 
     #[test]
@@ -354,6 +490,252 @@ pub mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_decider_poseidon2() -> Result<(), Error> {
+        // use Nova with the Poseidon2 sponge as FoldingScheme
+        type N = Nova<
+            Projective,
+            Projective2,
+            CubicFCircuit<Fr>,
+            KZG<'static, Bn254>,
+            Pedersen<Projective2>,
+            false,
+            Poseidon2Sponge<Fr>,
+        >;
+        type D = Decider<
+            Projective,
+            Projective2,
+            CubicFCircuit<Fr>,
+            KZG<'static, Bn254>,
+            Pedersen<Projective2>,
+            Groth16<Bn254>,
+            N,
+            false,
+            Poseidon2Sponge<Fr>,
+            Poseidon2Sponge<Fr>,
+        >;
+
+        let mut rng = rand::rngs::OsRng;
+
+        let F_circuit = CubicFCircuit::<Fr>::new(())?;
+        let z_0 = vec![Fr::from(3_u32)];
+
+        let preprocessor_param =
+            PreprocessorParam::new(poseidon2_canonical_config::<Fr>()?, F_circuit);
+        let nova_params = N::preprocess(&mut rng, &preprocessor_param)?;
+        let mut nova = N::init(&nova_params, F_circuit, z_0)?;
+
+        let (decider_pp, decider_vp) =
+            D::preprocess(&mut rng, (nova_params, F_circuit.state_len()))?;
+
+        nova.prove_step(&mut rng, (), None)?;
+        nova.prove_step(&mut rng, (), None)?;
+
+        let proof = D::prove(rng, decider_pp, nova.clone())?;
+        let verified = D::verify(
+            decider_vp,
+            nova.i,
+            nova.z_0,
+            nova.z_i,
+            &nova.U_i.get_commitments(),
+            &nova.u_i.get_commitments(),
+            &proof,
+        )?;
+        assert!(verified);
+        Ok(())
+    }
+
+    #[test]
+    fn test_decider_hiding() -> Result<(), Error> {
+        // use Nova with hiding commitments as FoldingScheme
+        type N = Nova<
+            Projective,
+            Projective2,
+            CubicFCircuit<Fr>,
+            KZG<'static, Bn254, true>,
+            Pedersen<Projective2, true>,
+            true,
+        >;
+        type D = Decider<
+            Projective,
+            Projective2,
+            CubicFCircuit<Fr>,
+            KZG<'static, Bn254, true>,
+            Pedersen<Projective2, true>,
+            Groth16<Bn254>,
+            N,
+            true,
+        >;
+
+        let mut rng = rand::rngs::OsRng;
+        let poseidon_config = poseidon_canonical_config::<Fr>();
+
+        let F_circuit = CubicFCircuit::<Fr>::new(())?;
+        let z_0 = vec![Fr::from(3_u32)];
+
+        let preprocessor_param = PreprocessorParam::new(poseidon_config, F_circuit);
+        let nova_params = N::preprocess(&mut rng, &preprocessor_param)?;
+        let mut nova = N::init(&nova_params, F_circuit, z_0)?;
+
+        let (decider_pp, decider_vp) =
+            D::preprocess(&mut rng, (nova_params, F_circuit.state_len()))?;
+
+        nova.prove_step(&mut rng, (), None)?;
+        nova.prove_step(&mut rng, (), None)?;
+
+        let proof = D::prove(rng, decider_pp, nova.clone())?;
+        // both openings are re-randomized with a fresh mask, even the one of cmE, which is
+        // committed with a zero blinding factor, since rE (and rT) are zero
+        assert!(proof
+            .kzg_proofs()
+            .iter()
+            .all(|p| !p.random_v.is_zero() && !p.mask.is_zero()));
+
+        let verified = D::verify(
+            decider_vp,
+            nova.i,
+            nova.z_0,
+            nova.z_i,
+            &nova.U_i.get_commitments(),
+            &nova.u_i.get_commitments(),
+            &proof,
+        )?;
+        assert!(verified);
+        Ok(())
+    }
+
+    #[test]
+    fn test_decider_keccak() -> Result<(), Error> {
+        type N = Nova<
+            Projective,
+            Projective2,
+            CubicFCircuit<Fr>,
+            KZG<'static, Bn254>,
+            Pedersen<Projective2>,
+            false,
+        >;
+        // the KZG challenges are derived with Keccak instead of in-circuit
+        type D = Decider<
+            Projective,
+            Projective2,
+            CubicFCircuit<Fr>,
+            KZG<'static, Bn254>,
+            Pedersen<Projective2>,
+            Groth16<Bn254>,
+            N,
+            false,
+            KeccakTranscript<Fr>,
+        >;
+
+        let mut rng = rand::rngs::OsRng;
+        let poseidon_config = poseidon_canonical_config::<Fr>();
+
+        let F_circuit = CubicFCircuit::<Fr>::new(())?;
+        let z_0 = vec![Fr::from(3_u32)];
+
+        let preprocessor_param = PreprocessorParam::new(poseidon_config, F_circuit);
+        let nova_params = N::preprocess(&mut rng, &preprocessor_param)?;
+        let mut nova = N::init(&nova_params, F_circuit, z_0)?;
+
+        let (decider_pp, decider_vp) =
+            D::preprocess(&mut rng, (nova_params, F_circuit.state_len()))?;
+
+        nova.prove_step(&mut rng, (), None)?;
+        nova.prove_step(&mut rng, (), None)?;
+
+        let mut proof = D::prove(rng, decider_pp, nova.clone())?;
+        let verified = D::verify(
+            decider_vp.clone(),
+            nova.i,
+            nova.z_0.clone(),
+            nova.z_i.clone(),
+            &nova.U_i.get_commitments(),
+            &nova.u_i.get_commitments(),
+            &proof,
+        )?;
+        assert!(verified);
+
+        // the circuit does not check the challenges, but the verifier derives them by itself
+        proof.kzg_challenges[0] += Fr::one();
+        assert!(matches!(
+            D::verify(
+                decider_vp,
+                nova.i,
+                nova.z_0,
+                nova.z_i,
+                &nova.U_i.get_commitments(),
+                &nova.u_i.get_commitments(),
+                &proof,
+            ),
+            Err(Error::KZGChallengesMismatch)
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_decider_with_ptau_srs() -> Result<(), Error> {
+        type N = Nova<
+            Projective,
+            Projective2,
+            CubicFCircuit<Fr>,
+            KZG<'static, Bn254>,
+            Pedersen<Projective2>,
+            false,
+        >;
+        type D = Decider<
+            Projective,
+            Projective2,
+            CubicFCircuit<Fr>,
+            KZG<'static, Bn254>,
+            Pedersen<Projective2>,
+            Groth16<Bn254>,
+            N,
+        >;
+
+        let mut rng = rand::rngs::OsRng;
+        let poseidon_config = poseidon_canonical_config::<Fr>();
+
+        let F_circuit = CubicFCircuit::<Fr>::new(())?;
+        let z_0 = vec![Fr::from(3_u32)];
+
+        // build a ceremony file large enough for Nova's witness and error vectors, and load the
+        // KZG parameters from it instead of sampling them
+        let (r1cs, _) = get_r1cs::<Projective, Projective2, _, PoseidonSponge<Fr>>(
+            &poseidon_config,
+            F_circuit,
+        )?;
+        let len = max(r1cs.n_constraints(), r1cs.n_witnesses());
+        let power = len.next_power_of_two().trailing_zeros();
+        let ptau = ptau_bytes(Fr::rand(&mut rng), power);
+        let (kzg_pk, kzg_vk) = load_ptau(&mut rng, Cursor::new(ptau), len)?;
+
+        let preprocessor_param = PreprocessorParam::new(poseidon_config, F_circuit)
+            .with_cs_params(kzg_pk.clone(), kzg_vk.clone());
+        let nova_params = N::preprocess(&mut rng, &preprocessor_param)?;
+        let mut nova = N::init(&nova_params, F_circuit, z_0)?;
+
+        let (decider_pp, decider_vp) =
+            D::preprocess(&mut rng, (nova_params, F_circuit.state_len()))?;
+        assert_eq!(decider_pp.1, kzg_pk);
+        assert_eq!(decider_vp.cs_vp.beta_h, kzg_vk.beta_h);
+
+        nova.prove_step(&mut rng, (), None)?;
+        nova.prove_step(&mut rng, (), None)?;
+
+        let proof = D::prove(rng, decider_pp, nova.clone())?;
+        let verified = D::verify(
+            decider_vp,
+            nova.i,
+            nova.z_0,
+            nova.z_i,
+            &nova.U_i.get_commitments(),
+            &nova.u_i.get_commitments(),
+            &proof,
+        )?;
+        assert!(verified);
+        Ok(())
+    }
+
     // Test to check the serialization and deserialization of diverse Decider related parameters.
     // This test is the same test as `test_decider` but it serializes values and then uses the
     // deserialized values to continue the checks.
@@ -432,7 +814,7 @@ pub mod tests {
 
         // decider proof generation
         let start = Instant::now();
-        let proof = D::prove(rng, decider_pp, nova.clone())?;
+        let proof = D::prove(rng, decider_pp.clone(), nova.clone())?;
         println!("Decider prove, {:?}", start.elapsed());
 
         // decider proof verification
@@ -494,6 +876,64 @@ pub mod tests {
             &proof_deserialized,
         )?;
         assert!(verified);
+
+        // the decider params and proof can also be written in the versioned format of
+        // `folding::envelope`, bound to the pp_hash of the Nova params
+        let pp_hash = nova.pp_hash;
+        let mut decider_pp_bytes = vec![];
+        N::write_decider_pp::<D>(
+            &decider_pp,
+            pp_hash,
+            &mut decider_pp_bytes,
+            ark_serialize::Compress::Yes,
+        )?;
+        let mut decider_vp_bytes = vec![];
+        N::write_decider_vp::<D>(
+            &decider_vp,
+            pp_hash,
+            &mut decider_vp_bytes,
+            ark_serialize::Compress::Yes,
+        )?;
+        let mut proof_bytes = vec![];
+        N::write_decider_proof::<D>(
+            &proof,
+            pp_hash,
+            &mut proof_bytes,
+            ark_serialize::Compress::Yes,
+        )?;
+
+        let decider_pp_read = N::read_decider_pp::<D>(&decider_pp_bytes[..], pp_hash)?;
+        let decider_vp_read = N::read_decider_vp::<D>(&decider_vp_bytes[..], pp_hash)?;
+        let proof_read = N::read_decider_proof::<D>(&proof_bytes[..], pp_hash)?;
+        assert!(D::verify(
+            decider_vp_read.clone(),
+            nova.i,
+            nova.z_0.clone(),
+            nova.z_i.clone(),
+            &nova.U_i.get_commitments(),
+            &nova.u_i.get_commitments(),
+            &proof_read,
+        )?);
+        let proof = D::prove(rng, decider_pp_read, nova.clone())?;
+        assert!(D::verify(
+            decider_vp_read,
+            nova.i,
+            nova.z_0,
+            nova.z_i,
+            &nova.U_i.get_commitments(),
+            &nova.u_i.get_commitments(),
+            &proof,
+        )?);
+
+        // wrong kind of content, and content bound to other params
+        assert!(matches!(
+            N::read_decider_proof::<D>(&decider_vp_bytes[..], pp_hash),
+            Err(Error::FormatMismatch(name, _, _)) if name == "kind"
+        ));
+        assert!(matches!(
+            N::read_decider_vp::<D>(&decider_vp_bytes[..], pp_hash + Fr::from(1_u32)),
+            Err(Error::PPHashMismatch)
+        ));
         Ok(())
     }
 }
@@ -3,9 +3,7 @@
 /// More details can be found at the documentation page:
 /// https://privacy-scaling-explorations.github.io/sonobe-docs/design/nova-decider-onchain.html
 use ark_crypto_primitives::sponge::{
-    constraints::CryptographicSpongeVar,
-    poseidon::{constraints::PoseidonSpongeVar, PoseidonSponge},
-    CryptographicSponge,
+    constraints::CryptographicSpongeVar, poseidon::PoseidonSponge, CryptographicSponge,
 };
 use ark_ff::{BigInteger, PrimeField};
 use ark_r1cs_std::{
@@ -23,11 +21,14 @@ use super::{
 use crate::commitment::{pedersen::Params as PedersenParams, CommitmentScheme};
 use crate::folding::{
     circuits::{
-        decider::on_chain::GenericOnchainDeciderCircuit, nonnative::affine::NonNativeAffineVar, CF1,
+        decider::on_chain::{DeciderEthTranscript, GenericOnchainDeciderCircuit},
+        nonnative::affine::NonNativeAffineVar,
+        CF1,
     },
     traits::{WitnessOps, WitnessVarOps},
 };
 use crate::frontend::FCircuit;
+use crate::transcript::{SpongeWithGadget, TranscriptVar};
 use crate::{
     arith::r1cs::{circuits::R1CSMatricesVar, R1CS},
     folding::circuits::decider::{DeciderEnabledNIFS, EvalGadget, KZGChallengesGadget},
@@ -73,16 +74,21 @@ impl<C: Curve> WitnessVarOps<C::ScalarField> for WitnessVar<C> {
     }
 }
 
-pub type DeciderEthCircuit<C1, C2> = GenericOnchainDeciderCircuit<
-    C1,
-    C2,
-    CommittedInstance<C1>,
-    CommittedInstance<C1>,
-    Witness<C1>,
-    R1CS<CF1<C1>>,
-    R1CSMatricesVar<CF1<C1>, FpVar<CF1<C1>>>,
-    DeciderNovaGadget,
->;
+/// Onchain decider circuit for Nova, where `T` is the transcript from which the KZG challenges
+/// are derived and `SP` is the sponge of Nova.
+pub type DeciderEthCircuit<C1, C2, T = PoseidonSponge<CF1<C1>>, SP = PoseidonSponge<CF1<C1>>> =
+    GenericOnchainDeciderCircuit<
+        C1,
+        C2,
+        CommittedInstance<C1>,
+        CommittedInstance<C1>,
+        Witness<C1>,
+        R1CS<CF1<C1>>,
+        R1CSMatricesVar<CF1<C1>, FpVar<CF1<C1>>>,
+        DeciderNovaGadget,
+        T,
+        SP,
+    >;
 
 /// returns an instance of the DeciderEthCircuit from the given Nova struct
 impl<
@@ -93,15 +99,17 @@ impl<
         // enforce that the CS2 is Pedersen commitment scheme, since we're at Ethereum's EVM decider
         CS2: CommitmentScheme<C2, H, ProverParams = PedersenParams<C2>>,
         const H: bool,
-    > TryFrom<Nova<C1, C2, FC, CS1, CS2, H>> for DeciderEthCircuit<C1, C2>
+        T: DeciderEthTranscript<CF1<C1>>,
+        SP: SpongeWithGadget<CF1<C1>>,
+    > TryFrom<Nova<C1, C2, FC, CS1, CS2, H, SP>> for DeciderEthCircuit<C1, C2, T, SP>
 {
     type Error = Error;
 
-    fn try_from(nova: Nova<C1, C2, FC, CS1, CS2, H>) -> Result<Self, Error> {
-        let mut transcript = PoseidonSponge::<C1::ScalarField>::new(&nova.poseidon_config);
+    fn try_from(nova: Nova<C1, C2, FC, CS1, CS2, H, SP>) -> Result<Self, Error> {
+        let mut transcript = SP::new(&nova.sponge_config);
 
         // compute the U_{i+1}, W_{i+1}
-        let (W_i1, U_i1, cmT, r_bits) = NIFS::<C1, CS1, PoseidonSponge<C1::ScalarField>, H>::prove(
+        let (W_i1, U_i1, cmT, r_bits) = NIFS::<C1, CS1, SP, H>::prove(
             &nova.cs_pp,
             &nova.r1cs.clone(),
             &mut transcript,
@@ -115,7 +123,12 @@ impl<
             .ok_or(Error::OutOfBounds)?;
 
         // compute the KZG challenges used as inputs in the circuit
-        let kzg_challenges = KZGChallengesGadget::get_challenges_native(&mut transcript, &U_i1);
+        let kzg_challenges = match T::kzg_transcript(nova.pp_hash) {
+            Some(mut kzg_transcript) => {
+                KZGChallengesGadget::get_challenges_native(&mut kzg_transcript, &U_i1)
+            }
+            None => KZGChallengesGadget::get_challenges_native(&mut transcript, &U_i1),
+        };
 
         // get KZG evals
         let kzg_evaluations = W_i1
@@ -127,10 +140,11 @@ impl<
 
         Ok(Self {
             _avar: PhantomData,
+            _t: PhantomData,
             arith: nova.r1cs,
             cf_arith: nova.cf_r1cs,
             cf_pedersen_params: nova.cf_cs_pp,
-            poseidon_config: nova.poseidon_config,
+            sponge_config: nova.sponge_config,
             pp_hash: nova.pp_hash,
             i: nova.i,
             z_0: nova.z_0,
@@ -162,9 +176,9 @@ impl<C: Curve>
     type RandomnessDummyCfg = ();
     type Randomness = CF1<C>;
 
-    fn fold_field_elements_gadget(
+    fn fold_field_elements_gadget<S: CryptographicSponge, T: TranscriptVar<CF1<C>, S>>(
         _arith: &R1CS<CF1<C>>,
-        transcript: &mut PoseidonSpongeVar<CF1<C>>,
+        transcript: &mut T,
         pp_hash: FpVar<CF1<C>>,
         U: CommittedInstanceVar<C>,
         U_vec: Vec<FpVar<CF1<C>>>,
@@ -174,7 +188,8 @@ impl<C: Curve>
     ) -> Result<CommittedInstanceVar<C>, SynthesisError> {
         let cs = transcript.cs();
         let cmT = NonNativeAffineVar::new_input(cs.clone(), || Ok(proof))?;
-        let (new_U, _) = NIFSGadget::verify(transcript, pp_hash, U, U_vec, u, Some(cmT))?;
+        let (new_U, _) =
+            NIFSGadget::<C, S, T>::verify(transcript, pp_hash, U, U_vec, u, Some(cmT))?;
         Ok(new_U)
     }
 
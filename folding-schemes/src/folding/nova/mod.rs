@@ -4,19 +4,25 @@
 /// The structure of the Nova code is the following:
 /// - NIFS implementation for Nova (nifs.rs), Mova (mova.rs), Ova (ova.rs)
 /// - IVC and the Decider (offchain Decider & onchain Decider) implementations for Nova
-use ark_crypto_primitives::sponge::{
-    poseidon::{PoseidonConfig, PoseidonSponge},
-    Absorb, CryptographicSponge,
-};
+/// - Non-uniform IVC (nivc), where each step executes one of several step circuits
+/// - Proof-carrying data (pcd), where the proofs of independent segments of the computation are
+///   merged following a binary tree
+use ark_crypto_primitives::sponge::{poseidon::PoseidonSponge, Absorb};
 use ark_ff::{BigInteger, PrimeField};
 use ark_r1cs_std::R1CSVar;
 use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystem, SynthesisMode};
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Valid};
-use ark_std::{cmp::max, fmt::Debug, marker::PhantomData, rand::RngCore, One, UniformRand, Zero};
+use ark_std::{
+    cmp::max,
+    fmt::{self, Debug, Formatter},
+    marker::PhantomData,
+    rand::RngCore,
+    One, UniformRand, Zero,
+};
 
-use crate::folding::{circuits::CF1, traits::Dummy};
+use crate::folding::{checkpoint::Checkpoint, circuits::CF1, envelope::Envelope, traits::Dummy};
 use crate::frontend::FCircuit;
-use crate::transcript::{poseidon::poseidon_canonical_config, Transcript};
+use crate::transcript::SpongeWithGadget;
 use crate::utils::vec::is_zero_vec;
 use crate::FoldingScheme;
 use crate::{
@@ -31,11 +37,16 @@ use crate::{
         CycleFoldWitness,
     },
 };
-use crate::{arith::ArithRelation, commitment::CommitmentScheme};
+use crate::{
+    arith::ArithRelation,
+    commitment::{CommitmentScheme, CommitmentSchemeId},
+};
 use crate::{Curve, Error};
 use decider_eth_circuit::WitnessVar;
 
 pub mod circuits;
+pub mod nivc;
+pub mod pcd;
 pub mod traits;
 pub mod zk;
 
@@ -73,10 +84,19 @@ impl<C: Curve> CycleFoldConfig for NovaCycleFoldConfig<C> {
 pub type NovaCycleFoldCircuit<C> = CycleFoldCircuit<NovaCycleFoldConfig<C>>;
 
 #[derive(Debug, Clone, Eq, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(bound = "")
+)]
 pub struct CommittedInstance<C: Curve> {
+    #[cfg_attr(feature = "serde", serde(with = "crate::utils::json::point"))]
     pub cmE: C,
+    #[cfg_attr(feature = "serde", serde(with = "crate::utils::json::field"))]
     pub u: C::ScalarField,
+    #[cfg_attr(feature = "serde", serde(with = "crate::utils::json::point"))]
     pub cmW: C,
+    #[cfg_attr(feature = "serde", serde(with = "crate::utils::json::field_vec"))]
     pub x: Vec<C::ScalarField>,
 }
 
@@ -137,27 +157,36 @@ impl<C: Curve> Inputize<CF1<C>> for CommittedInstance<C> {
 }
 
 #[derive(Debug, Clone, Eq, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(bound = "")
+)]
 pub struct Witness<C: Curve> {
+    #[cfg_attr(feature = "serde", serde(with = "crate::utils::json::field_vec"))]
     pub E: Vec<C::ScalarField>,
+    #[cfg_attr(feature = "serde", serde(with = "crate::utils::json::field"))]
     pub rE: C::ScalarField,
+    #[cfg_attr(feature = "serde", serde(with = "crate::utils::json::field_vec"))]
     pub W: Vec<C::ScalarField>,
+    #[cfg_attr(feature = "serde", serde(with = "crate::utils::json::field"))]
     pub rW: C::ScalarField,
 }
 
 impl<C: Curve> Witness<C> {
     pub fn new<const H: bool>(w: Vec<C::ScalarField>, e_len: usize, mut rng: impl RngCore) -> Self {
-        let (rW, rE) = if H {
-            (
-                C::ScalarField::rand(&mut rng),
-                C::ScalarField::rand(&mut rng),
-            )
+        let rW = if H {
+            C::ScalarField::rand(&mut rng)
         } else {
-            (C::ScalarField::zero(), C::ScalarField::zero())
+            C::ScalarField::zero()
         };
 
         Self {
+            // a fresh witness has E = 0, whose commitment is the zero point (as expected for an
+            // incoming instance), so rE must be zero too for the opening of cmE to stay
+            // consistent once the witness gets folded
             E: vec![C::ScalarField::zero(); e_len],
-            rE,
+            rE: C::ScalarField::zero(),
             W: w,
             rW,
         }
@@ -201,87 +230,190 @@ impl<C: Curve> WitnessOps<C::ScalarField> for Witness<C> {
     }
 }
 
-#[derive(Debug, Clone)]
-pub struct PreprocessorParam<C1, C2, FC, CS1, CS2, const H: bool = false>
-where
+/// Parameters used by `Nova::preprocess`.
+///
+/// The `T` type parameter selects the sponge used to hash the instances and to derive the
+/// challenges, both natively and in-circuit, and `sponge_config` holds its configuration (e.g.
+/// `PoseidonConfig` for the default `PoseidonSponge`, or `Poseidon2Config` for
+/// `Poseidon2Sponge`).
+#[derive(Clone)]
+pub struct PreprocessorParam<
+    C1,
+    C2,
+    FC,
+    CS1,
+    CS2,
+    const H: bool = false,
+    T = PoseidonSponge<CF1<C1>>,
+> where
     C1: Curve,
     C2: Curve,
     FC: FCircuit<C1::ScalarField>,
     CS1: CommitmentScheme<C1, H>,
     CS2: CommitmentScheme<C2, H>,
+    T: SpongeWithGadget<C1::ScalarField>,
 {
-    pub poseidon_config: PoseidonConfig<C1::ScalarField>,
+    pub sponge_config: T::Config,
     pub F: FC,
     // cs params if not provided, will be generated at the preprocess method
     pub cs_pp: Option<CS1::ProverParams>,
     pub cs_vp: Option<CS1::VerifierParams>,
     pub cf_cs_pp: Option<CS2::ProverParams>,
     pub cf_cs_vp: Option<CS2::VerifierParams>,
+    /// windows of the fixed-base tables to attach to the prover params of the commitment schemes
+    /// over C1 and C2 at the preprocess method, if any
+    pub fixed_base_window: Option<usize>,
+    pub cf_fixed_base_window: Option<usize>,
+}
+
+// `Debug` is implemented by hand for the structs that are generic over the sponge `T`, since
+// deriving it would require `T: Debug`, which arkworks' `PoseidonSponge` does not implement.
+impl<C1, C2, FC, CS1, CS2, const H: bool, T> Debug for PreprocessorParam<C1, C2, FC, CS1, CS2, H, T>
+where
+    C1: Curve,
+    C2: Curve,
+    FC: FCircuit<C1::ScalarField>,
+    CS1: CommitmentScheme<C1, H>,
+    CS2: CommitmentScheme<C2, H>,
+    T: SpongeWithGadget<C1::ScalarField>,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PreprocessorParam")
+            .field("sponge_config", &self.sponge_config)
+            .field("F", &self.F)
+            .field("cs_pp", &self.cs_pp)
+            .field("cs_vp", &self.cs_vp)
+            .field("cf_cs_pp", &self.cf_cs_pp)
+            .field("cf_cs_vp", &self.cf_cs_vp)
+            .field("fixed_base_window", &self.fixed_base_window)
+            .field("cf_fixed_base_window", &self.cf_fixed_base_window)
+            .finish()
+    }
 }
 
-impl<C1, C2, FC, CS1, CS2, const H: bool> PreprocessorParam<C1, C2, FC, CS1, CS2, H>
+impl<C1, C2, FC, CS1, CS2, const H: bool, T> PreprocessorParam<C1, C2, FC, CS1, CS2, H, T>
 where
     C1: Curve,
     C2: Curve,
     FC: FCircuit<C1::ScalarField>,
     CS1: CommitmentScheme<C1, H>,
     CS2: CommitmentScheme<C2, H>,
+    T: SpongeWithGadget<C1::ScalarField>,
 {
-    pub fn new(poseidon_config: PoseidonConfig<C1::ScalarField>, F: FC) -> Self {
+    pub fn new(sponge_config: T::Config, F: FC) -> Self {
         Self {
-            poseidon_config,
+            sponge_config,
             F,
             cs_pp: None,
             cs_vp: None,
             cf_cs_pp: None,
             cf_cs_vp: None,
+            fixed_base_window: None,
+            cf_fixed_base_window: None,
         }
     }
+
+    /// Sets the parameters of the commitment scheme over C1 (e.g. KZG parameters loaded from a
+    /// Powers of Tau ceremony through `commitment::ptau`), which are then used by `preprocess`
+    /// instead of generating new ones, and forwarded to the deciders.
+    pub fn with_cs_params(mut self, cs_pp: CS1::ProverParams, cs_vp: CS1::VerifierParams) -> Self {
+        self.cs_pp = Some(cs_pp);
+        self.cs_vp = Some(cs_vp);
+        self
+    }
+
+    /// Makes `preprocess` precompute fixed-base tables of windows of `window` bits for the
+    /// prover params of the commitment scheme over C1, when the scheme supports them (see
+    /// `CommitmentScheme::with_fixed_base_tables`). They speed up the commitments of every step at
+    /// the cost of `⌈254/window⌉` points per generator, so they are mostly worth it for the small
+    /// CycleFold params, see `with_cf_fixed_base_tables`.
+    pub fn with_fixed_base_tables(mut self, window: usize) -> Self {
+        self.fixed_base_window = Some(window);
+        self
+    }
+
+    /// Same as `with_fixed_base_tables`, for the commitment scheme over C2 used by CycleFold.
+    pub fn with_cf_fixed_base_tables(mut self, window: usize) -> Self {
+        self.cf_fixed_base_window = Some(window);
+        self
+    }
+
+    /// Attaches the requested fixed-base tables to the given prover params of the commitment
+    /// schemes over C1 and C2.
+    pub fn attach_fixed_base_tables(
+        &self,
+        cs_pp: CS1::ProverParams,
+        cf_cs_pp: CS2::ProverParams,
+    ) -> Result<(CS1::ProverParams, CS2::ProverParams), Error> {
+        let cs_pp = match self.fixed_base_window {
+            Some(window) => CS1::with_fixed_base_tables(cs_pp, window)?,
+            None => cs_pp,
+        };
+        let cf_cs_pp = match self.cf_fixed_base_window {
+            Some(window) => CS2::with_fixed_base_tables(cf_cs_pp, window)?,
+            None => cf_cs_pp,
+        };
+        Ok((cs_pp, cf_cs_pp))
+    }
 }
 
 /// Proving parameters for Nova-based IVC
-#[derive(Debug, Clone)]
-pub struct ProverParams<C1, C2, CS1, CS2, const H: bool = false>
+#[derive(Clone)]
+pub struct ProverParams<C1, C2, CS1, CS2, const H: bool = false, T = PoseidonSponge<CF1<C1>>>
 where
     C1: Curve,
     C2: Curve,
     CS1: CommitmentScheme<C1, H>,
     CS2: CommitmentScheme<C2, H>,
+    T: SpongeWithGadget<C1::ScalarField>,
 {
-    /// Poseidon sponge configuration
-    pub poseidon_config: PoseidonConfig<C1::ScalarField>,
+    /// Configuration of the sponge `T`
+    pub sponge_config: T::Config,
     /// Proving parameters of the underlying commitment scheme over C1
     pub cs_pp: CS1::ProverParams,
     /// Proving parameters of the underlying commitment scheme over C2
     pub cf_cs_pp: CS2::ProverParams,
 }
 
-impl<C1, C2, CS1, CS2, const H: bool> Valid for ProverParams<C1, C2, CS1, CS2, H>
+impl<C1, C2, CS1, CS2, const H: bool, T> Debug for ProverParams<C1, C2, CS1, CS2, H, T>
+where
+    C1: Curve,
+    C2: Curve,
+    CS1: CommitmentScheme<C1, H>,
+    CS2: CommitmentScheme<C2, H>,
+    T: SpongeWithGadget<C1::ScalarField>,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ProverParams")
+            .field("sponge_config", &self.sponge_config)
+            .field("cs_pp", &self.cs_pp)
+            .field("cf_cs_pp", &self.cf_cs_pp)
+            .finish()
+    }
+}
+
+impl<C1, C2, CS1, CS2, const H: bool, T> Valid for ProverParams<C1, C2, CS1, CS2, H, T>
 where
     C1: Curve,
     C2: Curve,
     CS1: CommitmentScheme<C1, H>,
     CS2: CommitmentScheme<C2, H>,
+    T: SpongeWithGadget<C1::ScalarField>,
 {
     fn check(&self) -> Result<(), ark_serialize::SerializationError> {
-        self.poseidon_config.full_rounds.check()?;
-        self.poseidon_config.partial_rounds.check()?;
-        self.poseidon_config.alpha.check()?;
-        self.poseidon_config.ark.check()?;
-        self.poseidon_config.mds.check()?;
-        self.poseidon_config.rate.check()?;
-        self.poseidon_config.capacity.check()?;
+        T::check_config(&self.sponge_config)?;
         self.cs_pp.check()?;
         self.cf_cs_pp.check()?;
         Ok(())
     }
 }
-impl<C1, C2, CS1, CS2, const H: bool> CanonicalSerialize for ProverParams<C1, C2, CS1, CS2, H>
+impl<C1, C2, CS1, CS2, const H: bool, T> CanonicalSerialize for ProverParams<C1, C2, CS1, CS2, H, T>
 where
     C1: Curve,
     C2: Curve,
     CS1: CommitmentScheme<C1, H>,
     CS2: CommitmentScheme<C2, H>,
+    T: SpongeWithGadget<C1::ScalarField>,
 {
     fn serialize_with_mode<W: std::io::prelude::Write>(
         &self,
@@ -296,12 +428,14 @@ where
         self.cs_pp.serialized_size(compress) + self.cf_cs_pp.serialized_size(compress)
     }
 }
-impl<C1, C2, CS1, CS2, const H: bool> CanonicalDeserialize for ProverParams<C1, C2, CS1, CS2, H>
+impl<C1, C2, CS1, CS2, const H: bool, T> CanonicalDeserialize
+    for ProverParams<C1, C2, CS1, CS2, H, T>
 where
     C1: Curve,
     C2: Curve,
     CS1: CommitmentScheme<C1, H>,
     CS2: CommitmentScheme<C2, H>,
+    T: SpongeWithGadget<C1::ScalarField>,
 {
     fn deserialize_with_mode<R: std::io::prelude::Read>(
         mut reader: R,
@@ -311,7 +445,8 @@ where
         let cs_pp = CS1::ProverParams::deserialize_with_mode(&mut reader, compress, validate)?;
         let cf_cs_pp = CS2::ProverParams::deserialize_with_mode(&mut reader, compress, validate)?;
         Ok(ProverParams {
-            poseidon_config: poseidon_canonical_config::<C1::ScalarField>(),
+            sponge_config: T::default_config()
+                .map_err(|_| ark_serialize::SerializationError::InvalidData)?,
             cs_pp,
             cf_cs_pp,
         })
@@ -319,16 +454,17 @@ where
 }
 
 /// Verification parameters for Nova-based IVC
-#[derive(Debug, Clone)]
-pub struct VerifierParams<C1, C2, CS1, CS2, const H: bool = false>
+#[derive(Clone)]
+pub struct VerifierParams<C1, C2, CS1, CS2, const H: bool = false, T = PoseidonSponge<CF1<C1>>>
 where
     C1: Curve,
     C2: Curve,
     CS1: CommitmentScheme<C1, H>,
     CS2: CommitmentScheme<C2, H>,
+    T: SpongeWithGadget<C1::ScalarField>,
 {
-    /// Poseidon sponge configuration
-    pub poseidon_config: PoseidonConfig<C1::ScalarField>,
+    /// Configuration of the sponge `T`
+    pub sponge_config: T::Config,
     /// R1CS of the Augmented step circuit
     pub r1cs: R1CS<C1::ScalarField>,
     /// R1CS of the CycleFold circuit
@@ -339,12 +475,32 @@ where
     pub cf_cs_vp: CS2::VerifierParams,
 }
 
-impl<C1, C2, CS1, CS2, const H: bool> Valid for VerifierParams<C1, C2, CS1, CS2, H>
+impl<C1, C2, CS1, CS2, const H: bool, T> Debug for VerifierParams<C1, C2, CS1, CS2, H, T>
 where
     C1: Curve,
     C2: Curve,
     CS1: CommitmentScheme<C1, H>,
     CS2: CommitmentScheme<C2, H>,
+    T: SpongeWithGadget<C1::ScalarField>,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("VerifierParams")
+            .field("sponge_config", &self.sponge_config)
+            .field("r1cs", &self.r1cs)
+            .field("cf_r1cs", &self.cf_r1cs)
+            .field("cs_vp", &self.cs_vp)
+            .field("cf_cs_vp", &self.cf_cs_vp)
+            .finish()
+    }
+}
+
+impl<C1, C2, CS1, CS2, const H: bool, T> Valid for VerifierParams<C1, C2, CS1, CS2, H, T>
+where
+    C1: Curve,
+    C2: Curve,
+    CS1: CommitmentScheme<C1, H>,
+    CS2: CommitmentScheme<C2, H>,
+    T: SpongeWithGadget<C1::ScalarField>,
 {
     fn check(&self) -> Result<(), ark_serialize::SerializationError> {
         self.cs_vp.check()?;
@@ -352,12 +508,14 @@ where
         Ok(())
     }
 }
-impl<C1, C2, CS1, CS2, const H: bool> CanonicalSerialize for VerifierParams<C1, C2, CS1, CS2, H>
+impl<C1, C2, CS1, CS2, const H: bool, T> CanonicalSerialize
+    for VerifierParams<C1, C2, CS1, CS2, H, T>
 where
     C1: Curve,
     C2: Curve,
     CS1: CommitmentScheme<C1, H>,
     CS2: CommitmentScheme<C2, H>,
+    T: SpongeWithGadget<C1::ScalarField>,
 {
     fn serialize_with_mode<W: std::io::prelude::Write>(
         &self,
@@ -373,36 +531,45 @@ where
     }
 }
 
-impl<C1, C2, CS1, CS2, const H: bool> VerifierParams<C1, C2, CS1, CS2, H>
+impl<C1, C2, CS1, CS2, const H: bool, T> VerifierParams<C1, C2, CS1, CS2, H, T>
 where
     C1: Curve,
     C2: Curve,
     CS1: CommitmentScheme<C1, H>,
     CS2: CommitmentScheme<C2, H>,
+    T: SpongeWithGadget<C1::ScalarField>,
 {
     /// returns the hash of the public parameters of Nova
     pub fn pp_hash(&self) -> Result<C1::ScalarField, Error> {
-        pp_hash::<C1, C2, CS1, CS2, H>(
+        pp_hash::<C1, C2, CS1, CS2, H, T>(
             &self.r1cs,
             &self.cf_r1cs,
             &self.cs_vp,
             &self.cf_cs_vp,
-            &self.poseidon_config,
+            &self.sponge_config,
         )
     }
 }
 
 #[derive(PartialEq, Eq, Debug, Clone, CanonicalSerialize, CanonicalDeserialize)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(bound = "")
+)]
 pub struct IVCProof<C1, C2>
 where
     C1: Curve,
     C2: Curve,
 {
     // current step of the IVC
+    #[cfg_attr(feature = "serde", serde(with = "crate::utils::json::field"))]
     pub i: C1::ScalarField,
     // initial state
+    #[cfg_attr(feature = "serde", serde(with = "crate::utils::json::field_vec"))]
     pub z_0: Vec<C1::ScalarField>,
     // current state
+    #[cfg_attr(feature = "serde", serde(with = "crate::utils::json::field_vec"))]
     pub z_i: Vec<C1::ScalarField>,
     // running instance
     pub W_i: Witness<C1>,
@@ -417,21 +584,24 @@ where
 
 /// Implements Nova+CycleFold's IVC, described in [Nova](https://eprint.iacr.org/2021/370.pdf) and
 /// [CycleFold](https://eprint.iacr.org/2023/1192.pdf), following the FoldingScheme trait
-/// The `H` const generic specifies whether the homorphic commitment scheme is blinding
-#[derive(Clone, Debug)]
-pub struct Nova<C1, C2, FC, CS1, CS2, const H: bool = false>
+/// The `H` const generic specifies whether the homorphic commitment scheme is blinding, and the
+/// `T` type parameter selects the sponge used to hash the instances and to derive the challenges.
+#[derive(Clone)]
+pub struct Nova<C1, C2, FC, CS1, CS2, const H: bool = false, T = PoseidonSponge<CF1<C1>>>
 where
     C1: Curve,
     C2: Curve,
     FC: FCircuit<C1::ScalarField>,
     CS1: CommitmentScheme<C1, H>,
     CS2: CommitmentScheme<C2, H>,
+    T: SpongeWithGadget<C1::ScalarField>,
 {
     /// R1CS of the Augmented Function circuit
     pub r1cs: R1CS<C1::ScalarField>,
     /// R1CS of the CycleFold circuit
     pub cf_r1cs: R1CS<C2::ScalarField>,
-    pub poseidon_config: PoseidonConfig<C1::ScalarField>,
+    /// Configuration of the sponge `T`
+    pub sponge_config: T::Config,
     /// CommitmentScheme::ProverParams over C1
     pub cs_pp: CS1::ProverParams,
     /// CycleFold CommitmentScheme::ProverParams, over C2
@@ -456,8 +626,39 @@ where
     pub cf_U_i: CycleFoldCommittedInstance<C2>,
 }
 
-impl<C1, C2, FC, CS1, CS2, const H: bool> FoldingScheme<C1, C2, FC>
-    for Nova<C1, C2, FC, CS1, CS2, H>
+impl<C1, C2, FC, CS1, CS2, const H: bool, T> Debug for Nova<C1, C2, FC, CS1, CS2, H, T>
+where
+    C1: Curve,
+    C2: Curve,
+    FC: FCircuit<C1::ScalarField>,
+    CS1: CommitmentScheme<C1, H>,
+    CS2: CommitmentScheme<C2, H>,
+    T: SpongeWithGadget<C1::ScalarField>,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Nova")
+            .field("r1cs", &self.r1cs)
+            .field("cf_r1cs", &self.cf_r1cs)
+            .field("sponge_config", &self.sponge_config)
+            .field("cs_pp", &self.cs_pp)
+            .field("cf_cs_pp", &self.cf_cs_pp)
+            .field("F", &self.F)
+            .field("pp_hash", &self.pp_hash)
+            .field("i", &self.i)
+            .field("z_0", &self.z_0)
+            .field("z_i", &self.z_i)
+            .field("w_i", &self.w_i)
+            .field("u_i", &self.u_i)
+            .field("W_i", &self.W_i)
+            .field("U_i", &self.U_i)
+            .field("cf_W_i", &self.cf_W_i)
+            .field("cf_U_i", &self.cf_U_i)
+            .finish()
+    }
+}
+
+impl<C1, C2, FC, CS1, CS2, const H: bool, T> FoldingScheme<C1, C2, FC>
+    for Nova<C1, C2, FC, CS1, CS2, H, T>
 where
     C1: Curve,
     C2: Curve,
@@ -465,10 +666,11 @@ where
     CS1: CommitmentScheme<C1, H>,
     CS2: CommitmentScheme<C2, H>,
     C1: Curve<BaseField = C2::ScalarField, ScalarField = C2::BaseField>,
+    T: SpongeWithGadget<C1::ScalarField>,
 {
-    type PreprocessorParam = PreprocessorParam<C1, C2, FC, CS1, CS2, H>;
-    type ProverParam = ProverParams<C1, C2, CS1, CS2, H>;
-    type VerifierParam = VerifierParams<C1, C2, CS1, CS2, H>;
+    type PreprocessorParam = PreprocessorParam<C1, C2, FC, CS1, CS2, H, T>;
+    type ProverParam = ProverParams<C1, C2, CS1, CS2, H, T>;
+    type VerifierParam = VerifierParams<C1, C2, CS1, CS2, H, T>;
     type RunningInstance = (CommittedInstance<C1>, Witness<C1>);
     type IncomingInstance = (CommittedInstance<C1>, Witness<C1>);
     type MultiCommittedInstanceWithWitness = ();
@@ -491,7 +693,7 @@ where
         validate: ark_serialize::Validate,
         fc_params: FC::Params,
     ) -> Result<Self::VerifierParam, Error> {
-        let poseidon_config = poseidon_canonical_config::<C1::ScalarField>();
+        let sponge_config = T::default_config()?;
 
         // generate the r1cs & cf_r1cs needed for the VerifierParams. In this way we avoid needing
         // to serialize them, saving significant space in the VerifierParams serialized size.
@@ -501,7 +703,7 @@ where
         let cs = ConstraintSystem::<C1::ScalarField>::new_ref();
         cs.set_mode(SynthesisMode::Setup);
         let augmented_F_circuit =
-            AugmentedFCircuit::<C1, C2, FC>::empty(&poseidon_config, f_circuit.clone());
+            AugmentedFCircuit::<C1, C2, FC, T>::empty(&sponge_config, f_circuit.clone());
         augmented_F_circuit.generate_constraints(cs.clone())?;
         cs.finalize();
         let cs = cs.into_inner().ok_or(Error::NoInnerConstraintSystem)?;
@@ -520,7 +722,7 @@ where
         let cf_cs_vp = CS2::VerifierParams::deserialize_with_mode(&mut reader, compress, validate)?;
 
         Ok(Self::VerifierParam {
-            poseidon_config,
+            sponge_config,
             r1cs,
             cf_r1cs,
             cs_vp,
@@ -533,7 +735,7 @@ where
         prep_param: &Self::PreprocessorParam,
     ) -> Result<(Self::ProverParam, Self::VerifierParam), Error> {
         let (r1cs, cf_r1cs) =
-            get_r1cs::<C1, C2, FC>(&prep_param.poseidon_config, prep_param.F.clone())?;
+            get_r1cs::<C1, C2, FC, T>(&prep_param.sponge_config, prep_param.F.clone())?;
 
         // if cs params exist, use them, if not, generate new ones
         let (cs_pp, cs_vp) = match (&prep_param.cs_pp, &prep_param.cs_vp) {
@@ -558,14 +760,15 @@ where
                 max(cf_r1cs.n_constraints(), cf_r1cs.n_witnesses()),
             )?,
         };
+        let (cs_pp, cf_cs_pp) = prep_param.attach_fixed_base_tables(cs_pp, cf_cs_pp)?;
 
-        let prover_params = ProverParams::<C1, C2, CS1, CS2, H> {
-            poseidon_config: prep_param.poseidon_config.clone(),
+        let prover_params = ProverParams::<C1, C2, CS1, CS2, H, T> {
+            sponge_config: prep_param.sponge_config.clone(),
             cs_pp: cs_pp.clone(),
             cf_cs_pp: cf_cs_pp.clone(),
         };
-        let verifier_params = VerifierParams::<C1, C2, CS1, CS2, H> {
-            poseidon_config: prep_param.poseidon_config.clone(),
+        let verifier_params = VerifierParams::<C1, C2, CS1, CS2, H, T> {
+            sponge_config: prep_param.sponge_config.clone(),
             r1cs,
             cf_r1cs,
             cs_vp,
@@ -590,7 +793,7 @@ where
         cs2.set_mode(SynthesisMode::Setup);
 
         let augmented_F_circuit =
-            AugmentedFCircuit::<C1, C2, FC>::empty(&pp.poseidon_config, F.clone());
+            AugmentedFCircuit::<C1, C2, FC, T>::empty(&pp.sponge_config, F.clone());
         let cf_circuit = NovaCycleFoldCircuit::<C1>::empty();
 
         augmented_F_circuit.generate_constraints(cs.clone())?;
@@ -616,7 +819,7 @@ where
         Ok(Self {
             r1cs,
             cf_r1cs,
-            poseidon_config: pp.poseidon_config.clone(),
+            sponge_config: pp.sponge_config.clone(),
             cs_pp: pp.cs_pp.clone(),
             cf_cs_pp: pp.cf_cs_pp.clone(),
             F,
@@ -643,12 +846,14 @@ where
         _other_instances: Option<Self::MultiCommittedInstanceWithWitness>,
     ) -> Result<(), Error> {
         // ensure that commitments are blinding if user has specified so.
+        // Only the blinding of cmW is checked: a fresh witness has E = 0 and rE = 0, and since
+        // cmT is committed with rT = 0, rE stays zero after folding.
         if H && self.i >= C1::ScalarField::one() {
             let blinding_commitments = if self.i == C1::ScalarField::one() {
                 // blinding values of the running instances are zero at the first iteration
-                vec![self.w_i.rW, self.w_i.rE]
+                vec![self.w_i.rW]
             } else {
-                vec![self.w_i.rW, self.w_i.rE, self.W_i.rW, self.W_i.rE]
+                vec![self.w_i.rW, self.W_i.rW]
             };
             if blinding_commitments.contains(&C1::ScalarField::zero()) {
                 return Err(Error::IncorrectBlinding(
@@ -658,11 +863,11 @@ where
             }
         }
         // `sponge` is for digest computation.
-        let sponge = PoseidonSponge::<C1::ScalarField>::new(&self.poseidon_config);
+        let sponge = T::new(&self.sponge_config);
         // `transcript` is for challenge generation.
         let mut transcript = sponge.clone();
 
-        let augmented_F_circuit: AugmentedFCircuit<C1, C2, FC>;
+        let augmented_F_circuit: AugmentedFCircuit<C1, C2, FC, T>;
 
         // Nova does not support (by design) multi-instances folding
         if _other_instances.is_some() {
@@ -700,7 +905,7 @@ where
 
         // fold Nova instances
         let (W_i1, U_i1, cmT, r_bits): (Witness<C1>, CommittedInstance<C1>, C1, Vec<bool>) =
-            NIFS::<C1, CS1, PoseidonSponge<C1::ScalarField>, H>::prove(
+            NIFS::<C1, CS1, T, H>::prove(
                 &self.cs_pp,
                 &self.r1cs,
                 &mut transcript,
@@ -713,8 +918,8 @@ where
 
         if self.i == C1::ScalarField::zero() {
             // base case
-            augmented_F_circuit = AugmentedFCircuit::<C1, C2, FC> {
-                poseidon_config: self.poseidon_config.clone(),
+            augmented_F_circuit = AugmentedFCircuit::<C1, C2, FC, T> {
+                sponge_config: self.sponge_config.clone(),
                 pp_hash: Some(self.pp_hash),
                 i: Some(C1::ScalarField::zero()), // = i=0
                 i_usize: Some(0),
@@ -738,10 +943,9 @@ where
             {
                 let r_Fr = C1::ScalarField::from_bigint(BigInteger::from_bits_le(&r_bits))
                     .ok_or(Error::OutOfBounds)?;
-                let expected =
-                    NIFS::<C1, CS1, PoseidonSponge<C1::ScalarField>, H>::fold_committed_instances(
-                        r_Fr, &self.U_i, &self.u_i, &cmT,
-                    );
+                let expected = NIFS::<C1, CS1, T, H>::fold_committed_instances(
+                    r_Fr, &self.U_i, &self.u_i, &cmT,
+                );
                 assert_eq!(U_i1, expected);
             }
         } else {
@@ -772,8 +976,8 @@ where
                 &mut rng,
             )?;
 
-            augmented_F_circuit = AugmentedFCircuit::<C1, C2, FC> {
-                poseidon_config: self.poseidon_config.clone(),
+            augmented_F_circuit = AugmentedFCircuit::<C1, C2, FC, T> {
+                sponge_config: self.sponge_config.clone(),
                 pp_hash: Some(self.pp_hash),
                 i: Some(self.i),
                 i_usize: Some(i_usize),
@@ -875,7 +1079,7 @@ where
         let cs2 = ConstraintSystem::<C1::BaseField>::new_ref();
         cs2.set_mode(SynthesisMode::Setup);
         let augmented_F_circuit =
-            AugmentedFCircuit::<C1, C2, FC>::empty(&pp.poseidon_config, f_circuit.clone());
+            AugmentedFCircuit::<C1, C2, FC, T>::empty(&pp.sponge_config, f_circuit.clone());
         let cf_circuit = NovaCycleFoldCircuit::<C1>::empty();
 
         augmented_F_circuit.generate_constraints(cs.clone())?;
@@ -891,7 +1095,7 @@ where
         Ok(Self {
             r1cs,
             cf_r1cs,
-            poseidon_config: pp.poseidon_config,
+            sponge_config: pp.sponge_config,
             cs_pp: pp.cs_pp,
             cf_cs_pp: pp.cf_cs_pp,
             F: f_circuit,
@@ -923,7 +1127,7 @@ where
             cf_U_i,
         } = ivc_proof;
 
-        let sponge = PoseidonSponge::<C1::ScalarField>::new(&vp.poseidon_config);
+        let sponge = T::new(&vp.sponge_config);
 
         if num_steps == C1::ScalarField::zero() {
             if z_0 != z_i {
@@ -964,7 +1168,8 @@ where
     }
 }
 
-impl<C1, C2, FC, CS1, CS2, const H: bool> Nova<C1, C2, FC, CS1, CS2, H>
+impl<C1, C2, FC, CS1, CS2, const H: bool, T> Checkpoint<C1, C2, FC>
+    for Nova<C1, C2, FC, CS1, CS2, H, T>
 where
     C1: Curve,
     C2: Curve,
@@ -972,10 +1177,53 @@ where
     CS1: CommitmentScheme<C1, H>,
     CS2: CommitmentScheme<C2, H>,
     C1: Curve<BaseField = C2::ScalarField, ScalarField = C2::BaseField>,
+    T: SpongeWithGadget<C1::ScalarField>,
+{
+    fn pp_hash(&self) -> C1::ScalarField {
+        self.pp_hash
+    }
+}
+
+impl<C1, C2, FC, CS1, CS2, const H: bool, T> Envelope<C1, C2, FC>
+    for Nova<C1, C2, FC, CS1, CS2, H, T>
+where
+    C1: Curve,
+    C2: Curve,
+    FC: FCircuit<C1::ScalarField>,
+    CS1: CommitmentScheme<C1, H> + CommitmentSchemeId,
+    CS2: CommitmentScheme<C2, H> + CommitmentSchemeId,
+    C1: Curve<BaseField = C2::ScalarField, ScalarField = C2::BaseField>,
+    T: SpongeWithGadget<C1::ScalarField>,
+{
+    fn scheme_id() -> String {
+        format!("nova-{}", T::ID)
+    }
+
+    fn commitment_ids() -> [(String, bool); 2] {
+        [
+            (CS1::ID.to_string(), CS1::is_hiding()),
+            (CS2::ID.to_string(), CS2::is_hiding()),
+        ]
+    }
+
+    fn vp_pp_hash(vp: &Self::VerifierParam) -> Result<C1::ScalarField, Error> {
+        vp.pp_hash()
+    }
+}
+
+impl<C1, C2, FC, CS1, CS2, const H: bool, T> Nova<C1, C2, FC, CS1, CS2, H, T>
+where
+    C1: Curve,
+    C2: Curve,
+    FC: FCircuit<C1::ScalarField>,
+    CS1: CommitmentScheme<C1, H>,
+    CS2: CommitmentScheme<C2, H>,
+    C1: Curve<BaseField = C2::ScalarField, ScalarField = C2::BaseField>,
+    T: SpongeWithGadget<C1::ScalarField>,
 {
     // folds the given cyclefold circuit and its instances
     #[allow(clippy::type_complexity)]
-    fn fold_cyclefold_circuit<T: Transcript<C1::ScalarField>>(
+    fn fold_cyclefold_circuit(
         &self,
         transcript: &mut T,
         cf_W_i: CycleFoldWitness<C2>, // witness of the running instance
@@ -1017,10 +1265,11 @@ pub fn get_r1cs_from_cs<F: PrimeField>(
     Ok(r1cs)
 }
 
-/// helper method to get the R1CS for both the AugmentedFCircuit and the CycleFold circuit
+/// helper method to get the R1CS for both the AugmentedFCircuit and the CycleFold circuit, where
+/// `sponge_config` is the configuration of the sponge `T`
 #[allow(clippy::type_complexity)]
-pub fn get_r1cs<C1, C2, FC>(
-    poseidon_config: &PoseidonConfig<C1::ScalarField>,
+pub fn get_r1cs<C1, C2, FC, T>(
+    sponge_config: &T::Config,
     F_circuit: FC,
 ) -> Result<(R1CS<C1::ScalarField>, R1CS<C2::ScalarField>), Error>
 where
@@ -1028,8 +1277,9 @@ where
     C2: Curve,
     FC: FCircuit<C1::ScalarField>,
     C1: Curve<BaseField = C2::ScalarField, ScalarField = C2::BaseField>,
+    T: SpongeWithGadget<C1::ScalarField>,
 {
-    let augmented_F_circuit = AugmentedFCircuit::<C1, C2, FC>::empty(poseidon_config, F_circuit);
+    let augmented_F_circuit = AugmentedFCircuit::<C1, C2, FC, T>::empty(sponge_config, F_circuit);
     let cf_circuit = NovaCycleFoldCircuit::<C1>::empty();
     let r1cs = get_r1cs_from_cs::<C1::ScalarField>(augmented_F_circuit)?;
     let cf_r1cs = get_r1cs_from_cs::<C2::ScalarField>(cf_circuit)?;
@@ -1042,10 +1292,13 @@ pub mod tests {
     use ark_bn254::{Bn254, Fr, G1Projective as Projective};
     use ark_grumpkin::Projective as Projective2;
 
+    use ark_crypto_primitives::sponge::poseidon::PoseidonConfig;
+
     use super::*;
     use crate::commitment::pedersen::Pedersen;
     use crate::frontend::utils::CubicFCircuit;
     use crate::transcript::poseidon::poseidon_canonical_config;
+    use crate::transcript::poseidon2::{poseidon2_canonical_config, Poseidon2Sponge};
 
     /// This test tests the Nova+CycleFold IVC, and by consequence it is also testing the
     /// AugmentedFCircuit
@@ -1078,6 +1331,71 @@ pub mod tests {
         Ok(())
     }
 
+    /// Checks that the fixed-base tables requested through the `PreprocessorParam` are attached
+    /// to the prover params by `preprocess`, and that the IVC works with them.
+    #[test]
+    fn test_ivc_fixed_base_tables() -> Result<(), Error> {
+        type N = Nova<
+            Projective,
+            Projective2,
+            CubicFCircuit<Fr>,
+            Pedersen<Projective>,
+            Pedersen<Projective2>,
+            false,
+        >;
+        let mut rng = ark_std::test_rng();
+        let F_circuit = CubicFCircuit::<Fr>::new(())?;
+
+        let prep_param = PreprocessorParam::new(poseidon_canonical_config::<Fr>(), F_circuit)
+            .with_fixed_base_tables(8)
+            .with_cf_fixed_base_tables(12);
+        let (pp, vp) = N::preprocess(&mut rng, &prep_param)?;
+        assert_eq!(pp.cs_pp.tables.as_ref().map(|t| t.window), Some(8));
+        assert_eq!(pp.cf_cs_pp.tables.as_ref().map(|t| t.window), Some(12));
+
+        let mut nova = N::init(&(pp, vp.clone()), F_circuit, vec![Fr::from(3_u32)])?;
+        for _ in 0..3 {
+            nova.prove_step(&mut rng, (), None)?;
+        }
+        N::verify(vp, nova.ivc_proof())?;
+
+        // unsupported windows are rejected by `preprocess`
+        let prep_param = PreprocessorParam::new(poseidon_canonical_config::<Fr>(), F_circuit)
+            .with_cf_fixed_base_tables(32);
+        assert!(matches!(
+            N::preprocess(&mut rng, &prep_param),
+            Err(Error::InvalidFixedBaseWindow(32, _))
+        ));
+        Ok(())
+    }
+
+    /// Runs the Nova+CycleFold IVC with the Poseidon2 sponge, which is selected through the
+    /// `PreprocessorParam`.
+    #[test]
+    fn test_ivc_poseidon2() -> Result<(), Error> {
+        type N = Nova<
+            Projective,
+            Projective2,
+            CubicFCircuit<Fr>,
+            KZG<'static, Bn254>,
+            Pedersen<Projective2>,
+            false,
+            Poseidon2Sponge<Fr>,
+        >;
+        let mut rng = ark_std::test_rng();
+        let F_circuit = CubicFCircuit::<Fr>::new(())?;
+        let poseidon2_config = poseidon2_canonical_config::<Fr>()?;
+
+        let prep_param = PreprocessorParam::new(poseidon2_config, F_circuit);
+        let nova_params = N::preprocess(&mut rng, &prep_param)?;
+        let mut nova = N::init(&nova_params, F_circuit, vec![Fr::from(3_u32)])?;
+        for _ in 0..3 {
+            nova.prove_step(&mut rng, (), None)?;
+        }
+        N::verify(nova_params.1, nova.ivc_proof())?;
+        Ok(())
+    }
+
     // test_ivc allowing to choose the CommitmentSchemes
     #[allow(clippy::type_complexity)]
     pub(crate) fn test_ivc_opt<
@@ -1099,7 +1417,7 @@ pub mod tests {
 
         let prep_param =
             PreprocessorParam::<Projective, Projective2, CubicFCircuit<Fr>, CS1, CS2, H> {
-                poseidon_config,
+                sponge_config: poseidon_config,
                 F: F_circuit,
                 cs_pp: None,
                 cs_vp: None,
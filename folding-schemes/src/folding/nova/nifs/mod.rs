@@ -20,6 +20,7 @@ use crate::transcript::{Transcript, TranscriptVar};
 use crate::{Curve, Error};
 
 pub mod mova;
+pub mod mova_circuits;
 pub mod nova;
 pub mod nova_circuits;
 pub mod ova;
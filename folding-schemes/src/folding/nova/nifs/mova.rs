@@ -1,20 +1,22 @@
 /// This module contains the implementation the NIFSTrait for the
 /// [Mova](https://eprint.iacr.org/2024/1220.pdf) NIFS (Non-Interactive Folding Scheme).
 use ark_crypto_primitives::sponge::Absorb;
-use ark_ff::PrimeField;
-use ark_poly::Polynomial;
+use ark_ff::{BigInteger, PrimeField};
+use ark_poly::{univariate::DensePolynomial, Polynomial};
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
 use ark_std::{log2, marker::PhantomData, rand::RngCore, One, UniformRand, Zero};
 
 use super::{
+    mova_circuits::CommittedInstanceVar,
     nova::NIFS as NovaNIFS,
     pointvsline::{PointVsLine, PointVsLineProof, PointvsLineEvaluationClaim},
     NIFSTrait,
 };
 use crate::arith::{r1cs::R1CS, Arith, ArithRelation};
 use crate::commitment::CommitmentScheme;
+use crate::constants::MOVA_NIFS_DOMAIN;
 use crate::folding::circuits::CF1;
-use crate::folding::traits::Dummy;
+use crate::folding::traits::{CommittedInstanceOps, Dummy, Inputize};
 use crate::transcript::Transcript;
 use crate::utils::{
     mle::dense_vec_to_dense_mle,
@@ -59,6 +61,42 @@ impl<C: Curve> Dummy<usize> for CommittedInstance<C> {
     }
 }
 
+impl<C: Curve> Dummy<&R1CS<CF1<C>>> for CommittedInstance<C> {
+    fn dummy(r1cs: &R1CS<CF1<C>>) -> Self {
+        Self {
+            rE: vec![C::ScalarField::zero(); log2(r1cs.n_constraints()) as usize],
+            ..Self::dummy(r1cs.n_public_inputs())
+        }
+    }
+}
+
+impl<C: Curve> CommittedInstanceOps<C> for CommittedInstance<C> {
+    type Var = CommittedInstanceVar<C>;
+
+    fn get_commitments(&self) -> Vec<C> {
+        vec![self.cmW]
+    }
+
+    fn is_incoming(&self) -> bool {
+        self.mleE == Zero::zero() && self.u == One::one()
+    }
+}
+
+impl<C: Curve> Inputize<CF1<C>> for CommittedInstance<C> {
+    /// Returns the internal representation in the same order as how the value
+    /// is allocated in `CommittedInstanceVar::new_input`.
+    fn inputize(&self) -> Vec<CF1<C>> {
+        [
+            &[self.u][..],
+            &self.x,
+            &self.rE,
+            &[self.mleE],
+            &self.cmW.inputize_nonnative(),
+        ]
+        .concat()
+    }
+}
+
 #[derive(Debug, Clone, Eq, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
 pub struct Witness<C: Curve> {
     pub E: Vec<C::ScalarField>,
@@ -122,6 +160,21 @@ pub struct Proof<C: Curve> {
     pub rE_prime: Vec<C::ScalarField>,
 }
 
+impl<C: Curve> Dummy<usize> for Proof<C> {
+    fn dummy(n_vars: usize) -> Self {
+        Self {
+            h_proof: PointVsLineProof {
+                h1: DensePolynomial::zero(),
+                h2: DensePolynomial::zero(),
+            },
+            mleE1_prime: C::ScalarField::zero(),
+            mleE2_prime: C::ScalarField::zero(),
+            mleT: C::ScalarField::zero(),
+            rE_prime: vec![C::ScalarField::zero(); n_vars],
+        }
+    }
+}
+
 /// Implements the Non-Interactive Folding Scheme described in section 4 of
 /// [Mova](https://eprint.iacr.org/2024/1220.pdf).
 /// `H` specifies whether the NIFS will use a blinding factor
@@ -211,10 +264,11 @@ impl<C: Curve, CS: CommitmentScheme<C, H>, T: Transcript<C::ScalarField>, const
         ),
         Error,
     > {
-        transcript.absorb(&pp_hash);
+        transcript.domain_separator(MOVA_NIFS_DOMAIN);
+        transcript.absorb_labelled(b"pp_hash", &pp_hash);
         // Protocol 5 is pre-processing
-        transcript.absorb(U_i);
-        transcript.absorb(u_i);
+        transcript.absorb_labelled(b"U_i", U_i);
+        transcript.absorb_labelled(b"u_i", u_i);
 
         // Protocol 6
         let (
@@ -228,8 +282,8 @@ impl<C: Curve, CS: CommitmentScheme<C, H>, T: Transcript<C::ScalarField>, const
 
         // Protocol 7
 
-        transcript.absorb(&mleE1_prime);
-        transcript.absorb(&mleE2_prime);
+        transcript.absorb_labelled(b"mleE1_prime", &mleE1_prime);
+        transcript.absorb_labelled(b"mleE2_prime", &mleE2_prime);
 
         // compute the cross terms
         let z1: Vec<C::ScalarField> = [vec![U_i.u], U_i.x.to_vec(), W_i.W.to_vec()].concat();
@@ -244,8 +298,9 @@ impl<C: Curve, CS: CommitmentScheme<C, H>, T: Transcript<C::ScalarField>, const
         let mleT = dense_vec_to_dense_mle(n_vars, &T);
         let mleT_evaluated = mleT.evaluate(&rE_prime);
 
-        transcript.absorb(&mleT_evaluated);
+        transcript.absorb_labelled(b"mleT", &mleT_evaluated);
 
+        transcript.absorb_label(b"alpha");
         let alpha: C::ScalarField = transcript.get_challenge();
 
         let ci = Self::fold_committed_instance(
@@ -266,13 +321,7 @@ impl<C: Curve, CS: CommitmentScheme<C, H>, T: Transcript<C::ScalarField>, const
             mleT: mleT_evaluated,
             rE_prime,
         };
-        Ok((
-            w,
-            ci,
-            proof,
-            vec![], // r_bits, returned to be passed as inputs to the circuit, not used at the
-                    // current impl status
-        ))
+        Ok((w, ci, proof, Self::challenge_bits(alpha)))
     }
 
     /// [Mova](https://eprint.iacr.org/2024/1220.pdf)'s section 4. It verifies the results from the proof
@@ -285,9 +334,10 @@ impl<C: Curve, CS: CommitmentScheme<C, H>, T: Transcript<C::ScalarField>, const
         u_i: &CommittedInstance<C>,
         proof: &Proof<C>,
     ) -> Result<(Self::CommittedInstance, Vec<bool>), Error> {
-        transcript.absorb(&pp_hash);
-        transcript.absorb(U_i);
-        transcript.absorb(u_i);
+        transcript.domain_separator(MOVA_NIFS_DOMAIN);
+        transcript.absorb_labelled(b"pp_hash", &pp_hash);
+        transcript.absorb_labelled(b"U_i", U_i);
+        transcript.absorb_labelled(b"u_i", u_i);
         let rE_prime = PointVsLine::<C, T>::verify(
             transcript,
             U_i,
@@ -298,10 +348,11 @@ impl<C: Curve, CS: CommitmentScheme<C, H>, T: Transcript<C::ScalarField>, const
             &proof.rE_prime,
         )?;
 
-        transcript.absorb(&proof.mleE1_prime);
-        transcript.absorb(&proof.mleE2_prime);
-        transcript.absorb(&proof.mleT);
+        transcript.absorb_labelled(b"mleE1_prime", &proof.mleE1_prime);
+        transcript.absorb_labelled(b"mleE2_prime", &proof.mleE2_prime);
+        transcript.absorb_labelled(b"mleT", &proof.mleT);
 
+        transcript.absorb_label(b"alpha");
         let alpha: C::ScalarField = transcript.get_challenge();
 
         Ok((
@@ -314,7 +365,7 @@ impl<C: Curve, CS: CommitmentScheme<C, H>, T: Transcript<C::ScalarField>, const
                 &proof.mleE2_prime,
                 &proof.mleT,
             )?,
-            vec![],
+            Self::challenge_bits(alpha),
         ))
     }
 }
@@ -322,6 +373,15 @@ impl<C: Curve, CS: CommitmentScheme<C, H>, T: Transcript<C::ScalarField>, const
 impl<C: Curve, CS: CommitmentScheme<C, H>, T: Transcript<C::ScalarField>, const H: bool>
     NIFS<C, CS, T, H>
 {
+    /// Returns the bits of the folding challenge `alpha`. Unlike Nova, Mova's challenge is a full
+    /// field element, so the CycleFold circuit that folds `cmW` needs all its
+    /// `MODULUS_BIT_SIZE` bits.
+    fn challenge_bits(alpha: C::ScalarField) -> Vec<bool> {
+        let mut bits = alpha.into_bigint().to_bits_le();
+        bits.truncate(C::ScalarField::MODULUS_BIT_SIZE as usize);
+        bits
+    }
+
     // Protocol 7 - point 3 (15)
     fn fold_committed_instance(
         a: C::ScalarField,
@@ -366,10 +426,21 @@ impl<C: Curve> ArithRelation<Witness<C>, CommittedInstance<C>> for R1CS<CF1<C>>
 
     fn check_evaluation(
         w: &Witness<C>,
-        _u: &CommittedInstance<C>,
+        u: &CommittedInstance<C>,
         e: Self::Evaluation,
     ) -> Result<(), Error> {
-        (w.E == e).then_some(()).ok_or(Error::NotSatisfied)
+        if w.E != e {
+            return Err(Error::NotSatisfied);
+        }
+        // since E is not committed, the instance only carries the claimed evaluation `mleE` of
+        // the MLE of E at `rE`, which also needs to be checked
+        let n_vars = log2(w.E.len()) as usize;
+        if u.rE.len() != n_vars {
+            return Err(Error::NotSatisfied);
+        }
+        (dense_vec_to_dense_mle(n_vars, &w.E).evaluate(&u.rE) == u.mleE)
+            .then_some(())
+            .ok_or(Error::NotSatisfied)
     }
 }
 
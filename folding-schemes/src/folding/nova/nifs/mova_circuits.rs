@@ -0,0 +1,397 @@
+/// contains [Mova](https://eprint.iacr.org/2024/1220.pdf) NIFS related circuits
+use ark_crypto_primitives::sponge::{constraints::AbsorbGadget, CryptographicSponge};
+use ark_r1cs_std::{
+    alloc::{AllocVar, AllocationMode},
+    boolean::Boolean,
+    convert::ToBitsGadget,
+    eq::EqGadget,
+    fields::{fp::FpVar, FieldVar},
+    poly::polynomial::univariate::dense::DensePolynomialVar,
+    uint8::UInt8,
+};
+use ark_relations::r1cs::{ConstraintSystemRef, Namespace, SynthesisError};
+use ark_std::fmt::Debug;
+use core::{borrow::Borrow, marker::PhantomData};
+
+use super::mova::{CommittedInstance, Proof};
+use super::pointvsline::padded_coeffs;
+use super::NIFSGadgetTrait;
+use crate::constants::MOVA_NIFS_DOMAIN;
+use crate::folding::circuits::{nonnative::affine::NonNativeAffineVar, CF1};
+use crate::folding::traits::CommittedInstanceVarOps;
+use crate::transcript::{AbsorbNonNativeGadget, TranscriptVar};
+use crate::Curve;
+
+/// CommittedInstanceVar contains the rE, mleE, u, x and cmW values which are folded on the main
+/// Mova constraints field (E1::Fr, where E1 is the main curve). Only cmW is represented
+/// non-natively, since Mova does not commit to the error term E.
+#[derive(Debug, Clone)]
+pub struct CommittedInstanceVar<C: Curve> {
+    pub rE: Vec<FpVar<C::ScalarField>>,
+    pub mleE: FpVar<C::ScalarField>,
+    pub u: FpVar<C::ScalarField>,
+    pub cmW: NonNativeAffineVar<C>,
+    pub x: Vec<FpVar<C::ScalarField>>,
+}
+
+impl<C: Curve> AllocVar<CommittedInstance<C>, CF1<C>> for CommittedInstanceVar<C> {
+    fn new_variable<T: Borrow<CommittedInstance<C>>>(
+        cs: impl Into<Namespace<CF1<C>>>,
+        f: impl FnOnce() -> Result<T, SynthesisError>,
+        mode: AllocationMode,
+    ) -> Result<Self, SynthesisError> {
+        f().and_then(|val| {
+            let cs = cs.into();
+
+            let u = FpVar::<C::ScalarField>::new_variable(cs.clone(), || Ok(val.borrow().u), mode)?;
+            let x: Vec<FpVar<C::ScalarField>> =
+                Vec::new_variable(cs.clone(), || Ok(val.borrow().x.clone()), mode)?;
+            let rE: Vec<FpVar<C::ScalarField>> =
+                Vec::new_variable(cs.clone(), || Ok(val.borrow().rE.clone()), mode)?;
+            let mleE =
+                FpVar::<C::ScalarField>::new_variable(cs.clone(), || Ok(val.borrow().mleE), mode)?;
+
+            let cmW =
+                NonNativeAffineVar::<C>::new_variable(cs.clone(), || Ok(val.borrow().cmW), mode)?;
+
+            Ok(Self {
+                rE,
+                mleE,
+                u,
+                cmW,
+                x,
+            })
+        })
+    }
+}
+
+impl<C: Curve> AbsorbGadget<C::ScalarField> for CommittedInstanceVar<C> {
+    fn to_sponge_bytes(&self) -> Result<Vec<UInt8<C::ScalarField>>, SynthesisError> {
+        FpVar::batch_to_sponge_bytes(&self.to_sponge_field_elements()?)
+    }
+
+    fn to_sponge_field_elements(&self) -> Result<Vec<FpVar<C::ScalarField>>, SynthesisError> {
+        Ok([
+            vec![self.u.clone()],
+            self.x.clone(),
+            self.rE.clone(),
+            vec![self.mleE.clone()],
+            self.cmW.to_native_sponge_field_elements()?,
+        ]
+        .concat())
+    }
+}
+
+impl<C: Curve> CommittedInstanceVarOps<C> for CommittedInstanceVar<C> {
+    type PointVar = NonNativeAffineVar<C>;
+
+    fn get_commitments(&self) -> Vec<Self::PointVar> {
+        vec![self.cmW.clone()]
+    }
+
+    fn get_public_inputs(&self) -> &[FpVar<CF1<C>>] {
+        &self.x
+    }
+
+    fn enforce_incoming(&self) -> Result<(), SynthesisError> {
+        self.mleE.enforce_equal(&FpVar::zero())?;
+        self.u.enforce_equal(&FpVar::one())
+    }
+
+    fn enforce_partial_equal(&self, other: &Self) -> Result<(), SynthesisError> {
+        self.u.enforce_equal(&other.u)?;
+        self.x.enforce_equal(&other.x)?;
+        self.rE.enforce_equal(&other.rE)?;
+        self.mleE.enforce_equal(&other.mleE)
+    }
+}
+
+/// ProofVar is the in-circuit representation of Mova's [`Proof`]. The coefficients of the
+/// point-vs-line polynomials `h1` and `h2` are padded to `n_vars + 1` elements, so that the size
+/// of the proof (and of the circuit verifying it) does not depend on their actual degree.
+/// The claimed evaluations `mleE1_prime`, `mleE2_prime` and the point `rE_prime` are not
+/// allocated, since the gadget recomputes them from `h1`, `h2` and the challenge `beta`.
+#[derive(Debug, Clone)]
+pub struct ProofVar<C: Curve> {
+    pub h1: Vec<FpVar<C::ScalarField>>,
+    pub h2: Vec<FpVar<C::ScalarField>>,
+    pub mleT: FpVar<C::ScalarField>,
+}
+
+impl<C: Curve> AllocVar<Proof<C>, CF1<C>> for ProofVar<C> {
+    fn new_variable<T: Borrow<Proof<C>>>(
+        cs: impl Into<Namespace<CF1<C>>>,
+        f: impl FnOnce() -> Result<T, SynthesisError>,
+        mode: AllocationMode,
+    ) -> Result<Self, SynthesisError> {
+        f().and_then(|val| {
+            let cs = cs.into();
+            let proof = val.borrow();
+
+            let n_vars = proof.rE_prime.len();
+            let h1 = padded_coeffs(&proof.h_proof.h1, n_vars)
+                .map_err(|_| SynthesisError::Unsatisfiable)?;
+            let h2 = padded_coeffs(&proof.h_proof.h2, n_vars)
+                .map_err(|_| SynthesisError::Unsatisfiable)?;
+
+            let h1 = Vec::new_variable(cs.clone(), || Ok(h1), mode)?;
+            let h2 = Vec::new_variable(cs.clone(), || Ok(h2), mode)?;
+            let mleT = FpVar::<C::ScalarField>::new_variable(cs.clone(), || Ok(proof.mleT), mode)?;
+
+            Ok(Self { h1, h2, mleT })
+        })
+    }
+}
+
+/// In-circuit version of [`super::pointvsline::PointVsLine::verify`], following Protocol 6 of
+/// [Mova](https://eprint.iacr.org/2024/1220.pdf). Instead of checking the claimed evaluations and
+/// point provided by the prover, it returns them as computed in-circuit.
+pub struct PointVsLineGadget<C: Curve> {
+    _c: PhantomData<C>,
+}
+
+impl<C: Curve> PointVsLineGadget<C> {
+    /// Returns `(mleE1_prime, mleE2_prime, rE_prime)`, i.e. the evaluations of `h1` and `h2` at
+    /// the challenge `beta`, and the point `l(beta)` on the line through `ci1.rE` and `ci2.rE`.
+    #[allow(clippy::type_complexity)]
+    pub fn verify<S: CryptographicSponge, T: TranscriptVar<CF1<C>, S>>(
+        transcript: &mut T,
+        ci1: &CommittedInstanceVar<C>,
+        ci2: &CommittedInstanceVar<C>,
+        h1: &[FpVar<CF1<C>>],
+        h2: &[FpVar<CF1<C>>],
+    ) -> Result<(FpVar<CF1<C>>, FpVar<CF1<C>>, Vec<FpVar<CF1<C>>>), SynthesisError> {
+        if h1.is_empty() || h2.is_empty() || ci1.rE.len() != ci2.rE.len() {
+            return Err(SynthesisError::Unsatisfiable);
+        }
+
+        // h1(0) == ci1.mleE
+        h1[0].enforce_equal(&ci1.mleE)?;
+        // h2(1) == ci2.mleE
+        h2.iter().sum::<FpVar<_>>().enforce_equal(&ci2.mleE)?;
+
+        transcript.absorb_labelled(b"h1", &h1)?;
+        transcript.absorb_labelled(b"h2", &h2)?;
+
+        transcript.absorb_label(b"beta")?;
+        let beta = transcript.get_challenge()?;
+
+        let mleE1_prime = DensePolynomialVar::from_coefficients_slice(h1).evaluate(&beta)?;
+        let mleE2_prime = DensePolynomialVar::from_coefficients_slice(h2).evaluate(&beta)?;
+
+        // l(beta) = r1 + beta * (r2 - r1)
+        let rE_prime = ci1
+            .rE
+            .iter()
+            .zip(&ci2.rE)
+            .map(|(r1, r2)| r1 + &beta * (r2 - r1))
+            .collect();
+
+        Ok((mleE1_prime, mleE2_prime, rE_prime))
+    }
+}
+
+/// Implements the circuit that does the checks of the Non-Interactive Folding Scheme Verifier
+/// described in section 4 of [Mova](https://eprint.iacr.org/2024/1220.pdf), where the cmW check
+/// is delegated to the NIFSCycleFoldGadget.
+pub struct NIFSGadget<C: Curve, S: CryptographicSponge, T: TranscriptVar<CF1<C>, S>> {
+    _c: PhantomData<C>,
+    _s: PhantomData<S>,
+    _t: PhantomData<T>,
+}
+
+impl<C, S, T> NIFSGadgetTrait<C, S, T> for NIFSGadget<C, S, T>
+where
+    C: Curve,
+    S: CryptographicSponge,
+    T: TranscriptVar<CF1<C>, S>,
+{
+    type CommittedInstance = CommittedInstance<C>;
+    type CommittedInstanceVar = CommittedInstanceVar<C>;
+    type Proof = Proof<C>;
+    type ProofVar = ProofVar<C>;
+
+    fn verify(
+        transcript: &mut T,
+        pp_hash: FpVar<CF1<C>>,
+        U_i: Self::CommittedInstanceVar,
+        // U_i_vec is passed to reuse the already computed U_i_vec from previous methods
+        U_i_vec: Vec<FpVar<CF1<C>>>,
+        u_i: Self::CommittedInstanceVar,
+        proof: Option<Self::ProofVar>,
+    ) -> Result<(Self::CommittedInstanceVar, Vec<Boolean<CF1<C>>>), SynthesisError> {
+        let proof = proof.ok_or(SynthesisError::AssignmentMissing)?;
+
+        transcript.domain_separator(MOVA_NIFS_DOMAIN)?;
+        transcript.absorb_labelled(b"pp_hash", &pp_hash)?;
+        transcript.absorb_labelled(b"U_i", &U_i_vec)?;
+        transcript.absorb_labelled(b"u_i", &u_i)?;
+
+        // Protocol 6
+        let (mleE1_prime, mleE2_prime, rE_prime) =
+            PointVsLineGadget::verify(transcript, &U_i, &u_i, &proof.h1, &proof.h2)?;
+
+        // Protocol 7
+        transcript.absorb_labelled(b"mleE1_prime", &mleE1_prime)?;
+        transcript.absorb_labelled(b"mleE2_prime", &mleE2_prime)?;
+        transcript.absorb_labelled(b"mleT", &proof.mleT)?;
+
+        transcript.absorb_label(b"alpha")?;
+        let alpha = transcript.get_challenge()?;
+
+        Ok((
+            Self::CommittedInstanceVar {
+                rE: rE_prime,
+                // ci3.mleE = mleE1' + alpha * mleT + alpha^2 * mleE2'
+                mleE: mleE1_prime + &alpha * &proof.mleT + alpha.square()? * mleE2_prime,
+                // ci3.u = U_i.u + alpha * u_i.u
+                u: U_i.u + &alpha * &u_i.u,
+                cmW: NonNativeAffineVar::new_constant(ConstraintSystemRef::None, C::zero())?,
+                // ci3.x = U_i.x + alpha * u_i.x
+                x: U_i
+                    .x
+                    .iter()
+                    .zip(u_i.x)
+                    .map(|(a, b)| a + &alpha * &b)
+                    .collect::<Vec<FpVar<CF1<C>>>>(),
+            },
+            alpha.to_bits_le()?,
+        ))
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use ark_crypto_primitives::sponge::poseidon::PoseidonSponge;
+    use ark_crypto_primitives::sponge::{
+        constraints::CryptographicSpongeVar, poseidon::constraints::PoseidonSpongeVar,
+    };
+    use ark_pallas::{Fr, Projective};
+    use ark_r1cs_std::R1CSVar;
+    use ark_relations::r1cs::ConstraintSystem;
+    use ark_std::{log2, UniformRand};
+
+    use crate::arith::{
+        r1cs::tests::{get_test_r1cs, get_test_z},
+        Arith,
+    };
+    use crate::commitment::{pedersen::Pedersen, CommitmentScheme};
+    use crate::folding::nova::nifs::{
+        mova::NIFS,
+        tests::{test_committed_instance_hash_opt, test_committed_instance_to_sponge_preimage_opt},
+        NIFSTrait,
+    };
+    use crate::transcript::poseidon::poseidon_canonical_config;
+    use crate::Error;
+
+    type N = NIFS<Projective, Pedersen<Projective>, PoseidonSponge<Fr>>;
+
+    fn rand_committed_instance(
+        rng: &mut impl ark_std::rand::RngCore,
+    ) -> CommittedInstance<Projective> {
+        CommittedInstance::<Projective> {
+            rE: (0..3).map(|_| Fr::rand(rng)).collect(),
+            mleE: Fr::rand(rng),
+            u: Fr::rand(rng),
+            cmW: Projective::rand(rng),
+            x: vec![Fr::rand(rng); 1],
+        }
+    }
+
+    #[test]
+    fn test_nifs_gadget() -> Result<(), Error> {
+        let mut rng = ark_std::test_rng();
+        let r1cs = get_test_r1cs::<Fr>();
+        let (pedersen_params, _) = Pedersen::<Projective>::setup(&mut rng, r1cs.n_witnesses())?;
+        let poseidon_config = poseidon_canonical_config::<Fr>();
+        let pp_hash = Fr::rand(&mut rng);
+
+        // prepare a running instance with non-zero E by folding two fresh instances, so that the
+        // point-vs-line polynomial h1 is not trivial
+        let mut instances = vec![];
+        for i in 0..3 {
+            let (w, x) = r1cs.split_z(&get_test_z(i + 3));
+            let w_i = N::new_witness(w, r1cs.n_constraints(), &mut rng);
+            let u_i = N::new_instance(&mut rng, &pedersen_params, &w_i, x, vec![])?;
+            instances.push((w_i, u_i));
+        }
+        let mut transcript = PoseidonSponge::<Fr>::new(&poseidon_config);
+        let (W_i, U_i, _, _) = N::prove(
+            &pedersen_params,
+            &r1cs,
+            &mut transcript,
+            pp_hash,
+            &instances[0].0,
+            &instances[0].1,
+            &instances[1].0,
+            &instances[1].1,
+        )?;
+        let (w_i, u_i) = instances[2].clone();
+
+        let mut transcript_p = PoseidonSponge::<Fr>::new(&poseidon_config);
+        let (_, _, proof, r_bits) = N::prove(
+            &pedersen_params,
+            &r1cs,
+            &mut transcript_p,
+            pp_hash,
+            &W_i,
+            &U_i,
+            &w_i,
+            &u_i,
+        )?;
+        assert_eq!(proof.rE_prime.len(), log2(r1cs.n_constraints()) as usize);
+        let mut transcript_v = PoseidonSponge::<Fr>::new(&poseidon_config);
+        let (ci_out, _) = N::verify(&mut transcript_v, pp_hash, &U_i, &u_i, &proof)?;
+
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let mut transcriptVar = PoseidonSpongeVar::<Fr>::new(cs.clone(), &poseidon_config);
+        let pp_hashVar = FpVar::<Fr>::new_witness(cs.clone(), || Ok(pp_hash))?;
+        let U_iVar = CommittedInstanceVar::new_witness(cs.clone(), || Ok(U_i.clone()))?;
+        let u_iVar = CommittedInstanceVar::new_witness(cs.clone(), || Ok(u_i.clone()))?;
+        let proofVar = ProofVar::new_witness(cs.clone(), || Ok(proof))?;
+
+        let (ciVar_out, r_bitsVar) =
+            NIFSGadget::<Projective, PoseidonSponge<Fr>, PoseidonSpongeVar<Fr>>::verify(
+                &mut transcriptVar,
+                pp_hashVar,
+                U_iVar.clone(),
+                U_iVar.to_sponge_field_elements()?,
+                u_iVar,
+                Some(proofVar),
+            )?;
+        assert!(cs.is_satisfied()?);
+
+        // check that the natively computed and in-circuit computed values match
+        assert_eq!(ciVar_out.u.value()?, ci_out.u);
+        assert_eq!(ciVar_out.x.value()?, ci_out.x);
+        assert_eq!(ciVar_out.rE.value()?, ci_out.rE);
+        assert_eq!(ciVar_out.mleE.value()?, ci_out.mleE);
+        assert_eq!(r_bitsVar.value()?, r_bits);
+        Ok(())
+    }
+
+    #[test]
+    fn test_committed_instance_to_sponge_preimage() -> Result<(), Error> {
+        let mut rng = ark_std::test_rng();
+        let ci = rand_committed_instance(&mut rng);
+
+        test_committed_instance_to_sponge_preimage_opt::<
+            N,
+            NIFSGadget<Projective, PoseidonSponge<Fr>, PoseidonSpongeVar<Fr>>,
+        >(ci)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_committed_instance_hash() -> Result<(), Error> {
+        let mut rng = ark_std::test_rng();
+        let ci = rand_committed_instance(&mut rng);
+
+        test_committed_instance_hash_opt::<
+            N,
+            NIFSGadget<Projective, PoseidonSponge<Fr>, PoseidonSpongeVar<Fr>>,
+        >(ci)?;
+        Ok(())
+    }
+}
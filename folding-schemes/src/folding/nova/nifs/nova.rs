@@ -11,7 +11,7 @@ use std::marker::PhantomData;
 use super::NIFSTrait;
 use crate::arith::r1cs::R1CS;
 use crate::commitment::CommitmentScheme;
-use crate::constants::NOVA_N_BITS_RO;
+use crate::constants::{NOVA_NIFS_DOMAIN, NOVA_N_BITS_RO};
 use crate::folding::circuits::{
     cyclefold::{CycleFoldCommittedInstance, CycleFoldWitness},
     nonnative::affine::NonNativeAffineVar,
@@ -23,7 +23,8 @@ use crate::utils::vec::{hadamard, mat_vec_mul, vec_add, vec_scalar_mul, vec_sub}
 use crate::{Curve, Error};
 
 /// ChallengeGadget computes the RO challenge used for the Nova instances NIFS, it contains a
-/// rust-native and an in-circuit compatible versions.
+/// rust-native and an in-circuit compatible versions. It is shared by the Nova and Ova NIFS, which
+/// pass their own `domain` to separate the challenges.
 pub struct ChallengeGadget<C: Curve, CI: Absorb> {
     _c: PhantomData<C>,
     _ci: PhantomData<CI>,
@@ -31,18 +32,22 @@ pub struct ChallengeGadget<C: Curve, CI: Absorb> {
 impl<C: Curve, CI: Absorb> ChallengeGadget<C, CI> {
     pub fn get_challenge_native<T: Transcript<C::ScalarField>>(
         transcript: &mut T,
+        domain: &[u8],
         pp_hash: C::ScalarField, // public params hash
         U_i: &CI,
         u_i: &CI,
         cmT: Option<&C>,
     ) -> Vec<bool> {
-        transcript.absorb(&pp_hash);
-        transcript.absorb(&U_i);
-        transcript.absorb(&u_i);
+        transcript.domain_separator(domain);
+        transcript.absorb_labelled(b"pp_hash", &pp_hash);
+        transcript.absorb_labelled(b"U_i", &U_i);
+        transcript.absorb_labelled(b"u_i", &u_i);
         // in the Nova case we absorb the cmT, in Ova case we don't since it is not used.
         if let Some(cmT_value) = cmT {
+            transcript.absorb_label(b"cmT");
             transcript.absorb_nonnative(cmT_value);
         }
+        transcript.absorb_label(b"r");
         transcript.squeeze_bits(NOVA_N_BITS_RO)
     }
 
@@ -53,18 +58,22 @@ impl<C: Curve, CI: Absorb> ChallengeGadget<C, CI> {
         CIVar: AbsorbGadget<CF1<C>>,
     >(
         transcript: &mut T,
+        domain: &[u8],
         pp_hash: FpVar<CF1<C>>,      // public params hash
         U_i_vec: Vec<FpVar<CF1<C>>>, // apready processed input, so we don't have to recompute these values
         u_i: CIVar,
         cmT: Option<NonNativeAffineVar<C>>,
     ) -> Result<Vec<Boolean<C::ScalarField>>, SynthesisError> {
-        transcript.absorb(&pp_hash)?;
-        transcript.absorb(&U_i_vec)?;
-        transcript.absorb(&u_i)?;
+        transcript.domain_separator(domain)?;
+        transcript.absorb_labelled(b"pp_hash", &pp_hash)?;
+        transcript.absorb_labelled(b"U_i", &U_i_vec)?;
+        transcript.absorb_labelled(b"u_i", &u_i)?;
         // in the Nova case we absorb the cmT, in Ova case we don't since it is not used.
         if let Some(cmT_value) = cmT {
+            transcript.absorb_label(b"cmT")?;
             transcript.absorb_nonnative(&cmT_value)?;
         }
+        transcript.absorb_label(b"r")?;
         transcript.squeeze_bits(NOVA_N_BITS_RO)
     }
 }
@@ -158,6 +167,7 @@ impl<C: Curve, CS: CommitmentScheme<C, H>, T: Transcript<C::ScalarField>, const
 
         let r_bits = ChallengeGadget::<C, Self::CommittedInstance>::get_challenge_native(
             transcript,
+            NOVA_NIFS_DOMAIN,
             pp_hash,
             U_i,
             u_i,
@@ -182,6 +192,7 @@ impl<C: Curve, CS: CommitmentScheme<C, H>, T: Transcript<C::ScalarField>, const
     ) -> Result<(Self::CommittedInstance, Vec<bool>), Error> {
         let r_bits = ChallengeGadget::<C, Self::CommittedInstance>::get_challenge_native(
             transcript,
+            NOVA_NIFS_DOMAIN,
             pp_hash,
             U_i,
             u_i,
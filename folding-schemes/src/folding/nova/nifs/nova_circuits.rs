@@ -5,6 +5,7 @@ use ark_r1cs_std::{
     boolean::Boolean,
     eq::EqGadget,
     fields::{fp::FpVar, FieldVar},
+    select::CondSelectGadget,
     uint8::UInt8,
 };
 use ark_relations::r1cs::{ConstraintSystemRef, Namespace, SynthesisError};
@@ -12,6 +13,7 @@ use ark_std::{fmt::Debug, Zero};
 use core::{borrow::Borrow, marker::PhantomData};
 
 use super::NIFSGadgetTrait;
+use crate::constants::NOVA_NIFS_DOMAIN;
 use crate::folding::traits::CommittedInstanceVarOps;
 use crate::transcript::TranscriptVar;
 use crate::{
@@ -59,6 +61,29 @@ impl<C: Curve> AllocVar<CommittedInstance<C>, CF1<C>> for CommittedInstanceVar<C
     }
 }
 
+impl<C: Curve> CondSelectGadget<CF1<C>> for CommittedInstanceVar<C> {
+    fn conditionally_select(
+        cond: &Boolean<CF1<C>>,
+        true_value: &Self,
+        false_value: &Self,
+    ) -> Result<Self, SynthesisError> {
+        if true_value.x.len() != false_value.x.len() {
+            return Err(SynthesisError::Unsatisfiable);
+        }
+        Ok(Self {
+            u: cond.select(&true_value.u, &false_value.u)?,
+            x: true_value
+                .x
+                .iter()
+                .zip(&false_value.x)
+                .map(|(t, f)| cond.select(t, f))
+                .collect::<Result<_, _>>()?,
+            cmE: cond.select(&true_value.cmE, &false_value.cmE)?,
+            cmW: cond.select(&true_value.cmW, &false_value.cmW)?,
+        })
+    }
+}
+
 impl<C: Curve> AbsorbGadget<C::ScalarField> for CommittedInstanceVar<C> {
     fn to_sponge_bytes(&self) -> Result<Vec<UInt8<C::ScalarField>>, SynthesisError> {
         FpVar::batch_to_sponge_bytes(&self.to_sponge_field_elements()?)
@@ -130,6 +155,7 @@ where
     ) -> Result<(Self::CommittedInstanceVar, Vec<Boolean<CF1<C>>>), SynthesisError> {
         let r_bits = ChallengeGadget::<C, CommittedInstance<C>>::get_challenge_gadget(
             transcript,
+            NOVA_NIFS_DOMAIN,
             pp_hash.clone(),
             U_i_vec,
             u_i.clone(),
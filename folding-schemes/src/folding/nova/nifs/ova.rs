@@ -13,6 +13,7 @@ use super::ova_circuits::CommittedInstanceVar;
 use super::NIFSTrait;
 use crate::arith::{r1cs::R1CS, Arith};
 use crate::commitment::CommitmentScheme;
+use crate::constants::OVA_NIFS_DOMAIN;
 use crate::folding::traits::{CommittedInstanceOps, Inputize};
 use crate::folding::{circuits::CF1, traits::Dummy};
 use crate::transcript::Transcript;
@@ -43,6 +44,22 @@ impl<C: Curve> Absorb for CommittedInstance<C> {
     }
 }
 
+impl<C: Curve> Dummy<usize> for CommittedInstance<C> {
+    fn dummy(io_len: usize) -> Self {
+        Self {
+            u: C::ScalarField::zero(),
+            x: vec![C::ScalarField::zero(); io_len],
+            cmWE: C::zero(),
+        }
+    }
+}
+
+impl<C: Curve> Dummy<&R1CS<CF1<C>>> for CommittedInstance<C> {
+    fn dummy(r1cs: &R1CS<CF1<C>>) -> Self {
+        Self::dummy(r1cs.n_public_inputs())
+    }
+}
+
 impl<C: Curve> CommittedInstanceOps<C> for CommittedInstance<C> {
     type Var = CommittedInstanceVar<C>;
 
@@ -187,7 +204,12 @@ impl<C: Curve, CS: CommitmentScheme<C, H>, T: Transcript<C::ScalarField>, const
         let mut transcript_v = transcript.clone();
 
         let r_bits = ChallengeGadget::<C, Self::CommittedInstance>::get_challenge_native(
-            transcript, pp_hash, U_i, u_i, None, // cmT not used in Ova
+            transcript,
+            OVA_NIFS_DOMAIN,
+            pp_hash,
+            U_i,
+            u_i,
+            None, // cmT not used in Ova
         );
         let r_Fr = C::ScalarField::from_bigint(BigInteger::from_bits_le(&r_bits))
             .ok_or(Error::OutOfBounds)?;
@@ -210,7 +232,12 @@ impl<C: Curve, CS: CommitmentScheme<C, H>, T: Transcript<C::ScalarField>, const
         _proof: &Self::Proof, // unused in Ova
     ) -> Result<(Self::CommittedInstance, Vec<bool>), Error> {
         let r_bits = ChallengeGadget::<C, Self::CommittedInstance>::get_challenge_native(
-            transcript, pp_hash, U_i, u_i, None, // cmT not used in Ova
+            transcript,
+            OVA_NIFS_DOMAIN,
+            pp_hash,
+            U_i,
+            u_i,
+            None, // cmT not used in Ova
         );
         let r = C::ScalarField::from_bigint(BigInteger::from_bits_le(&r_bits))
             .ok_or(Error::OutOfBounds)?;
@@ -13,6 +13,7 @@ use core::{borrow::Borrow, marker::PhantomData};
 
 use super::ova::CommittedInstance;
 use super::NIFSGadgetTrait;
+use crate::constants::OVA_NIFS_DOMAIN;
 use crate::folding::traits::CommittedInstanceVarOps;
 use crate::transcript::TranscriptVar;
 use crate::{
@@ -117,6 +118,7 @@ where
     ) -> Result<(Self::CommittedInstanceVar, Vec<Boolean<CF1<C>>>), SynthesisError> {
         let r_bits = ChallengeGadget::<C, CommittedInstance<C>>::get_challenge_gadget(
             transcript,
+            OVA_NIFS_DOMAIN,
             pp_hash.clone(),
             U_i_vec,
             u_i.clone(),
@@ -55,11 +55,10 @@ impl<C: Curve, T: Transcript<C::ScalarField>> PointVsLine<C, T> {
         let h1 = compute_h(&mleE1, &ci1.rE, &r2_sub_r1)?;
         let h2 = compute_h(&mleE2, &ci1.rE, &r2_sub_r1)?;
 
-        transcript.absorb(&h1.coeffs());
-        transcript.absorb(&h2.coeffs());
+        transcript.absorb_labelled(b"h1", &padded_coeffs(&h1, n_vars)?);
+        transcript.absorb_labelled(b"h2", &padded_coeffs(&h2, n_vars)?);
 
-        let beta_scalar = C::ScalarField::from_le_bytes_mod_order(b"beta");
-        transcript.absorb(&beta_scalar);
+        transcript.absorb_label(b"beta");
         let beta = transcript.get_challenge();
 
         let mleE1_prime = h1.evaluate(&beta);
@@ -97,11 +96,11 @@ impl<C: Curve, T: Transcript<C::ScalarField>> PointVsLine<C, T> {
             return Err(Error::NotEqual);
         }
 
-        transcript.absorb(&proof.h1.coeffs());
-        transcript.absorb(&proof.h2.coeffs());
+        let n_vars = ci1.rE.len();
+        transcript.absorb_labelled(b"h1", &padded_coeffs(&proof.h1, n_vars)?);
+        transcript.absorb_labelled(b"h2", &padded_coeffs(&proof.h2, n_vars)?);
 
-        let beta_scalar = C::ScalarField::from_le_bytes_mod_order(b"beta");
-        transcript.absorb(&beta_scalar);
+        transcript.absorb_label(b"beta");
         let beta = transcript.get_challenge();
 
         if *mleE1_prime != proof.h1.evaluate(&beta) {
@@ -161,6 +160,22 @@ fn compute_h<F: PrimeField>(
     Ok(poly.swap_remove(0))
 }
 
+/// Returns the coefficients of `h` padded with zeros up to `n_vars + 1`, i.e. the number of
+/// coefficients of a polynomial of degree `n_vars`. `DensePolynomial` trims the leading zero
+/// coefficients, so without the padding the absorbed values would depend on the actual degree of
+/// `h`, while the in-circuit verifier always absorbs a fixed number of coefficients.
+pub(crate) fn padded_coeffs<F: PrimeField>(
+    h: &DensePolynomial<F>,
+    n_vars: usize,
+) -> Result<Vec<F>, Error> {
+    if h.coeffs().len() > n_vars + 1 {
+        return Err(Error::NotExpectedLength(h.coeffs().len(), n_vars + 1));
+    }
+    let mut coeffs = h.coeffs().to_vec();
+    coeffs.resize(n_vars + 1, F::zero());
+    Ok(coeffs)
+}
+
 fn compute_l<F: PrimeField>(r1: &[F], r2_sub_r1: &[F], x: F) -> Result<Vec<F>, Error> {
     if r1.len() != r2_sub_r1.len() {
         return Err(Error::NotEqual);
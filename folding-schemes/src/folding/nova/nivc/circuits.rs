@@ -0,0 +1,437 @@
+/// contains the augmented circuit used by the non-uniform IVC (NIVC) mode of Nova, following
+/// [SuperNova](https://eprint.iacr.org/2022/1758.pdf)
+use ark_crypto_primitives::sponge::{
+    constraints::{AbsorbGadget, CryptographicSpongeVar},
+    poseidon::{constraints::PoseidonSpongeVar, PoseidonConfig, PoseidonSponge},
+};
+use ark_ff::PrimeField;
+use ark_r1cs_std::{
+    alloc::AllocVar,
+    boolean::Boolean,
+    eq::EqGadget,
+    fields::{fp::FpVar, FieldVar},
+    prelude::CurveVar,
+    R1CSVar,
+};
+use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystemRef, SynthesisError};
+use ark_std::{One, Zero};
+
+use super::NIVCFCircuit;
+use crate::folding::circuits::{
+    cyclefold::{
+        CycleFoldChallengeGadget, CycleFoldCommittedInstance, CycleFoldCommittedInstanceVar,
+        CycleFoldConfig, NIFSFullGadget,
+    },
+    nonnative::{affine::NonNativeAffineVar, uint::NonNativeUintVar},
+    CF1,
+};
+use crate::folding::nova::{
+    nifs::{
+        nova_circuits::{CommittedInstanceVar, NIFSGadget},
+        NIFSGadgetTrait,
+    },
+    CommittedInstance, NovaCycleFoldConfig,
+};
+use crate::folding::traits::Dummy;
+use crate::transcript::AbsorbNonNativeGadget;
+use crate::Curve;
+
+/// In-circuit counterpart of [`super::nivc_hash`].
+///
+/// Returns `H(pp_hash, i, pc_0, pc_i, z_0, z_i, U_i[0], ..., U_i[n-1])`, together with the
+/// in-circuit representation of each of the running instances as vectors of field elements, so
+/// they can be reused in other gadgets without recalculating (reconstraining) them.
+#[allow(clippy::type_complexity, clippy::too_many_arguments)]
+pub fn nivc_hash_gadget<C: Curve>(
+    sponge: &PoseidonSpongeVar<CF1<C>>,
+    pp_hash: &FpVar<CF1<C>>,
+    i: &FpVar<CF1<C>>,
+    pc_0: &FpVar<CF1<C>>,
+    pc_i: &FpVar<CF1<C>>,
+    z_0: &[FpVar<CF1<C>>],
+    z_i: &[FpVar<CF1<C>>],
+    U_i: &[CommittedInstanceVar<C>],
+) -> Result<(FpVar<CF1<C>>, Vec<Vec<FpVar<CF1<C>>>>), SynthesisError> {
+    let mut sponge = sponge.clone();
+    let U_vecs = U_i
+        .iter()
+        .map(|U| U.to_sponge_field_elements())
+        .collect::<Result<Vec<_>, _>>()?;
+    sponge.absorb(&pp_hash)?;
+    sponge.absorb(&i)?;
+    sponge.absorb(&pc_0)?;
+    sponge.absorb(&pc_i)?;
+    sponge.absorb(&z_0)?;
+    sponge.absorb(&z_i)?;
+    for U_vec in &U_vecs {
+        sponge.absorb(U_vec)?;
+    }
+    Ok((
+        // `unwrap` is safe because the sponge is guaranteed to return a single element
+        sponge.squeeze_field_elements(1)?.pop().unwrap(),
+        U_vecs,
+    ))
+}
+
+/// `AugmentedFCircuit` is the NIVC counterpart of [`crate::folding::nova::circuits::AugmentedFCircuit`].
+///
+/// There is one `AugmentedFCircuit` (`F'_j`) per step circuit `F_j`, where `j` (`self.pc`) is
+/// fixed at circuit construction time. Compared to the uniform case, `F'_j`:
+/// - carries one running instance per step circuit, `U_i[0..n]`, inside the hash of the state,
+///   together with the initial and the current program counters `pc_0` and `pc_i`;
+/// - enforces that the current program counter `pc_i` is `j`, ie. that the step being proven is
+///   the one selected by the previous step (or by `pc_0` in the base case);
+/// - folds the incoming instance `u_i` into `U_i[last_pc]`, where `last_pc` is the index of the
+///   step circuit whose execution is attested by `u_i`, and leaves the other running instances
+///   untouched;
+/// - outputs the program counter of the next step, `pc_{i+1} = φ(z_{i+1})`, computed by
+///   [`NIVCFCircuit::next_pc`].
+///
+/// The CycleFold part is the same as in Nova, since the CycleFold circuit does not depend on the
+/// step circuit being folded.
+#[derive(Debug, Clone)]
+pub struct AugmentedFCircuit<C1: Curve, C2: Curve, FC: NIVCFCircuit<CF1<C1>>> {
+    pub(super) poseidon_config: PoseidonConfig<CF1<C1>>,
+    pub(super) pp_hash: Option<CF1<C1>>,
+    pub(super) i: Option<CF1<C1>>,
+    pub(super) i_usize: Option<usize>,
+    pub(super) pc: usize, // index of the step circuit `F` in the list of circuits
+    pub(super) n_circuits: usize, // number of step circuits
+    pub(super) pc_0: Option<CF1<C1>>,
+    pub(super) last_pc: Option<usize>,
+    pub(super) z_0: Option<Vec<C1::ScalarField>>,
+    pub(super) z_i: Option<Vec<C1::ScalarField>>,
+    pub(super) external_inputs: Option<FC::ExternalInputs>,
+    pub(super) u_i_cmW: Option<C1>,
+    pub(super) U_i: Option<Vec<CommittedInstance<C1>>>,
+    pub(super) U_i1_cmE: Option<C1>,
+    pub(super) U_i1_cmW: Option<C1>,
+    pub(super) cmT: Option<C1>,
+    pub(super) F: FC, // F circuit
+
+    // cyclefold verifier on C1
+    // Here 'cf1, cf2' are for each of the CycleFold circuits, corresponding to the fold of cmW and
+    // cmE respectively
+    pub(super) cf1_u_i_cmW: Option<C2>, // input
+    pub(super) cf2_u_i_cmW: Option<C2>, // input
+    pub(super) cf_U_i: Option<CycleFoldCommittedInstance<C2>>, // input
+    pub(super) cf1_cmT: Option<C2>,
+    pub(super) cf2_cmT: Option<C2>,
+}
+
+impl<C1: Curve, C2: Curve, FC: NIVCFCircuit<CF1<C1>>> AugmentedFCircuit<C1, C2, FC> {
+    pub fn empty(
+        poseidon_config: &PoseidonConfig<CF1<C1>>,
+        F_circuit: FC,
+        pc: usize,
+        n_circuits: usize,
+    ) -> Self {
+        Self {
+            poseidon_config: poseidon_config.clone(),
+            pp_hash: None,
+            i: None,
+            i_usize: None,
+            pc,
+            n_circuits,
+            pc_0: None,
+            last_pc: None,
+            z_0: None,
+            z_i: None,
+            external_inputs: None,
+            u_i_cmW: None,
+            U_i: None,
+            U_i1_cmE: None,
+            U_i1_cmW: None,
+            cmT: None,
+            F: F_circuit,
+            // cyclefold values
+            cf1_u_i_cmW: None,
+            cf2_u_i_cmW: None,
+            cf_U_i: None,
+            cf1_cmT: None,
+            cf2_cmT: None,
+        }
+    }
+}
+
+impl<C1, C2, FC> AugmentedFCircuit<C1, C2, FC>
+where
+    C1: Curve<BaseField = C2::ScalarField, ScalarField = C2::BaseField>,
+    C2: Curve,
+    FC: NIVCFCircuit<CF1<C1>>,
+{
+    /// Generates the constraints of `F'_j` and returns `(z_{i+1}, pc_{i+1})`.
+    #[allow(clippy::type_complexity)]
+    pub fn compute_next_state(
+        self,
+        cs: ConstraintSystemRef<CF1<C1>>,
+    ) -> Result<(Vec<FpVar<CF1<C1>>>, FpVar<CF1<C1>>), SynthesisError> {
+        let pp_hash = FpVar::<CF1<C1>>::new_witness(cs.clone(), || {
+            Ok(self.pp_hash.unwrap_or_else(CF1::<C1>::zero))
+        })?;
+        let i = FpVar::<CF1<C1>>::new_witness(cs.clone(), || {
+            Ok(self.i.unwrap_or_else(CF1::<C1>::zero))
+        })?;
+        let pc_0 = FpVar::<CF1<C1>>::new_witness(cs.clone(), || {
+            Ok(self.pc_0.unwrap_or_else(CF1::<C1>::zero))
+        })?;
+        let last_pc = FpVar::<CF1<C1>>::new_witness(cs.clone(), || {
+            Ok(CF1::<C1>::from(self.last_pc.unwrap_or(0) as u64))
+        })?;
+        let z_0 = Vec::<FpVar<CF1<C1>>>::new_witness(cs.clone(), || {
+            Ok(self
+                .z_0
+                .unwrap_or(vec![CF1::<C1>::zero(); self.F.state_len()]))
+        })?;
+        let z_i = Vec::<FpVar<CF1<C1>>>::new_witness(cs.clone(), || {
+            Ok(self
+                .z_i
+                .unwrap_or(vec![CF1::<C1>::zero(); self.F.state_len()]))
+        })?;
+        let external_inputs = FC::ExternalInputsVar::new_witness(cs.clone(), || {
+            Ok(self.external_inputs.unwrap_or_default())
+        })?;
+
+        let u_dummy = CommittedInstance::dummy(2);
+        let U_i = Vec::<CommittedInstanceVar<C1>>::new_witness(cs.clone(), || {
+            Ok(self.U_i.unwrap_or(vec![u_dummy.clone(); self.n_circuits]))
+        })?;
+        if U_i.len() != self.n_circuits {
+            return Err(SynthesisError::Unsatisfiable);
+        }
+        let U_i1_cmE = NonNativeAffineVar::new_witness(cs.clone(), || {
+            Ok(self.U_i1_cmE.unwrap_or_else(C1::zero))
+        })?;
+        let U_i1_cmW = NonNativeAffineVar::new_witness(cs.clone(), || {
+            Ok(self.U_i1_cmW.unwrap_or_else(C1::zero))
+        })?;
+
+        let cmT =
+            NonNativeAffineVar::new_witness(cs.clone(), || Ok(self.cmT.unwrap_or_else(C1::zero)))?;
+
+        let cf_u_dummy = CycleFoldCommittedInstance::dummy(NovaCycleFoldConfig::<C1>::IO_LEN);
+        let cf_U_i = CycleFoldCommittedInstanceVar::<C2>::new_witness(cs.clone(), || {
+            Ok(self.cf_U_i.unwrap_or(cf_u_dummy.clone()))
+        })?;
+        let cf1_cmT =
+            C2::Var::new_witness(cs.clone(), || Ok(self.cf1_cmT.unwrap_or_else(C2::zero)))?;
+        let cf2_cmT =
+            C2::Var::new_witness(cs.clone(), || Ok(self.cf2_cmT.unwrap_or_else(C2::zero)))?;
+
+        // `sponge` is for digest computation.
+        let sponge = PoseidonSpongeVar::<C1::ScalarField>::new(cs.clone(), &self.poseidon_config);
+        // `transcript` is for challenge generation.
+        let mut transcript = sponge.clone();
+
+        let is_basecase = i.is_zero()?;
+
+        // The program counter of the current step is the index of this circuit. Using it as a
+        // constant in the hash below enforces that u_i (ie. the previous step) selected `F_j` as
+        // the next circuit to be executed.
+        let pc_i = FpVar::constant(CF1::<C1>::from(self.pc as u64));
+        // In the base case there is no previous step, so we enforce that `F_j` is the first
+        // circuit of the program.
+        pc_0.conditional_enforce_equal(&pc_i, &is_basecase)?;
+
+        // Primary Part
+        // P.1. Compute u_i.x
+        // u_i.x[0] = H(i, pc_0, pc_i, z_0, z_i, U_i[0..n])
+        let (u_i_x, U_i_vecs) =
+            nivc_hash_gadget(&sponge, &pp_hash, &i, &pc_0, &pc_i, &z_0, &z_i, &U_i)?;
+        // u_i.x[1] = H(cf_U_i)
+        let (cf_u_i_x, cf_U_i_vec) = cf_U_i.clone().hash(&sponge, pp_hash.clone())?;
+
+        // P.2. Construct u_i
+        let u_i = CommittedInstanceVar {
+            // u_i.cmE = cm(0)
+            cmE: NonNativeAffineVar::new_constant(cs.clone(), C1::zero())?,
+            // u_i.u = 1
+            u: FpVar::one(),
+            // u_i.cmW is provided by the prover as witness
+            cmW: NonNativeAffineVar::new_witness(cs.clone(), || {
+                Ok(self.u_i_cmW.unwrap_or(C1::zero()))
+            })?,
+            // u_i.x is computed in step 1
+            x: vec![u_i_x, cf_u_i_x],
+        };
+
+        // P.3. Select the running instance U_i[last_pc] that u_i is folded into.
+        // `last_pc` does not need to be bound to the hash: folding u_i into the running instance
+        // of a circuit other than the one that produced u_i results in a running instance that
+        // does not satisfy the R1CS of that circuit, unless u_i also satisfies it, in which case
+        // the trace remains valid.
+        let is_last_pc = (0..self.n_circuits)
+            .map(|k| last_pc.is_eq(&FpVar::constant(CF1::<C1>::from(k as u64))))
+            .collect::<Result<Vec<_>, _>>()?;
+        // `last_pc` must point to one of the circuits.
+        Boolean::kary_or(&is_last_pc)?.enforce_equal(&Boolean::TRUE)?;
+        let mut U_sel = U_i[0].clone();
+        let mut U_sel_vec = U_i_vecs[0].clone();
+        for k in 1..self.n_circuits {
+            U_sel = is_last_pc[k].select(&U_i[k], &U_sel)?;
+            U_sel_vec = U_i_vecs[k]
+                .iter()
+                .zip(&U_sel_vec)
+                .map(|(a, b)| is_last_pc[k].select(a, b))
+                .collect::<Result<_, _>>()?;
+        }
+
+        // P.4. nifs.verify, obtains U_{i+1} by folding u_i & U_i[last_pc].
+        // Notice that NIFSGadget::verify does not fold cmE & cmW.
+        // We set `U_i1.cmE` and `U_i1.cmW` to unconstrained witnesses `U_i1_cmE` and `U_i1_cmW`
+        // respectively.
+        // The correctness of them will be checked on the other curve.
+        let (mut U_i1, r_bits) = NIFSGadget::<
+            C1,
+            PoseidonSponge<C1::ScalarField>,
+            PoseidonSpongeVar<C1::ScalarField>,
+        >::verify(
+            &mut transcript,
+            pp_hash.clone(),
+            U_sel.clone(),
+            U_sel_vec,
+            u_i.clone(),
+            Some(cmT.clone()),
+        )?;
+        U_i1.cmE = U_i1_cmE;
+        U_i1.cmW = U_i1_cmW;
+
+        // The vector of running instances for the next step only differs from U_i in the
+        // position `last_pc`.
+        let U_i1_vec = U_i
+            .iter()
+            .zip(&is_last_pc)
+            .map(|(U, is_selected)| is_selected.select(&U_i1, U))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // convert r_bits to a `NonNativeFieldVar`
+        let r_nonnat = {
+            let mut bits = r_bits;
+            bits.resize(C1::BaseField::MODULUS_BIT_SIZE as usize, Boolean::FALSE);
+            NonNativeUintVar::from(&bits)
+        };
+
+        // P.5.a compute and check the first output of F'
+
+        // get z_{i+1} and pc_{i+1} from the F circuit
+        let i_usize = self.i_usize.unwrap_or(0);
+        let z_i1 =
+            self.F
+                .generate_step_constraints(cs.clone(), i_usize, z_i, external_inputs.clone())?;
+        let pc_i1 = self
+            .F
+            .next_pc(cs.clone(), i_usize, &z_i1, &external_inputs)?;
+
+        // Base case: u_{i+1}.x[0] == H((i+1, pc_0, pc_{i+1}, z_0, z_{i+1}, [U_{\bot}; n])
+        // Non-base case: u_{i+1}.x[0] == H((i+1, pc_0, pc_{i+1}, z_0, z_{i+1}, U_{i+1}[0..n])
+        let i1 = i + FpVar::<CF1<C1>>::one();
+        let (u_i1_x, _) = nivc_hash_gadget(
+            &sponge, &pp_hash, &i1, &pc_0, &pc_i1, &z_0, &z_i1, &U_i1_vec,
+        )?;
+        let U_dummy_vec =
+            vec![CommittedInstanceVar::new_constant(cs.clone(), u_dummy)?; self.n_circuits];
+        let (u_i1_x_base, _) = nivc_hash_gadget(
+            &sponge,
+            &pp_hash,
+            &FpVar::<CF1<C1>>::one(),
+            &pc_0,
+            &pc_i1,
+            &z_0,
+            &z_i1,
+            &U_dummy_vec,
+        )?;
+        let x = is_basecase.select(&u_i1_x_base, &u_i1_x)?;
+        // This line "converts" `x` from a witness to a public input, see the
+        // comment in the uniform `AugmentedFCircuit` for the rationale.
+        FpVar::new_input(cs.clone(), || x.value())?.enforce_equal(&x)?;
+
+        // CycleFold part
+        // C.1. Compute cf1_u_i.x and cf2_u_i.x
+        let cfW_x = vec![
+            r_nonnat.clone(),
+            U_sel.cmW.x,
+            U_sel.cmW.y,
+            u_i.cmW.x,
+            u_i.cmW.y,
+            U_i1.cmW.x,
+            U_i1.cmW.y,
+        ];
+        let cfE_x = vec![
+            r_nonnat,
+            U_sel.cmE.x,
+            U_sel.cmE.y,
+            cmT.x,
+            cmT.y,
+            U_i1.cmE.x,
+            U_i1.cmE.y,
+        ];
+
+        // C.2. Construct `cf1_u_i` and `cf2_u_i`
+        let cf1_u_i = CycleFoldCommittedInstanceVar {
+            // cf1_u_i.cmE = 0
+            cmE: C2::Var::zero(),
+            // cf1_u_i.u = 1
+            u: NonNativeUintVar::new_constant(cs.clone(), C1::BaseField::one())?,
+            // cf1_u_i.cmW is provided by the prover as witness
+            cmW: C2::Var::new_witness(cs.clone(), || Ok(self.cf1_u_i_cmW.unwrap_or(C2::zero())))?,
+            // cf1_u_i.x is computed in step 1
+            x: cfW_x,
+        };
+        let cf2_u_i = CycleFoldCommittedInstanceVar {
+            // cf2_u_i.cmE = 0
+            cmE: C2::Var::zero(),
+            // cf2_u_i.u = 1
+            u: NonNativeUintVar::new_constant(cs.clone(), C1::BaseField::one())?,
+            // cf2_u_i.cmW is provided by the prover as witness
+            cmW: C2::Var::new_witness(cs.clone(), || Ok(self.cf2_u_i_cmW.unwrap_or(C2::zero())))?,
+            // cf2_u_i.x is computed in step 1
+            x: cfE_x,
+        };
+
+        // C.3. nifs.verify, obtains cf1_U_{i+1} by folding cf1_u_i & cf_U_i, and then cf_U_{i+1}
+        // by folding cf2_u_i & cf1_U_{i+1}.
+        let cf1_r_bits = CycleFoldChallengeGadget::<C2>::get_challenge_gadget(
+            &mut transcript,
+            pp_hash.clone(),
+            cf_U_i_vec,
+            cf1_u_i.clone(),
+            cf1_cmT.clone(),
+        )?;
+        let cf1_U_i1 =
+            NIFSFullGadget::<C2>::fold_committed_instance(cf1_r_bits, cf1_cmT, cf_U_i, cf1_u_i)?;
+
+        let cf2_r_bits = CycleFoldChallengeGadget::<C2>::get_challenge_gadget(
+            &mut transcript,
+            pp_hash.clone(),
+            cf1_U_i1.to_native_sponge_field_elements()?,
+            cf2_u_i.clone(),
+            cf2_cmT.clone(),
+        )?;
+        let cf_U_i1 =
+            NIFSFullGadget::<C2>::fold_committed_instance(cf2_r_bits, cf2_cmT, cf1_U_i1, cf2_u_i)?;
+
+        // Back to Primary Part
+        // P.5.b compute and check the second output of F'
+        // Base case: u_{i+1}.x[1] == H(cf_U_{\bot})
+        // Non-base case: u_{i+1}.x[1] == H(cf_U_{i+1})
+        let (cf_u_i1_x, _) = cf_U_i1.clone().hash(&sponge, pp_hash.clone())?;
+        let (cf_u_i1_x_base, _) =
+            CycleFoldCommittedInstanceVar::<C2>::new_constant(cs.clone(), cf_u_dummy)?
+                .hash(&sponge, pp_hash)?;
+        let cf_x = is_basecase.select(&cf_u_i1_x_base, &cf_u_i1_x)?;
+        FpVar::new_input(cs.clone(), || cf_x.value())?.enforce_equal(&cf_x)?;
+
+        Ok((z_i1, pc_i1))
+    }
+}
+
+impl<C1, C2, FC> ConstraintSynthesizer<CF1<C1>> for AugmentedFCircuit<C1, C2, FC>
+where
+    C1: Curve<BaseField = C2::ScalarField, ScalarField = C2::BaseField>,
+    C2: Curve,
+    FC: NIVCFCircuit<CF1<C1>>,
+{
+    fn generate_constraints(self, cs: ConstraintSystemRef<CF1<C1>>) -> Result<(), SynthesisError> {
+        self.compute_next_state(cs).map(|_| ())
+    }
+}
@@ -0,0 +1,1001 @@
+/// Implements non-uniform IVC (NIVC) on top of Nova+CycleFold, following the approach described in
+/// [SuperNova](https://eprint.iacr.org/2022/1758.pdf).
+///
+/// Instead of a single step circuit `F`, the program is defined by a list of step circuits
+/// `F_0, ..., F_{n-1}`. At each step, the circuit to be executed is selected by the program
+/// counter `pc_i`, which is computed in-circuit by the previous step (see
+/// [`NIVCFCircuit::next_pc`]). The prover keeps one running instance per step circuit, so the cost
+/// of each step only depends on the size of the circuit being executed, instead of on the size of
+/// the union of all the circuits.
+use ark_crypto_primitives::sponge::{
+    poseidon::{PoseidonConfig, PoseidonSponge},
+    CryptographicSponge,
+};
+use ark_ff::PrimeField;
+use ark_r1cs_std::{fields::fp::FpVar, R1CSVar};
+use ark_relations::r1cs::{ConstraintSystem, ConstraintSystemRef, SynthesisError};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Valid};
+use ark_std::{rand::RngCore, One, Zero};
+use num_bigint::BigUint;
+
+use super::{
+    get_r1cs_from_cs,
+    nifs::{nova::NIFS, NIFSTrait},
+    CommittedInstance, NovaCycleFoldCircuit, NovaCycleFoldConfig, ProverParams, Witness,
+};
+use crate::arith::{
+    r1cs::{extract_w_x, R1CS},
+    Arith, ArithRelation,
+};
+use crate::commitment::CommitmentScheme;
+use crate::folding::circuits::{
+    cyclefold::{fold_cyclefold_circuit, CycleFoldCommittedInstance, CycleFoldWitness},
+    CF1,
+};
+use crate::folding::traits::{CommittedInstanceOps, Dummy};
+use crate::frontend::FCircuit;
+use crate::transcript::{poseidon::poseidon_canonical_config, Transcript};
+use crate::utils::pp_hash;
+use crate::{Curve, Error};
+
+pub mod circuits;
+
+use circuits::AugmentedFCircuit;
+
+/// `NIVCFCircuit` extends [`FCircuit`] with the computation of the program counter, so that the
+/// step circuit can be used in non-uniform IVC.
+///
+/// Since [`NIVC`] holds the list of step circuits as a `Vec<FC>`, programs with heterogeneous
+/// step circuits are expected to implement this trait for a type (e.g. an enum) that dispatches
+/// to the concrete circuit. All the step circuits must share the same `state_len` and external
+/// inputs type.
+pub trait NIVCFCircuit<F: PrimeField>: FCircuit<F> {
+    /// generates the constraints that compute the program counter of the next step, ie. the
+    /// index of the step circuit that has to be executed next (the function `φ` in SuperNova),
+    /// from the new state `z_{i+1}` and the external inputs of the current step.
+    fn next_pc(
+        &self,
+        cs: ConstraintSystemRef<F>,
+        i: usize,
+        z_i1: &[FpVar<F>],
+        external_inputs: &Self::ExternalInputsVar,
+    ) -> Result<FpVar<F>, SynthesisError>;
+}
+
+/// Returns `H(pp_hash, i, pc_0, pc_i, z_0, z_i, U_i[0], ..., U_i[n-1])`, the hash of the NIVC state
+/// that is used as the first public input of the incoming instances. It is compatible with the
+/// in-circuit implementation [`circuits::nivc_hash_gadget`].
+#[allow(clippy::too_many_arguments)]
+pub fn nivc_hash<C: Curve, T: Transcript<CF1<C>>>(
+    sponge: &T,
+    pp_hash: CF1<C>,
+    i: CF1<C>,
+    pc_0: CF1<C>,
+    pc_i: CF1<C>,
+    z_0: &[CF1<C>],
+    z_i: &[CF1<C>],
+    U_i: &[CommittedInstance<C>],
+) -> CF1<C> {
+    let mut sponge = sponge.clone();
+    sponge.absorb(&pp_hash);
+    sponge.absorb(&i);
+    sponge.absorb(&pc_0);
+    sponge.absorb(&pc_i);
+    sponge.absorb(&z_0);
+    sponge.absorb(&z_i);
+    for U in U_i {
+        sponge.absorb(U);
+    }
+    sponge.squeeze_field_elements(1)[0]
+}
+
+#[derive(Debug, Clone)]
+pub struct PreprocessorParam<C1, C2, FC, CS1, CS2, const H: bool = false>
+where
+    C1: Curve,
+    C2: Curve,
+    FC: NIVCFCircuit<C1::ScalarField>,
+    CS1: CommitmentScheme<C1, H>,
+    CS2: CommitmentScheme<C2, H>,
+{
+    pub poseidon_config: PoseidonConfig<C1::ScalarField>,
+    /// list of step circuits, where the step circuit `F[j]` is selected when `pc_i == j`
+    pub F: Vec<FC>,
+    // cs params if not provided, will be generated at the preprocess method
+    pub cs_pp: Option<CS1::ProverParams>,
+    pub cs_vp: Option<CS1::VerifierParams>,
+    pub cf_cs_pp: Option<CS2::ProverParams>,
+    pub cf_cs_vp: Option<CS2::VerifierParams>,
+}
+
+impl<C1, C2, FC, CS1, CS2, const H: bool> PreprocessorParam<C1, C2, FC, CS1, CS2, H>
+where
+    C1: Curve,
+    C2: Curve,
+    FC: NIVCFCircuit<C1::ScalarField>,
+    CS1: CommitmentScheme<C1, H>,
+    CS2: CommitmentScheme<C2, H>,
+{
+    pub fn new(poseidon_config: PoseidonConfig<C1::ScalarField>, F: Vec<FC>) -> Self {
+        Self {
+            poseidon_config,
+            F,
+            cs_pp: None,
+            cs_vp: None,
+            cf_cs_pp: None,
+            cf_cs_vp: None,
+        }
+    }
+}
+
+/// Verification parameters for Nova-based NIVC
+#[derive(Debug, Clone)]
+pub struct VerifierParams<C1, C2, CS1, CS2, const H: bool = false>
+where
+    C1: Curve,
+    C2: Curve,
+    CS1: CommitmentScheme<C1, H>,
+    CS2: CommitmentScheme<C2, H>,
+{
+    /// Poseidon sponge configuration
+    pub poseidon_config: PoseidonConfig<C1::ScalarField>,
+    /// R1CS of the Augmented step circuits, one per step circuit
+    pub r1cs: Vec<R1CS<C1::ScalarField>>,
+    /// R1CS of the CycleFold circuit
+    pub cf_r1cs: R1CS<C2::ScalarField>,
+    /// Verification parameters of the underlying commitment scheme over C1
+    pub cs_vp: CS1::VerifierParams,
+    /// Verification parameters of the underlying commitment scheme over C2
+    pub cf_cs_vp: CS2::VerifierParams,
+}
+
+impl<C1, C2, CS1, CS2, const H: bool> Valid for VerifierParams<C1, C2, CS1, CS2, H>
+where
+    C1: Curve,
+    C2: Curve,
+    CS1: CommitmentScheme<C1, H>,
+    CS2: CommitmentScheme<C2, H>,
+{
+    fn check(&self) -> Result<(), ark_serialize::SerializationError> {
+        self.cs_vp.check()?;
+        self.cf_cs_vp.check()?;
+        Ok(())
+    }
+}
+impl<C1, C2, CS1, CS2, const H: bool> CanonicalSerialize for VerifierParams<C1, C2, CS1, CS2, H>
+where
+    C1: Curve,
+    C2: Curve,
+    CS1: CommitmentScheme<C1, H>,
+    CS2: CommitmentScheme<C2, H>,
+{
+    fn serialize_with_mode<W: std::io::prelude::Write>(
+        &self,
+        mut writer: W,
+        compress: ark_serialize::Compress,
+    ) -> Result<(), ark_serialize::SerializationError> {
+        self.cs_vp.serialize_with_mode(&mut writer, compress)?;
+        self.cf_cs_vp.serialize_with_mode(&mut writer, compress)
+    }
+
+    fn serialized_size(&self, compress: ark_serialize::Compress) -> usize {
+        self.cs_vp.serialized_size(compress) + self.cf_cs_vp.serialized_size(compress)
+    }
+}
+
+impl<C1, C2, CS1, CS2, const H: bool> VerifierParams<C1, C2, CS1, CS2, H>
+where
+    C1: Curve,
+    C2: Curve,
+    CS1: CommitmentScheme<C1, H>,
+    CS2: CommitmentScheme<C2, H>,
+{
+    /// returns the hash of the public parameters of the NIVC, which covers the R1CS of all the
+    /// augmented step circuits
+    pub fn pp_hash(&self) -> Result<C1::ScalarField, Error> {
+        pp_hash::<C1, C2, CS1, CS2, H, PoseidonSponge<C1::ScalarField>>(
+            &self.r1cs,
+            &self.cf_r1cs,
+            &self.cs_vp,
+            &self.cf_cs_vp,
+            &self.poseidon_config,
+        )
+    }
+}
+
+#[derive(PartialEq, Eq, Debug, Clone, CanonicalSerialize, CanonicalDeserialize)]
+pub struct IVCProof<C1, C2>
+where
+    C1: Curve,
+    C2: Curve,
+{
+    // current step of the IVC
+    pub i: C1::ScalarField,
+    // initial program counter
+    pub pc_0: usize,
+    // program counter of the next step
+    pub pc_i: usize,
+    // index of the step circuit of which u_i is an instance
+    pub last_pc: usize,
+    // initial state
+    pub z_0: Vec<C1::ScalarField>,
+    // current state
+    pub z_i: Vec<C1::ScalarField>,
+    // running instances, one per step circuit
+    pub W_i: Vec<Witness<C1>>,
+    pub U_i: Vec<CommittedInstance<C1>>,
+    // incoming instance
+    pub w_i: Witness<C1>,
+    pub u_i: CommittedInstance<C1>,
+    // CycleFold instances
+    pub cf_W_i: CycleFoldWitness<C2>,
+    pub cf_U_i: CycleFoldCommittedInstance<C2>,
+}
+
+/// Implements Nova+CycleFold's non-uniform IVC.
+/// The `H` const generic specifies whether the homorphic commitment scheme is blinding
+#[derive(Clone, Debug)]
+pub struct NIVC<C1, C2, FC, CS1, CS2, const H: bool = false>
+where
+    C1: Curve,
+    C2: Curve,
+    FC: NIVCFCircuit<C1::ScalarField>,
+    CS1: CommitmentScheme<C1, H>,
+    CS2: CommitmentScheme<C2, H>,
+{
+    /// R1CS of the Augmented Function circuits, one per step circuit
+    pub r1cs: Vec<R1CS<C1::ScalarField>>,
+    /// R1CS of the CycleFold circuit
+    pub cf_r1cs: R1CS<C2::ScalarField>,
+    pub poseidon_config: PoseidonConfig<C1::ScalarField>,
+    /// CommitmentScheme::ProverParams over C1
+    pub cs_pp: CS1::ProverParams,
+    /// CycleFold CommitmentScheme::ProverParams, over C2
+    pub cf_cs_pp: CS2::ProverParams,
+    /// F circuits, the circuits that are being folded
+    pub F: Vec<FC>,
+    /// public params hash
+    pub pp_hash: C1::ScalarField,
+    pub i: C1::ScalarField,
+    /// initial program counter
+    pub pc_0: usize,
+    /// program counter, ie. index of the step circuit to be executed at the next step
+    pub pc_i: usize,
+    /// index of the step circuit of which `u_i` is an instance
+    pub last_pc: usize,
+    /// initial state
+    pub z_0: Vec<C1::ScalarField>,
+    /// current i-th state
+    pub z_i: Vec<C1::ScalarField>,
+    /// Nova instances
+    pub w_i: Witness<C1>,
+    pub u_i: CommittedInstance<C1>,
+    pub W_i: Vec<Witness<C1>>,
+    pub U_i: Vec<CommittedInstance<C1>>,
+
+    /// CycleFold running instance
+    pub cf_W_i: CycleFoldWitness<C2>,
+    pub cf_U_i: CycleFoldCommittedInstance<C2>,
+}
+
+impl<C1, C2, FC, CS1, CS2, const H: bool> NIVC<C1, C2, FC, CS1, CS2, H>
+where
+    C1: Curve<BaseField = C2::ScalarField, ScalarField = C2::BaseField>,
+    C2: Curve,
+    FC: NIVCFCircuit<C1::ScalarField>,
+    CS1: CommitmentScheme<C1, H>,
+    CS2: CommitmentScheme<C2, H>,
+{
+    /// Deserializes the [`VerifierParams`], recomputing the R1CS of the augmented step circuits
+    /// and of the CycleFold circuit from the given step circuits' params.
+    pub fn vp_deserialize_with_mode<R: std::io::prelude::Read>(
+        mut reader: R,
+        compress: ark_serialize::Compress,
+        validate: ark_serialize::Validate,
+        fc_params: Vec<FC::Params>,
+    ) -> Result<VerifierParams<C1, C2, CS1, CS2, H>, Error> {
+        let poseidon_config = poseidon_canonical_config::<C1::ScalarField>();
+
+        let F = fc_params
+            .into_iter()
+            .map(FC::new)
+            .collect::<Result<Vec<_>, _>>()?;
+        let (r1cs, cf_r1cs) = get_r1cs::<C1, C2, FC>(&poseidon_config, &F)?;
+
+        let cs_vp = CS1::VerifierParams::deserialize_with_mode(&mut reader, compress, validate)?;
+        let cf_cs_vp = CS2::VerifierParams::deserialize_with_mode(&mut reader, compress, validate)?;
+
+        Ok(VerifierParams {
+            poseidon_config,
+            r1cs,
+            cf_r1cs,
+            cs_vp,
+            cf_cs_vp,
+        })
+    }
+
+    #[allow(clippy::type_complexity)]
+    pub fn preprocess(
+        mut rng: impl RngCore,
+        prep_param: &PreprocessorParam<C1, C2, FC, CS1, CS2, H>,
+    ) -> Result<
+        (
+            ProverParams<C1, C2, CS1, CS2, H>,
+            VerifierParams<C1, C2, CS1, CS2, H>,
+        ),
+        Error,
+    > {
+        let (r1cs, cf_r1cs) = get_r1cs::<C1, C2, FC>(&prep_param.poseidon_config, &prep_param.F)?;
+
+        // if cs params exist, use them, if not, generate new ones
+        let (cs_pp, cs_vp) = match (&prep_param.cs_pp, &prep_param.cs_vp) {
+            (Some(cs_pp), Some(cs_vp)) => (cs_pp.clone(), cs_vp.clone()),
+            _ => CS1::setup(
+                &mut rng,
+                // `CS1` is shared by all the step circuits, so its length is the maximum length
+                // of `e` and `w` across all the augmented circuits.
+                r1cs.iter()
+                    .map(|r1cs| r1cs.n_constraints().max(r1cs.n_witnesses()))
+                    .max()
+                    .unwrap_or_default(),
+            )?,
+        };
+        let (cf_cs_pp, cf_cs_vp) = match (&prep_param.cf_cs_pp, &prep_param.cf_cs_vp) {
+            (Some(cf_cs_pp), Some(cf_cs_vp)) => (cf_cs_pp.clone(), cf_cs_vp.clone()),
+            _ => CS2::setup(&mut rng, cf_r1cs.n_constraints().max(cf_r1cs.n_witnesses()))?,
+        };
+        let (cs_pp, cf_cs_pp) = prep_param.attach_fixed_base_tables(cs_pp, cf_cs_pp)?;
+
+        let prover_params = ProverParams::<C1, C2, CS1, CS2, H> {
+            poseidon_config: prep_param.poseidon_config.clone(),
+            cs_pp,
+            cf_cs_pp,
+        };
+        let verifier_params = VerifierParams::<C1, C2, CS1, CS2, H> {
+            poseidon_config: prep_param.poseidon_config.clone(),
+            r1cs,
+            cf_r1cs,
+            cs_vp,
+            cf_cs_vp,
+        };
+
+        Ok((prover_params, verifier_params))
+    }
+
+    /// Initializes the NIVC for the given parameters, step circuits, initial state `z_0` and
+    /// initial program counter `pc_0`.
+    pub fn init(
+        params: &(
+            ProverParams<C1, C2, CS1, CS2, H>,
+            VerifierParams<C1, C2, CS1, CS2, H>,
+        ),
+        F: Vec<FC>,
+        z_0: Vec<C1::ScalarField>,
+        pc_0: usize,
+    ) -> Result<Self, Error> {
+        let (pp, vp) = params;
+
+        if F.len() != vp.r1cs.len() {
+            return Err(Error::NotSameLength(
+                "F.len()".to_string(),
+                F.len(),
+                "vp.r1cs.len()".to_string(),
+                vp.r1cs.len(),
+            ));
+        }
+        if pc_0 >= F.len() {
+            return Err(Error::OutOfBounds);
+        }
+
+        // compute the public params hash
+        let pp_hash = vp.pp_hash()?;
+
+        // setup the dummy instances, one running instance per step circuit. The incoming instance
+        // is a dummy instance of the circuit 0, which is consistent with `last_pc = 0`.
+        let (W_dummy, U_dummy): (Vec<Witness<C1>>, Vec<CommittedInstance<C1>>) = vp
+            .r1cs
+            .iter()
+            .map(|r1cs| r1cs.dummy_witness_instance())
+            .unzip();
+        let (w_dummy, u_dummy) = vp.r1cs[0].dummy_witness_instance();
+        let (cf_W_dummy, cf_U_dummy) = vp.cf_r1cs.dummy_witness_instance();
+
+        Ok(Self {
+            r1cs: vp.r1cs.clone(),
+            cf_r1cs: vp.cf_r1cs.clone(),
+            poseidon_config: pp.poseidon_config.clone(),
+            cs_pp: pp.cs_pp.clone(),
+            cf_cs_pp: pp.cf_cs_pp.clone(),
+            F,
+            pp_hash,
+            i: C1::ScalarField::zero(),
+            pc_0,
+            pc_i: pc_0,
+            last_pc: 0,
+            z_0: z_0.clone(),
+            z_i: z_0,
+            w_i: w_dummy,
+            u_i: u_dummy,
+            W_i: W_dummy,
+            U_i: U_dummy,
+            // cyclefold running instance
+            cf_W_i: cf_W_dummy,
+            cf_U_i: cf_U_dummy,
+        })
+    }
+
+    /// Implements IVC.P of the NIVC, executing the step circuit `F[pc_i]`.
+    pub fn prove_step(
+        &mut self,
+        mut rng: impl RngCore,
+        external_inputs: FC::ExternalInputs,
+    ) -> Result<(), Error> {
+        let pc = self.pc_i;
+        let last_pc = self.last_pc;
+        let n_circuits = self.F.len();
+        if pc >= n_circuits || last_pc >= n_circuits {
+            return Err(Error::OutOfBounds);
+        }
+
+        // ensure that commitments are blinding if user has specified so.
+        // As in Nova, only the blinding of cmW is checked, since rE stays zero.
+        if H && self.i >= C1::ScalarField::one() {
+            let mut blinding_commitments = vec![self.w_i.rW];
+            // blinding values of the running instance are zero until it has been folded once
+            if self.U_i[last_pc] != CommittedInstance::dummy(&self.r1cs[last_pc]) {
+                blinding_commitments.push(self.W_i[last_pc].rW);
+            }
+            if blinding_commitments.contains(&C1::ScalarField::zero()) {
+                return Err(Error::IncorrectBlinding(
+                    H,
+                    format!("{blinding_commitments:?}"),
+                ));
+            }
+        }
+        // `sponge` is for digest computation.
+        let sponge = PoseidonSponge::<C1::ScalarField>::new(&self.poseidon_config);
+        // `transcript` is for challenge generation.
+        let mut transcript = sponge.clone();
+
+        let F = self.F[pc].clone();
+        if self.z_i.len() != F.state_len() {
+            return Err(Error::NotSameLength(
+                "z_i.len()".to_string(),
+                self.z_i.len(),
+                "F.state_len()".to_string(),
+                F.state_len(),
+            ));
+        }
+
+        let i_bn: BigUint = self.i.into();
+        let i_usize: usize = i_bn.try_into().map_err(|_| Error::MaxStep)?;
+
+        let mut augmented_F_circuit =
+            AugmentedFCircuit::<C1, C2, FC>::empty(&self.poseidon_config, F, pc, n_circuits);
+        augmented_F_circuit.pp_hash = Some(self.pp_hash);
+        augmented_F_circuit.i = Some(self.i);
+        augmented_F_circuit.i_usize = Some(i_usize);
+        augmented_F_circuit.pc_0 = Some(C1::ScalarField::from(self.pc_0 as u64));
+        augmented_F_circuit.last_pc = Some(last_pc);
+        augmented_F_circuit.z_0 = Some(self.z_0.clone());
+        augmented_F_circuit.z_i = Some(self.z_i.clone());
+        augmented_F_circuit.external_inputs = Some(external_inputs);
+        augmented_F_circuit.u_i_cmW = Some(self.u_i.cmW);
+        augmented_F_circuit.U_i = Some(self.U_i.clone());
+
+        // In the base case there is nothing to fold, and the running instances remain the dummy
+        // ones. The values of the folding are left unassigned, since the outputs of the augmented
+        // circuit do not depend on them in the base case.
+        if self.i != C1::ScalarField::zero() {
+            // fold u_i into the running instance of the circuit that produced it
+            let (W_i1, U_i1, cmT, r_bits): (Witness<C1>, CommittedInstance<C1>, C1, Vec<bool>) =
+                NIFS::<C1, CS1, PoseidonSponge<C1::ScalarField>, H>::prove(
+                    &self.cs_pp,
+                    &self.r1cs[last_pc],
+                    &mut transcript,
+                    self.pp_hash,
+                    &self.W_i[last_pc],
+                    &self.U_i[last_pc],
+                    &self.w_i,
+                    &self.u_i,
+                )?;
+
+            #[cfg(test)]
+            {
+                use ark_ff::BigInteger;
+                let r_Fr = C1::ScalarField::from_bigint(BigInteger::from_bits_le(&r_bits))
+                    .ok_or(Error::OutOfBounds)?;
+                let expected =
+                    NIFS::<C1, CS1, PoseidonSponge<C1::ScalarField>, H>::fold_committed_instances(
+                        r_Fr,
+                        &self.U_i[last_pc],
+                        &self.u_i,
+                        &cmT,
+                    );
+                assert_eq!(U_i1, expected);
+            }
+
+            // CycleFold part:
+            let cfW_circuit = NovaCycleFoldCircuit::<C1> {
+                r_bits: Some(r_bits.clone()),
+                points: Some(vec![self.U_i[last_pc].cmW, self.u_i.cmW]),
+            };
+            let cfE_circuit = NovaCycleFoldCircuit::<C1> {
+                r_bits: Some(r_bits),
+                points: Some(vec![self.U_i[last_pc].cmE, cmT]),
+            };
+
+            // fold self.cf_U_i + cfW_U -> folded running with cfW
+            let (cfW_u_i, cfW_W_i1, cfW_U_i1, cfW_cmT) = self.fold_cyclefold_circuit(
+                &mut transcript,
+                self.cf_W_i.clone(), // CycleFold running instance witness
+                self.cf_U_i.clone(), // CycleFold running instance
+                cfW_circuit,
+                &mut rng,
+            )?;
+            // fold [the output from folding self.cf_U_i + cfW_U] + cfE_U = folded_running_with_cfW + cfE
+            let (cfE_u_i, cf_W_i1, cf_U_i1, cf_cmT) = self.fold_cyclefold_circuit(
+                &mut transcript,
+                cfW_W_i1,
+                cfW_U_i1,
+                cfE_circuit,
+                &mut rng,
+            )?;
+
+            augmented_F_circuit.U_i1_cmE = Some(U_i1.cmE);
+            augmented_F_circuit.U_i1_cmW = Some(U_i1.cmW);
+            augmented_F_circuit.cmT = Some(cmT);
+            // cyclefold values
+            augmented_F_circuit.cf1_u_i_cmW = Some(cfW_u_i.cmW);
+            augmented_F_circuit.cf2_u_i_cmW = Some(cfE_u_i.cmW);
+            augmented_F_circuit.cf_U_i = Some(self.cf_U_i.clone());
+            augmented_F_circuit.cf1_cmT = Some(cfW_cmT);
+            augmented_F_circuit.cf2_cmT = Some(cf_cmT);
+
+            self.W_i[last_pc] = W_i1;
+            self.U_i[last_pc] = U_i1;
+            self.cf_W_i = cf_W_i1;
+            self.cf_U_i = cf_U_i1;
+        }
+
+        let cs = ConstraintSystem::<C1::ScalarField>::new_ref();
+
+        let (z_i1, pc_i1) = augmented_F_circuit.compute_next_state(cs.clone())?;
+        let z_i1 = z_i1.value()?;
+        let pc_i1: BigUint = pc_i1.value()?.into();
+        let pc_i1: usize = pc_i1.try_into().map_err(|_| Error::OutOfBounds)?;
+
+        #[cfg(test)]
+        assert!(cs.is_satisfied()?);
+
+        let cs = cs.into_inner().ok_or(Error::NoInnerConstraintSystem)?;
+        let (w_i1, x_i1) = extract_w_x::<C1::ScalarField>(&cs);
+
+        #[cfg(test)]
+        if x_i1.len() != 2 {
+            return Err(Error::NotExpectedLength(x_i1.len(), 2));
+        }
+
+        // set values for next iteration
+        self.i += C1::ScalarField::one();
+        self.z_i = z_i1;
+        self.pc_i = pc_i1;
+        self.last_pc = pc;
+        self.w_i = Witness::<C1>::new::<H>(w_i1, self.r1cs[pc].n_constraints(), &mut rng);
+        self.u_i = self.w_i.commit::<CS1, H>(&self.cs_pp, x_i1)?;
+
+        #[cfg(test)]
+        {
+            self.u_i.check_incoming()?;
+            self.r1cs[pc].check_relation(&self.w_i, &self.u_i)?;
+            self.r1cs[last_pc].check_relation(&self.W_i[last_pc], &self.U_i[last_pc])?;
+        }
+
+        Ok(())
+    }
+
+    /// returns the current state `z_i`
+    pub fn state(&self) -> Vec<C1::ScalarField> {
+        self.z_i.clone()
+    }
+
+    /// returns the program counter of the next step
+    pub fn pc(&self) -> usize {
+        self.pc_i
+    }
+
+    pub fn ivc_proof(&self) -> IVCProof<C1, C2> {
+        IVCProof {
+            i: self.i,
+            pc_0: self.pc_0,
+            pc_i: self.pc_i,
+            last_pc: self.last_pc,
+            z_0: self.z_0.clone(),
+            z_i: self.z_i.clone(),
+            W_i: self.W_i.clone(),
+            U_i: self.U_i.clone(),
+            w_i: self.w_i.clone(),
+            u_i: self.u_i.clone(),
+            cf_W_i: self.cf_W_i.clone(),
+            cf_U_i: self.cf_U_i.clone(),
+        }
+    }
+
+    pub fn from_ivc_proof(
+        ivc_proof: IVCProof<C1, C2>,
+        fcircuit_params: Vec<FC::Params>,
+        params: (
+            ProverParams<C1, C2, CS1, CS2, H>,
+            VerifierParams<C1, C2, CS1, CS2, H>,
+        ),
+    ) -> Result<Self, Error> {
+        let IVCProof {
+            i,
+            pc_0,
+            pc_i,
+            last_pc,
+            z_0,
+            z_i,
+            W_i,
+            U_i,
+            w_i,
+            u_i,
+            cf_W_i,
+            cf_U_i,
+        } = ivc_proof;
+        let (pp, vp) = params;
+
+        let F = fcircuit_params
+            .into_iter()
+            .map(FC::new)
+            .collect::<Result<Vec<_>, _>>()?;
+        if F.len() != vp.r1cs.len() || W_i.len() != F.len() || U_i.len() != F.len() {
+            return Err(Error::NotSameLength(
+                "F.len()".to_string(),
+                F.len(),
+                "U_i.len()".to_string(),
+                U_i.len(),
+            ));
+        }
+
+        Ok(Self {
+            pp_hash: vp.pp_hash()?,
+            r1cs: vp.r1cs,
+            cf_r1cs: vp.cf_r1cs,
+            poseidon_config: pp.poseidon_config,
+            cs_pp: pp.cs_pp,
+            cf_cs_pp: pp.cf_cs_pp,
+            F,
+            i,
+            pc_0,
+            pc_i,
+            last_pc,
+            z_0,
+            z_i,
+            w_i,
+            u_i,
+            W_i,
+            U_i,
+            cf_W_i,
+            cf_U_i,
+        })
+    }
+
+    /// Implements IVC.V of the NIVC. As in Nova, this method does not include the commitments
+    /// verification, which is done in the Decider.
+    pub fn verify(
+        vp: VerifierParams<C1, C2, CS1, CS2, H>,
+        ivc_proof: IVCProof<C1, C2>,
+    ) -> Result<(), Error> {
+        let IVCProof {
+            i: num_steps,
+            pc_0,
+            pc_i,
+            last_pc,
+            z_0,
+            z_i,
+            W_i,
+            U_i,
+            w_i,
+            u_i,
+            cf_W_i,
+            cf_U_i,
+        } = ivc_proof;
+
+        let sponge = PoseidonSponge::<C1::ScalarField>::new(&vp.poseidon_config);
+
+        if num_steps == C1::ScalarField::zero() {
+            if z_0 != z_i || pc_0 != pc_i {
+                return Err(Error::IVCVerificationFail);
+            }
+            return Ok(());
+        }
+
+        let n_circuits = vp.r1cs.len();
+        if W_i.len() != n_circuits || U_i.len() != n_circuits || last_pc >= n_circuits {
+            return Err(Error::IVCVerificationFail);
+        }
+        if u_i.x.len() != 2 || U_i.iter().any(|U| U.x.len() != 2) {
+            return Err(Error::IVCVerificationFail);
+        }
+
+        let pp_hash = vp.pp_hash()?;
+
+        // check that u_i's output points to the running instances
+        // u_i.X[0] == H(i, pc_0, pc_i, z_0, z_i, U_i[0..n])
+        let expected_u_i_x = nivc_hash(
+            &sponge,
+            pp_hash,
+            num_steps,
+            C1::ScalarField::from(pc_0 as u64),
+            C1::ScalarField::from(pc_i as u64),
+            &z_0,
+            &z_i,
+            &U_i,
+        );
+        if expected_u_i_x != u_i.x[0] {
+            return Err(Error::IVCVerificationFail);
+        }
+        // u_i.X[1] == H(cf_U_i)
+        let expected_cf_u_i_x = cf_U_i.hash_cyclefold(&sponge, pp_hash);
+        if expected_cf_u_i_x != u_i.x[1] {
+            return Err(Error::IVCVerificationFail);
+        }
+
+        // check R1CS satisfiability of the incoming instance w.r.t. the circuit that produced it
+        u_i.check_incoming()?;
+        vp.r1cs[last_pc].check_relation(&w_i, &u_i)?;
+        // check RelaxedR1CS satisfiability of each of the running instances
+        for ((r1cs, W), U) in vp.r1cs.iter().zip(&W_i).zip(&U_i) {
+            r1cs.check_relation(W, U)?;
+        }
+
+        // check CycleFold RelaxedR1CS satisfiability
+        vp.cf_r1cs.check_relation(&cf_W_i, &cf_U_i)?;
+
+        Ok(())
+    }
+
+    // folds the given cyclefold circuit and its instances
+    #[allow(clippy::type_complexity)]
+    fn fold_cyclefold_circuit<T: Transcript<C1::ScalarField>>(
+        &self,
+        transcript: &mut T,
+        cf_W_i: CycleFoldWitness<C2>, // witness of the running instance
+        cf_U_i: CycleFoldCommittedInstance<C2>, // running instance
+        cf_circuit: NovaCycleFoldCircuit<C1>,
+        rng: &mut impl RngCore,
+    ) -> Result<
+        (
+            CycleFoldCommittedInstance<C2>, // u_i
+            CycleFoldWitness<C2>,           // W_i1
+            CycleFoldCommittedInstance<C2>, // U_i1
+            C2,                             // cmT
+        ),
+        Error,
+    > {
+        fold_cyclefold_circuit::<NovaCycleFoldConfig<C1>, C2, CS2, H>(
+            transcript,
+            self.cf_r1cs.clone(),
+            self.cf_cs_pp.clone(),
+            self.pp_hash,
+            cf_W_i,
+            cf_U_i,
+            cf_circuit,
+            rng,
+        )
+    }
+}
+
+/// helper method to get the R1CS of the AugmentedFCircuit of each of the step circuits, and of the
+/// CycleFold circuit
+#[allow(clippy::type_complexity)]
+pub fn get_r1cs<C1, C2, FC>(
+    poseidon_config: &PoseidonConfig<C1::ScalarField>,
+    F_circuits: &[FC],
+) -> Result<(Vec<R1CS<C1::ScalarField>>, R1CS<C2::ScalarField>), Error>
+where
+    C1: Curve<BaseField = C2::ScalarField, ScalarField = C2::BaseField>,
+    C2: Curve,
+    FC: NIVCFCircuit<C1::ScalarField>,
+{
+    if F_circuits.is_empty() {
+        return Err(Error::Empty);
+    }
+    let r1cs = F_circuits
+        .iter()
+        .enumerate()
+        .map(|(pc, F)| {
+            get_r1cs_from_cs::<C1::ScalarField>(AugmentedFCircuit::<C1, C2, FC>::empty(
+                poseidon_config,
+                F.clone(),
+                pc,
+                F_circuits.len(),
+            ))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let cf_r1cs = get_r1cs_from_cs::<C2::ScalarField>(NovaCycleFoldCircuit::<C1>::empty())?;
+    Ok((r1cs, cf_r1cs))
+}
+
+#[cfg(test)]
+pub mod tests {
+    use ark_bn254::{Bn254, Fr, G1Projective as Projective};
+    use ark_ff::BigInteger;
+    use ark_grumpkin::Projective as Projective2;
+    use ark_r1cs_std::{
+        alloc::AllocVar, convert::ToBitsGadget, fields::FieldVar, prelude::Boolean,
+    };
+    use ark_std::marker::PhantomData;
+
+    use super::*;
+    use crate::commitment::{kzg::KZG, pedersen::Pedersen};
+    use crate::frontend::utils::cubic_step_native;
+
+    /// Step circuits used to test the NIVC: circuit 0 computes `x^3 + x + 5` and circuit 1
+    /// computes `x^2 + 1`. In both cases the next circuit is selected by the least significant
+    /// bit of the new state.
+    #[derive(Clone, Copy, Debug)]
+    pub struct TestNIVCFCircuit<F: PrimeField> {
+        circuit: usize,
+        _f: PhantomData<F>,
+    }
+
+    impl<F: PrimeField> FCircuit<F> for TestNIVCFCircuit<F> {
+        type Params = usize;
+        type ExternalInputs = ();
+        type ExternalInputsVar = ();
+
+        fn new(circuit: Self::Params) -> Result<Self, Error> {
+            Ok(Self {
+                circuit,
+                _f: PhantomData,
+            })
+        }
+        fn state_len(&self) -> usize {
+            1
+        }
+        fn generate_step_constraints(
+            &self,
+            cs: ConstraintSystemRef<F>,
+            _i: usize,
+            z_i: Vec<FpVar<F>>,
+            _external_inputs: Self::ExternalInputsVar,
+        ) -> Result<Vec<FpVar<F>>, SynthesisError> {
+            let z_i = z_i[0].clone();
+            match self.circuit {
+                0 => {
+                    let five = FpVar::<F>::new_constant(cs.clone(), F::from(5u32))?;
+                    Ok(vec![&z_i * &z_i * &z_i + &z_i + &five])
+                }
+                _ => Ok(vec![&z_i * &z_i + FpVar::one()]),
+            }
+        }
+    }
+
+    impl<F: PrimeField> NIVCFCircuit<F> for TestNIVCFCircuit<F> {
+        fn next_pc(
+            &self,
+            _cs: ConstraintSystemRef<F>,
+            _i: usize,
+            z_i1: &[FpVar<F>],
+            _external_inputs: &Self::ExternalInputsVar,
+        ) -> Result<FpVar<F>, SynthesisError> {
+            let lsb = z_i1[0].to_bits_le()?[0].clone();
+            Ok(Boolean::le_bits_to_fp(&[lsb])?)
+        }
+    }
+
+    /// native implementation of `TestNIVCFCircuit`, returns `(z_{i+1}, pc_{i+1})`
+    fn test_step_native(pc: usize, z_i: Vec<Fr>) -> (Vec<Fr>, usize) {
+        let z_i1 = match pc {
+            0 => cubic_step_native(z_i),
+            _ => vec![z_i[0] * z_i[0] + Fr::one()],
+        };
+        let pc_i1 = z_i1[0].into_bigint().is_odd() as usize;
+        (z_i1, pc_i1)
+    }
+
+    #[test]
+    fn test_nivc() -> Result<(), Error> {
+        let poseidon_config = poseidon_canonical_config::<Fr>();
+
+        // run the test using Pedersen commitments on both sides of the curve cycle
+        test_nivc_opt::<Pedersen<Projective>, Pedersen<Projective2>, false>(
+            poseidon_config.clone(),
+            5,
+        )?;
+
+        test_nivc_opt::<Pedersen<Projective, true>, Pedersen<Projective2, true>, true>(
+            poseidon_config.clone(),
+            5,
+        )?;
+
+        // run the test using KZG for the commitments on the main curve, and Pedersen for the
+        // commitments on the secondary curve
+        test_nivc_opt::<KZG<Bn254>, Pedersen<Projective2>, false>(poseidon_config, 5)?;
+        Ok(())
+    }
+
+    fn test_nivc_opt<
+        CS1: CommitmentScheme<Projective, H>,
+        CS2: CommitmentScheme<Projective2, H>,
+        const H: bool,
+    >(
+        poseidon_config: PoseidonConfig<Fr>,
+        num_steps: usize,
+    ) -> Result<(), Error> {
+        type N<CS1, CS2, const H: bool> =
+            NIVC<Projective, Projective2, TestNIVCFCircuit<Fr>, CS1, CS2, H>;
+
+        let mut rng = ark_std::test_rng();
+
+        let F = vec![
+            TestNIVCFCircuit::<Fr>::new(0)?,
+            TestNIVCFCircuit::<Fr>::new(1)?,
+        ];
+        let prep_param = PreprocessorParam::new(poseidon_config, F.clone());
+        let params = N::<CS1, CS2, H>::preprocess(&mut rng, &prep_param)?;
+        // the augmented circuits of the two step circuits have different sizes
+        assert_ne!(
+            params.1.r1cs[0].n_constraints(),
+            params.1.r1cs[1].n_constraints()
+        );
+
+        let z_0 = vec![Fr::from(3_u32)];
+        let pc_0 = 1;
+        let mut nivc = N::<CS1, CS2, H>::init(&params, F, z_0.clone(), pc_0)?;
+
+        let (mut z_i, mut pc_i) = (z_0, pc_0);
+        let mut executed = [false; 2];
+        for _ in 0..num_steps {
+            executed[pc_i] = true;
+            nivc.prove_step(&mut rng, ())?;
+            (z_i, pc_i) = test_step_native(pc_i, z_i);
+            assert_eq!(nivc.state(), z_i);
+            assert_eq!(nivc.pc(), pc_i);
+        }
+        assert_eq!(Fr::from(num_steps as u32), nivc.i);
+        // the trace is heterogeneous
+        assert!(executed.iter().all(|e| *e));
+        if H {
+            // the steps beyond i=1 have checked the blinding of the running instances, which is
+            // non-zero for all the ones that have been folded
+            assert!(!nivc.w_i.rW.is_zero());
+            for (j, (U, W)) in nivc.U_i.iter().zip(&nivc.W_i).enumerate() {
+                if *U != CommittedInstance::dummy(&nivc.r1cs[j]) {
+                    assert!(!W.rW.is_zero());
+                }
+            }
+        }
+
+        // serialize the verifier params, and deserialize them recomputing the R1CSs
+        let mut vp_serialized = vec![];
+        params.1.serialize_compressed(&mut vp_serialized)?;
+        let vp_deserialized = N::<CS1, CS2, H>::vp_deserialize_with_mode(
+            &mut vp_serialized.as_slice(),
+            ark_serialize::Compress::Yes,
+            ark_serialize::Validate::Yes,
+            vec![0, 1], // fcircuit_params
+        )?;
+        assert_eq!(vp_deserialized.pp_hash()?, params.1.pp_hash()?);
+
+        let ivc_proof = nivc.ivc_proof();
+
+        // serialize & deserialize the IVCProof
+        let mut ivc_proof_serialized = vec![];
+        ivc_proof.serialize_compressed(&mut ivc_proof_serialized)?;
+        let ivc_proof_deserialized = IVCProof::<Projective, Projective2>::deserialize_compressed(
+            ivc_proof_serialized.as_slice(),
+        )?;
+        assert_eq!(ivc_proof, ivc_proof_deserialized);
+
+        N::<CS1, CS2, H>::verify(vp_deserialized, ivc_proof_deserialized)?;
+
+        // a proof claiming a different initial program counter must not verify
+        let mut wrong_ivc_proof = ivc_proof;
+        wrong_ivc_proof.pc_0 = 1 - pc_0;
+        assert!(N::<CS1, CS2, H>::verify(params.1, wrong_ivc_proof).is_err());
+        Ok(())
+    }
+}
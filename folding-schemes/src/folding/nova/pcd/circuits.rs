@@ -0,0 +1,449 @@
+/// contains the node circuit used by the proof-carrying data (PCD) mode of Nova
+use ark_crypto_primitives::sponge::{
+    constraints::{AbsorbGadget, CryptographicSpongeVar},
+    poseidon::{constraints::PoseidonSpongeVar, PoseidonConfig, PoseidonSponge},
+};
+use ark_ff::PrimeField;
+use ark_r1cs_std::{
+    alloc::{AllocVar, AllocationMode},
+    boolean::Boolean,
+    eq::EqGadget,
+    fields::{fp::FpVar, FieldVar},
+    prelude::CurveVar,
+    R1CSVar,
+};
+use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystemRef, Namespace, SynthesisError};
+use ark_std::{borrow::Borrow, One, Zero};
+
+use super::PCDCycleFoldConfig;
+use crate::folding::circuits::{
+    cyclefold::{
+        CycleFoldChallengeGadget, CycleFoldCommittedInstance, CycleFoldCommittedInstanceVar,
+        CycleFoldConfig, NIFSFullGadget,
+    },
+    nonnative::{affine::NonNativeAffineVar, uint::NonNativeUintVar},
+    CF1,
+};
+use crate::folding::nova::{
+    nifs::{
+        nova_circuits::{CommittedInstanceVar, NIFSGadget},
+        NIFSGadgetTrait,
+    },
+    CommittedInstance, IVCProof,
+};
+use crate::folding::traits::{CommittedInstanceVarOps, Dummy};
+use crate::frontend::FCircuit;
+use crate::transcript::AbsorbNonNativeGadget;
+use crate::Curve;
+
+/// The values of a child proof that are input to the [`NodeCircuit`], ie. the parts of the child's
+/// [`IVCProof`] from which the node circuit recomputes the public inputs of the child's incoming
+/// instance `u_i`, together with the running instances that get folded.
+#[derive(Debug, Clone)]
+pub struct ChildInstance<C1: Curve, C2: Curve> {
+    pub(super) i: CF1<C1>,
+    pub(super) z_0: Vec<CF1<C1>>,
+    pub(super) z_i: Vec<CF1<C1>>,
+    pub(super) U_i: CommittedInstance<C1>,
+    pub(super) u_i_cmW: C1,
+    pub(super) cf_U_i: CycleFoldCommittedInstance<C2>,
+}
+
+impl<C1: Curve, C2: Curve> From<&IVCProof<C1, C2>> for ChildInstance<C1, C2> {
+    fn from(proof: &IVCProof<C1, C2>) -> Self {
+        Self {
+            i: proof.i,
+            z_0: proof.z_0.clone(),
+            z_i: proof.z_i.clone(),
+            U_i: proof.U_i.clone(),
+            u_i_cmW: proof.u_i.cmW,
+            cf_U_i: proof.cf_U_i.clone(),
+        }
+    }
+}
+
+/// The dummy child of a leaf, where `i = 0`. The parameter is the state length.
+impl<C1: Curve, C2: Curve> Dummy<usize> for ChildInstance<C1, C2> {
+    fn dummy(state_len: usize) -> Self {
+        Self {
+            i: CF1::<C1>::zero(),
+            z_0: vec![CF1::<C1>::zero(); state_len],
+            z_i: vec![CF1::<C1>::zero(); state_len],
+            U_i: CommittedInstance::dummy(2),
+            u_i_cmW: C1::zero(),
+            cf_U_i: CycleFoldCommittedInstance::dummy(PCDCycleFoldConfig::<C1>::IO_LEN),
+        }
+    }
+}
+
+/// In-circuit representation of a [`ChildInstance`].
+#[derive(Debug, Clone)]
+pub struct ChildInstanceVar<C1: Curve, C2: Curve> {
+    pub i: FpVar<CF1<C1>>,
+    pub z_0: Vec<FpVar<CF1<C1>>>,
+    pub z_i: Vec<FpVar<CF1<C1>>>,
+    pub U_i: CommittedInstanceVar<C1>,
+    pub u_i_cmW: NonNativeAffineVar<C1>,
+    pub cf_U_i: CycleFoldCommittedInstanceVar<C2>,
+}
+
+impl<C1, C2> AllocVar<ChildInstance<C1, C2>, CF1<C1>> for ChildInstanceVar<C1, C2>
+where
+    C1: Curve<BaseField = C2::ScalarField, ScalarField = C2::BaseField>,
+    C2: Curve,
+{
+    fn new_variable<T: Borrow<ChildInstance<C1, C2>>>(
+        cs: impl Into<Namespace<CF1<C1>>>,
+        f: impl FnOnce() -> Result<T, SynthesisError>,
+        mode: AllocationMode,
+    ) -> Result<Self, SynthesisError> {
+        f().and_then(|val| {
+            let cs = cs.into();
+            let val = val.borrow();
+
+            Ok(Self {
+                i: FpVar::new_variable(cs.clone(), || Ok(val.i), mode)?,
+                z_0: Vec::new_variable(cs.clone(), || Ok(val.z_0.clone()), mode)?,
+                z_i: Vec::new_variable(cs.clone(), || Ok(val.z_i.clone()), mode)?,
+                U_i: CommittedInstanceVar::new_variable(cs.clone(), || Ok(val.U_i.clone()), mode)?,
+                u_i_cmW: NonNativeAffineVar::new_variable(cs.clone(), || Ok(val.u_i_cmW), mode)?,
+                cf_U_i: CycleFoldCommittedInstanceVar::new_variable(
+                    cs.clone(),
+                    || Ok(val.cf_U_i.clone()),
+                    mode,
+                )?,
+            })
+        })
+    }
+}
+
+/// `NodeCircuit` is the PCD counterpart of [`crate::folding::nova::circuits::AugmentedFCircuit`].
+///
+/// Instead of a single running instance, it takes as input two child proofs (left and right), which
+/// attest the execution of the segments `z0_L -> zi_L` and `z0_R -> zi_R` of the computation, and
+/// it:
+/// - recomputes the public inputs of the incoming instances `u_L` and `u_R` of the children;
+/// - folds `u_L` into `U_L` and `u_R` into `U_R`, and then the two resulting running instances
+///   together into `U_{i+1}`;
+/// - merges the CycleFold running instances `cf_U_L` and `cf_U_R`, and then folds into the result
+///   the six CycleFold instances that attest the commitments of the three folds above;
+/// - enforces that the segments are contiguous, ie. `zi_L == z0_R`, and applies `F` to `zi_R`.
+///
+/// The output state is then `H(i_L + i_R + 1, z0_L, F(zi_R), U_{i+1})`.
+///
+/// A leaf node does not have children (which is detected by `i_L == 0`), in which case it applies
+/// `F` to its own initial state `z_0` and outputs `H(1, z_0, F(z_0), U_⊥)`, as Nova's base case.
+#[derive(Debug, Clone)]
+pub struct NodeCircuit<C1: Curve, C2: Curve, FC: FCircuit<CF1<C1>>> {
+    pub(super) poseidon_config: PoseidonConfig<CF1<C1>>,
+    pub(super) pp_hash: Option<CF1<C1>>,
+    pub(super) i_usize: Option<usize>,
+    pub(super) z_0: Option<Vec<CF1<C1>>>, // initial state of a leaf
+    pub(super) external_inputs: Option<FC::ExternalInputs>,
+    pub(super) children: [Option<ChildInstance<C1, C2>>; 2],
+    // commitments of the folded instances U_L', U_R' and U_{i+1}, and the cross terms of each of
+    // the three folds
+    pub(super) folded_cmE: Option<[C1; 3]>,
+    pub(super) folded_cmW: Option<[C1; 3]>,
+    pub(super) cmT: Option<[C1; 3]>,
+    pub(super) F: FC, // F circuit
+
+    // cyclefold verifier on C1
+    pub(super) cf_merge_cmT: Option<C2>,
+    // Here the CycleFold instances correspond to the fold of cmW and cmE (in this order) of each
+    // of the three folds above
+    pub(super) cf_u_cmW: Option<[C2; 6]>, // input
+    pub(super) cf_cmT: Option<[C2; 6]>,
+}
+
+impl<C1: Curve, C2: Curve, FC: FCircuit<CF1<C1>>> NodeCircuit<C1, C2, FC> {
+    pub fn empty(poseidon_config: &PoseidonConfig<CF1<C1>>, F_circuit: FC) -> Self {
+        Self {
+            poseidon_config: poseidon_config.clone(),
+            pp_hash: None,
+            i_usize: None,
+            z_0: None,
+            external_inputs: None,
+            children: [None, None],
+            folded_cmE: None,
+            folded_cmW: None,
+            cmT: None,
+            F: F_circuit,
+            // cyclefold values
+            cf_merge_cmT: None,
+            cf_u_cmW: None,
+            cf_cmT: None,
+        }
+    }
+}
+
+impl<C1, C2, FC> NodeCircuit<C1, C2, FC>
+where
+    C1: Curve<BaseField = C2::ScalarField, ScalarField = C2::BaseField>,
+    C2: Curve,
+    FC: FCircuit<CF1<C1>>,
+{
+    pub fn compute_next_state(
+        self,
+        cs: ConstraintSystemRef<CF1<C1>>,
+    ) -> Result<Vec<FpVar<CF1<C1>>>, SynthesisError> {
+        let state_len = self.F.state_len();
+        let pp_hash = FpVar::<CF1<C1>>::new_witness(cs.clone(), || {
+            Ok(self.pp_hash.unwrap_or_else(CF1::<C1>::zero))
+        })?;
+        let z_0 = Vec::<FpVar<CF1<C1>>>::new_witness(cs.clone(), || {
+            Ok(self.z_0.unwrap_or(vec![CF1::<C1>::zero(); state_len]))
+        })?;
+        let external_inputs = FC::ExternalInputsVar::new_witness(cs.clone(), || {
+            Ok(self.external_inputs.unwrap_or_default())
+        })?;
+
+        let [left, right] = self.children.map(|child| {
+            ChildInstanceVar::<C1, C2>::new_witness(cs.clone(), || {
+                Ok(child.unwrap_or_else(|| ChildInstance::dummy(state_len)))
+            })
+        });
+        let (left, right) = (left?, right?);
+
+        let folded_cmE = Vec::<NonNativeAffineVar<C1>>::new_witness(cs.clone(), || {
+            Ok(self.folded_cmE.unwrap_or([C1::zero(); 3]).to_vec())
+        })?;
+        let folded_cmW = Vec::<NonNativeAffineVar<C1>>::new_witness(cs.clone(), || {
+            Ok(self.folded_cmW.unwrap_or([C1::zero(); 3]).to_vec())
+        })?;
+        let cmT = Vec::<NonNativeAffineVar<C1>>::new_witness(cs.clone(), || {
+            Ok(self.cmT.unwrap_or([C1::zero(); 3]).to_vec())
+        })?;
+
+        let cf_merge_cmT = C2::Var::new_witness(cs.clone(), || {
+            Ok(self.cf_merge_cmT.unwrap_or_else(C2::zero))
+        })?;
+        let cf_u_cmW = Vec::<C2::Var>::new_witness(cs.clone(), || {
+            Ok(self.cf_u_cmW.unwrap_or([C2::zero(); 6]).to_vec())
+        })?;
+        let cf_cmT = Vec::<C2::Var>::new_witness(cs.clone(), || {
+            Ok(self.cf_cmT.unwrap_or([C2::zero(); 6]).to_vec())
+        })?;
+
+        // `sponge` is for digest computation.
+        let sponge = PoseidonSpongeVar::<C1::ScalarField>::new(cs.clone(), &self.poseidon_config);
+        // `transcript` is for challenge generation.
+        let mut transcript = sponge.clone();
+
+        // a leaf has dummy children, whose number of steps is zero
+        let is_leaf = left.i.is_zero()?;
+
+        // Primary Part
+        // P.1. For each child, compute u.x and construct u, and fold it into the child's running
+        // instance U.
+        // Each element of `folds` is `(U, u, U', r_bits)`, where `U'` is the result of folding
+        // `u` into `U`.
+        let mut folds = Vec::with_capacity(3);
+        let mut cf_U_vecs = Vec::with_capacity(2);
+        for (k, child) in [&left, &right].into_iter().enumerate() {
+            // u.x[0] = H(i, z_0, z_i, U)
+            let (u_x, U_vec) = child
+                .U_i
+                .hash(&sponge, &pp_hash, &child.i, &child.z_0, &child.z_i)?;
+            // u.x[1] = H(cf_U)
+            let (cf_u_x, cf_U_vec) = child.cf_U_i.hash(&sponge, pp_hash.clone())?;
+            cf_U_vecs.push(cf_U_vec);
+
+            let u = CommittedInstanceVar {
+                // u.cmE = cm(0)
+                cmE: NonNativeAffineVar::new_constant(cs.clone(), C1::zero())?,
+                // u.u = 1
+                u: FpVar::one(),
+                // u.cmW is provided by the prover as witness
+                cmW: child.u_i_cmW.clone(),
+                // u.x is computed above
+                x: vec![u_x, cf_u_x],
+            };
+
+            // nifs.verify, obtains U' by folding u & U. As in Nova, the commitments of U' are
+            // unconstrained witnesses, whose correctness is checked on the other curve.
+            let (mut U1, r_bits) = NIFSGadget::<
+                C1,
+                PoseidonSponge<C1::ScalarField>,
+                PoseidonSpongeVar<C1::ScalarField>,
+            >::verify(
+                &mut transcript,
+                pp_hash.clone(),
+                child.U_i.clone(),
+                U_vec,
+                u.clone(),
+                Some(cmT[k].clone()),
+            )?;
+            U1.cmE = folded_cmE[k].clone();
+            U1.cmW = folded_cmW[k].clone();
+            folds.push((child.U_i.clone(), u, U1, r_bits));
+        }
+
+        // P.2. Fold the two running instances U_L' and U_R' into U_{i+1}.
+        let (U_L1, U_R1) = (folds[0].2.clone(), folds[1].2.clone());
+        let (mut U_i1, r_bits) = NIFSGadget::<
+            C1,
+            PoseidonSponge<C1::ScalarField>,
+            PoseidonSpongeVar<C1::ScalarField>,
+        >::verify(
+            &mut transcript,
+            pp_hash.clone(),
+            U_L1.clone(),
+            U_L1.to_sponge_field_elements()?,
+            U_R1.clone(),
+            Some(cmT[2].clone()),
+        )?;
+        U_i1.cmE = folded_cmE[2].clone();
+        U_i1.cmW = folded_cmW[2].clone();
+        folds.push((U_L1, U_R1, U_i1.clone(), r_bits));
+
+        // P.3. Compute the state of the node.
+        // The segments of the children must be contiguous. This is not enforced for a leaf, whose
+        // children are dummy.
+        left.z_i
+            .conditional_enforce_equal(&right.z_0, &!is_leaf.clone())?;
+        // The node covers the segment z0_L -> zi_R plus one step of F. For a leaf, both ends are
+        // replaced by the leaf's initial state z_0.
+        let z_start = z_0
+            .iter()
+            .zip(&left.z_0)
+            .map(|(a, b)| is_leaf.select(a, b))
+            .collect::<Result<Vec<_>, _>>()?;
+        let z_in = z_0
+            .iter()
+            .zip(&right.z_i)
+            .map(|(a, b)| is_leaf.select(a, b))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // P.4.a compute and check the first output of the node circuit
+
+        // get z_{i+1} from the F circuit
+        let i_usize = self.i_usize.unwrap_or(0);
+        let z_i1 = self
+            .F
+            .generate_step_constraints(cs.clone(), i_usize, z_in, external_inputs)?;
+
+        // Leaf: u_{i+1}.x[0] == H((1, z_0, z_{i+1}, U_{\bot})
+        // Non-leaf: u_{i+1}.x[0] == H((i_L + i_R + 1, z0_L, z_{i+1}, U_{i+1})
+        let u_dummy = CommittedInstance::dummy(2);
+        let (u_i1_x, _) = U_i1.hash(
+            &sponge,
+            &pp_hash,
+            &(&left.i + &right.i + FpVar::<CF1<C1>>::one()),
+            &z_start,
+            &z_i1,
+        )?;
+        let (u_i1_x_leaf, _) = CommittedInstanceVar::new_constant(cs.clone(), u_dummy)?.hash(
+            &sponge,
+            &pp_hash,
+            &FpVar::<CF1<C1>>::one(),
+            &z_start,
+            &z_i1,
+        )?;
+        let x = is_leaf.select(&u_i1_x_leaf, &u_i1_x)?;
+        // This line "converts" `x` from a witness to a public input, see the
+        // comment in the uniform `AugmentedFCircuit` for the rationale.
+        FpVar::new_input(cs.clone(), || x.value())?.enforce_equal(&x)?;
+
+        // CycleFold part
+        // C.1. Compute the public inputs of the CycleFold instances. For each of the three folds,
+        // the cmW one computes U'.cmW = U.cmW + r * u.cmW (+ r^2 * 0), and the cmE one computes
+        // U'.cmE = U.cmE + r * cmT + r^2 * u.cmE, where u.cmE is zero for the fresh instances of
+        // the children, but not for U_R'.
+        let zero = NonNativeAffineVar::new_constant(cs.clone(), C1::zero())?;
+        let mut cf_x = Vec::with_capacity(6);
+        for (k, (U, u, U1, r_bits)) in folds.into_iter().enumerate() {
+            // convert r_bits to a `NonNativeFieldVar`
+            let r_nonnat = {
+                let mut bits = r_bits;
+                bits.resize(C1::BaseField::MODULUS_BIT_SIZE as usize, Boolean::FALSE);
+                NonNativeUintVar::from(&bits)
+            };
+            cf_x.push(vec![
+                r_nonnat.clone(),
+                U.cmW.x,
+                U.cmW.y,
+                u.cmW.x,
+                u.cmW.y,
+                zero.x.clone(),
+                zero.y.clone(),
+                U1.cmW.x,
+                U1.cmW.y,
+            ]);
+            cf_x.push(vec![
+                r_nonnat,
+                U.cmE.x,
+                U.cmE.y,
+                cmT[k].x.clone(),
+                cmT[k].y.clone(),
+                u.cmE.x,
+                u.cmE.y,
+                U1.cmE.x,
+                U1.cmE.y,
+            ]);
+        }
+
+        // C.2. Merge the CycleFold running instances of the children, cf_U_L and cf_U_R.
+        let cf_merge_r_bits = CycleFoldChallengeGadget::<C2>::get_challenge_gadget(
+            &mut transcript,
+            pp_hash.clone(),
+            cf_U_vecs[0].clone(),
+            right.cf_U_i.clone(),
+            cf_merge_cmT.clone(),
+        )?;
+        let mut cf_U_i1 = NIFSFullGadget::<C2>::fold_running_instances(
+            cf_merge_r_bits,
+            cf_merge_cmT,
+            left.cf_U_i,
+            right.cf_U_i,
+        )?;
+
+        // C.3. Construct the CycleFold instances and fold them one by one into cf_U_{i+1}.
+        for ((x, cmW), cf_cmT_j) in cf_x.into_iter().zip(cf_u_cmW).zip(cf_cmT) {
+            let cf_u = CycleFoldCommittedInstanceVar {
+                // cf_u.cmE = 0
+                cmE: C2::Var::zero(),
+                // cf_u.u = 1
+                u: NonNativeUintVar::new_constant(cs.clone(), C1::BaseField::one())?,
+                // cf_u.cmW is provided by the prover as witness
+                cmW,
+                // cf_u.x is computed in step C.1
+                x,
+            };
+            let cf_r_bits = CycleFoldChallengeGadget::<C2>::get_challenge_gadget(
+                &mut transcript,
+                pp_hash.clone(),
+                cf_U_i1.to_native_sponge_field_elements()?,
+                cf_u.clone(),
+                cf_cmT_j.clone(),
+            )?;
+            cf_U_i1 =
+                NIFSFullGadget::<C2>::fold_committed_instance(cf_r_bits, cf_cmT_j, cf_U_i1, cf_u)?;
+        }
+
+        // Back to Primary Part
+        // P.4.b compute and check the second output of the node circuit
+        // Leaf: u_{i+1}.x[1] == H(cf_U_{\bot})
+        // Non-leaf: u_{i+1}.x[1] == H(cf_U_{i+1})
+        let cf_u_dummy = CycleFoldCommittedInstance::dummy(PCDCycleFoldConfig::<C1>::IO_LEN);
+        let (cf_u_i1_x, _) = cf_U_i1.hash(&sponge, pp_hash.clone())?;
+        let (cf_u_i1_x_leaf, _) =
+            CycleFoldCommittedInstanceVar::<C2>::new_constant(cs.clone(), cf_u_dummy)?
+                .hash(&sponge, pp_hash)?;
+        let cf_x = is_leaf.select(&cf_u_i1_x_leaf, &cf_u_i1_x)?;
+        FpVar::new_input(cs.clone(), || cf_x.value())?.enforce_equal(&cf_x)?;
+
+        Ok(z_i1)
+    }
+}
+
+impl<C1, C2, FC> ConstraintSynthesizer<CF1<C1>> for NodeCircuit<C1, C2, FC>
+where
+    C1: Curve<BaseField = C2::ScalarField, ScalarField = C2::BaseField>,
+    C2: Curve,
+    FC: FCircuit<CF1<C1>>,
+{
+    fn generate_constraints(self, cs: ConstraintSystemRef<CF1<C1>>) -> Result<(), SynthesisError> {
+        self.compute_next_state(cs).map(|_| ())
+    }
+}
@@ -0,0 +1,742 @@
+/// Implements proof-carrying data (PCD) on top of Nova+CycleFold, ie. tree-shaped folding, where
+/// the proofs of two contiguous segments of a computation are merged into a single proof.
+///
+/// The computation `z_0 -> F(z_0) -> F(F(z_0)) -> ...` is split in segments that can be proven in
+/// parallel (eg. in different machines), and the proofs are then aggregated following a binary
+/// tree:
+/// - a leaf proves a single step of `F` from a given state `z_0`, see [`PCD::prove_leaf`];
+/// - an internal node takes the proofs of two contiguous segments `z0_L -> zi_L` and
+///   `zi_L -> zi_R`, folds their running instances (and their CycleFold running instances)
+///   together, and applies one more step of `F`, resulting in a proof of the segment
+///   `z0_L -> F(zi_R)`, see [`PCD::prove_node`].
+///
+/// The proofs are [`PCDProof`]s, which wrap an [`IVCProof`] of the [`circuits::NodeCircuit`]
+/// whose `i` is the number of steps of `F` of the segment (ie. the number of nodes of the tree),
+/// and are verified with [`PCD::verify`]. The scope of this module is limited to:
+/// - binary trees, where each node has exactly two children;
+/// - children that are contiguous segments of the same computation, ie. the node circuit enforces
+///   `left.z_i == right.z_0`, so proofs of independent computations can not be merged;
+/// - proofs generated by [`PCD::prove_leaf`] and [`PCD::prove_node`]: the proofs of Nova's linear
+///   IVC are proofs of Nova's augmented circuit, which differs from the node circuit, so they can
+///   not be used as children.
+///
+/// There is no decider for PCD proofs yet: the Nova deciders are built for the R1CS and the
+/// CycleFold circuit of Nova's augmented circuit, which differ from the ones of the node circuit.
+use ark_crypto_primitives::sponge::{
+    poseidon::{PoseidonConfig, PoseidonSponge},
+    CryptographicSponge,
+};
+use ark_ff::{BigInteger, PrimeField};
+use ark_r1cs_std::R1CSVar;
+use ark_relations::r1cs::ConstraintSystem;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::{marker::PhantomData, rand::RngCore, Zero};
+use num_bigint::BigUint;
+
+use super::{
+    get_r1cs_from_cs,
+    nifs::{nova::NIFS, NIFSTrait},
+    CommittedInstance, IVCProof, PreprocessorParam, ProverParams, VerifierParams, Witness,
+};
+use crate::arith::{
+    r1cs::{extract_w_x, R1CS},
+    Arith, ArithRelation,
+};
+use crate::commitment::CommitmentScheme;
+use crate::constants::NOVA_N_BITS_RO;
+use crate::folding::circuits::{
+    cyclefold::{
+        fold_cyclefold_circuit, CycleFoldChallengeGadget, CycleFoldCircuit,
+        CycleFoldCommittedInstance, CycleFoldConfig, CycleFoldWitness,
+    },
+    CF1,
+};
+use crate::folding::traits::CommittedInstanceOps;
+use crate::frontend::FCircuit;
+use crate::transcript::poseidon::poseidon_canonical_config;
+use crate::{Curve, Error};
+
+pub mod circuits;
+
+use circuits::{ChildInstance, NodeCircuit};
+
+/// Configuration for the CycleFold circuit of the PCD node circuit
+pub struct PCDCycleFoldConfig<C: Curve> {
+    _c: PhantomData<C>,
+}
+
+impl<C: Curve> CycleFoldConfig for PCDCycleFoldConfig<C> {
+    const RANDOMNESS_BIT_LENGTH: usize = NOVA_N_BITS_RO;
+    // Number of points to be folded in the CycleFold circuit. Folding two running instances
+    // requires 3 points for the cmE (U.cmE + r * cmT + r^2 * U'.cmE), and all the CycleFold
+    // instances folded into the same running instance need to share the same circuit.
+    const N_INPUT_POINTS: usize = 3;
+    type C = C;
+}
+
+/// CycleFold circuit for computing random linear combinations of group elements
+/// in the PCD node circuit.
+pub type PCDCycleFoldCircuit<C> = CycleFoldCircuit<PCDCycleFoldConfig<C>>;
+
+/// Proof of a segment of the computation, returned by [`PCD::prove_leaf`] and [`PCD::prove_node`].
+#[derive(PartialEq, Eq, Debug, Clone, CanonicalSerialize, CanonicalDeserialize)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(bound = "")
+)]
+pub struct PCDProof<C1, C2>
+where
+    C1: Curve,
+    C2: Curve,
+{
+    /// Index of the first step of the segment in the whole computation. It is only used by the
+    /// prover to compute the index of the steps of `F`, and it is not bound by the proof, in the
+    /// same way as the `i` given to `F` in Nova's augmented circuit.
+    pub start: usize,
+    /// Proof of the node circuit, whose `i` is the number of steps of the segment
+    pub ivc_proof: IVCProof<C1, C2>,
+}
+
+/// Implements proof-carrying data (PCD) for Nova+CycleFold, where proofs of contiguous segments
+/// of the computation are merged following a binary tree.
+/// The `H` const generic specifies whether the homorphic commitment scheme is blinding
+#[derive(Clone, Debug)]
+pub struct PCD<C1, C2, FC, CS1, CS2, const H: bool = false>
+where
+    C1: Curve,
+    C2: Curve,
+    FC: FCircuit<C1::ScalarField>,
+    CS1: CommitmentScheme<C1, H>,
+    CS2: CommitmentScheme<C2, H>,
+{
+    /// R1CS of the node circuit
+    pub r1cs: R1CS<C1::ScalarField>,
+    /// R1CS of the CycleFold circuit
+    pub cf_r1cs: R1CS<C2::ScalarField>,
+    pub poseidon_config: PoseidonConfig<C1::ScalarField>,
+    /// CommitmentScheme::ProverParams over C1
+    pub cs_pp: CS1::ProverParams,
+    /// CycleFold CommitmentScheme::ProverParams, over C2
+    pub cf_cs_pp: CS2::ProverParams,
+    /// F circuit, the circuit that is being folded
+    pub F: FC,
+    /// public params hash
+    pub pp_hash: C1::ScalarField,
+}
+
+impl<C1, C2, FC, CS1, CS2, const H: bool> PCD<C1, C2, FC, CS1, CS2, H>
+where
+    C1: Curve<BaseField = C2::ScalarField, ScalarField = C2::BaseField>,
+    C2: Curve,
+    FC: FCircuit<C1::ScalarField>,
+    CS1: CommitmentScheme<C1, H>,
+    CS2: CommitmentScheme<C2, H>,
+{
+    /// Deserializes the [`VerifierParams`], recomputing the R1CS of the node circuit and of the
+    /// CycleFold circuit from the given step circuit's params.
+    pub fn vp_deserialize_with_mode<R: std::io::prelude::Read>(
+        mut reader: R,
+        compress: ark_serialize::Compress,
+        validate: ark_serialize::Validate,
+        fc_params: FC::Params,
+    ) -> Result<VerifierParams<C1, C2, CS1, CS2, H>, Error> {
+        let poseidon_config = poseidon_canonical_config::<C1::ScalarField>();
+
+        let (r1cs, cf_r1cs) = get_r1cs::<C1, C2, FC>(&poseidon_config, FC::new(fc_params)?)?;
+
+        let cs_vp = CS1::VerifierParams::deserialize_with_mode(&mut reader, compress, validate)?;
+        let cf_cs_vp = CS2::VerifierParams::deserialize_with_mode(&mut reader, compress, validate)?;
+
+        Ok(VerifierParams {
+            poseidon_config,
+            r1cs,
+            cf_r1cs,
+            cs_vp,
+            cf_cs_vp,
+        })
+    }
+
+    #[allow(clippy::type_complexity)]
+    pub fn preprocess(
+        mut rng: impl RngCore,
+        prep_param: &PreprocessorParam<C1, C2, FC, CS1, CS2, H>,
+    ) -> Result<
+        (
+            ProverParams<C1, C2, CS1, CS2, H>,
+            VerifierParams<C1, C2, CS1, CS2, H>,
+        ),
+        Error,
+    > {
+        let (r1cs, cf_r1cs) =
+            get_r1cs::<C1, C2, FC>(&prep_param.sponge_config, prep_param.F.clone())?;
+
+        // if cs params exist, use them, if not, generate new ones
+        let (cs_pp, cs_vp) = match (&prep_param.cs_pp, &prep_param.cs_vp) {
+            (Some(cs_pp), Some(cs_vp)) => (cs_pp.clone(), cs_vp.clone()),
+            _ => CS1::setup(&mut rng, r1cs.n_constraints().max(r1cs.n_witnesses()))?,
+        };
+        let (cf_cs_pp, cf_cs_vp) = match (&prep_param.cf_cs_pp, &prep_param.cf_cs_vp) {
+            (Some(cf_cs_pp), Some(cf_cs_vp)) => (cf_cs_pp.clone(), cf_cs_vp.clone()),
+            _ => CS2::setup(&mut rng, cf_r1cs.n_constraints().max(cf_r1cs.n_witnesses()))?,
+        };
+        let (cs_pp, cf_cs_pp) = prep_param.attach_fixed_base_tables(cs_pp, cf_cs_pp)?;
+
+        let prover_params = ProverParams::<C1, C2, CS1, CS2, H> {
+            sponge_config: prep_param.sponge_config.clone(),
+            cs_pp,
+            cf_cs_pp,
+        };
+        let verifier_params = VerifierParams::<C1, C2, CS1, CS2, H> {
+            sponge_config: prep_param.sponge_config.clone(),
+            r1cs,
+            cf_r1cs,
+            cs_vp,
+            cf_cs_vp,
+        };
+
+        Ok((prover_params, verifier_params))
+    }
+
+    /// Initializes the PCD prover for the given parameters and step circuit. The same prover can
+    /// be used (or cloned) to prove any of the leaves and nodes of the tree.
+    pub fn init(
+        params: &(
+            ProverParams<C1, C2, CS1, CS2, H>,
+            VerifierParams<C1, C2, CS1, CS2, H>,
+        ),
+        F: FC,
+    ) -> Result<Self, Error> {
+        let (pp, vp) = params;
+
+        Ok(Self {
+            r1cs: vp.r1cs.clone(),
+            cf_r1cs: vp.cf_r1cs.clone(),
+            poseidon_config: pp.sponge_config.clone(),
+            cs_pp: pp.cs_pp.clone(),
+            cf_cs_pp: pp.cf_cs_pp.clone(),
+            F,
+            pp_hash: vp.pp_hash()?,
+        })
+    }
+
+    /// Proves a leaf of the tree, ie. the single step `step` of the computation (which is the `i`
+    /// passed to `F`) from the state `z_0`. As in Nova's base case, the running instances of the
+    /// resulting proof are the dummy ones.
+    pub fn prove_leaf(
+        &self,
+        mut rng: impl RngCore,
+        step: usize,
+        z_0: Vec<C1::ScalarField>,
+        external_inputs: FC::ExternalInputs,
+    ) -> Result<PCDProof<C1, C2>, Error> {
+        if z_0.len() != self.F.state_len() {
+            return Err(Error::NotSameLength(
+                "z_0.len()".to_string(),
+                z_0.len(),
+                "F.state_len()".to_string(),
+                self.F.state_len(),
+            ));
+        }
+
+        // The children of a leaf are dummy, and the values of the folding are left unassigned,
+        // since the outputs of the node circuit do not depend on them for a leaf.
+        let mut node_circuit =
+            NodeCircuit::<C1, C2, FC>::empty(&self.poseidon_config, self.F.clone());
+        node_circuit.pp_hash = Some(self.pp_hash);
+        node_circuit.i_usize = Some(step);
+        node_circuit.z_0 = Some(z_0.clone());
+        node_circuit.external_inputs = Some(external_inputs);
+
+        let (W_dummy, U_dummy) = self.r1cs.dummy_witness_instance();
+        let (cf_W_dummy, cf_U_dummy) = self.cf_r1cs.dummy_witness_instance();
+        let ivc_proof = self.prove_node_circuit(
+            &mut rng,
+            node_circuit,
+            C1::ScalarField::from(1u64),
+            z_0,
+            (W_dummy, U_dummy),
+            (cf_W_dummy, cf_U_dummy),
+        )?;
+        Ok(PCDProof {
+            start: step,
+            ivc_proof,
+        })
+    }
+
+    /// Proves an internal node of the tree, which merges the proofs `left` and `right` of two
+    /// contiguous segments of the computation (ie. `left.z_i == right.z_0`, and `right` starts
+    /// at the step that follows the last step of `left`) and applies one more step of `F`.
+    ///
+    /// The `i` passed to `F` is the index of the step of the node in the whole computation, ie.
+    /// `left.start + left.i + right.i`.
+    pub fn prove_node(
+        &self,
+        mut rng: impl RngCore,
+        left: &PCDProof<C1, C2>,
+        right: &PCDProof<C1, C2>,
+        external_inputs: FC::ExternalInputs,
+    ) -> Result<PCDProof<C1, C2>, Error> {
+        let (start, right_start) = (left.start, right.start);
+        let (left, right) = (&left.ivc_proof, &right.ivc_proof);
+        if left.i.is_zero() || right.i.is_zero() {
+            return Err(Error::CantBeZero(
+                "number of steps of a child proof".to_string(),
+            ));
+        }
+        if left.z_i != right.z_0 {
+            return Err(Error::PCDStateMismatch);
+        }
+        // the node circuit assumes that the incoming instances of the children are fresh
+        left.u_i.check_incoming()?;
+        right.u_i.check_incoming()?;
+
+        let left_steps: usize = BigUint::from(left.i)
+            .try_into()
+            .map_err(|_| Error::MaxStep)?;
+        let right_steps: usize = BigUint::from(right.i)
+            .try_into()
+            .map_err(|_| Error::MaxStep)?;
+        if start.checked_add(left_steps) != Some(right_start) {
+            return Err(Error::PCDStateMismatch);
+        }
+        let i_usize = right_start.checked_add(right_steps).ok_or(Error::MaxStep)?;
+
+        // `sponge` is for digest computation.
+        let sponge = PoseidonSponge::<C1::ScalarField>::new(&self.poseidon_config);
+        // `transcript` is for challenge generation.
+        let mut transcript = sponge.clone();
+
+        // fold the incoming instance of each child into its running instance
+        let (W_L1, U_L1, cmT_L, r_bits_L) =
+            NIFS::<C1, CS1, PoseidonSponge<C1::ScalarField>, H>::prove(
+                &self.cs_pp,
+                &self.r1cs,
+                &mut transcript,
+                self.pp_hash,
+                &left.W_i,
+                &left.U_i,
+                &left.w_i,
+                &left.u_i,
+            )?;
+        let (W_R1, U_R1, cmT_R, r_bits_R) =
+            NIFS::<C1, CS1, PoseidonSponge<C1::ScalarField>, H>::prove(
+                &self.cs_pp,
+                &self.r1cs,
+                &mut transcript,
+                self.pp_hash,
+                &right.W_i,
+                &right.U_i,
+                &right.w_i,
+                &right.u_i,
+            )?;
+        // fold the two resulting running instances together
+        let (W_i1, U_i1, cmT_LR, r_bits_LR) =
+            NIFS::<C1, CS1, PoseidonSponge<C1::ScalarField>, H>::prove(
+                &self.cs_pp,
+                &self.r1cs,
+                &mut transcript,
+                self.pp_hash,
+                &W_L1,
+                &U_L1,
+                &W_R1,
+                &U_R1,
+            )?;
+
+        // CycleFold part:
+        // merge the CycleFold running instances of the children
+        let (cf_T, cf_merge_cmT) =
+            NIFS::<C2, CS2, PoseidonSponge<C2::ScalarField>, H>::compute_cyclefold_cmT(
+                &self.cf_cs_pp,
+                &self.cf_r1cs,
+                &left.cf_W_i,
+                &left.cf_U_i,
+                &right.cf_W_i,
+                &right.cf_U_i,
+            )?;
+        let cf_r_bits = CycleFoldChallengeGadget::get_challenge_native(
+            &mut transcript,
+            self.pp_hash,
+            left.cf_U_i.clone(),
+            right.cf_U_i.clone(),
+            cf_merge_cmT,
+        );
+        let cf_r = CF1::<C2>::from_bigint(BigInteger::from_bits_le(&cf_r_bits))
+            .ok_or(Error::OutOfBounds)?;
+        let mut cf_W_i1 = NIFS::<C2, CS2, PoseidonSponge<C2::ScalarField>, H>::fold_witness(
+            cf_r,
+            &left.cf_W_i,
+            &right.cf_W_i,
+            &cf_T,
+        )?;
+        let mut cf_U_i1 =
+            NIFS::<C2, CS2, PoseidonSponge<C2::ScalarField>, H>::fold_committed_instances(
+                cf_r,
+                &left.cf_U_i,
+                &right.cf_U_i,
+                &cf_merge_cmT,
+            );
+
+        // fold the CycleFold circuits of the cmW and cmE of each of the three folds above, in the
+        // same order as in the node circuit
+        let zero = C1::zero();
+        let cf_circuits = [
+            (
+                r_bits_L,
+                [left.U_i.cmW, left.u_i.cmW, zero],
+                [left.U_i.cmE, cmT_L, left.u_i.cmE],
+            ),
+            (
+                r_bits_R,
+                [right.U_i.cmW, right.u_i.cmW, zero],
+                [right.U_i.cmE, cmT_R, right.u_i.cmE],
+            ),
+            (
+                r_bits_LR,
+                [U_L1.cmW, U_R1.cmW, zero],
+                [U_L1.cmE, cmT_LR, U_R1.cmE],
+            ),
+        ]
+        .into_iter()
+        .flat_map(|(r_bits, points_W, points_E)| {
+            [
+                PCDCycleFoldCircuit::<C1> {
+                    r_bits: Some(r_bits.clone()),
+                    points: Some(points_W.to_vec()),
+                },
+                PCDCycleFoldCircuit::<C1> {
+                    r_bits: Some(r_bits),
+                    points: Some(points_E.to_vec()),
+                },
+            ]
+        });
+        let mut cf_u_cmW = [C2::zero(); 6];
+        let mut cf_cmT = [C2::zero(); 6];
+        for (j, cf_circuit) in cf_circuits.enumerate() {
+            let (cf_u, W, U, cmT) = fold_cyclefold_circuit::<PCDCycleFoldConfig<C1>, C2, CS2, H>(
+                &mut transcript,
+                self.cf_r1cs.clone(),
+                self.cf_cs_pp.clone(),
+                self.pp_hash,
+                cf_W_i1,
+                cf_U_i1,
+                cf_circuit,
+                &mut rng,
+            )?;
+            cf_u_cmW[j] = cf_u.cmW;
+            cf_cmT[j] = cmT;
+            cf_W_i1 = W;
+            cf_U_i1 = U;
+        }
+
+        let mut node_circuit =
+            NodeCircuit::<C1, C2, FC>::empty(&self.poseidon_config, self.F.clone());
+        node_circuit.pp_hash = Some(self.pp_hash);
+        node_circuit.i_usize = Some(i_usize);
+        node_circuit.external_inputs = Some(external_inputs);
+        node_circuit.children = [
+            Some(ChildInstance::from(left)),
+            Some(ChildInstance::from(right)),
+        ];
+        node_circuit.folded_cmE = Some([U_L1.cmE, U_R1.cmE, U_i1.cmE]);
+        node_circuit.folded_cmW = Some([U_L1.cmW, U_R1.cmW, U_i1.cmW]);
+        node_circuit.cmT = Some([cmT_L, cmT_R, cmT_LR]);
+        node_circuit.cf_merge_cmT = Some(cf_merge_cmT);
+        node_circuit.cf_u_cmW = Some(cf_u_cmW);
+        node_circuit.cf_cmT = Some(cf_cmT);
+
+        let ivc_proof = self.prove_node_circuit(
+            &mut rng,
+            node_circuit,
+            left.i + right.i + C1::ScalarField::from(1u64),
+            left.z_0.clone(),
+            (W_i1, U_i1),
+            (cf_W_i1, cf_U_i1),
+        )?;
+        Ok(PCDProof { start, ivc_proof })
+    }
+
+    /// Implements the verification of a PCD proof, ie. of a proof returned by
+    /// [`PCD::prove_leaf`] or [`PCD::prove_node`]. As in Nova, this method does not include the
+    /// commitments verification, which is done in the Decider.
+    pub fn verify(
+        vp: VerifierParams<C1, C2, CS1, CS2, H>,
+        proof: PCDProof<C1, C2>,
+    ) -> Result<(), Error> {
+        let IVCProof {
+            i: num_steps,
+            z_0,
+            z_i,
+            W_i,
+            U_i,
+            w_i,
+            u_i,
+            cf_W_i,
+            cf_U_i,
+        } = proof.ivc_proof;
+
+        // every leaf and node of the tree proves one step, so there are no PCD proofs of zero
+        // steps
+        if num_steps.is_zero() {
+            return Err(Error::IVCVerificationFail);
+        }
+        if u_i.x.len() != 2 || U_i.x.len() != 2 {
+            return Err(Error::IVCVerificationFail);
+        }
+
+        let sponge = PoseidonSponge::<C1::ScalarField>::new(&vp.sponge_config);
+        let pp_hash = vp.pp_hash()?;
+
+        // check that u_i's output points to the running instance
+        // u_i.X[0] == H(i, z_0, z_i, U_i)
+        let expected_u_i_x = U_i.hash(&sponge, pp_hash, num_steps, &z_0, &z_i);
+        if expected_u_i_x != u_i.x[0] {
+            return Err(Error::IVCVerificationFail);
+        }
+        // u_i.X[1] == H(cf_U_i)
+        let expected_cf_u_i_x = cf_U_i.hash_cyclefold(&sponge, pp_hash);
+        if expected_cf_u_i_x != u_i.x[1] {
+            return Err(Error::IVCVerificationFail);
+        }
+
+        // check R1CS satisfiability, which is equivalent to checking if `u_i`
+        // is an incoming instance and if `w_i` and `u_i` satisfy RelaxedR1CS
+        u_i.check_incoming()?;
+        vp.r1cs.check_relation(&w_i, &u_i)?;
+        // check RelaxedR1CS satisfiability
+        vp.r1cs.check_relation(&W_i, &U_i)?;
+
+        // check CycleFold RelaxedR1CS satisfiability
+        vp.cf_r1cs.check_relation(&cf_W_i, &cf_U_i)?;
+
+        Ok(())
+    }
+
+    // generates the constraints of the given node circuit, and returns the proof whose incoming
+    // instance is the resulting instance of the node circuit
+    fn prove_node_circuit(
+        &self,
+        mut rng: impl RngCore,
+        node_circuit: NodeCircuit<C1, C2, FC>,
+        i: C1::ScalarField,
+        z_0: Vec<C1::ScalarField>,
+        (W_i, U_i): (Witness<C1>, CommittedInstance<C1>),
+        (cf_W_i, cf_U_i): (CycleFoldWitness<C2>, CycleFoldCommittedInstance<C2>),
+    ) -> Result<IVCProof<C1, C2>, Error> {
+        let cs = ConstraintSystem::<C1::ScalarField>::new_ref();
+
+        let z_i = node_circuit.compute_next_state(cs.clone())?.value()?;
+
+        #[cfg(test)]
+        assert!(cs.is_satisfied()?);
+
+        let cs = cs.into_inner().ok_or(Error::NoInnerConstraintSystem)?;
+        let (w_i, x_i) = extract_w_x::<C1::ScalarField>(&cs);
+
+        #[cfg(test)]
+        if x_i.len() != 2 {
+            return Err(Error::NotExpectedLength(x_i.len(), 2));
+        }
+
+        let w_i = Witness::<C1>::new::<H>(w_i, self.r1cs.n_constraints(), &mut rng);
+        let u_i = w_i.commit::<CS1, H>(&self.cs_pp, x_i)?;
+
+        #[cfg(test)]
+        {
+            u_i.check_incoming()?;
+            self.r1cs.check_relation(&w_i, &u_i)?;
+            self.r1cs.check_relation(&W_i, &U_i)?;
+            self.cf_r1cs.check_relation(&cf_W_i, &cf_U_i)?;
+        }
+
+        Ok(IVCProof {
+            i,
+            z_0,
+            z_i,
+            W_i,
+            U_i,
+            w_i,
+            u_i,
+            cf_W_i,
+            cf_U_i,
+        })
+    }
+}
+
+/// helper method to get the R1CS for both the node circuit and the CycleFold circuit
+#[allow(clippy::type_complexity)]
+pub fn get_r1cs<C1, C2, FC>(
+    poseidon_config: &PoseidonConfig<C1::ScalarField>,
+    F_circuit: FC,
+) -> Result<(R1CS<C1::ScalarField>, R1CS<C2::ScalarField>), Error>
+where
+    C1: Curve<BaseField = C2::ScalarField, ScalarField = C2::BaseField>,
+    C2: Curve,
+    FC: FCircuit<C1::ScalarField>,
+{
+    let node_circuit = NodeCircuit::<C1, C2, FC>::empty(poseidon_config, F_circuit);
+    let cf_circuit = PCDCycleFoldCircuit::<C1>::empty();
+    let r1cs = get_r1cs_from_cs::<C1::ScalarField>(node_circuit)?;
+    let cf_r1cs = get_r1cs_from_cs::<C2::ScalarField>(cf_circuit)?;
+    Ok((r1cs, cf_r1cs))
+}
+
+#[cfg(test)]
+pub mod tests {
+    use ark_pallas::{Fr, Projective};
+    use ark_r1cs_std::fields::fp::FpVar;
+    use ark_relations::r1cs::{ConstraintSystemRef, SynthesisError};
+    use ark_vesta::Projective as Projective2;
+
+    use super::*;
+    use crate::commitment::pedersen::Pedersen;
+    use crate::frontend::utils::{cubic_step_native, CubicFCircuit};
+
+    type P<CS1, CS2, const H: bool> = PCD<Projective, Projective2, CubicFCircuit<Fr>, CS1, CS2, H>;
+
+    /// Test circuit whose state is `z_{i+1} = z_i + i`, so that its output depends on the index
+    /// of every step.
+    #[derive(Clone, Copy, Debug)]
+    struct StepIndexFCircuit<F: PrimeField> {
+        _f: PhantomData<F>,
+    }
+    impl<F: PrimeField> FCircuit<F> for StepIndexFCircuit<F> {
+        type Params = ();
+        type ExternalInputs = ();
+        type ExternalInputsVar = ();
+
+        fn new(_params: Self::Params) -> Result<Self, Error> {
+            Ok(Self { _f: PhantomData })
+        }
+        fn state_len(&self) -> usize {
+            1
+        }
+        fn generate_step_constraints(
+            &self,
+            _cs: ConstraintSystemRef<F>,
+            i: usize,
+            z_i: Vec<FpVar<F>>,
+            _external_inputs: Self::ExternalInputsVar,
+        ) -> Result<Vec<FpVar<F>>, SynthesisError> {
+            Ok(vec![&z_i[0] + F::from(i as u64)])
+        }
+    }
+
+    #[test]
+    fn test_pcd_step_index() -> Result<(), Error> {
+        let mut rng = ark_std::test_rng();
+        let poseidon_config = poseidon_canonical_config::<Fr>();
+
+        let F_circuit = StepIndexFCircuit::<Fr>::new(())?;
+        let prep_param = PreprocessorParam::new(poseidon_config, F_circuit);
+        type PS = PCD<
+            Projective,
+            Projective2,
+            StepIndexFCircuit<Fr>,
+            Pedersen<Projective>,
+            Pedersen<Projective2>,
+        >;
+        let params = PS::preprocess(&mut rng, &prep_param)?;
+        let pcd = PS::init(&params, F_circuit)?;
+
+        // steps in the order of the computation: leaf_0, leaf_1, node_01, leaf_3, leaf_4,
+        // node_34, root, so F is given the indexes 0..7, and z_7 = z_0 + (0 + 1 + ... + 6)
+        let z_0 = vec![Fr::from(3_u32)];
+        let z = |k: u64| vec![z_0[0] + Fr::from(k * (k - 1) / 2)];
+        let leaf_0 = pcd.prove_leaf(&mut rng, 0, z_0.clone(), ())?;
+        let leaf_1 = pcd.prove_leaf(&mut rng, 1, z(1), ())?;
+        let leaf_3 = pcd.prove_leaf(&mut rng, 3, z(3), ())?;
+        let leaf_4 = pcd.prove_leaf(&mut rng, 4, z(4), ())?;
+        let node_01 = pcd.prove_node(&mut rng, &leaf_0, &leaf_1, ())?;
+        assert_eq!(node_01.ivc_proof.z_i, z(3));
+        let node_34 = pcd.prove_node(&mut rng, &leaf_3, &leaf_4, ())?;
+        assert_eq!(node_34.ivc_proof.z_i, z(6));
+        let root = pcd.prove_node(&mut rng, &node_01, &node_34, ())?;
+        assert_eq!(root.ivc_proof.z_i, z(7));
+        PS::verify(params.1, root)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_pcd() -> Result<(), Error> {
+        // run the test using Pedersen commitments on both sides of the curve cycle, both in
+        // non-hiding and in hiding mode
+        test_pcd_opt::<Pedersen<Projective>, Pedersen<Projective2>, false>()?;
+        test_pcd_opt::<Pedersen<Projective, true>, Pedersen<Projective2, true>, true>()?;
+        Ok(())
+    }
+
+    fn test_pcd_opt<CS1, CS2, const H: bool>() -> Result<(), Error>
+    where
+        CS1: CommitmentScheme<Projective, H>,
+        CS2: CommitmentScheme<Projective2, H>,
+    {
+        let mut rng = ark_std::test_rng();
+        let poseidon_config = poseidon_canonical_config::<Fr>();
+
+        let F_circuit = CubicFCircuit::<Fr>::new(())?;
+        let prep_param = PreprocessorParam::new(poseidon_config, F_circuit);
+        let params = P::<CS1, CS2, H>::preprocess(&mut rng, &prep_param)?;
+        let pcd = P::<CS1, CS2, H>::init(&params, F_circuit)?;
+
+        // compute natively the states of the computation, so that the leaves can be proven
+        // independently
+        let n_steps = 7;
+        let mut states = vec![vec![Fr::from(3_u32)]];
+        for k in 0..n_steps {
+            states.push(cubic_step_native(states[k].clone()));
+        }
+
+        // Tree with 4 leaves and 3 internal nodes (7 steps), where each node applies F after its
+        // children, so the steps in the order of the computation are:
+        // leaf_0, leaf_1, node_01, leaf_3, leaf_4, node_34, root
+        let leaf_0 = pcd.prove_leaf(&mut rng, 0, states[0].clone(), ())?;
+        let leaf_1 = pcd.prove_leaf(&mut rng, 1, states[1].clone(), ())?;
+        let leaf_3 = pcd.prove_leaf(&mut rng, 3, states[3].clone(), ())?;
+        let leaf_4 = pcd.prove_leaf(&mut rng, 4, states[4].clone(), ())?;
+        for leaf in [&leaf_0, &leaf_1, &leaf_3, &leaf_4] {
+            P::<CS1, CS2, H>::verify(params.1.clone(), leaf.clone())?;
+        }
+
+        let node_01 = pcd.prove_node(&mut rng, &leaf_0, &leaf_1, ())?;
+        assert_eq!(node_01.ivc_proof.z_i, states[3]);
+        P::<CS1, CS2, H>::verify(params.1.clone(), node_01.clone())?;
+        let node_34 = pcd.prove_node(&mut rng, &leaf_3, &leaf_4, ())?;
+        assert_eq!(node_34.start, 3);
+        assert_eq!(node_34.ivc_proof.z_0, states[3]);
+        assert_eq!(node_34.ivc_proof.z_i, states[6]);
+        P::<CS1, CS2, H>::verify(params.1.clone(), node_34.clone())?;
+
+        let root = pcd.prove_node(&mut rng, &node_01, &node_34, ())?;
+        assert_eq!(root.ivc_proof.i, Fr::from(n_steps as u32));
+        assert_eq!(root.ivc_proof.z_0, states[0]);
+        assert_eq!(root.ivc_proof.z_i, states[n_steps]);
+
+        // serialize the verifier params, and deserialize them recomputing the R1CSs
+        let mut vp_serialized = vec![];
+        params.1.serialize_compressed(&mut vp_serialized)?;
+        let vp_deserialized = P::<CS1, CS2, H>::vp_deserialize_with_mode(
+            &mut vp_serialized.as_slice(),
+            ark_serialize::Compress::Yes,
+            ark_serialize::Validate::Yes,
+            (), // fcircuit_params
+        )?;
+        assert_eq!(vp_deserialized.pp_hash()?, params.1.pp_hash()?);
+        P::<CS1, CS2, H>::verify(vp_deserialized, root.clone())?;
+
+        // the children of a node must be contiguous segments
+        assert!(pcd.prove_node(&mut rng, &leaf_0, &leaf_3, ()).is_err());
+        // also in the indexes of their steps
+        let mut leaf_1_moved = leaf_1.clone();
+        leaf_1_moved.start = 2;
+        assert!(pcd
+            .prove_node(&mut rng, &leaf_0, &leaf_1_moved, ())
+            .is_err());
+
+        // a proof claiming a different initial state must not verify
+        let mut wrong_root = root;
+        wrong_root.ivc_proof.z_0 = states[1].clone();
+        assert!(P::<CS1, CS2, H>::verify(params.1, wrong_root).is_err());
+        Ok(())
+    }
+}
@@ -32,10 +32,7 @@
 //! instance (steps 2,3,4 from section D.4 of the [HyperNova](https://eprint.iacr.org/2023/573.pdf)
 //! paper).
 //! And the Use-case-2 would require a modified version of the Decider circuits.
-use ark_crypto_primitives::sponge::{
-    poseidon::{PoseidonConfig, PoseidonSponge},
-    CryptographicSponge,
-};
+use ark_crypto_primitives::sponge::CryptographicSponge;
 use ark_std::{rand::RngCore, One, Zero};
 
 use super::{
@@ -47,6 +44,7 @@ use crate::{
     commitment::CommitmentScheme,
     folding::traits::CommittedInstanceOps,
     frontend::FCircuit,
+    transcript::SpongeWithGadget,
     Curve, Error,
 };
 
@@ -69,15 +67,16 @@ impl<C1: Curve, C2: Curve> RandomizedIVCProof<C1, C2> {
         FC: FCircuit<C1::ScalarField>,
         CS1: CommitmentScheme<C1, true>,
         CS2: CommitmentScheme<C2, true>,
+        T: SpongeWithGadget<C1::ScalarField>,
     >(
-        nova: &Nova<C1, C2, FC, CS1, CS2, true>,
+        nova: &Nova<C1, C2, FC, CS1, CS2, true, T>,
         mut rng: impl RngCore,
     ) -> Result<RandomizedIVCProof<C1, C2>, Error> {
-        let mut transcript = PoseidonSponge::<C1::ScalarField>::new(&nova.poseidon_config);
+        let mut transcript = T::new(&nova.sponge_config);
 
         // I. Compute proof for 'regular' instances
         // 1. Fold the instance-witness pairs (U_i, W_i) with (u_i, w_i)
-        let (W_f, U_f, cmT, _) = NIFS::<C1, CS1, PoseidonSponge<C1::ScalarField>, true>::prove(
+        let (W_f, U_f, cmT, _) = NIFS::<C1, CS1, T, true>::prove(
             &nova.cs_pp,
             &nova.r1cs,
             &mut transcript,
@@ -94,17 +93,16 @@ impl<C1: Curve, C2: Curve> RandomizedIVCProof<C1, C2> {
             .sample_witness_instance::<CS1>(&nova.cs_pp, &mut rng)?;
 
         // 3. Fold the instance-witness pair (U_f, W_f) with (U_r, W_r)
-        let (W_i_prime, _, cmT_i_prime, _) =
-            NIFS::<C1, CS1, PoseidonSponge<C1::ScalarField>, true>::prove(
-                &nova.cs_pp,
-                &nova.r1cs,
-                &mut transcript,
-                nova.pp_hash,
-                &W_f,
-                &U_f,
-                &W_r,
-                &U_r,
-            )?;
+        let (W_i_prime, _, cmT_i_prime, _) = NIFS::<C1, CS1, T, true>::prove(
+            &nova.cs_pp,
+            &nova.r1cs,
+            &mut transcript,
+            nova.pp_hash,
+            &W_f,
+            &U_f,
+            &W_r,
+            &U_r,
+        )?;
 
         Ok(RandomizedIVCProof {
             U_i: nova.U_i.clone(),
@@ -121,11 +119,15 @@ impl<C1: Curve, C2: Curve> RandomizedIVCProof<C1, C2> {
     /// Verify a zero-knowledge proof of a Nova IVC proof
     /// It implements the verifier of appendix D.4. in https://eprint.iacr.org/2023/573.pdf
     #[allow(clippy::too_many_arguments)]
-    pub fn verify<CS1: CommitmentScheme<C1, true>, CS2: CommitmentScheme<C2, true>>(
+    pub fn verify<
+        CS1: CommitmentScheme<C1, true>,
+        CS2: CommitmentScheme<C2, true>,
+        T: SpongeWithGadget<C1::ScalarField>,
+    >(
         r1cs: &R1CS<C1::ScalarField>,
         cf_r1cs: &R1CS<C2::ScalarField>,
         pp_hash: C1::ScalarField,
-        poseidon_config: &PoseidonConfig<C1::ScalarField>,
+        sponge_config: &T::Config,
         i: C1::ScalarField,
         z_0: Vec<C1::ScalarField>,
         z_i: Vec<C1::ScalarField>,
@@ -150,7 +152,7 @@ impl<C1: Curve, C2: Curve> RandomizedIVCProof<C1, C2> {
         }
 
         // b. Check computed hashes are correct
-        let sponge = PoseidonSponge::<C1::ScalarField>::new(poseidon_config);
+        let sponge = T::new(sponge_config);
         let expected_u_i_x = proof.U_i.hash(&sponge, pp_hash, i, &z_0, &z_i);
         if expected_u_i_x != proof.u_i.x[0] {
             return Err(Error::zkIVCVerificationFail);
@@ -166,9 +168,9 @@ impl<C1: Curve, C2: Curve> RandomizedIVCProof<C1, C2> {
             return Err(Error::zkIVCVerificationFail);
         }
 
-        let mut transcript = PoseidonSponge::<C1::ScalarField>::new(poseidon_config);
+        let mut transcript = T::new(sponge_config);
         // 3. Obtain the U_f folded instance
-        let (U_f, _) = NIFS::<C1, CS1, PoseidonSponge<C1::ScalarField>, true>::verify(
+        let (U_f, _) = NIFS::<C1, CS1, T, true>::verify(
             &mut transcript,
             pp_hash,
             &proof.u_i,
@@ -177,7 +179,7 @@ impl<C1: Curve, C2: Curve> RandomizedIVCProof<C1, C2> {
         )?;
 
         // 4. Obtain the U^{\prime}_i folded instance
-        let (U_i_prime, _) = NIFS::<C1, CS1, PoseidonSponge<C1::ScalarField>, true>::verify(
+        let (U_i_prime, _) = NIFS::<C1, CS1, T, true>::verify(
             &mut transcript,
             pp_hash,
             &U_f,
@@ -203,6 +205,7 @@ pub mod tests {
     use crate::frontend::utils::CubicFCircuit;
     use crate::transcript::poseidon::poseidon_canonical_config;
     use ark_bn254::{Fr, G1Projective as Projective};
+    use ark_crypto_primitives::sponge::poseidon::PoseidonSponge;
     use ark_grumpkin::Projective as Projective2;
     use rand::rngs::OsRng;
 
@@ -219,17 +222,20 @@ pub mod tests {
         >(poseidon_config.clone(), F_circuit, 3)?;
 
         let proof = RandomizedIVCProof::new(&nova, &mut rng)?;
-        let verify =
-            RandomizedIVCProof::verify::<Pedersen<Projective, true>, Pedersen<Projective2, true>>(
-                &nova.r1cs,
-                &nova.cf_r1cs,
-                nova.pp_hash,
-                &nova.poseidon_config,
-                nova.i,
-                nova.z_0,
-                nova.z_i,
-                &proof,
-            );
+        let verify = RandomizedIVCProof::verify::<
+            Pedersen<Projective, true>,
+            Pedersen<Projective2, true>,
+            PoseidonSponge<Fr>,
+        >(
+            &nova.r1cs,
+            &nova.cf_r1cs,
+            nova.pp_hash,
+            &nova.sponge_config,
+            nova.i,
+            nova.z_0,
+            nova.z_i,
+            &proof,
+        );
         assert!(verify.is_ok());
         Ok(())
     }
@@ -246,17 +252,20 @@ pub mod tests {
         >(poseidon_config.clone(), F_circuit, 0)?;
 
         let proof = RandomizedIVCProof::new(&nova, &mut rng)?;
-        let verify =
-            RandomizedIVCProof::verify::<Pedersen<Projective, true>, Pedersen<Projective2, true>>(
-                &nova.r1cs,
-                &nova.cf_r1cs,
-                nova.pp_hash,
-                &nova.poseidon_config,
-                nova.i,
-                nova.z_0,
-                nova.z_i,
-                &proof,
-            );
+        let verify = RandomizedIVCProof::verify::<
+            Pedersen<Projective, true>,
+            Pedersen<Projective2, true>,
+            PoseidonSponge<Fr>,
+        >(
+            &nova.r1cs,
+            &nova.cf_r1cs,
+            nova.pp_hash,
+            &nova.sponge_config,
+            nova.i,
+            nova.z_0,
+            nova.z_i,
+            &proof,
+        );
         assert!(verify.is_ok());
         Ok(())
     }
@@ -280,17 +289,20 @@ pub mod tests {
         nova_with_incorrect_running_instance.U_i = sampled_committed_instance;
         let incorrect_proof =
             RandomizedIVCProof::new(&nova_with_incorrect_running_instance, &mut rng)?;
-        let verify =
-            RandomizedIVCProof::verify::<Pedersen<Projective, true>, Pedersen<Projective2, true>>(
-                &nova_with_incorrect_running_instance.r1cs,
-                &nova_with_incorrect_running_instance.cf_r1cs,
-                nova_with_incorrect_running_instance.pp_hash,
-                &nova_with_incorrect_running_instance.poseidon_config,
-                nova_with_incorrect_running_instance.i,
-                nova_with_incorrect_running_instance.z_0,
-                nova_with_incorrect_running_instance.z_i,
-                &incorrect_proof,
-            );
+        let verify = RandomizedIVCProof::verify::<
+            Pedersen<Projective, true>,
+            Pedersen<Projective2, true>,
+            PoseidonSponge<Fr>,
+        >(
+            &nova_with_incorrect_running_instance.r1cs,
+            &nova_with_incorrect_running_instance.cf_r1cs,
+            nova_with_incorrect_running_instance.pp_hash,
+            &nova_with_incorrect_running_instance.sponge_config,
+            nova_with_incorrect_running_instance.i,
+            nova_with_incorrect_running_instance.z_0,
+            nova_with_incorrect_running_instance.z_i,
+            &incorrect_proof,
+        );
         assert!(verify.is_err());
         Ok(())
     }
@@ -314,17 +326,20 @@ pub mod tests {
         nova_with_incorrect_running_witness.W_i = sampled_committed_witness;
         let incorrect_proof =
             RandomizedIVCProof::new(&nova_with_incorrect_running_witness, &mut rng)?;
-        let verify =
-            RandomizedIVCProof::verify::<Pedersen<Projective, true>, Pedersen<Projective2, true>>(
-                &nova_with_incorrect_running_witness.r1cs,
-                &nova_with_incorrect_running_witness.cf_r1cs,
-                nova_with_incorrect_running_witness.pp_hash,
-                &nova_with_incorrect_running_witness.poseidon_config,
-                nova_with_incorrect_running_witness.i,
-                nova_with_incorrect_running_witness.z_0,
-                nova_with_incorrect_running_witness.z_i,
-                &incorrect_proof,
-            );
+        let verify = RandomizedIVCProof::verify::<
+            Pedersen<Projective, true>,
+            Pedersen<Projective2, true>,
+            PoseidonSponge<Fr>,
+        >(
+            &nova_with_incorrect_running_witness.r1cs,
+            &nova_with_incorrect_running_witness.cf_r1cs,
+            nova_with_incorrect_running_witness.pp_hash,
+            &nova_with_incorrect_running_witness.sponge_config,
+            nova_with_incorrect_running_witness.i,
+            nova_with_incorrect_running_witness.z_0,
+            nova_with_incorrect_running_witness.z_i,
+            &incorrect_proof,
+        );
         assert!(verify.is_err());
         Ok(())
     }
@@ -0,0 +1,426 @@
+/// This file implements the offchain decider circuit for Ova, which reuses the generic offchain
+/// decider circuits.
+///
+/// Since Ova does not keep the error term `E` of the running instance, but only its commitment
+/// (together with the witness) in `cmWE`, the decider recomputes `E` and works with witnesses
+/// whose committed vector is `W || E`.
+use ark_crypto_primitives::sponge::{poseidon::PoseidonSponge, CryptographicSponge};
+use ark_ff::{BigInteger, PrimeField};
+use ark_r1cs_std::{
+    alloc::{AllocVar, AllocationMode},
+    fields::fp::FpVar,
+};
+use ark_relations::r1cs::{Namespace, SynthesisError};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::{borrow::Borrow, marker::PhantomData, Zero};
+
+use super::{compute_T, Ova};
+use crate::arith::{
+    r1cs::{circuits::R1CSMatricesVar, R1CS},
+    Arith, ArithRelation, ArithRelationGadget,
+};
+use crate::commitment::CommitmentScheme;
+use crate::folding::{
+    circuits::{
+        decider::{
+            off_chain::{GenericOffchainDeciderCircuit1, GenericOffchainDeciderCircuit2},
+            DeciderChallenge, DeciderEnabledNIFS, KZGChallengesGadget,
+        },
+        CF1,
+    },
+    nova::nifs::{
+        ova::{self, CommittedInstance, NIFS},
+        ova_circuits::{CommittedInstanceVar, NIFSGadget},
+        NIFSGadgetTrait, NIFSTrait,
+    },
+    traits::{Dummy, WitnessOps, WitnessVarOps},
+};
+use crate::frontend::FCircuit;
+use crate::transcript::{poseidon::poseidon_custom_config, TranscriptVar};
+use crate::utils::gadgets::{EquivalenceGadget, VectorGadget};
+use crate::{Curve, Error};
+
+/// Witness used by the Ova decider, which contains the full vector `WE = W || E` committed in
+/// `cmWE`, i.e., the witness `W` concatenated with the error term `E` (or the cross term `T` for
+/// incoming instances).
+#[derive(Debug, Clone, Eq, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+pub struct DeciderWitness<C: Curve> {
+    pub WE: Vec<C::ScalarField>,
+    pub rW: C::ScalarField,
+}
+
+impl<C: Curve> DeciderWitness<C> {
+    /// Builds the decider witness of a running instance, by computing its error term `E` from
+    /// the R1CS evaluated at `z = (U.u, U.x, W.w)`.
+    pub fn new(
+        r1cs: &R1CS<CF1<C>>,
+        W: &ova::Witness<C>,
+        U: &CommittedInstance<C>,
+    ) -> Result<Self, Error> {
+        let E = r1cs.eval_at_z(&[&[U.u][..], &U.x, &W.w].concat())?;
+        Ok(Self {
+            WE: [W.w.clone(), E].concat(),
+            rW: W.rW,
+        })
+    }
+}
+
+impl<C: Curve> Dummy<&R1CS<CF1<C>>> for DeciderWitness<C> {
+    fn dummy(r1cs: &R1CS<CF1<C>>) -> Self {
+        Self {
+            WE: vec![C::ScalarField::zero(); r1cs.n_witnesses() + r1cs.n_constraints()],
+            rW: C::ScalarField::zero(),
+        }
+    }
+}
+
+impl<C: Curve> WitnessOps<C::ScalarField> for DeciderWitness<C> {
+    type Var = DeciderWitnessVar<C>;
+
+    fn get_openings(&self) -> Vec<(&[C::ScalarField], C::ScalarField)> {
+        vec![(&self.WE, self.rW)]
+    }
+}
+
+/// In-circuit representation of the [`DeciderWitness`].
+#[derive(Debug, Clone)]
+pub struct DeciderWitnessVar<C: Curve> {
+    pub WE: Vec<FpVar<C::ScalarField>>,
+    pub rW: FpVar<C::ScalarField>,
+}
+
+impl<C: Curve> AllocVar<DeciderWitness<C>, CF1<C>> for DeciderWitnessVar<C> {
+    fn new_variable<T: Borrow<DeciderWitness<C>>>(
+        cs: impl Into<Namespace<CF1<C>>>,
+        f: impl FnOnce() -> Result<T, SynthesisError>,
+        mode: AllocationMode,
+    ) -> Result<Self, SynthesisError> {
+        f().and_then(|val| {
+            let cs = cs.into();
+
+            let WE: Vec<FpVar<C::ScalarField>> =
+                Vec::new_variable(cs.clone(), || Ok(val.borrow().WE.clone()), mode)?;
+            let rW =
+                FpVar::<C::ScalarField>::new_variable(cs.clone(), || Ok(val.borrow().rW), mode)?;
+
+            Ok(Self { WE, rW })
+        })
+    }
+}
+
+impl<C: Curve> WitnessVarOps<C::ScalarField> for DeciderWitnessVar<C> {
+    fn get_openings(&self) -> Vec<(&[FpVar<C::ScalarField>], FpVar<C::ScalarField>)> {
+        vec![(&self.WE, self.rW.clone())]
+    }
+}
+
+/// Implements [`ArithRelation`] for R1CS, where the witness is of type [`DeciderWitness`], and the
+/// committed instance is Ova's [`CommittedInstance`]. As in Nova, the check is the relaxed R1CS
+/// one, with the error term taken from the tail of `WE`.
+impl<C: Curve> ArithRelation<DeciderWitness<C>, CommittedInstance<C>> for R1CS<CF1<C>> {
+    type Evaluation = Vec<CF1<C>>;
+
+    fn eval_relation(
+        &self,
+        w: &DeciderWitness<C>,
+        u: &CommittedInstance<C>,
+    ) -> Result<Self::Evaluation, Error> {
+        let W = w.WE.get(..self.n_witnesses()).ok_or(Error::NotSatisfied)?;
+        self.eval_at_z(&[&[u.u][..], &u.x, W].concat())
+    }
+
+    fn check_evaluation(
+        w: &DeciderWitness<C>,
+        _u: &CommittedInstance<C>,
+        e: Self::Evaluation,
+    ) -> Result<(), Error> {
+        let E = w.WE.get(w.WE.len().saturating_sub(e.len())..);
+        (E == Some(&e[..])).then_some(()).ok_or(Error::NotSatisfied)
+    }
+}
+
+impl<C: Curve> ArithRelationGadget<DeciderWitnessVar<C>, CommittedInstanceVar<C>>
+    for R1CSMatricesVar<C::ScalarField, FpVar<C::ScalarField>>
+{
+    type Evaluation = (Vec<FpVar<C::ScalarField>>, Vec<FpVar<C::ScalarField>>);
+
+    fn eval_relation(
+        &self,
+        w: &DeciderWitnessVar<C>,
+        u: &CommittedInstanceVar<C>,
+    ) -> Result<Self::Evaluation, SynthesisError> {
+        // `z = (u, x, W)` has as many elements as the columns of the matrices
+        let n_w = self.A.n_cols - 1 - u.x.len();
+        let W = w.WE.get(..n_w).ok_or(SynthesisError::Unsatisfiable)?;
+        self.eval_at_z(&[&[u.u.clone()][..], &u.x, W].concat())
+    }
+
+    fn enforce_evaluation(
+        w: &DeciderWitnessVar<C>,
+        _u: &CommittedInstanceVar<C>,
+        (AzBz, uCz): Self::Evaluation,
+    ) -> Result<(), SynthesisError> {
+        let n_w = w.WE.len().saturating_sub(uCz.len());
+        let E = w.WE.get(n_w..).ok_or(SynthesisError::Unsatisfiable)?;
+        EquivalenceGadget::<C::ScalarField>::enforce_equivalent(&AzBz[..], &uCz.add(E)?[..])
+    }
+}
+
+pub struct DeciderOvaGadget;
+
+impl<C: Curve>
+    DeciderEnabledNIFS<
+        C,
+        CommittedInstance<C>,
+        CommittedInstance<C>,
+        DeciderWitness<C>,
+        R1CS<CF1<C>>,
+    > for DeciderOvaGadget
+{
+    // Ova's NIFS does not have a proof
+    type ProofDummyCfg = ();
+    type Proof = ();
+    type RandomnessDummyCfg = ();
+    type Randomness = CF1<C>;
+
+    fn fold_field_elements_gadget<S: CryptographicSponge, T: TranscriptVar<CF1<C>, S>>(
+        _arith: &R1CS<CF1<C>>,
+        transcript: &mut T,
+        pp_hash: FpVar<CF1<C>>,
+        U: CommittedInstanceVar<C>,
+        U_vec: Vec<FpVar<CF1<C>>>,
+        u: CommittedInstanceVar<C>,
+        _proof: (),
+        _randomness: CF1<C>,
+    ) -> Result<CommittedInstanceVar<C>, SynthesisError> {
+        let (new_U, _) = NIFSGadget::<C, S, T>::verify(transcript, pp_hash, U, U_vec, u, None)?;
+        Ok(new_U)
+    }
+
+    fn fold_group_elements_native(
+        U_commitments: &[C],
+        u_commitments: &[C],
+        _proof: Option<Self::Proof>,
+        r: Self::Randomness,
+    ) -> Result<Vec<C>, Error> {
+        let U_cmWE = U_commitments[0];
+        let u_cmWE = u_commitments[0];
+        Ok(vec![U_cmWE + u_cmWE.mul(r)])
+    }
+}
+
+/// Circuit that implements part of the in-circuit checks needed for the offchain verification over
+/// the Curve2's BaseField (=Curve1's ScalarField).
+pub type DeciderCircuit1<C1, C2, P = CF1<C1>> = GenericOffchainDeciderCircuit1<
+    C1,
+    C2,
+    CommittedInstance<C1>,
+    CommittedInstance<C1>,
+    DeciderWitness<C1>,
+    R1CS<CF1<C1>>,
+    R1CSMatricesVar<CF1<C1>, FpVar<CF1<C1>>>,
+    DeciderOvaGadget,
+    P,
+>;
+
+impl<
+        C1: Curve,
+        C2: Curve,
+        FC: FCircuit<C1::ScalarField>,
+        CS1: CommitmentScheme<C1, H>,
+        CS2: CommitmentScheme<C2, H>,
+        const H: bool,
+    > TryFrom<Ova<C1, C2, FC, CS1, CS2, H>> for DeciderCircuit1<C1, C2, CS1::Challenge>
+where
+    CS1::Challenge: DeciderChallenge<CF1<C1>>,
+{
+    type Error = Error;
+
+    fn try_from(ova: Ova<C1, C2, FC, CS1, CS2, H>) -> Result<Self, Error> {
+        let mut transcript = PoseidonSponge::<C1::ScalarField>::new(&ova.poseidon_config);
+        // pp_hash is absorbed to transcript at the NIFS::prove call
+
+        // compute the U_{i+1}, W_{i+1}
+        let (W_i1, U_i1, _, r_bits) = NIFS::<C1, CS1, PoseidonSponge<C1::ScalarField>, H>::prove(
+            &ova.cs_pp,
+            &ova.r1cs,
+            &mut transcript,
+            ova.pp_hash,
+            &ova.W_i,
+            &ova.U_i,
+            &ova.w_i,
+            &ova.u_i,
+        )?;
+        let r_Fr = C1::ScalarField::from_bigint(BigInteger::from_bits_le(&r_bits))
+            .ok_or(Error::OutOfBounds)?;
+
+        let W_i1 = DeciderWitness::new(&ova.r1cs, &W_i1, &U_i1)?;
+        let W_i = DeciderWitness::new(&ova.r1cs, &ova.W_i, &ova.U_i)?;
+        // the incoming instance commits to the cross term instead of its (zero) error term
+        let w_i = DeciderWitness {
+            WE: [
+                ova.w_i.w.clone(),
+                compute_T(&ova.r1cs, &ova.W_i, &ova.U_i, &ova.w_i, &ova.u_i)?,
+            ]
+            .concat(),
+            rW: ova.w_i.rW,
+        };
+
+        // compute the KZG challenges used as inputs in the circuit
+        let openings = W_i1.get_openings();
+        let lens = openings.iter().map(|(v, _)| v.len()).collect::<Vec<_>>();
+        let kzg_challenges = KZGChallengesGadget::get_opening_challenges_native::<
+            C1,
+            _,
+            _,
+            CS1::Challenge,
+        >(&mut transcript, &U_i1, &lens);
+
+        // get KZG evals
+        let kzg_evaluations = openings
+            .iter()
+            .zip(&kzg_challenges)
+            .map(|((v, _), c)| c.evaluate_native(v))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self {
+            _avar: PhantomData,
+            arith: ova.r1cs,
+            sponge_config: ova.poseidon_config,
+            pp_hash: ova.pp_hash,
+            i: ova.i,
+            z_0: ova.z_0,
+            z_i: ova.z_i,
+            U_i: ova.U_i,
+            W_i,
+            u_i: ova.u_i,
+            w_i,
+            U_i1,
+            W_i1,
+            proof: (),
+            randomness: r_Fr,
+            cf_U_i: ova.cf_U_i,
+            kzg_challenges,
+            kzg_evaluations,
+        })
+    }
+}
+
+/// Circuit that implements part of the in-circuit checks needed for the offchain verification over
+/// the Curve1's BaseField (=Curve2's ScalarField).
+pub type DeciderCircuit2<C2, P = CF1<C2>> = GenericOffchainDeciderCircuit2<C2, P>;
+
+impl<
+        C1: Curve,
+        C2: Curve,
+        FC: FCircuit<C1::ScalarField>,
+        CS1: CommitmentScheme<C1, H>,
+        CS2: CommitmentScheme<C2, H>,
+        const H: bool,
+    > TryFrom<Ova<C1, C2, FC, CS1, CS2, H>> for DeciderCircuit2<C2, CS2::Challenge>
+where
+    CS2::Challenge: DeciderChallenge<CF1<C2>>,
+{
+    type Error = Error;
+
+    fn try_from(ova: Ova<C1, C2, FC, CS1, CS2, H>) -> Result<Self, Error> {
+        // Create a poseidon config on `C2`'s scalar field for `circuit2`, with
+        // the same parameters (`full_rounds` etc.) as `circuit1` to ensure the
+        // security level is the same.
+        let poseidon_config = poseidon_custom_config(
+            ova.poseidon_config.full_rounds,
+            ova.poseidon_config.partial_rounds,
+            ova.poseidon_config.alpha,
+            ova.poseidon_config.rate,
+            ova.poseidon_config.capacity,
+        );
+        let mut transcript = PoseidonSponge::<C2::ScalarField>::new(&poseidon_config);
+        let pp_hash_Fq =
+            C2::ScalarField::from_le_bytes_mod_order(&ova.pp_hash.into_bigint().to_bytes_le());
+        transcript.absorb(&pp_hash_Fq);
+
+        // compute the KZG challenges used as inputs in the circuit
+        let openings = ova.cf_W_i.get_openings();
+        let lens = openings.iter().map(|(v, _)| v.len()).collect::<Vec<_>>();
+        let kzg_challenges = KZGChallengesGadget::get_opening_challenges_native::<
+            C2,
+            _,
+            _,
+            CS2::Challenge,
+        >(&mut transcript, &ova.cf_U_i, &lens);
+
+        // get KZG evals
+        let kzg_evaluations = openings
+            .iter()
+            .zip(&kzg_challenges)
+            .map(|((v, _), c)| c.evaluate_native(v))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Self {
+            cf_arith: ova.cf_r1cs,
+            poseidon_config,
+            pp_hash: pp_hash_Fq,
+            cf_U_i: ova.cf_U_i,
+            cf_W_i: ova.cf_W_i,
+            kzg_challenges,
+            kzg_evaluations,
+        })
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use ark_pallas::{Fq, Fr, Projective};
+    use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystem};
+    use ark_vesta::Projective as Projective2;
+
+    use super::*;
+    use crate::commitment::pedersen::Pedersen;
+    use crate::folding::nova::PreprocessorParam;
+    use crate::frontend::utils::CubicFCircuit;
+    use crate::transcript::poseidon::poseidon_canonical_config;
+    use crate::FoldingScheme;
+
+    #[test]
+    fn test_decider_circuits() -> Result<(), Error> {
+        let mut rng = ark_std::test_rng();
+        let poseidon_config = poseidon_canonical_config::<Fr>();
+
+        let F_circuit = CubicFCircuit::<Fr>::new(())?;
+        let z_0 = vec![Fr::from(3_u32)];
+
+        type O = Ova<
+            Projective,
+            Projective2,
+            CubicFCircuit<Fr>,
+            Pedersen<Projective>,
+            Pedersen<Projective2>,
+            false,
+        >;
+
+        let prep_param = PreprocessorParam::new(poseidon_config, F_circuit);
+        let ova_params = O::preprocess(&mut rng, &prep_param)?;
+
+        // generate an Ova instance and do two steps of it, so that the running instance is not
+        // the dummy one
+        let mut ova = O::init(&ova_params, F_circuit, z_0.clone())?;
+        ova.prove_step(&mut rng, (), None)?;
+        ova.prove_step(&mut rng, (), None)?;
+        // verify the IVC
+        let ivc_proof = ova.ivc_proof();
+        O::verify(ova_params.1, ivc_proof)?;
+
+        // load the DeciderCircuit 1 & 2 from the Ova instance
+        let decider_circuit1 = DeciderCircuit1::<Projective, Projective2>::try_from(ova.clone())?;
+        let decider_circuit2 = DeciderCircuit2::<Projective2>::try_from(ova)?;
+
+        // generate the constraints of both circuits and check that are satisfied by the inputs
+        let cs1 = ConstraintSystem::<Fr>::new_ref();
+        decider_circuit1.generate_constraints(cs1.clone())?;
+        assert!(cs1.is_satisfied()?);
+        let cs2 = ConstraintSystem::<Fq>::new_ref();
+        decider_circuit2.generate_constraints(cs2.clone())?;
+        assert!(cs2.is_satisfied()?);
+        Ok(())
+    }
+}
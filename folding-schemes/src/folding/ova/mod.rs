@@ -0,0 +1,684 @@
+/// Implements an IVC based on the [Ova](https://hackmd.io/V4838nnlRKal9ZiTHiGYzw) folding
+/// scheme, together with [CycleFold](https://eprint.iacr.org/2023/1192.pdf).
+///
+/// The IVC follows the same design as Nova+CycleFold (see [`crate::folding::nova`]), but the
+/// primary instances are Ova's committed instances, in which the witness and the error term are
+/// committed together in a single commitment `cmWE`. The prover of step `i` commits to the cross
+/// term `T` between the running instance and the new incoming instance alongside the incoming
+/// witness, so that folding does not need any extra message from the prover, and a single
+/// CycleFold circuit is needed at each step. This is what makes the recursion overhead of Ova
+/// lower than Nova's.
+use ark_crypto_primitives::sponge::{
+    poseidon::{PoseidonConfig, PoseidonSponge},
+    CryptographicSponge,
+};
+use ark_ff::{BigInteger, PrimeField};
+use ark_r1cs_std::R1CSVar;
+use ark_relations::r1cs::ConstraintSystem;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use ark_std::{rand::RngCore, One, Zero};
+
+use crate::arith::{
+    r1cs::{extract_w_x, R1CS},
+    Arith, ArithRelation,
+};
+use crate::commitment::CommitmentScheme;
+use crate::folding::{
+    circuits::{
+        cyclefold::{fold_cyclefold_circuit, CycleFoldCommittedInstance, CycleFoldWitness},
+        CF1,
+    },
+    nova::{
+        get_r1cs_from_cs,
+        nifs::{
+            ova::{CommittedInstance, Witness, NIFS},
+            NIFSTrait,
+        },
+        NovaCycleFoldCircuit, NovaCycleFoldConfig, PreprocessorParam, ProverParams, VerifierParams,
+    },
+    traits::{CommittedInstanceOps, Dummy},
+};
+use crate::frontend::FCircuit;
+use crate::transcript::poseidon::poseidon_canonical_config;
+use crate::utils::vec::{vec_add, vec_sub};
+use crate::{Curve, Error, FoldingScheme};
+
+pub mod circuits;
+pub mod decider;
+pub mod decider_circuits;
+
+use circuits::AugmentedFCircuit;
+
+/// Computes the cross term `T` between the running instance `(U, W)` and the incoming instance
+/// `(u, w)`, so that the error term of the instance resulting from folding them with challenge
+/// `r` is `E + r * T`. Since `u` is a satisfying R1CS instance, `T` can be obtained by evaluating
+/// the R1CS at the sum of both `z` vectors and subtracting the evaluations at each of them.
+pub fn compute_T<C: Curve>(
+    r1cs: &R1CS<CF1<C>>,
+    W: &Witness<C>,
+    U: &CommittedInstance<C>,
+    w: &Witness<C>,
+    u: &CommittedInstance<C>,
+) -> Result<Vec<CF1<C>>, Error> {
+    let z_U = [&[U.u][..], &U.x, &W.w].concat();
+    let z_u = [&[u.u][..], &u.x, &w.w].concat();
+    let E_sum = r1cs.eval_at_z(&vec_add(&z_U, &z_u)?)?;
+    let E_U = r1cs.eval_at_z(&z_U)?;
+    let E_u = r1cs.eval_at_z(&z_u)?;
+    vec_sub(&vec_sub(&E_sum, &E_U)?, &E_u)
+}
+
+/// helper method to get the R1CS for both the AugmentedFCircuit and the CycleFold circuit
+#[allow(clippy::type_complexity)]
+pub fn get_r1cs<C1, C2, FC>(
+    poseidon_config: &PoseidonConfig<C1::ScalarField>,
+    F_circuit: FC,
+) -> Result<(R1CS<C1::ScalarField>, R1CS<C2::ScalarField>), Error>
+where
+    C1: Curve<BaseField = C2::ScalarField, ScalarField = C2::BaseField>,
+    C2: Curve,
+    FC: FCircuit<C1::ScalarField>,
+{
+    let augmented_F_circuit = AugmentedFCircuit::<C1, C2, FC>::empty(poseidon_config, F_circuit);
+    let cf_circuit = NovaCycleFoldCircuit::<C1>::empty();
+    let r1cs = get_r1cs_from_cs::<C1::ScalarField>(augmented_F_circuit)?;
+    let cf_r1cs = get_r1cs_from_cs::<C2::ScalarField>(cf_circuit)?;
+    Ok((r1cs, cf_r1cs))
+}
+
+#[derive(PartialEq, Eq, Debug, Clone, CanonicalSerialize, CanonicalDeserialize)]
+pub struct IVCProof<C1, C2>
+where
+    C1: Curve,
+    C2: Curve,
+{
+    // current step of the IVC
+    pub i: C1::ScalarField,
+    // initial state
+    pub z_0: Vec<C1::ScalarField>,
+    // current state
+    pub z_i: Vec<C1::ScalarField>,
+    // running instance
+    pub W_i: Witness<C1>,
+    pub U_i: CommittedInstance<C1>,
+    // incoming instance
+    pub w_i: Witness<C1>,
+    pub u_i: CommittedInstance<C1>,
+    // CycleFold instances
+    pub cf_W_i: CycleFoldWitness<C2>,
+    pub cf_U_i: CycleFoldCommittedInstance<C2>,
+}
+
+/// Implements Ova+CycleFold's IVC, following the FoldingScheme trait.
+/// The `H` const generic specifies whether the homorphic commitment scheme is blinding
+#[derive(Clone, Debug)]
+pub struct Ova<C1, C2, FC, CS1, CS2, const H: bool = false>
+where
+    C1: Curve,
+    C2: Curve,
+    FC: FCircuit<C1::ScalarField>,
+    CS1: CommitmentScheme<C1, H>,
+    CS2: CommitmentScheme<C2, H>,
+{
+    /// R1CS of the Augmented Function circuit
+    pub r1cs: R1CS<C1::ScalarField>,
+    /// R1CS of the CycleFold circuit
+    pub cf_r1cs: R1CS<C2::ScalarField>,
+    pub poseidon_config: PoseidonConfig<C1::ScalarField>,
+    /// CommitmentScheme::ProverParams over C1
+    pub cs_pp: CS1::ProverParams,
+    /// CycleFold CommitmentScheme::ProverParams, over C2
+    pub cf_cs_pp: CS2::ProverParams,
+    /// F circuit, the circuit that is being folded
+    pub F: FC,
+    /// public params hash
+    pub pp_hash: C1::ScalarField,
+    pub i: C1::ScalarField,
+    /// initial state
+    pub z_0: Vec<C1::ScalarField>,
+    /// current i-th state
+    pub z_i: Vec<C1::ScalarField>,
+    /// Ova instances
+    pub w_i: Witness<C1>,
+    pub u_i: CommittedInstance<C1>,
+    pub W_i: Witness<C1>,
+    pub U_i: CommittedInstance<C1>,
+
+    /// CycleFold running instance
+    pub cf_W_i: CycleFoldWitness<C2>,
+    pub cf_U_i: CycleFoldCommittedInstance<C2>,
+}
+
+impl<C1, C2, FC, CS1, CS2, const H: bool> FoldingScheme<C1, C2, FC> for Ova<C1, C2, FC, CS1, CS2, H>
+where
+    C1: Curve<BaseField = C2::ScalarField, ScalarField = C2::BaseField>,
+    C2: Curve,
+    FC: FCircuit<C1::ScalarField>,
+    CS1: CommitmentScheme<C1, H>,
+    CS2: CommitmentScheme<C2, H>,
+{
+    type PreprocessorParam = PreprocessorParam<C1, C2, FC, CS1, CS2, H>;
+    type ProverParam = ProverParams<C1, C2, CS1, CS2, H>;
+    type VerifierParam = VerifierParams<C1, C2, CS1, CS2, H>;
+    type RunningInstance = (CommittedInstance<C1>, Witness<C1>);
+    type IncomingInstance = (CommittedInstance<C1>, Witness<C1>);
+    type MultiCommittedInstanceWithWitness = ();
+    type CFInstance = (CycleFoldCommittedInstance<C2>, CycleFoldWitness<C2>);
+    type IVCProof = IVCProof<C1, C2>;
+
+    fn pp_deserialize_with_mode<R: std::io::prelude::Read>(
+        reader: R,
+        compress: ark_serialize::Compress,
+        validate: ark_serialize::Validate,
+        _fc_params: FC::Params, // FCircuit params
+    ) -> Result<Self::ProverParam, Error> {
+        Ok(Self::ProverParam::deserialize_with_mode(
+            reader, compress, validate,
+        )?)
+    }
+
+    fn vp_deserialize_with_mode<R: std::io::prelude::Read>(
+        mut reader: R,
+        compress: ark_serialize::Compress,
+        validate: ark_serialize::Validate,
+        fc_params: FC::Params,
+    ) -> Result<Self::VerifierParam, Error> {
+        let poseidon_config = poseidon_canonical_config::<C1::ScalarField>();
+
+        // generate the r1cs & cf_r1cs needed for the VerifierParams, so that they don't need to
+        // be serialized.
+        let (r1cs, cf_r1cs) = get_r1cs::<C1, C2, FC>(&poseidon_config, FC::new(fc_params)?)?;
+
+        let cs_vp = CS1::VerifierParams::deserialize_with_mode(&mut reader, compress, validate)?;
+        let cf_cs_vp = CS2::VerifierParams::deserialize_with_mode(&mut reader, compress, validate)?;
+
+        Ok(Self::VerifierParam {
+            poseidon_config,
+            r1cs,
+            cf_r1cs,
+            cs_vp,
+            cf_cs_vp,
+        })
+    }
+
+    fn preprocess(
+        mut rng: impl RngCore,
+        prep_param: &Self::PreprocessorParam,
+    ) -> Result<(Self::ProverParam, Self::VerifierParam), Error> {
+        let (r1cs, cf_r1cs) =
+            get_r1cs::<C1, C2, FC>(&prep_param.sponge_config, prep_param.F.clone())?;
+
+        // if cs params exist, use them, if not, generate new ones
+        let (cs_pp, cs_vp) = match (&prep_param.cs_pp, &prep_param.cs_vp) {
+            (Some(cs_pp), Some(cs_vp)) => (cs_pp.clone(), cs_vp.clone()),
+            // `CS1` commits to the concatenation of the witness vector `w`
+            // and the error term `e` (or the cross term `t`), whose length
+            // is the number of constraints.
+            _ => CS1::setup(&mut rng, r1cs.n_witnesses() + r1cs.n_constraints())?,
+        };
+        let (cf_cs_pp, cf_cs_vp) = match (&prep_param.cf_cs_pp, &prep_param.cf_cs_vp) {
+            (Some(cf_cs_pp), Some(cf_cs_vp)) => (cf_cs_pp.clone(), cf_cs_vp.clone()),
+            _ => CS2::setup(
+                &mut rng,
+                // `CS2` is for committing to CycleFold's witness vector `w` and
+                // error term `e`, where the length of `e` is the number of
+                // constraints, so we set `len` to the maximum of `e` and `w`'s
+                // lengths.
+                cf_r1cs.n_constraints().max(cf_r1cs.n_witnesses()),
+            )?,
+        };
+        let (cs_pp, cf_cs_pp) = prep_param.attach_fixed_base_tables(cs_pp, cf_cs_pp)?;
+
+        let prover_params = ProverParams::<C1, C2, CS1, CS2, H> {
+            sponge_config: prep_param.sponge_config.clone(),
+            cs_pp,
+            cf_cs_pp,
+        };
+        let verifier_params = VerifierParams::<C1, C2, CS1, CS2, H> {
+            sponge_config: prep_param.sponge_config.clone(),
+            r1cs,
+            cf_r1cs,
+            cs_vp,
+            cf_cs_vp,
+        };
+
+        Ok((prover_params, verifier_params))
+    }
+
+    /// Initializes the Ova+CycleFold's IVC for the given parameters and initial state `z_0`.
+    fn init(
+        params: &(Self::ProverParam, Self::VerifierParam),
+        F: FC,
+        z_0: Vec<C1::ScalarField>,
+    ) -> Result<Self, Error> {
+        let (pp, vp) = params;
+
+        let (r1cs, cf_r1cs) = get_r1cs::<C1, C2, FC>(&pp.sponge_config, F.clone())?;
+
+        // compute the public params hash
+        let pp_hash = vp.pp_hash()?;
+
+        // setup the dummy instances
+        let (W_dummy, U_dummy) = (Witness::dummy(&r1cs), CommittedInstance::dummy(&r1cs));
+        let (w_dummy, u_dummy) = (Witness::dummy(&r1cs), CommittedInstance::dummy(&r1cs));
+        let (cf_W_dummy, cf_U_dummy) = cf_r1cs.dummy_witness_instance();
+
+        Ok(Self {
+            r1cs,
+            cf_r1cs,
+            poseidon_config: pp.sponge_config.clone(),
+            cs_pp: pp.cs_pp.clone(),
+            cf_cs_pp: pp.cf_cs_pp.clone(),
+            F,
+            pp_hash,
+            i: C1::ScalarField::zero(),
+            z_0: z_0.clone(),
+            z_i: z_0,
+            w_i: w_dummy,
+            u_i: u_dummy,
+            W_i: W_dummy,
+            U_i: U_dummy,
+            // cyclefold running instance
+            cf_W_i: cf_W_dummy,
+            cf_U_i: cf_U_dummy,
+        })
+    }
+
+    /// Implements IVC.P of Ova+CycleFold
+    fn prove_step(
+        &mut self,
+        mut rng: impl RngCore,
+        external_inputs: FC::ExternalInputs,
+        // Ova does not support multi-instances folding
+        _other_instances: Option<Self::MultiCommittedInstanceWithWitness>,
+    ) -> Result<(), Error> {
+        // ensure that commitments are blinding if user has specified so.
+        if H && self.i >= C1::ScalarField::one() {
+            let blinding_commitments = if self.i == C1::ScalarField::one() {
+                // blinding values of the running instances are zero at the first iteration
+                vec![self.w_i.rW]
+            } else {
+                vec![self.w_i.rW, self.W_i.rW]
+            };
+            if blinding_commitments.contains(&C1::ScalarField::zero()) {
+                return Err(Error::IncorrectBlinding(
+                    H,
+                    format!("{blinding_commitments:?}"),
+                ));
+            }
+        }
+        // `sponge` is for digest computation.
+        let sponge = PoseidonSponge::<C1::ScalarField>::new(&self.poseidon_config);
+        // `transcript` is for challenge generation.
+        let mut transcript = sponge.clone();
+
+        let augmented_F_circuit: AugmentedFCircuit<C1, C2, FC>;
+
+        if _other_instances.is_some() {
+            return Err(Error::NoMultiInstances);
+        }
+
+        if self.z_i.len() != self.F.state_len() {
+            return Err(Error::NotSameLength(
+                "z_i.len()".to_string(),
+                self.z_i.len(),
+                "F.state_len()".to_string(),
+                self.F.state_len(),
+            ));
+        }
+
+        if self.i > C1::ScalarField::from_le_bytes_mod_order(&usize::MAX.to_le_bytes()) {
+            return Err(Error::MaxStep);
+        }
+
+        let i_usize;
+
+        #[cfg(target_pointer_width = "64")]
+        {
+            let mut i_bytes: [u8; 8] = [0; 8];
+            i_bytes.copy_from_slice(&self.i.into_bigint().to_bytes_le()[..8]);
+            i_usize = usize::from_le_bytes(i_bytes);
+        }
+
+        #[cfg(target_pointer_width = "32")]
+        {
+            let mut i_bytes: [u8; 4] = [0; 4];
+            i_bytes.copy_from_slice(&self.i.into_bigint().to_bytes_le()[..4]);
+            i_usize = usize::from_le_bytes(i_bytes);
+        }
+
+        let (W_i1, U_i1) = if self.i == C1::ScalarField::zero() {
+            // base case: there is nothing to fold yet, so U_1 and W_1 are the dummy instance and
+            // witness, which is also what the augmented circuit outputs in the base case. Notice
+            // that folding the dummy instances would not work here, since Ova's NIFS assumes
+            // `u_i.u = 1`.
+            augmented_F_circuit = AugmentedFCircuit::<C1, C2, FC> {
+                poseidon_config: self.poseidon_config.clone(),
+                pp_hash: Some(self.pp_hash),
+                i: Some(C1::ScalarField::zero()), // = i=0
+                i_usize: Some(0),
+                z_0: Some(self.z_0.clone()), // = z_i
+                z_i: Some(self.z_i.clone()),
+                external_inputs: Some(external_inputs.clone()),
+                u_i_cmWE: Some(self.u_i.cmWE),  // = dummy
+                U_i: Some(self.U_i.clone()),    // = dummy
+                U_i1_cmWE: Some(self.U_i.cmWE), // = dummy
+                F: self.F.clone(),
+                cf_u_i_cmW: None,
+                cf_U_i: None,
+                cf_cmT: None,
+            };
+
+            (self.W_i.clone(), self.U_i.clone())
+        } else {
+            // fold Ova instances
+            let (W_i1, U_i1, _, r_bits) =
+                NIFS::<C1, CS1, PoseidonSponge<C1::ScalarField>, H>::prove(
+                    &self.cs_pp,
+                    &self.r1cs,
+                    &mut transcript,
+                    self.pp_hash,
+                    &self.W_i,
+                    &self.U_i,
+                    &self.w_i,
+                    &self.u_i,
+                )?;
+
+            // CycleFold part:
+            let cf_circuit = NovaCycleFoldCircuit::<C1> {
+                r_bits: Some(r_bits),
+                points: Some(vec![self.U_i.cmWE, self.u_i.cmWE]),
+            };
+
+            // fold self.cf_U_i + cf_u_i -> cf_U_i1
+            let (cf_u_i, cf_W_i1, cf_U_i1, cf_cmT) =
+                fold_cyclefold_circuit::<NovaCycleFoldConfig<C1>, C2, CS2, H>(
+                    &mut transcript,
+                    self.cf_r1cs.clone(),
+                    self.cf_cs_pp.clone(),
+                    self.pp_hash,
+                    self.cf_W_i.clone(), // CycleFold running instance witness
+                    self.cf_U_i.clone(), // CycleFold running instance
+                    cf_circuit,
+                    &mut rng,
+                )?;
+
+            augmented_F_circuit = AugmentedFCircuit::<C1, C2, FC> {
+                poseidon_config: self.poseidon_config.clone(),
+                pp_hash: Some(self.pp_hash),
+                i: Some(self.i),
+                i_usize: Some(i_usize),
+                z_0: Some(self.z_0.clone()),
+                z_i: Some(self.z_i.clone()),
+                external_inputs: Some(external_inputs.clone()),
+                u_i_cmWE: Some(self.u_i.cmWE),
+                U_i: Some(self.U_i.clone()),
+                U_i1_cmWE: Some(U_i1.cmWE),
+                F: self.F.clone(),
+                // cyclefold values
+                cf_u_i_cmW: Some(cf_u_i.cmW),
+                cf_U_i: Some(self.cf_U_i.clone()),
+                cf_cmT: Some(cf_cmT),
+            };
+
+            self.cf_W_i = cf_W_i1;
+            self.cf_U_i = cf_U_i1;
+
+            (W_i1, U_i1)
+        };
+
+        let cs = ConstraintSystem::<C1::ScalarField>::new_ref();
+
+        let z_i1 = augmented_F_circuit
+            .compute_next_state(cs.clone())?
+            .value()?;
+
+        #[cfg(test)]
+        assert!(cs.is_satisfied()?);
+
+        let cs = cs.into_inner().ok_or(Error::NoInnerConstraintSystem)?;
+        let (w_i1, x_i1) = extract_w_x::<C1::ScalarField>(&cs);
+
+        #[cfg(test)]
+        if x_i1.len() != 2 {
+            return Err(Error::NotExpectedLength(x_i1.len(), 2));
+        }
+
+        // set values for next iteration
+        self.i += C1::ScalarField::one();
+        self.z_i = z_i1;
+        self.W_i = W_i1;
+        self.U_i = U_i1;
+        // the new incoming instance commits to its witness together with the cross term against
+        // the new running instance, which it will be folded with at the next step.
+        self.w_i = Witness::<C1>::new::<H>(w_i1, &mut rng);
+        let u_i = CommittedInstance {
+            u: C1::ScalarField::one(),
+            x: x_i1,
+            cmWE: C1::zero(),
+        };
+        let T = compute_T(&self.r1cs, &self.W_i, &self.U_i, &self.w_i, &u_i)?;
+        self.u_i = self.w_i.commit::<CS1, H>(&self.cs_pp, u_i.x, T)?;
+
+        #[cfg(test)]
+        {
+            self.u_i.check_incoming()?;
+            let z_u = [&[self.u_i.u][..], &self.u_i.x, &self.w_i.w].concat();
+            if self.r1cs.eval_at_z(&z_u)?.iter().any(|e| !e.is_zero()) {
+                return Err(Error::NotSatisfied);
+            }
+            let z_U = [&[self.U_i.u][..], &self.U_i.x, &self.W_i.w].concat();
+            let WE = [self.W_i.w.clone(), self.r1cs.eval_at_z(&z_U)?].concat();
+            assert_eq!(self.U_i.cmWE, CS1::commit(&self.cs_pp, &WE, &self.W_i.rW)?);
+        }
+
+        Ok(())
+    }
+
+    fn state(&self) -> Vec<C1::ScalarField> {
+        self.z_i.clone()
+    }
+
+    fn ivc_proof(&self) -> Self::IVCProof {
+        Self::IVCProof {
+            i: self.i,
+            z_0: self.z_0.clone(),
+            z_i: self.z_i.clone(),
+            W_i: self.W_i.clone(),
+            U_i: self.U_i.clone(),
+            w_i: self.w_i.clone(),
+            u_i: self.u_i.clone(),
+            cf_W_i: self.cf_W_i.clone(),
+            cf_U_i: self.cf_U_i.clone(),
+        }
+    }
+
+    fn from_ivc_proof(
+        ivc_proof: IVCProof<C1, C2>,
+        fcircuit_params: FC::Params,
+        params: (Self::ProverParam, Self::VerifierParam),
+    ) -> Result<Self, Error> {
+        let IVCProof {
+            i,
+            z_0,
+            z_i,
+            W_i,
+            U_i,
+            w_i,
+            u_i,
+            cf_W_i,
+            cf_U_i,
+        } = ivc_proof;
+        let (pp, vp) = params;
+
+        let f_circuit = FC::new(fcircuit_params)?;
+        let (r1cs, cf_r1cs) = get_r1cs::<C1, C2, FC>(&pp.sponge_config, f_circuit.clone())?;
+
+        Ok(Self {
+            r1cs,
+            cf_r1cs,
+            poseidon_config: pp.sponge_config,
+            cs_pp: pp.cs_pp,
+            cf_cs_pp: pp.cf_cs_pp,
+            F: f_circuit,
+            pp_hash: vp.pp_hash()?,
+            i,
+            z_0,
+            z_i,
+            w_i,
+            u_i,
+            W_i,
+            U_i,
+            cf_W_i,
+            cf_U_i,
+        })
+    }
+
+    /// Implements IVC.V of Ova+CycleFold. Notice that this method does not include the
+    /// commitments verification, and hence neither the check of the running instance, whose
+    /// error term is only bound by `cmWE`. Such checks are done by the Decider.
+    fn verify(vp: Self::VerifierParam, ivc_proof: Self::IVCProof) -> Result<(), Error> {
+        let Self::IVCProof {
+            i: num_steps,
+            z_0,
+            z_i,
+            U_i,
+            w_i,
+            u_i,
+            cf_W_i,
+            cf_U_i,
+            ..
+        } = ivc_proof;
+
+        let sponge = PoseidonSponge::<C1::ScalarField>::new(&vp.sponge_config);
+
+        if num_steps == C1::ScalarField::zero() {
+            if z_0 != z_i {
+                return Err(Error::IVCVerificationFail);
+            }
+            return Ok(());
+        }
+
+        if u_i.x.len() != 2 || U_i.x.len() != 2 {
+            return Err(Error::IVCVerificationFail);
+        }
+
+        let pp_hash = vp.pp_hash()?;
+
+        // check that u_i's output points to the running instance
+        // u_i.X[0] == H(i, z_0, z_i, U_i)
+        let expected_u_i_x = U_i.hash(&sponge, pp_hash, num_steps, &z_0, &z_i);
+        if expected_u_i_x != u_i.x[0] {
+            return Err(Error::IVCVerificationFail);
+        }
+        // u_i.X[1] == H(cf_U_i)
+        let expected_cf_u_i_x = cf_U_i.hash_cyclefold(&sponge, pp_hash);
+        if expected_cf_u_i_x != u_i.x[1] {
+            return Err(Error::IVCVerificationFail);
+        }
+
+        // check R1CS satisfiability, which is equivalent to checking if `u_i`
+        // is an incoming instance and if `w_i` and `u_i` satisfy R1CS
+        u_i.check_incoming()?;
+        let z_u = [&[u_i.u][..], &u_i.x, &w_i.w].concat();
+        if vp.r1cs.eval_at_z(&z_u)?.iter().any(|e| !e.is_zero()) {
+            return Err(Error::NotSatisfied);
+        }
+
+        // check CycleFold RelaxedR1CS satisfiability
+        vp.cf_r1cs.check_relation(&cf_W_i, &cf_U_i)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use ark_bn254::{Bn254, Fr, G1Projective as Projective};
+    use ark_grumpkin::Projective as Projective2;
+
+    use super::*;
+    use crate::commitment::{kzg::KZG, pedersen::Pedersen};
+    use crate::frontend::utils::CubicFCircuit;
+
+    type O<CS1, CS2, const H: bool> = Ova<Projective, Projective2, CubicFCircuit<Fr>, CS1, CS2, H>;
+
+    /// This test tests the Ova+CycleFold IVC, and by consequence it is also testing the
+    /// AugmentedFCircuit
+    #[test]
+    fn test_ivc() -> Result<(), Error> {
+        let poseidon_config = poseidon_canonical_config::<Fr>();
+
+        let F_circuit = CubicFCircuit::<Fr>::new(())?;
+
+        // run the test using Pedersen commitments on both sides of the curve cycle
+        test_ivc_opt::<Pedersen<Projective>, Pedersen<Projective2>, false>(
+            poseidon_config.clone(),
+            F_circuit,
+            3,
+        )?;
+
+        test_ivc_opt::<Pedersen<Projective, true>, Pedersen<Projective2, true>, true>(
+            poseidon_config.clone(),
+            F_circuit,
+            3,
+        )?;
+
+        // run the test using KZG for the commitments on the main curve, and Pedersen for the
+        // commitments on the secondary curve
+        test_ivc_opt::<KZG<Bn254>, Pedersen<Projective2>, false>(poseidon_config, F_circuit, 3)?;
+        Ok(())
+    }
+
+    // test_ivc allowing to choose the CommitmentSchemes
+    fn test_ivc_opt<
+        CS1: CommitmentScheme<Projective, H>,
+        CS2: CommitmentScheme<Projective2, H>,
+        const H: bool,
+    >(
+        poseidon_config: PoseidonConfig<Fr>,
+        F_circuit: CubicFCircuit<Fr>,
+        num_steps: usize,
+    ) -> Result<(), Error> {
+        let mut rng = ark_std::test_rng();
+
+        let prep_param = PreprocessorParam::new(poseidon_config, F_circuit);
+        let ova_params = O::<CS1, CS2, H>::preprocess(&mut rng, &prep_param)?;
+
+        let z_0 = vec![Fr::from(3_u32)];
+        let mut ova = O::<CS1, CS2, H>::init(&ova_params, F_circuit, z_0)?;
+
+        for _ in 0..num_steps {
+            ova.prove_step(&mut rng, (), None)?;
+        }
+        assert_eq!(Fr::from(num_steps as u32), ova.i);
+
+        // serialize the verifier params, and deserialize them
+        let mut ova_vp_serialized = vec![];
+        ova_params.1.serialize_compressed(&mut ova_vp_serialized)?;
+        let ova_vp_deserialized = O::<CS1, CS2, H>::vp_deserialize_with_mode(
+            &mut ova_vp_serialized.as_slice(),
+            ark_serialize::Compress::Yes,
+            ark_serialize::Validate::Yes,
+            (), // fcircuit_params
+        )?;
+
+        // serialize the IVCProof, and deserialize it
+        let ivc_proof = ova.ivc_proof();
+        let mut ivc_proof_serialized = vec![];
+        ivc_proof.serialize_compressed(&mut ivc_proof_serialized)?;
+        let ivc_proof_deserialized = IVCProof::<Projective, Projective2>::deserialize_compressed(
+            ivc_proof_serialized.as_slice(),
+        )?;
+        assert_eq!(ivc_proof, ivc_proof_deserialized);
+
+        // verify the deserialized IVCProof with the deserialized VerifierParams
+        O::<CS1, CS2, H>::verify(ova_vp_deserialized, ivc_proof_deserialized.clone())?;
+
+        // an unsatisfying incoming witness must be rejected
+        let mut tampered_ivc_proof = ivc_proof_deserialized;
+        tampered_ivc_proof.w_i.w[0] += Fr::one();
+        assert!(O::<CS1, CS2, H>::verify(ova_params.1, tampered_ivc_proof).is_err());
+        Ok(())
+    }
+}
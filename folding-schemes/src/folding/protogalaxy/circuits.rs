@@ -1,7 +1,5 @@
 use ark_crypto_primitives::sponge::{
-    constraints::CryptographicSpongeVar,
-    poseidon::{constraints::PoseidonSpongeVar, PoseidonConfig, PoseidonSponge},
-    CryptographicSponge,
+    constraints::CryptographicSpongeVar, poseidon::PoseidonSponge, CryptographicSponge,
 };
 use ark_ff::PrimeField;
 use ark_poly::{univariate::DensePolynomial, EvaluationDomain, GeneralEvaluationDomain};
@@ -24,6 +22,7 @@ use super::{
     CommittedInstance, CommittedInstanceVar, ProtoGalaxyCycleFoldConfig,
 };
 use crate::{
+    constants::PROTOGALAXY_FOLDING_DOMAIN,
     folding::{
         circuits::{
             cyclefold::{
@@ -36,7 +35,7 @@ use crate::{
         traits::{CommittedInstanceVarOps, Dummy},
     },
     frontend::FCircuit,
-    transcript::{AbsorbNonNativeGadget, TranscriptVar},
+    transcript::{AbsorbNonNativeGadget, SpongeWithGadget, TranscriptVar},
     utils::gadgets::VectorGadget,
     Curve,
 };
@@ -58,14 +57,17 @@ impl FoldingGadget {
         let t = instance.betas.len();
 
         // absorb the committed instances
-        transcript.absorb(instance)?;
-        transcript.absorb(&vec_instances)?;
+        transcript.domain_separator(PROTOGALAXY_FOLDING_DOMAIN)?;
+        transcript.absorb_labelled(b"instance", instance)?;
+        transcript.absorb_labelled(b"vec_instances", &vec_instances)?;
 
+        transcript.absorb_label(b"delta")?;
         let delta = transcript.get_challenge()?;
         let deltas = exponential_powers_var(delta, t);
 
-        transcript.absorb(&F_coeffs)?;
+        transcript.absorb_labelled(b"F_coeffs", &F_coeffs)?;
 
+        transcript.absorb_label(b"alpha")?;
         let alpha = transcript.get_challenge()?;
         let alphas = all_powers_var(alpha.clone(), t);
 
@@ -100,8 +102,9 @@ impl FoldingGadget {
         );
         let K_X = DensePolynomialVar { coeffs: K_coeffs };
 
-        transcript.absorb(&K_X.coeffs)?;
+        transcript.absorb_labelled(b"K_coeffs", &K_X.coeffs)?;
 
+        transcript.absorb_label(b"gamma")?;
         let gamma = transcript.get_challenge()?;
 
         let L_X_evals = L_X
@@ -175,7 +178,7 @@ impl AugmentationGadget {
         C2: Curve,
         S: CryptographicSponge,
     >(
-        transcript: &mut PoseidonSpongeVar<CF1<C1>>,
+        transcript: &mut impl TranscriptVar<CF1<C1>, S>,
         pp_hash: FpVar<CF1<C1>>,
         mut cf_U: CycleFoldCommittedInstanceVar<C2>,
         cf_u_cmWs: Vec<C2::Var>,
@@ -230,9 +233,17 @@ impl AugmentationGadget {
 /// Furthermore, to reduce circuit size over `C2`, we implement the constraints
 /// defined in [CycleFold](https://eprint.iacr.org/2023/1192.pdf). These extra
 /// constraints verify the correct folding of CycleFold instances.
-#[derive(Debug, Clone)]
-pub struct AugmentedFCircuit<C1: Curve, C2: Curve, FC: FCircuit<CF1<C1>>> {
-    pub(super) poseidon_config: PoseidonConfig<CF1<C1>>,
+///
+/// The instances are hashed and the challenges are derived in-circuit with the gadget of the
+/// sponge `T`, which must match the sponge used natively by `ProtoGalaxy`.
+#[derive(Clone)]
+pub struct AugmentedFCircuit<
+    C1: Curve,
+    C2: Curve,
+    FC: FCircuit<CF1<C1>>,
+    T: SpongeWithGadget<CF1<C1>> = PoseidonSponge<CF1<C1>>,
+> {
+    pub(super) sponge_config: T::Config,
     pub(super) pp_hash: CF1<C1>,
     pub(super) i: CF1<C1>,
     pub(super) i_usize: usize,
@@ -241,6 +252,9 @@ pub struct AugmentedFCircuit<C1: Curve, C2: Curve, FC: FCircuit<CF1<C1>>> {
     pub(super) external_inputs: FC::ExternalInputs,
     pub(super) F: FC, // F circuit
     pub(super) u_i_phi: C1,
+    /// the other `k - 1` incoming instances that are folded in the same step
+    /// together with `u_i`
+    pub(super) us: Vec<CommittedInstance<C1, false>>,
     pub(super) U_i: CommittedInstance<C1, true>,
     pub(super) U_i1_phi: C1,
     pub(super) F_coeffs: Vec<CF1<C1>>,
@@ -248,27 +262,23 @@ pub struct AugmentedFCircuit<C1: Curve, C2: Curve, FC: FCircuit<CF1<C1>>> {
 
     pub(super) phi_stars: Vec<C1>,
 
-    pub(super) cf1_u_i_cmW: C2,                        // input
-    pub(super) cf2_u_i_cmW: C2,                        // input
+    pub(super) cf_u_i_cmWs: Vec<C2>,                   // input
     pub(super) cf_U_i: CycleFoldCommittedInstance<C2>, // input
-    pub(super) cf1_cmT: C2,
-    pub(super) cf2_cmT: C2,
+    pub(super) cf_cmTs: Vec<C2>,
 }
 
-impl<C1: Curve, C2: Curve, FC: FCircuit<CF1<C1>>> AugmentedFCircuit<C1, C2, FC> {
-    pub fn empty(
-        poseidon_config: &PoseidonConfig<CF1<C1>>,
-        F_circuit: FC,
-        t: usize,
-        d: usize,
-        k: usize,
-    ) -> Self {
+impl<C1: Curve, C2: Curve, FC: FCircuit<CF1<C1>>, T: SpongeWithGadget<CF1<C1>>>
+    AugmentedFCircuit<C1, C2, FC, T>
+{
+    /// Creates an `AugmentedFCircuit` filled with dummy values, where `k` is
+    /// the number of incoming instances folded in each step.
+    pub fn empty(sponge_config: &T::Config, F_circuit: FC, t: usize, d: usize, k: usize) -> Self {
         let u_dummy = CommittedInstance::dummy((2, t));
         let cf_u_dummy =
             CycleFoldCommittedInstance::dummy(ProtoGalaxyCycleFoldConfig::<C1>::IO_LEN);
 
         Self {
-            poseidon_config: poseidon_config.clone(),
+            sponge_config: sponge_config.clone(),
             pp_hash: CF1::<C1>::zero(),
             i: CF1::<C1>::zero(),
             i_usize: 0,
@@ -276,6 +286,7 @@ impl<C1: Curve, C2: Curve, FC: FCircuit<CF1<C1>>> AugmentedFCircuit<C1, C2, FC>
             z_i: vec![CF1::<C1>::zero(); F_circuit.state_len()],
             external_inputs: FC::ExternalInputs::default(),
             u_i_phi: C1::zero(),
+            us: vec![CommittedInstance::dummy((2, 0)); k - 1],
             U_i: u_dummy,
             U_i1_phi: C1::zero(),
             F_coeffs: vec![CF1::<C1>::zero(); t],
@@ -283,20 +294,19 @@ impl<C1: Curve, C2: Curve, FC: FCircuit<CF1<C1>>> AugmentedFCircuit<C1, C2, FC>
             phi_stars: vec![C1::zero(); k],
             F: F_circuit,
             // cyclefold values
-            cf1_u_i_cmW: C2::zero(),
-            cf2_u_i_cmW: C2::zero(),
+            cf_u_i_cmWs: vec![C2::zero(); k + 1],
             cf_U_i: cf_u_dummy,
-            cf1_cmT: C2::zero(),
-            cf2_cmT: C2::zero(),
+            cf_cmTs: vec![C2::zero(); k + 1],
         }
     }
 }
 
-impl<C1, C2, FC> AugmentedFCircuit<C1, C2, FC>
+impl<C1, C2, FC, T> AugmentedFCircuit<C1, C2, FC, T>
 where
     C1: Curve<BaseField = C2::ScalarField, ScalarField = C2::BaseField>,
     C2: Curve,
     FC: FCircuit<CF1<C1>>,
+    T: SpongeWithGadget<CF1<C1>>,
 {
     pub fn compute_next_state(
         self,
@@ -312,6 +322,7 @@ where
         let u_dummy = CommittedInstance::<C1, true>::dummy((2, self.U_i.betas.len()));
         let U_i = CommittedInstanceVar::<C1, true>::new_witness(cs.clone(), || Ok(self.U_i))?;
         let u_i_phi = NonNativeAffineVar::new_witness(cs.clone(), || Ok(self.u_i_phi))?;
+        let us = Vec::<CommittedInstanceVar<C1, false>>::new_witness(cs.clone(), || Ok(self.us))?;
         let U_i1_phi = NonNativeAffineVar::new_witness(cs.clone(), || Ok(self.U_i1_phi))?;
         let phi_stars =
             Vec::<NonNativeAffineVar<C1>>::new_witness(cs.clone(), || Ok(self.phi_stars))?;
@@ -320,14 +331,14 @@ where
             CycleFoldCommittedInstance::dummy(ProtoGalaxyCycleFoldConfig::<C1>::IO_LEN);
         let cf_U_i =
             CycleFoldCommittedInstanceVar::<C2>::new_witness(cs.clone(), || Ok(self.cf_U_i))?;
-        let cf1_cmT = C2::Var::new_witness(cs.clone(), || Ok(self.cf1_cmT))?;
-        let cf2_cmT = C2::Var::new_witness(cs.clone(), || Ok(self.cf2_cmT))?;
+        let cf_u_i_cmWs = Vec::<C2::Var>::new_witness(cs.clone(), || Ok(self.cf_u_i_cmWs))?;
+        let cf_cmTs = Vec::<C2::Var>::new_witness(cs.clone(), || Ok(self.cf_cmTs))?;
 
         let F_coeffs = Vec::new_witness(cs.clone(), || Ok(self.F_coeffs))?;
         let K_coeffs = Vec::new_witness(cs.clone(), || Ok(self.K_coeffs))?;
 
         // `sponge` is for digest computation.
-        let sponge = PoseidonSpongeVar::<C1::ScalarField>::new(cs.clone(), &self.poseidon_config);
+        let sponge = T::Var::new(cs.clone(), &self.sponge_config);
         // `transcript` is for challenge generation.
         let mut transcript = sponge.clone();
 
@@ -340,13 +351,18 @@ where
         // u_i.x[1] = H(cf_U_i)
         let (cf_u_i_x, _) = cf_U_i.clone().hash(&sponge, pp_hash.clone())?;
 
-        // P.2. Prepare incoming primary instances
+        // P.2. Prepare incoming primary instances, where `u_i` comes first and
+        // is followed by the other `k - 1` incoming instances
+        let (u_phis, u_xs): (Vec<_>, Vec<_>) = [(u_i_phi, vec![u_i_x, cf_u_i_x])]
+            .into_iter()
+            .chain(us.into_iter().map(|u| (u.phi, u.x)))
+            .unzip();
         // P.3. Fold incoming primary instances into the running instance
         let (U_i1, r) = AugmentationGadget::prepare_and_fold_primary(
             &mut transcript,
             U_i.clone(),
-            vec![u_i_phi.clone()],
-            vec![vec![u_i_x, cf_u_i_x]],
+            u_phis.clone(),
+            u_xs,
             U_i1_phi,
             F_coeffs,
             K_coeffs,
@@ -388,64 +404,58 @@ where
         FpVar::new_input(cs.clone(), || x.value())?.enforce_equal(&x)?;
 
         // CycleFold part
-        // C.1. Compute cf1_u_i.x and cf2_u_i.x
-        let mut r0_bits = r[0].to_bits_le()?;
-        let mut r1_bits = r[1].to_bits_le()?;
-        r0_bits.resize(C1::ScalarField::MODULUS_BIT_SIZE as usize, Boolean::FALSE);
-        r1_bits.resize(C1::ScalarField::MODULUS_BIT_SIZE as usize, Boolean::FALSE);
-        let cf1_x = [
-            r0_bits
-                .chunks(C1::BaseField::MODULUS_BIT_SIZE as usize - 1)
-                .map(|bits| {
-                    let mut bits = bits.to_vec();
-                    bits.resize(C1::BaseField::MODULUS_BIT_SIZE as usize, Boolean::FALSE);
-                    NonNativeUintVar::from(&bits)
-                })
-                .collect::<Vec<_>>(),
-            vec![
-                NonNativeUintVar::new_constant(cs.clone(), C1::BaseField::zero())?,
-                NonNativeUintVar::new_constant(cs.clone(), C1::BaseField::zero())?,
-                U_i.phi.x.clone(),
-                U_i.phi.y.clone(),
-                phi_stars[0].x.clone(),
-                phi_stars[0].y.clone(),
-            ],
-        ]
-        .concat();
-        let cf2_x = [
-            r1_bits
-                .chunks(C1::BaseField::MODULUS_BIT_SIZE as usize - 1)
-                .map(|bits| {
-                    let mut bits = bits.to_vec();
-                    bits.resize(C1::BaseField::MODULUS_BIT_SIZE as usize, Boolean::FALSE);
-                    NonNativeUintVar::from(&bits)
-                })
-                .collect::<Vec<_>>(),
-            vec![
-                phi_stars[0].x.clone(),
-                phi_stars[0].y.clone(),
-                u_i_phi.x.clone(),
-                u_i_phi.y.clone(),
-                U_i1.phi.x.clone(),
-                U_i1.phi.y.clone(),
-            ],
+        // C.1. Compute cf_u_i.x for each CycleFold instance
+        // The `j`-th CycleFold circuit enforces the `j`-th step of the random
+        // linear combination of the commitments, i.e.,
+        // `phi_stars[j - 1] + phis[j] * r[j] == phi_stars[j]`, where `phis` is
+        // `[U_i.phi, u_phis[0], ..., u_phis[k - 1]]`, `phi_stars[-1]` is the
+        // point at infinity, and `phi_stars[k]` is `U_i1.phi`.
+        let phis = [vec![U_i.phi.clone()], u_phis].concat();
+        let phi_stars = [
+            vec![NonNativeAffineVar::new_constant(cs.clone(), C1::zero())?],
+            phi_stars,
+            vec![U_i1.phi.clone()],
         ]
         .concat();
+        let cf_u_i_xs = r
+            .iter()
+            .zip(&phis)
+            .zip(phi_stars.windows(2))
+            .map(|((r, phi), phi_stars)| -> Result<_, SynthesisError> {
+                let mut r_bits = r.to_bits_le()?;
+                r_bits.resize(C1::ScalarField::MODULUS_BIT_SIZE as usize, Boolean::FALSE);
+                Ok([
+                    r_bits
+                        .chunks(C1::BaseField::MODULUS_BIT_SIZE as usize - 1)
+                        .map(|bits| {
+                            let mut bits = bits.to_vec();
+                            bits.resize(C1::BaseField::MODULUS_BIT_SIZE as usize, Boolean::FALSE);
+                            NonNativeUintVar::from(&bits)
+                        })
+                        .collect::<Vec<_>>(),
+                    vec![
+                        phi_stars[0].x.clone(),
+                        phi_stars[0].y.clone(),
+                        phi.x.clone(),
+                        phi.y.clone(),
+                        phi_stars[1].x.clone(),
+                        phi_stars[1].y.clone(),
+                    ],
+                ]
+                .concat())
+            })
+            .collect::<Result<Vec<_>, SynthesisError>>()?;
 
         // C.2. Prepare incoming CycleFold instances
         // C.3. Fold incoming CycleFold instances into the running instance
-        let cf_U_i1 =
-            AugmentationGadget::prepare_and_fold_cyclefold::<C1, C2, PoseidonSponge<CF1<C1>>>(
-                &mut transcript,
-                pp_hash.clone(),
-                cf_U_i,
-                vec![
-                    C2::Var::new_witness(cs.clone(), || Ok(self.cf1_u_i_cmW))?,
-                    C2::Var::new_witness(cs.clone(), || Ok(self.cf2_u_i_cmW))?,
-                ],
-                vec![cf1_x, cf2_x],
-                vec![cf1_cmT, cf2_cmT],
-            )?;
+        let cf_U_i1 = AugmentationGadget::prepare_and_fold_cyclefold::<C1, C2, T>(
+            &mut transcript,
+            pp_hash.clone(),
+            cf_U_i,
+            cf_u_i_cmWs,
+            cf_u_i_xs,
+            cf_cmTs,
+        )?;
 
         // Back to Primary Part
         // P.4.b compute and check the second output of F'
@@ -471,11 +481,12 @@ where
     }
 }
 
-impl<C1, C2, FC> ConstraintSynthesizer<CF1<C1>> for AugmentedFCircuit<C1, C2, FC>
+impl<C1, C2, FC, T> ConstraintSynthesizer<CF1<C1>> for AugmentedFCircuit<C1, C2, FC, T>
 where
     C1: Curve<BaseField = C2::ScalarField, ScalarField = C2::BaseField>,
     C2: Curve,
     FC: FCircuit<CF1<C1>>,
+    T: SpongeWithGadget<CF1<C1>>,
 {
     fn generate_constraints(self, cs: ConstraintSystemRef<CF1<C1>>) -> Result<(), SynthesisError> {
         self.compute_next_state(cs).map(|_| ())
@@ -494,6 +505,7 @@ mod tests {
     };
 
     use ark_bn254::{Fr, G1Projective as Projective};
+    use ark_crypto_primitives::sponge::poseidon::constraints::PoseidonSpongeVar;
     use ark_relations::r1cs::ConstraintSystem;
 
     #[test]
@@ -2,6 +2,7 @@
 /// the Decider from decider.rs file will be more efficient.
 /// More details can be found at the documentation page:
 /// https://privacy-scaling-explorations.github.io/sonobe-docs/design/nova-decider-onchain.html
+use ark_crypto_primitives::sponge::poseidon::PoseidonSponge;
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
 use ark_snark::SNARK;
 use ark_std::{
@@ -16,8 +17,12 @@ use super::decider_eth_circuit::DeciderProtoGalaxyGadget;
 use super::ProtoGalaxy;
 use crate::arith::Arith;
 use crate::folding::traits::{InputizeNonNative, WitnessOps};
-use crate::folding::{circuits::decider::DeciderEnabledNIFS, traits::Dummy};
+use crate::folding::{
+    circuits::{decider::DeciderEnabledNIFS, CF1},
+    traits::Dummy,
+};
 use crate::frontend::FCircuit;
+use crate::transcript::SpongeWithGadget;
 use crate::Error;
 use crate::{
     commitment::{kzg::Proof as KZGProof, pedersen::Params as PedersenParams, CommitmentScheme},
@@ -26,35 +31,78 @@ use crate::{
 use crate::{Decider as DeciderTrait, FoldingScheme};
 
 #[derive(Debug, Clone, Eq, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(bound(
+        serialize = "CS::Proof: serde::Serialize",
+        deserialize = "CS::Proof: serde::Deserialize<'de>"
+    ))
+)]
 pub struct Proof<C, CS, S>
 where
     C: Curve,
     CS: CommitmentScheme<C, ProverChallenge = C::ScalarField, Challenge = C::ScalarField>,
     S: SNARK<C::ScalarField>,
 {
+    #[cfg_attr(feature = "serde", serde(with = "crate::utils::json::canonical"))]
     snark_proof: S::Proof,
     kzg_proofs: [CS::Proof; 1],
+    #[cfg_attr(feature = "serde", serde(with = "crate::utils::json::field_vec"))]
     L_X_evals: Vec<C::ScalarField>,
     // the KZG challenges are provided by the prover, but in-circuit they are checked to match
     // the in-circuit computed computed ones.
+    #[cfg_attr(feature = "serde", serde(with = "crate::utils::json::field_array"))]
     kzg_challenges: [C::ScalarField; 1],
 }
 
+impl<C, CS, S> Proof<C, CS, S>
+where
+    C: Curve,
+    CS: CommitmentScheme<C, ProverChallenge = C::ScalarField, Challenge = C::ScalarField>,
+    S: SNARK<C::ScalarField>,
+{
+    pub fn snark_proof(&self) -> &S::Proof {
+        &self.snark_proof
+    }
+
+    pub fn kzg_proofs(&self) -> &[CS::Proof; 1] {
+        &self.kzg_proofs
+    }
+
+    pub fn L_X_evals(&self) -> &[C::ScalarField] {
+        &self.L_X_evals
+    }
+
+    pub fn kzg_challenges(&self) -> [C::ScalarField; 1] {
+        self.kzg_challenges
+    }
+}
+
 #[derive(Debug, Clone, Eq, PartialEq, CanonicalSerialize, CanonicalDeserialize)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(bound = "")
+)]
 pub struct VerifierParam<C1, CS_VerifyingKey, S_VerifyingKey>
 where
     C1: Curve,
     CS_VerifyingKey: Clone + CanonicalSerialize + CanonicalDeserialize,
     S_VerifyingKey: Clone + CanonicalSerialize + CanonicalDeserialize,
 {
+    #[cfg_attr(feature = "serde", serde(with = "crate::utils::json::field"))]
     pub pp_hash: C1::ScalarField,
+    #[cfg_attr(feature = "serde", serde(with = "crate::utils::json::canonical"))]
     pub snark_vp: S_VerifyingKey,
+    #[cfg_attr(feature = "serde", serde(with = "crate::utils::json::canonical"))]
     pub cs_vp: CS_VerifyingKey,
 }
 
-/// Onchain Decider, for ethereum use cases
+/// Onchain Decider, for ethereum use cases, where `SP` is the sponge of the decided ProtoGalaxy
+/// instance
 #[derive(Clone, Debug)]
-pub struct Decider<C1, C2, FC, CS1, CS2, S, FS> {
+pub struct Decider<C1, C2, FC, CS1, CS2, S, FS, const K: usize = 1, SP = PoseidonSponge<CF1<C1>>> {
     _c1: PhantomData<C1>,
     _c2: PhantomData<C2>,
     _fc: PhantomData<FC>,
@@ -62,10 +110,11 @@ pub struct Decider<C1, C2, FC, CS1, CS2, S, FS> {
     _cs2: PhantomData<CS2>,
     _s: PhantomData<S>,
     _fs: PhantomData<FS>,
+    _sp: PhantomData<SP>,
 }
 
-impl<C1, C2, FC, CS1, CS2, S, FS> DeciderTrait<C1, C2, FC, FS>
-    for Decider<C1, C2, FC, CS1, CS2, S, FS>
+impl<C1, C2, FC, CS1, CS2, S, FS, const K: usize, SP> DeciderTrait<C1, C2, FC, FS>
+    for Decider<C1, C2, FC, CS1, CS2, S, FS, K, SP>
 where
     C1: Curve<BaseField = C2::ScalarField, ScalarField = C2::BaseField>,
     C2: Curve,
@@ -82,11 +131,12 @@ where
     S: SNARK<C1::ScalarField>,
     FS: FoldingScheme<C1, C2, FC>,
     // constrain FS into ProtoGalaxy, since this is a Decider specifically for ProtoGalaxy
-    ProtoGalaxy<C1, C2, FC, CS1, CS2>: From<FS>,
-    crate::folding::protogalaxy::ProverParams<C1, C2, CS1, CS2>:
+    ProtoGalaxy<C1, C2, FC, CS1, CS2, K, false, SP>: From<FS>,
+    crate::folding::protogalaxy::ProverParams<C1, C2, CS1, CS2, false, SP>:
         From<<FS as FoldingScheme<C1, C2, FC>>::ProverParam>,
-    crate::folding::protogalaxy::VerifierParams<C1, C2, CS1, CS2>:
+    crate::folding::protogalaxy::VerifierParams<C1, C2, CS1, CS2, false, SP>:
         From<<FS as FoldingScheme<C1, C2, FC>>::VerifierParam>,
+    SP: SpongeWithGadget<C1::ScalarField>,
 {
     type PreprocessorParam = ((FS::ProverParam, FS::VerifierParam), usize);
     type ProverParam = (S::ProvingKey, CS1::ProverParams);
@@ -100,32 +150,29 @@ where
         ((pp, vp), state_len): Self::PreprocessorParam,
     ) -> Result<(Self::ProverParam, Self::VerifierParam), Error> {
         // get the FoldingScheme prover & verifier params from ProtoGalaxy
-        let protogalaxy_pp: <ProtoGalaxy<C1, C2, FC, CS1, CS2> as FoldingScheme<
+        let protogalaxy_pp: <ProtoGalaxy<C1, C2, FC, CS1, CS2, K, false, SP> as FoldingScheme<
             C1,
             C2,
             FC,
         >>::ProverParam = pp.into();
-        let protogalaxy_vp: <ProtoGalaxy<C1, C2, FC, CS1, CS2> as FoldingScheme<
+        let protogalaxy_vp: <ProtoGalaxy<C1, C2, FC, CS1, CS2, K, false, SP> as FoldingScheme<
             C1,
             C2,
             FC,
         >>::VerifierParam = vp.into();
         let pp_hash = protogalaxy_vp.pp_hash()?;
 
-        // We fix `k`, the number of incoming instances, to 1, because
-        // multi-instances folding is not supported yet.
-        // TODO: Support multi-instances folding and make `k` a constant generic parameter (as in
-        // HyperNova). Tracking issue:
-        // https://github.com/privacy-scaling-explorations/sonobe/issues/82
+        // Regardless of `K`, the decider only folds the last incoming instance
+        // `u_i` into the running instance `U_i`, so `k` here is always 1.
         let k = 1;
         let d = protogalaxy_vp.r1cs.degree();
         let t = log2(protogalaxy_vp.r1cs.n_constraints()) as usize;
 
-        let circuit = DeciderEthCircuit::<C1, C2>::dummy((
+        let circuit = DeciderEthCircuit::<C1, C2, SP>::dummy((
             protogalaxy_vp.r1cs,
             protogalaxy_vp.cf_r1cs,
             protogalaxy_pp.cf_cs_params,
-            protogalaxy_pp.poseidon_config,
+            protogalaxy_pp.sponge_config,
             (t, d, k),
             k + 1, // `k + 1` is the length of `L_X_evals`
             state_len,
@@ -152,7 +199,7 @@ where
     ) -> Result<Self::Proof, Error> {
         let (snark_pk, cs_pk): (S::ProvingKey, CS1::ProverParams) = pp;
 
-        let circuit = DeciderEthCircuit::<C1, C2>::try_from(ProtoGalaxy::from(folding_scheme))?;
+        let circuit = DeciderEthCircuit::<C1, C2, SP>::try_from(ProtoGalaxy::from(folding_scheme))?;
 
         let L_X_evals = circuit.randomness.clone();
 
@@ -264,6 +311,7 @@ pub mod tests {
     use super::*;
     use crate::commitment::kzg::KZG;
     use crate::commitment::pedersen::Pedersen;
+    use crate::folding::nova::PreprocessorParam;
     use crate::folding::protogalaxy::ProverParams;
     use crate::folding::traits::CommittedInstanceOps;
     use crate::frontend::utils::CubicFCircuit;
@@ -272,6 +320,7 @@ pub mod tests {
 
     #[test]
     fn test_decider() -> Result<(), Error> {
+        const K: usize = 1;
         // use ProtoGalaxy as FoldingScheme
         type PG = ProtoGalaxy<
             Projective,
@@ -279,6 +328,7 @@ pub mod tests {
             CubicFCircuit<Fr>,
             KZG<'static, Bn254>,
             Pedersen<Projective2>,
+            K,
         >;
         type D = Decider<
             Projective,
@@ -288,6 +338,7 @@ pub mod tests {
             Pedersen<Projective2>,
             Groth16<Bn254>, // here we define the Snark to use in the decider
             PG,             // here we define the FoldingScheme to use
+            K,
         >;
 
         let mut rng = rand::rngs::OsRng;
@@ -296,7 +347,7 @@ pub mod tests {
         let F_circuit = CubicFCircuit::<Fr>::new(())?;
         let z_0 = vec![Fr::from(3_u32)];
 
-        let preprocessor_param = (poseidon_config, F_circuit);
+        let preprocessor_param = PreprocessorParam::new(poseidon_config, F_circuit);
         let protogalaxy_params = PG::preprocess(&mut rng, &preprocessor_param)?;
 
         let start = Instant::now();
@@ -347,6 +398,7 @@ pub mod tests {
     // deserialized values to continue the checks.
     #[test]
     fn test_decider_serialization() -> Result<(), Error> {
+        const K: usize = 1;
         // use ProtoGalaxy as FoldingScheme
         type PG = ProtoGalaxy<
             Projective,
@@ -354,6 +406,7 @@ pub mod tests {
             CubicFCircuit<Fr>,
             KZG<'static, Bn254>,
             Pedersen<Projective2>,
+            K,
         >;
         type D = Decider<
             Projective,
@@ -363,6 +416,7 @@ pub mod tests {
             Pedersen<Projective2>,
             Groth16<Bn254>, // here we define the Snark to use in the decider
             PG,             // here we define the FoldingScheme to use
+            K,
         >;
 
         let mut rng = rand::rngs::OsRng;
@@ -371,7 +425,7 @@ pub mod tests {
         let F_circuit = CubicFCircuit::<Fr>::new(())?;
         let z_0 = vec![Fr::from(3_u32)];
 
-        let preprocessor_param = (poseidon_config, F_circuit);
+        let preprocessor_param = PreprocessorParam::new(poseidon_config, F_circuit);
         let protogalaxy_params = PG::preprocess(&mut rng, &preprocessor_param)?;
 
         // prepare the Decider prover & verifier params
@@ -1,9 +1,7 @@
 /// This file implements the onchain (Ethereum's EVM) decider circuit. For non-ethereum use cases,
 /// other more efficient approaches can be used.
 use ark_crypto_primitives::sponge::{
-    constraints::CryptographicSpongeVar,
-    poseidon::{constraints::PoseidonSpongeVar, PoseidonSponge},
-    CryptographicSponge,
+    constraints::CryptographicSpongeVar, poseidon::PoseidonSponge, CryptographicSponge,
 };
 use ark_ff::PrimeField;
 use ark_r1cs_std::{
@@ -28,6 +26,7 @@ use crate::{
         traits::{WitnessOps, WitnessVarOps},
     },
     frontend::FCircuit,
+    transcript::{SpongeWithGadget, TranscriptVar},
     Curve, Error,
 };
 
@@ -68,7 +67,9 @@ impl<F: PrimeField> WitnessVarOps<F> for WitnessVar<F> {
     }
 }
 
-pub type DeciderEthCircuit<C1, C2> = GenericOnchainDeciderCircuit<
+/// Onchain decider circuit for ProtoGalaxy, where `SP` is the sponge of ProtoGalaxy, from which
+/// the KZG challenges are also derived.
+pub type DeciderEthCircuit<C1, C2, SP = PoseidonSponge<CF1<C1>>> = GenericOnchainDeciderCircuit<
     C1,
     C2,
     CommittedInstance<C1, RUNNING>,
@@ -77,6 +78,8 @@ pub type DeciderEthCircuit<C1, C2> = GenericOnchainDeciderCircuit<
     R1CS<CF1<C1>>,
     R1CSMatricesVar<CF1<C1>, FpVar<CF1<C1>>>,
     DeciderProtoGalaxyGadget,
+    PoseidonSponge<CF1<C1>>,
+    SP,
 >;
 
 /// returns an instance of the DeciderEthCircuit from the given ProtoGalaxy struct
@@ -87,12 +90,16 @@ impl<
         CS1: CommitmentScheme<C1, false>,
         // enforce that the CS2 is Pedersen commitment scheme, since we're at Ethereum's EVM decider
         CS2: CommitmentScheme<C2, false, ProverParams = PedersenParams<C2>>,
-    > TryFrom<ProtoGalaxy<C1, C2, FC, CS1, CS2>> for DeciderEthCircuit<C1, C2>
+        const K: usize,
+        SP: SpongeWithGadget<CF1<C1>>,
+    > TryFrom<ProtoGalaxy<C1, C2, FC, CS1, CS2, K, false, SP>> for DeciderEthCircuit<C1, C2, SP>
 {
     type Error = Error;
 
-    fn try_from(protogalaxy: ProtoGalaxy<C1, C2, FC, CS1, CS2>) -> Result<Self, Error> {
-        let mut transcript = PoseidonSponge::<C1::ScalarField>::new(&protogalaxy.poseidon_config);
+    fn try_from(
+        protogalaxy: ProtoGalaxy<C1, C2, FC, CS1, CS2, K, false, SP>,
+    ) -> Result<Self, Error> {
+        let mut transcript = SP::new(&protogalaxy.sponge_config);
 
         let (U_i1, W_i1, proof, aux) = Folding::prove(
             &mut transcript,
@@ -116,10 +123,11 @@ impl<
 
         Ok(Self {
             _avar: PhantomData,
+            _t: PhantomData,
             arith: protogalaxy.r1cs,
             cf_arith: protogalaxy.cf_r1cs,
             cf_pedersen_params: protogalaxy.cf_cs_params,
-            poseidon_config: protogalaxy.poseidon_config,
+            sponge_config: protogalaxy.sponge_config,
             pp_hash: protogalaxy.pp_hash,
             i: protogalaxy.i,
             z_0: protogalaxy.z_0,
@@ -156,9 +164,9 @@ impl<C: Curve>
     type Randomness = Vec<CF1<C>>;
     type RandomnessDummyCfg = usize;
 
-    fn fold_field_elements_gadget(
+    fn fold_field_elements_gadget<S: CryptographicSponge, T: TranscriptVar<CF1<C>, S>>(
         _arith: &R1CS<CF1<C>>,
-        transcript: &mut PoseidonSpongeVar<CF1<C>>,
+        transcript: &mut T,
         _pp_hash: FpVar<CF1<C>>,
         U: CommittedInstanceVar<C, RUNNING>,
         _U_vec: Vec<FpVar<CF1<C>>>,
@@ -198,6 +206,7 @@ pub mod tests {
 
     use super::*;
     use crate::commitment::pedersen::Pedersen;
+    use crate::folding::nova::PreprocessorParam;
     use crate::folding::protogalaxy::ProtoGalaxy;
     use crate::frontend::{utils::CubicFCircuit, FCircuit};
     use crate::transcript::poseidon::poseidon_canonical_config;
@@ -217,8 +226,12 @@ pub mod tests {
             CubicFCircuit<Fr>,
             Pedersen<Projective>,
             Pedersen<Projective2>,
+            1,
         >;
-        let pg_params = PG::preprocess(&mut rng, &(poseidon_config, F_circuit))?;
+        let pg_params = PG::preprocess(
+            &mut rng,
+            &PreprocessorParam::new(poseidon_config, F_circuit),
+        )?;
 
         // generate a Nova instance and do a step of it
         let mut protogalaxy = PG::init(&pg_params, F_circuit, z_0.clone())?;
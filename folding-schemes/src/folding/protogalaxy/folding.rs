@@ -12,6 +12,7 @@ use super::utils::{all_powers, betas_star, exponential_powers, pow_i};
 use super::ProtoGalaxyError;
 use super::{CommittedInstance, Witness};
 
+use crate::constants::PROTOGALAXY_FOLDING_DOMAIN;
 use crate::transcript::Transcript;
 use crate::utils::vec::*;
 use crate::Error;
@@ -103,9 +104,11 @@ impl<C: Curve> Folding<C> {
         }
 
         // absorb the committed instances
-        transcript.absorb(instance);
-        transcript.absorb(&vec_instances);
+        transcript.domain_separator(PROTOGALAXY_FOLDING_DOMAIN);
+        transcript.absorb_labelled(b"instance", instance);
+        transcript.absorb_labelled(b"vec_instances", &vec_instances);
 
+        transcript.absorb_label(b"delta");
         let delta = transcript.get_challenge();
         let deltas = exponential_powers(delta, t);
 
@@ -126,8 +129,9 @@ impl<C: Curve> Folding<C> {
         let F_X_dense = DensePolynomial::from(F_X.clone());
         let mut F_coeffs = F_X_dense.coeffs;
         F_coeffs.resize(t, C::ScalarField::zero());
-        transcript.absorb(&F_coeffs);
+        transcript.absorb_labelled(b"F_coeffs", &F_coeffs);
 
+        transcript.absorb_label(b"alpha");
         let alpha = transcript.get_challenge();
 
         // eval F(alpha)
@@ -216,8 +220,9 @@ impl<C: Curve> Folding<C> {
 
         let mut K_coeffs = K_X.coeffs.clone();
         K_coeffs.resize(d * k + 1, C::ScalarField::zero());
-        transcript.absorb(&K_coeffs);
+        transcript.absorb_labelled(b"K_coeffs", &K_coeffs);
 
+        transcript.absorb_label(b"gamma");
         let gamma = transcript.get_challenge();
 
         let L_X_evals = L_X
@@ -273,17 +278,35 @@ impl<C: Curve> Folding<C> {
         // polys from P
         proof: ProtoGalaxyProof<C::ScalarField>,
     ) -> Result<CommittedInstance<C, true>, Error> {
+        Self::verify_with_lagrange_evals(transcript, instance, vec_instances, proof)
+            .map(|(folded_instance, _)| folded_instance)
+    }
+
+    /// Same as [`Folding::verify`], but it also returns the evaluations of the Lagrange
+    /// polynomials at the challenge `gamma`, which the decider circuits take as public inputs.
+    pub fn verify_with_lagrange_evals(
+        transcript: &mut impl Transcript<C::ScalarField>,
+        // running instance
+        instance: &CommittedInstance<C, true>,
+        // incoming instances
+        vec_instances: &[CommittedInstance<C, false>],
+        // polys from P
+        proof: ProtoGalaxyProof<C::ScalarField>,
+    ) -> Result<(CommittedInstance<C, true>, Vec<C::ScalarField>), Error> {
         let t = instance.betas.len();
 
         // absorb the committed instances
-        transcript.absorb(instance);
-        transcript.absorb(&vec_instances);
+        transcript.domain_separator(PROTOGALAXY_FOLDING_DOMAIN);
+        transcript.absorb_labelled(b"instance", instance);
+        transcript.absorb_labelled(b"vec_instances", &vec_instances);
 
+        transcript.absorb_label(b"delta");
         let delta = transcript.get_challenge();
         let deltas = exponential_powers(delta, t);
 
-        transcript.absorb(&proof.F_coeffs);
+        transcript.absorb_labelled(b"F_coeffs", &proof.F_coeffs);
 
+        transcript.absorb_label(b"alpha");
         let alpha = transcript.get_challenge();
         let alphas = all_powers(alpha, t);
 
@@ -295,7 +318,7 @@ impl<C: Curve> Folding<C> {
 
         let betas_star = betas_star(&instance.betas, &deltas, alpha);
 
-        transcript.absorb(&proof.K_coeffs);
+        transcript.absorb_labelled(b"K_coeffs", &proof.K_coeffs);
 
         let k = vec_instances.len();
         let H =
@@ -305,6 +328,7 @@ impl<C: Curve> Folding<C> {
         let K_X: DensePolynomial<C::ScalarField> =
             DensePolynomial::<C::ScalarField>::from_coefficients_vec(proof.K_coeffs);
 
+        transcript.absorb_label(b"gamma");
         let gamma = transcript.get_challenge();
 
         let L_X_evals = L_X
@@ -326,12 +350,15 @@ impl<C: Curve> Folding<C> {
         }
 
         // return the folded instance
-        Ok(CommittedInstance {
-            betas: betas_star,
-            phi: phi_star,
-            e: e_star,
-            x: x_star,
-        })
+        Ok((
+            CommittedInstance {
+                betas: betas_star,
+                phi: phi_star,
+                e: e_star,
+                x: x_star,
+            },
+            L_X_evals,
+        ))
     }
 }
 
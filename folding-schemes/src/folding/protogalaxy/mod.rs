@@ -1,8 +1,5 @@
 /// Implements the scheme described in [ProtoGalaxy](https://eprint.iacr.org/2023/1106.pdf)
-use ark_crypto_primitives::sponge::{
-    poseidon::{PoseidonConfig, PoseidonSponge},
-    CryptographicSponge,
-};
+use ark_crypto_primitives::sponge::{poseidon::PoseidonSponge, CryptographicSponge};
 use ark_ff::{BigInteger, PrimeField};
 use ark_r1cs_std::{
     alloc::{AllocVar, AllocationMode},
@@ -16,7 +13,13 @@ use ark_relations::r1cs::{
 };
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, Valid};
 use ark_std::{
-    borrow::Borrow, cmp::max, fmt::Debug, log2, marker::PhantomData, rand::RngCore, One, Zero,
+    borrow::Borrow,
+    cmp::max,
+    fmt::{self, Debug, Formatter},
+    log2,
+    marker::PhantomData,
+    rand::RngCore,
+    One, UniformRand, Zero,
 };
 use constants::{INCOMING, RUNNING};
 use num_bigint::BigUint;
@@ -26,7 +29,7 @@ use crate::{
         r1cs::{extract_r1cs, extract_w_x, R1CS},
         Arith, ArithRelation,
     },
-    commitment::CommitmentScheme,
+    commitment::{CommitmentScheme, CommitmentSchemeId},
     folding::circuits::{
         cyclefold::{
             fold_cyclefold_circuit, CycleFoldCircuit, CycleFoldCommittedInstance, CycleFoldConfig,
@@ -35,23 +38,29 @@ use crate::{
         nonnative::affine::NonNativeAffineVar,
         CF1,
     },
+    folding::nova::PreprocessorParam,
     frontend::{utils::DummyCircuit, FCircuit},
-    transcript::poseidon::poseidon_canonical_config,
+    transcript::SpongeWithGadget,
     utils::pp_hash,
-    Curve, Error, FoldingScheme,
+    Curve, Error, FoldingScheme, MultiFolding,
 };
 
 pub mod circuits;
 pub mod constants;
+pub mod decider;
+pub mod decider_circuits;
 pub mod decider_eth;
 pub mod decider_eth_circuit;
 pub mod folding;
 pub mod traits;
 pub(crate) mod utils;
+pub mod zk;
 
 use circuits::AugmentedFCircuit;
 use folding::Folding;
 
+use super::checkpoint::Checkpoint;
+use super::envelope::Envelope;
 use super::traits::{
     CommittedInstanceOps, CommittedInstanceVarOps, Dummy, Inputize, WitnessOps, WitnessVarOps,
 };
@@ -77,10 +86,19 @@ pub type ProtoGalaxyCycleFoldCircuit<C> = CycleFoldCircuit<ProtoGalaxyCycleFoldC
 /// they have slightly different structures (e.g., length of `betas`) and
 /// behaviors (e.g., in satisfiability checks).
 #[derive(Clone, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(bound = "")
+)]
 pub struct CommittedInstance<C: Curve, const TYPE: bool> {
+    #[cfg_attr(feature = "serde", serde(with = "crate::utils::json::point"))]
     phi: C,
+    #[cfg_attr(feature = "serde", serde(with = "crate::utils::json::field_vec"))]
     betas: Vec<C::ScalarField>,
+    #[cfg_attr(feature = "serde", serde(with = "crate::utils::json::field"))]
     e: C::ScalarField,
+    #[cfg_attr(feature = "serde", serde(with = "crate::utils::json::field_vec"))]
     x: Vec<C::ScalarField>,
 }
 
@@ -224,20 +242,27 @@ impl<C: Curve, const TYPE: bool> CommittedInstanceVarOps<C> for CommittedInstanc
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, CanonicalSerialize, CanonicalDeserialize)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(bound = "")
+)]
 pub struct Witness<F: PrimeField> {
+    #[cfg_attr(feature = "serde", serde(with = "crate::utils::json::field_vec"))]
     w: Vec<F>,
+    #[cfg_attr(feature = "serde", serde(with = "crate::utils::json::field"))]
     r_w: F,
 }
 
 impl<F: PrimeField> Witness<F> {
-    pub fn new(w: Vec<F>) -> Self {
-        // note: at the current version, we don't use the blinding factors and we set them to 0
-        // always.
-        // Tracking issue: https://github.com/privacy-scaling-explorations/sonobe/issues/82
-        Self { w, r_w: F::zero() }
+    /// Creates a new witness from `w`. When `H` is set, the blinding factor
+    /// `r_w` is sampled at random, so that the commitment to `w` is hiding.
+    pub fn new<const H: bool>(w: Vec<F>, mut rng: impl RngCore) -> Self {
+        let r_w = if H { F::rand(&mut rng) } else { F::zero() };
+        Self { w, r_w }
     }
 
-    pub fn commit<CS: CommitmentScheme<C>, C: Curve<ScalarField = F>>(
+    pub fn commit<CS: CommitmentScheme<C, H>, C: Curve<ScalarField = F>, const H: bool>(
         &self,
         params: &CS::ProverParams,
         x: Vec<F>,
@@ -314,27 +339,45 @@ pub enum ProtoGalaxyError {
 }
 
 /// Proving parameters for ProtoGalaxy-based IVC
-#[derive(Debug, Clone)]
-pub struct ProverParams<C1, C2, CS1, CS2>
+#[derive(Clone)]
+pub struct ProverParams<C1, C2, CS1, CS2, const H: bool = false, T = PoseidonSponge<CF1<C1>>>
 where
     C1: Curve,
     C2: Curve,
-    CS1: CommitmentScheme<C1>,
-    CS2: CommitmentScheme<C2>,
+    CS1: CommitmentScheme<C1, H>,
+    CS2: CommitmentScheme<C2, H>,
+    T: SpongeWithGadget<C1::ScalarField>,
 {
-    /// Poseidon sponge configuration
-    pub poseidon_config: PoseidonConfig<C1::ScalarField>,
+    /// Configuration of the sponge `T`
+    pub sponge_config: T::Config,
     /// Proving parameters of the underlying commitment scheme over C1
     pub cs_params: CS1::ProverParams,
     /// Proving parameters of the underlying commitment scheme over C2
     pub cf_cs_params: CS2::ProverParams,
 }
-impl<C1, C2, CS1, CS2> CanonicalSerialize for ProverParams<C1, C2, CS1, CS2>
+impl<C1, C2, CS1, CS2, const H: bool, T> Debug for ProverParams<C1, C2, CS1, CS2, H, T>
 where
     C1: Curve,
     C2: Curve,
-    CS1: CommitmentScheme<C1, false>,
-    CS2: CommitmentScheme<C2, false>,
+    CS1: CommitmentScheme<C1, H>,
+    CS2: CommitmentScheme<C2, H>,
+    T: SpongeWithGadget<C1::ScalarField>,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ProverParams")
+            .field("sponge_config", &self.sponge_config)
+            .field("cs_params", &self.cs_params)
+            .field("cf_cs_params", &self.cf_cs_params)
+            .finish()
+    }
+}
+impl<C1, C2, CS1, CS2, const H: bool, T> CanonicalSerialize for ProverParams<C1, C2, CS1, CS2, H, T>
+where
+    C1: Curve,
+    C2: Curve,
+    CS1: CommitmentScheme<C1, H>,
+    CS2: CommitmentScheme<C2, H>,
+    T: SpongeWithGadget<C1::ScalarField>,
 {
     fn serialize_with_mode<W: std::io::prelude::Write>(
         &self,
@@ -349,32 +392,29 @@ where
         self.cs_params.serialized_size(compress) + self.cf_cs_params.serialized_size(compress)
     }
 }
-impl<C1, C2, CS1, CS2> Valid for ProverParams<C1, C2, CS1, CS2>
+impl<C1, C2, CS1, CS2, const H: bool, T> Valid for ProverParams<C1, C2, CS1, CS2, H, T>
 where
     C1: Curve,
     C2: Curve,
-    CS1: CommitmentScheme<C1>,
-    CS2: CommitmentScheme<C2>,
+    CS1: CommitmentScheme<C1, H>,
+    CS2: CommitmentScheme<C2, H>,
+    T: SpongeWithGadget<C1::ScalarField>,
 {
     fn check(&self) -> Result<(), ark_serialize::SerializationError> {
-        self.poseidon_config.full_rounds.check()?;
-        self.poseidon_config.partial_rounds.check()?;
-        self.poseidon_config.alpha.check()?;
-        self.poseidon_config.ark.check()?;
-        self.poseidon_config.mds.check()?;
-        self.poseidon_config.rate.check()?;
-        self.poseidon_config.capacity.check()?;
+        T::check_config(&self.sponge_config)?;
         self.cs_params.check()?;
         self.cf_cs_params.check()?;
         Ok(())
     }
 }
-impl<C1, C2, CS1, CS2> CanonicalDeserialize for ProverParams<C1, C2, CS1, CS2>
+impl<C1, C2, CS1, CS2, const H: bool, T> CanonicalDeserialize
+    for ProverParams<C1, C2, CS1, CS2, H, T>
 where
     C1: Curve,
     C2: Curve,
-    CS1: CommitmentScheme<C1, false>,
-    CS2: CommitmentScheme<C2, false>,
+    CS1: CommitmentScheme<C1, H>,
+    CS2: CommitmentScheme<C2, H>,
+    T: SpongeWithGadget<C1::ScalarField>,
 {
     fn deserialize_with_mode<R: std::io::prelude::Read>(
         mut reader: R,
@@ -385,7 +425,8 @@ where
         let cf_cs_params =
             CS2::ProverParams::deserialize_with_mode(&mut reader, compress, validate)?;
         Ok(ProverParams {
-            poseidon_config: poseidon_canonical_config::<C1::ScalarField>(),
+            sponge_config: T::default_config()
+                .map_err(|_| ark_serialize::SerializationError::InvalidData)?,
             cs_params,
             cf_cs_params,
         })
@@ -393,16 +434,17 @@ where
 }
 
 /// Verification parameters for ProtoGalaxy-based IVC
-#[derive(Debug, Clone)]
-pub struct VerifierParams<C1, C2, CS1, CS2>
+#[derive(Clone)]
+pub struct VerifierParams<C1, C2, CS1, CS2, const H: bool = false, T = PoseidonSponge<CF1<C1>>>
 where
     C1: Curve,
     C2: Curve,
-    CS1: CommitmentScheme<C1>,
-    CS2: CommitmentScheme<C2>,
+    CS1: CommitmentScheme<C1, H>,
+    CS2: CommitmentScheme<C2, H>,
+    T: SpongeWithGadget<C1::ScalarField>,
 {
-    /// Poseidon sponge configuration
-    pub poseidon_config: PoseidonConfig<C1::ScalarField>,
+    /// Configuration of the sponge `T`
+    pub sponge_config: T::Config,
     /// R1CS of the Augmented step circuit
     pub r1cs: R1CS<C1::ScalarField>,
     /// R1CS of the CycleFold circuit
@@ -413,12 +455,32 @@ where
     pub cf_cs_vp: CS2::VerifierParams,
 }
 
-impl<C1, C2, CS1, CS2> Valid for VerifierParams<C1, C2, CS1, CS2>
+impl<C1, C2, CS1, CS2, const H: bool, T> Debug for VerifierParams<C1, C2, CS1, CS2, H, T>
 where
     C1: Curve,
     C2: Curve,
-    CS1: CommitmentScheme<C1>,
-    CS2: CommitmentScheme<C2>,
+    CS1: CommitmentScheme<C1, H>,
+    CS2: CommitmentScheme<C2, H>,
+    T: SpongeWithGadget<C1::ScalarField>,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("VerifierParams")
+            .field("sponge_config", &self.sponge_config)
+            .field("r1cs", &self.r1cs)
+            .field("cf_r1cs", &self.cf_r1cs)
+            .field("cs_vp", &self.cs_vp)
+            .field("cf_cs_vp", &self.cf_cs_vp)
+            .finish()
+    }
+}
+
+impl<C1, C2, CS1, CS2, const H: bool, T> Valid for VerifierParams<C1, C2, CS1, CS2, H, T>
+where
+    C1: Curve,
+    C2: Curve,
+    CS1: CommitmentScheme<C1, H>,
+    CS2: CommitmentScheme<C2, H>,
+    T: SpongeWithGadget<C1::ScalarField>,
 {
     fn check(&self) -> Result<(), ark_serialize::SerializationError> {
         self.cs_vp.check()?;
@@ -426,12 +488,14 @@ where
         Ok(())
     }
 }
-impl<C1, C2, CS1, CS2> CanonicalSerialize for VerifierParams<C1, C2, CS1, CS2>
+impl<C1, C2, CS1, CS2, const H: bool, T> CanonicalSerialize
+    for VerifierParams<C1, C2, CS1, CS2, H, T>
 where
     C1: Curve,
     C2: Curve,
-    CS1: CommitmentScheme<C1>,
-    CS2: CommitmentScheme<C2>,
+    CS1: CommitmentScheme<C1, H>,
+    CS2: CommitmentScheme<C2, H>,
+    T: SpongeWithGadget<C1::ScalarField>,
 {
     fn serialize_with_mode<W: std::io::prelude::Write>(
         &self,
@@ -447,31 +511,38 @@ where
     }
 }
 
-impl<C1, C2, CS1, CS2> VerifierParams<C1, C2, CS1, CS2>
+impl<C1, C2, CS1, CS2, const H: bool, T> VerifierParams<C1, C2, CS1, CS2, H, T>
 where
     C1: Curve,
     C2: Curve,
-    CS1: CommitmentScheme<C1>,
-    CS2: CommitmentScheme<C2>,
+    CS1: CommitmentScheme<C1, H>,
+    CS2: CommitmentScheme<C2, H>,
+    T: SpongeWithGadget<C1::ScalarField>,
 {
     /// returns the hash of the public parameters of ProtoGalaxy
     pub fn pp_hash(&self) -> Result<C1::ScalarField, Error> {
-        // TODO: support hiding commitments in ProtoGalaxy. For now, `H` is set to false. Tracking
-        // issue: https://github.com/privacy-scaling-explorations/sonobe/issues/82
-        pp_hash::<C1, C2, CS1, CS2, false>(
+        pp_hash::<C1, C2, CS1, CS2, H, T>(
             &self.r1cs,
             &self.cf_r1cs,
             &self.cs_vp,
             &self.cf_cs_vp,
-            &self.poseidon_config,
+            &self.sponge_config,
         )
     }
 }
 
 #[derive(PartialEq, Eq, Debug, Clone, CanonicalSerialize, CanonicalDeserialize)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(bound = "")
+)]
 pub struct IVCProof<C1: Curve, C2: Curve> {
+    #[cfg_attr(feature = "serde", serde(with = "crate::utils::json::field"))]
     pub i: C1::ScalarField,
+    #[cfg_attr(feature = "serde", serde(with = "crate::utils::json::field_vec"))]
     pub z_0: Vec<C1::ScalarField>,
+    #[cfg_attr(feature = "serde", serde(with = "crate::utils::json::field_vec"))]
     pub z_i: Vec<C1::ScalarField>,
     pub W_i: Witness<C1::ScalarField>,
     pub U_i: CommittedInstance<C1, true>,
@@ -484,22 +555,41 @@ pub struct IVCProof<C1: Curve, C2: Curve> {
 /// Implements ProtoGalaxy+CycleFold's IVC, described in [ProtoGalaxy] and
 /// [CycleFold], following the FoldingScheme trait
 ///
+/// `K` is the number of incoming instances folded into the running instance
+/// at each step, i.e., the instance `u_i` output by the previous step plus
+/// `K - 1` other instances passed to `prove_step`. `K + 1` must be a power of
+/// two. The other instances are only folded from the second step on, i.e.,
+/// `prove_step` at `i = 0` takes `None`.
+///
 /// [ProtoGalaxy]: https://eprint.iacr.org/2023/1106.pdf
 /// [CycleFold]: https://eprint.iacr.org/2023/1192.pdf
-#[derive(Clone, Debug)]
-pub struct ProtoGalaxy<C1, C2, FC, CS1, CS2>
-where
+///
+/// The `T` type parameter selects the sponge used to hash the instances and to derive the
+/// challenges.
+#[derive(Clone)]
+pub struct ProtoGalaxy<
+    C1,
+    C2,
+    FC,
+    CS1,
+    CS2,
+    const K: usize = 1,
+    const H: bool = false,
+    T = PoseidonSponge<CF1<C1>>,
+> where
     C1: Curve,
     C2: Curve,
     FC: FCircuit<C1::ScalarField>,
-    CS1: CommitmentScheme<C1>,
-    CS2: CommitmentScheme<C2>,
+    CS1: CommitmentScheme<C1, H>,
+    CS2: CommitmentScheme<C2, H>,
+    T: SpongeWithGadget<C1::ScalarField>,
 {
     /// R1CS of the Augmented Function circuit
     pub r1cs: R1CS<C1::ScalarField>,
     /// R1CS of the CycleFold circuit
     pub cf_r1cs: R1CS<C2::ScalarField>,
-    pub poseidon_config: PoseidonConfig<C1::ScalarField>,
+    /// Configuration of the sponge `T`
+    pub sponge_config: T::Config,
     /// CommitmentScheme::ProverParams over C1
     pub cs_params: CS1::ProverParams,
     /// CycleFold CommitmentScheme::ProverParams, over C2
@@ -524,22 +614,131 @@ where
     pub cf_U_i: CycleFoldCommittedInstance<C2>,
 }
 
-impl<C1, C2, FC, CS1, CS2> ProtoGalaxy<C1, C2, FC, CS1, CS2>
+impl<C1, C2, FC, CS1, CS2, const K: usize, const H: bool, T> Debug
+    for ProtoGalaxy<C1, C2, FC, CS1, CS2, K, H, T>
+where
+    C1: Curve,
+    C2: Curve,
+    FC: FCircuit<C1::ScalarField>,
+    CS1: CommitmentScheme<C1, H>,
+    CS2: CommitmentScheme<C2, H>,
+    T: SpongeWithGadget<C1::ScalarField>,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ProtoGalaxy")
+            .field("r1cs", &self.r1cs)
+            .field("cf_r1cs", &self.cf_r1cs)
+            .field("sponge_config", &self.sponge_config)
+            .field("cs_params", &self.cs_params)
+            .field("cf_cs_params", &self.cf_cs_params)
+            .field("F", &self.F)
+            .field("pp_hash", &self.pp_hash)
+            .field("i", &self.i)
+            .field("z_0", &self.z_0)
+            .field("z_i", &self.z_i)
+            .field("w_i", &self.w_i)
+            .field("u_i", &self.u_i)
+            .field("W_i", &self.W_i)
+            .field("U_i", &self.U_i)
+            .field("cf_W_i", &self.cf_W_i)
+            .field("cf_U_i", &self.cf_U_i)
+            .finish()
+    }
+}
+
+impl<C1, C2, FC, CS1, CS2, const K: usize, const H: bool, T> MultiFolding<C1, C2, FC>
+    for ProtoGalaxy<C1, C2, FC, CS1, CS2, K, H, T>
 where
     C1: Curve<BaseField = C2::ScalarField, ScalarField = C2::BaseField>,
     C2: Curve,
     FC: FCircuit<C1::ScalarField>,
-    CS1: CommitmentScheme<C1>,
-    CS2: CommitmentScheme<C2>,
+    CS1: CommitmentScheme<C1, H>,
+    CS2: CommitmentScheme<C2, H>,
+    T: SpongeWithGadget<C1::ScalarField>,
+{
+    type RunningInstance = (CommittedInstance<C1, true>, Witness<C1::ScalarField>);
+    type IncomingInstance = (CommittedInstance<C1, false>, Witness<C1::ScalarField>);
+    type MultiInstance = Vec<Self::IncomingInstance>;
+
+    /// ProtoGalaxy folds a single running instance at each step, so there is
+    /// no need to create extra running instances.
+    fn new_running_instance(
+        &self,
+        _rng: impl RngCore,
+        _state: Vec<C1::ScalarField>,
+        _external_inputs: FC::ExternalInputs,
+    ) -> Result<Self::RunningInstance, Error> {
+        Err(Error::NotSupported(
+            "multiple running instances in ProtoGalaxy".to_string(),
+        ))
+    }
+
+    /// Creates a new incoming instance for the given state, which satisfies
+    /// the R1CS of the augmented circuit. This method can be used to generate
+    /// the 'other' incoming instances to be folded in the multi-instance
+    /// folding step.
+    fn new_incoming_instance(
+        &self,
+        mut rng: impl RngCore,
+        state: Vec<C1::ScalarField>,
+        external_inputs: FC::ExternalInputs,
+    ) -> Result<Self::IncomingInstance, Error> {
+        // run the augmented circuit in its base case with the given state,
+        // which gives us a fresh R1CS instance.
+        let mut augmented_F_circuit = AugmentedFCircuit::<C1, C2, FC, T>::empty(
+            &self.sponge_config,
+            self.F.clone(),
+            self.U_i.betas.len(),
+            self.r1cs.degree(),
+            K,
+        );
+        augmented_F_circuit.pp_hash = self.pp_hash;
+        augmented_F_circuit.z_0.clone_from(&self.z_0);
+        augmented_F_circuit.z_i = state;
+        augmented_F_circuit.external_inputs = external_inputs;
+
+        let cs = ConstraintSystem::<C1::ScalarField>::new_ref();
+        augmented_F_circuit.generate_constraints(cs.clone())?;
+
+        #[cfg(test)]
+        assert!(cs.is_satisfied()?);
+
+        let cs = cs.into_inner().ok_or(Error::NoInnerConstraintSystem)?;
+        let (w, x) = extract_w_x::<C1::ScalarField>(&cs);
+
+        let w = Witness::new::<H>(w, &mut rng);
+        let u = w.commit::<CS1, C1, H>(&self.cs_params, x)?;
+
+        #[cfg(test)]
+        {
+            u.check_incoming()?;
+            self.r1cs.check_relation(&w, &u)?;
+        }
+
+        Ok((u, w))
+    }
+}
+
+impl<C1, C2, FC, CS1, CS2, const K: usize, const H: bool, T>
+    ProtoGalaxy<C1, C2, FC, CS1, CS2, K, H, T>
+where
+    C1: Curve<BaseField = C2::ScalarField, ScalarField = C2::BaseField>,
+    C2: Curve,
+    FC: FCircuit<C1::ScalarField>,
+    CS1: CommitmentScheme<C1, H>,
+    CS2: CommitmentScheme<C2, H>,
+    T: SpongeWithGadget<C1::ScalarField>,
 {
     /// This method computes the parameter `t` in ProtoGalaxy for folding `F'`,
     /// the augmented circuit of `F`
-    fn compute_t(
-        poseidon_config: &PoseidonConfig<CF1<C1>>,
-        F: &FC,
-        d: usize,
-        k: usize,
-    ) -> Result<usize, Error> {
+    fn compute_t(sponge_config: &T::Config, F: &FC, d: usize, k: usize) -> Result<usize, Error> {
+        // ProtoGalaxy requires the number of instances being folded, i.e., the
+        // running instance and the `k` incoming instances, to be a power of
+        // two.
+        if k == 0 || !(k + 1).is_power_of_two() {
+            return Err(Error::ProtoGalaxy(ProtoGalaxyError::WrongNumInstances(k)));
+        }
+
         // In ProtoGalaxy, prover and verifier are parameterized by `t = log(n)`
         // where `n` is the number of constraints in the circuit (known as the
         // mapping `f` in the paper).
@@ -580,8 +779,8 @@ where
         // Compute `augmentation_constraints`, the size of `F'` without `F`.
         let cs = ConstraintSystem::<C1::ScalarField>::new_ref();
         cs.set_mode(SynthesisMode::Setup);
-        AugmentedFCircuit::<C1, C2, DummyCircuit>::empty(
-            poseidon_config,
+        AugmentedFCircuit::<C1, C2, DummyCircuit, T>::empty(
+            sponge_config,
             dummy_circuit.clone(),
             1,
             d,
@@ -604,8 +803,8 @@ where
         for t in t_lower_bound..=t_upper_bound {
             let cs = ConstraintSystem::<C1::ScalarField>::new_ref();
             cs.set_mode(SynthesisMode::Setup);
-            AugmentedFCircuit::<C1, C2, DummyCircuit>::empty(
-                poseidon_config,
+            AugmentedFCircuit::<C1, C2, DummyCircuit, T>::empty(
+                sponge_config,
                 dummy_circuit.clone(),
                 t,
                 d,
@@ -621,21 +820,23 @@ where
     }
 }
 
-impl<C1, C2, FC, CS1, CS2> FoldingScheme<C1, C2, FC> for ProtoGalaxy<C1, C2, FC, CS1, CS2>
+impl<C1, C2, FC, CS1, CS2, const K: usize, const H: bool, T> FoldingScheme<C1, C2, FC>
+    for ProtoGalaxy<C1, C2, FC, CS1, CS2, K, H, T>
 where
     C1: Curve<BaseField = C2::ScalarField, ScalarField = C2::BaseField>,
     C2: Curve,
     FC: FCircuit<C1::ScalarField>,
-    CS1: CommitmentScheme<C1>,
-    CS2: CommitmentScheme<C2>,
+    CS1: CommitmentScheme<C1, H>,
+    CS2: CommitmentScheme<C2, H>,
+    T: SpongeWithGadget<C1::ScalarField>,
 {
-    type PreprocessorParam = (PoseidonConfig<CF1<C1>>, FC);
-    type ProverParam = ProverParams<C1, C2, CS1, CS2>;
-    type VerifierParam = VerifierParams<C1, C2, CS1, CS2>;
+    /// Reuse Nova's PreprocessorParam.
+    type PreprocessorParam = PreprocessorParam<C1, C2, FC, CS1, CS2, H, T>;
+    type ProverParam = ProverParams<C1, C2, CS1, CS2, H, T>;
+    type VerifierParam = VerifierParams<C1, C2, CS1, CS2, H, T>;
     type RunningInstance = (CommittedInstance<C1, true>, Witness<C1::ScalarField>);
     type IncomingInstance = (CommittedInstance<C1, false>, Witness<C1::ScalarField>);
-    type MultiCommittedInstanceWithWitness =
-        (CommittedInstance<C1, false>, Witness<C1::ScalarField>);
+    type MultiCommittedInstanceWithWitness = Vec<Self::IncomingInstance>;
     type CFInstance = (CycleFoldCommittedInstance<C2>, CycleFoldWitness<C2>);
     type IVCProof = IVCProof<C1, C2>;
 
@@ -656,21 +857,20 @@ where
         validate: ark_serialize::Validate,
         fc_params: FC::Params,
     ) -> Result<Self::VerifierParam, Error> {
-        let poseidon_config = poseidon_canonical_config::<C1::ScalarField>();
+        let sponge_config = T::default_config()?;
 
         // generate the r1cs & cf_r1cs needed for the VerifierParams. In this way we avoid needing
         // to serialize them, saving significant space in the VerifierParams serialized size.
 
         let f_circuit = FC::new(fc_params)?;
-        let k = 1;
         let d = R1CS::<CF1<C1>>::empty().degree();
-        let t = Self::compute_t(&poseidon_config, &f_circuit, d, k)?;
+        let t = Self::compute_t(&sponge_config, &f_circuit, d, K)?;
 
         // main circuit R1CS:
         let cs = ConstraintSystem::<C1::ScalarField>::new_ref();
         cs.set_mode(SynthesisMode::Setup);
         let augmented_F_circuit =
-            AugmentedFCircuit::<C1, C2, FC>::empty(&poseidon_config, f_circuit.clone(), t, d, k);
+            AugmentedFCircuit::<C1, C2, FC, T>::empty(&sponge_config, f_circuit.clone(), t, d, K);
         augmented_F_circuit.generate_constraints(cs.clone())?;
         cs.finalize();
         let cs = cs.into_inner().ok_or(Error::NoInnerConstraintSystem)?;
@@ -689,7 +889,7 @@ where
         let cf_cs_vp = CS2::VerifierParams::deserialize_with_mode(&mut reader, compress, validate)?;
 
         Ok(Self::VerifierParam {
-            poseidon_config,
+            sponge_config,
             r1cs,
             cf_r1cs,
             cs_vp,
@@ -699,16 +899,11 @@ where
 
     fn preprocess(
         mut rng: impl RngCore,
-        (poseidon_config, F): &Self::PreprocessorParam,
+        prep_param: &Self::PreprocessorParam,
     ) -> Result<(Self::ProverParam, Self::VerifierParam), Error> {
-        // We fix `k`, the number of incoming instances, to 1, because
-        // multi-instances folding is not supported yet.
-        // TODO: Support multi-instances folding and make `k` a constant generic parameter (as in
-        // HyperNova). Tracking issue:
-        // https://github.com/privacy-scaling-explorations/sonobe/issues/82
-        let k = 1;
+        let (sponge_config, F) = (&prep_param.sponge_config, &prep_param.F);
         let d = R1CS::<CF1<C1>>::empty().degree();
-        let t = Self::compute_t(poseidon_config, F, d, k)?;
+        let t = Self::compute_t(sponge_config, F, d, K)?;
 
         // prepare the circuit to obtain its R1CS
         let cs = ConstraintSystem::<C1::ScalarField>::new_ref();
@@ -717,7 +912,7 @@ where
         cs2.set_mode(SynthesisMode::Setup);
 
         let augmented_F_circuit =
-            AugmentedFCircuit::<C1, C2, FC>::empty(poseidon_config, F.clone(), t, d, k);
+            AugmentedFCircuit::<C1, C2, FC, T>::empty(sponge_config, F.clone(), t, d, K);
         let cf_circuit = ProtoGalaxyCycleFoldCircuit::<C1>::empty();
 
         augmented_F_circuit.generate_constraints(cs.clone())?;
@@ -730,25 +925,34 @@ where
         let cs2 = cs2.into_inner().ok_or(Error::NoInnerConstraintSystem)?;
         let cf_r1cs = extract_r1cs::<C1::BaseField>(&cs2)?;
 
-        // `CS1` is for committing to ProtoGalaxy's witness vector `w`, so we
-        // set `len` to the number of witnesses in `r1cs`.
-        let (cs_pp, cs_vp) = CS1::setup(&mut rng, r1cs.n_witnesses())?;
-        // `CS2` is for committing to CycleFold's witness vector `w` and error
-        // term `e`, where the length of `e` is the number of constraints, so we
-        // set `len` to the maximum of `e` and `w`'s lengths.
-        let (cf_cs_pp, cf_cs_vp) = CS2::setup(
-            &mut rng,
-            max(cf_r1cs.n_constraints(), cf_r1cs.n_witnesses()),
-        )?;
+        // if cs params exist, use them, if not, generate new ones
+        let (cs_pp, cs_vp) = match (&prep_param.cs_pp, &prep_param.cs_vp) {
+            (Some(cs_pp), Some(cs_vp)) => (cs_pp.clone(), cs_vp.clone()),
+            // `CS1` is for committing to ProtoGalaxy's witness vector `w`, so we
+            // set `len` to the number of witnesses in `r1cs`.
+            _ => CS1::setup(&mut rng, r1cs.n_witnesses())?,
+        };
+        let (cf_cs_pp, cf_cs_vp) = match (&prep_param.cf_cs_pp, &prep_param.cf_cs_vp) {
+            (Some(cf_cs_pp), Some(cf_cs_vp)) => (cf_cs_pp.clone(), cf_cs_vp.clone()),
+            _ => CS2::setup(
+                &mut rng,
+                // `CS2` is for committing to CycleFold's witness vector `w` and
+                // error term `e`, where the length of `e` is the number of
+                // constraints, so we set `len` to the maximum of `e` and `w`'s
+                // lengths.
+                max(cf_r1cs.n_constraints(), cf_r1cs.n_witnesses()),
+            )?,
+        };
+        let (cs_pp, cf_cs_pp) = prep_param.attach_fixed_base_tables(cs_pp, cf_cs_pp)?;
 
         Ok((
             Self::ProverParam {
-                poseidon_config: poseidon_config.clone(),
+                sponge_config: sponge_config.clone(),
                 cs_params: cs_pp,
                 cf_cs_params: cf_cs_pp,
             },
             Self::VerifierParam {
-                poseidon_config: poseidon_config.clone(),
+                sponge_config: sponge_config.clone(),
                 r1cs,
                 cf_r1cs,
                 cs_vp,
@@ -777,7 +981,7 @@ where
         Ok(Self {
             r1cs: vp.r1cs.clone(),
             cf_r1cs: vp.cf_r1cs.clone(),
-            poseidon_config: pp.poseidon_config.clone(),
+            sponge_config: pp.sponge_config.clone(),
             cs_params: pp.cs_params.clone(),
             cf_cs_params: pp.cf_cs_params.clone(),
             F,
@@ -800,26 +1004,60 @@ where
         &mut self,
         mut rng: impl RngCore,
         external_inputs: FC::ExternalInputs,
-        _other_instances: Option<Self::MultiCommittedInstanceWithWitness>,
+        other_instances: Option<Self::MultiCommittedInstanceWithWitness>,
     ) -> Result<(), Error> {
-        // Multi-instances folding is not supported yet.
-        if _other_instances.is_some() {
-            return Err(Error::NoMultiInstances);
+        // recall, `K` is the number of all the incoming instances, including
+        // `u_i` that is not part of the `other_instances`, hence the +1 in the
+        // following check.
+        // At the base step (i = 0) there is no running instance to fold into,
+        // so the other instances are not expected.
+        let (us, ws): (Vec<_>, Vec<_>) = match other_instances {
+            Some(other_instances) if self.i.is_zero() => {
+                if !other_instances.is_empty() {
+                    return Err(Error::OtherInstancesAtBaseStep);
+                }
+                (vec![], vec![])
+            }
+            None if self.i.is_zero() => (vec![], vec![]),
+            Some(other_instances) => {
+                if other_instances.len() + 1 != K {
+                    return Err(Error::NotSameLength(
+                        "other_instances.len()".to_string(),
+                        other_instances.len(),
+                        "protogalaxy.k".to_string(),
+                        K,
+                    ));
+                }
+                other_instances.into_iter().unzip()
+            }
+            None if K == 1 => (vec![], vec![]),
+            None => return Err(Error::MissingOtherInstances(1, K)),
+        };
+
+        // ensure that commitments are blinding if user has specified so.
+        if H && self.i >= C1::ScalarField::one() {
+            let mut blinding_commitments = if self.i == C1::ScalarField::one() {
+                // blinding value of the running instance is zero at the first iteration
+                vec![self.w_i.r_w]
+            } else {
+                vec![self.w_i.r_w, self.W_i.r_w]
+            };
+            blinding_commitments.extend(ws.iter().map(|w| w.r_w));
+            if blinding_commitments.contains(&C1::ScalarField::zero()) {
+                return Err(Error::IncorrectBlinding(
+                    H,
+                    format!("{blinding_commitments:?}"),
+                ));
+            }
         }
-        // We fix `k`, the number of incoming instances, to 1, because
-        // multi-instances folding is not supported yet.
-        // TODO: Support multi-instances folding and make `k` a constant generic parameter (as in
-        // HyperNova). Tracking issue:
-        // https://github.com/privacy-scaling-explorations/sonobe/issues/82
-        let k = 1;
         let d = self.r1cs.degree();
 
         // `sponge` is for digest computation.
-        let sponge = PoseidonSponge::<C1::ScalarField>::new(&self.poseidon_config);
+        let sponge = T::new(&self.sponge_config);
         // `transcript` is for challenge generation.
         let mut transcript_prover = sponge.clone();
 
-        let mut augmented_F_circuit: AugmentedFCircuit<C1, C2, FC>;
+        let mut augmented_F_circuit: AugmentedFCircuit<C1, C2, FC, T>;
 
         if self.z_i.len() != self.F.state_len() {
             return Err(Error::NotSameLength(
@@ -835,11 +1073,11 @@ where
 
         if self.i.is_zero() {
             augmented_F_circuit = AugmentedFCircuit::empty(
-                &self.poseidon_config,
+                &self.sponge_config,
                 self.F.clone(),
                 self.U_i.betas.len(),
                 d,
-                k,
+                K,
             );
             augmented_F_circuit.pp_hash = self.pp_hash;
             augmented_F_circuit.z_0.clone_from(&self.z_0);
@@ -851,56 +1089,61 @@ where
         // There is no need to update `self.U_i` etc. as they are unchanged.
         } else {
             // Primary part:
-            // Compute `U_{i+1}` by folding `u_i` into `U_i`.
+            // Compute `U_{i+1}` by folding `u_i` and the other incoming
+            // instances `us` into `U_i`.
+            let all_us = [vec![self.u_i.clone()], us].concat();
+            let all_ws = [vec![self.w_i.clone()], ws].concat();
             let (U_i1, W_i1, proof, aux) = Folding::prove(
                 &mut transcript_prover,
                 &self.r1cs,
                 &self.U_i,
                 &self.W_i,
-                &[self.u_i.clone()],
-                &[self.w_i.clone()],
+                &all_us,
+                &all_ws,
             )?;
 
             // CycleFold part:
-            let mut r0_bits = aux.L_X_evals[0].into_bigint().to_bits_le();
-            let mut r1_bits = aux.L_X_evals[1].into_bigint().to_bits_le();
-            r0_bits.resize(C1::ScalarField::MODULUS_BIT_SIZE as usize, false);
-            r1_bits.resize(C1::ScalarField::MODULUS_BIT_SIZE as usize, false);
-
-            // cyclefold circuit for enforcing:
-            // 0 + U_i.phi * L_evals[0] == phi_stars[0]
-            let cf1_circuit = ProtoGalaxyCycleFoldCircuit::<C1> {
-                r_bits: Some(r0_bits),
-                points: Some(vec![C1::zero(), self.U_i.phi]),
-            };
-
-            // cyclefold circuit for enforcing:
-            // phi_stars[0] + u_i.phi * L_evals[1] == U_i1.phi
-            // i.e., U_i.phi * L_evals[0] + u_i.phi * L_evals[1] == U_i1.phi
-            let cf2_circuit = ProtoGalaxyCycleFoldCircuit::<C1> {
-                r_bits: Some(r1_bits),
-                points: Some(vec![aux.phi_stars[0], self.u_i.phi]),
-            };
-
-            // fold self.cf_U_i + cf1_U -> folded running with cf1
-            let (cf1_u_i, cf1_W_i1, cf1_U_i1, cf1_cmT) = self.fold_cyclefold_circuit(
-                &mut transcript_prover,
-                self.cf_W_i.clone(), // CycleFold running instance witness
-                self.cf_U_i.clone(), // CycleFold running instance
-                cf1_circuit,
-                &mut rng,
-            )?;
-            // fold [the output from folding self.cf_U_i + cf1_U] + cf2_U = folded_running_with_cf1 + cf2
-            let (cf2_u_i, cf_W_i1, cf_U_i1, cf2_cmT) = self.fold_cyclefold_circuit(
-                &mut transcript_prover,
-                cf1_W_i1,
-                cf1_U_i1.clone(),
-                cf2_circuit,
-                &mut rng,
-            )?;
+            // We need `K + 1` cyclefold circuits to compute
+            // U_i.phi * L_evals[0] + \sum_j all_us[j].phi * L_evals[j + 1] == U_i1.phi
+            // where the `j`-th circuit enforces
+            // phi_stars[j - 1] + phis[j] * L_evals[j] == phi_stars[j]
+            // with `phis = [U_i.phi, all_us[0].phi, ..., all_us[K - 1].phi]`,
+            // `phi_stars[-1] = 0` and `phi_stars[K] = U_i1.phi`.
+            let phis = [
+                vec![self.U_i.phi],
+                all_us.iter().map(|u| u.phi).collect::<Vec<_>>(),
+            ]
+            .concat();
+            let phi_stars = [vec![C1::zero()], aux.phi_stars.clone(), vec![U_i1.phi]].concat();
+
+            let mut cf_W_i1 = self.cf_W_i.clone(); // CycleFold running instance witness
+            let mut cf_U_i1 = self.cf_U_i.clone(); // CycleFold running instance
+            let mut cf_u_i_cmWs = vec![];
+            let mut cf_cmTs = vec![];
+            for ((r, phi), phi_stars) in aux.L_X_evals.iter().zip(&phis).zip(phi_stars.windows(2)) {
+                let mut r_bits = r.into_bigint().to_bits_le();
+                r_bits.resize(C1::ScalarField::MODULUS_BIT_SIZE as usize, false);
+
+                let cf_circuit = ProtoGalaxyCycleFoldCircuit::<C1> {
+                    r_bits: Some(r_bits),
+                    points: Some(vec![phi_stars[0], *phi]),
+                };
+
+                // fold the current cyclefold circuit into the CycleFold running instance
+                let (cf_u_i, cf_cmT);
+                (cf_u_i, cf_W_i1, cf_U_i1, cf_cmT) = self.fold_cyclefold_circuit(
+                    &mut transcript_prover,
+                    cf_W_i1,
+                    cf_U_i1,
+                    cf_circuit,
+                    &mut rng,
+                )?;
+                cf_u_i_cmWs.push(cf_u_i.cmW);
+                cf_cmTs.push(cf_cmT);
+            }
 
             augmented_F_circuit = AugmentedFCircuit {
-                poseidon_config: self.poseidon_config.clone(),
+                sponge_config: self.sponge_config.clone(),
                 pp_hash: self.pp_hash,
                 i: self.i,
                 i_usize,
@@ -908,6 +1151,7 @@ where
                 z_i: self.z_i.clone(),
                 external_inputs: external_inputs.clone(),
                 u_i_phi: self.u_i.phi,
+                us: all_us[1..].to_vec(),
                 U_i: self.U_i.clone(),
                 U_i1_phi: U_i1.phi,
                 F_coeffs: proof.F_coeffs.clone(),
@@ -915,23 +1159,16 @@ where
                 phi_stars: aux.phi_stars,
                 F: self.F.clone(),
                 // cyclefold values
-                cf1_u_i_cmW: cf1_u_i.cmW,
-                cf2_u_i_cmW: cf2_u_i.cmW,
+                cf_u_i_cmWs,
                 cf_U_i: self.cf_U_i.clone(),
-                cf1_cmT,
-                cf2_cmT,
+                cf_cmTs,
             };
 
             #[cfg(test)]
             {
                 let mut transcript_verifier = sponge.clone();
                 assert_eq!(
-                    Folding::verify(
-                        &mut transcript_verifier,
-                        &self.U_i,
-                        &[self.u_i.clone()],
-                        proof
-                    )?,
+                    Folding::verify(&mut transcript_verifier, &self.U_i, &all_us, proof)?,
                     U_i1
                 );
             }
@@ -962,8 +1199,8 @@ where
         // set values for next iteration
         self.i += C1::ScalarField::one();
         self.z_i = z_i1;
-        self.w_i = Witness::new(w_i1);
-        self.u_i = self.w_i.commit::<CS1, C1>(&self.cs_params, x_i1)?;
+        self.w_i = Witness::new::<H>(w_i1, &mut rng);
+        self.u_i = self.w_i.commit::<CS1, C1, H>(&self.cs_params, x_i1)?;
 
         #[cfg(test)]
         {
@@ -1016,7 +1253,7 @@ where
         Ok(Self {
             r1cs: vp.r1cs.clone(),
             cf_r1cs: vp.cf_r1cs.clone(),
-            poseidon_config: pp.poseidon_config,
+            sponge_config: pp.sponge_config,
             cs_params: pp.cs_params,
             cf_cs_params: pp.cf_cs_params,
             F: f_circuit,
@@ -1047,7 +1284,7 @@ where
             cf_U_i,
         } = ivc_proof;
 
-        let sponge = PoseidonSponge::<C1::ScalarField>::new(&vp.poseidon_config);
+        let sponge = T::new(&vp.sponge_config);
 
         if u_i.x.len() != 2 || U_i.x.len() != 2 {
             return Err(Error::IVCVerificationFail);
@@ -1081,19 +1318,62 @@ where
     }
 }
 
-impl<C1, C2, FC, CS1, CS2> ProtoGalaxy<C1, C2, FC, CS1, CS2>
+impl<C1, C2, FC, CS1, CS2, const K: usize, const H: bool, T> Checkpoint<C1, C2, FC>
+    for ProtoGalaxy<C1, C2, FC, CS1, CS2, K, H, T>
+where
+    C1: Curve<BaseField = C2::ScalarField, ScalarField = C2::BaseField>,
+    C2: Curve,
+    FC: FCircuit<C1::ScalarField>,
+    CS1: CommitmentScheme<C1, H>,
+    CS2: CommitmentScheme<C2, H>,
+    T: SpongeWithGadget<C1::ScalarField>,
+{
+    fn pp_hash(&self) -> C1::ScalarField {
+        self.pp_hash
+    }
+}
+
+impl<C1, C2, FC, CS1, CS2, const K: usize, const H: bool, T> Envelope<C1, C2, FC>
+    for ProtoGalaxy<C1, C2, FC, CS1, CS2, K, H, T>
+where
+    C1: Curve<BaseField = C2::ScalarField, ScalarField = C2::BaseField>,
+    C2: Curve,
+    FC: FCircuit<C1::ScalarField>,
+    CS1: CommitmentScheme<C1, H> + CommitmentSchemeId,
+    CS2: CommitmentScheme<C2, H> + CommitmentSchemeId,
+    T: SpongeWithGadget<C1::ScalarField>,
+{
+    fn scheme_id() -> String {
+        format!("protogalaxy-k{}-{}", K, T::ID)
+    }
+
+    fn commitment_ids() -> [(String, bool); 2] {
+        [
+            (CS1::ID.to_string(), CS1::is_hiding()),
+            (CS2::ID.to_string(), CS2::is_hiding()),
+        ]
+    }
+
+    fn vp_pp_hash(vp: &Self::VerifierParam) -> Result<C1::ScalarField, Error> {
+        vp.pp_hash()
+    }
+}
+
+impl<C1, C2, FC, CS1, CS2, const K: usize, const H: bool, T>
+    ProtoGalaxy<C1, C2, FC, CS1, CS2, K, H, T>
 where
     C1: Curve<BaseField = C2::ScalarField, ScalarField = C2::BaseField>,
     C2: Curve,
     FC: FCircuit<C1::ScalarField>,
-    CS1: CommitmentScheme<C1>,
-    CS2: CommitmentScheme<C2>,
+    CS1: CommitmentScheme<C1, H>,
+    CS2: CommitmentScheme<C2, H>,
+    T: SpongeWithGadget<C1::ScalarField>,
 {
     // folds the given cyclefold circuit and its instances
     #[allow(clippy::type_complexity)]
     fn fold_cyclefold_circuit(
         &self,
-        transcript: &mut PoseidonSponge<C1::ScalarField>,
+        transcript: &mut T,
         cf_W_i: CycleFoldWitness<C2>, // witness of the running instance
         cf_U_i: CycleFoldCommittedInstance<C2>, // running instance
         cf_circuit: ProtoGalaxyCycleFoldCircuit<C1>,
@@ -1107,7 +1387,7 @@ where
         ),
         Error,
     > {
-        fold_cyclefold_circuit::<ProtoGalaxyCycleFoldConfig<C1>, C2, CS2, false>(
+        fold_cyclefold_circuit::<ProtoGalaxyCycleFoldConfig<C1>, C2, CS2, H>(
             transcript,
             self.cf_r1cs.clone(),
             self.cf_cs_params.clone(),
@@ -1121,10 +1401,11 @@ where
 }
 
 #[cfg(test)]
-mod tests {
+pub mod tests {
     use super::*;
 
     use ark_bn254::{Bn254, Fr, G1Projective as Projective};
+    use ark_crypto_primitives::sponge::poseidon::PoseidonConfig;
     use ark_grumpkin::Projective as Projective2;
     use ark_std::test_rng;
     use rayon::prelude::*;
@@ -1144,36 +1425,107 @@ mod tests {
         let F_circuit = CubicFCircuit::<Fr>::new(())?;
 
         // run the test using Pedersen commitments on both sides of the curve cycle
-        let _ = test_ivc_opt::<Pedersen<Projective>, Pedersen<Projective2>>(
+        let _ = test_ivc_opt::<Pedersen<Projective>, Pedersen<Projective2>, 1, false>(
+            poseidon_config.clone(),
+            F_circuit,
+            3,
+        )?;
+        // run the test using hiding Pedersen commitments on both sides of the curve cycle
+        let _ = test_ivc_opt::<Pedersen<Projective, true>, Pedersen<Projective2, true>, 1, true>(
             poseidon_config.clone(),
             F_circuit,
+            3,
         )?;
         // run the test using KZG for the commitments on the main curve, and Pedersen for the
         // commitments on the secondary curve
-        let _ = test_ivc_opt::<KZG<Bn254>, Pedersen<Projective2>>(poseidon_config, F_circuit)?;
+        let _ = test_ivc_opt::<KZG<Bn254>, Pedersen<Projective2>, 1, false>(
+            poseidon_config.clone(),
+            F_circuit,
+            3,
+        )?;
+        // run the test folding multiple incoming instances at each step
+        let _ = test_ivc_opt::<Pedersen<Projective>, Pedersen<Projective2>, 3, false>(
+            poseidon_config,
+            F_circuit,
+            3,
+        )?;
         Ok(())
     }
 
-    // test_ivc allowing to choose the CommitmentSchemes
-    fn test_ivc_opt<CS1: CommitmentScheme<Projective>, CS2: CommitmentScheme<Projective2>>(
+    // test_ivc allowing to choose the CommitmentSchemes and the number of incoming instances
+    #[allow(clippy::type_complexity)]
+    pub(crate) fn test_ivc_opt<
+        CS1: CommitmentScheme<Projective, H>,
+        CS2: CommitmentScheme<Projective2, H>,
+        const K: usize,
+        const H: bool,
+    >(
         poseidon_config: PoseidonConfig<Fr>,
         F_circuit: CubicFCircuit<Fr>,
-    ) -> Result<(), Error> {
-        type PG<CS1, CS2> = ProtoGalaxy<Projective, Projective2, CubicFCircuit<Fr>, CS1, CS2>;
-
-        let params = PG::<CS1, CS2>::preprocess(&mut test_rng(), &(poseidon_config, F_circuit))?;
+        num_steps: usize,
+    ) -> Result<ProtoGalaxy<Projective, Projective2, CubicFCircuit<Fr>, CS1, CS2, K, H>, Error>
+    {
+        type PG<CS1, CS2, const K: usize, const H: bool> =
+            ProtoGalaxy<Projective, Projective2, CubicFCircuit<Fr>, CS1, CS2, K, H>;
+
+        let params = PG::<CS1, CS2, K, H>::preprocess(
+            &mut test_rng(),
+            &PreprocessorParam::new(poseidon_config, F_circuit),
+        )?;
 
         let z_0 = vec![Fr::from(3_u32)];
-        let mut protogalaxy = PG::init(&params, F_circuit, z_0.clone())?;
-
-        let num_steps: usize = 3;
-        for _ in 0..num_steps {
-            protogalaxy.prove_step(&mut test_rng(), (), None)?;
+        let mut protogalaxy = PG::<CS1, CS2, K, H>::init(&params, F_circuit, z_0.clone())?;
+
+        for i in 0..num_steps {
+            // prepare some new instances to fold in the multi-instance folding step
+            let other_instances = (0..K - 1)
+                .map(|j| {
+                    let instance_state = vec![Fr::from(j as u32 + 15_u32)];
+                    protogalaxy.new_incoming_instance(&mut test_rng(), instance_state, ())
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+
+            if i == 0 && K > 1 {
+                // the other instances can not be folded at the base step
+                assert!(matches!(
+                    protogalaxy
+                        .clone()
+                        .prove_step(&mut test_rng(), (), Some(other_instances)),
+                    Err(Error::OtherInstancesAtBaseStep)
+                ));
+                protogalaxy.prove_step(&mut test_rng(), (), None)?;
+            } else {
+                protogalaxy.prove_step(&mut test_rng(), (), Some(other_instances))?;
+            }
         }
         assert_eq!(Fr::from(num_steps as u32), protogalaxy.i);
 
         let ivc_proof = protogalaxy.ivc_proof();
-        PG::<CS1, CS2>::verify(params.1, ivc_proof)?;
+        PG::<CS1, CS2, K, H>::verify(params.1, ivc_proof)?;
+        Ok(protogalaxy)
+    }
+
+    #[test]
+    fn test_wrong_num_instances() -> Result<(), Error> {
+        let poseidon_config = poseidon_canonical_config::<Fr>();
+        let F_circuit = CubicFCircuit::<Fr>::new(())?;
+
+        // `K + 1` is not a power of two
+        type PG = ProtoGalaxy<
+            Projective,
+            Projective2,
+            CubicFCircuit<Fr>,
+            Pedersen<Projective>,
+            Pedersen<Projective2>,
+            2,
+        >;
+        assert!(matches!(
+            PG::preprocess(
+                &mut test_rng(),
+                &PreprocessorParam::new(poseidon_config, F_circuit)
+            ),
+            Err(Error::ProtoGalaxy(ProtoGalaxyError::WrongNumInstances(2)))
+        ));
         Ok(())
     }
 
@@ -0,0 +1,999 @@
+//! Implements a zero-knowledge layer for ProtoGalaxy's IVC proofs, following the same approach
+//! as [`crate::folding::nova::zk`] (Use-case-3 there), i.e., it randomizes the last IVC proof so
+//! that it can be verified without revealing the witnesses of the folded steps.
+//!
+//! Unlike Nova, ProtoGalaxy can not fold two running instances together, and a random
+//! satisfying incoming (i.e., plain R1CS) instance can not be sampled. Therefore, before folding
+//! the last incoming instance `u_i`, we blind the running instance `U_i` with a random masking
+//! instance `U_r` that shares the same `betas` as `U_i` and whose `z` vector is
+//! `z_r = [0, x_r, w_r]` for random `x_r`, `w_r`.
+//!
+//! Since `f(z) = Az ∘ Bz - z[0] Cz` is quadratic, for a challenge `rho` we have
+//! `f(z_i + rho * z_r) = f(z_i) + rho * t + rho^2 * f(z_r)`, where `t` is the cross term.
+//! Hence the masked instance `U_m = U_i + rho * U_r` with error term
+//! `e_i + rho * T + rho^2 * e_r` (where `T` and `e_r` are the `pow_i(betas)`-weighted sums of `t`
+//! and `f(z_r)`) is satisfied by the witness `w_i + rho * w_r`, which is uniformly random.
+//! `u_i` is then folded into `U_m`, so that the published folding proof and folded witness only
+//! depend on the masked witness.
+//!
+//! A [`RandomizedIVCProof`] can either be checked with [`RandomizedIVCProof::verify`], or be
+//! handed to the [`RandomizedDecider`], which checks the masking and the last fold in-circuit.
+use ark_crypto_primitives::sponge::{
+    constraints::CryptographicSpongeVar, poseidon::PoseidonSponge, CryptographicSponge,
+};
+use ark_ff::{BigInteger, PrimeField};
+use ark_r1cs_std::{alloc::AllocVar, eq::EqGadget, fields::fp::FpVar};
+use ark_relations::r1cs::SynthesisError;
+use ark_snark::SNARK;
+use ark_std::{
+    log2,
+    marker::PhantomData,
+    rand::{CryptoRng, RngCore},
+    One, UniformRand, Zero,
+};
+
+use super::{
+    circuits::FoldingGadget,
+    constants::{INCOMING, RUNNING},
+    decider::{ProverParam, VerifierParam},
+    decider_circuits::DeciderCircuit2,
+    folding::{Folding, ProtoGalaxyProof},
+    utils::pow_i,
+    CommittedInstance, CommittedInstanceVar, ProtoGalaxy, ProverParams, VerifierParams, Witness,
+};
+use crate::{
+    arith::{
+        r1cs::{circuits::R1CSMatricesVar, R1CS},
+        Arith, ArithRelation,
+    },
+    commitment::{CommitmentScheme, EvaluationProof},
+    folding::{
+        circuits::{
+            cyclefold::{CycleFoldCommittedInstance, CycleFoldWitness},
+            decider::{
+                off_chain::GenericOffchainDeciderCircuit1, DeciderChallenge, DeciderEnabledNIFS,
+                KZGChallengesGadget,
+            },
+            nonnative::affine::NonNativeAffineVar,
+            CF1,
+        },
+        traits::{CommittedInstanceOps, Dummy, Inputize, InputizeNonNative, WitnessOps},
+    },
+    frontend::FCircuit,
+    transcript::{
+        poseidon::poseidon_canonical_config, SpongeWithGadget, Transcript, TranscriptVar,
+    },
+    utils::vec::{vec_add, vec_scalar_mul, vec_sub},
+    Curve, Error,
+};
+
+pub struct RandomizedIVCProof<C1: Curve, C2: Curve> {
+    pub U_i: CommittedInstance<C1, RUNNING>,
+    pub u_i: CommittedInstance<C1, INCOMING>,
+    pub U_r: CommittedInstance<C1, RUNNING>, // masking instance
+    pub T: C1::ScalarField,                  // cross term between U_i and U_r
+    pub pi: ProtoGalaxyProof<C1::ScalarField>, // proof of folding u_i into U_i + rho * U_r
+    pub W_i_prime: Witness<C1::ScalarField>,
+    pub cf_U_i: CycleFoldCommittedInstance<C2>,
+    pub cf_W_i: CycleFoldWitness<C2>,
+}
+
+impl<C1: Curve, C2: Curve> RandomizedIVCProof<C1, C2> {
+    /// Compute a zero-knowledge proof of a ProtoGalaxy IVC proof
+    pub fn new<
+        FC: FCircuit<C1::ScalarField>,
+        CS1: CommitmentScheme<C1, true>,
+        CS2: CommitmentScheme<C2, true>,
+        const K: usize,
+        T: SpongeWithGadget<C1::ScalarField>,
+    >(
+        protogalaxy: &ProtoGalaxy<C1, C2, FC, CS1, CS2, K, true, T>,
+        mut rng: impl RngCore,
+    ) -> Result<RandomizedIVCProof<C1, C2>, Error>
+    where
+        C1: Curve<BaseField = C2::ScalarField, ScalarField = C2::BaseField>,
+    {
+        let r1cs = &protogalaxy.r1cs;
+        let (U_i, W_i) = (&protogalaxy.U_i, &protogalaxy.W_i);
+        let mut transcript = T::new(&protogalaxy.sponge_config);
+        transcript.absorb(&protogalaxy.pp_hash);
+
+        // 1. Sample a random masking instance-witness pair (U_r, W_r), where
+        // `z_r = [0, x_r, w_r]`
+        let W_r = Witness::new::<true>(
+            (0..r1cs.n_witnesses())
+                .map(|_| C1::ScalarField::rand(&mut rng))
+                .collect(),
+            &mut rng,
+        );
+        let x_r = (0..r1cs.n_public_inputs())
+            .map(|_| C1::ScalarField::rand(&mut rng))
+            .collect::<Vec<_>>();
+        let z_r = [&[C1::ScalarField::zero()][..], &x_r, &W_r.w].concat();
+        let U_r = CommittedInstance {
+            phi: CS1::commit(&protogalaxy.cs_params, &W_r.w, &W_r.r_w)?,
+            betas: U_i.betas.clone(),
+            e: weighted_sum(&r1cs.eval_at_z(&z_r)?, &U_i.betas),
+            x: x_r,
+        };
+
+        // 2. Compute the cross term T between (U_i, W_i) and (U_r, W_r)
+        let z = [&[C1::ScalarField::one()][..], &U_i.x, &W_i.w].concat();
+        let T = weighted_sum(
+            &vec_sub(
+                &vec_sub(&r1cs.eval_at_z(&vec_add(&z, &z_r)?)?, &r1cs.eval_at_z(&z)?)?,
+                &r1cs.eval_at_z(&z_r)?,
+            )?,
+            &U_i.betas,
+        );
+
+        // 3. Mask (U_i, W_i) with (U_r, W_r)
+        transcript.absorb(U_i);
+        transcript.absorb(&U_r);
+        transcript.absorb(&T);
+        let rho = transcript.get_challenge();
+
+        let U_m = mask(U_i, &U_r, T, rho)?;
+        let W_m = Witness {
+            w: vec_add(&W_i.w, &vec_scalar_mul(&W_r.w, &rho))?,
+            r_w: W_i.r_w + rho * W_r.r_w,
+        };
+
+        // 4. Fold the incoming instance-witness pair (u_i, w_i) into (U_m, W_m)
+        let (_, W_i_prime, pi, _) = Folding::prove(
+            &mut transcript,
+            r1cs,
+            &U_m,
+            &W_m,
+            &[protogalaxy.u_i.clone()],
+            &[protogalaxy.w_i.clone()],
+        )?;
+
+        Ok(RandomizedIVCProof {
+            U_i: U_i.clone(),
+            u_i: protogalaxy.u_i.clone(),
+            U_r,
+            T,
+            pi,
+            W_i_prime,
+            cf_U_i: protogalaxy.cf_U_i.clone(),
+            cf_W_i: protogalaxy.cf_W_i.clone(),
+        })
+    }
+
+    /// Recomputes the challenge `rho`, masks `U_i` with it and folds `u_i` into the masked
+    /// instance. Returns `rho`, the folded instance and the evaluations of the Lagrange
+    /// polynomials at the folding challenge. `transcript` must have absorbed `pp_hash` already.
+    fn fold<T: Transcript<C1::ScalarField>>(
+        &self,
+        transcript: &mut T,
+    ) -> Result<
+        (
+            C1::ScalarField,
+            CommittedInstance<C1, RUNNING>,
+            Vec<C1::ScalarField>,
+        ),
+        Error,
+    > {
+        if self.U_r.betas != self.U_i.betas {
+            return Err(Error::zkIVCVerificationFail);
+        }
+        transcript.absorb(&self.U_i);
+        transcript.absorb(&self.U_r);
+        transcript.absorb(&self.T);
+        let rho = transcript.get_challenge();
+
+        let U_m = mask(&self.U_i, &self.U_r, self.T, rho)?;
+        let (U_f, L_X_evals) = Folding::verify_with_lagrange_evals(
+            transcript,
+            &U_m,
+            &[self.u_i.clone()],
+            self.pi.clone(),
+        )?;
+        Ok((rho, U_f, L_X_evals))
+    }
+
+    /// Verify a zero-knowledge proof of a ProtoGalaxy IVC proof
+    #[allow(clippy::too_many_arguments)]
+    pub fn verify<T: SpongeWithGadget<C1::ScalarField>>(
+        r1cs: &R1CS<C1::ScalarField>,
+        cf_r1cs: &R1CS<C2::ScalarField>,
+        pp_hash: C1::ScalarField,
+        sponge_config: &T::Config,
+        i: C1::ScalarField,
+        z_0: Vec<C1::ScalarField>,
+        z_i: Vec<C1::ScalarField>,
+        proof: &RandomizedIVCProof<C1, C2>,
+    ) -> Result<(), Error>
+    where
+        C1: Curve<BaseField = C2::ScalarField, ScalarField = C2::BaseField>,
+    {
+        // Handles case where i=0
+        if i == C1::ScalarField::zero() {
+            if z_0 == z_i {
+                return Ok(());
+            } else {
+                return Err(Error::zkIVCVerificationFail);
+            }
+        }
+
+        // 1. Check that u_i.x is correct - including the cyclefold running instance
+        // a. Check length
+        if proof.u_i.x.len() != 2 {
+            return Err(Error::IVCVerificationFail);
+        }
+
+        // b. Check computed hashes are correct
+        let sponge = T::new(sponge_config);
+        let expected_u_i_x = proof.U_i.hash(&sponge, pp_hash, i, &z_0, &z_i);
+        if expected_u_i_x != proof.u_i.x[0] {
+            return Err(Error::zkIVCVerificationFail);
+        }
+
+        let expected_cf_u_i_x = proof.cf_U_i.hash_cyclefold(&sponge, pp_hash);
+        if expected_cf_u_i_x != proof.u_i.x[1] {
+            return Err(Error::IVCVerificationFail);
+        }
+
+        // 2. Check that u_i is an incoming instance
+        proof.u_i.check_incoming()?;
+
+        // 3. Obtain the U^{\prime}_i folded instance, i.e., fold u_i into U_i + rho * U_r
+        let mut transcript = T::new(sponge_config);
+        transcript.absorb(&pp_hash);
+        let (_, U_i_prime, _) = proof.fold(&mut transcript)?;
+
+        // 4. Check that W^{\prime}_i is a satisfying witness
+        r1cs.check_relation(&proof.W_i_prime, &U_i_prime)?;
+
+        // 5. Check that the cyclefold instance-witness pair satisfies the cyclefold relaxed r1cs
+        cf_r1cs.check_relation(&proof.cf_W_i, &proof.cf_U_i)?;
+
+        Ok(())
+    }
+}
+
+/// Computes the masked running instance `U + rho * U_r`, whose error term is
+/// `e + rho * T + rho^2 * e_r`
+fn mask<C: Curve>(
+    U: &CommittedInstance<C, RUNNING>,
+    U_r: &CommittedInstance<C, RUNNING>,
+    T: CF1<C>,
+    rho: CF1<C>,
+) -> Result<CommittedInstance<C, RUNNING>, Error> {
+    Ok(CommittedInstance {
+        phi: U.phi + U_r.phi * rho,
+        betas: U.betas.clone(),
+        e: U.e + rho * T + rho * rho * U_r.e,
+        x: vec_add(&U.x, &vec_scalar_mul(&U_r.x, &rho))?,
+    })
+}
+
+/// Computes `\sum_i pow_i(betas) * v_i`
+fn weighted_sum<C: Curve>(v: &[CF1<C>], betas: &[CF1<C>]) -> CF1<C> {
+    v.iter()
+        .enumerate()
+        .map(|(i, v_i)| pow_i(i, betas) * v_i)
+        .sum()
+}
+
+/// The masking instance `U_r`, the cross term `T` and the folding proof `pi` of a
+/// [`RandomizedIVCProof`], which are witnesses of the decider circuit.
+#[derive(Debug, Clone)]
+pub struct MaskedFoldingProof<C: Curve> {
+    pub U_r: CommittedInstance<C, RUNNING>,
+    pub T: CF1<C>,
+    pub pi: ProtoGalaxyProof<CF1<C>>,
+}
+
+impl<C: Curve> Dummy<(usize, usize, usize, usize)> for MaskedFoldingProof<C> {
+    fn dummy((io_len, t, d, k): (usize, usize, usize, usize)) -> Self {
+        Self {
+            U_r: CommittedInstance::dummy((io_len, t)),
+            T: Zero::zero(),
+            pi: ProtoGalaxyProof::dummy((t, d, k)),
+        }
+    }
+}
+
+/// The public values of the fold done by [`RandomizedDeciderGadget`]: the masking challenge
+/// `rho`, the commitment `phi_m = U_i.phi + rho * U_r.phi` of the masked instance and the
+/// evaluations of the Lagrange polynomials at the folding challenge.
+#[derive(Debug, Clone)]
+pub struct MaskedFoldingRandomness<C: Curve> {
+    pub rho: CF1<C>,
+    pub phi_m: C,
+    pub L_X_evals: Vec<CF1<C>>,
+}
+
+impl<C: Curve> Dummy<usize> for MaskedFoldingRandomness<C> {
+    fn dummy(n_evals: usize) -> Self {
+        Self {
+            rho: Zero::zero(),
+            phi_m: C::zero(),
+            L_X_evals: vec![Zero::zero(); n_evals],
+        }
+    }
+}
+
+/// Same as [`super::decider_eth_circuit::DeciderProtoGalaxyGadget`], but it masks the running
+/// instance with `U_r` before folding the incoming instance into it, as done by
+/// [`RandomizedIVCProof`].
+pub struct RandomizedDeciderGadget;
+
+impl<C: Curve>
+    DeciderEnabledNIFS<
+        C,
+        CommittedInstance<C, RUNNING>,
+        CommittedInstance<C, INCOMING>,
+        Witness<CF1<C>>,
+        R1CS<CF1<C>>,
+    > for RandomizedDeciderGadget
+{
+    type Proof = MaskedFoldingProof<C>;
+    type ProofDummyCfg = (usize, usize, usize, usize);
+    type Randomness = MaskedFoldingRandomness<C>;
+    type RandomnessDummyCfg = usize;
+
+    fn fold_field_elements_gadget<S: CryptographicSponge, T: TranscriptVar<CF1<C>, S>>(
+        _arith: &R1CS<CF1<C>>,
+        transcript: &mut T,
+        pp_hash: FpVar<CF1<C>>,
+        U: CommittedInstanceVar<C, RUNNING>,
+        _U_vec: Vec<FpVar<CF1<C>>>,
+        u: CommittedInstanceVar<C, INCOMING>,
+        proof: Self::Proof,
+        randomness: Self::Randomness,
+    ) -> Result<CommittedInstanceVar<C, RUNNING>, SynthesisError> {
+        let cs = transcript.cs();
+        let U_r = CommittedInstanceVar::<C, RUNNING> {
+            phi: NonNativeAffineVar::new_input(cs.clone(), || Ok(proof.U_r.phi))?,
+            betas: U.betas.clone(),
+            e: FpVar::new_witness(cs.clone(), || Ok(proof.U_r.e))?,
+            x: Vec::new_witness(cs.clone(), || Ok(proof.U_r.x))?,
+        };
+        let T = FpVar::new_witness(cs.clone(), || Ok(proof.T))?;
+        let F_coeffs = Vec::new_witness(cs.clone(), || Ok(&proof.pi.F_coeffs[..]))?;
+        let K_coeffs = Vec::new_witness(cs.clone(), || Ok(&proof.pi.K_coeffs[..]))?;
+        let rho = FpVar::new_input(cs.clone(), || Ok(randomness.rho))?;
+        // `phi_m` is checked by the verifier, who computes it from `U_i.phi` and `U_r.phi`
+        let phi_m = NonNativeAffineVar::new_input(cs.clone(), || Ok(randomness.phi_m))?;
+        let L_X_evals = Vec::new_input(cs.clone(), || Ok(randomness.L_X_evals))?;
+
+        transcript.absorb(&pp_hash)?;
+        transcript.absorb(&U)?;
+        transcript.absorb(&U_r)?;
+        transcript.absorb(&T)?;
+        transcript.get_challenge()?.enforce_equal(&rho)?;
+
+        let U_m = CommittedInstanceVar::<C, RUNNING> {
+            phi: phi_m,
+            e: &U.e + &rho * &T + &rho * &rho * &U_r.e,
+            x: U.x
+                .iter()
+                .zip(&U_r.x)
+                .map(|(x, x_r)| x + &rho * x_r)
+                .collect(),
+            betas: U.betas,
+        };
+
+        let (U_next, L_X_evals_computed) =
+            FoldingGadget::fold_committed_instance(transcript, &U_m, &[u], F_coeffs, K_coeffs)?;
+        L_X_evals_computed.enforce_equal(&L_X_evals)?;
+
+        Ok(U_next)
+    }
+
+    /// `U_commitments` are the commitments of the masked running instance, i.e., `[phi_m]`.
+    fn fold_group_elements_native(
+        U_commitments: &[C],
+        u_commitments: &[C],
+        _: Option<Self::Proof>,
+        randomness: Self::Randomness,
+    ) -> Result<Vec<C>, Error> {
+        let L_X_evals = randomness.L_X_evals;
+        Ok(vec![
+            U_commitments[0] * L_X_evals[0] + u_commitments[0] * L_X_evals[1],
+        ])
+    }
+}
+
+/// Circuit that implements part of the in-circuit checks needed for the offchain verification of
+/// a [`RandomizedIVCProof`] over the Curve2's BaseField (=Curve1's ScalarField).
+pub type RandomizedDeciderCircuit1<C1, C2, P = CF1<C1>, SP = PoseidonSponge<CF1<C1>>> =
+    GenericOffchainDeciderCircuit1<
+        C1,
+        C2,
+        CommittedInstance<C1, RUNNING>,
+        CommittedInstance<C1, INCOMING>,
+        Witness<CF1<C1>>,
+        R1CS<CF1<C1>>,
+        R1CSMatricesVar<CF1<C1>, FpVar<CF1<C1>>>,
+        RandomizedDeciderGadget,
+        P,
+        SP,
+    >;
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct RandomizedDeciderProof<C1, C2, CS1, CS2, S1, S2>
+where
+    C1: Curve,
+    C2: Curve,
+    CS1: CommitmentScheme<C1, true>,
+    CS2: CommitmentScheme<C2, true>,
+    S1: SNARK<C1::ScalarField>,
+    S2: SNARK<C2::ScalarField>,
+{
+    c1_snark_proof: S1::Proof,
+    c2_snark_proof: S2::Proof,
+    cs1_proofs: [CS1::Proof; 1],
+    cs2_proofs: [CS2::Proof; 2],
+    // the commitment of the masking instance U_r
+    phi_r: C1,
+    // the challenge used to mask U_i with U_r, it is checked in-circuit
+    rho: C1::ScalarField,
+    // the evaluations of the Lagrange polynomials at the challenge of the last fold,
+    // U_{i+1}=Folding.V(U_i + rho * U_r, u_i), they are checked in-circuit
+    L_X_evals: Vec<C1::ScalarField>,
+    // cyclefold committed instance
+    cf_U_final: CycleFoldCommittedInstance<C2>,
+    // the CS challenges are provided by the prover, but in-circuit they are checked to match the
+    // in-circuit computed computed ones.
+    cs1_challenges: [CS1::Challenge; 1],
+    cs2_challenges: [CS2::Challenge; 2],
+}
+
+/// Offchain Decider for [`RandomizedIVCProof`]s. Unlike [`super::decider::Decider`], its prover
+/// only needs the randomized proof, so it can be run by a party that must not learn the
+/// witnesses of the folded steps, and the commitments are opened in hiding mode. `SP` is the
+/// sponge of the ProtoGalaxy instance from which the proofs are obtained.
+#[derive(Clone, Debug)]
+pub struct RandomizedDecider<C1, C2, CS1, CS2, S1, S2, SP = PoseidonSponge<CF1<C1>>> {
+    _c1: PhantomData<C1>,
+    _c2: PhantomData<C2>,
+    _cs1: PhantomData<CS1>,
+    _cs2: PhantomData<CS2>,
+    _s1: PhantomData<S1>,
+    _s2: PhantomData<S2>,
+    _sp: PhantomData<SP>,
+}
+
+impl<C1, C2, CS1, CS2, S1, S2, SP> RandomizedDecider<C1, C2, CS1, CS2, S1, S2, SP>
+where
+    C1: Curve<BaseField = C2::ScalarField, ScalarField = C2::BaseField>,
+    C2: Curve,
+    CS1: CommitmentScheme<C1, true>,
+    CS1::ProverChallenge: DeciderChallenge<C1::ScalarField>,
+    CS1::Challenge: DeciderChallenge<C1::ScalarField>,
+    CS1::Proof: EvaluationProof<C1::ScalarField>,
+    CS2: CommitmentScheme<C2, true>,
+    CS2::ProverChallenge: DeciderChallenge<C2::ScalarField>,
+    CS2::Challenge: DeciderChallenge<C2::ScalarField>,
+    CS2::Proof: EvaluationProof<C2::ScalarField>,
+    S1: SNARK<C1::ScalarField>,
+    S2: SNARK<C2::ScalarField>,
+    SP: SpongeWithGadget<C1::ScalarField>,
+{
+    #[allow(clippy::type_complexity)]
+    pub fn preprocess(
+        mut rng: impl RngCore + CryptoRng,
+        pg_pp: ProverParams<C1, C2, CS1, CS2, true, SP>,
+        pg_vp: VerifierParams<C1, C2, CS1, CS2, true, SP>,
+        state_len: usize,
+    ) -> Result<
+        (
+            ProverParam<CS1::ProverParams, S1::ProvingKey, CS2::ProverParams, S2::ProvingKey>,
+            VerifierParam<
+                C1,
+                CS1::VerifierParams,
+                S1::VerifyingKey,
+                CS2::VerifierParams,
+                S2::VerifyingKey,
+            >,
+        ),
+        Error,
+    > {
+        let pp_hash = pg_vp.pp_hash()?;
+
+        // the decider only folds the last incoming instance `u_i`, so `k` is always 1
+        let k = 1;
+        let d = pg_vp.r1cs.degree();
+        let t = log2(pg_vp.r1cs.n_constraints()) as usize;
+        let io_len = pg_vp.r1cs.n_public_inputs();
+
+        // `circuit2` uses the canonical Poseidon config on `C2`'s scalar field, see
+        // `DeciderCircuit2::try_from`
+        let poseidon_config2 = poseidon_canonical_config::<C2::ScalarField>();
+
+        let circuit1 = RandomizedDeciderCircuit1::<C1, C2, CS1::Challenge, SP>::dummy((
+            pg_vp.r1cs,
+            &pg_vp.cf_r1cs,
+            pg_vp.sponge_config,
+            (io_len, t, d, k),
+            k + 1, // `k + 1` is the length of `L_X_evals`
+            state_len,
+            1, // ProtoGalaxy's running CommittedInstance contains 1 commitment
+        ));
+        let circuit2 = DeciderCircuit2::<C2, CS2::Challenge>::dummy((
+            pg_vp.cf_r1cs,
+            poseidon_config2,
+            2, // CycleFold's running CommittedInstance contains 2 commitments
+        ));
+
+        let (c1_snark_pp, c1_snark_vp) = S1::circuit_specific_setup(circuit1, &mut rng)
+            .map_err(|e| Error::SNARKSetupFail(e.to_string()))?;
+        let (c2_snark_pp, c2_snark_vp) = S2::circuit_specific_setup(circuit2, &mut rng)
+            .map_err(|e| Error::SNARKSetupFail(e.to_string()))?;
+
+        let pp = ProverParam {
+            c1_snark_pp,
+            c1_cs_pp: pg_pp.cs_params,
+            c2_snark_pp,
+            c2_cs_pp: pg_pp.cf_cs_params,
+        };
+        let vp = VerifierParam {
+            pp_hash,
+            c1_snark_vp,
+            c1_cs_vp: pg_vp.cs_vp,
+            c2_snark_vp,
+            c2_cs_vp: pg_vp.cf_cs_vp,
+        };
+        Ok((pp, vp))
+    }
+
+    pub fn prove(
+        mut rng: impl RngCore + CryptoRng,
+        pp: &ProverParam<CS1::ProverParams, S1::ProvingKey, CS2::ProverParams, S2::ProvingKey>,
+        pg_vp: &VerifierParams<C1, C2, CS1, CS2, true, SP>,
+        i: C1::ScalarField,
+        z_0: Vec<C1::ScalarField>,
+        z_i: Vec<C1::ScalarField>,
+        proof: &RandomizedIVCProof<C1, C2>,
+    ) -> Result<RandomizedDeciderProof<C1, C2, CS1, CS2, S1, S2>, Error> {
+        let pp_hash = pg_vp.pp_hash()?;
+
+        // recompute U_{i+1} = Folding.V(U_i + rho * U_r, u_i), whose witness is W_i_prime
+        let mut transcript = SP::new(&pg_vp.sponge_config);
+        transcript.absorb(&pp_hash);
+        let (rho, U_i1, L_X_evals) = proof.fold(&mut transcript)?;
+        let W_i1 = proof.W_i_prime.clone();
+
+        let openings = W_i1.get_openings();
+        let lens = openings.iter().map(|(v, _)| v.len()).collect::<Vec<_>>();
+        let c1_kzg_challenges = KZGChallengesGadget::get_opening_challenges_native::<
+            C1,
+            _,
+            _,
+            CS1::Challenge,
+        >(&mut transcript, &U_i1, &lens);
+        let c1_kzg_evaluations = openings
+            .iter()
+            .zip(&c1_kzg_challenges)
+            .map(|((v, _), c)| c.evaluate_native(v))
+            .collect::<Result<Vec<_>, _>>()?;
+        let c1_kzg_proofs = openings
+            .iter()
+            .zip(&c1_kzg_challenges)
+            .map(|((v, r), c)| {
+                let c = CS1::ProverChallenge::from_elements(c.to_elements())?;
+                CS1::prove_with_challenge(&pp.c1_cs_pp, c, v, r, Some(&mut rng))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let circuit1 = RandomizedDeciderCircuit1::<C1, C2, CS1::Challenge, SP> {
+            _avar: PhantomData,
+            arith: pg_vp.r1cs.clone(),
+            sponge_config: pg_vp.sponge_config.clone(),
+            pp_hash,
+            i,
+            z_0,
+            z_i,
+            U_i: proof.U_i.clone(),
+            // the witnesses of `U_i` and `u_i` are not used by the circuit
+            W_i: Witness::dummy(&pg_vp.r1cs),
+            u_i: proof.u_i.clone(),
+            w_i: Witness::dummy(&pg_vp.r1cs),
+            U_i1,
+            W_i1,
+            proof: MaskedFoldingProof {
+                U_r: proof.U_r.clone(),
+                T: proof.T,
+                pi: proof.pi.clone(),
+            },
+            randomness: MaskedFoldingRandomness {
+                rho,
+                phi_m: proof.U_i.phi + proof.U_r.phi * rho,
+                L_X_evals: L_X_evals.clone(),
+            },
+            cf_U_i: proof.cf_U_i.clone(),
+            kzg_challenges: c1_kzg_challenges.clone(),
+            kzg_evaluations: c1_kzg_evaluations,
+        };
+
+        // `circuit2` uses the canonical Poseidon config on `C2`'s scalar field, as in
+        // `DeciderCircuit2::try_from`
+        let poseidon_config2 = poseidon_canonical_config::<C2::ScalarField>();
+        let pp_hash_Fq =
+            C2::ScalarField::from_le_bytes_mod_order(&pp_hash.into_bigint().to_bytes_le());
+        let mut transcript = PoseidonSponge::<C2::ScalarField>::new(&poseidon_config2);
+        transcript.absorb(&pp_hash_Fq);
+
+        let openings = proof.cf_W_i.get_openings();
+        let lens = openings.iter().map(|(v, _)| v.len()).collect::<Vec<_>>();
+        let c2_kzg_challenges = KZGChallengesGadget::get_opening_challenges_native::<
+            C2,
+            _,
+            _,
+            CS2::Challenge,
+        >(&mut transcript, &proof.cf_U_i, &lens);
+        let c2_kzg_evaluations = openings
+            .iter()
+            .zip(&c2_kzg_challenges)
+            .map(|((v, _), c)| c.evaluate_native(v))
+            .collect::<Result<Vec<_>, _>>()?;
+        let c2_kzg_proofs = openings
+            .iter()
+            .zip(&c2_kzg_challenges)
+            .map(|((v, r), c)| {
+                let c = CS2::ProverChallenge::from_elements(c.to_elements())?;
+                CS2::prove_with_challenge(&pp.c2_cs_pp, c, v, r, Some(&mut rng))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let circuit2 = DeciderCircuit2::<C2, CS2::Challenge> {
+            cf_arith: pg_vp.cf_r1cs.clone(),
+            poseidon_config: poseidon_config2,
+            pp_hash: pp_hash_Fq,
+            cf_U_i: proof.cf_U_i.clone(),
+            cf_W_i: proof.cf_W_i.clone(),
+            kzg_challenges: c2_kzg_challenges.clone(),
+            kzg_evaluations: c2_kzg_evaluations,
+        };
+
+        let c1_snark_proof = S1::prove(&pp.c1_snark_pp, circuit1, &mut rng)
+            .map_err(|e| Error::Other(e.to_string()))?;
+        let c2_snark_proof = S2::prove(&pp.c2_snark_pp, circuit2, &mut rng)
+            .map_err(|e| Error::Other(e.to_string()))?;
+
+        Ok(RandomizedDeciderProof {
+            c1_snark_proof,
+            c2_snark_proof,
+            cs1_proofs: c1_kzg_proofs
+                .try_into()
+                .map_err(|e: Vec<_>| Error::NotExpectedLength(e.len(), 1))?,
+            cs2_proofs: c2_kzg_proofs
+                .try_into()
+                .map_err(|e: Vec<_>| Error::NotExpectedLength(e.len(), 2))?,
+            phi_r: proof.U_r.phi,
+            rho,
+            L_X_evals,
+            cf_U_final: proof.cf_U_i.clone(),
+            cs1_challenges: c1_kzg_challenges
+                .try_into()
+                .map_err(|e: Vec<_>| Error::NotExpectedLength(e.len(), 1))?,
+            cs2_challenges: c2_kzg_challenges
+                .try_into()
+                .map_err(|e: Vec<_>| Error::NotExpectedLength(e.len(), 2))?,
+        })
+    }
+
+    pub fn verify(
+        vp: &VerifierParam<
+            C1,
+            CS1::VerifierParams,
+            S1::VerifyingKey,
+            CS2::VerifierParams,
+            S2::VerifyingKey,
+        >,
+        i: C1::ScalarField,
+        z_0: Vec<C1::ScalarField>,
+        z_i: Vec<C1::ScalarField>,
+        running_commitments: &[C1],
+        incoming_commitments: &[C1],
+        proof: &RandomizedDeciderProof<C1, C2, CS1, CS2, S1, S2>,
+    ) -> Result<bool, Error> {
+        if i <= C1::ScalarField::one() {
+            return Err(Error::NotEnoughSteps);
+        }
+
+        // fold the commitments of the masked running instance and of the incoming instance
+        let phi_m = running_commitments[0] + proof.phi_r * proof.rho;
+        let randomness = MaskedFoldingRandomness {
+            rho: proof.rho,
+            phi_m,
+            L_X_evals: proof.L_X_evals.clone(),
+        };
+        let U_final_commitments = RandomizedDeciderGadget::fold_group_elements_native(
+            &[phi_m],
+            incoming_commitments,
+            None,
+            randomness,
+        )?;
+        let cf_U = &proof.cf_U_final;
+
+        // snark proof 1, the inputs allocated by `RandomizedDeciderGadget` come last
+        let c1_public_input = [
+            &[vp.pp_hash, i][..],
+            &z_0,
+            &z_i,
+            &U_final_commitments.inputize_nonnative(),
+            &cf_U.inputize_nonnative(),
+            &proof
+                .cs1_challenges
+                .iter()
+                .flat_map(|c| c.to_elements())
+                .collect::<Vec<_>>(),
+            &proof
+                .cs1_proofs
+                .iter()
+                .map(|p| p.evaluation())
+                .collect::<Vec<_>>(),
+            &proof.phi_r.inputize_nonnative(),
+            &[proof.rho],
+            &phi_m.inputize_nonnative(),
+            &proof.L_X_evals,
+        ]
+        .concat();
+
+        let c1_snark_v = S1::verify(&vp.c1_snark_vp, &c1_public_input, &proof.c1_snark_proof)
+            .map_err(|e| Error::Other(e.to_string()))?;
+        if !c1_snark_v {
+            return Err(Error::SNARKVerificationFail);
+        }
+
+        // snark proof 2
+        let pp_hash_Fq =
+            C2::ScalarField::from_le_bytes_mod_order(&vp.pp_hash.into_bigint().to_bytes_le());
+        let c2_public_input: Vec<C2::ScalarField> = [
+            &[pp_hash_Fq][..],
+            &cf_U.inputize(),
+            &proof
+                .cs2_challenges
+                .iter()
+                .flat_map(|c| c.to_elements())
+                .collect::<Vec<_>>(),
+            &proof
+                .cs2_proofs
+                .iter()
+                .map(|p| p.evaluation())
+                .collect::<Vec<_>>(),
+        ]
+        .concat();
+
+        let c2_snark_v = S2::verify(&vp.c2_snark_vp, &c2_public_input, &proof.c2_snark_proof)
+            .map_err(|e| Error::Other(e.to_string()))?;
+        if !c2_snark_v {
+            return Err(Error::SNARKVerificationFail);
+        }
+
+        // check C1 commitments (main instance commitments)
+        for ((cm, c), pi) in U_final_commitments
+            .iter()
+            .zip(&proof.cs1_challenges)
+            .zip(&proof.cs1_proofs)
+        {
+            CS1::verify_with_challenge(&vp.c1_cs_vp, c.clone(), cm, pi)?;
+        }
+
+        // check C2 commitments (CycleFold instance commitments)
+        for ((cm, c), pi) in cf_U
+            .get_commitments()
+            .iter()
+            .zip(&proof.cs2_challenges)
+            .zip(&proof.cs2_proofs)
+        {
+            CS2::verify_with_challenge(&vp.c2_cs_vp, c.clone(), cm, pi)?;
+        }
+
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use crate::commitment::pedersen::Pedersen;
+    use crate::folding::protogalaxy::tests::test_ivc_opt;
+    use crate::frontend::utils::CubicFCircuit;
+    use crate::transcript::poseidon::poseidon_canonical_config;
+    use ark_bn254::{Fr, G1Projective as Projective};
+    use ark_grumpkin::Projective as Projective2;
+    use rand::rngs::OsRng;
+
+    // Tests zk proof generation and verification for a valid ProtoGalaxy IVC proof
+    #[test]
+    fn test_zk_protogalaxy_ivc() -> Result<(), Error> {
+        let mut rng = OsRng;
+        let poseidon_config = poseidon_canonical_config::<Fr>();
+        let F_circuit = CubicFCircuit::<Fr>::new(())?;
+        let protogalaxy = test_ivc_opt::<
+            Pedersen<Projective, true>,
+            Pedersen<Projective2, true>,
+            1,
+            true,
+        >(poseidon_config, F_circuit, 3)?;
+
+        let proof = RandomizedIVCProof::new(&protogalaxy, &mut rng)?;
+        RandomizedIVCProof::verify::<PoseidonSponge<Fr>>(
+            &protogalaxy.r1cs,
+            &protogalaxy.cf_r1cs,
+            protogalaxy.pp_hash,
+            &protogalaxy.sponge_config,
+            protogalaxy.i,
+            protogalaxy.z_0,
+            protogalaxy.z_i,
+            &proof,
+        )?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_zk_protogalaxy_when_i_is_zero() -> Result<(), Error> {
+        let mut rng = OsRng;
+        let poseidon_config = poseidon_canonical_config::<Fr>();
+        let F_circuit = CubicFCircuit::<Fr>::new(())?;
+        let protogalaxy = test_ivc_opt::<
+            Pedersen<Projective, true>,
+            Pedersen<Projective2, true>,
+            1,
+            true,
+        >(poseidon_config, F_circuit, 0)?;
+
+        let proof = RandomizedIVCProof::new(&protogalaxy, &mut rng)?;
+        RandomizedIVCProof::verify::<PoseidonSponge<Fr>>(
+            &protogalaxy.r1cs,
+            &protogalaxy.cf_r1cs,
+            protogalaxy.pp_hash,
+            &protogalaxy.sponge_config,
+            protogalaxy.i,
+            protogalaxy.z_0,
+            protogalaxy.z_i,
+            &proof,
+        )?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_zk_protogalaxy_verification_fails_with_wrong_running_instance() -> Result<(), Error> {
+        let mut rng = OsRng;
+        let poseidon_config = poseidon_canonical_config::<Fr>();
+        let F_circuit = CubicFCircuit::<Fr>::new(())?;
+        let mut protogalaxy = test_ivc_opt::<
+            Pedersen<Projective, true>,
+            Pedersen<Projective2, true>,
+            1,
+            true,
+        >(poseidon_config, F_circuit, 3)?;
+
+        // proof verification fails with incorrect running instance
+        protogalaxy.U_i.e = Fr::rand(&mut rng);
+        let incorrect_proof = RandomizedIVCProof::new(&protogalaxy, &mut rng)?;
+        let verify = RandomizedIVCProof::verify::<PoseidonSponge<Fr>>(
+            &protogalaxy.r1cs,
+            &protogalaxy.cf_r1cs,
+            protogalaxy.pp_hash,
+            &protogalaxy.sponge_config,
+            protogalaxy.i,
+            protogalaxy.z_0,
+            protogalaxy.z_i,
+            &incorrect_proof,
+        );
+        assert!(verify.is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_zk_protogalaxy_verification_fails_with_wrong_running_witness() -> Result<(), Error> {
+        let mut rng = OsRng;
+        let poseidon_config = poseidon_canonical_config::<Fr>();
+        let F_circuit = CubicFCircuit::<Fr>::new(())?;
+        let mut protogalaxy = test_ivc_opt::<
+            Pedersen<Projective, true>,
+            Pedersen<Projective2, true>,
+            1,
+            true,
+        >(poseidon_config, F_circuit, 3)?;
+
+        // proof verification fails with incorrect running witness
+        protogalaxy.W_i = Witness::new::<true>(
+            (0..protogalaxy.r1cs.n_witnesses())
+                .map(|_| Fr::rand(&mut rng))
+                .collect(),
+            &mut rng,
+        );
+        let incorrect_proof = RandomizedIVCProof::new(&protogalaxy, &mut rng)?;
+        let verify = RandomizedIVCProof::verify::<PoseidonSponge<Fr>>(
+            &protogalaxy.r1cs,
+            &protogalaxy.cf_r1cs,
+            protogalaxy.pp_hash,
+            &protogalaxy.sponge_config,
+            protogalaxy.i,
+            protogalaxy.z_0,
+            protogalaxy.z_i,
+            &incorrect_proof,
+        );
+        assert!(verify.is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_randomized_decider() -> Result<(), Error> {
+        use crate::commitment::ipa::IPA;
+        use crate::folding::nova::PreprocessorParam;
+        use crate::FoldingScheme;
+        use ark_groth16::Groth16;
+        // Note: do not use the MNTx_298 curves in practice, these are just for tests. Use the
+        // MNTx_753 curves instead.
+        use ark_mnt4_298::{Fr, G1Projective as Projective, MNT4_298 as MNT4};
+        use ark_mnt6_298::{G1Projective as Projective2, MNT6_298 as MNT6};
+
+        type PG = ProtoGalaxy<
+            Projective,
+            Projective2,
+            CubicFCircuit<Fr>,
+            IPA<Projective, true>,
+            IPA<Projective2, true>,
+            1,
+            true,
+        >;
+        type D = RandomizedDecider<
+            Projective,
+            Projective2,
+            IPA<Projective, true>,
+            IPA<Projective2, true>,
+            Groth16<MNT4>,
+            Groth16<MNT6>,
+        >;
+
+        let mut rng = OsRng;
+        let poseidon_config = poseidon_canonical_config::<Fr>();
+        let F_circuit = CubicFCircuit::<Fr>::new(())?;
+
+        let pg_params = PG::preprocess(
+            &mut rng,
+            &PreprocessorParam::new(poseidon_config, F_circuit),
+        )?;
+        let mut protogalaxy = PG::init(&pg_params, F_circuit, vec![Fr::from(3_u32)])?;
+        protogalaxy.prove_step(&mut rng, (), None)?;
+        protogalaxy.prove_step(&mut rng, (), None)?;
+
+        // the decider prover only gets the randomized proof
+        let randomized_proof = RandomizedIVCProof::new(&protogalaxy, &mut rng)?;
+        let (pg_pp, pg_vp) = pg_params;
+        let (decider_pp, decider_vp) =
+            D::preprocess(&mut rng, pg_pp, pg_vp.clone(), F_circuit.state_len())?;
+        let proof = D::prove(
+            &mut rng,
+            &decider_pp,
+            &pg_vp,
+            protogalaxy.i,
+            protogalaxy.z_0.clone(),
+            protogalaxy.z_i.clone(),
+            &randomized_proof,
+        )?;
+
+        let verified = D::verify(
+            &decider_vp,
+            protogalaxy.i,
+            protogalaxy.z_0.clone(),
+            protogalaxy.z_i.clone(),
+            &randomized_proof.U_i.get_commitments(),
+            &randomized_proof.u_i.get_commitments(),
+            &proof,
+        )?;
+        assert!(verified);
+
+        // a proof for a different final state must be rejected
+        assert!(D::verify(
+            &decider_vp,
+            protogalaxy.i,
+            protogalaxy.z_0,
+            vec![Fr::zero()],
+            &randomized_proof.U_i.get_commitments(),
+            &randomized_proof.u_i.get_commitments(),
+            &proof,
+        )
+        .is_err());
+        Ok(())
+    }
+}
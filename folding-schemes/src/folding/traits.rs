@@ -1,14 +1,13 @@
 use ark_crypto_primitives::sponge::{
     constraints::{AbsorbGadget, CryptographicSpongeVar},
-    poseidon::constraints::PoseidonSpongeVar,
-    Absorb,
+    Absorb, CryptographicSponge,
 };
 use ark_ff::PrimeField;
 use ark_r1cs_std::{alloc::AllocVar, fields::fp::FpVar};
 use ark_relations::r1cs::SynthesisError;
 
 use crate::{
-    transcript::{AbsorbNonNativeGadget, Transcript},
+    transcript::{AbsorbNonNativeGadget, Transcript, TranscriptVar},
     Curve, Error,
 };
 
@@ -68,9 +67,9 @@ pub trait CommittedInstanceVarOps<C: Curve> {
     /// instance `self` as a vector of field elements, so they can be reused in
     /// other gadgets avoiding recalculating (reconstraining) them.
     #[allow(clippy::type_complexity)]
-    fn hash(
+    fn hash<S: CryptographicSponge, T: TranscriptVar<CF1<C>, S>>(
         &self,
-        sponge: &PoseidonSpongeVar<CF1<C>>,
+        sponge: &T,
         pp_hash: &FpVar<CF1<C>>,
         i: &FpVar<CF1<C>>,
         z_0: &[FpVar<CF1<C>>],
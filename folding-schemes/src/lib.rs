@@ -72,6 +72,14 @@ pub enum Error {
     // Comparators errors
     #[error("Not equal")]
     NotEqual,
+    #[error("Public params hash (pp_hash) does not match the expected one")]
+    PPHashMismatch,
+    #[error("The KZG challenges do not match the ones derived from the commitments")]
+    KZGChallengesMismatch,
+    #[error(
+        "The left and right proofs are not contiguous segments of the computation (in their states or in the indexes of their steps)"
+    )]
+    PCDStateMismatch,
     #[error("Vectors should have the same length ({0}: {1}, {2}: {3})")]
     NotSameLength(String, usize, String, usize),
     #[error("Vector's length ({0}) is not the expected ({1})")]
@@ -100,6 +108,18 @@ pub enum Error {
     IncorrectBlinding(bool, String),
     #[error("Commitment verification failed")]
     CommitmentVerificationFail,
+    #[error("Invalid SRS: {0}")]
+    InvalidSRS(String),
+    #[error("Fixed-base tables window of {0} bits is not supported, it must be between 1 and {1}")]
+    InvalidFixedBaseWindow(usize, usize),
+
+    // On-disk format errors
+    #[error("Not a params or proof file of this library: invalid magic bytes")]
+    InvalidMagic,
+    #[error("Unsupported format version {0}, the latest supported one is {1}")]
+    UnsupportedFormatVersion(u16, u16),
+    #[error("The {0} of the file does not match the expected one (expected: {1}, found: {2})")]
+    FormatMismatch(String, String, String),
 
     // Polynomial IOP errors, from https://github.com/EspressoSystems/hyperplonk/blob/main/subroutines/src/poly_iop/errors.rs
     #[error("Invalid Polynomial IOP Prover: {0}")]
@@ -134,6 +154,8 @@ pub enum Error {
     NoMultiInstances,
     #[error("Missing 'other' instances, since this is a multi-instances folding scheme. Expected number of instances, mu:{0}, nu:{1}")]
     MissingOtherInstances(usize, usize),
+    #[error("'Other' instances can not be folded at the base step (i = 0), they should be passed from the second step on")]
+    OtherInstancesAtBaseStep,
 }
 
 /// FoldingScheme defines trait that is implemented by the diverse folding schemes. It is defined
@@ -0,0 +1,340 @@
+/// This file implements `DebugTranscript` and `DebugTranscriptVar`, wrappers around a `Transcript`
+/// and a `TranscriptVar` that record every operation performed on them.
+///
+/// Since the native and in-circuit transcripts of a protocol must absorb and squeeze exactly the
+/// same values, comparing the two logs pinpoints the first operation at which the prover (or the
+/// native verifier) and the circuit diverge, which is otherwise only visible as a mismatching
+/// challenge at the very end.
+use ark_crypto_primitives::sponge::{
+    constraints::{AbsorbGadget, CryptographicSpongeVar},
+    Absorb, CryptographicSponge,
+};
+use ark_ec::{AffineRepr, CurveGroup};
+use ark_ff::{BigInteger, PrimeField};
+use ark_r1cs_std::{boolean::Boolean, fields::fp::FpVar, groups::CurveVar, uint8::UInt8, R1CSVar};
+use ark_relations::r1cs::{ConstraintSystemRef, SynthesisError};
+use ark_std::marker::PhantomData;
+
+use super::{AbsorbNonNative, AbsorbNonNativeGadget, Transcript, TranscriptVar};
+
+/// An operation performed on a transcript.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TranscriptOp<F: PrimeField> {
+    DomainSeparator(Vec<u8>),
+    Label(Vec<u8>),
+    Absorb(Vec<F>),
+    Squeeze(Vec<F>),
+    SqueezeBits(Vec<bool>),
+    SqueezeBytes(Vec<u8>),
+}
+
+/// Returns the index of the first operation that differs between `a` and `b`, or `None` if the
+/// two logs are equal.
+pub fn first_divergence<F: PrimeField>(
+    a: &[TranscriptOp<F>],
+    b: &[TranscriptOp<F>],
+) -> Option<usize> {
+    (0..a.len().max(b.len())).find(|&i| a.get(i) != b.get(i))
+}
+
+/// A `Transcript` that forwards every operation to `T` and records it.
+#[derive(Clone, Debug)]
+pub struct DebugTranscript<F: PrimeField, T> {
+    pub inner: T,
+    pub log: Vec<TranscriptOp<F>>,
+}
+
+impl<F: PrimeField, T: CryptographicSponge> CryptographicSponge for DebugTranscript<F, T> {
+    type Config = T::Config;
+
+    fn new(config: &Self::Config) -> Self {
+        Self {
+            inner: T::new(config),
+            log: vec![],
+        }
+    }
+
+    fn absorb(&mut self, input: &impl Absorb) {
+        self.log.push(TranscriptOp::Absorb(
+            input.to_sponge_field_elements_as_vec::<F>(),
+        ));
+        self.inner.absorb(input);
+    }
+
+    fn squeeze_bytes(&mut self, num_bytes: usize) -> Vec<u8> {
+        let bytes = self.inner.squeeze_bytes(num_bytes);
+        self.log.push(TranscriptOp::SqueezeBytes(bytes.clone()));
+        bytes
+    }
+
+    fn squeeze_bits(&mut self, num_bits: usize) -> Vec<bool> {
+        let bits = self.inner.squeeze_bits(num_bits);
+        self.log.push(TranscriptOp::SqueezeBits(bits.clone()));
+        bits
+    }
+
+    /// Note that if `E` is not `F`, the squeezed elements are recorded reduced modulo `F`.
+    fn squeeze_field_elements<E: PrimeField>(&mut self, num_elements: usize) -> Vec<E> {
+        let elements = self.inner.squeeze_field_elements::<E>(num_elements);
+        self.log.push(TranscriptOp::Squeeze(
+            elements
+                .iter()
+                .map(|e| F::from_le_bytes_mod_order(&e.into_bigint().to_bytes_le()))
+                .collect(),
+        ));
+        elements
+    }
+}
+
+impl<F: PrimeField + Absorb, T: Transcript<F>> Transcript<F> for DebugTranscript<F, T> {
+    fn absorb_point<C: CurveGroup<BaseField = F>>(&mut self, v: &C) {
+        let (x, y) = v.into_affine().xy().unwrap_or_default();
+        self.log.push(TranscriptOp::Absorb(vec![x, y]));
+        self.inner.absorb_point(v);
+    }
+    fn absorb_nonnative<V: AbsorbNonNative>(&mut self, v: &V) {
+        self.log.push(TranscriptOp::Absorb(
+            v.to_native_sponge_field_elements_as_vec(),
+        ));
+        self.inner.absorb_nonnative(v);
+    }
+    fn get_challenge(&mut self) -> F {
+        let c = self.inner.get_challenge();
+        self.log.push(TranscriptOp::Squeeze(vec![c]));
+        c
+    }
+    fn get_challenge_nbits(&mut self, nbits: usize) -> Vec<bool> {
+        let bits = self.inner.get_challenge_nbits(nbits);
+        self.log.push(TranscriptOp::SqueezeBits(bits.clone()));
+        bits
+    }
+    fn get_challenges(&mut self, n: usize) -> Vec<F> {
+        let c = self.inner.get_challenges(n);
+        self.log.push(TranscriptOp::Squeeze(c.clone()));
+        c
+    }
+    fn domain_separator(&mut self, domain: &[u8]) {
+        self.log
+            .push(TranscriptOp::DomainSeparator(domain.to_vec()));
+        self.inner.domain_separator(domain);
+    }
+    fn absorb_label(&mut self, label: &[u8]) {
+        self.log.push(TranscriptOp::Label(label.to_vec()));
+        self.inner.absorb_label(label);
+    }
+}
+
+/// In-circuit counterpart of `DebugTranscript`, which forwards every operation to `T` and records
+/// it together with the assigned values.
+///
+/// Operations are only recorded when the constraint system holds an assignment, i.e., not in setup
+/// mode, and recording them does not add any constraint.
+#[derive(Clone)]
+pub struct DebugTranscriptVar<F: PrimeField, S, T> {
+    pub inner: T,
+    pub log: Vec<TranscriptOp<F>>,
+    _s: PhantomData<S>,
+}
+
+impl<F: PrimeField, S, T: CryptographicSpongeVar<F, S>> DebugTranscriptVar<F, S, T>
+where
+    S: CryptographicSponge,
+{
+    fn record(
+        &mut self,
+        op: impl FnOnce() -> Result<TranscriptOp<F>, SynthesisError>,
+    ) -> Result<(), SynthesisError> {
+        if !self.inner.cs().is_in_setup_mode() {
+            self.log.push(op()?);
+        }
+        Ok(())
+    }
+}
+
+impl<F, S, T> CryptographicSpongeVar<F, DebugTranscript<F, S>> for DebugTranscriptVar<F, S, T>
+where
+    F: PrimeField,
+    S: CryptographicSponge,
+    T: CryptographicSpongeVar<F, S>,
+{
+    type Parameters = T::Parameters;
+
+    fn new(cs: ConstraintSystemRef<F>, params: &Self::Parameters) -> Self {
+        Self {
+            inner: T::new(cs, params),
+            log: vec![],
+            _s: PhantomData,
+        }
+    }
+
+    fn cs(&self) -> ConstraintSystemRef<F> {
+        self.inner.cs()
+    }
+
+    fn absorb(&mut self, input: &impl AbsorbGadget<F>) -> Result<(), SynthesisError> {
+        let elements = input.to_sponge_field_elements()?;
+        self.record(|| Ok(TranscriptOp::Absorb(elements.value()?)))?;
+        self.inner.absorb(&elements)
+    }
+
+    fn squeeze_bytes(&mut self, num_bytes: usize) -> Result<Vec<UInt8<F>>, SynthesisError> {
+        let bytes = self.inner.squeeze_bytes(num_bytes)?;
+        self.record(|| Ok(TranscriptOp::SqueezeBytes(bytes.value()?)))?;
+        Ok(bytes)
+    }
+
+    fn squeeze_bits(&mut self, num_bits: usize) -> Result<Vec<Boolean<F>>, SynthesisError> {
+        let bits = self.inner.squeeze_bits(num_bits)?;
+        self.record(|| Ok(TranscriptOp::SqueezeBits(bits.value()?)))?;
+        Ok(bits)
+    }
+
+    fn squeeze_field_elements(
+        &mut self,
+        num_elements: usize,
+    ) -> Result<Vec<FpVar<F>>, SynthesisError> {
+        let elements = self.inner.squeeze_field_elements(num_elements)?;
+        self.record(|| Ok(TranscriptOp::Squeeze(elements.value()?)))?;
+        Ok(elements)
+    }
+}
+
+impl<F, S, T> TranscriptVar<F, DebugTranscript<F, S>> for DebugTranscriptVar<F, S, T>
+where
+    F: PrimeField,
+    S: CryptographicSponge,
+    T: TranscriptVar<F, S>,
+{
+    fn absorb_point<C: CurveGroup<BaseField = F>, GC: CurveVar<C, F>>(
+        &mut self,
+        v: &GC,
+    ) -> Result<(), SynthesisError> {
+        self.record(|| {
+            let (x, y) = v.value()?.into_affine().xy().unwrap_or_default();
+            Ok(TranscriptOp::Absorb(vec![x, y]))
+        })?;
+        self.inner.absorb_point(v)
+    }
+    fn absorb_nonnative<V: AbsorbNonNativeGadget<F>>(
+        &mut self,
+        v: &V,
+    ) -> Result<(), SynthesisError> {
+        // `absorb_nonnative` absorbs the native sponge field elements of `v`,
+        // so we compute them once and reuse them for both the log and `T`.
+        let elements = v.to_native_sponge_field_elements()?;
+        self.record(|| Ok(TranscriptOp::Absorb(elements.value()?)))?;
+        self.inner.absorb(&elements)
+    }
+    fn get_challenge(&mut self) -> Result<FpVar<F>, SynthesisError> {
+        let c = self.inner.get_challenge()?;
+        self.record(|| Ok(TranscriptOp::Squeeze(vec![c.value()?])))?;
+        Ok(c)
+    }
+    fn get_challenge_nbits(&mut self, nbits: usize) -> Result<Vec<Boolean<F>>, SynthesisError> {
+        let bits = self.inner.get_challenge_nbits(nbits)?;
+        self.record(|| Ok(TranscriptOp::SqueezeBits(bits.value()?)))?;
+        Ok(bits)
+    }
+    fn get_challenges(&mut self, n: usize) -> Result<Vec<FpVar<F>>, SynthesisError> {
+        let c = self.inner.get_challenges(n)?;
+        self.record(|| Ok(TranscriptOp::Squeeze(c.value()?)))?;
+        Ok(c)
+    }
+    fn domain_separator(&mut self, domain: &[u8]) -> Result<(), SynthesisError> {
+        self.record(|| Ok(TranscriptOp::DomainSeparator(domain.to_vec())))?;
+        self.inner.domain_separator(domain)
+    }
+    fn absorb_label(&mut self, label: &[u8]) -> Result<(), SynthesisError> {
+        self.record(|| Ok(TranscriptOp::Label(label.to_vec())))?;
+        self.inner.absorb_label(label)
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use ark_crypto_primitives::sponge::poseidon::{constraints::PoseidonSpongeVar, PoseidonSponge};
+    use ark_pallas::{Fr, Projective};
+    use ark_r1cs_std::alloc::AllocVar;
+    use ark_relations::r1cs::ConstraintSystem;
+    use ark_std::UniformRand;
+
+    use super::*;
+    use crate::commitment::pedersen::Pedersen;
+    use crate::folding::circuits::nonnative::affine::NonNativeAffineVar;
+    use crate::folding::nova::{
+        nifs::{
+            nova::NIFS,
+            nova_circuits::{CommittedInstanceVar, NIFSGadget},
+            NIFSGadgetTrait, NIFSTrait,
+        },
+        CommittedInstance,
+    };
+    use crate::transcript::poseidon::poseidon_canonical_config;
+    use crate::Error;
+
+    type T = DebugTranscript<Fr, PoseidonSponge<Fr>>;
+    type TVar = DebugTranscriptVar<Fr, PoseidonSponge<Fr>, PoseidonSpongeVar<Fr>>;
+
+    #[test]
+    fn test_debug_transcript_logs_match() -> Result<(), Error> {
+        let mut rng = ark_std::test_rng();
+        let poseidon_config = poseidon_canonical_config::<Fr>();
+        let pp_hash = Fr::rand(&mut rng);
+        let ci = (0..2)
+            .map(|_| CommittedInstance::<Projective> {
+                cmE: Projective::rand(&mut rng),
+                u: Fr::rand(&mut rng),
+                cmW: Projective::rand(&mut rng),
+                x: vec![Fr::rand(&mut rng); 1],
+            })
+            .collect::<Vec<_>>();
+        let (U_i, u_i) = (ci[0].clone(), ci[1].clone());
+        let cmT = Projective::rand(&mut rng);
+
+        // native NIFS.V
+        let mut transcript = T::new(&poseidon_config);
+        NIFS::<Projective, Pedersen<Projective>, T>::verify(
+            &mut transcript,
+            pp_hash,
+            &U_i,
+            &u_i,
+            &cmT,
+        )?;
+
+        // in-circuit NIFS.V
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let mut transcriptVar = TVar::new(cs.clone(), &poseidon_config);
+        let pp_hashVar = FpVar::new_witness(cs.clone(), || Ok(pp_hash))?;
+        let U_iVar = CommittedInstanceVar::new_witness(cs.clone(), || Ok(U_i.clone()))?;
+        let u_iVar = CommittedInstanceVar::new_witness(cs.clone(), || Ok(u_i.clone()))?;
+        let cmTVar = NonNativeAffineVar::new_witness(cs.clone(), || Ok(cmT))?;
+        let U_i_vec = U_iVar.to_sponge_field_elements()?;
+        NIFSGadget::<Projective, T, TVar>::verify(
+            &mut transcriptVar,
+            pp_hashVar,
+            U_iVar,
+            U_i_vec,
+            u_iVar,
+            Some(cmTVar),
+        )?;
+
+        assert!(!transcript.log.is_empty());
+        assert_eq!(first_divergence(&transcript.log, &transcriptVar.log), None);
+
+        // a diverging transcript is detected at the first differing operation
+        let mut transcript = T::new(&poseidon_config);
+        NIFS::<Projective, Pedersen<Projective>, T>::verify(
+            &mut transcript,
+            pp_hash + Fr::from(1),
+            &U_i,
+            &u_i,
+            &cmT,
+        )?;
+        let i = first_divergence(&transcript.log, &transcriptVar.log);
+        assert!(matches!(
+            transcript.log[i.unwrap()],
+            TranscriptOp::Absorb(_)
+        ));
+        Ok(())
+    }
+}
@@ -0,0 +1,517 @@
+/// This file implements a Keccak256-based `Transcript` and its matching `TranscriptVar`.
+///
+/// The transcript keeps a 32-byte chaining state and a buffer of absorbed bytes. Field elements
+/// are absorbed as their big-endian encoding of `ceil(MODULUS_BIT_SIZE / 8)` bytes, which for
+/// BN254 coincides with the `uint256` word layout of the EVM. Each squeeze computes
+/// `state = keccak256(state || buffer)`, clears the buffer and outputs `state`, so a challenge in
+/// BN254's scalar field is simply `uint256(keccak256(abi.encodePacked(state, inputs))) mod r`,
+/// which is cheap to recompute in Solidity.
+///
+/// Unlike the Poseidon transcript, the squeezed challenges are not absorbed back, since the
+/// chaining state already binds the next challenges to the previous ones.
+///
+/// It can be selected as the transcript `T` of `nova::decider_eth::Decider`, whose KZG challenges
+/// are then derived natively from `pp_hash` and the folded commitments, and recomputed by the
+/// NovaDecider Solidity contract with `keccak256` instead of being checked in-circuit.
+///
+/// Note that Keccak is expensive in-circuit (a permutation costs ~150k constraints), so
+/// `KeccakTranscriptVar` is meant for the parts of the verification whose challenges are also
+/// derived natively on-chain, while the folding itself should keep using Poseidon.
+use ark_crypto_primitives::sponge::{
+    constraints::{AbsorbGadget, CryptographicSpongeVar},
+    Absorb, CryptographicSponge,
+};
+use ark_ec::{AffineRepr, CurveGroup};
+use ark_ff::{BigInteger, PrimeField};
+use ark_r1cs_std::{
+    boolean::Boolean, convert::ToBitsGadget, fields::fp::FpVar, groups::CurveVar, uint8::UInt8,
+};
+use ark_relations::r1cs::{ConstraintSystemRef, SynthesisError};
+use ark_std::marker::PhantomData;
+use sha3::{Digest, Keccak256};
+
+use super::{AbsorbNonNative, AbsorbNonNativeGadget, Transcript, TranscriptVar};
+
+/// Number of bytes of the Keccak256 output, which is also the size of the chaining state.
+const DIGEST_LEN: usize = 32;
+/// Rate (in bytes) of Keccak256.
+const RATE: usize = 136;
+
+/// Returns the number of bytes used to encode an element of `F`.
+fn field_bytes_len<F: PrimeField>() -> usize {
+    (F::MODULUS_BIT_SIZE as usize).div_ceil(8)
+}
+
+/// Returns the big-endian encoding of `x` in `field_bytes_len::<F>()` bytes.
+fn field_to_be_bytes<F: PrimeField>(x: &F) -> Vec<u8> {
+    let mut bytes = x.into_bigint().to_bytes_le();
+    bytes.truncate(field_bytes_len::<F>());
+    bytes.reverse();
+    bytes
+}
+
+#[derive(Clone, Debug)]
+pub struct KeccakTranscript<F: PrimeField> {
+    state: [u8; DIGEST_LEN],
+    buffer: Vec<u8>,
+    _f: PhantomData<F>,
+}
+
+impl<F: PrimeField> KeccakTranscript<F> {
+    fn squeeze_block(&mut self) -> [u8; DIGEST_LEN] {
+        let mut hasher = Keccak256::new();
+        hasher.update(self.state);
+        hasher.update(&self.buffer);
+        self.state.copy_from_slice(&hasher.finalize());
+        self.buffer.clear();
+        self.state
+    }
+}
+
+impl<F: PrimeField> CryptographicSponge for KeccakTranscript<F> {
+    type Config = ();
+
+    fn new(_params: &Self::Config) -> Self {
+        Self {
+            state: [0; DIGEST_LEN],
+            buffer: vec![],
+            _f: PhantomData,
+        }
+    }
+
+    fn absorb(&mut self, input: &impl Absorb) {
+        for x in input.to_sponge_field_elements_as_vec::<F>() {
+            self.buffer.extend(field_to_be_bytes(&x));
+        }
+    }
+
+    fn squeeze_bytes(&mut self, num_bytes: usize) -> Vec<u8> {
+        let mut output = Vec::with_capacity(num_bytes.next_multiple_of(DIGEST_LEN));
+        while output.len() < num_bytes {
+            output.extend(self.squeeze_block());
+        }
+        output.truncate(num_bytes);
+        output
+    }
+
+    fn squeeze_bits(&mut self, num_bits: usize) -> Vec<bool> {
+        self.squeeze_bytes(num_bits.div_ceil(8))
+            .into_iter()
+            .flat_map(|byte| (0..8).map(move |i| (byte >> i) & 1 == 1))
+            .take(num_bits)
+            .collect()
+    }
+
+    fn squeeze_field_elements<T: PrimeField>(&mut self, num_elements: usize) -> Vec<T> {
+        (0..num_elements)
+            .map(|_| T::from_be_bytes_mod_order(&self.squeeze_bytes(field_bytes_len::<T>())))
+            .collect()
+    }
+}
+
+impl<F: PrimeField + Absorb> Transcript<F> for KeccakTranscript<F> {
+    // Compatible with the in-circuit `TranscriptVar::absorb_point`
+    fn absorb_point<C: CurveGroup<BaseField = F>>(&mut self, p: &C) {
+        let (x, y) = p.into_affine().xy().unwrap_or_default();
+        self.absorb(&x);
+        self.absorb(&y);
+    }
+    fn absorb_nonnative<V: AbsorbNonNative>(&mut self, v: &V) {
+        self.absorb(&v.to_native_sponge_field_elements_as_vec::<F>());
+    }
+    fn get_challenge(&mut self) -> F {
+        self.squeeze_field_elements(1)[0]
+    }
+    fn get_challenge_nbits(&mut self, nbits: usize) -> Vec<bool> {
+        self.squeeze_bits(nbits)
+    }
+    fn get_challenges(&mut self, n: usize) -> Vec<F> {
+        self.squeeze_field_elements(n)
+    }
+}
+
+/// In-circuit counterpart of `KeccakTranscript`. Byte strings are represented as vectors of
+/// `Boolean`s, byte by byte, with the bits of each byte in little-endian order.
+#[derive(Clone)]
+pub struct KeccakTranscriptVar<F: PrimeField> {
+    cs: ConstraintSystemRef<F>,
+    state: Vec<Boolean<F>>,
+    buffer: Vec<Boolean<F>>,
+}
+
+impl<F: PrimeField> KeccakTranscriptVar<F> {
+    /// Returns the big-endian encoding of `x`, matching `field_to_be_bytes`.
+    fn field_var_to_be_bits(x: &FpVar<F>) -> Result<Vec<Boolean<F>>, SynthesisError> {
+        let mut bits = x.to_bits_le()?;
+        bits.resize(field_bytes_len::<F>() * 8, Boolean::FALSE);
+        Ok(bits.chunks(8).rev().flatten().cloned().collect())
+    }
+
+    fn squeeze_block(&mut self) -> Vec<Boolean<F>> {
+        let input = [&self.state[..], &self.buffer[..]].concat();
+        self.state = keccak256_gadget(&input);
+        self.buffer.clear();
+        self.state.clone()
+    }
+
+    fn squeeze_bytes_as_bits(&mut self, num_bytes: usize) -> Vec<Boolean<F>> {
+        let mut output = Vec::with_capacity(num_bytes.next_multiple_of(DIGEST_LEN) * 8);
+        while output.len() < num_bytes * 8 {
+            output.extend(self.squeeze_block());
+        }
+        output.truncate(num_bytes * 8);
+        output
+    }
+}
+
+impl<F: PrimeField> CryptographicSpongeVar<F, KeccakTranscript<F>> for KeccakTranscriptVar<F> {
+    type Parameters = ();
+
+    fn new(cs: ConstraintSystemRef<F>, _params: &Self::Parameters) -> Self {
+        Self {
+            cs,
+            state: vec![Boolean::FALSE; DIGEST_LEN * 8],
+            buffer: vec![],
+        }
+    }
+
+    fn cs(&self) -> ConstraintSystemRef<F> {
+        self.cs.clone()
+    }
+
+    fn absorb(&mut self, input: &impl AbsorbGadget<F>) -> Result<(), SynthesisError> {
+        for x in input.to_sponge_field_elements()? {
+            self.buffer.extend(Self::field_var_to_be_bits(&x)?);
+        }
+        Ok(())
+    }
+
+    fn squeeze_bytes(&mut self, num_bytes: usize) -> Result<Vec<UInt8<F>>, SynthesisError> {
+        Ok(self
+            .squeeze_bytes_as_bits(num_bytes)
+            .chunks(8)
+            .map(UInt8::from_bits_le)
+            .collect())
+    }
+
+    fn squeeze_bits(&mut self, num_bits: usize) -> Result<Vec<Boolean<F>>, SynthesisError> {
+        let mut bits = self.squeeze_bytes_as_bits(num_bits.div_ceil(8));
+        bits.truncate(num_bits);
+        Ok(bits)
+    }
+
+    fn squeeze_field_elements(
+        &mut self,
+        num_elements: usize,
+    ) -> Result<Vec<FpVar<F>>, SynthesisError> {
+        (0..num_elements)
+            .map(|_| {
+                let be_bits = self.squeeze_bytes_as_bits(field_bytes_len::<F>());
+                // reverse the order of the bytes to get the little-endian bits of the integer
+                let le_bits = be_bits
+                    .chunks(8)
+                    .rev()
+                    .flatten()
+                    .cloned()
+                    .collect::<Vec<_>>();
+                // `le_bits` may encode an integer larger than the modulus, so we split it into
+                // two parts that both fit in the field, and let the field arithmetic reduce it.
+                let (lo, hi) = le_bits.split_at(F::MODULUS_BIT_SIZE as usize - 1);
+                let lo = Boolean::le_bits_to_fp(lo)?;
+                let hi = Boolean::le_bits_to_fp(hi)?;
+                Ok(lo + hi * F::from(2u8).pow([F::MODULUS_BIT_SIZE as u64 - 1]))
+            })
+            .collect()
+    }
+}
+
+impl<F: PrimeField> TranscriptVar<F, KeccakTranscript<F>> for KeccakTranscriptVar<F> {
+    fn absorb_point<C: CurveGroup<BaseField = F>, GC: CurveVar<C, F>>(
+        &mut self,
+        v: &GC,
+    ) -> Result<(), SynthesisError> {
+        let mut vec = v.to_constraint_field()?;
+        // The last element in the vector tells whether the point is infinity, and as in the
+        // Poseidon transcript, we can avoid absorbing it without loss of soundness.
+        vec.pop();
+        self.absorb(&vec)
+    }
+    fn absorb_nonnative<V: AbsorbNonNativeGadget<F>>(
+        &mut self,
+        v: &V,
+    ) -> Result<(), SynthesisError> {
+        self.absorb(&v.to_native_sponge_field_elements()?)
+    }
+    fn get_challenge(&mut self) -> Result<FpVar<F>, SynthesisError> {
+        let c = self.squeeze_field_elements(1)?;
+        Ok(c[0].clone())
+    }
+    fn get_challenge_nbits(&mut self, nbits: usize) -> Result<Vec<Boolean<F>>, SynthesisError> {
+        self.squeeze_bits(nbits)
+    }
+    fn get_challenges(&mut self, n: usize) -> Result<Vec<FpVar<F>>, SynthesisError> {
+        self.squeeze_field_elements(n)
+    }
+}
+
+/// Rotation offsets of the ρ step, indexed by `x + 5 * y`.
+const ROTATION_OFFSETS: [u32; 25] = [
+    0, 1, 62, 28, 27, //
+    36, 44, 6, 55, 20, //
+    3, 10, 43, 25, 39, //
+    41, 45, 15, 21, 8, //
+    18, 2, 61, 56, 14,
+];
+
+/// Round constants of the ι step.
+const ROUND_CONSTANTS: [u64; 24] = [
+    0x0000000000000001,
+    0x0000000000008082,
+    0x800000000000808A,
+    0x8000000080008000,
+    0x000000000000808B,
+    0x0000000080000001,
+    0x8000000080008081,
+    0x8000000000008009,
+    0x000000000000008A,
+    0x0000000000000088,
+    0x0000000080008009,
+    0x000000008000000A,
+    0x000000008000808B,
+    0x800000000000008B,
+    0x8000000000008089,
+    0x8000000000008003,
+    0x8000000000008002,
+    0x8000000000000080,
+    0x000000000000800A,
+    0x800000008000000A,
+    0x8000000080008081,
+    0x8000000000008080,
+    0x0000000080000001,
+    0x8000000080008008,
+];
+
+type LaneVar<F> = Vec<Boolean<F>>;
+
+fn xor_lanes<F: PrimeField>(a: &[Boolean<F>], b: &[Boolean<F>]) -> LaneVar<F> {
+    a.iter().zip(b).map(|(a, b)| a ^ b).collect()
+}
+
+/// Rotates the 64-bit lane `a` (in little-endian bits) to the left by `n` positions.
+fn rotate_lane<F: PrimeField>(a: &[Boolean<F>], n: u32) -> LaneVar<F> {
+    let n = n as usize;
+    (0..64).map(|z| a[(z + 64 - n) % 64].clone()).collect()
+}
+
+/// Keccak-f[1600] permutation over 25 lanes of 64 bits each.
+fn keccak_f<F: PrimeField>(mut a: Vec<LaneVar<F>>) -> Vec<LaneVar<F>> {
+    for rc in ROUND_CONSTANTS {
+        // θ
+        let c = (0..5)
+            .map(|x| (1..5).fold(a[x].clone(), |acc, y| xor_lanes(&acc, &a[x + 5 * y])))
+            .collect::<Vec<_>>();
+        let d = (0..5)
+            .map(|x| xor_lanes(&c[(x + 4) % 5], &rotate_lane(&c[(x + 1) % 5], 1)))
+            .collect::<Vec<_>>();
+        for (i, lane) in a.iter_mut().enumerate() {
+            *lane = xor_lanes(lane, &d[i % 5]);
+        }
+
+        // ρ and π
+        let mut b = vec![vec![]; 25];
+        for x in 0..5 {
+            for y in 0..5 {
+                b[y + 5 * ((2 * x + 3 * y) % 5)] =
+                    rotate_lane(&a[x + 5 * y], ROTATION_OFFSETS[x + 5 * y]);
+            }
+        }
+
+        // χ
+        for x in 0..5 {
+            for y in 0..5 {
+                a[x + 5 * y] = b[x + 5 * y]
+                    .iter()
+                    .zip(&b[(x + 1) % 5 + 5 * y])
+                    .zip(&b[(x + 2) % 5 + 5 * y])
+                    .map(|((b0, b1), b2)| b0 ^ &(&!b1 & b2))
+                    .collect();
+            }
+        }
+
+        // ι
+        a[0] = a[0]
+            .iter()
+            .enumerate()
+            .map(|(z, bit)| {
+                if (rc >> z) & 1 == 1 {
+                    !bit
+                } else {
+                    bit.clone()
+                }
+            })
+            .collect();
+    }
+    a
+}
+
+/// Computes Keccak256 of the message `input` (given as bits, byte by byte, with the bits of each
+/// byte in little-endian order) and returns the digest in the same representation.
+fn keccak256_gadget<F: PrimeField>(input: &[Boolean<F>]) -> Vec<Boolean<F>> {
+    assert_eq!(input.len() % 8, 0);
+
+    // multi-rate padding with Keccak's domain separator `0x01`
+    let mut padded = input.to_vec();
+    let pad_len = RATE - (input.len() / 8) % RATE;
+    let mut padding = vec![0u8; pad_len];
+    padding[0] |= 0x01;
+    padding[pad_len - 1] |= 0x80;
+    padded.extend(
+        padding
+            .into_iter()
+            .flat_map(|byte| (0..8).map(move |i| Boolean::constant((byte >> i) & 1 == 1))),
+    );
+
+    let mut state = vec![vec![Boolean::FALSE; 64]; 25];
+    for block in padded.chunks(RATE * 8) {
+        for (lane, bits) in state.iter_mut().zip(block.chunks(64)) {
+            *lane = xor_lanes(lane, bits);
+        }
+        state = keccak_f(state);
+    }
+
+    state.concat()[..DIGEST_LEN * 8].to_vec()
+}
+
+#[cfg(test)]
+pub mod tests {
+    use ark_bn254::{constraints::GVar, Bn254, Fq, Fr, G1Projective as G1};
+    use ark_ff::UniformRand;
+    use ark_poly_commit::kzg10::VerifierKey;
+    use ark_r1cs_std::{alloc::AllocVar, R1CSVar};
+    use ark_relations::r1cs::ConstraintSystem;
+    use ark_std::{test_rng, Zero};
+
+    use super::*;
+    use crate::commitment::{
+        kzg::{ProverKey, KZG},
+        CommitmentScheme,
+    };
+    use crate::Error;
+
+    #[test]
+    fn test_keccak256_gadget() -> Result<(), Error> {
+        let rng = &mut test_rng();
+        // cover the single-byte padding case and messages spanning more than one block
+        for len in [0, 1, RATE - 1, RATE, 200] {
+            let msg = (0..len).map(|_| u8::rand(rng)).collect::<Vec<_>>();
+            let expected = Keccak256::digest(&msg).to_vec();
+
+            let cs = ConstraintSystem::<Fr>::new_ref();
+            let msg_var = Vec::<UInt8<Fr>>::new_witness(cs.clone(), || Ok(msg))?;
+            let bits = msg_var
+                .iter()
+                .map(|b| b.to_bits_le())
+                .collect::<Result<Vec<_>, _>>()?
+                .concat();
+            let digest = keccak256_gadget(&bits)
+                .chunks(8)
+                .map(UInt8::from_bits_le)
+                .collect::<Vec<_>>();
+
+            assert_eq!(digest.value()?, expected);
+            assert!(cs.is_satisfied()?);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_transcript_and_transcriptvar_get_challenge() -> Result<(), Error> {
+        let rng = &mut test_rng();
+        let v = (0..5).map(|_| Fr::rand(rng)).collect::<Vec<_>>();
+
+        // use 'native' transcript
+        let mut tr = KeccakTranscript::<Fr>::new(&());
+        tr.absorb(&v);
+        let c = tr.get_challenge();
+        let c_bits = tr.get_challenge_nbits(100);
+        let challenges = tr.get_challenges(2);
+
+        // use 'gadget' transcript
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let mut tr_var = KeccakTranscriptVar::<Fr>::new(cs.clone(), &());
+        let v_var = Vec::<FpVar<Fr>>::new_witness(cs.clone(), || Ok(v))?;
+        tr_var.absorb(&v_var)?;
+        let c_var = tr_var.get_challenge()?;
+        let c_bits_var = tr_var.get_challenge_nbits(100)?;
+        let challenges_var = tr_var.get_challenges(2)?;
+
+        // assert that native & gadget transcripts return the same challenges
+        assert_eq!(c, c_var.value()?);
+        assert_eq!(c_bits, c_bits_var.value()?);
+        assert_eq!(challenges, challenges_var.value()?);
+        assert!(cs.is_satisfied()?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_transcript_challenge_matches_evm_encoding() {
+        let rng = &mut test_rng();
+        let (a, b) = (Fr::rand(rng), Fr::rand(rng));
+
+        let mut tr = KeccakTranscript::<Fr>::new(&());
+        tr.absorb(&a);
+        tr.absorb(&b);
+        let c = tr.get_challenge();
+
+        // keccak256(abi.encodePacked(bytes32(0), uint256(a), uint256(b))) mod r
+        let mut hasher = Keccak256::new();
+        hasher.update([0u8; 32]);
+        hasher.update(a.into_bigint().to_bytes_be());
+        hasher.update(b.into_bigint().to_bytes_be());
+        assert_eq!(c, Fr::from_be_bytes_mod_order(&hasher.finalize()));
+    }
+
+    #[test]
+    fn test_transcript_and_transcriptvar_absorb_native_point() -> Result<(), Error> {
+        // use 'native' transcript
+        let mut tr = KeccakTranscript::<Fq>::new(&());
+        let rng = &mut test_rng();
+
+        let p = G1::rand(rng);
+        tr.absorb_point(&p);
+        let c = tr.get_challenge();
+
+        // use 'gadget' transcript
+        let cs = ConstraintSystem::<Fq>::new_ref();
+        let mut tr_var = KeccakTranscriptVar::<Fq>::new(cs.clone(), &());
+        let p_var = GVar::new_witness(cs.clone(), || Ok(p))?;
+        tr_var.absorb_point(&p_var)?;
+        let c_var = tr_var.get_challenge()?;
+
+        // assert that native & gadget transcripts return the same challenge
+        assert_eq!(c, c_var.value()?);
+        assert!(cs.is_satisfied()?);
+        Ok(())
+    }
+
+    // the schemes are generic over the `Transcript`, so the Keccak transcript can be plugged in
+    // wherever the challenges are also derived on-chain, e.g. for the KZG openings
+    #[test]
+    fn test_kzg_with_keccak_transcript() -> Result<(), Error> {
+        let mut rng = &mut test_rng();
+        let transcript_p = &mut KeccakTranscript::<Fr>::new(&());
+        let transcript_v = &mut KeccakTranscript::<Fr>::new(&());
+
+        let n = 10;
+        let (pk, vk): (ProverKey<G1>, VerifierKey<Bn254>) = KZG::<Bn254>::setup(&mut rng, n)?;
+
+        let v: Vec<Fr> = std::iter::repeat_with(|| Fr::rand(rng)).take(n).collect();
+        let cm = KZG::<Bn254>::commit(&pk, &v, &Fr::zero())?;
+
+        let proof = KZG::<Bn254>::prove(&pk, transcript_p, &cm, &v, &Fr::zero(), None)?;
+
+        // verify the proof:
+        KZG::<Bn254>::verify(&vk, transcript_v, &cm, &proof)?;
+        Ok(())
+    }
+}
@@ -1,10 +1,24 @@
-use ark_crypto_primitives::sponge::{constraints::CryptographicSpongeVar, CryptographicSponge};
+use ark_crypto_primitives::sponge::{
+    constraints::{AbsorbGadget, CryptographicSpongeVar},
+    Absorb, CryptographicSponge,
+};
 use ark_ec::CurveGroup;
 use ark_ff::PrimeField;
-use ark_r1cs_std::{boolean::Boolean, fields::fp::FpVar, groups::CurveVar};
+use ark_r1cs_std::{
+    boolean::Boolean,
+    fields::{fp::FpVar, FieldVar},
+    groups::CurveVar,
+};
 use ark_relations::r1cs::SynthesisError;
+use ark_serialize::SerializationError;
+use ark_std::fmt::Debug;
 
+use crate::Error;
+
+pub mod debug;
+pub mod keccak;
 pub mod poseidon;
+pub mod poseidon2;
 
 /// An interface for objects that can be absorbed by a `Transcript`.
 ///
@@ -56,6 +70,28 @@ impl<F: PrimeField, T: AbsorbNonNativeGadget<F>> AbsorbNonNativeGadget<F> for [T
     }
 }
 
+/// Kinds of labels that can be absorbed by a `Transcript`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum LabelKind {
+    DomainSeparator = 0,
+    Label = 1,
+}
+
+/// Encodes `label` as field elements, so that it is absorbed in the same way
+/// natively and in-circuit.
+///
+/// The first element packs the kind and the length of the label, so that a
+/// label is never confused with a domain separator, nor with a label that it is
+/// a prefix of. It is followed by the bytes of the label, in little-endian
+/// chunks that fit in a field element.
+fn encode_label<F: PrimeField>(kind: LabelKind, label: &[u8]) -> Vec<F> {
+    let chunk_size = (F::MODULUS_BIT_SIZE as usize - 1) / 8;
+    [F::from(((label.len() as u64) << 8) | kind as u64)]
+        .into_iter()
+        .chain(label.chunks(chunk_size).map(F::from_le_bytes_mod_order))
+        .collect()
+}
+
 pub trait Transcript<F: PrimeField>: CryptographicSponge {
     /// `absorb_point` is for absorbing points whose `BaseField` is the field of
     /// the sponge, i.e., the type `C` of these points should satisfy
@@ -85,6 +121,32 @@ pub trait Transcript<F: PrimeField>: CryptographicSponge {
     /// get_challenge_nbits returns a field element of size nbits
     fn get_challenge_nbits(&mut self, nbits: usize) -> Vec<bool>;
     fn get_challenges(&mut self, n: usize) -> Vec<F>;
+
+    /// `domain_separator` absorbs the name of the protocol that is about to
+    /// use the transcript, so that the challenges of different protocols are
+    /// never derived from the same transcript state.
+    fn domain_separator(&mut self, domain: &[u8])
+    where
+        F: Absorb,
+    {
+        self.absorb(&encode_label::<F>(LabelKind::DomainSeparator, domain));
+    }
+    /// `absorb_label` absorbs a label naming the next message that is absorbed
+    /// or the next challenge that is squeezed.
+    fn absorb_label(&mut self, label: &[u8])
+    where
+        F: Absorb,
+    {
+        self.absorb(&encode_label::<F>(LabelKind::Label, label));
+    }
+    /// `absorb_labelled` absorbs the message `v` preceded by its `label`.
+    fn absorb_labelled(&mut self, label: &[u8], v: &impl Absorb)
+    where
+        F: Absorb,
+    {
+        self.absorb_label(label);
+        self.absorb(v);
+    }
 }
 
 pub trait TranscriptVar<F: PrimeField, S: CryptographicSponge>:
@@ -125,4 +187,61 @@ pub trait TranscriptVar<F: PrimeField, S: CryptographicSponge>:
     /// `GC.scalar_mul_le` method.
     fn get_challenge_nbits(&mut self, nbits: usize) -> Result<Vec<Boolean<F>>, SynthesisError>;
     fn get_challenges(&mut self, n: usize) -> Result<Vec<FpVar<F>>, SynthesisError>;
+
+    /// In-circuit counterpart of `Transcript::domain_separator`. The domain
+    /// separator is absorbed as a constant.
+    fn domain_separator(&mut self, domain: &[u8]) -> Result<(), SynthesisError> {
+        let elements = encode_label::<F>(LabelKind::DomainSeparator, domain);
+        self.absorb(
+            &elements
+                .into_iter()
+                .map(FpVar::constant)
+                .collect::<Vec<_>>(),
+        )
+    }
+    /// In-circuit counterpart of `Transcript::absorb_label`. The label is
+    /// absorbed as a constant.
+    fn absorb_label(&mut self, label: &[u8]) -> Result<(), SynthesisError> {
+        let elements = encode_label::<F>(LabelKind::Label, label);
+        self.absorb(
+            &elements
+                .into_iter()
+                .map(FpVar::constant)
+                .collect::<Vec<_>>(),
+        )
+    }
+    /// In-circuit counterpart of `Transcript::absorb_labelled`.
+    fn absorb_labelled(
+        &mut self,
+        label: &[u8],
+        v: &impl AbsorbGadget<F>,
+    ) -> Result<(), SynthesisError> {
+        self.absorb_label(label)?;
+        self.absorb(v)
+    }
+}
+
+/// `SpongeWithGadget` links a native `Transcript` with the `TranscriptVar` that follows it
+/// in-circuit, so that a folding scheme can be made generic over the sponge that it uses to hash
+/// its instances and to derive its challenges.
+pub trait SpongeWithGadget<F: PrimeField>:
+    Transcript<F> + CryptographicSponge<Config: Clone + Debug>
+{
+    /// In-circuit counterpart of the sponge.
+    type Var: TranscriptVar<F, Self, Parameters = Self::Config>;
+
+    /// Identifier of the sponge, used to describe the params and proofs written to disk (see
+    /// `folding::envelope`).
+    const ID: &'static str;
+
+    /// Returns the configuration that is used when none is provided, e.g. when deserializing the
+    /// parameters of a folding scheme.
+    fn default_config() -> Result<Self::Config, Error>;
+
+    /// Serializes `config`, so that it can be bound to the hash of the public parameters.
+    fn config_to_le_bytes(config: &Self::Config) -> Result<Vec<u8>, Error>;
+
+    /// Checks the validity of `config`, as part of the validation of the parameters of a folding
+    /// scheme.
+    fn check_config(config: &Self::Config) -> Result<(), SerializationError>;
 }
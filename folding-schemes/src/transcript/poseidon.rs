@@ -9,8 +9,10 @@ use ark_ec::{AffineRepr, CurveGroup};
 use ark_ff::{BigInteger, PrimeField};
 use ark_r1cs_std::{boolean::Boolean, fields::fp::FpVar, groups::CurveVar};
 use ark_relations::r1cs::SynthesisError;
+use ark_serialize::{CanonicalSerialize, SerializationError, Valid};
 
-use super::{AbsorbNonNative, AbsorbNonNativeGadget, Transcript, TranscriptVar};
+use super::{AbsorbNonNative, AbsorbNonNativeGadget, SpongeWithGadget, Transcript, TranscriptVar};
+use crate::Error;
 
 impl<F: PrimeField + Absorb> Transcript<F> for PoseidonSponge<F> {
     // Compatible with the in-circuit `TranscriptVar::absorb_point`
@@ -81,6 +83,38 @@ impl<F: PrimeField> TranscriptVar<F, PoseidonSponge<F>> for PoseidonSpongeVar<F>
     }
 }
 
+impl<F: PrimeField + Absorb> SpongeWithGadget<F> for PoseidonSponge<F> {
+    type Var = PoseidonSpongeVar<F>;
+
+    const ID: &'static str = "poseidon";
+
+    fn default_config() -> Result<Self::Config, Error> {
+        Ok(poseidon_canonical_config())
+    }
+
+    fn config_to_le_bytes(config: &Self::Config) -> Result<Vec<u8>, Error> {
+        let mut bytes = Vec::new();
+        config.full_rounds.serialize_uncompressed(&mut bytes)?;
+        config.partial_rounds.serialize_uncompressed(&mut bytes)?;
+        config.alpha.serialize_uncompressed(&mut bytes)?;
+        config.ark.serialize_uncompressed(&mut bytes)?;
+        config.mds.serialize_uncompressed(&mut bytes)?;
+        config.rate.serialize_uncompressed(&mut bytes)?;
+        config.capacity.serialize_uncompressed(&mut bytes)?;
+        Ok(bytes)
+    }
+
+    fn check_config(config: &Self::Config) -> Result<(), SerializationError> {
+        config.full_rounds.check()?;
+        config.partial_rounds.check()?;
+        config.alpha.check()?;
+        config.ark.check()?;
+        config.mds.check()?;
+        config.rate.check()?;
+        config.capacity.check()
+    }
+}
+
 /// This Poseidon configuration generator produces a Poseidon configuration with custom parameters
 pub fn poseidon_custom_config<F: PrimeField>(
     full_rounds: usize,
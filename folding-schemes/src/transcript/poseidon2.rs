@@ -0,0 +1,808 @@
+/// This file implements a Poseidon2-based `Transcript` and its matching `TranscriptVar`, following
+/// [Poseidon2](https://eprint.iacr.org/2023/323.pdf).
+///
+/// The sponge is a duplex construction with the same absorbing and squeezing semantics as
+/// arkworks' `PoseidonSponge`, so `Poseidon2Sponge` can be used wherever a `PoseidonSponge` is
+/// used as a `Transcript`, and it can be selected as the sponge of the folding schemes through
+/// `SpongeWithGadget`.
+///
+/// The permutation and its parameters are the ones of the
+/// [reference implementation](https://github.com/HorizenLabs/poseidon2), whose only instance over
+/// BN254's scalar field has width 3: the external rounds use `circ(2, 1, 1)`, the internal rounds
+/// use `J + diag(1, 1, 2)`, where `J` is the all-ones matrix, and the round constants are sampled
+/// with the Grain LFSR of the reference script. The tests check the permutation against the
+/// published test vectors. Since linear layers are free in R1CS, the in-circuit cost of a
+/// permutation is dominated by the S-boxes, i.e., `3 * (full_rounds * t + partial_rounds)`
+/// constraints for `alpha = 5`, which is about the cost of a Poseidon permutation of the same
+/// width: the cheaper linear layers of Poseidon2 mostly speed up the native hashing.
+use ark_crypto_primitives::sponge::{
+    constraints::{AbsorbGadget, CryptographicSpongeVar},
+    poseidon::find_poseidon_ark_and_mds,
+    Absorb, CryptographicSponge,
+};
+use ark_ec::{AffineRepr, CurveGroup};
+use ark_ff::{BigInteger, PrimeField};
+use ark_r1cs_std::{
+    boolean::Boolean,
+    convert::ToBitsGadget,
+    fields::{fp::FpVar, FieldVar},
+    groups::CurveVar,
+    uint8::UInt8,
+};
+use ark_relations::r1cs::{ConstraintSystemRef, SynthesisError};
+use ark_serialize::{CanonicalSerialize, SerializationError, Valid};
+use ark_std::{any::TypeId, ops::Add};
+
+use super::{AbsorbNonNative, AbsorbNonNativeGadget, SpongeWithGadget, Transcript, TranscriptVar};
+use crate::Error;
+
+/// Width of the permutation of the reference implementation over BN254's scalar field, which is
+/// the only one supported.
+const WIDTH: usize = 3;
+
+/// Parameters of the Poseidon2 permutation and of the sponge built on top of it.
+#[derive(Clone, Debug)]
+pub struct Poseidon2Config<F: PrimeField> {
+    /// Number of full rounds, split evenly before and after the partial rounds.
+    pub full_rounds: usize,
+    /// Number of partial rounds.
+    pub partial_rounds: usize,
+    /// Exponent of the S-box.
+    pub alpha: u64,
+    /// Round constants of the full rounds, `full_rounds` rows of `rate + capacity` elements.
+    pub external_rc: Vec<Vec<F>>,
+    /// Round constants of the partial rounds, which are only added to the first state element.
+    pub internal_rc: Vec<F>,
+    /// Diagonal `d` of the internal matrix `J + diag(d)`.
+    pub internal_diag: Vec<F>,
+    /// Number of state elements that are absorbed or squeezed per permutation.
+    pub rate: usize,
+    /// Number of state elements reserved for the capacity.
+    pub capacity: usize,
+}
+
+impl<F: PrimeField> Poseidon2Config<F> {
+    /// Width of the permutation.
+    pub fn width(&self) -> usize {
+        self.rate + self.capacity
+    }
+}
+
+impl<F: PrimeField> Valid for Poseidon2Config<F> {
+    /// Checks the parameters, and that their shapes match the permutation, which would otherwise
+    /// panic when indexing them.
+    fn check(&self) -> Result<(), SerializationError> {
+        self.external_rc.check()?;
+        self.internal_rc.check()?;
+        self.internal_diag.check()?;
+        if self.width() != WIDTH
+            || self.capacity == 0
+            || self.full_rounds % 2 != 0
+            || self.external_rc.len() != self.full_rounds
+            || self.external_rc.iter().any(|rc| rc.len() != WIDTH)
+            || self.internal_rc.len() != self.partial_rounds
+            || self.internal_diag.len() != WIDTH
+        {
+            return Err(SerializationError::InvalidData);
+        }
+        Ok(())
+    }
+}
+
+/// This Poseidon2 configuration generator produces a configuration with custom round numbers.
+///
+/// The capacity is fixed to 1, and the width `t = rate + 1` must be 3, the width of the instance
+/// of the reference implementation over BN254's scalar field. The round constants are sampled as
+/// in the reference script, so that the configuration matches the reference instance when
+/// instantiated with its parameters (see `poseidon2_canonical_config`).
+pub fn poseidon2_custom_config<F: PrimeField>(
+    full_rounds: usize,
+    partial_rounds: usize,
+    alpha: u64,
+    rate: usize,
+) -> Result<Poseidon2Config<F>, Error> {
+    let t = rate + 1;
+    if t != WIDTH {
+        return Err(Error::NotSupported(format!("Poseidon2 with width {t}")));
+    }
+    if full_rounds % 2 != 0 {
+        return Err(Error::NotSupported(format!(
+            "Poseidon2 with an odd number of full rounds ({full_rounds})"
+        )));
+    }
+
+    // The reference script samples `full_rounds * t + partial_rounds` constants from the same
+    // Grain LFSR as Poseidon, whose state only depends on the field size, the width and the
+    // number of rounds. Hence they are the first elements of the stream from which
+    // `find_poseidon_ark_and_mds` samples the `(full_rounds + partial_rounds) * t` constants of
+    // Poseidon with the same parameters.
+    let (ark, _) = find_poseidon_ark_and_mds::<F>(
+        F::MODULUS_BIT_SIZE as u64,
+        rate,
+        full_rounds as u64,
+        partial_rounds as u64,
+        0,
+    );
+    let mut constants = ark.into_iter().flatten();
+    let mut external_rc = (0..full_rounds / 2)
+        .map(|_| constants.by_ref().take(t).collect())
+        .collect::<Vec<Vec<F>>>();
+    let internal_rc = constants.by_ref().take(partial_rounds).collect();
+    external_rc.extend((0..full_rounds / 2).map(|_| constants.by_ref().take(t).collect()));
+
+    Ok(Poseidon2Config {
+        full_rounds,
+        partial_rounds,
+        alpha,
+        external_rc,
+        internal_rc,
+        internal_diag: vec![F::one(), F::one(), F::from(2u8)],
+        rate,
+        capacity: 1,
+    })
+}
+
+/// This Poseidon2 configuration generator returns the instance of the reference implementation
+/// over BN254's scalar field (`POSEIDON2_BN256_PARAMS`), which has width 3 (rate 2), 8 full
+/// rounds, 56 partial rounds and `alpha = 5`, targeting 128 bits of security. Over other fields,
+/// the round constants are sampled in the same way for the field size.
+pub fn poseidon2_canonical_config<F: PrimeField>() -> Result<Poseidon2Config<F>, Error> {
+    let full_rounds = 8;
+    let partial_rounds = 56;
+    let alpha = 5;
+    let rate = 2;
+
+    poseidon2_custom_config(full_rounds, partial_rounds, alpha, rate)
+}
+
+/// Applies the external matrix `circ(2, 1, 1)` to `state`, with additions only, so that it is
+/// shared by the native and the in-circuit permutations.
+fn apply_external_matrix<T: Clone + Add<Output = T>>(state: &mut [T]) {
+    let sum = state[1..]
+        .iter()
+        .fold(state[0].clone(), |acc, x| acc + x.clone());
+    state.iter_mut().for_each(|x| *x = x.clone() + sum.clone());
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum DuplexSpongeMode {
+    /// The sponge is absorbing, and the next element is added at this index of the rate.
+    Absorbing { next_absorb_index: usize },
+    /// The sponge is squeezing, and the next element is read at this index of the rate.
+    Squeezing { next_squeeze_index: usize },
+}
+
+/// Native Poseidon2 sponge.
+#[derive(Clone, Debug)]
+pub struct Poseidon2Sponge<F: PrimeField> {
+    pub config: Poseidon2Config<F>,
+    /// Current state, where the first `capacity` elements are the capacity.
+    pub state: Vec<F>,
+    mode: DuplexSpongeMode,
+}
+
+impl<F: PrimeField> Poseidon2Sponge<F> {
+    fn apply_s_box(&self, x: F) -> F {
+        x.pow([self.config.alpha])
+    }
+
+    fn apply_internal_matrix(state: &mut [F], diag: &[F]) {
+        let sum = state.iter().sum::<F>();
+        state
+            .iter_mut()
+            .zip(diag)
+            .for_each(|(x, d)| *x = *x * d + sum);
+    }
+
+    fn permute(&mut self) {
+        let half_full_rounds = self.config.full_rounds / 2;
+        let mut state = self.state.clone();
+
+        apply_external_matrix(&mut state);
+        for (r, rc) in self.config.external_rc.iter().enumerate() {
+            if r == half_full_rounds {
+                for rc in &self.config.internal_rc {
+                    state[0] = self.apply_s_box(state[0] + rc);
+                    Self::apply_internal_matrix(&mut state, &self.config.internal_diag);
+                }
+            }
+            state
+                .iter_mut()
+                .zip(rc)
+                .for_each(|(x, rc)| *x = self.apply_s_box(*x + rc));
+            apply_external_matrix(&mut state);
+        }
+
+        self.state = state;
+    }
+
+    fn absorb_internal(&mut self, mut rate_start: usize, mut elements: &[F]) {
+        let (capacity, rate) = (self.config.capacity, self.config.rate);
+        loop {
+            if rate_start + elements.len() <= rate {
+                for (i, x) in elements.iter().enumerate() {
+                    self.state[capacity + rate_start + i] += x;
+                }
+                self.mode = DuplexSpongeMode::Absorbing {
+                    next_absorb_index: rate_start + elements.len(),
+                };
+                return;
+            }
+            let n = rate - rate_start;
+            for (i, x) in elements[..n].iter().enumerate() {
+                self.state[capacity + rate_start + i] += x;
+            }
+            self.permute();
+            elements = &elements[n..];
+            rate_start = 0;
+        }
+    }
+
+    fn squeeze_internal(&mut self, mut rate_start: usize, num_elements: usize) -> Vec<F> {
+        let (capacity, rate) = (self.config.capacity, self.config.rate);
+        let mut output = Vec::with_capacity(num_elements);
+        loop {
+            let remaining = num_elements - output.len();
+            if rate_start + remaining <= rate {
+                output.extend_from_slice(
+                    &self.state[capacity + rate_start..capacity + rate_start + remaining],
+                );
+                self.mode = DuplexSpongeMode::Squeezing {
+                    next_squeeze_index: rate_start + remaining,
+                };
+                return output;
+            }
+            output.extend_from_slice(&self.state[capacity + rate_start..capacity + rate]);
+            self.permute();
+            rate_start = 0;
+        }
+    }
+
+    /// Squeezes `num_elements` elements of the sponge field `F`.
+    pub fn squeeze_native_field_elements(&mut self, num_elements: usize) -> Vec<F> {
+        match self.mode {
+            DuplexSpongeMode::Absorbing { .. } => {
+                self.permute();
+                self.squeeze_internal(0, num_elements)
+            }
+            DuplexSpongeMode::Squeezing { next_squeeze_index } => {
+                let mut squeeze_index = next_squeeze_index;
+                if squeeze_index == self.config.rate {
+                    self.permute();
+                    squeeze_index = 0;
+                }
+                self.squeeze_internal(squeeze_index, num_elements)
+            }
+        }
+    }
+}
+
+impl<F: PrimeField> CryptographicSponge for Poseidon2Sponge<F> {
+    type Config = Poseidon2Config<F>;
+
+    fn new(config: &Self::Config) -> Self {
+        Self {
+            config: config.clone(),
+            state: vec![F::zero(); config.width()],
+            mode: DuplexSpongeMode::Absorbing {
+                next_absorb_index: 0,
+            },
+        }
+    }
+
+    fn absorb(&mut self, input: &impl Absorb) {
+        let elements = input.to_sponge_field_elements_as_vec::<F>();
+        if elements.is_empty() {
+            return;
+        }
+        match self.mode {
+            DuplexSpongeMode::Absorbing { next_absorb_index } => {
+                let mut absorb_index = next_absorb_index;
+                if absorb_index == self.config.rate {
+                    self.permute();
+                    absorb_index = 0;
+                }
+                self.absorb_internal(absorb_index, &elements);
+            }
+            DuplexSpongeMode::Squeezing { .. } => {
+                self.absorb_internal(0, &elements);
+            }
+        }
+    }
+
+    fn squeeze_bytes(&mut self, num_bytes: usize) -> Vec<u8> {
+        self.squeeze_bits(num_bytes * 8)
+            .chunks(8)
+            .map(|bits| {
+                bits.iter()
+                    .enumerate()
+                    .fold(0u8, |byte, (i, &bit)| byte | ((bit as u8) << i))
+            })
+            .collect()
+    }
+
+    fn squeeze_bits(&mut self, num_bits: usize) -> Vec<bool> {
+        // only the `MODULUS_BIT_SIZE - 1` lower bits of each element are (close to) uniform
+        let usable_bits = F::MODULUS_BIT_SIZE as usize - 1;
+        self.squeeze_native_field_elements(num_bits.div_ceil(usable_bits))
+            .into_iter()
+            .flat_map(|x| x.into_bigint().to_bits_le()[..usable_bits].to_vec())
+            .take(num_bits)
+            .collect()
+    }
+
+    fn squeeze_field_elements<T: PrimeField>(&mut self, num_elements: usize) -> Vec<T> {
+        if TypeId::of::<T>() == TypeId::of::<F>() {
+            self.squeeze_native_field_elements(num_elements)
+                .into_iter()
+                .map(|x| T::from_le_bytes_mod_order(&x.into_bigint().to_bytes_le()))
+                .collect()
+        } else {
+            (0..num_elements)
+                .map(|_| {
+                    let bits = self.squeeze_bits(T::MODULUS_BIT_SIZE as usize - 1);
+                    T::from_bigint(T::BigInt::from_bits_le(&bits))
+                        // `unwrap` is safe because the integer is smaller than the modulus
+                        .unwrap()
+                })
+                .collect()
+        }
+    }
+}
+
+impl<F: PrimeField + Absorb> Transcript<F> for Poseidon2Sponge<F> {
+    // Compatible with the in-circuit `TranscriptVar::absorb_point`
+    fn absorb_point<C: CurveGroup<BaseField = F>>(&mut self, p: &C) {
+        let (x, y) = p.into_affine().xy().unwrap_or_default();
+        self.absorb(&x);
+        self.absorb(&y);
+    }
+    fn absorb_nonnative<V: AbsorbNonNative>(&mut self, v: &V) {
+        self.absorb(&v.to_native_sponge_field_elements_as_vec::<F>());
+    }
+    fn get_challenge(&mut self) -> F {
+        let c = self.squeeze_native_field_elements(1);
+        self.absorb(&c[0]);
+        c[0]
+    }
+    fn get_challenge_nbits(&mut self, nbits: usize) -> Vec<bool> {
+        let bits = self.squeeze_bits(nbits);
+        self.absorb(&F::from(F::BigInt::from_bits_le(&bits)));
+        bits
+    }
+    fn get_challenges(&mut self, n: usize) -> Vec<F> {
+        let c = self.squeeze_native_field_elements(n);
+        self.absorb(&c);
+        c
+    }
+}
+
+/// In-circuit counterpart of `Poseidon2Sponge`.
+#[derive(Clone)]
+pub struct Poseidon2SpongeVar<F: PrimeField> {
+    cs: ConstraintSystemRef<F>,
+    pub config: Poseidon2Config<F>,
+    /// Current state, where the first `capacity` elements are the capacity.
+    pub state: Vec<FpVar<F>>,
+    mode: DuplexSpongeMode,
+}
+
+impl<F: PrimeField> Poseidon2SpongeVar<F> {
+    fn apply_s_box(&self, x: FpVar<F>) -> Result<FpVar<F>, SynthesisError> {
+        x.pow_by_constant([self.config.alpha])
+    }
+
+    fn apply_internal_matrix(state: &mut [FpVar<F>], diag: &[F]) {
+        let sum = state.iter().fold(FpVar::zero(), |acc, x| acc + x);
+        state
+            .iter_mut()
+            .zip(diag)
+            .for_each(|(x, d)| *x = &*x * *d + &sum);
+    }
+
+    fn permute(&mut self) -> Result<(), SynthesisError> {
+        let half_full_rounds = self.config.full_rounds / 2;
+        let mut state = self.state.clone();
+
+        apply_external_matrix(&mut state);
+        for (r, rc) in self.config.external_rc.iter().enumerate() {
+            if r == half_full_rounds {
+                for rc in &self.config.internal_rc {
+                    state[0] = self.apply_s_box(&state[0] + *rc)?;
+                    Self::apply_internal_matrix(&mut state, &self.config.internal_diag);
+                }
+            }
+            state = state
+                .iter()
+                .zip(rc)
+                .map(|(x, rc)| self.apply_s_box(x + *rc))
+                .collect::<Result<_, _>>()?;
+            apply_external_matrix(&mut state);
+        }
+
+        self.state = state;
+        Ok(())
+    }
+
+    fn absorb_internal(
+        &mut self,
+        mut rate_start: usize,
+        mut elements: &[FpVar<F>],
+    ) -> Result<(), SynthesisError> {
+        let (capacity, rate) = (self.config.capacity, self.config.rate);
+        loop {
+            if rate_start + elements.len() <= rate {
+                for (i, x) in elements.iter().enumerate() {
+                    self.state[capacity + rate_start + i] += x;
+                }
+                self.mode = DuplexSpongeMode::Absorbing {
+                    next_absorb_index: rate_start + elements.len(),
+                };
+                return Ok(());
+            }
+            let n = rate - rate_start;
+            for (i, x) in elements[..n].iter().enumerate() {
+                self.state[capacity + rate_start + i] += x;
+            }
+            self.permute()?;
+            elements = &elements[n..];
+            rate_start = 0;
+        }
+    }
+
+    fn squeeze_internal(
+        &mut self,
+        mut rate_start: usize,
+        num_elements: usize,
+    ) -> Result<Vec<FpVar<F>>, SynthesisError> {
+        let (capacity, rate) = (self.config.capacity, self.config.rate);
+        let mut output = Vec::with_capacity(num_elements);
+        loop {
+            let remaining = num_elements - output.len();
+            if rate_start + remaining <= rate {
+                output.extend_from_slice(
+                    &self.state[capacity + rate_start..capacity + rate_start + remaining],
+                );
+                self.mode = DuplexSpongeMode::Squeezing {
+                    next_squeeze_index: rate_start + remaining,
+                };
+                return Ok(output);
+            }
+            output.extend_from_slice(&self.state[capacity + rate_start..capacity + rate]);
+            self.permute()?;
+            rate_start = 0;
+        }
+    }
+}
+
+impl<F: PrimeField> CryptographicSpongeVar<F, Poseidon2Sponge<F>> for Poseidon2SpongeVar<F> {
+    type Parameters = Poseidon2Config<F>;
+
+    fn new(cs: ConstraintSystemRef<F>, config: &Self::Parameters) -> Self {
+        Self {
+            cs,
+            config: config.clone(),
+            state: vec![FpVar::zero(); config.width()],
+            mode: DuplexSpongeMode::Absorbing {
+                next_absorb_index: 0,
+            },
+        }
+    }
+
+    fn cs(&self) -> ConstraintSystemRef<F> {
+        self.cs.clone()
+    }
+
+    fn absorb(&mut self, input: &impl AbsorbGadget<F>) -> Result<(), SynthesisError> {
+        let elements = input.to_sponge_field_elements()?;
+        if elements.is_empty() {
+            return Ok(());
+        }
+        match self.mode {
+            DuplexSpongeMode::Absorbing { next_absorb_index } => {
+                let mut absorb_index = next_absorb_index;
+                if absorb_index == self.config.rate {
+                    self.permute()?;
+                    absorb_index = 0;
+                }
+                self.absorb_internal(absorb_index, &elements)
+            }
+            DuplexSpongeMode::Squeezing { .. } => self.absorb_internal(0, &elements),
+        }
+    }
+
+    fn squeeze_bytes(&mut self, num_bytes: usize) -> Result<Vec<UInt8<F>>, SynthesisError> {
+        Ok(self
+            .squeeze_bits(num_bytes * 8)?
+            .chunks(8)
+            .map(UInt8::from_bits_le)
+            .collect())
+    }
+
+    fn squeeze_bits(&mut self, num_bits: usize) -> Result<Vec<Boolean<F>>, SynthesisError> {
+        let usable_bits = F::MODULUS_BIT_SIZE as usize - 1;
+        let mut bits = vec![];
+        for x in self.squeeze_field_elements(num_bits.div_ceil(usable_bits))? {
+            bits.extend_from_slice(&x.to_bits_le()?[..usable_bits]);
+        }
+        bits.truncate(num_bits);
+        Ok(bits)
+    }
+
+    fn squeeze_field_elements(
+        &mut self,
+        num_elements: usize,
+    ) -> Result<Vec<FpVar<F>>, SynthesisError> {
+        match self.mode {
+            DuplexSpongeMode::Absorbing { .. } => {
+                self.permute()?;
+                self.squeeze_internal(0, num_elements)
+            }
+            DuplexSpongeMode::Squeezing { next_squeeze_index } => {
+                let mut squeeze_index = next_squeeze_index;
+                if squeeze_index == self.config.rate {
+                    self.permute()?;
+                    squeeze_index = 0;
+                }
+                self.squeeze_internal(squeeze_index, num_elements)
+            }
+        }
+    }
+}
+
+impl<F: PrimeField> TranscriptVar<F, Poseidon2Sponge<F>> for Poseidon2SpongeVar<F> {
+    fn absorb_point<C: CurveGroup<BaseField = F>, GC: CurveVar<C, F>>(
+        &mut self,
+        v: &GC,
+    ) -> Result<(), SynthesisError> {
+        let mut vec = v.to_constraint_field()?;
+        // The last element in the vector tells whether the point is infinity, and as in the
+        // Poseidon transcript, we can avoid absorbing it without loss of soundness.
+        vec.pop();
+        self.absorb(&vec)
+    }
+    fn absorb_nonnative<V: AbsorbNonNativeGadget<F>>(
+        &mut self,
+        v: &V,
+    ) -> Result<(), SynthesisError> {
+        self.absorb(&v.to_native_sponge_field_elements()?)
+    }
+    fn get_challenge(&mut self) -> Result<FpVar<F>, SynthesisError> {
+        let c = self.squeeze_field_elements(1)?;
+        self.absorb(&c[0])?;
+        Ok(c[0].clone())
+    }
+    fn get_challenge_nbits(&mut self, nbits: usize) -> Result<Vec<Boolean<F>>, SynthesisError> {
+        let bits = self.squeeze_bits(nbits)?;
+        self.absorb(&Boolean::le_bits_to_fp(&bits)?)?;
+        Ok(bits)
+    }
+    fn get_challenges(&mut self, n: usize) -> Result<Vec<FpVar<F>>, SynthesisError> {
+        let c = self.squeeze_field_elements(n)?;
+        self.absorb(&c)?;
+        Ok(c)
+    }
+}
+
+impl<F: PrimeField + Absorb> SpongeWithGadget<F> for Poseidon2Sponge<F> {
+    type Var = Poseidon2SpongeVar<F>;
+
+    const ID: &'static str = "poseidon2";
+
+    fn default_config() -> Result<Self::Config, Error> {
+        poseidon2_canonical_config()
+    }
+
+    fn config_to_le_bytes(config: &Self::Config) -> Result<Vec<u8>, Error> {
+        let mut bytes = Vec::new();
+        config.full_rounds.serialize_uncompressed(&mut bytes)?;
+        config.partial_rounds.serialize_uncompressed(&mut bytes)?;
+        config.alpha.serialize_uncompressed(&mut bytes)?;
+        config.external_rc.serialize_uncompressed(&mut bytes)?;
+        config.internal_rc.serialize_uncompressed(&mut bytes)?;
+        config.internal_diag.serialize_uncompressed(&mut bytes)?;
+        config.rate.serialize_uncompressed(&mut bytes)?;
+        config.capacity.serialize_uncompressed(&mut bytes)?;
+        Ok(bytes)
+    }
+
+    fn check_config(config: &Self::Config) -> Result<(), SerializationError> {
+        config.check()
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use ark_bn254::{Fq, Fr, G1Projective as G1};
+    use ark_ff::UniformRand;
+    use ark_r1cs_std::{
+        alloc::AllocVar, groups::curves::short_weierstrass::ProjectiveVar, R1CSVar,
+    };
+    use ark_relations::r1cs::ConstraintSystem;
+    use ark_std::{str::FromStr, test_rng};
+
+    use super::*;
+    use crate::folding::circuits::nonnative::affine::NonNativeAffineVar;
+    use crate::folding::nova::{nifs::nova_circuits::CommittedInstanceVar, CommittedInstance};
+    use crate::folding::traits::{CommittedInstanceOps, CommittedInstanceVarOps};
+
+    #[test]
+    fn test_transcript_and_transcriptvar_absorb_and_squeeze() -> Result<(), Error> {
+        let config = poseidon2_canonical_config::<Fr>()?;
+        let rng = &mut test_rng();
+        // absorb more elements than the rate, and squeeze several times, to go through all the
+        // duplex sponge transitions
+        let v = (0..25).map(|_| Fr::rand(rng)).collect::<Vec<_>>();
+
+        // use 'native' transcript
+        let mut tr = Poseidon2Sponge::<Fr>::new(&config);
+        tr.absorb(&v);
+        let c = tr.get_challenges(13);
+        tr.absorb(&v[0]);
+        let c_bits = tr.get_challenge_nbits(200);
+
+        // use 'gadget' transcript
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let mut tr_var = Poseidon2SpongeVar::<Fr>::new(cs.clone(), &config);
+        let v_var = Vec::<FpVar<Fr>>::new_witness(cs.clone(), || Ok(v.clone()))?;
+        tr_var.absorb(&v_var)?;
+        let c_var = tr_var.get_challenges(13)?;
+        tr_var.absorb(&v_var[0])?;
+        let c_bits_var = tr_var.get_challenge_nbits(200)?;
+
+        // assert that native & gadget transcripts return the same challenges
+        assert_eq!(c, c_var.value()?);
+        assert_eq!(c_bits, c_bits_var.value()?);
+        assert!(cs.is_satisfied()?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_poseidon2_kat() -> Result<(), Error> {
+        // test vector of the BN254 instance of the reference implementation: the permutation of
+        // [0, 1, 2], see https://github.com/HorizenLabs/poseidon2/blob/main/plain_implementations/src/poseidon2/poseidon2.rs
+        let config = poseidon2_canonical_config::<Fr>()?;
+        let expected = [
+            "5297208644449048816064511434384511824916970985131888684874823260532015509555",
+            "21816030159894113985964609355246484851575571273661473159848781012394295965040",
+            "13940986381491601233448981668101586453321811870310341844570924906201623195336",
+        ]
+        .map(|x| Fr::from_str(x).unwrap());
+
+        let mut sponge = Poseidon2Sponge::<Fr>::new(&config);
+        sponge.state = vec![Fr::from(0u8), Fr::from(1u8), Fr::from(2u8)];
+        sponge.permute();
+        assert_eq!(sponge.state, expected);
+
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let mut sponge_var = Poseidon2SpongeVar::<Fr>::new(cs.clone(), &config);
+        sponge_var.state = Vec::<FpVar<Fr>>::new_witness(cs.clone(), || {
+            Ok(vec![Fr::from(0u8), Fr::from(1u8), Fr::from(2u8)])
+        })?;
+        let n_alloc_constraints = cs.num_constraints();
+        sponge_var.permute()?;
+        assert_eq!(sponge_var.state.value()?, expected);
+        assert!(cs.is_satisfied()?);
+        // the permutation costs 3 constraints per S-box
+        assert_eq!(
+            cs.num_constraints() - n_alloc_constraints,
+            3 * (config.full_rounds * config.width() + config.partial_rounds)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_poseidon2_config_errors() {
+        // only the width of the reference instance is supported
+        for rate in [1, 3, 11] {
+            assert!(matches!(
+                poseidon2_custom_config::<Fr>(8, 56, 5, rate),
+                Err(Error::NotSupported(_))
+            ));
+        }
+        // the full rounds must be split evenly around the partial rounds
+        assert!(matches!(
+            poseidon2_custom_config::<Fr>(7, 56, 5, 2),
+            Err(Error::NotSupported(_))
+        ));
+    }
+
+    #[test]
+    fn test_poseidon2_config_check() -> Result<(), Error> {
+        let config = poseidon2_canonical_config::<Fr>()?;
+        Poseidon2Sponge::<Fr>::check_config(&config)?;
+
+        // configs whose shape does not match the permutation are rejected
+        let mut bad_config = config.clone();
+        bad_config.internal_rc.pop();
+        assert!(Poseidon2Sponge::<Fr>::check_config(&bad_config).is_err());
+        let mut bad_config = config.clone();
+        bad_config.external_rc[0].push(Fr::from(1u8));
+        assert!(Poseidon2Sponge::<Fr>::check_config(&bad_config).is_err());
+        let mut bad_config = config;
+        bad_config.rate = 3;
+        assert!(Poseidon2Sponge::<Fr>::check_config(&bad_config).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_transcript_and_transcriptvar_absorb_native_point() -> Result<(), Error> {
+        // use 'native' transcript
+        let config = poseidon2_canonical_config::<Fq>()?;
+        let mut tr = Poseidon2Sponge::<Fq>::new(&config);
+        let rng = &mut test_rng();
+
+        let p = G1::rand(rng);
+        tr.absorb_point(&p);
+        let c = tr.get_challenge();
+
+        // use 'gadget' transcript
+        let cs = ConstraintSystem::<Fq>::new_ref();
+        let mut tr_var = Poseidon2SpongeVar::<Fq>::new(cs.clone(), &config);
+        let p_var = ProjectiveVar::<ark_bn254::g1::Config, FpVar<Fq>>::new_witness(
+            ConstraintSystem::<Fq>::new_ref(),
+            || Ok(p),
+        )?;
+        tr_var.absorb_point(&p_var)?;
+        let c_var = tr_var.get_challenge()?;
+
+        // assert that native & gadget transcripts return the same challenge
+        assert_eq!(c, c_var.value()?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_transcript_and_transcriptvar_absorb_nonnative_point() -> Result<(), Error> {
+        // use 'native' transcript
+        let config = poseidon2_canonical_config::<Fr>()?;
+        let mut tr = Poseidon2Sponge::<Fr>::new(&config);
+        let rng = &mut test_rng();
+
+        let p = G1::rand(rng);
+        tr.absorb_nonnative(&p);
+        let c = tr.get_challenge();
+
+        // use 'gadget' transcript
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let mut tr_var = Poseidon2SpongeVar::<Fr>::new(cs.clone(), &config);
+        let p_var =
+            NonNativeAffineVar::<G1>::new_witness(ConstraintSystem::<Fr>::new_ref(), || Ok(p))?;
+        tr_var.absorb_nonnative(&p_var)?;
+        let c_var = tr_var.get_challenge()?;
+
+        // assert that native & gadget transcripts return the same challenge
+        assert_eq!(c, c_var.value()?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_poseidon2_committed_instance_hash() -> Result<(), Error> {
+        // the in-circuit hash of a committed instance, as done in Nova's AugmentedFCircuit, matches
+        // the native one
+        let config = poseidon2_canonical_config::<Fr>()?;
+        let rng = &mut test_rng();
+        let U_i = CommittedInstance::<G1> {
+            cmE: G1::rand(rng),
+            u: Fr::rand(rng),
+            cmW: G1::rand(rng),
+            x: vec![Fr::rand(rng), Fr::rand(rng)],
+        };
+        let (pp_hash, i) = (Fr::rand(rng), Fr::rand(rng));
+        let z = (0..5).map(|_| Fr::rand(rng)).collect::<Vec<_>>();
+
+        let cs = ConstraintSystem::<Fr>::new_ref();
+        let U_i_var = CommittedInstanceVar::<G1>::new_witness(cs.clone(), || Ok(U_i.clone()))?;
+        let pp_hash_var = FpVar::new_witness(cs.clone(), || Ok(pp_hash))?;
+        let i_var = FpVar::new_witness(cs.clone(), || Ok(i))?;
+        let z_var = Vec::<FpVar<Fr>>::new_witness(cs.clone(), || Ok(z.clone()))?;
+
+        let sponge = Poseidon2SpongeVar::<Fr>::new(cs.clone(), &config);
+        let (h_var, _) = U_i_var.hash(&sponge, &pp_hash_var, &i_var, &z_var, &z_var)?;
+        assert_eq!(
+            h_var.value()?,
+            U_i.hash(&Poseidon2Sponge::<Fr>::new(&config), pp_hash, i, &z, &z)
+        );
+        assert!(cs.is_satisfied()?);
+        Ok(())
+    }
+}
@@ -139,7 +139,7 @@ pub fn eval_mle<F: PrimeField>(
 
     for i in 1..n_vars + 1 {
         let r = point[i - 1].clone();
-        for b in 0..(1 << (n_vars - 1)) {
+        for b in 0..(1 << (n_vars - i)) {
             let left = poly[b << 1].clone();
             let right = poly[(b << 1) + 1].clone();
             poly[b] = left.clone() + r.clone() * (right - left);
@@ -0,0 +1,393 @@
+//! Serde helpers for the `serde` feature, used through `#[serde(with = "...")]` to give the IVC
+//! proofs, committed instances, decider proofs and verifier params a stable, human-readable JSON
+//! encoding:
+//! - field elements are encoded as `0x`-prefixed big-endian hex strings, padded to the byte size
+//!   of the field,
+//! - points are encoded as `{ "x": <field>, "y": <field> }` with their affine coordinates, and the
+//!   point at infinity as `null`,
+//! - values without a JSON structure of their own (e.g. the SNARK proofs and verifying keys) are
+//!   encoded as the `0x`-prefixed hex string of their compressed `CanonicalSerialize` bytes.
+//!
+//! Decoding rejects non-canonical field elements and points that are not in the curve subgroup.
+use ark_ec::{short_weierstrass::SWFlags, AffineRepr, CurveGroup};
+use ark_ff::{BigInteger, PrimeField};
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize, CanonicalSerializeWithFlags};
+use ark_std::Zero;
+use serde::{
+    de::Error as _, ser::Error as _, ser::SerializeStruct, Deserialize, Deserializer, Serialize,
+    Serializer,
+};
+
+use crate::Curve;
+
+fn to_hex(bytes: &[u8]) -> String {
+    let mut s = String::with_capacity(2 + 2 * bytes.len());
+    s.push_str("0x");
+    for b in bytes {
+        s.push_str(&format!("{b:02x}"));
+    }
+    s
+}
+
+fn from_hex(s: &str) -> Result<Vec<u8>, String> {
+    let s = s.strip_prefix("0x").unwrap_or(s);
+    if !s.is_ascii() {
+        return Err(format!("invalid hex string {s}"));
+    }
+    // allow odd-length strings, as in `0x1`
+    let s = if s.len() % 2 == 1 {
+        format!("0{s}")
+    } else {
+        s.to_string()
+    };
+    (0..s.len())
+        .step_by(2)
+        .map(|i| {
+            u8::from_str_radix(&s[i..i + 2], 16).map_err(|_| format!("invalid hex string {s}"))
+        })
+        .collect()
+}
+
+fn field_to_hex<F: PrimeField>(f: &F) -> String {
+    to_hex(&f.into_bigint().to_bytes_be())
+}
+
+fn field_from_hex<F: PrimeField>(s: &str) -> Result<F, String> {
+    let n_bytes = F::zero().into_bigint().to_bytes_be().len();
+    let bytes = from_hex(s)?;
+    if bytes.len() > n_bytes {
+        return Err(format!("{s} is larger than the field size"));
+    }
+    let mut padded = vec![0; n_bytes - bytes.len()];
+    padded.extend(bytes);
+    let f = F::from_be_bytes_mod_order(&padded);
+    // `from_be_bytes_mod_order` reduces the value, so a non-canonical one is detected by not
+    // roundtripping
+    if f.into_bigint().to_bytes_be() != padded {
+        return Err(format!("{s} is not a canonical field element"));
+    }
+    Ok(f)
+}
+
+fn point_from_coordinates<C: Curve>(x: C::BaseField, y: C::BaseField) -> Result<C, String> {
+    // `Curve` does not provide a method to construct a point from its coordinates, so it is
+    // deserialized from them instead, as in `NonNativeAffineVar::value`
+    let mut bytes = vec![];
+    x.serialize_uncompressed(&mut bytes)
+        .map_err(|e| e.to_string())?;
+    y.serialize_with_flags(
+        &mut bytes,
+        if y <= -y {
+            SWFlags::YIsPositive
+        } else {
+            SWFlags::YIsNegative
+        },
+    )
+    .map_err(|e| e.to_string())?;
+    // the checked deserialization ensures that the point is on the curve and in its subgroup
+    C::deserialize_uncompressed(&bytes[..]).map_err(|_| "invalid point".to_string())
+}
+
+/// Serializes a field element by reference, to be used in sequences.
+struct FieldRef<'a, F>(&'a F);
+
+impl<F: PrimeField> Serialize for FieldRef<'_, F> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&field_to_hex(self.0))
+    }
+}
+
+/// Deserializes an owned field element, to be used in sequences.
+struct FieldOwned<F>(F);
+
+impl<'de, F: PrimeField> Deserialize<'de> for FieldOwned<F> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        field_from_hex(&s).map(FieldOwned).map_err(D::Error::custom)
+    }
+}
+
+/// Serializes a point by reference, to be used in sequences.
+struct PointRef<'a, C>(&'a C);
+
+impl<C: Curve> Serialize for PointRef<'_, C> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self.0.into_affine().xy() {
+            Some((x, y)) => {
+                let mut state = serializer.serialize_struct("Point", 2)?;
+                state.serialize_field("x", &FieldRef(&x))?;
+                state.serialize_field("y", &FieldRef(&y))?;
+                state.end()
+            }
+            None => serializer.serialize_none(),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(bound = "")]
+struct Coordinates<F: PrimeField> {
+    x: FieldOwned<F>,
+    y: FieldOwned<F>,
+}
+
+/// Deserializes an owned point, to be used in sequences.
+struct PointOwned<C>(C);
+
+impl<'de, C: Curve> Deserialize<'de> for PointOwned<C> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        match Option::<Coordinates<C::BaseField>>::deserialize(deserializer)? {
+            Some(Coordinates { x, y }) => point_from_coordinates(x.0, y.0)
+                .map(PointOwned)
+                .map_err(D::Error::custom),
+            None => Ok(PointOwned(C::zero())),
+        }
+    }
+}
+
+/// Encoding of a field element.
+pub mod field {
+    use super::*;
+
+    pub fn serialize<F: PrimeField, S: Serializer>(
+        f: &F,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        FieldRef(f).serialize(serializer)
+    }
+
+    pub fn deserialize<'de, F: PrimeField, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<F, D::Error> {
+        Ok(FieldOwned::deserialize(deserializer)?.0)
+    }
+}
+
+/// Encoding of a vector of field elements.
+pub mod field_vec {
+    use super::*;
+
+    pub fn serialize<F: PrimeField, S: Serializer>(
+        v: &[F],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(v.iter().map(FieldRef))
+    }
+
+    pub fn deserialize<'de, F: PrimeField, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Vec<F>, D::Error> {
+        Ok(Vec::<FieldOwned<F>>::deserialize(deserializer)?
+            .into_iter()
+            .map(|f| f.0)
+            .collect())
+    }
+}
+
+/// Encoding of an array of field elements.
+pub mod field_array {
+    use super::*;
+
+    pub fn serialize<F: PrimeField, S: Serializer, const N: usize>(
+        v: &[F; N],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        field_vec::serialize(v, serializer)
+    }
+
+    pub fn deserialize<'de, F: PrimeField, D: Deserializer<'de>, const N: usize>(
+        deserializer: D,
+    ) -> Result<[F; N], D::Error> {
+        field_vec::deserialize(deserializer)?
+            .try_into()
+            .map_err(|v: Vec<F>| {
+                D::Error::custom(format!("expected {N} field elements, found {}", v.len()))
+            })
+    }
+}
+
+/// Encoding of a point.
+pub mod point {
+    use super::*;
+
+    pub fn serialize<C: Curve, S: Serializer>(p: &C, serializer: S) -> Result<S::Ok, S::Error> {
+        PointRef(p).serialize(serializer)
+    }
+
+    pub fn deserialize<'de, C: Curve, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<C, D::Error> {
+        Ok(PointOwned::deserialize(deserializer)?.0)
+    }
+}
+
+/// Encoding of a vector of points.
+pub mod point_vec {
+    use super::*;
+
+    pub fn serialize<C: Curve, S: Serializer>(v: &[C], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(v.iter().map(PointRef))
+    }
+
+    pub fn deserialize<'de, C: Curve, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Vec<C>, D::Error> {
+        Ok(Vec::<PointOwned<C>>::deserialize(deserializer)?
+            .into_iter()
+            .map(|p| p.0)
+            .collect())
+    }
+}
+
+/// Encoding of any `CanonicalSerialize` value, as the hex string of its compressed bytes.
+pub mod canonical {
+    use super::*;
+
+    pub fn serialize<T: CanonicalSerialize, S: Serializer>(
+        v: &T,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let mut bytes = vec![];
+        v.serialize_compressed(&mut bytes)
+            .map_err(S::Error::custom)?;
+        serializer.serialize_str(&to_hex(&bytes))
+    }
+
+    pub fn deserialize<'de, T: CanonicalDeserialize, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<T, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        let bytes = from_hex(&s).map_err(D::Error::custom)?;
+        T::deserialize_compressed(&bytes[..]).map_err(D::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_pallas::{Fr, Projective as G1};
+    use ark_std::UniformRand;
+    use ark_vesta::Projective as G2;
+    use serde::de::DeserializeOwned;
+
+    use super::*;
+    use crate::commitment::pedersen::Pedersen;
+    use crate::folding::{
+        hypernova::HyperNova,
+        nova::{Nova, PreprocessorParam as NovaPreprocessorParam},
+        protogalaxy::ProtoGalaxy,
+    };
+    use crate::frontend::{utils::CubicFCircuit, FCircuit};
+    use crate::transcript::poseidon::poseidon_canonical_config;
+    use crate::{Error, FoldingScheme};
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    #[serde(bound = "")]
+    struct Values<C: Curve> {
+        #[serde(with = "field")]
+        f: C::ScalarField,
+        #[serde(with = "field_vec")]
+        v: Vec<C::ScalarField>,
+        #[serde(with = "field_array")]
+        a: [C::ScalarField; 2],
+        #[serde(with = "point")]
+        p: C,
+        #[serde(with = "point_vec")]
+        ps: Vec<C>,
+    }
+
+    #[test]
+    fn test_values_roundtrip() {
+        let mut rng = ark_std::test_rng();
+        let values = Values::<G1> {
+            f: Fr::rand(&mut rng),
+            v: vec![Fr::from(0_u32), Fr::from(1_u32), -Fr::from(1_u32)],
+            a: [Fr::rand(&mut rng), Fr::rand(&mut rng)],
+            p: G1::rand(&mut rng),
+            ps: vec![G1::zero(), G1::rand(&mut rng)],
+        };
+
+        let json = serde_json::to_value(&values).unwrap();
+        assert_eq!(json["v"][1], "0x".to_string() + &"0".repeat(63) + "1");
+        assert!(json["p"]["x"].is_string() && json["p"]["y"].is_string());
+        assert!(json["ps"][0].is_null());
+
+        let decoded: Values<G1> = serde_json::from_value(json).unwrap();
+        assert_eq!(decoded, values);
+    }
+
+    #[test]
+    fn test_reject_invalid_values() {
+        let mut rng = ark_std::test_rng();
+        let values = Values::<G1> {
+            f: Fr::rand(&mut rng),
+            v: vec![],
+            a: [Fr::rand(&mut rng), Fr::rand(&mut rng)],
+            p: G1::rand(&mut rng),
+            ps: vec![],
+        };
+        let json = serde_json::to_value(&values).unwrap();
+
+        // non-canonical field element (the modulus itself)
+        let mut invalid = json.clone();
+        invalid["f"] = to_hex(&Fr::MODULUS.to_bytes_be()).into();
+        assert!(serde_json::from_value::<Values<G1>>(invalid).is_err());
+
+        // point not on the curve
+        let mut invalid = json.clone();
+        invalid["p"]["y"] = "0x1".into();
+        assert!(serde_json::from_value::<Values<G1>>(invalid).is_err());
+
+        // wrong length of a fixed-size array
+        let mut invalid = json;
+        invalid["a"] = vec!["0x1"].into();
+        assert!(serde_json::from_value::<Values<G1>>(invalid).is_err());
+    }
+
+    #[test]
+    fn test_ivc_proof_roundtrip() -> Result<(), Error> {
+        let poseidon_config = poseidon_canonical_config::<Fr>();
+        type FC = CubicFCircuit<Fr>;
+        let f_circuit = FC::new(())?;
+
+        type N = Nova<G1, G2, FC, Pedersen<G1>, Pedersen<G2>, false>;
+        let prep_param = NovaPreprocessorParam::new(poseidon_config.clone(), f_circuit);
+        test_ivc_proof_roundtrip_opt::<FC, N>(prep_param.clone())?;
+
+        type HN = HyperNova<G1, G2, FC, Pedersen<G1>, Pedersen<G2>, 1, 1, false>;
+        test_ivc_proof_roundtrip_opt::<FC, HN>(prep_param)?;
+
+        type P = ProtoGalaxy<G1, G2, FC, Pedersen<G1>, Pedersen<G2>, 1>;
+        test_ivc_proof_roundtrip_opt::<FC, P>((poseidon_config, f_circuit))?;
+        Ok(())
+    }
+
+    fn test_ivc_proof_roundtrip_opt<FC: FCircuit<Fr, Params = ()>, FS: FoldingScheme<G1, G2, FC>>(
+        prep_param: FS::PreprocessorParam,
+    ) -> Result<(), Error>
+    where
+        FS::IVCProof: Serialize + DeserializeOwned,
+    {
+        let mut rng = ark_std::test_rng();
+        let fs_params = FS::preprocess(&mut rng, &prep_param)?;
+        let mut fs = FS::init(&fs_params, FC::new(())?, vec![Fr::from(3_u32)])?;
+        for _ in 0..3 {
+            fs.prove_step(&mut rng, FC::ExternalInputs::default(), None)?;
+        }
+        let ivc_proof = fs.ivc_proof();
+
+        let json = serde_json::to_string(&ivc_proof).map_err(|e| Error::Other(e.to_string()))?;
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(
+            value["i"],
+            to_hex(&Fr::from(3_u32).into_bigint().to_bytes_be())
+        );
+        assert!(value["z_0"][0].is_string());
+        assert!(value["cf_U_i"]["cmW"]["x"].is_string());
+
+        let decoded: FS::IVCProof =
+            serde_json::from_str(&json).map_err(|e| Error::Other(e.to_string()))?;
+        assert_eq!(decoded, ivc_proof);
+        FS::verify(fs_params.1, decoded)?;
+        Ok(())
+    }
+}
@@ -1,7 +1,6 @@
 use std::path::Path;
 use std::path::PathBuf;
 
-use ark_crypto_primitives::sponge::poseidon::PoseidonConfig;
 use ark_ec::AffineRepr;
 use ark_ff::PrimeField;
 use ark_serialize::CanonicalSerialize;
@@ -9,12 +8,16 @@ use sha3::{Digest, Sha3_256};
 
 use crate::arith::ArithSerializer;
 use crate::commitment::CommitmentScheme;
+use crate::transcript::SpongeWithGadget;
 use crate::{Curve, Error};
 
 pub mod gadgets;
 pub mod hypercube;
+#[cfg(feature = "serde")]
+pub mod json;
 pub mod lagrange_poly;
 pub mod mle;
+pub mod msm;
 pub mod vec;
 
 // expose espresso local modules
@@ -40,19 +43,21 @@ pub fn get_cm_coordinates<C: Curve>(cm: &C) -> Vec<C::BaseField> {
     vec![cm_x, cm_y]
 }
 
-/// returns the hash of the given public parameters of the Folding Scheme
-pub fn pp_hash<C1, C2, CS1, CS2, const H: bool>(
+/// returns the hash of the given public parameters of the Folding Scheme, where `sponge_config`
+/// is the configuration of the sponge `T` used by the scheme
+pub fn pp_hash<C1, C2, CS1, CS2, const H: bool, T>(
     arith: &impl ArithSerializer,
     cf_arith: &impl ArithSerializer,
     cs_vp: &CS1::VerifierParams,
     cf_cs_vp: &CS2::VerifierParams,
-    poseidon_config: &PoseidonConfig<C1::ScalarField>,
+    sponge_config: &T::Config,
 ) -> Result<C1::ScalarField, Error>
 where
     C1: Curve,
     C2: Curve,
     CS1: CommitmentScheme<C1, H>,
     CS2: CommitmentScheme<C2, H>,
+    T: SpongeWithGadget<C1::ScalarField>,
 {
     let mut hasher = Sha3_256::new();
 
@@ -70,30 +75,8 @@ where
     let mut cf_cs_vp_bytes = Vec::new();
     cf_cs_vp.serialize_uncompressed(&mut cf_cs_vp_bytes)?;
     hasher.update(cf_cs_vp_bytes);
-    // poseidon params
-    let mut poseidon_config_bytes = Vec::new();
-    poseidon_config
-        .full_rounds
-        .serialize_uncompressed(&mut poseidon_config_bytes)?;
-    poseidon_config
-        .partial_rounds
-        .serialize_uncompressed(&mut poseidon_config_bytes)?;
-    poseidon_config
-        .alpha
-        .serialize_uncompressed(&mut poseidon_config_bytes)?;
-    poseidon_config
-        .ark
-        .serialize_uncompressed(&mut poseidon_config_bytes)?;
-    poseidon_config
-        .mds
-        .serialize_uncompressed(&mut poseidon_config_bytes)?;
-    poseidon_config
-        .rate
-        .serialize_uncompressed(&mut poseidon_config_bytes)?;
-    poseidon_config
-        .capacity
-        .serialize_uncompressed(&mut poseidon_config_bytes)?;
-    hasher.update(poseidon_config_bytes);
+    // sponge params
+    hasher.update(T::config_to_le_bytes(sponge_config)?);
 
     let public_params_hash = hasher.finalize();
     Ok(C1::ScalarField::from_le_bytes_mod_order(
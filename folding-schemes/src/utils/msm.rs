@@ -0,0 +1,143 @@
+use ark_ec::{AffineRepr, VariableBaseMSM};
+use ark_ff::PrimeField;
+use ark_std::{cfg_iter, log2, Zero};
+use rayon::prelude::*;
+
+use crate::Curve;
+
+/// Computes `<bases, scalars>` exploiting the small scalars, which dominate the witnesses of
+/// circuits with many bit decompositions: zeros are skipped, the bases of ones are summed, the
+/// scalars that fit in a `u64` go through a bucketed MSM with as many windows as bits their maximum
+/// has, and only the remaining ones go through the full-size MSM.
+pub fn small_value_msm<C: Curve>(bases: &[C::Affine], scalars: &[C::ScalarField]) -> C {
+    let bigints: Vec<_> = cfg_iter!(scalars).map(|s| s.into_bigint()).collect();
+    let is_small =
+        |s: &<C::ScalarField as PrimeField>::BigInt| s.as_ref()[1..].iter().all(|l| *l == 0);
+    if !bigints.iter().any(is_small) {
+        return <C as VariableBaseMSM>::msm_bigint(bases, &bigints);
+    }
+
+    let mut ones = vec![];
+    let (mut small_bases, mut small_scalars) = (vec![], vec![]);
+    let (mut large_bases, mut large_scalars) = (vec![], vec![]);
+    for (base, s) in bases.iter().zip(bigints) {
+        if !is_small(&s) {
+            large_bases.push(*base);
+            large_scalars.push(s);
+            continue;
+        }
+        match s.as_ref()[0] {
+            0 => {}
+            1 => ones.push(*base),
+            small => {
+                small_bases.push(*base);
+                small_scalars.push(small);
+            }
+        }
+    }
+
+    let ones_sum: C = cfg_iter!(ones).map(|base| base.into_group()).sum();
+    let large_msm = if large_bases.is_empty() {
+        C::zero()
+    } else {
+        <C as VariableBaseMSM>::msm_bigint(&large_bases, &large_scalars)
+    };
+    ones_sum + u64_msm::<C>(&small_bases, &small_scalars) + large_msm
+}
+
+/// Pippenger's MSM for `u64` scalars, whose number of windows depends on the bit size of the
+/// largest scalar instead of the size of the field.
+fn u64_msm<C: Curve>(bases: &[C::Affine], scalars: &[u64]) -> C {
+    let max_bits = 64 - scalars.iter().fold(0, |acc, s| acc | s).leading_zeros() as usize;
+    if max_bits == 0 {
+        return C::zero();
+    }
+    let c = if bases.len() < 32 {
+        3
+    } else {
+        // same window size heuristic as arkworks' MSM (ln(n) + 2)
+        log2(bases.len()) as usize * 69 / 100 + 2
+    }
+    .min(max_bits);
+    let n_windows = max_bits.div_ceil(c);
+
+    let window_sums: Vec<C> = (0..n_windows)
+        .into_par_iter()
+        .map(|w| {
+            let mut buckets = vec![C::zero(); (1 << c) - 1];
+            for (base, s) in bases.iter().zip(scalars) {
+                let digit = ((s >> (w * c)) & ((1 << c) - 1)) as usize;
+                if digit != 0 {
+                    buckets[digit - 1] += *base;
+                }
+            }
+            // Σ_d d⋅buckets[d-1], through running sums
+            let mut running_sum = C::zero();
+            let mut res = C::zero();
+            for bucket in buckets.into_iter().rev() {
+                running_sum += bucket;
+                res += running_sum;
+            }
+            res
+        })
+        .collect();
+
+    window_sums
+        .iter()
+        .rev()
+        .fold(C::zero(), |mut res, window_sum| {
+            for _ in 0..c {
+                res.double_in_place();
+            }
+            res + window_sum
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_pallas::{Fr, Projective};
+    use ark_std::{test_rng, UniformRand};
+
+    use super::*;
+    use crate::Error;
+
+    #[test]
+    fn test_small_value_msm() -> Result<(), Error> {
+        let mut rng = test_rng();
+        let n = 100;
+        let bases: Vec<_> = std::iter::repeat_with(|| ark_pallas::Affine::rand(&mut rng))
+            .take(n)
+            .collect();
+
+        let vectors: Vec<Vec<Fr>> = vec![
+            // only large scalars
+            (0..n).map(|_| Fr::rand(&mut rng)).collect(),
+            // only zeros
+            vec![Fr::zero(); n],
+            // bits
+            (0..n).map(|i| Fr::from((i % 3 == 0) as u64)).collect(),
+            // mixed zeros, ones, small and large scalars
+            (0..n)
+                .map(|i| match i % 5 {
+                    0 => Fr::zero(),
+                    1 => Fr::from(1),
+                    2 => Fr::from(i as u64 * 1000),
+                    3 => Fr::from(u64::MAX - i as u64),
+                    _ => Fr::rand(&mut rng),
+                })
+                .collect(),
+        ];
+        for v in vectors {
+            assert_eq!(
+                small_value_msm::<Projective>(&bases, &v),
+                Projective::msm_unchecked(&bases, &v)
+            );
+            // also with fewer scalars than bases
+            assert_eq!(
+                small_value_msm::<Projective>(&bases, &v[..10]),
+                Projective::msm_unchecked(&bases[..10], &v[..10])
+            );
+        }
+        Ok(())
+    }
+}
@@ -4,8 +4,14 @@ use ark_groth16::Groth16;
 use crypto::digest::Digest;
 use crypto::sha3::Sha3;
 use folding_schemes::commitment::kzg::KZG;
+use folding_schemes::folding::hypernova::decider_eth::Proof as HyperNovaProof;
+use folding_schemes::folding::hypernova::{cccs::CCCS, lcccs::LCCCS};
 use folding_schemes::folding::nova::decider_eth::Proof;
 use folding_schemes::folding::nova::CommittedInstance;
+use folding_schemes::folding::protogalaxy::constants::{INCOMING, RUNNING};
+use folding_schemes::folding::protogalaxy::decider_eth::Proof as ProtoGalaxyProof;
+use folding_schemes::folding::protogalaxy::CommittedInstance as ProtoGalaxyCommittedInstance;
+use folding_schemes::folding::traits::CommittedInstanceOps;
 use folding_schemes::Error;
 use num_bigint::BigUint;
 
@@ -21,6 +27,30 @@ pub enum NovaVerificationMode {
     OpaqueWithInputs,
 }
 
+/// Specifies which API to use for a HyperNova proof verification in a contract.
+#[derive(Copy, Clone, Debug, Default)]
+pub enum HyperNovaVerificationMode {
+    /// Use the `verifyHyperNovaProof` function.
+    #[default]
+    Explicit,
+    /// Use the `verifyOpaqueHyperNovaProof` function.
+    Opaque,
+    /// Use the `verifyOpaqueHyperNovaProofWithInputs` function.
+    OpaqueWithInputs,
+}
+
+/// Specifies which API to use for a ProtoGalaxy proof verification in a contract.
+#[derive(Copy, Clone, Debug, Default)]
+pub enum ProtoGalaxyVerificationMode {
+    /// Use the `verifyProtoGalaxyProof` function.
+    #[default]
+    Explicit,
+    /// Use the `verifyOpaqueProtoGalaxyProof` function.
+    Opaque,
+    /// Use the `verifyOpaqueProtoGalaxyProofWithInputs` function.
+    OpaqueWithInputs,
+}
+
 /// Formats call data from a vec of bytes to a hashmap
 /// Useful for debugging directly on the EVM
 /// !! Should follow the contract's function signature, we assume the order of arguments is correct
@@ -33,17 +63,31 @@ pub fn get_formatted_calldata(calldata: Vec<u8>) -> Vec<String> {
     formatted_calldata
 }
 
-/// Prepares solidity calldata for calling the NovaDecider contract
-pub fn prepare_calldata_for_nova_cyclefold_verifier(
+/// Prepares solidity calldata for calling the NovaDecider contract.
+/// When `H` is set (ie. the decided Nova instance uses hiding commitments), the blinding
+/// evaluations of the KZG openings are appended to the KZG evaluations, and their masks to the KZG
+/// proofs, as expected by a NovaDecider contract rendered with hiding enabled.
+pub fn prepare_calldata_for_nova_cyclefold_verifier<const H: bool>(
     verification_mode: NovaVerificationMode,
     i: ark_bn254::Fr,
     z_0: Vec<ark_bn254::Fr>,
     z_i: Vec<ark_bn254::Fr>,
     running_instance: &CommittedInstance<ark_bn254::G1Projective>,
     incoming_instance: &CommittedInstance<ark_bn254::G1Projective>,
-    proof: &Proof<ark_bn254::G1Projective, KZG<Bn254>, Groth16<Bn254>>,
+    proof: &Proof<ark_bn254::G1Projective, KZG<Bn254, H>, Groth16<Bn254>, H>,
 ) -> Result<Vec<u8>, Error> {
-    let selector = get_function_selector(verification_mode, z_0.len());
+    let selector = get_function_selector(verification_mode, z_0.len(), H);
+    let (random_vs, masks) = if H {
+        (
+            vec![
+                proof.kzg_proofs()[0].random_v,
+                proof.kzg_proofs()[1].random_v,
+            ],
+            vec![proof.kzg_proofs()[0].mask, proof.kzg_proofs()[1].mask],
+        )
+    } else {
+        (vec![], vec![])
+    };
 
     Ok([
         selector.to_eth(),
@@ -59,29 +103,134 @@ pub fn prepare_calldata_for_nova_cyclefold_verifier(
         proof.kzg_challenges().to_eth(),      // challenge_W, challenge_E
         proof.kzg_proofs()[0].eval.to_eth(),  // eval W
         proof.kzg_proofs()[1].eval.to_eth(),  // eval E
+        random_vs.to_eth(),                   // random_v W, random_v E (only if hiding)
         proof.kzg_proofs()[0].proof.to_eth(), // W kzg_proof
         proof.kzg_proofs()[1].proof.to_eth(), // E kzg_proof
+        masks.to_eth(),                       // mask W, mask E (only if hiding)
+    ]
+    .concat())
+}
+
+/// Prepares solidity calldata for calling the HyperNovaDecider contract
+pub fn prepare_calldata_for_hypernova_cyclefold_verifier(
+    verification_mode: HyperNovaVerificationMode,
+    i: ark_bn254::Fr,
+    z_0: Vec<ark_bn254::Fr>,
+    z_i: Vec<ark_bn254::Fr>,
+    running_instance: &LCCCS<ark_bn254::G1Projective>,
+    incoming_instance: &CCCS<ark_bn254::G1Projective>,
+    proof: &HyperNovaProof<ark_bn254::G1Projective, KZG<Bn254>, Groth16<Bn254>>,
+) -> Result<Vec<u8>, Error> {
+    let selector = get_hypernova_function_selector(verification_mode, z_0.len());
+
+    Ok([
+        selector.to_eth(),
+        i.to_eth(),   // i
+        z_0.to_eth(), // z_0
+        z_i.to_eth(), // z_i
+        running_instance.C.to_eth(),
+        incoming_instance.C.to_eth(),
+        proof.rho().to_eth(),             // rho
+        proof.snark_proof().to_eth(),     // pA, pB, pC
+        proof.kzg_challenge().to_eth(),   // challenge_C
+        proof.kzg_proof().eval.to_eth(),  // eval C
+        proof.kzg_proof().proof.to_eth(), // C kzg_proof
+    ]
+    .concat())
+}
+
+/// Prepares solidity calldata for calling the ProtoGalaxyDecider contract
+pub fn prepare_calldata_for_protogalaxy_cyclefold_verifier(
+    verification_mode: ProtoGalaxyVerificationMode,
+    i: ark_bn254::Fr,
+    z_0: Vec<ark_bn254::Fr>,
+    z_i: Vec<ark_bn254::Fr>,
+    running_instance: &ProtoGalaxyCommittedInstance<ark_bn254::G1Projective, RUNNING>,
+    incoming_instance: &ProtoGalaxyCommittedInstance<ark_bn254::G1Projective, INCOMING>,
+    proof: &ProtoGalaxyProof<ark_bn254::G1Projective, KZG<Bn254>, Groth16<Bn254>>,
+) -> Result<Vec<u8>, Error> {
+    let selector = get_protogalaxy_function_selector(verification_mode, z_0.len());
+
+    Ok([
+        selector.to_eth(),
+        i.to_eth(),   // i
+        z_0.to_eth(), // z_0
+        z_i.to_eth(), // z_i
+        running_instance.get_commitments().to_eth(),
+        incoming_instance.get_commitments().to_eth(),
+        proof.L_X_evals().to_eth(),           // L_X_evals
+        proof.snark_proof().to_eth(),         // pA, pB, pC
+        proof.kzg_challenges().to_eth(),      // challenge_phi
+        proof.kzg_proofs()[0].eval.to_eth(),  // eval phi
+        proof.kzg_proofs()[0].proof.to_eth(), // phi kzg_proof
     ]
     .concat())
 }
 
 /// Computes the function selector for the nova cyclefold verifier.
-/// It is computed on the fly since it depends on the IVC state length.
-fn get_function_selector(mode: NovaVerificationMode, state_len: usize) -> [u8; 4] {
+/// It is computed on the fly since it depends on the IVC state length, and on whether the
+/// commitments are hiding (which adds the two blinding evaluations to the KZG evaluations, and
+/// the two masks to the KZG proofs).
+fn get_function_selector(mode: NovaVerificationMode, state_len: usize, hiding: bool) -> [u8; 4] {
+    let (kzg_evals_len, kzg_proofs_len) = if hiding { (6, 4) } else { (4, 2) };
     let fn_sig = match mode {
         NovaVerificationMode::Explicit =>
             format!(
-                "verifyNovaProof(uint256[{}],uint256[4],uint256[2],uint256[3],uint256[2],uint256[2][2],uint256[2],uint256[4],uint256[2][2])",
+                "verifyNovaProof(uint256[{}],uint256[4],uint256[2],uint256[3],uint256[2],uint256[2][2],uint256[2],uint256[{kzg_evals_len}],uint256[2][{kzg_proofs_len}])",
                 state_len * 2 + 1
             ),
         NovaVerificationMode::Opaque =>
-            format!("verifyOpaqueNovaProof(uint256[{}])", 26 + 2 * state_len),
+            format!("verifyOpaqueNovaProof(uint256[{}])", 18 + kzg_evals_len + 2 * kzg_proofs_len + 2 * state_len),
         NovaVerificationMode::OpaqueWithInputs =>
-            format!("verifyOpaqueNovaProofWithInputs(uint256,uint256[{state_len}],uint256[{state_len}],uint256[25])"),
+            format!("verifyOpaqueNovaProofWithInputs(uint256,uint256[{state_len}],uint256[{state_len}],uint256[{}])", 17 + kzg_evals_len + 2 * kzg_proofs_len),
+    };
+
+    keccak_selector(&fn_sig)
+}
+
+/// Computes the function selector for the hypernova cyclefold verifier.
+/// It is computed on the fly since it depends on the IVC state length.
+fn get_hypernova_function_selector(mode: HyperNovaVerificationMode, state_len: usize) -> [u8; 4] {
+    let fn_sig = match mode {
+        HyperNovaVerificationMode::Explicit =>
+            format!(
+                "verifyHyperNovaProof(uint256[{}],uint256[4],uint256,uint256[2],uint256[2][2],uint256[2],uint256[2],uint256[2])",
+                state_len * 2 + 1
+            ),
+        HyperNovaVerificationMode::Opaque =>
+            format!("verifyOpaqueHyperNovaProof(uint256[{}])", 18 + 2 * state_len),
+        HyperNovaVerificationMode::OpaqueWithInputs =>
+            format!("verifyOpaqueHyperNovaProofWithInputs(uint256,uint256[{state_len}],uint256[{state_len}],uint256[17])"),
+    };
+
+    keccak_selector(&fn_sig)
+}
+
+/// Computes the function selector for the protogalaxy cyclefold verifier.
+/// It is computed on the fly since it depends on the IVC state length.
+fn get_protogalaxy_function_selector(
+    mode: ProtoGalaxyVerificationMode,
+    state_len: usize,
+) -> [u8; 4] {
+    let fn_sig = match mode {
+        ProtoGalaxyVerificationMode::Explicit =>
+            format!(
+                "verifyProtoGalaxyProof(uint256[{}],uint256[4],uint256[2],uint256[2],uint256[2][2],uint256[2],uint256[2],uint256[2])",
+                state_len * 2 + 1
+            ),
+        ProtoGalaxyVerificationMode::Opaque =>
+            format!("verifyOpaqueProtoGalaxyProof(uint256[{}])", 19 + 2 * state_len),
+        ProtoGalaxyVerificationMode::OpaqueWithInputs =>
+            format!("verifyOpaqueProtoGalaxyProofWithInputs(uint256,uint256[{state_len}],uint256[{state_len}],uint256[18])"),
     };
 
+    keccak_selector(&fn_sig)
+}
+
+/// Returns the first 4 bytes of the keccak256 hash of the given function signature.
+pub(crate) fn keccak_selector(fn_sig: &str) -> [u8; 4] {
     let mut hasher = Sha3::keccak256();
-    hasher.input_str(&fn_sig);
+    hasher.input_str(fn_sig);
     let hash = &mut [0u8; 32];
     hasher.result(hash);
     [hash[0], hash[1], hash[2], hash[3]]
@@ -3,6 +3,7 @@
 #![allow(clippy::upper_case_acronyms)]
 
 use ark_bn254::{Bn254, Fq, Fr, G1Affine, G1Projective};
+use ark_crypto_primitives::sponge::poseidon::PoseidonSponge;
 use ark_groth16::VerifyingKey as ArkG16VerifierKey;
 use ark_poly_commit::kzg10::VerifierKey as ArkKZG10VerifierKey;
 use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
@@ -10,14 +11,15 @@ use askama::Template;
 
 use folding_schemes::folding::circuits::nonnative::uint::NonNativeUintVar;
 use folding_schemes::folding::nova::decider_eth::VerifierParam as DeciderVerifierParam;
+use folding_schemes::transcript::keccak::KeccakTranscript;
 
 use super::g16::Groth16Verifier;
 use super::kzg::KZG10Verifier;
 use crate::utils::HeaderInclusion;
 use crate::{Groth16VerifierKey, KZG10VerifierKey, ProtocolVerifierKey, PRAGMA_GROTH16_VERIFIER};
 
-pub fn get_decider_template_for_cyclefold_decider(
-    nova_cyclefold_vk: NovaCycleFoldVerifierKey,
+pub fn get_decider_template_for_cyclefold_decider<const H: bool, const K: bool>(
+    nova_cyclefold_vk: NovaCycleFoldVerifierKey<H, K>,
 ) -> String {
     HeaderInclusion::<NovaCycleFoldDecider>::builder()
         .template(nova_cyclefold_vk)
@@ -37,10 +39,18 @@ pub struct NovaCycleFoldDecider {
     public_inputs_len: usize,
     num_limbs: usize,
     bits_per_limb: usize,
+    // whether the KZG commitments are hiding, in which case the openings are checked with the
+    // evaluations of the blinding polynomials and the masks of the commitments
+    hiding: bool,
+    kzg_evals_len: usize,
+    kzg_proofs_len: usize,
+    // whether the KZG challenges are derived with keccak256 by the contract, instead of being
+    // checked in-circuit
+    keccak: bool,
 }
 
-impl From<NovaCycleFoldVerifierKey> for NovaCycleFoldDecider {
-    fn from(value: NovaCycleFoldVerifierKey) -> Self {
+impl<const H: bool, const K: bool> From<NovaCycleFoldVerifierKey<H, K>> for NovaCycleFoldDecider {
+    fn from(value: NovaCycleFoldVerifierKey<H, K>) -> Self {
         let groth16_verifier = Groth16Verifier::from(value.g16_vk);
         let public_inputs_len = groth16_verifier.gamma_abc_len;
         let bits_per_limb = NonNativeUintVar::<Fq>::bits_per_limb();
@@ -52,20 +62,34 @@ impl From<NovaCycleFoldVerifierKey> for NovaCycleFoldDecider {
             public_inputs_len,
             num_limbs: (250_f32 / (bits_per_limb as f32)).ceil() as usize,
             bits_per_limb,
+            hiding: H,
+            kzg_evals_len: if H { 6 } else { 4 },
+            kzg_proofs_len: if H { 4 } else { 2 },
+            keccak: K,
         }
     }
 }
 
+/// Verifier key of the NovaDecider contract. `H` indicates if the decided Nova instance uses hiding
+/// commitments (`Nova<..., H = true>`), in which case the contract expects the blinding
+/// evaluations of the KZG openings. `K` indicates if the decider derives the KZG challenges from a
+/// `KeccakTranscript`, in which case the contract recomputes them with `keccak256`. Neither of them
+/// is serialized, but the keys of such deciders are tagged with a different protocol name.
 #[derive(CanonicalDeserialize, CanonicalSerialize, PartialEq, Debug, Clone)]
-pub struct NovaCycleFoldVerifierKey {
+pub struct NovaCycleFoldVerifierKey<const H: bool = false, const K: bool = false> {
     pp_hash: Fr,
     g16_vk: Groth16VerifierKey,
     kzg_vk: KZG10VerifierKey,
     z_len: usize,
 }
 
-impl ProtocolVerifierKey for NovaCycleFoldVerifierKey {
-    const PROTOCOL_NAME: &'static str = "NovaCycleFold";
+impl<const H: bool, const K: bool> ProtocolVerifierKey for NovaCycleFoldVerifierKey<H, K> {
+    const PROTOCOL_NAME: &'static str = match (H, K) {
+        (false, false) => "NovaCycleFold",
+        (true, false) => "NovaCycleFoldHiding",
+        (false, true) => "NovaCycleFoldKeccak",
+        (true, true) => "NovaCycleFoldHidingKeccak",
+    };
 
     fn render_as_template(self, pragma: Option<String>) -> Vec<u8> {
         HeaderInclusion::<NovaCycleFoldDecider>::builder()
@@ -91,11 +115,46 @@ impl From<(Fr, Groth16VerifierKey, KZG10VerifierKey, usize)> for NovaCycleFoldVe
 
 // implements From assuming that the 'batchCheck' method from the KZG10 template will not be used
 // in the NovaCycleFoldDecider verifier contract
-impl
+impl<const H: bool>
+    From<(
+        DeciderVerifierParam<
+            G1Projective,
+            ArkKZG10VerifierKey<Bn254>,
+            ArkG16VerifierKey<Bn254>,
+            H,
+            PoseidonSponge<Fr>,
+        >,
+        usize,
+    )> for NovaCycleFoldVerifierKey<H, false>
+{
+    fn from(
+        value: (
+            DeciderVerifierParam<
+                G1Projective,
+                ArkKZG10VerifierKey<Bn254>,
+                ArkG16VerifierKey<Bn254>,
+                H,
+                PoseidonSponge<Fr>,
+            >,
+            usize,
+        ),
+    ) -> Self {
+        Self::from_decider_vp(value.0, value.1)
+    }
+}
+
+// same as above, for the deciders that derive the KZG challenges with Keccak
+impl<const H: bool>
     From<(
-        DeciderVerifierParam<G1Projective, ArkKZG10VerifierKey<Bn254>, ArkG16VerifierKey<Bn254>>,
+        DeciderVerifierParam<
+            G1Projective,
+            ArkKZG10VerifierKey<Bn254>,
+            ArkG16VerifierKey<Bn254>,
+            H,
+            KeccakTranscript<Fr>,
+        >,
         usize,
-    )> for NovaCycleFoldVerifierKey
+    )> for NovaCycleFoldVerifierKey<H, true>
 {
     fn from(
         value: (
@@ -103,11 +162,27 @@ impl
                 G1Projective,
                 ArkKZG10VerifierKey<Bn254>,
                 ArkG16VerifierKey<Bn254>,
+                H,
+                KeccakTranscript<Fr>,
             >,
             usize,
         ),
     ) -> Self {
-        let decider_vp = value.0;
+        Self::from_decider_vp(value.0, value.1)
+    }
+}
+
+impl<const H: bool, const K: bool> NovaCycleFoldVerifierKey<H, K> {
+    fn from_decider_vp<T: Send + Sync>(
+        decider_vp: DeciderVerifierParam<
+            G1Projective,
+            ArkKZG10VerifierKey<Bn254>,
+            ArkG16VerifierKey<Bn254>,
+            H,
+            T,
+        >,
+        z_len: usize,
+    ) -> Self {
         let g16_vk = Groth16VerifierKey::from(decider_vp.snark_vp);
         // pass `Vec::new()` since batchCheck will not be used
         let kzg_vk = KZG10VerifierKey::from((decider_vp.cs_vp, Vec::new()));
@@ -115,7 +190,7 @@ impl
             pp_hash: decider_vp.pp_hash,
             g16_vk,
             kzg_vk,
-            z_len: value.1,
+            z_len,
         }
     }
 }
@@ -138,7 +213,7 @@ impl NovaCycleFoldVerifierKey {
 }
 
 #[cfg(test)]
-mod tests {
+pub mod tests {
     use ark_bn254::{Bn254, Fr, G1Projective as G1, G1Projective};
     use ark_ff::PrimeField;
     use ark_groth16::Groth16;
@@ -168,7 +243,7 @@ mod tests {
             traits::CommittedInstanceOps,
         },
         frontend::FCircuit,
-        transcript::poseidon::poseidon_canonical_config,
+        transcript::{keccak::KeccakTranscript, poseidon::poseidon_canonical_config},
         Decider, Error, FoldingScheme,
     };
 
@@ -255,10 +330,11 @@ mod tests {
     fn nova_cyclefold_vk_serde_roundtrip() {
         let (pp_hash, _, kzg_vk, _, g16_vk, _) = setup(DEFAULT_SETUP_LEN);
 
-        let decider_vp = DeciderVerifierParam {
+        let decider_vp: DeciderVerifierParam<G1, _, _> = DeciderVerifierParam {
             pp_hash,
             snark_vp: g16_vk,
             cs_vp: kzg_vk,
+            _t: PhantomData,
         };
         let nova_cyclefold_vk = NovaCycleFoldVerifierKey::from((decider_vp, 1));
 
@@ -269,16 +345,23 @@ mod tests {
         let obtained_nova_cyclefold_vk =
             NovaCycleFoldVerifierKey::deserialize_protocol_verifier_key(bytes.as_slice()).unwrap();
 
-        assert_eq!(nova_cyclefold_vk, obtained_nova_cyclefold_vk)
+        assert_eq!(nova_cyclefold_vk, obtained_nova_cyclefold_vk);
+
+        // the key of a hiding decider has the same format, but it is tagged differently
+        assert!(
+            NovaCycleFoldVerifierKey::<true>::deserialize_protocol_verifier_key(bytes.as_slice())
+                .is_err()
+        );
     }
 
     #[test]
     fn nova_cyclefold_decider_template_renders() {
         let (pp_hash, _, kzg_vk, _, g16_vk, _) = setup(DEFAULT_SETUP_LEN);
-        let decider_vp = DeciderVerifierParam {
+        let decider_vp: DeciderVerifierParam<G1, _, _> = DeciderVerifierParam {
             pp_hash,
             snark_vp: g16_vk,
             cs_vp: kzg_vk,
+            _t: PhantomData,
         };
         let nova_cyclefold_vk = NovaCycleFoldVerifierKey::from((decider_vp, 1));
 
@@ -310,10 +393,10 @@ mod tests {
         (nova_params, decider_params)
     }
 
-    fn interact_with_contract<'a, FC: FCircuit<Fr>>(
+    fn interact_with_contract<FC: FCircuit<Fr>, const H: bool>(
         nova_cyclefold_verifier_bytecode: &[u8],
-        nova: &NOVA<FC>,
-        proof: &Proof<G1Projective, KZG<Bn254>, Groth16<Bn254>>,
+        nova: &Nova<G1, G2, FC, KZG<'static, Bn254, H>, Pedersen<G2, H>, H>,
+        proof: &Proof<G1Projective, KZG<Bn254, H>, Groth16<Bn254>, H>,
         mode: NovaVerificationMode,
     ) {
         let mut evm = Evm::default();
@@ -430,4 +513,150 @@ mod tests {
     fn nova_cyclefold_solidity_verifier_multi_input() {
         nova_cyclefold_solidity_verifier_test::<MultiInputsFCircuit<Fr>>(vec![Fr::from(1_u32); 5]);
     }
+
+    #[test]
+    fn nova_cyclefold_solidity_verifier_hiding() {
+        type NOVA_H =
+            Nova<G1, G2, CubicFCircuit<Fr>, KZG<'static, Bn254, true>, Pedersen<G2, true>, true>;
+        type DECIDER_H = DeciderEth<
+            G1,
+            G2,
+            CubicFCircuit<Fr>,
+            KZG<'static, Bn254, true>,
+            Pedersen<G2, true>,
+            Groth16<Bn254>,
+            NOVA_H,
+            true,
+        >;
+
+        let mut rng = ark_std::rand::rngs::OsRng;
+        let poseidon_config = poseidon_canonical_config::<Fr>();
+
+        let f_circuit = CubicFCircuit::<Fr>::new(()).unwrap();
+        let prep_param = PreprocessorParam::new(poseidon_config, f_circuit);
+        let nova_params = NOVA_H::preprocess(&mut rng, &prep_param).unwrap();
+        let (decider_pp, decider_vp) =
+            DECIDER_H::preprocess(&mut rng, (nova_params.clone(), f_circuit.state_len())).unwrap();
+
+        let mut nova = NOVA_H::init(&nova_params, f_circuit, vec![Fr::from(3_u32)]).unwrap();
+        for _ in 0..3 {
+            nova.prove_step(&mut rng, (), None).unwrap();
+        }
+
+        let proof = DECIDER_H::prove(rng, decider_pp, nova.clone()).unwrap();
+        let verified = DECIDER_H::verify(
+            decider_vp.clone(),
+            nova.i,
+            nova.z_0.clone(),
+            nova.z_i.clone(),
+            &nova.U_i.get_commitments(),
+            &nova.u_i.get_commitments(),
+            &proof,
+        )
+        .unwrap();
+        assert!(verified);
+
+        // the verifier key of the hiding decider renders the hiding contract
+        let nova_cyclefold_vk = NovaCycleFoldVerifierKey::from((decider_vp, f_circuit.state_len()));
+        let decider_solidity_code = get_decider_template_for_cyclefold_decider(nova_cyclefold_vk);
+        let nova_cyclefold_verifier_bytecode =
+            compile_solidity(decider_solidity_code, "NovaDecider");
+
+        for mode in [Explicit, Opaque, OpaqueWithInputs] {
+            interact_with_contract(&nova_cyclefold_verifier_bytecode, &nova, &proof, mode);
+        }
+
+        // change the blinding evaluation of the W opening, which is followed by the blinding
+        // evaluation of the E opening, the two KZG proofs and the two masks (9 words) at the end
+        // of the calldata, to make the EVM check fail
+        let mut evm = Evm::default();
+        let verifier_address = evm.create(nova_cyclefold_verifier_bytecode);
+        let mut calldata = prepare_calldata_for_nova_cyclefold_verifier(
+            Explicit,
+            nova.i,
+            nova.z_0.clone(),
+            nova.z_i.clone(),
+            &nova.U_i,
+            &nova.u_i,
+            &proof,
+        )
+        .unwrap();
+        let random_v_W_last_byte = calldata.len() - 32 * 9 - 1;
+        calldata[random_v_W_last_byte] ^= 1;
+        let (_, output) = evm.call(verifier_address, calldata);
+        assert_eq!(*output.last().unwrap(), 0);
+    }
+
+    #[test]
+    fn nova_cyclefold_solidity_verifier_keccak() {
+        type DECIDER_K = DeciderEth<
+            G1,
+            G2,
+            CubicFCircuit<Fr>,
+            KZG<'static, Bn254>,
+            Pedersen<G2>,
+            Groth16<Bn254>,
+            NOVA<CubicFCircuit<Fr>>,
+            false,
+            KeccakTranscript<Fr>,
+        >;
+
+        let mut rng = ark_std::rand::rngs::OsRng;
+        let poseidon_config = poseidon_canonical_config::<Fr>();
+
+        let f_circuit = CubicFCircuit::<Fr>::new(()).unwrap();
+        let prep_param = PreprocessorParam::new(poseidon_config, f_circuit);
+        let nova_params = NOVA::preprocess(&mut rng, &prep_param).unwrap();
+        let (decider_pp, decider_vp) =
+            DECIDER_K::preprocess(&mut rng, (nova_params.clone(), f_circuit.state_len())).unwrap();
+
+        let mut nova = NOVA::init(&nova_params, f_circuit, vec![Fr::from(3_u32)]).unwrap();
+        for _ in 0..3 {
+            nova.prove_step(&mut rng, (), None).unwrap();
+        }
+
+        let proof = DECIDER_K::prove(rng, decider_pp, nova.clone()).unwrap();
+        let verified = DECIDER_K::verify(
+            decider_vp.clone(),
+            nova.i,
+            nova.z_0.clone(),
+            nova.z_i.clone(),
+            &nova.U_i.get_commitments(),
+            &nova.u_i.get_commitments(),
+            &proof,
+        )
+        .unwrap();
+        assert!(verified);
+
+        // the verifier key of the Keccak decider renders the contract that derives the KZG
+        // challenges with keccak256
+        let nova_cyclefold_vk = NovaCycleFoldVerifierKey::from((decider_vp, f_circuit.state_len()));
+        let decider_solidity_code = get_decider_template_for_cyclefold_decider(nova_cyclefold_vk);
+        let nova_cyclefold_verifier_bytecode =
+            compile_solidity(decider_solidity_code, "NovaDecider");
+
+        for mode in [Explicit, Opaque, OpaqueWithInputs] {
+            interact_with_contract(&nova_cyclefold_verifier_bytecode, &nova, &proof, mode);
+        }
+
+        // change the challenge of the W opening, which is placed right before the two evaluations
+        // and the two KZG proofs (6 words) at the end of the calldata. Since the Groth16 proof does
+        // not check it, the EVM check only fails thanks to the keccak256 derivation
+        let mut evm = Evm::default();
+        let verifier_address = evm.create(nova_cyclefold_verifier_bytecode);
+        let mut calldata = prepare_calldata_for_nova_cyclefold_verifier(
+            Explicit,
+            nova.i,
+            nova.z_0.clone(),
+            nova.z_i.clone(),
+            &nova.U_i,
+            &nova.u_i,
+            &proof,
+        )
+        .unwrap();
+        let challenge_W_last_byte = calldata.len() - 32 * 7 - 1;
+        calldata[challenge_W_last_byte] ^= 1;
+        let (_, output) = evm.call(verifier_address, calldata);
+        assert_eq!(*output.last().unwrap(), 0);
+    }
 }
@@ -0,0 +1,381 @@
+#![allow(non_snake_case)]
+#![allow(non_camel_case_types)]
+#![allow(clippy::upper_case_acronyms)]
+
+use ark_bn254::{Bn254, Fq, Fr, G1Affine, G1Projective};
+use ark_groth16::VerifyingKey as ArkG16VerifierKey;
+use ark_poly_commit::kzg10::VerifierKey as ArkKZG10VerifierKey;
+use ark_serialize::{CanonicalDeserialize, CanonicalSerialize};
+use askama::Template;
+
+use folding_schemes::folding::circuits::nonnative::uint::NonNativeUintVar;
+use folding_schemes::folding::protogalaxy::decider_eth::VerifierParam as DeciderVerifierParam;
+
+use super::g16::Groth16Verifier;
+use super::kzg::KZG10Verifier;
+use crate::utils::HeaderInclusion;
+use crate::{Groth16VerifierKey, KZG10VerifierKey, ProtocolVerifierKey, PRAGMA_GROTH16_VERIFIER};
+
+pub fn get_decider_template_for_protogalaxy_cyclefold_decider(
+    protogalaxy_cyclefold_vk: ProtoGalaxyCycleFoldVerifierKey,
+) -> String {
+    HeaderInclusion::<ProtoGalaxyCycleFoldDecider>::builder()
+        .template(protogalaxy_cyclefold_vk)
+        .build()
+        .render()
+        .unwrap()
+}
+
+#[derive(Template, Default)]
+#[template(path = "protogalaxy_cyclefold_decider.askama.sol", ext = "sol")]
+pub struct ProtoGalaxyCycleFoldDecider {
+    pp_hash: Fr, // public params hash
+    groth16_verifier: Groth16Verifier,
+    kzg10_verifier: KZG10Verifier,
+    // z_len denotes the FCircuit state (z_i) length
+    z_len: usize,
+    public_inputs_len: usize,
+    num_limbs: usize,
+    bits_per_limb: usize,
+}
+
+impl From<ProtoGalaxyCycleFoldVerifierKey> for ProtoGalaxyCycleFoldDecider {
+    fn from(value: ProtoGalaxyCycleFoldVerifierKey) -> Self {
+        let groth16_verifier = Groth16Verifier::from(value.g16_vk);
+        let public_inputs_len = groth16_verifier.gamma_abc_len;
+        let bits_per_limb = NonNativeUintVar::<Fq>::bits_per_limb();
+        Self {
+            pp_hash: value.pp_hash,
+            groth16_verifier,
+            kzg10_verifier: KZG10Verifier::from(value.kzg_vk),
+            z_len: value.z_len,
+            public_inputs_len,
+            num_limbs: (250_f32 / (bits_per_limb as f32)).ceil() as usize,
+            bits_per_limb,
+        }
+    }
+}
+
+#[derive(CanonicalDeserialize, CanonicalSerialize, PartialEq, Debug, Clone)]
+pub struct ProtoGalaxyCycleFoldVerifierKey {
+    pp_hash: Fr,
+    g16_vk: Groth16VerifierKey,
+    kzg_vk: KZG10VerifierKey,
+    z_len: usize,
+}
+
+impl ProtocolVerifierKey for ProtoGalaxyCycleFoldVerifierKey {
+    const PROTOCOL_NAME: &'static str = "ProtoGalaxyCycleFold";
+
+    fn render_as_template(self, pragma: Option<String>) -> Vec<u8> {
+        HeaderInclusion::<ProtoGalaxyCycleFoldDecider>::builder()
+            .pragma_version(pragma.unwrap_or(PRAGMA_GROTH16_VERIFIER.to_string()))
+            .template(self)
+            .build()
+            .render()
+            .unwrap()
+            .into_bytes()
+    }
+}
+
+impl From<(Fr, Groth16VerifierKey, KZG10VerifierKey, usize)> for ProtoGalaxyCycleFoldVerifierKey {
+    fn from(value: (Fr, Groth16VerifierKey, KZG10VerifierKey, usize)) -> Self {
+        Self {
+            pp_hash: value.0,
+            g16_vk: value.1,
+            kzg_vk: value.2,
+            z_len: value.3,
+        }
+    }
+}
+
+// implements From assuming that the 'batchCheck' method from the KZG10 template will not be used
+// in the ProtoGalaxyCycleFoldDecider verifier contract
+impl
+    From<(
+        DeciderVerifierParam<G1Projective, ArkKZG10VerifierKey<Bn254>, ArkG16VerifierKey<Bn254>>,
+        usize,
+    )> for ProtoGalaxyCycleFoldVerifierKey
+{
+    fn from(
+        value: (
+            DeciderVerifierParam<
+                G1Projective,
+                ArkKZG10VerifierKey<Bn254>,
+                ArkG16VerifierKey<Bn254>,
+            >,
+            usize,
+        ),
+    ) -> Self {
+        let decider_vp = value.0;
+        let g16_vk = Groth16VerifierKey::from(decider_vp.snark_vp);
+        // pass `Vec::new()` since batchCheck will not be used
+        let kzg_vk = KZG10VerifierKey::from((decider_vp.cs_vp, Vec::new()));
+        Self {
+            pp_hash: decider_vp.pp_hash,
+            g16_vk,
+            kzg_vk,
+            z_len: value.1,
+        }
+    }
+}
+
+impl ProtoGalaxyCycleFoldVerifierKey {
+    pub fn new(
+        pp_hash: Fr,
+        vkey_g16: ArkG16VerifierKey<Bn254>,
+        vkey_kzg: ArkKZG10VerifierKey<Bn254>,
+        crs_points: Vec<G1Affine>,
+        z_len: usize,
+    ) -> Self {
+        Self {
+            pp_hash,
+            g16_vk: Groth16VerifierKey::from(vkey_g16),
+            kzg_vk: KZG10VerifierKey::from((vkey_kzg, crs_points)),
+            z_len,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ark_bn254::{Bn254, Fr, G1Projective as G1, G1Projective};
+    use ark_groth16::Groth16;
+    use ark_grumpkin::Projective as G2;
+    use askama::Template;
+    use std::time::Instant;
+
+    use super::{DeciderVerifierParam, ProtoGalaxyCycleFoldDecider};
+    use crate::calldata::ProtoGalaxyVerificationMode::{Explicit, Opaque, OpaqueWithInputs};
+    use crate::calldata::{
+        prepare_calldata_for_protogalaxy_cyclefold_verifier, ProtoGalaxyVerificationMode,
+    };
+    use crate::verifiers::nova_cyclefold::tests::{CubicFCircuit, MultiInputsFCircuit};
+    use crate::verifiers::tests::{setup, DEFAULT_SETUP_LEN};
+    use crate::{
+        evm::{compile_solidity, save_solidity, Evm},
+        utils::HeaderInclusion,
+        verifiers::protogalaxy_cyclefold::get_decider_template_for_protogalaxy_cyclefold_decider,
+        ProtoGalaxyCycleFoldVerifierKey, ProtocolVerifierKey,
+    };
+    use folding_schemes::folding::protogalaxy::decider_eth::Proof;
+    use folding_schemes::{
+        commitment::{kzg::KZG, pedersen::Pedersen},
+        folding::{
+            nova::PreprocessorParam,
+            protogalaxy::{decider_eth::Decider as DeciderEth, ProtoGalaxy},
+            traits::CommittedInstanceOps,
+        },
+        frontend::FCircuit,
+        transcript::poseidon::poseidon_canonical_config,
+        Decider, FoldingScheme,
+    };
+
+    const K: usize = 1;
+
+    type PG<FC> = ProtoGalaxy<G1, G2, FC, KZG<'static, Bn254>, Pedersen<G2>, K>;
+    type DECIDER<FC> =
+        DeciderEth<G1, G2, FC, KZG<'static, Bn254>, Pedersen<G2>, Groth16<Bn254>, PG<FC>, K>;
+
+    type FS_PP<FC> = <PG<FC> as FoldingScheme<G1, G2, FC>>::ProverParam;
+    type FS_VP<FC> = <PG<FC> as FoldingScheme<G1, G2, FC>>::VerifierParam;
+    type DECIDER_PP<FC> = <DECIDER<FC> as Decider<G1, G2, FC, PG<FC>>>::ProverParam;
+    type DECIDER_VP<FC> = <DECIDER<FC> as Decider<G1, G2, FC, PG<FC>>>::VerifierParam;
+
+    #[test]
+    fn protogalaxy_cyclefold_vk_serde_roundtrip() {
+        let (pp_hash, _, kzg_vk, _, g16_vk, _) = setup(DEFAULT_SETUP_LEN);
+
+        let decider_vp = DeciderVerifierParam {
+            pp_hash,
+            snark_vp: g16_vk,
+            cs_vp: kzg_vk,
+        };
+        let protogalaxy_cyclefold_vk = ProtoGalaxyCycleFoldVerifierKey::from((decider_vp, 1));
+
+        let mut bytes = vec![];
+        protogalaxy_cyclefold_vk
+            .serialize_protocol_verifier_key(&mut bytes)
+            .unwrap();
+        let obtained_protogalaxy_cyclefold_vk =
+            ProtoGalaxyCycleFoldVerifierKey::deserialize_protocol_verifier_key(bytes.as_slice())
+                .unwrap();
+
+        assert_eq!(protogalaxy_cyclefold_vk, obtained_protogalaxy_cyclefold_vk)
+    }
+
+    #[test]
+    fn protogalaxy_cyclefold_decider_template_renders() {
+        let (pp_hash, _, kzg_vk, _, g16_vk, _) = setup(DEFAULT_SETUP_LEN);
+        let decider_vp = DeciderVerifierParam {
+            pp_hash,
+            snark_vp: g16_vk,
+            cs_vp: kzg_vk,
+        };
+        let protogalaxy_cyclefold_vk = ProtoGalaxyCycleFoldVerifierKey::from((decider_vp, 1));
+
+        let decider_solidity_code = HeaderInclusion::<ProtoGalaxyCycleFoldDecider>::builder()
+            .template(protogalaxy_cyclefold_vk)
+            .build();
+
+        save_solidity(
+            "ProtoGalaxyDecider.sol",
+            &decider_solidity_code.render().unwrap(),
+        );
+    }
+
+    /// Initializes ProtoGalaxy parameters and DeciderEth parameters. Only for test purposes.
+    #[allow(clippy::type_complexity)]
+    fn init_params<FC: FCircuit<Fr, Params = ()>>(
+    ) -> ((FS_PP<FC>, FS_VP<FC>), (DECIDER_PP<FC>, DECIDER_VP<FC>)) {
+        let mut rng = ark_std::rand::rngs::OsRng;
+        let poseidon_config = poseidon_canonical_config::<Fr>();
+
+        let f_circuit = FC::new(()).unwrap();
+        let protogalaxy_params = PG::preprocess(
+            &mut rng,
+            &PreprocessorParam::new(poseidon_config, f_circuit.clone()),
+        )
+        .unwrap();
+        let decider_params = DECIDER::<FC>::preprocess(
+            &mut rng,
+            (protogalaxy_params.clone(), f_circuit.state_len()),
+        )
+        .unwrap();
+
+        (protogalaxy_params, decider_params)
+    }
+
+    fn interact_with_contract<FC: FCircuit<Fr>>(
+        protogalaxy_cyclefold_verifier_bytecode: &[u8],
+        protogalaxy: &PG<FC>,
+        proof: &Proof<G1Projective, KZG<Bn254>, Groth16<Bn254>>,
+        mode: ProtoGalaxyVerificationMode,
+    ) {
+        let mut evm = Evm::default();
+        let verifier_address = evm.create(protogalaxy_cyclefold_verifier_bytecode.to_vec());
+
+        let calldata: Vec<u8> = prepare_calldata_for_protogalaxy_cyclefold_verifier(
+            mode,
+            protogalaxy.i,
+            protogalaxy.z_0.clone(),
+            protogalaxy.z_i.clone(),
+            &protogalaxy.U_i,
+            &protogalaxy.u_i,
+            proof,
+        )
+        .unwrap();
+
+        let (_, output) = evm.call(verifier_address, calldata.clone());
+        assert_eq!(*output.last().unwrap(), 1);
+
+        // change i to make calldata invalid, placed between bytes 4 - 35
+        let mut invalid_calldata = calldata.clone();
+        invalid_calldata[35] += 1;
+        let (_, output) = evm.call(verifier_address, invalid_calldata.clone());
+        assert_eq!(*output.last().unwrap(), 0);
+
+        // change z_0 to make the EVM check fail, placed between bytes 35 - 67
+        let mut invalid_calldata = calldata.clone();
+        invalid_calldata[67] += 1;
+        let (_, output) = evm.call(verifier_address, invalid_calldata.clone());
+        assert_eq!(*output.last().unwrap(), 0);
+
+        // change z_i to make the EVM check fail, placed between bytes 68 - 100
+        let mut invalid_calldata = calldata.clone();
+        invalid_calldata[99] += 1;
+        let (_, output) = evm.call(verifier_address, invalid_calldata.clone());
+        assert_eq!(*output.last().unwrap(), 0);
+    }
+
+    /// This function allows to define which FCircuit to use for the test, and how many prove_step
+    /// rounds to perform.
+    /// Actions performed by this test:
+    /// - runs the ProtoGalaxyCycleFold folding scheme for the given FCircuit and n_steps times
+    /// - generates a DeciderEth proof, and executes it through the EVM
+    /// - modifies the calldata and checks that it does not pass the EVM check
+    /// - modifies the z_0 and checks that it does not pass the EVM check
+    #[allow(clippy::type_complexity)]
+    fn protogalaxy_cyclefold_solidity_verifier_opt<FC: FCircuit<Fr, Params = ()>>(
+        fs_params: (FS_PP<FC>, FS_VP<FC>),
+        decider_params: (DECIDER_PP<FC>, DECIDER_VP<FC>),
+        z_0: Vec<Fr>,
+        n_steps: usize,
+    ) {
+        let (decider_pp, decider_vp) = decider_params;
+
+        let f_circuit = FC::new(()).unwrap();
+
+        let protogalaxy_cyclefold_vk =
+            ProtoGalaxyCycleFoldVerifierKey::from((decider_vp.clone(), f_circuit.state_len()));
+
+        let mut rng = ark_std::rand::rngs::OsRng;
+
+        let mut protogalaxy = PG::<FC>::init(&fs_params, f_circuit, z_0).unwrap();
+        for _ in 0..n_steps {
+            protogalaxy
+                .prove_step(&mut rng, FC::ExternalInputs::default(), None)
+                .unwrap();
+        }
+
+        let start = Instant::now();
+        let proof = DECIDER::<FC>::prove(rng, decider_pp, protogalaxy.clone()).unwrap();
+        println!("generated Decider proof: {:?}", start.elapsed());
+
+        let verified = DECIDER::<FC>::verify(
+            decider_vp,
+            protogalaxy.i,
+            protogalaxy.z_0.clone(),
+            protogalaxy.z_i.clone(),
+            &protogalaxy.U_i.get_commitments(),
+            &protogalaxy.u_i.get_commitments(),
+            &proof,
+        )
+        .unwrap();
+        assert!(verified);
+
+        let decider_solidity_code =
+            get_decider_template_for_protogalaxy_cyclefold_decider(protogalaxy_cyclefold_vk);
+
+        let protogalaxy_cyclefold_verifier_bytecode =
+            compile_solidity(decider_solidity_code, "ProtoGalaxyDecider");
+
+        for mode in [Explicit, Opaque, OpaqueWithInputs] {
+            interact_with_contract(
+                &protogalaxy_cyclefold_verifier_bytecode,
+                &protogalaxy,
+                &proof,
+                mode,
+            );
+        }
+    }
+
+    /// Given an `FCircuit` type and initial IVC state `z_0`, this function tests the
+    /// `ProtoGalaxyCycleFold` verifier with a few different folding steps.
+    fn protogalaxy_cyclefold_solidity_verifier_test<FC: FCircuit<Fr, Params = ()>>(z_0: Vec<Fr>) {
+        let (protogalaxy_params, decider_params) = init_params::<FC>();
+        for num_steps in [2, 3] {
+            protogalaxy_cyclefold_solidity_verifier_opt::<FC>(
+                protogalaxy_params.clone(),
+                decider_params.clone(),
+                z_0.clone(),
+                num_steps,
+            )
+        }
+    }
+
+    #[test]
+    fn protogalaxy_cyclefold_solidity_verifier_single_input() {
+        protogalaxy_cyclefold_solidity_verifier_test::<CubicFCircuit<Fr>>(vec![Fr::from(3_u32)]);
+    }
+
+    #[test]
+    fn protogalaxy_cyclefold_solidity_verifier_multi_input() {
+        protogalaxy_cyclefold_solidity_verifier_test::<MultiInputsFCircuit<Fr>>(vec![
+            Fr::from(
+                1_u32
+            );
+            5
+        ]);
+    }
+}
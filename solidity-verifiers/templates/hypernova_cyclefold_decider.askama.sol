@@ -0,0 +1,197 @@
+/*
+    Sonobe's HyperNova + CycleFold decider verifier.
+    Joint effort by 0xPARC & PSE.
+
+    More details at https://github.com/privacy-scaling-explorations/sonobe
+    Usage and design documentation at https://privacy-scaling-explorations.github.io/sonobe-docs/
+
+    Uses the https://github.com/iden3/snarkjs/blob/master/templates/verifier_groth16.sol.ejs
+    Groth16 verifier implementation and a KZG10 Solidity template adapted from
+    https://github.com/weijiekoh/libkzg.
+    Additionally we implement the HyperNovaDecider contract, which combines the
+    Groth16 and KZG10 verifiers to verify the zkSNARK proofs coming from
+    HyperNova+CycleFold folding.
+*/
+
+
+/* =============================== */
+/* KZG10 verifier methods */
+{{ kzg10_verifier }}
+
+/* =============================== */
+/* Groth16 verifier methods */
+{{ groth16_verifier }}
+
+
+/* =============================== */
+/* HyperNova+CycleFold Decider verifier */
+/**
+ * @notice  Computes the decomposition of a `uint256` into num_limbs limbs of bits_per_limb bits each.
+ * @dev     Compatible with sonobe::folding-schemes::folding::circuits::nonnative::nonnative_field_to_field_elements.
+ */
+library LimbsDecomposition {
+    function decompose(uint256 x) internal pure returns (uint256[{{num_limbs}}] memory) {
+        uint256[{{num_limbs}}] memory limbs;
+        for (uint8 i = 0; i < {{num_limbs}}; i++) {
+            limbs[i] = (x >> ({{bits_per_limb}} * i)) & ((1 << {{bits_per_limb}}) - 1);
+        }
+        return limbs;
+    }
+}
+
+/**
+ * @author PSE & 0xPARC
+ * @title  Interface for the HyperNovaDecider contract hiding proof details.
+ * @dev    This interface enables calling the verifyHyperNovaProof function without exposing the proof details.
+ */
+interface OpaqueHyperNovaDecider {
+    /**
+     * @notice  Verifies a HyperNova+CycleFold proof given initial and final IVC states, number of steps and the rest proof inputs concatenated.
+     * @dev     This function should simply reorganize arguments and pass them to the proper verification function.
+     */
+    function verifyOpaqueHyperNovaProofWithInputs(
+        uint256 steps, // number of folded steps (i)
+        uint256[{{ z_len }}] calldata initial_state, // initial IVC state (z0)
+        uint256[{{ z_len }}] calldata final_state, // IVC state after i steps (zi)
+        uint256[17] calldata proof // the rest of the decider inputs
+    ) external view returns (bool);
+
+    /**
+     * @notice  Verifies a HyperNova+CycleFold proof given all the proof inputs collected in a single array.
+     * @dev     This function should simply reorganize arguments and pass them to the proper verification function.
+     */
+    function verifyOpaqueHyperNovaProof(uint256[{{ 18 + z_len * 2 }}] calldata proof) external view returns (bool);
+}
+
+/**
+ * @author  PSE & 0xPARC
+ * @title   HyperNovaDecider contract, for verifying HyperNova IVC SNARK proofs.
+ * @dev     This is an askama template which, when templated, features a Groth16 and KZG10 verifiers from which this contract inherits.
+ */
+contract HyperNovaDecider is Groth16Verifier, KZG10Verifier, OpaqueHyperNovaDecider {
+    /**
+     * @notice  Number of LCCCS (running) instances folded at each step of the HyperNova IVC.
+     */
+    uint256 public constant MU = {{ mu }};
+
+    /**
+     * @notice  Number of CCCS (incoming) instances folded at each step of the HyperNova IVC.
+     */
+    uint256 public constant NU = {{ nu }};
+
+    /**
+     * @notice  Verifies a hypernova cyclefold proof consisting of a KZG proof and of a groth16 proof.
+     * @dev     The selector of this function is "dynamic", since it depends on `z_len`.
+     */
+    function verifyHyperNovaProof(
+        // inputs are grouped to prevent errors due stack too deep
+        uint256[{{ 1 + z_len * 2 }}] calldata i_z0_zi, // [i, z0, zi] where |z0| == |zi|
+        uint256[4] calldata U_i_C_u_i_C, // [U_i_C[2], u_i_C[2]]
+        uint256 rho, // randomness of the last NIMFS fold
+        uint256[2] calldata pA, // groth16
+        uint256[2][2] calldata pB, // groth16
+        uint256[2] calldata pC, // groth16
+        uint256[2] calldata challenge_C_kzg_eval, // [challenge_C, eval_C]
+        uint256[2] calldata kzg_proof // proof_C
+    ) public view returns (bool) {
+
+        require(i_z0_zi[0] >= 2, "Folding: the number of folded steps should be at least 2");
+
+        // from gamma_abc_len, we subtract 1.
+        uint256[{{ public_inputs_len - 1 }}] memory public_inputs;
+
+        public_inputs[0] = {{pp_hash}};
+        public_inputs[1] = i_z0_zi[0];
+
+        for (uint i = 0; i < {{ z_len * 2 }}; i++) {
+            public_inputs[2 + i] = i_z0_zi[1 + i];
+        }
+
+        {
+            // U_i.C + rho * u_i.C
+            uint256[2] memory mulScalarPoint = super.mulScalar([U_i_C_u_i_C[2], U_i_C_u_i_C[3]], rho);
+            uint256[2] memory C = super.add([U_i_C_u_i_C[0], U_i_C_u_i_C[1]], mulScalarPoint);
+
+            {
+                uint256[{{num_limbs}}] memory C_x_limbs = LimbsDecomposition.decompose(C[0]);
+                uint256[{{num_limbs}}] memory C_y_limbs = LimbsDecomposition.decompose(C[1]);
+
+                for (uint8 k = 0; k < {{num_limbs}}; k++) {
+                    public_inputs[{{ z_len * 2 + 2 }} + k] = C_x_limbs[k];
+                    public_inputs[{{ z_len * 2 + 2 + num_limbs }} + k] = C_y_limbs[k];
+                }
+            }
+
+            require(this.check(C, kzg_proof, challenge_C_kzg_eval[0], challenge_C_kzg_eval[1]), "KZG: verifying proof for challenge C failed");
+        }
+
+        {
+            // add the challenge, the evaluation and rho
+            public_inputs[{{ z_len * 2 + 2 + num_limbs * 2 }}] = challenge_C_kzg_eval[0];
+            public_inputs[{{ z_len * 2 + 2 + num_limbs * 2 + 1 }}] = challenge_C_kzg_eval[1];
+            public_inputs[{{ z_len * 2 + 2 + num_limbs * 2 + 2 }}] = rho;
+
+            bool success_g16 = this.verifyProof(pA, pB, pC, public_inputs);
+            require(success_g16 == true, "Groth16: verifying proof failed");
+        }
+
+        return(true);
+    }
+
+    /**
+     * @notice  Verifies a HyperNova+CycleFold proof given initial and final IVC states, number of steps and the rest proof inputs concatenated.
+     * @dev     Simply reorganization of arguments and call to the `verifyHyperNovaProof` function.
+     */
+    function verifyOpaqueHyperNovaProofWithInputs(
+        uint256 steps,
+        uint256[{{ z_len }}] calldata initial_state,
+        uint256[{{ z_len }}] calldata final_state,
+        uint256[17] calldata proof
+    ) public override view returns (bool) {
+        uint256[1 + 2 * {{ z_len }}] memory i_z0_zi;
+        i_z0_zi[0] = steps;
+        for (uint256 i = 0; i < {{ z_len }}; i++) {
+            i_z0_zi[i + 1] = initial_state[i];
+            i_z0_zi[i + 1 + {{ z_len }}] = final_state[i];
+        }
+
+        uint256[4] memory U_i_C_u_i_C = [proof[0], proof[1], proof[2], proof[3]];
+        uint256 rho = proof[4];
+        uint256[2] memory pA = [proof[5], proof[6]];
+        uint256[2][2] memory pB = [[proof[7], proof[8]], [proof[9], proof[10]]];
+        uint256[2] memory pC = [proof[11], proof[12]];
+        uint256[2] memory challenge_C_kzg_eval = [proof[13], proof[14]];
+        uint256[2] memory kzg_proof = [proof[15], proof[16]];
+
+        return this.verifyHyperNovaProof(
+            i_z0_zi,
+            U_i_C_u_i_C,
+            rho,
+            pA,
+            pB,
+            pC,
+            challenge_C_kzg_eval,
+            kzg_proof
+        );
+    }
+
+    /**
+     * @notice  Verifies a HyperNova+CycleFold proof given all proof inputs concatenated.
+     * @dev     Simply reorganization of arguments and call to the `verifyHyperNovaProof` function.
+     */
+    function verifyOpaqueHyperNovaProof(uint256[{{ 18 + z_len * 2 }}] calldata proof) public override view returns (bool) {
+        uint256[{{ z_len }}] memory z0;
+        uint256[{{ z_len }}] memory zi;
+        for (uint256 i = 0; i < {{ z_len }}; i++) {
+            z0[i] = proof[i + 1];
+            zi[i] = proof[i + 1 + {{ z_len }}];
+        }
+
+        uint256[17] memory extracted_proof;
+        for (uint256 i = 0; i < 17; i++) {
+            extracted_proof[i] = proof[{{ 1 + 2 * z_len }} + i];
+        }
+
+        return this.verifyOpaqueHyperNovaProofWithInputs(proof[0], z0, zi, extracted_proof);
+    }
+}
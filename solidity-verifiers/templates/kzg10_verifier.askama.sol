@@ -115,6 +115,10 @@ contract KZG10Verifier {
             {{ g1.0[0] }},
             {{ g1.0[1] }}
     ];
+    uint256[2] GAMMA_G_1 = [
+            {{ gamma_g1.0[0] }},
+            {{ gamma_g1.0[1] }}
+    ];
     uint256[2][2] G_2 = [
         [
             {{ g2.0[0][0] }},
@@ -185,6 +189,49 @@ contract KZG10Verifier {
         return pairing(pi, VK, rhs_pairing, G_2);
     }
 
+    /**
+     * @notice  Verifies a single point evaluation proof of a hiding commitment.
+     * @dev     The commitment is blinded as c = poly(tau) * g1 + r * gamma_g1, and the proof opens
+     *          c + xi * mask, where mask is a hiding commitment to the zero polynomial sampled by
+     *          the prover and xi is derived from x, y, c and mask as in sonobe's KZG
+     *          `mask_challenge`. We remove the blinding with the revealed random_v, which is the
+     *          evaluation at x of the blinding polynomial of c + xi * mask, and perform the same
+     *          check as in `check`.
+     * @param   c  G_1 point hiding commitment to polynomial.
+     * @param   pi G_1 point proof.
+     * @param   x  Value to prove evaluation of polynomial at.
+     * @param   y  Evaluation poly(x).
+     * @param   random_v  Evaluation of the blinding polynomial at x.
+     * @param   mask  G_1 point hiding commitment to the zero polynomial.
+     * @return  result Indicates if KZG proof is correct.
+     */
+    function checkHiding(
+        uint256[2] calldata c,
+        uint256[2] calldata pi,
+        uint256 x,
+        uint256 y,
+        uint256 random_v,
+        uint256[2] calldata mask
+    )
+        public
+        view
+        returns (bool result)
+    {
+        // the points are absorbed as two limbs of 253 bits per coordinate, as in sonobe's
+        // KeccakTranscript
+        uint256 limb_mask = (1 << 253) - 1;
+        uint256 xi = uint256(keccak256(abi.encodePacked(
+            bytes32(0), x, y,
+            c[0] & limb_mask, c[0] >> 253, c[1] & limb_mask, c[1] >> 253,
+            mask[0] & limb_mask, mask[0] >> 253, mask[1] & limb_mask, mask[1] >> 253
+        ))) % BN254_SCALAR_FIELD;
+        uint256[2] memory masked_c = add(c, mulScalar(mask, xi));
+        uint256[2] memory unblinded_c = add(masked_c, negate(mulScalar(GAMMA_G_1, random_v)));
+        uint256[2] memory rhs_pairing =
+            add(mulScalar(negate(pi), x), add(negate(unblinded_c), mulScalar(G_1, y)));
+        return pairing(pi, VK, rhs_pairing, G_2);
+    }
+
     function evalPolyAt(uint256[] memory _coefficients, uint256 _index) public pure returns (uint256) {
         uint256 m = BN254_SCALAR_FIELD;
         uint256 result = 0;
@@ -53,14 +53,14 @@ interface OpaqueDecider {
         uint256 steps, // number of folded steps (i)
         uint256[{{ z_len }}] calldata initial_state, // initial IVC state (z0)
         uint256[{{ z_len }}] calldata final_state, // IVC state after i steps (zi)
-        uint256[25] calldata proof // the rest of the decider inputs
+        uint256[{{ 17 + kzg_evals_len + kzg_proofs_len * 2 }}] calldata proof // the rest of the decider inputs
     ) external view returns (bool);
 
     /**
      * @notice  Verifies a Nova+CycleFold proof given all the proof inputs collected in a single array.
      * @dev     This function should simply reorganize arguments and pass them to the proper verification function.
      */
-    function verifyOpaqueNovaProof(uint256[{{ 26 + z_len * 2 }}] calldata proof) external view returns (bool);
+    function verifyOpaqueNovaProof(uint256[{{ 18 + kzg_evals_len + kzg_proofs_len * 2 + z_len * 2 }}] calldata proof) external view returns (bool);
 }
 
 /**
@@ -79,9 +79,27 @@ contract NovaDecider is Groth16Verifier, KZG10Verifier, OpaqueDecider {
         }
     }
 
+    {%- if keccak %}
+    /**
+     * @notice  Absorbs the point `p` into sonobe's KeccakTranscript, whose pending input is `prefix`,
+     *          and squeezes the new state, from which the next KZG challenge is taken.
+     * @dev     Compatible with sonobe::folding-schemes::transcript::keccak::KeccakTranscript, where
+     *          each coordinate of `p` is absorbed as two limbs of 253 bits (`AbsorbNonNative`).
+     */
+    function squeezeKeccakState(bytes memory prefix, uint256[2] memory p) internal pure returns (bytes32) {
+        uint256 mask = (1 << 253) - 1;
+        return keccak256(abi.encodePacked(prefix, p[0] & mask, p[0] >> 253, p[1] & mask, p[1] >> 253));
+    }
+    {%- endif %}
+
     /**
      * @notice  Verifies a nova cyclefold proof consisting of two KZG proofs and of a groth16 proof.
-     * @dev     The selector of this function is "dynamic", since it depends on `z_len`.
+     * @dev     The selector of this function is "dynamic", since it depends on `z_len`, and on
+     *          whether the commitments are hiding, in which case the KZG evaluations are followed
+     *          by the evaluations of the blinding polynomials, and the KZG proofs by the masks
+     *          that re-randomize the commitments before they are opened.
+     *          If the decider uses a KeccakTranscript, the KZG challenges are not checked by the
+     *          Groth16 proof, so they are recomputed here from pp_hash and the folded commitments.
      */
     function verifyNovaProof(
         // inputs are grouped to prevent errors due stack too deep
@@ -92,8 +110,8 @@ contract NovaDecider is Groth16Verifier, KZG10Verifier, OpaqueDecider {
         uint256[2] calldata pA, // groth16 
         uint256[2][2] calldata pB, // groth16
         uint256[2] calldata pC, // groth16
-        uint256[4] calldata challenge_W_challenge_E_kzg_evals, // [challenge_W, challenge_E, eval_W, eval_E]
-        uint256[2][2] calldata kzg_proof // [proof_W, proof_E]
+        uint256[{{ kzg_evals_len }}] calldata challenge_W_challenge_E_kzg_evals, // [challenge_W, challenge_E, eval_W, eval_E{% if hiding %}, random_v_W, random_v_E{% endif %}]
+        uint256[2][{{ kzg_proofs_len }}] calldata kzg_proof // [proof_W, proof_E{% if hiding %}, mask_W, mask_E{% endif %}]
     ) public view returns (bool) {
 
         require(i_z0_zi[0] >= 2, "Folding: the number of folded steps should be at least 2");
@@ -107,6 +125,11 @@ contract NovaDecider is Groth16Verifier, KZG10Verifier, OpaqueDecider {
         for (uint i = 0; i < {{ z_len * 2 }}; i++) {
             public_inputs[2 + i] = i_z0_zi[1 + i];
         }
+        {%- if keccak %}
+
+        // state of the KeccakTranscript from which the KZG challenges are derived
+        bytes32 keccak_state;
+        {%- endif %}
 
         {
             // U_i.cmW + r * u_i.cmW
@@ -122,8 +145,17 @@ contract NovaDecider is Groth16Verifier, KZG10Verifier, OpaqueDecider {
                     public_inputs[{{ z_len * 2 + 2 + num_limbs }} + k] = cmW_y_limbs[k];
                 }
             }
+            {%- if keccak %}
+
+            keccak_state = squeezeKeccakState(abi.encodePacked(bytes32(0), uint256({{ pp_hash }})), cmW);
+            require(uint256(keccak_state) % BN254_SCALAR_FIELD == challenge_W_challenge_E_kzg_evals[0], "Keccak: challenge W does not match the commitments");
+            {%- endif %}
         
+            {% if hiding -%}
+            require(this.checkHiding(cmW, kzg_proof[0], challenge_W_challenge_E_kzg_evals[0], challenge_W_challenge_E_kzg_evals[2], challenge_W_challenge_E_kzg_evals[4], kzg_proof[2]), "KZG: verifying proof for challenge W failed");
+            {%- else -%}
             require(this.check(cmW, kzg_proof[0], challenge_W_challenge_E_kzg_evals[0], challenge_W_challenge_E_kzg_evals[2]), "KZG: verifying proof for challenge W failed");
+            {%- endif %}
         }
 
         {
@@ -140,8 +172,17 @@ contract NovaDecider is Groth16Verifier, KZG10Verifier, OpaqueDecider {
                     public_inputs[{{ z_len * 2 + 2 + num_limbs * 3 }} + k] = cmE_y_limbs[k];
                 }
             }
+            {%- if keccak %}
+
+            keccak_state = squeezeKeccakState(abi.encodePacked(keccak_state), cmE);
+            require(uint256(keccak_state) % BN254_SCALAR_FIELD == challenge_W_challenge_E_kzg_evals[1], "Keccak: challenge E does not match the commitments");
+            {%- endif %}
 
+            {% if hiding -%}
+            require(this.checkHiding(cmE, kzg_proof[1], challenge_W_challenge_E_kzg_evals[1], challenge_W_challenge_E_kzg_evals[3], challenge_W_challenge_E_kzg_evals[5], kzg_proof[3]), "KZG: verifying proof for challenge E failed");
+            {%- else -%}
             require(this.check(cmE, kzg_proof[1], challenge_W_challenge_E_kzg_evals[1], challenge_W_challenge_E_kzg_evals[3]), "KZG: verifying proof for challenge E failed");
+            {%- endif %}
         }
 
         {
@@ -177,7 +218,7 @@ contract NovaDecider is Groth16Verifier, KZG10Verifier, OpaqueDecider {
         uint256 steps,
         uint256[{{ z_len }}] calldata initial_state,
         uint256[{{ z_len }}] calldata final_state,
-        uint256[25] calldata proof
+        uint256[{{ 17 + kzg_evals_len + kzg_proofs_len * 2 }}] calldata proof
     ) public override view returns (bool) {
         uint256[1 + 2 * {{ z_len }}] memory i_z0_zi;
         i_z0_zi[0] = steps;
@@ -192,8 +233,8 @@ contract NovaDecider is Groth16Verifier, KZG10Verifier, OpaqueDecider {
         uint256[2] memory pA = [proof[9], proof[10]];
         uint256[2][2] memory pB = [[proof[11], proof[12]], [proof[13], proof[14]]];
         uint256[2] memory pC = [proof[15], proof[16]];
-        uint256[4] memory challenge_W_challenge_E_kzg_evals = [proof[17], proof[18], proof[19], proof[20]];
-        uint256[2][2] memory kzg_proof = [[proof[21], proof[22]], [proof[23], proof[24]]];
+        uint256[{{ kzg_evals_len }}] memory challenge_W_challenge_E_kzg_evals = [proof[17], proof[18], proof[19], proof[20]{% if hiding %}, proof[21], proof[22]{% endif %}];
+        uint256[2][{{ kzg_proofs_len }}] memory kzg_proof = [[proof[{{ 17 + kzg_evals_len }}], proof[{{ 18 + kzg_evals_len }}]], [proof[{{ 19 + kzg_evals_len }}], proof[{{ 20 + kzg_evals_len }}]]{% if hiding %}, [proof[{{ 21 + kzg_evals_len }}], proof[{{ 22 + kzg_evals_len }}]], [proof[{{ 23 + kzg_evals_len }}], proof[{{ 24 + kzg_evals_len }}]]{% endif %}];
 
         return this.verifyNovaProof(
             i_z0_zi,
@@ -212,7 +253,7 @@ contract NovaDecider is Groth16Verifier, KZG10Verifier, OpaqueDecider {
      * @notice  Verifies a Nova+CycleFold proof given all proof inputs concatenated.
      * @dev     Simply reorganization of arguments and call to the `verifyNovaProof` function.
      */
-    function verifyOpaqueNovaProof(uint256[{{ 26 + z_len * 2 }}] calldata proof) public override view returns (bool) {
+    function verifyOpaqueNovaProof(uint256[{{ 18 + kzg_evals_len + kzg_proofs_len * 2 + z_len * 2 }}] calldata proof) public override view returns (bool) {
         uint256[{{ z_len }}] memory z0;
         uint256[{{ z_len }}] memory zi;
         for (uint256 i = 0; i < {{ z_len }}; i++) {
@@ -220,8 +261,8 @@ contract NovaDecider is Groth16Verifier, KZG10Verifier, OpaqueDecider {
             zi[i] = proof[i + 1 + {{ z_len }}];
         }
 
-        uint256[25] memory extracted_proof;
-        for (uint256 i = 0; i < 25; i++) {
+        uint256[{{ 17 + kzg_evals_len + kzg_proofs_len * 2 }}] memory extracted_proof;
+        for (uint256 i = 0; i < {{ 17 + kzg_evals_len + kzg_proofs_len * 2 }}; i++) {
             extracted_proof[i] = proof[{{ 1 + 2 * z_len }} + i];
         }
 
@@ -0,0 +1,189 @@
+/*
+    Sonobe's ProtoGalaxy + CycleFold decider verifier.
+    Joint effort by 0xPARC & PSE.
+
+    More details at https://github.com/privacy-scaling-explorations/sonobe
+    Usage and design documentation at https://privacy-scaling-explorations.github.io/sonobe-docs/
+
+    Uses the https://github.com/iden3/snarkjs/blob/master/templates/verifier_groth16.sol.ejs
+    Groth16 verifier implementation and a KZG10 Solidity template adapted from
+    https://github.com/weijiekoh/libkzg.
+    Additionally we implement the ProtoGalaxyDecider contract, which combines the
+    Groth16 and KZG10 verifiers to verify the zkSNARK proofs coming from
+    ProtoGalaxy+CycleFold folding.
+*/
+
+
+/* =============================== */
+/* KZG10 verifier methods */
+{{ kzg10_verifier }}
+
+/* =============================== */
+/* Groth16 verifier methods */
+{{ groth16_verifier }}
+
+
+/* =============================== */
+/* ProtoGalaxy+CycleFold Decider verifier */
+/**
+ * @notice  Computes the decomposition of a `uint256` into num_limbs limbs of bits_per_limb bits each.
+ * @dev     Compatible with sonobe::folding-schemes::folding::circuits::nonnative::nonnative_field_to_field_elements.
+ */
+library LimbsDecomposition {
+    function decompose(uint256 x) internal pure returns (uint256[{{num_limbs}}] memory) {
+        uint256[{{num_limbs}}] memory limbs;
+        for (uint8 i = 0; i < {{num_limbs}}; i++) {
+            limbs[i] = (x >> ({{bits_per_limb}} * i)) & ((1 << {{bits_per_limb}}) - 1);
+        }
+        return limbs;
+    }
+}
+
+/**
+ * @author PSE & 0xPARC
+ * @title  Interface for the ProtoGalaxyDecider contract hiding proof details.
+ * @dev    This interface enables calling the verifyProtoGalaxyProof function without exposing the proof details.
+ */
+interface OpaqueProtoGalaxyDecider {
+    /**
+     * @notice  Verifies a ProtoGalaxy+CycleFold proof given initial and final IVC states, number of steps and the rest proof inputs concatenated.
+     * @dev     This function should simply reorganize arguments and pass them to the proper verification function.
+     */
+    function verifyOpaqueProtoGalaxyProofWithInputs(
+        uint256 steps, // number of folded steps (i)
+        uint256[{{ z_len }}] calldata initial_state, // initial IVC state (z0)
+        uint256[{{ z_len }}] calldata final_state, // IVC state after i steps (zi)
+        uint256[18] calldata proof // the rest of the decider inputs
+    ) external view returns (bool);
+
+    /**
+     * @notice  Verifies a ProtoGalaxy+CycleFold proof given all the proof inputs collected in a single array.
+     * @dev     This function should simply reorganize arguments and pass them to the proper verification function.
+     */
+    function verifyOpaqueProtoGalaxyProof(uint256[{{ 19 + z_len * 2 }}] calldata proof) external view returns (bool);
+}
+
+/**
+ * @author  PSE & 0xPARC
+ * @title   ProtoGalaxyDecider contract, for verifying ProtoGalaxy IVC SNARK proofs.
+ * @dev     This is an askama template which, when templated, features a Groth16 and KZG10 verifiers from which this contract inherits.
+ */
+contract ProtoGalaxyDecider is Groth16Verifier, KZG10Verifier, OpaqueProtoGalaxyDecider {
+    /**
+     * @notice  Verifies a protogalaxy cyclefold proof consisting of a KZG proof and of a groth16 proof.
+     * @dev     The selector of this function is "dynamic", since it depends on `z_len`.
+     */
+    function verifyProtoGalaxyProof(
+        // inputs are grouped to prevent errors due stack too deep
+        uint256[{{ 1 + z_len * 2 }}] calldata i_z0_zi, // [i, z0, zi] where |z0| == |zi|
+        uint256[4] calldata U_i_phi_u_i_phi, // [U_i_phi[2], u_i_phi[2]]
+        uint256[2] calldata L_X_evals, // evaluations of the Lagrange polynomials at the last fold's challenge
+        uint256[2] calldata pA, // groth16
+        uint256[2][2] calldata pB, // groth16
+        uint256[2] calldata pC, // groth16
+        uint256[2] calldata challenge_phi_kzg_eval, // [challenge_phi, eval_phi]
+        uint256[2] calldata kzg_proof // proof_phi
+    ) public view returns (bool) {
+
+        require(i_z0_zi[0] >= 2, "Folding: the number of folded steps should be at least 2");
+
+        // from gamma_abc_len, we subtract 1.
+        uint256[{{ public_inputs_len - 1 }}] memory public_inputs;
+
+        public_inputs[0] = {{pp_hash}};
+        public_inputs[1] = i_z0_zi[0];
+
+        for (uint i = 0; i < {{ z_len * 2 }}; i++) {
+            public_inputs[2 + i] = i_z0_zi[1 + i];
+        }
+
+        {
+            // U_i.phi * L_X_evals[0] + u_i.phi * L_X_evals[1]
+            uint256[2] memory U_i_phi_L_0 = super.mulScalar([U_i_phi_u_i_phi[0], U_i_phi_u_i_phi[1]], L_X_evals[0]);
+            uint256[2] memory u_i_phi_L_1 = super.mulScalar([U_i_phi_u_i_phi[2], U_i_phi_u_i_phi[3]], L_X_evals[1]);
+            uint256[2] memory phi = super.add(U_i_phi_L_0, u_i_phi_L_1);
+
+            {
+                uint256[{{num_limbs}}] memory phi_x_limbs = LimbsDecomposition.decompose(phi[0]);
+                uint256[{{num_limbs}}] memory phi_y_limbs = LimbsDecomposition.decompose(phi[1]);
+
+                for (uint8 k = 0; k < {{num_limbs}}; k++) {
+                    public_inputs[{{ z_len * 2 + 2 }} + k] = phi_x_limbs[k];
+                    public_inputs[{{ z_len * 2 + 2 + num_limbs }} + k] = phi_y_limbs[k];
+                }
+            }
+
+            require(this.check(phi, kzg_proof, challenge_phi_kzg_eval[0], challenge_phi_kzg_eval[1]), "KZG: verifying proof for challenge phi failed");
+        }
+
+        {
+            // add the challenge, the evaluation and the Lagrange polynomials' evaluations
+            public_inputs[{{ z_len * 2 + 2 + num_limbs * 2 }}] = challenge_phi_kzg_eval[0];
+            public_inputs[{{ z_len * 2 + 2 + num_limbs * 2 + 1 }}] = challenge_phi_kzg_eval[1];
+            public_inputs[{{ z_len * 2 + 2 + num_limbs * 2 + 2 }}] = L_X_evals[0];
+            public_inputs[{{ z_len * 2 + 2 + num_limbs * 2 + 3 }}] = L_X_evals[1];
+
+            bool success_g16 = this.verifyProof(pA, pB, pC, public_inputs);
+            require(success_g16 == true, "Groth16: verifying proof failed");
+        }
+
+        return(true);
+    }
+
+    /**
+     * @notice  Verifies a ProtoGalaxy+CycleFold proof given initial and final IVC states, number of steps and the rest proof inputs concatenated.
+     * @dev     Simply reorganization of arguments and call to the `verifyProtoGalaxyProof` function.
+     */
+    function verifyOpaqueProtoGalaxyProofWithInputs(
+        uint256 steps,
+        uint256[{{ z_len }}] calldata initial_state,
+        uint256[{{ z_len }}] calldata final_state,
+        uint256[18] calldata proof
+    ) public override view returns (bool) {
+        uint256[1 + 2 * {{ z_len }}] memory i_z0_zi;
+        i_z0_zi[0] = steps;
+        for (uint256 i = 0; i < {{ z_len }}; i++) {
+            i_z0_zi[i + 1] = initial_state[i];
+            i_z0_zi[i + 1 + {{ z_len }}] = final_state[i];
+        }
+
+        uint256[4] memory U_i_phi_u_i_phi = [proof[0], proof[1], proof[2], proof[3]];
+        uint256[2] memory L_X_evals = [proof[4], proof[5]];
+        uint256[2] memory pA = [proof[6], proof[7]];
+        uint256[2][2] memory pB = [[proof[8], proof[9]], [proof[10], proof[11]]];
+        uint256[2] memory pC = [proof[12], proof[13]];
+        uint256[2] memory challenge_phi_kzg_eval = [proof[14], proof[15]];
+        uint256[2] memory kzg_proof = [proof[16], proof[17]];
+
+        return this.verifyProtoGalaxyProof(
+            i_z0_zi,
+            U_i_phi_u_i_phi,
+            L_X_evals,
+            pA,
+            pB,
+            pC,
+            challenge_phi_kzg_eval,
+            kzg_proof
+        );
+    }
+
+    /**
+     * @notice  Verifies a ProtoGalaxy+CycleFold proof given all proof inputs concatenated.
+     * @dev     Simply reorganization of arguments and call to the `verifyProtoGalaxyProof` function.
+     */
+    function verifyOpaqueProtoGalaxyProof(uint256[{{ 19 + z_len * 2 }}] calldata proof) public override view returns (bool) {
+        uint256[{{ z_len }}] memory z0;
+        uint256[{{ z_len }}] memory zi;
+        for (uint256 i = 0; i < {{ z_len }}; i++) {
+            z0[i] = proof[i + 1];
+            zi[i] = proof[i + 1 + {{ z_len }}];
+        }
+
+        uint256[18] memory extracted_proof;
+        for (uint256 i = 0; i < 18; i++) {
+            extracted_proof[i] = proof[{{ 1 + 2 * z_len }} + i];
+        }
+
+        return this.verifyOpaqueProtoGalaxyProofWithInputs(proof[0], z0, zi, extracted_proof);
+    }
+}